@@ -16,7 +16,7 @@ fn layout_items(c: &mut Criterion) {
             }
             let mut layout = Layout::new("root");
             b.iter(|| {
-                let _ = layout.layout(&mut stac).unwrap();
+                layout.layout(&mut stac).unwrap();
             })
         });
     }
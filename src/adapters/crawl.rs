@@ -0,0 +1,351 @@
+use super::{GeoTiff, Las, NetCdf};
+use crate::{
+    geometry, id, media_type, stac::Handle, Asset, Catalog, Collection, Error, Extent, Href, Item,
+    Reader, Result, Stac,
+};
+use chrono::SecondsFormat;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// How [Crawl] groups items into collections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Group {
+    /// One collection per directory, with an id made from the directory's
+    /// path below the crawl root, e.g. `2023/tiles` becomes `2023-tiles`.
+    ///
+    /// Items in the crawl root itself are children of the root catalog.
+    #[default]
+    Directory,
+
+    /// One collection per kind of data: `raster`, `pointcloud`, or `netcdf`.
+    Format,
+
+    /// No collections; every item is a child of the root catalog.
+    None,
+}
+
+/// Builds a [Stac] from a directory tree of data files.
+///
+/// Files are recognized by their extension and turned into items by the
+/// adapters in this module:
+///
+/// | Extension | Adapter | Format |
+/// | --------- | ------- | ------ |
+/// | `.tif`, `.tiff` | [GeoTiff] | `raster` |
+/// | `.las`, `.laz` | [Las] | `pointcloud` |
+/// | `.nc` | [NetCdf] | `netcdf` |
+///
+/// Other files, and files and directories whose names start with a `.`, are
+/// skipped. Each item's `data` asset points to the file's absolute path.
+/// Items are grouped into collections as configured with
+/// [group_by](Crawl::group_by), and each collection's extent covers its
+/// items. The result has no hrefs, so it's ready for a
+/// [Layout](crate::Layout).
+///
+/// # Examples
+///
+/// ```
+/// use stac::adapters::Crawl;
+/// let (mut stac, root) = Crawl::new("data/adapters").build().unwrap();
+/// let children = stac.children(root);
+/// assert_eq!(stac.get(children[0]).unwrap().id(), "geotiff");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crawl {
+    root: PathBuf,
+    id: Option<String>,
+    group: Group,
+}
+
+/// The kinds of data files that can be crawled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Format {
+    Raster,
+    PointCloud,
+    NetCdf,
+}
+
+impl Crawl {
+    /// Creates a new crawl of this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Crawl;
+    /// let crawl = Crawl::new("data/adapters");
+    /// ```
+    pub fn new(root: impl AsRef<Path>) -> Crawl {
+        Crawl {
+            root: root.as_ref().to_path_buf(),
+            id: None,
+            group: Group::default(),
+        }
+    }
+
+    /// Sets the id of the root catalog.
+    ///
+    /// Defaults to the name of the crawled directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Crawl;
+    /// let (mut stac, root) = Crawl::new("data/adapters")
+    ///     .with_id("sensors")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(stac.get(root).unwrap().id(), "sensors");
+    /// ```
+    pub fn with_id(mut self, id: impl ToString) -> Crawl {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Sets how items are grouped into collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::{Crawl, Group};
+    /// let (mut stac, root) = Crawl::new("data/adapters")
+    ///     .group_by(Group::Format)
+    ///     .build()
+    ///     .unwrap();
+    /// let ids: Vec<_> = stac
+    ///     .children(root)
+    ///     .into_iter()
+    ///     .map(|child| stac.get(child).unwrap().id().to_string())
+    ///     .collect();
+    /// assert_eq!(ids, ["netcdf", "pointcloud", "raster"]);
+    /// ```
+    pub fn group_by(mut self, group: Group) -> Crawl {
+        self.group = group;
+        self
+    }
+
+    /// Crawls the directory and builds the catalog.
+    ///
+    /// Collections are ordered by id, and items by id within each
+    /// collection. Returns an error if a recognized file can't be read by
+    /// its adapter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::{Crawl, Group};
+    /// let (mut stac, root) = Crawl::new("data/adapters/pointcloud")
+    ///     .group_by(Group::None)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(stac.children(root).len(), 2);
+    /// ```
+    pub fn build(&self) -> Result<(Stac<Reader>, Handle)> {
+        let root = self.canonical_root()?;
+        let mut paths = Vec::new();
+        find_files(&root, &mut paths)?;
+        paths.sort();
+
+        let mut groups: BTreeMap<Option<String>, Vec<Item>> = BTreeMap::new();
+        for path in paths {
+            let Some(format) = Format::from_path(&path) else {
+                continue;
+            };
+            let item = format.to_item(&path)?;
+            groups
+                .entry(self.group(&root, &path, format))
+                .or_default()
+                .push(item);
+        }
+
+        let id = self.id.clone().unwrap_or_else(|| {
+            root.file_name()
+                .map(|name| id::slugify(&name.to_string_lossy()))
+                .unwrap_or_else(|| "catalog".to_string())
+        });
+        let mut catalog = Catalog::new(id);
+        catalog.description = format!("Crawled from {}", root.display());
+        let (mut stac, handle) = Stac::new(catalog)?;
+        for (group, mut items) in groups {
+            items.sort_by(|a, b| a.id.cmp(&b.id));
+            let parent = match group {
+                Some(group) => {
+                    let mut collection = collection(&group);
+                    collection.extent = extent(&items)?;
+                    for item in &mut items {
+                        item.collection = Some(group.as_str().into());
+                    }
+                    stac.add_child(handle, collection)?
+                }
+                None => handle,
+            };
+            let _ = stac.add_items(parent, items)?;
+        }
+        Ok((stac, handle))
+    }
+}
+
+impl Crawl {
+    /// Returns the canonical path of the crawled directory.
+    pub(super) fn canonical_root(&self) -> Result<PathBuf> {
+        fs::canonicalize(&self.root).map_err(Error::from)
+    }
+
+    /// Returns the id of the collection for this file, or `None` if its item
+    /// belongs to the root catalog.
+    pub(super) fn group(&self, root: &Path, path: &Path, format: Format) -> Option<String> {
+        match self.group {
+            Group::Directory => path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(root).ok())
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .map(|relative| id::slugify(&relative.to_string_lossy())),
+            Group::Format => Some(format.name().to_string()),
+            Group::None => None,
+        }
+    }
+}
+
+impl Format {
+    pub(super) fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "tif" | "tiff" => Some(Format::Raster),
+            "las" | "laz" => Some(Format::PointCloud),
+            "nc" => Some(Format::NetCdf),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Raster => "raster",
+            Format::PointCloud => "pointcloud",
+            Format::NetCdf => "netcdf",
+        }
+    }
+
+    pub(super) fn to_item(self, path: &Path) -> Result<Item> {
+        match self {
+            Format::Raster => {
+                let geotiff = GeoTiff::read(path)?;
+                let id = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy())
+                    .unwrap_or_default();
+                let mut item = Item::new(id);
+                let mut asset = Asset::new(Href::from(path));
+                asset.r#type = Some(media_type::GEOTIFF.into());
+                asset.roles = Some(vec!["data".to_string()]);
+                let _ = item.assets.insert("data".to_string(), asset);
+                let _ = geotiff.update_item(&mut item, "data");
+                item.geometry = geotiff.geometry();
+                item.bbox = item.geometry.as_ref().and_then(geometry::bbox);
+                Ok(item)
+            }
+            Format::PointCloud => Las::read(path)?.to_item(),
+            Format::NetCdf => NetCdf::read(path)?.to_item(),
+        }
+    }
+}
+
+/// Creates an empty collection for a group of items.
+pub(super) fn collection(id: &str) -> Collection {
+    let mut collection = Collection::new(id);
+    collection.description = format!("Items in {}", id);
+    collection.license = "other".to_string();
+    collection
+}
+
+/// Collects the paths of the files below this directory, skipping hidden
+/// files and directories.
+fn find_files(directory: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            find_files(&entry.path(), paths)?;
+        } else if file_type.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the extent that covers these items.
+pub(super) fn extent(items: &[Item]) -> Result<Extent> {
+    let mut extent = Extent::default();
+    let mut bbox: Option<[f64; 4]> = None;
+    let mut interval = None;
+    for item in items {
+        if let Some(item_bbox) = item.horizontal_bbox() {
+            bbox = Some(match bbox {
+                Some(bbox) => [
+                    bbox[0].min(item_bbox[0]),
+                    bbox[1].min(item_bbox[1]),
+                    bbox[2].max(item_bbox[2]),
+                    bbox[3].max(item_bbox[3]),
+                ],
+                None => item_bbox,
+            });
+        }
+        if let Some((start, end)) = item.interval()? {
+            interval = Some(match interval {
+                Some((min, max)) => (start.min(min), end.max(max)),
+                None => (start, end),
+            });
+        }
+    }
+    if let Some(bbox) = bbox {
+        extent.spatial.bbox = vec![bbox.to_vec()];
+    }
+    if let Some((start, end)) = interval {
+        extent.temporal.interval = vec![[
+            Some(start.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            Some(end.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        ]];
+    }
+    Ok(extent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Crawl;
+    use crate::{Collection, Item};
+
+    #[test]
+    fn group_by_directory() {
+        let (mut stac, root) = Crawl::new("data/adapters").build().unwrap();
+        assert_eq!(stac.get(root).unwrap().id(), "adapters");
+        let ids: Vec<_> = stac
+            .children(root)
+            .into_iter()
+            .map(|child| stac.get(child).unwrap().id().to_string())
+            .collect();
+        assert_eq!(ids, ["geotiff", "netcdf", "pointcloud"]);
+
+        let geotiff = stac.children(root)[0];
+        let collection: &Collection = stac.get(geotiff).unwrap().as_collection().unwrap();
+        assert_eq!(collection.license, "other");
+        let items = stac.children(geotiff);
+        assert_eq!(items.len(), 2);
+        let item: &Item = stac.get(items[1]).unwrap().as_item().unwrap();
+        assert_eq!(item.id, "utm");
+        assert_eq!(item.collection.as_deref(), Some("geotiff"));
+        assert!(item.assets["data"].href.ends_with("geotiff/utm.tif"));
+        assert!(item.geometry.is_some());
+    }
+
+    #[test]
+    fn skip_unrecognized() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join("readme.txt"), "not data").unwrap();
+        let (stac, root) = Crawl::new(directory.path()).build().unwrap();
+        assert!(stac.children(root).is_empty());
+    }
+}
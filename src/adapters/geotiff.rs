@@ -0,0 +1,695 @@
+use super::extension_uris;
+use crate::{utm, Asset, Error, Item, Result};
+use geojson::{Geometry, Value as GeometryValue};
+use roxmltree::Document;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// The extensions whose fields are set from a GeoTIFF.
+const EXTENSIONS: [(&str, &str); 3] = [
+    ("file", "2.1.0"),
+    ("projection", "1.1.0"),
+    ("raster", "1.1.0"),
+];
+
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const SAMPLE_FORMAT: u16 = 339;
+const MODEL_PIXEL_SCALE: u16 = 33550;
+const MODEL_TIEPOINT: u16 = 33922;
+const MODEL_TRANSFORMATION: u16 = 34264;
+const GEO_KEY_DIRECTORY: u16 = 34735;
+const GDAL_METADATA: u16 = 42112;
+const GDAL_NODATA: u16 = 42113;
+
+/// The tags that are read; the others, e.g. strip offsets, can be large and are skipped.
+const TAGS: [u16; 11] = [
+    IMAGE_WIDTH,
+    IMAGE_LENGTH,
+    BITS_PER_SAMPLE,
+    SAMPLES_PER_PIXEL,
+    SAMPLE_FORMAT,
+    MODEL_PIXEL_SCALE,
+    MODEL_TIEPOINT,
+    MODEL_TRANSFORMATION,
+    GEO_KEY_DIRECTORY,
+    GDAL_METADATA,
+    GDAL_NODATA,
+];
+
+const GT_MODEL_TYPE: u16 = 1024;
+const GT_RASTER_TYPE: u16 = 1025;
+const GEOGRAPHIC_TYPE: u16 = 2048;
+const PROJECTED_CS_TYPE: u16 = 3072;
+
+/// The GeoKey value for user-defined coordinate systems, which have no EPSG code.
+const USER_DEFINED: u16 = 32767;
+
+/// Projection and raster metadata read from the tags of a (Geo)TIFF's first image.
+///
+/// Only the file's header and the tags it needs are read, not the pixels. The
+/// fields are set on [assets](Asset), rather than items, so each asset of an
+/// item can have its own coordinate reference system and grid:
+///
+/// - `file:size`, if the file size is known
+/// - [projection](https://github.com/stac-extensions/projection) `proj:epsg`,
+///   `proj:shape`, `proj:transform`, and `proj:bbox`, if the file is georeferenced
+/// - [raster](https://github.com/stac-extensions/raster) `raster:bands`, with
+///   each band's data type, nodata value, spatial resolution, and GDAL scale and offset
+///
+/// Both classic TIFF and BigTIFF files, in either byte order, are supported.
+/// Following GDAL, transforms of `PixelIsPoint` rasters are shifted by half a
+/// pixel so they describe pixel corners.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{adapters::GeoTiff, Asset};
+/// let geotiff = GeoTiff::read("data/adapters/geotiff/utm.tif").unwrap();
+/// let mut asset = Asset::new("data/adapters/geotiff/utm.tif");
+/// geotiff.update_asset(&mut asset);
+/// assert_eq!(asset.additional_fields["proj:epsg"], 32633);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoTiff {
+    size: Option<u64>,
+    width: u64,
+    height: u64,
+    data_types: Vec<&'static str>,
+    transform: Option<[f64; 6]>,
+    epsg: Option<u16>,
+    nodata: Option<f64>,
+    scales: HashMap<usize, f64>,
+    offsets: HashMap<usize, f64>,
+}
+
+#[derive(Debug)]
+enum TagValue {
+    Numbers(Vec<f64>),
+    Text(String),
+}
+
+/// Reads the header and image file directory of a TIFF.
+#[derive(Debug)]
+struct TiffReader<R> {
+    reader: R,
+    len: u64,
+    little_endian: bool,
+    big_tiff: bool,
+}
+
+impl GeoTiff {
+    /// Reads a (Geo)TIFF file's tags and size.
+    ///
+    /// Returns [Error::InvalidMetadata] if the file isn't a TIFF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::GeoTiff;
+    /// let geotiff = GeoTiff::read("data/adapters/geotiff/utm.tif").unwrap();
+    /// ```
+    pub fn read(path: impl AsRef<Path>) -> Result<GeoTiff> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        let mut geotiff = GeoTiff::from_reader(BufReader::new(file))?;
+        geotiff.size = Some(size);
+        Ok(geotiff)
+    }
+
+    /// Reads a (Geo)TIFF's tags from a reader, e.g. a [Cursor](std::io::Cursor)
+    /// over bytes fetched from a remote server.
+    ///
+    /// The size isn't known, so `file:size` won't be set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{adapters::GeoTiff, Asset};
+    /// use std::io::Cursor;
+    /// let bytes = std::fs::read("data/adapters/geotiff/utm.tif").unwrap();
+    /// let geotiff = GeoTiff::from_reader(Cursor::new(bytes)).unwrap();
+    /// let mut asset = Asset::new("utm.tif");
+    /// geotiff.update_asset(&mut asset);
+    /// assert!(!asset.additional_fields.contains_key("file:size"));
+    /// ```
+    pub fn from_reader(reader: impl Read + Seek) -> Result<GeoTiff> {
+        let tags = TiffReader::new(reader)?.tags()?;
+        let numbers = |tag| match tags.get(&tag) {
+            Some(TagValue::Numbers(numbers)) => Some(numbers.as_slice()),
+            _ => None,
+        };
+        let first = |tag| numbers(tag).and_then(|numbers| numbers.first().copied());
+        let width = first(IMAGE_WIDTH)
+            .ok_or_else(|| Error::InvalidMetadata("missing ImageWidth".to_string()))?;
+        let height = first(IMAGE_LENGTH)
+            .ok_or_else(|| Error::InvalidMetadata("missing ImageLength".to_string()))?;
+        let samples = first(SAMPLES_PER_PIXEL).unwrap_or(1.0) as usize;
+        let bits_per_sample = numbers(BITS_PER_SAMPLE).unwrap_or(&[1.0]);
+        let sample_format = numbers(SAMPLE_FORMAT).unwrap_or(&[1.0]);
+        let data_types = (0..samples)
+            .map(|i| {
+                let bits = bits_per_sample.get(i).or(bits_per_sample.last());
+                let format = sample_format.get(i).or(sample_format.last());
+                data_type(
+                    format.copied().unwrap_or(1.0) as u16,
+                    bits.copied().unwrap_or(1.0) as u16,
+                )
+            })
+            .collect();
+
+        let geo_keys = geo_keys(numbers(GEO_KEY_DIRECTORY).unwrap_or_default());
+        let epsg = epsg(&geo_keys);
+        let mut transform = match (
+            numbers(MODEL_TRANSFORMATION),
+            numbers(MODEL_PIXEL_SCALE),
+            numbers(MODEL_TIEPOINT),
+        ) {
+            (Some(m), _, _) if m.len() >= 8 => Some([m[0], m[1], m[3], m[4], m[5], m[7]]),
+            (_, Some(scale), Some(tiepoint)) if scale.len() >= 2 && tiepoint.len() >= 6 => {
+                let [i, j, _, x, y, ..] = tiepoint else {
+                    unreachable!("tiepoints have at least six values")
+                };
+                Some([
+                    scale[0],
+                    0.0,
+                    x - i * scale[0],
+                    0.0,
+                    -scale[1],
+                    y + j * scale[1],
+                ])
+            }
+            _ => None,
+        };
+        if let (Some(transform), Some(2)) = (transform.as_mut(), geo_keys.get(&GT_RASTER_TYPE)) {
+            transform[2] -= (transform[0] + transform[1]) / 2.0;
+            transform[5] -= (transform[3] + transform[4]) / 2.0;
+        }
+
+        let mut scales = HashMap::new();
+        let mut offsets = HashMap::new();
+        if let Some(TagValue::Text(metadata)) = tags.get(&GDAL_METADATA) {
+            let document = Document::parse(metadata)?;
+            for item in document.descendants().filter(|n| n.has_tag_name("Item")) {
+                let sample = item.attribute("sample").and_then(|s| s.parse().ok());
+                let value = item.text().and_then(|s| s.trim().parse().ok());
+                let (Some(sample), Some(value)) = (sample, value) else {
+                    continue;
+                };
+                match item.attribute("role") {
+                    Some("scale") => {
+                        let _ = scales.insert(sample, value);
+                    }
+                    Some("offset") => {
+                        let _ = offsets.insert(sample, value);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let nodata = match tags.get(&GDAL_NODATA) {
+            Some(TagValue::Text(nodata)) => nodata.trim().parse().ok(),
+            _ => None,
+        };
+        Ok(GeoTiff {
+            size: None,
+            width: width as u64,
+            height: height as u64,
+            data_types,
+            transform,
+            epsg,
+            nodata,
+            scales,
+            offsets,
+        })
+    }
+
+    /// Sets an asset's `file:size`, `proj:*`, and `raster:bands` fields.
+    ///
+    /// Existing values of these fields are overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{adapters::GeoTiff, Asset};
+    /// let geotiff = GeoTiff::read("data/adapters/geotiff/utm.tif").unwrap();
+    /// let mut asset = Asset::new("data/adapters/geotiff/utm.tif");
+    /// geotiff.update_asset(&mut asset);
+    /// assert_eq!(asset.additional_fields["proj:shape"], serde_json::json!([3, 4]));
+    /// ```
+    pub fn update_asset(&self, asset: &mut Asset) {
+        asset.additional_fields.extend(self.fields());
+    }
+
+    /// Sets the fields of an item's asset, and adds the file, projection,
+    /// and raster extensions to the item if it doesn't already have them.
+    ///
+    /// Returns `false`, and does nothing, if the item has no asset with this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{adapters::GeoTiff, Asset, Item};
+    /// let mut item = Item::new("an-id");
+    /// let _ = item.assets.insert(
+    ///     "data".to_string(),
+    ///     Asset::new("data/adapters/geotiff/utm.tif"),
+    /// );
+    /// let geotiff = GeoTiff::read("data/adapters/geotiff/utm.tif").unwrap();
+    /// assert!(geotiff.update_item(&mut item, "data"));
+    /// assert_eq!(item.extensions.unwrap().len(), 3);
+    /// ```
+    pub fn update_item(&self, item: &mut Item, key: &str) -> bool {
+        let Some(asset) = item.assets.get_mut(key) else {
+            return false;
+        };
+        self.update_asset(asset);
+        let extensions = item.extensions.get_or_insert_with(Vec::new);
+        for (uri, (identifier, _)) in extension_uris(&EXTENSIONS).into_iter().zip(EXTENSIONS) {
+            let prefix = format!("https://stac-extensions.github.io/{}/", identifier);
+            if !extensions
+                .iter()
+                .any(|extension| extension.starts_with(&prefix))
+            {
+                extensions.push(uri);
+            }
+        }
+        true
+    }
+
+    /// Returns the footprint in WGS84, if the raster is georeferenced in
+    /// geographic coordinates or a UTM zone.
+    pub(super) fn geometry(&self) -> Option<Geometry> {
+        let [xmin, ymin, xmax, ymax] = self.proj_bbox()?;
+        let corners = [
+            (xmin, ymin),
+            (xmax, ymin),
+            (xmax, ymax),
+            (xmin, ymax),
+            (xmin, ymin),
+        ];
+        let ring: Vec<Vec<f64>> = match u32::from(self.epsg?) {
+            4326 | 4269 => corners.iter().map(|&(x, y)| vec![x, y]).collect(),
+            epsg => {
+                let (zone, north) = utm::zone(epsg)?;
+                corners
+                    .iter()
+                    .map(|&(x, y)| {
+                        let (lon, lat) = utm::to_lon_lat(zone, north, x, y);
+                        vec![lon, lat]
+                    })
+                    .collect()
+            }
+        };
+        Some(Geometry::new(GeometryValue::Polygon(vec![ring])))
+    }
+
+    /// Returns the bounds of the raster's corners in its own coordinates.
+    fn proj_bbox(&self) -> Option<[f64; 4]> {
+        let transform = self.transform?;
+        let (width, height) = (self.width as f64, self.height as f64);
+        let corners =
+            [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)].map(|(col, row)| {
+                (
+                    transform[0] * col + transform[1] * row + transform[2],
+                    transform[3] * col + transform[4] * row + transform[5],
+                )
+            });
+        let xs = corners.map(|(x, _)| x);
+        let ys = corners.map(|(_, y)| y);
+        let min = |values: [f64; 4]| values.into_iter().fold(f64::INFINITY, f64::min);
+        let max = |values: [f64; 4]| values.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        Some([min(xs), min(ys), max(xs), max(ys)])
+    }
+
+    fn fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        if let Some(size) = self.size {
+            let _ = fields.insert("file:size".to_string(), size.into());
+        }
+        let _ = fields.insert("proj:shape".to_string(), json!([self.height, self.width]));
+        if let Some(epsg) = self.epsg {
+            let _ = fields.insert("proj:epsg".to_string(), epsg.into());
+        }
+        if let Some(transform) = self.transform {
+            let _ = fields.insert("proj:transform".to_string(), json!(transform));
+        }
+        if let Some(bbox) = self.proj_bbox() {
+            let _ = fields.insert("proj:bbox".to_string(), json!(bbox));
+        }
+        let bands: Vec<Value> = self
+            .data_types
+            .iter()
+            .enumerate()
+            .map(|(i, data_type)| {
+                let mut band = Map::new();
+                let _ = band.insert("data_type".to_string(), (*data_type).into());
+                if let Some(nodata) = self.nodata {
+                    let _ = band.insert("nodata".to_string(), nodata.into());
+                }
+                if let Some(transform) = self.transform.filter(|t| t[1] == 0.0 && t[3] == 0.0) {
+                    let _ =
+                        band.insert("spatial_resolution".to_string(), transform[0].abs().into());
+                }
+                if let Some(scale) = self.scales.get(&i) {
+                    let _ = band.insert("scale".to_string(), (*scale).into());
+                }
+                if let Some(offset) = self.offsets.get(&i) {
+                    let _ = band.insert("offset".to_string(), (*offset).into());
+                }
+                band.into()
+            })
+            .collect();
+        let _ = fields.insert("raster:bands".to_string(), bands.into());
+        fields
+    }
+}
+
+impl<R: Read + Seek> TiffReader<R> {
+    fn new(mut reader: R) -> Result<TiffReader<R>> {
+        let mut byte_order = [0; 2];
+        reader.read_exact(&mut byte_order)?;
+        let little_endian = match &byte_order {
+            b"II" => true,
+            b"MM" => false,
+            _ => return Err(Error::InvalidMetadata("not a TIFF file".to_string())),
+        };
+        let len = reader.seek(SeekFrom::End(0))?;
+        let _ = reader.seek(SeekFrom::Start(2))?;
+        let mut tiff = TiffReader {
+            reader,
+            len,
+            little_endian,
+            big_tiff: false,
+        };
+        match tiff.u16()? {
+            42 => {}
+            43 => tiff.big_tiff = true,
+            version => {
+                return Err(Error::InvalidMetadata(format!(
+                    "invalid TIFF version: {}",
+                    version
+                )))
+            }
+        }
+        Ok(tiff)
+    }
+
+    /// Reads the tags of the first image file directory that are in [TAGS].
+    fn tags(&mut self) -> Result<HashMap<u16, TagValue>> {
+        if self.big_tiff {
+            let _ = self.u16()?;
+            let _ = self.u16()?;
+        }
+        let ifd = self.offset()?;
+        let _ = self.reader.seek(SeekFrom::Start(ifd))?;
+        let count = if self.big_tiff {
+            self.u64()?
+        } else {
+            u64::from(self.u16()?)
+        };
+        let (count_size, entry_size, inline_size) = if self.big_tiff {
+            (8, 20, 8)
+        } else {
+            (2, 12, 4)
+        };
+        // Counts and offsets come from the file, so check them against its
+        // length before seeking or allocating.
+        if count
+            .checked_mul(entry_size)
+            .and_then(|size| size.checked_add(ifd + count_size))
+            .is_none_or(|end| end > self.len)
+        {
+            return Err(Error::InvalidMetadata(format!(
+                "image file directory with {} entries at {} is outside of the file",
+                count, ifd
+            )));
+        }
+        let mut tags = HashMap::new();
+        for i in 0..count {
+            let _ = self
+                .reader
+                .seek(SeekFrom::Start(ifd + count_size + i * entry_size))?;
+            let tag = self.u16()?;
+            if !TAGS.contains(&tag) {
+                continue;
+            }
+            let field_type = self.u16()?;
+            let count = self.offset()?;
+            let size = match field_type {
+                1 | 2 | 6 | 7 => 1,
+                3 | 8 => 2,
+                4 | 9 | 11 => 4,
+                5 | 10 | 12 | 16 | 17 => 8,
+                _ => continue,
+            };
+            let length = count
+                .checked_mul(size)
+                .filter(|&length| length <= self.len)
+                .ok_or_else(|| {
+                    Error::InvalidMetadata(format!("tag {} has too many values: {}", tag, count))
+                })?;
+            if length > inline_size {
+                let offset = self.offset()?;
+                let _ = self.reader.seek(SeekFrom::Start(offset))?;
+            }
+            let mut bytes = vec![0; length as usize];
+            self.reader.read_exact(&mut bytes)?;
+            let value = if field_type == 2 {
+                TagValue::Text(
+                    String::from_utf8_lossy(&bytes)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                )
+            } else {
+                TagValue::Numbers(
+                    bytes
+                        .chunks_exact(size as usize)
+                        .map(|b| self.number(field_type, b))
+                        .collect(),
+                )
+            };
+            let _ = tags.insert(tag, value);
+        }
+        Ok(tags)
+    }
+
+    fn number(&self, field_type: u16, bytes: &[u8]) -> f64 {
+        macro_rules! decode {
+            ($t:ty) => {{
+                let bytes = bytes.try_into().expect("sized by field type");
+                if self.little_endian {
+                    <$t>::from_le_bytes(bytes)
+                } else {
+                    <$t>::from_be_bytes(bytes)
+                }
+            }};
+        }
+        match field_type {
+            1 | 7 => f64::from(bytes[0]),
+            6 => f64::from(bytes[0] as i8),
+            3 => f64::from(decode!(u16)),
+            8 => f64::from(decode!(i16)),
+            4 => f64::from(decode!(u32)),
+            9 => f64::from(decode!(i32)),
+            11 => f64::from(decode!(f32)),
+            12 => decode!(f64),
+            16 => decode!(u64) as f64,
+            17 => decode!(i64) as f64,
+            5 => {
+                let (numerator, denominator) = bytes.split_at(4);
+                self.number(4, numerator) / self.number(4, denominator)
+            }
+            10 => {
+                let (numerator, denominator) = bytes.split_at(4);
+                self.number(9, numerator) / self.number(9, denominator)
+            }
+            _ => f64::NAN,
+        }
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let mut bytes = [0; 2];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(self.number(3, &bytes) as u16)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0; 8];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(if self.little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads an offset or count, which is eight bytes in BigTIFF and four otherwise.
+    fn offset(&mut self) -> Result<u64> {
+        if self.big_tiff {
+            self.u64()
+        } else {
+            let mut bytes = [0; 4];
+            self.reader.read_exact(&mut bytes)?;
+            Ok(self.number(4, &bytes) as u64)
+        }
+    }
+}
+
+/// Returns the GeoKeys with values stored in the directory itself.
+///
+/// LAS files store the same directory in a variable length record.
+pub(super) fn geo_keys(directory: &[f64]) -> HashMap<u16, u16> {
+    directory
+        .chunks_exact(4)
+        .skip(1)
+        .filter(|key| key[1] == 0.0)
+        .map(|key| (key[0] as u16, key[3] as u16))
+        .collect()
+}
+
+/// Returns the EPSG code of the model's coordinate reference system, unless it's user-defined.
+pub(super) fn epsg(geo_keys: &HashMap<u16, u16>) -> Option<u16> {
+    match geo_keys.get(&GT_MODEL_TYPE) {
+        Some(1) => geo_keys.get(&PROJECTED_CS_TYPE),
+        Some(2) => geo_keys.get(&GEOGRAPHIC_TYPE),
+        _ => geo_keys
+            .get(&PROJECTED_CS_TYPE)
+            .or_else(|| geo_keys.get(&GEOGRAPHIC_TYPE)),
+    }
+    .copied()
+    .filter(|&code| code != USER_DEFINED)
+}
+
+/// Returns the raster extension data type for a TIFF sample format and bit depth.
+fn data_type(sample_format: u16, bits: u16) -> &'static str {
+    match (sample_format, bits) {
+        (1, 8) => "uint8",
+        (1, 16) => "uint16",
+        (1, 32) => "uint32",
+        (1, 64) => "uint64",
+        (2, 8) => "int8",
+        (2, 16) => "int16",
+        (2, 32) => "int32",
+        (2, 64) => "int64",
+        (3, 16) => "float16",
+        (3, 32) => "float32",
+        (3, 64) => "float64",
+        (5, 32) => "cint16",
+        (5, 64) => "cint32",
+        (6, 64) => "cfloat32",
+        (6, 128) => "cfloat64",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeoTiff;
+    use crate::{Asset, Error, Item};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn asset(path: &str) -> Asset {
+        let mut asset = Asset::new(path);
+        GeoTiff::read(path).unwrap().update_asset(&mut asset);
+        asset
+    }
+
+    #[test]
+    fn projected() {
+        let asset = asset("data/adapters/geotiff/utm.tif");
+        let fields = &asset.additional_fields;
+        assert_eq!(fields["file:size"], 516);
+        assert_eq!(fields["proj:epsg"], 32633);
+        assert_eq!(fields["proj:shape"], json!([3, 4]));
+        assert_eq!(
+            fields["proj:transform"],
+            json!([10.0, 0.0, 300000.0, 0.0, -10.0, 5500000.0])
+        );
+        assert_eq!(
+            fields["proj:bbox"],
+            json!([300000.0, 5499970.0, 300040.0, 5500000.0])
+        );
+        assert_eq!(
+            fields["raster:bands"],
+            json!([{
+                "data_type": "uint16",
+                "nodata": 0.0,
+                "spatial_resolution": 10.0,
+                "scale": 0.0001,
+                "offset": -0.1
+            }])
+        );
+    }
+
+    #[test]
+    fn big_tiff_pixel_is_point() {
+        let asset = asset("data/adapters/geotiff/geographic.tif");
+        let fields = &asset.additional_fields;
+        assert_eq!(fields["proj:epsg"], 4326);
+        assert_eq!(
+            fields["proj:transform"],
+            json!([0.5, 0.0, -10.25, 0.0, -0.5, 50.25])
+        );
+        assert_eq!(fields["proj:bbox"], json!([-10.25, 49.25, -9.25, 50.25]));
+        assert_eq!(fields["raster:bands"][0]["data_type"], "float32");
+        assert_eq!(fields["raster:bands"][0]["nodata"], -9999.0);
+    }
+
+    #[test]
+    fn mixed_crs_item() {
+        let mut item = Item::new("an-id");
+        for (key, path) in [
+            ("utm", "data/adapters/geotiff/utm.tif"),
+            ("geographic", "data/adapters/geotiff/geographic.tif"),
+        ] {
+            let _ = item.assets.insert(key.to_string(), Asset::new(path));
+            assert!(GeoTiff::read(path).unwrap().update_item(&mut item, key));
+        }
+        assert_eq!(item.extensions.as_ref().unwrap().len(), 3);
+        assert_eq!(item.assets["utm"].additional_fields["proj:epsg"], 32633);
+        assert_eq!(
+            item.assets["geographic"].additional_fields["proj:epsg"],
+            4326
+        );
+        assert!(!GeoTiff::read("data/adapters/geotiff/utm.tif")
+            .unwrap()
+            .update_item(&mut item, "not-an-asset"));
+    }
+
+    #[test]
+    fn not_a_tiff() {
+        let error = GeoTiff::from_reader(Cursor::new(b"not a tiff")).unwrap_err();
+        assert!(matches!(error, Error::InvalidMetadata(_)));
+    }
+
+    #[test]
+    fn too_many_values() {
+        // A little-endian BigTIFF with one ImageWidth entry claiming 2^62
+        // eight-byte values.
+        let mut bytes = b"II".to_vec();
+        bytes.extend(43u16.to_le_bytes());
+        bytes.extend(8u16.to_le_bytes());
+        bytes.extend(0u16.to_le_bytes());
+        bytes.extend(16u64.to_le_bytes());
+        bytes.extend(1u64.to_le_bytes());
+        bytes.extend(256u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend((1u64 << 62).to_le_bytes());
+        bytes.extend(0u64.to_le_bytes());
+        let error = GeoTiff::from_reader(Cursor::new(bytes)).unwrap_err();
+        assert!(
+            matches!(&error, Error::InvalidMetadata(message) if message.contains("too many values")),
+            "{:?}",
+            error
+        );
+    }
+}
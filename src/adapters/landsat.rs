@@ -0,0 +1,619 @@
+use super::{extension_uris, Band};
+use crate::{geometry, media_type, Asset, Error, Item, Result};
+use chrono::{DateTime, SecondsFormat};
+use geojson::{Geometry, Value as GeometryValue};
+use roxmltree::Document;
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, path::Path};
+
+/// The extensions used by Landsat items.
+const EXTENSIONS: [(&str, &str); 3] = [("eo", "1.1.0"), ("projection", "1.1.0"), ("view", "1.0.0")];
+
+/// The OLI and TIRS bands of Landsat 8 and 9.
+///
+/// Wavelengths are in micrometers, from the USGS band designations.
+const OLI_TIRS_BANDS: [Band; 11] = [
+    band("B1", "coastal", 0.44, 0.02),
+    band("B2", "blue", 0.48, 0.06),
+    band("B3", "green", 0.56, 0.06),
+    band("B4", "red", 0.65, 0.04),
+    band("B5", "nir08", 0.86, 0.03),
+    band("B6", "swir16", 1.6, 0.08),
+    band("B7", "swir22", 2.2, 0.2),
+    band("B8", "pan", 0.59, 0.18),
+    band("B9", "cirrus", 1.37, 0.02),
+    band("B10", "lwir11", 10.9, 0.8),
+    band("B11", "lwir12", 12.0, 1.0),
+];
+
+/// The ETM+ bands of Landsat 7.
+const ETM_BANDS: [Band; 8] = [
+    band("B1", "blue", 0.48, 0.07),
+    band("B2", "green", 0.56, 0.08),
+    band("B3", "red", 0.66, 0.06),
+    band("B4", "nir08", 0.84, 0.12),
+    band("B5", "swir16", 1.65, 0.2),
+    band("B6", "lwir", 11.45, 2.1),
+    band("B7", "swir22", 2.22, 0.26),
+    band("B8", "pan", 0.71, 0.38),
+];
+
+/// The TM bands of Landsat 4 and 5, which are the ETM+ bands without the panchromatic band.
+const TM_BANDS: [Band; 7] = [
+    ETM_BANDS[0],
+    ETM_BANDS[1],
+    ETM_BANDS[2],
+    ETM_BANDS[3],
+    ETM_BANDS[4],
+    ETM_BANDS[5],
+    ETM_BANDS[6],
+];
+
+const fn band(
+    name: &'static str,
+    common_name: &'static str,
+    center_wavelength: f64,
+    full_width_half_max: f64,
+) -> Band {
+    Band {
+        name,
+        common_name,
+        center_wavelength,
+        full_width_half_max,
+    }
+}
+
+/// Creates [Items](Item) from the MTL metadata of Landsat Collection 2 products.
+///
+/// The MTL file can be in any of the three formats that USGS distributes:
+/// ODL text (`_MTL.txt`), XML (`_MTL.xml`), or JSON (`_MTL.json`). The item gets:
+///
+/// - the product corners as its geometry
+/// - [eo](https://github.com/stac-extensions/eo) cloud cover and per-asset bands
+/// - [projection](https://github.com/stac-extensions/projection) EPSG code, and per-asset shapes and transforms
+/// - [view](https://github.com/stac-extensions/view) sun angles and off-nadir angle
+/// - an asset for each file in the product, keyed by its file name suffix,
+///   e.g. `SR_B4` for `LC08_L2SP_..._SR_B4.TIF`, or `mtl_txt` for `LC08_L2SP_..._MTL.txt`
+///
+/// # Examples
+///
+/// ```
+/// use stac::adapters::Landsat;
+/// let landsat = Landsat::read(
+///     "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL.txt",
+/// )
+/// .unwrap();
+/// let item = landsat.to_item().unwrap();
+/// assert_eq!(item.properties.additional_fields["platform"], "landsat-8");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Landsat {
+    metadata: String,
+    root: String,
+}
+
+/// The groups of an MTL file, each with its fields in file order.
+#[derive(Debug, Default)]
+struct Mtl(HashMap<String, Vec<(String, String)>>);
+
+impl Landsat {
+    /// Reads an MTL file.
+    ///
+    /// Asset hrefs are relative to the current directory, i.e. they start
+    /// with the MTL file's directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Landsat;
+    /// let landsat = Landsat::read(
+    ///     "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL.xml",
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn read(path: impl AsRef<Path>) -> Result<Landsat> {
+        let path = path.as_ref();
+        let landsat = Landsat::new(std::fs::read_to_string(path)?);
+        match path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            Some(parent) => Ok(landsat.with_root(parent.to_string_lossy().replace('\\', "/"))),
+            None => Ok(landsat),
+        }
+    }
+
+    /// Creates a new adapter from the contents of an MTL file in any format.
+    ///
+    /// Asset hrefs are bare file names unless a root is set with
+    /// [with_root](Landsat::with_root).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Landsat;
+    /// let mtl = std::fs::read_to_string(
+    ///     "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL.json",
+    /// )
+    /// .unwrap();
+    /// let item = Landsat::new(mtl).to_item().unwrap();
+    /// assert_eq!(
+    ///     item.assets["SR_B4"].href,
+    ///     "LC08_L2SP_047027_20201204_20210313_02_T1_SR_B4.TIF"
+    /// );
+    /// ```
+    pub fn new(metadata: impl ToString) -> Landsat {
+        Landsat {
+            metadata: metadata.to_string(),
+            root: String::new(),
+        }
+    }
+
+    /// Sets the href of the product's directory, which asset hrefs are joined to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Landsat;
+    /// # let mtl = std::fs::read_to_string("data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL.txt").unwrap();
+    /// let item = Landsat::new(mtl)
+    ///     .with_root("s3://a-bucket/LC08_L2SP_047027_20201204_20210313_02_T1")
+    ///     .to_item()
+    ///     .unwrap();
+    /// assert!(item.assets["SR_B4"].href.starts_with("s3://a-bucket/"));
+    /// ```
+    pub fn with_root(mut self, root: impl ToString) -> Landsat {
+        self.root = root.to_string().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Creates an item from the metadata.
+    ///
+    /// Returns [Error::InvalidMetadata] if a required field is missing or
+    /// can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Landsat;
+    /// let landsat = Landsat::read(
+    ///     "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL.txt",
+    /// )
+    /// .unwrap();
+    /// let item = landsat.to_item().unwrap();
+    /// assert_eq!(item.id, "LC08_L2SP_047027_20201204_20210313_02_T1");
+    /// ```
+    pub fn to_item(&self) -> Result<Item> {
+        let mtl = Mtl::read(&self.metadata)?;
+        let id = mtl.get("PRODUCT_CONTENTS", "LANDSAT_PRODUCT_ID")?;
+        let mut item = Item::new(id);
+        item.extensions = Some(extension_uris(&EXTENSIONS));
+        item.geometry = Some(footprint(&mtl)?);
+        item.bbox = item.geometry.as_ref().and_then(geometry::bbox);
+        let datetime = format!(
+            "{}T{}",
+            mtl.get("IMAGE_ATTRIBUTES", "DATE_ACQUIRED")?,
+            mtl.get("IMAGE_ATTRIBUTES", "SCENE_CENTER_TIME")?
+        );
+        item.properties.datetime = Some(
+            DateTime::parse_from_rfc3339(&datetime)
+                .map_err(|_| Error::InvalidMetadata(format!("invalid datetime: {}", datetime)))?
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        );
+
+        let properties = &mut item.properties.additional_fields;
+        let spacecraft = mtl.get("IMAGE_ATTRIBUTES", "SPACECRAFT_ID")?;
+        let _ = properties.insert(
+            "platform".to_string(),
+            spacecraft.to_lowercase().replace('_', "-").into(),
+        );
+        let _ = properties.insert("constellation".to_string(), "landsat".into());
+        let sensor = mtl.get("IMAGE_ATTRIBUTES", "SENSOR_ID")?;
+        let _ = properties.insert("instruments".to_string(), instruments(sensor).into());
+        let cloud_cover: f64 = mtl.parse("IMAGE_ATTRIBUTES", "CLOUD_COVER")?;
+        if cloud_cover >= 0.0 {
+            let _ = properties.insert("eo:cloud_cover".to_string(), cloud_cover.into());
+        }
+        if let Some(epsg) = epsg(&mtl)? {
+            let _ = properties.insert("proj:epsg".to_string(), epsg.into());
+        }
+        let _ = properties.insert(
+            "view:sun_azimuth".to_string(),
+            mtl.parse::<f64>("IMAGE_ATTRIBUTES", "SUN_AZIMUTH")?.into(),
+        );
+        let _ = properties.insert(
+            "view:sun_elevation".to_string(),
+            mtl.parse::<f64>("IMAGE_ATTRIBUTES", "SUN_ELEVATION")?
+                .into(),
+        );
+        if mtl.has("IMAGE_ATTRIBUTES", "ROLL_ANGLE") {
+            let _ = properties.insert(
+                "view:off_nadir".to_string(),
+                mtl.parse::<f64>("IMAGE_ATTRIBUTES", "ROLL_ANGLE")?
+                    .abs()
+                    .into(),
+            );
+        }
+
+        let bands: &[Band] = match sensor {
+            "OLI_TIRS" | "OLI" | "TIRS" => &OLI_TIRS_BANDS,
+            "ETM" => &ETM_BANDS,
+            "TM" => &TM_BANDS,
+            _ => &[],
+        };
+        for (key, file_name) in mtl.fields("PRODUCT_CONTENTS") {
+            if !key.starts_with("FILE_NAME_") {
+                continue;
+            }
+            let (key, asset) = self.asset(id, file_name, bands, &mtl)?;
+            let _ = item.assets.insert(key, asset);
+        }
+        Ok(item)
+    }
+
+    fn href(&self, file_name: &str) -> String {
+        if self.root.is_empty() {
+            file_name.to_string()
+        } else {
+            format!("{}/{}", self.root, file_name)
+        }
+    }
+
+    /// Creates the asset for a file in the product, e.g. `LC08_L2SP_..._SR_B4.TIF`.
+    fn asset(
+        &self,
+        id: &str,
+        file_name: &str,
+        bands: &[Band],
+        mtl: &Mtl,
+    ) -> Result<(String, Asset)> {
+        let suffix = file_name
+            .strip_prefix(id)
+            .map(|suffix| suffix.trim_start_matches('_'))
+            .unwrap_or(file_name);
+        let mut asset = Asset::new(self.href(file_name));
+        let Some(name) = suffix.strip_suffix(".TIF") else {
+            let r#type = match suffix.rsplit_once('.') {
+                Some((_, "xml")) => media_type::XML,
+                Some((_, "json")) => media_type::JSON,
+                _ => media_type::TEXT,
+            };
+            asset.r#type = Some(r#type.into());
+            asset.roles = Some(vec!["metadata".to_string()]);
+            return Ok((suffix.to_lowercase().replace('.', "_"), asset));
+        };
+
+        asset.r#type = Some(media_type::COG.into());
+        asset.roles = Some(vec![
+            if name.contains("QA") { "qa" } else { "data" }.to_string()
+        ]);
+        let band = name
+            .split('_')
+            .find(|part| {
+                part.strip_prefix('B')
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            })
+            .and_then(|part| bands.iter().find(|band| band.name == part));
+        let grid = match band.map(|band| band.common_name) {
+            Some("pan") => "PANCHROMATIC",
+            Some(common_name) if common_name.starts_with("lwir") => "THERMAL",
+            _ => "REFLECTIVE",
+        };
+        let fields = &mut asset.additional_fields;
+        if let Some(band) = band {
+            let _ = fields.insert("eo:bands".to_string(), json!([band.to_value()]));
+        }
+        fields.extend(projection(mtl, grid)?);
+        Ok((name.to_string(), asset))
+    }
+}
+
+impl Mtl {
+    /// Parses an MTL file, detecting whether it's JSON, XML, or ODL text.
+    fn read(metadata: &str) -> Result<Mtl> {
+        let metadata = metadata.trim_start();
+        if metadata.starts_with('{') {
+            Mtl::from_json(metadata)
+        } else if metadata.starts_with('<') {
+            Mtl::from_xml(metadata)
+        } else {
+            Mtl::from_odl(metadata)
+        }
+    }
+
+    fn from_json(metadata: &str) -> Result<Mtl> {
+        let value: Value = serde_json::from_str(metadata)?;
+        let mut mtl = Mtl::default();
+        let groups = value
+            .get("LANDSAT_METADATA_FILE")
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::InvalidMetadata("missing LANDSAT_METADATA_FILE".to_string()))?;
+        for (group, fields) in groups {
+            let fields = fields
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        value => value.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect();
+            let _ = mtl.0.insert(group.clone(), fields);
+        }
+        Ok(mtl)
+    }
+
+    fn from_xml(metadata: &str) -> Result<Mtl> {
+        let document = Document::parse(metadata)?;
+        let mut mtl = Mtl::default();
+        for group in document
+            .root_element()
+            .children()
+            .filter(|n| n.is_element())
+        {
+            let fields = group
+                .children()
+                .filter(|node| node.is_element())
+                .map(|node| {
+                    (
+                        node.tag_name().name().to_string(),
+                        node.text().unwrap_or_default().trim().to_string(),
+                    )
+                })
+                .collect();
+            let _ = mtl.0.insert(group.tag_name().name().to_string(), fields);
+        }
+        Ok(mtl)
+    }
+
+    /// Parses the `GROUP = ... END_GROUP = ...` format of `_MTL.txt` files.
+    fn from_odl(metadata: &str) -> Result<Mtl> {
+        let mut mtl = Mtl::default();
+        let mut group: Option<&str> = None;
+        for line in metadata.lines().map(str::trim) {
+            if line.is_empty() || line == "END" {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+                .ok_or_else(|| Error::InvalidMetadata(format!("invalid MTL line: {}", line)))?;
+            match key {
+                "GROUP" if value != "LANDSAT_METADATA_FILE" => group = Some(value),
+                "GROUP" => {}
+                "END_GROUP" => group = None,
+                _ => {
+                    if let Some(group) = group {
+                        mtl.0
+                            .entry(group.to_string())
+                            .or_default()
+                            .push((key.to_string(), value.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(mtl)
+    }
+
+    fn fields(&self, group: &str) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .get(group)
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    fn has(&self, group: &str, key: &str) -> bool {
+        self.fields(group).any(|(k, _)| k == key)
+    }
+
+    fn get(&self, group: &str, key: &str) -> Result<&str> {
+        self.fields(group)
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+            .ok_or_else(|| Error::InvalidMetadata(format!("missing {}", key)))
+    }
+
+    fn parse<T: std::str::FromStr>(&self, group: &str, key: &str) -> Result<T> {
+        let value = self.get(group, key)?;
+        value
+            .parse()
+            .map_err(|_| Error::InvalidMetadata(format!("invalid {}: {}", key, value)))
+    }
+}
+
+/// Returns the instruments for a `SENSOR_ID`, e.g. `["oli", "tirs"]` for `OLI_TIRS`.
+fn instruments(sensor: &str) -> Vec<String> {
+    sensor
+        .split('_')
+        .map(|instrument| match instrument {
+            "ETM" => "etm+".to_string(),
+            instrument => instrument.to_lowercase(),
+        })
+        .collect()
+}
+
+/// Creates a polygon from the product's corners, counterclockwise from the upper left.
+fn footprint(mtl: &Mtl) -> Result<Geometry> {
+    let corner = |corner: &str| -> Result<Vec<f64>> {
+        Ok(vec![
+            mtl.parse(
+                "PROJECTION_ATTRIBUTES",
+                &format!("CORNER_{}_LON_PRODUCT", corner),
+            )?,
+            mtl.parse(
+                "PROJECTION_ATTRIBUTES",
+                &format!("CORNER_{}_LAT_PRODUCT", corner),
+            )?,
+        ])
+    };
+    let ring = vec![
+        corner("UL")?,
+        corner("LL")?,
+        corner("LR")?,
+        corner("UR")?,
+        corner("UL")?,
+    ];
+    Ok(Geometry::new(GeometryValue::Polygon(vec![ring])))
+}
+
+/// Returns the EPSG code of a UTM or polar stereographic product.
+///
+/// Landsat UTM products always use the northern hemisphere zones.
+fn epsg(mtl: &Mtl) -> Result<Option<u32>> {
+    match mtl.get("PROJECTION_ATTRIBUTES", "MAP_PROJECTION")? {
+        "UTM" => Ok(Some(
+            32600 + mtl.parse::<u32>("PROJECTION_ATTRIBUTES", "UTM_ZONE")?,
+        )),
+        "PS" => Ok(Some(3031)),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the `gsd`, `proj:shape`, and `proj:transform` of a grid, e.g. `REFLECTIVE`.
+///
+/// MTL corner coordinates are pixel centers, so the transform's origin is
+/// half a pixel up and to the left of the upper left corner.
+fn projection(mtl: &Mtl, grid: &str) -> Result<Map<String, Value>> {
+    let group = "PROJECTION_ATTRIBUTES";
+    let mut fields = Map::new();
+    let cell_size = format!("GRID_CELL_SIZE_{}", grid);
+    if !mtl.has(group, &cell_size) {
+        return Ok(fields);
+    }
+    let cell_size: f64 = mtl.parse(group, &cell_size)?;
+    let _ = fields.insert("gsd".to_string(), cell_size.into());
+    let rows: u64 = mtl.parse(group, &format!("{}_LINES", grid))?;
+    let cols: u64 = mtl.parse(group, &format!("{}_SAMPLES", grid))?;
+    let _ = fields.insert("proj:shape".to_string(), json!([rows, cols]));
+    let x: f64 = mtl.parse(group, "CORNER_UL_PROJECTION_X_PRODUCT")?;
+    let y: f64 = mtl.parse(group, "CORNER_UL_PROJECTION_Y_PRODUCT")?;
+    let _ = fields.insert(
+        "proj:transform".to_string(),
+        json!([
+            cell_size,
+            0.0,
+            x - cell_size / 2.0,
+            0.0,
+            -cell_size,
+            y + cell_size / 2.0
+        ]),
+    );
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Landsat;
+    use crate::Error;
+
+    const MTL: &str = "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_MTL";
+
+    #[test]
+    fn level_2() {
+        let item = Landsat::read(format!("{}.txt", MTL))
+            .unwrap()
+            .to_item()
+            .unwrap();
+        assert_eq!(
+            item.properties.datetime.as_deref(),
+            Some("2020-12-04T19:02:12.713042Z")
+        );
+        assert_eq!(
+            item.bbox.as_ref().unwrap(),
+            &[-124.36544, 46.09418, -121.20839, 48.24297]
+        );
+        let properties = &item.properties.additional_fields;
+        assert_eq!(
+            properties["instruments"],
+            serde_json::json!(["oli", "tirs"])
+        );
+        assert_eq!(properties["eo:cloud_cover"], 1.55);
+        assert_eq!(properties["proj:epsg"], 32610);
+        assert_eq!(properties["view:off_nadir"], 0.001);
+
+        let mut keys: Vec<_> = item.assets.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "QA_PIXEL",
+                "QA_RADSAT",
+                "SR_B1",
+                "SR_B2",
+                "SR_B3",
+                "SR_B4",
+                "SR_B5",
+                "SR_B6",
+                "SR_B7",
+                "SR_QA_AEROSOL",
+                "ST_B10",
+                "ST_QA",
+                "ST_TRAD",
+                "ang_txt",
+                "mtl_txt",
+                "mtl_xml"
+            ]
+        );
+        let b4 = &item.assets["SR_B4"];
+        assert_eq!(
+            b4.href,
+            "data/adapters/landsat/LC08_L2SP_047027_20201204_20210313_02_T1_SR_B4.TIF"
+        );
+        assert_eq!(b4.additional_fields["eo:bands"][0]["common_name"], "red");
+        assert_eq!(b4.additional_fields["gsd"], 30.0);
+        assert_eq!(
+            b4.additional_fields["proj:transform"],
+            serde_json::json!([30.0, 0.0, 398385.0, 0.0, -30.0, 5343015.0])
+        );
+        assert_eq!(
+            item.assets["ST_B10"].additional_fields["eo:bands"][0]["common_name"],
+            "lwir11"
+        );
+        assert_eq!(item.assets["QA_PIXEL"].roles.as_ref().unwrap(), &["qa"]);
+        assert_eq!(
+            item.assets["mtl_xml"].r#type.as_deref(),
+            Some(crate::media_type::XML)
+        );
+    }
+
+    #[test]
+    fn formats_agree() {
+        let item = |extension: &str| {
+            Landsat::read(format!("{}.{}", MTL, extension))
+                .unwrap()
+                .to_item()
+                .unwrap()
+        };
+        let txt = item("txt");
+        assert_eq!(txt, item("xml"));
+        assert_eq!(txt, item("json"));
+    }
+
+    #[cfg(feature = "schemas")]
+    #[test]
+    fn valid() {
+        let item = Landsat::read(format!("{}.txt", MTL))
+            .unwrap()
+            .to_item()
+            .unwrap();
+        let value = serde_json::to_value(item).unwrap();
+        let issues = crate::validate::Schemas::new().validate(&value).unwrap();
+        assert!(
+            issues.iter().all(|issue| issue.rule != "schema"),
+            "{:?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn missing_field() {
+        let error = Landsat::new("GROUP = PRODUCT_CONTENTS\nEND_GROUP = PRODUCT_CONTENTS\nEND")
+            .to_item()
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidMetadata(_)));
+    }
+}
@@ -0,0 +1,483 @@
+use super::{extension_uris, geotiff};
+use crate::{geometry, media_type, utm, Asset, Error, Item, Result};
+use chrono::NaiveDate;
+use geojson::{Geometry, Value as GeometryValue};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// The extensions used by point cloud items.
+const EXTENSIONS: [(&str, &str); 2] = [("pointcloud", "1.0.0"), ("projection", "1.1.0")];
+
+/// The size of the LAS 1.0–1.2 header; later versions append fields.
+const MIN_HEADER_SIZE: usize = 227;
+
+/// The size of the LAS 1.4 header.
+const MAX_HEADER_SIZE: usize = 375;
+
+/// The record id of an OGC WKT coordinate system record.
+const WKT_RECORD: u16 = 2112;
+
+/// The record id of a GeoTIFF GeoKeyDirectory record.
+const GEO_KEY_DIRECTORY_RECORD: u16 = 34735;
+
+/// Creates [Items](Item) from the headers of LAS and LAZ point cloud files.
+///
+/// Only the public header and the (extended) variable length records are
+/// read, not the points. The item gets:
+///
+/// - its id from the file name
+/// - [pointcloud](https://github.com/stac-extensions/pointcloud) `pc:count`,
+///   `pc:type`, `pc:encoding`, `pc:schemas` (the point format's dimensions),
+///   and `pc:statistics` (the X, Y, and Z bounds)
+/// - [projection](https://github.com/stac-extensions/projection) `proj:epsg`,
+///   from the WKT or GeoTIFF keys, and `proj:bbox`, the three-dimensional bounds
+/// - a geometry and bounding box, if the coordinate reference system is
+///   geographic (EPSG:4326 or EPSG:4269) or WGS84 or NAD83 UTM
+/// - the file creation date as its datetime, if the header has one
+/// - a `data` asset for the file
+///
+/// # Examples
+///
+/// ```
+/// use stac::adapters::Las;
+/// let las = Las::read("data/adapters/pointcloud/points.las").unwrap();
+/// let item = las.to_item().unwrap();
+/// assert_eq!(item.properties.additional_fields["pc:count"], 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Las {
+    point_format: u8,
+    compressed: bool,
+    point_count: u64,
+    min: [f64; 3],
+    max: [f64; 3],
+    epsg: Option<u32>,
+    created: Option<NaiveDate>,
+    href: Option<String>,
+}
+
+impl Las {
+    /// Reads the header of a LAS or LAZ file.
+    ///
+    /// The item's `data` asset href is the path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Las;
+    /// let las = Las::read("data/adapters/pointcloud/points.laz").unwrap();
+    /// ```
+    pub fn read(path: impl AsRef<Path>) -> Result<Las> {
+        let path = path.as_ref();
+        let las = Las::from_reader(BufReader::new(File::open(path)?))?;
+        Ok(las.with_href(path.to_string_lossy().replace('\\', "/")))
+    }
+
+    /// Reads the header of a LAS or LAZ file from a reader.
+    ///
+    /// The item won't have a `data` asset, and can't be created without an
+    /// id, unless an href is set with [with_href](Las::with_href).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Las;
+    /// use std::io::Cursor;
+    /// let bytes = std::fs::read("data/adapters/pointcloud/points.las").unwrap();
+    /// let las = Las::from_reader(Cursor::new(bytes)).unwrap();
+    /// assert!(las.to_item().is_err());
+    /// ```
+    pub fn from_reader(mut reader: impl Read + Seek) -> Result<Las> {
+        let mut header = vec![0; MIN_HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        if !header.starts_with(b"LASF") {
+            return Err(Error::InvalidMetadata("not a LAS file".to_string()));
+        }
+        let version = (header[24], header[25]);
+        let header_size = usize::from(u16_at(&header, 94));
+        if version >= (1, 4) && header_size >= MAX_HEADER_SIZE {
+            header.resize(MAX_HEADER_SIZE, 0);
+            reader.read_exact(&mut header[MIN_HEADER_SIZE..])?;
+        }
+        let vlr_count = u32_at(&header, 100);
+        let point_format = header[104];
+        let mut point_count = u64::from(u32_at(&header, 107));
+        let (mut evlr_start, mut evlr_count) = (0, 0);
+        if header.len() >= MAX_HEADER_SIZE {
+            evlr_start = u64_at(&header, 235);
+            evlr_count = u32_at(&header, 243);
+            point_count = point_count.max(u64_at(&header, 247));
+        }
+        let (day, year) = (u16_at(&header, 90), u16_at(&header, 92));
+        let created = if day > 0 && year > 0 {
+            NaiveDate::from_yo_opt(i32::from(year), u32::from(day))
+        } else {
+            None
+        };
+
+        let mut epsg = None;
+        let _ = reader.seek(SeekFrom::Start(header_size as u64))?;
+        for _ in 0..vlr_count {
+            let mut vlr = [0; 54];
+            reader.read_exact(&mut vlr)?;
+            let length = u64::from(u16_at(&vlr, 20));
+            epsg = epsg.or(crs_record(
+                &mut reader,
+                &vlr[2..18],
+                u16_at(&vlr, 18),
+                length,
+            )?);
+        }
+        if evlr_count > 0 && evlr_start > 0 {
+            let _ = reader.seek(SeekFrom::Start(evlr_start))?;
+            for _ in 0..evlr_count {
+                let mut evlr = [0; 60];
+                reader.read_exact(&mut evlr)?;
+                let length = u64_at(&evlr, 20);
+                epsg = epsg.or(crs_record(
+                    &mut reader,
+                    &evlr[2..18],
+                    u16_at(&evlr, 18),
+                    length,
+                )?);
+            }
+        }
+
+        Ok(Las {
+            point_format: point_format & 0x3f,
+            compressed: point_format & 0x80 != 0,
+            point_count,
+            min: [
+                f64_at(&header, 187),
+                f64_at(&header, 203),
+                f64_at(&header, 219),
+            ],
+            max: [
+                f64_at(&header, 179),
+                f64_at(&header, 195),
+                f64_at(&header, 211),
+            ],
+            epsg,
+            created,
+            href: None,
+        })
+    }
+
+    /// Sets the href of the file, which is used for the item id and the `data` asset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Las;
+    /// # let bytes = std::fs::read("data/adapters/pointcloud/points.las").unwrap();
+    /// let las = Las::from_reader(std::io::Cursor::new(bytes))
+    ///     .unwrap()
+    ///     .with_href("s3://a-bucket/tile-a.las");
+    /// let item = las.to_item().unwrap();
+    /// assert_eq!(item.id, "tile-a");
+    /// ```
+    pub fn with_href(mut self, href: impl ToString) -> Las {
+        self.href = Some(href.to_string());
+        self
+    }
+
+    /// Creates an item from the header.
+    ///
+    /// Returns [Error::InvalidMetadata] if there's no href to take the id from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Las;
+    /// let las = Las::read("data/adapters/pointcloud/points.laz").unwrap();
+    /// let item = las.to_item().unwrap();
+    /// assert_eq!(item.properties.additional_fields["pc:encoding"], "laszip");
+    /// ```
+    pub fn to_item(&self) -> Result<Item> {
+        let href = self
+            .href
+            .as_deref()
+            .ok_or_else(|| Error::InvalidMetadata("missing id".to_string()))?;
+        let file_name = href.rsplit('/').next().unwrap_or(href);
+        let id = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem);
+        let mut item = Item::new(id);
+        item.extensions = Some(extension_uris(&EXTENSIONS));
+        item.geometry = self.geometry();
+        item.bbox = item.geometry.as_ref().and_then(geometry::bbox);
+        if let Some(created) = self.created {
+            item.properties.datetime = Some(format!("{}T00:00:00Z", created));
+        }
+
+        let properties = &mut item.properties.additional_fields;
+        let _ = properties.insert("pc:count".to_string(), self.point_count.into());
+        let _ = properties.insert("pc:type".to_string(), "lidar".into());
+        let _ = properties.insert(
+            "pc:encoding".to_string(),
+            if self.compressed { "laszip" } else { "las" }.into(),
+        );
+        let _ = properties.insert("pc:schemas".to_string(), self.schemas().into());
+        let _ = properties.insert(
+            "pc:statistics".to_string(),
+            ["X", "Y", "Z"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    json!({"name": name, "minimum": self.min[i], "maximum": self.max[i]})
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        );
+        let _ = properties.insert("proj:epsg".to_string(), self.epsg.into());
+        let _ = properties.insert(
+            "proj:bbox".to_string(),
+            json!([
+                self.min[0],
+                self.min[1],
+                self.min[2],
+                self.max[0],
+                self.max[1],
+                self.max[2]
+            ]),
+        );
+
+        let mut asset = Asset::new(href);
+        asset.r#type = Some(
+            if self.compressed {
+                media_type::LAZ
+            } else {
+                media_type::LAS
+            }
+            .into(),
+        );
+        asset.roles = Some(vec!["data".to_string()]);
+        let _ = item.assets.insert("data".to_string(), asset);
+        Ok(item)
+    }
+
+    /// Returns the footprint in WGS84, if the bounds can be converted.
+    fn geometry(&self) -> Option<Geometry> {
+        let corners = [
+            (self.min[0], self.min[1]),
+            (self.max[0], self.min[1]),
+            (self.max[0], self.max[1]),
+            (self.min[0], self.max[1]),
+            (self.min[0], self.min[1]),
+        ];
+        let ring: Vec<Vec<f64>> = match self.epsg? {
+            4326 | 4269 => corners.iter().map(|&(x, y)| vec![x, y]).collect(),
+            epsg => {
+                let (zone, north) = utm::zone(epsg)?;
+                corners
+                    .iter()
+                    .map(|&(x, y)| {
+                        let (lon, lat) = utm::to_lon_lat(zone, north, x, y);
+                        vec![lon, lat]
+                    })
+                    .collect()
+            }
+        };
+        Some(Geometry::new(GeometryValue::Polygon(vec![ring])))
+    }
+
+    /// Returns the dimensions of the point format, named as PDAL names them.
+    ///
+    /// Coordinates are reported as scaled doubles, as readers return them.
+    fn schemas(&self) -> Vec<Value> {
+        let format = self.point_format;
+        let mut dimensions = vec![
+            ("X", 8, "floating"),
+            ("Y", 8, "floating"),
+            ("Z", 8, "floating"),
+            ("Intensity", 2, "unsigned"),
+            ("ReturnNumber", 1, "unsigned"),
+            ("NumberOfReturns", 1, "unsigned"),
+        ];
+        if format >= 6 {
+            dimensions.push(("ClassFlags", 1, "unsigned"));
+            dimensions.push(("ScanChannel", 1, "unsigned"));
+        }
+        dimensions.extend([
+            ("ScanDirectionFlag", 1, "unsigned"),
+            ("EdgeOfFlightLine", 1, "unsigned"),
+            ("Classification", 1, "unsigned"),
+            ("ScanAngleRank", 4, "floating"),
+            ("UserData", 1, "unsigned"),
+            ("PointSourceId", 2, "unsigned"),
+        ]);
+        if format != 0 && format != 2 {
+            dimensions.push(("GpsTime", 8, "floating"));
+        }
+        if matches!(format, 2 | 3 | 5 | 7 | 8 | 10) {
+            dimensions.extend([
+                ("Red", 2, "unsigned"),
+                ("Green", 2, "unsigned"),
+                ("Blue", 2, "unsigned"),
+            ]);
+        }
+        if matches!(format, 8 | 10) {
+            dimensions.push(("Infrared", 2, "unsigned"));
+        }
+        dimensions
+            .into_iter()
+            .map(|(name, size, r#type)| json!({"name": name, "size": size, "type": r#type}))
+            .collect()
+    }
+}
+
+/// Reads a (extended) variable length record's data if it describes the
+/// coordinate reference system, and returns its EPSG code.
+fn crs_record(
+    reader: &mut impl Read,
+    user_id: &[u8],
+    record_id: u16,
+    length: u64,
+) -> Result<Option<u32>> {
+    let is_crs = user_id.starts_with(b"LASF_Projection")
+        && matches!(record_id, WKT_RECORD | GEO_KEY_DIRECTORY_RECORD);
+    let mut data = Vec::new();
+    let _ = reader.take(length).read_to_end(&mut data)?;
+    if data.len() as u64 != length {
+        return Err(Error::InvalidMetadata(
+            "truncated variable length record".to_string(),
+        ));
+    }
+    if !is_crs {
+        return Ok(None);
+    }
+    if record_id == WKT_RECORD {
+        Ok(wkt_epsg(
+            String::from_utf8_lossy(&data).trim_end_matches('\0'),
+        ))
+    } else {
+        let directory: Vec<f64> = data
+            .chunks_exact(2)
+            .map(|b| f64::from(u16::from_le_bytes([b[0], b[1]])))
+            .collect();
+        Ok(geotiff::epsg(&geotiff::geo_keys(&directory)).map(u32::from))
+    }
+}
+
+/// Returns the EPSG code of a WKT1 or WKT2 coordinate reference system, which
+/// is its last authority.
+fn wkt_epsg(wkt: &str) -> Option<u32> {
+    let (_, code) = ["AUTHORITY[\"EPSG\",\"", "ID[\"EPSG\","]
+        .iter()
+        .filter_map(|prefix| wkt.rfind(prefix).map(|i| (i, &wkt[i + prefix.len()..])))
+        .max_by_key(|(i, _)| *i)?;
+    let digits: String = code.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("four bytes"))
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("eight bytes"))
+}
+
+fn f64_at(bytes: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("eight bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Las;
+    use crate::Error;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn las_1_4_with_wkt() {
+        let item = Las::read("data/adapters/pointcloud/points.las")
+            .unwrap()
+            .to_item()
+            .unwrap();
+        assert_eq!(item.id, "points");
+        assert_eq!(
+            item.properties.datetime.as_deref(),
+            Some("2023-04-10T00:00:00Z")
+        );
+        let properties = &item.properties.additional_fields;
+        assert_eq!(properties["pc:count"], 3);
+        assert_eq!(properties["pc:type"], "lidar");
+        assert_eq!(properties["pc:encoding"], "las");
+        assert_eq!(properties["proj:epsg"], 32610);
+        assert_eq!(
+            properties["proj:bbox"],
+            json!([538000.0, 5205200.0, 10.0, 538100.0, 5205300.0, 50.0])
+        );
+        assert_eq!(
+            properties["pc:statistics"][2],
+            json!({"name": "Z", "minimum": 10.0, "maximum": 50.0})
+        );
+        let schemas = properties["pc:schemas"].as_array().unwrap();
+        assert_eq!(schemas.len(), 15);
+        assert_eq!(schemas[14]["name"], "GpsTime");
+        let bbox = item.bbox.as_ref().unwrap();
+        assert!((bbox[0] + 122.4997).abs() < 1e-3, "{:?}", bbox);
+        assert!((bbox[1] - 47.0).abs() < 1e-2, "{:?}", bbox);
+        let asset = &item.assets["data"];
+        assert_eq!(asset.r#type.as_deref(), Some(crate::media_type::LAS));
+    }
+
+    #[test]
+    fn laz_with_geo_keys() {
+        let item = Las::read("data/adapters/pointcloud/points.laz")
+            .unwrap()
+            .to_item()
+            .unwrap();
+        let properties = &item.properties.additional_fields;
+        assert_eq!(properties["pc:count"], 1000);
+        assert_eq!(properties["pc:encoding"], "laszip");
+        assert_eq!(properties["proj:epsg"], 26910);
+        let schemas = properties["pc:schemas"].as_array().unwrap();
+        assert_eq!(schemas.last().unwrap()["name"], "Blue");
+        assert!(item.geometry.is_some());
+        assert_eq!(
+            item.assets["data"].r#type.as_deref(),
+            Some(crate::media_type::LAZ)
+        );
+    }
+
+    #[cfg(feature = "schemas")]
+    #[test]
+    fn valid() {
+        let item = Las::read("data/adapters/pointcloud/points.las")
+            .unwrap()
+            .to_item()
+            .unwrap();
+        let value = serde_json::to_value(item).unwrap();
+        let issues = crate::validate::Schemas::new().validate(&value).unwrap();
+        assert!(
+            issues.iter().all(|issue| issue.rule != "schema"),
+            "{:?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn not_las() {
+        let error = Las::from_reader(Cursor::new(vec![0; 400])).unwrap_err();
+        assert!(matches!(error, Error::InvalidMetadata(_)));
+    }
+
+    #[test]
+    fn wkt_epsg() {
+        assert_eq!(
+            super::wkt_epsg(r#"PROJCRS["a",BASEGEOGCRS["b",ID["EPSG",4326]],ID["EPSG",32633]]"#),
+            Some(32633)
+        );
+        assert_eq!(super::wkt_epsg("LOCAL_CS[\"a\"]"), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Create [Items](crate::Item) from sensor metadata.
+//!
+//! This module is enabled by the `adapters` feature. Each adapter reads the
+//! metadata files that ship with a sensor's products, without reading the
+//! imagery itself, and builds a spec-valid item with the relevant extension
+//! fields and an asset for each band file.
+//!
+//! ```
+//! use stac::adapters::Sentinel2;
+//! let sentinel2 = Sentinel2::read(
+//!     "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE",
+//! )
+//! .unwrap();
+//! let item = sentinel2.to_item().unwrap();
+//! ```
+
+mod crawl;
+mod geotiff;
+mod landsat;
+mod las;
+mod netcdf;
+mod sentinel2;
+#[cfg(feature = "watch")]
+mod watch;
+
+#[cfg(feature = "watch")]
+pub use watch::{Change, Watch};
+pub use {
+    crawl::{Crawl, Group},
+    geotiff::GeoTiff,
+    landsat::Landsat,
+    las::Las,
+    netcdf::NetCdf,
+    sentinel2::Sentinel2,
+};
+
+use crate::{extensions, Error, Result};
+use roxmltree::{Document, Node};
+use serde_json::{json, Value};
+
+/// A spectral band, as described by the [eo extension](https://github.com/stac-extensions/eo).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Band {
+    name: &'static str,
+    common_name: &'static str,
+    center_wavelength: f64,
+    full_width_half_max: f64,
+}
+
+impl Band {
+    fn to_value(self) -> Value {
+        json!({
+            "name": self.name,
+            "common_name": self.common_name,
+            "center_wavelength": self.center_wavelength,
+            "full_width_half_max": self.full_width_half_max,
+        })
+    }
+}
+
+/// Returns the schema URIs for these extension versions.
+fn extension_uris(extensions: &[(&str, &str)]) -> Vec<String> {
+    extensions
+        .iter()
+        .map(|(identifier, version)| {
+            extensions::get(identifier)
+                .expect("adapters should only use registered extensions")
+                .uri(version)
+        })
+        .collect()
+}
+
+/// Returns the first descendant element with this (local) tag name.
+fn find<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants()
+        .find(|node| node.is_element() && node.tag_name().name() == name)
+}
+
+/// Returns the trimmed text of the first descendant element with this tag name.
+fn text<'a>(document: &'a Document<'_>, name: &str) -> Result<&'a str> {
+    find(document.root(), name)
+        .and_then(|node| node.text())
+        .map(str::trim)
+        .ok_or_else(|| Error::InvalidMetadata(format!("missing {}", name)))
+}
+
+/// Parses the text of a node's first descendant element with this tag name.
+fn parse<T: std::str::FromStr>(node: Node<'_, '_>, name: &str) -> Result<T> {
+    let text = find(node, name)
+        .and_then(|node| node.text())
+        .ok_or_else(|| Error::InvalidMetadata(format!("missing {}", name)))?;
+    text.trim()
+        .parse()
+        .map_err(|_| Error::InvalidMetadata(format!("invalid {}: {}", name, text)))
+}
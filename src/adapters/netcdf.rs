@@ -0,0 +1,951 @@
+use super::extension_uris;
+use crate::{media_type, Asset, Error, Item, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use geojson::{Geometry, Value as GeometryValue};
+use serde_json::{json, Map};
+use std::path::Path;
+
+/// The extensions used by NetCDF items.
+const EXTENSIONS: [(&str, &str); 1] = [("datacube", "2.2.0")];
+
+/// Variable attributes that name other, auxiliary, variables.
+const AUXILIARY_ATTRIBUTES: [&str; 5] = [
+    "bounds",
+    "climatology",
+    "coordinates",
+    "grid_mapping",
+    "ancillary_variables",
+];
+
+/// Creates [Items](Item) from the header of a NetCDF file that follows the
+/// [CF conventions](https://cfconventions.org/).
+///
+/// Only the classic formats (CDF-1, CDF-2, and CDF-5) are supported; NetCDF-4
+/// files are HDF5 files, and are rejected with [Error::InvalidMetadata]. The
+/// item gets:
+///
+/// - its id from the `id` global attribute, or the file name
+/// - its `title` and `description` from the `title` and `summary` global attributes
+/// - a bounding box and geometry from the `geospatial_{lat,lon}_{min,max}`
+///   global attributes, or the extent of the latitude and longitude coordinates
+/// - a datetime, or a start and end datetime, from the `time_coverage_start`
+///   and `time_coverage_end` global attributes, or the extent of the time coordinate
+/// - [datacube](https://github.com/stac-extensions/datacube) `cube:dimensions`
+///   for each dimension with a coordinate variable, and `cube:variables` for
+///   every other variable
+/// - a `data` asset for the file, if its href is known
+///
+/// Coordinate extents use their `bounds` (or, for time, `climatology`)
+/// variables when they have them, so a climatology's time coverage spans all
+/// of the years it averages. Times are only converted for the `standard`,
+/// `gregorian`, and `proleptic_gregorian` calendars.
+///
+/// # Examples
+///
+/// ```
+/// use stac::adapters::NetCdf;
+/// let netcdf = NetCdf::read("data/adapters/netcdf/tas_climatology_1991-2020.nc").unwrap();
+/// let item = netcdf.to_item().unwrap();
+/// assert_eq!(
+///     item.properties.additional_fields["start_datetime"],
+///     "1991-01-01T00:00:00Z"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetCdf {
+    data: Vec<u8>,
+    href: Option<String>,
+}
+
+/// The parsed header of a classic NetCDF file.
+#[derive(Debug)]
+struct Header {
+    numrecs: u64,
+    record_size: u64,
+    dimensions: Vec<Dimension>,
+    attributes: Vec<Attribute>,
+    variables: Vec<Variable>,
+}
+
+#[derive(Debug)]
+struct Dimension {
+    name: String,
+    length: u64,
+    unlimited: bool,
+}
+
+#[derive(Debug)]
+struct Attribute {
+    name: String,
+    value: AttributeValue,
+}
+
+#[derive(Debug)]
+enum AttributeValue {
+    Text(String),
+    Numbers(Vec<f64>),
+}
+
+#[derive(Debug)]
+struct Variable {
+    name: String,
+    dimensions: Vec<usize>,
+    attributes: Vec<Attribute>,
+    nc_type: u32,
+    vsize: u64,
+    begin: u64,
+}
+
+/// The axis of a coordinate variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+    Z,
+    T,
+    Other,
+}
+
+/// Reads big-endian header fields.
+#[derive(Debug)]
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+    version: u8,
+}
+
+impl NetCdf {
+    /// Reads a NetCDF file.
+    ///
+    /// The whole file is read into memory. The item's `data` asset href is the path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::NetCdf;
+    /// let netcdf = NetCdf::read("data/adapters/netcdf/tas_climatology_1991-2020.nc").unwrap();
+    /// ```
+    pub fn read(path: impl AsRef<Path>) -> Result<NetCdf> {
+        let path = path.as_ref();
+        Ok(NetCdf::new(std::fs::read(path)?).with_href(path.to_string_lossy().replace('\\', "/")))
+    }
+
+    /// Creates a new adapter from the contents of a NetCDF file.
+    ///
+    /// The item won't have a `data` asset unless an href is set with
+    /// [with_href](NetCdf::with_href).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::NetCdf;
+    /// let data = std::fs::read("data/adapters/netcdf/tas_climatology_1991-2020.nc").unwrap();
+    /// let netcdf = NetCdf::new(data);
+    /// // This file has no `id` attribute, so its href is needed for the item id.
+    /// assert!(netcdf.to_item().is_err());
+    /// ```
+    pub fn new(data: impl Into<Vec<u8>>) -> NetCdf {
+        NetCdf {
+            data: data.into(),
+            href: None,
+        }
+    }
+
+    /// Sets the href of the file, which is used for the `data` asset and, if
+    /// there's no `id` attribute, the item id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::NetCdf;
+    /// # let data = std::fs::read("data/adapters/netcdf/tas_climatology_1991-2020.nc").unwrap();
+    /// let item = NetCdf::new(data)
+    ///     .with_href("s3://a-bucket/tas.nc")
+    ///     .to_item()
+    ///     .unwrap();
+    /// assert_eq!(item.id, "tas");
+    /// assert_eq!(item.assets["data"].href, "s3://a-bucket/tas.nc");
+    /// ```
+    pub fn with_href(mut self, href: impl ToString) -> NetCdf {
+        self.href = Some(href.to_string());
+        self
+    }
+
+    /// Creates an item from the file's header and coordinate variables.
+    ///
+    /// Returns [Error::InvalidMetadata] if the file isn't a classic NetCDF
+    /// file, or if it has no `id` attribute and no href.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::NetCdf;
+    /// let netcdf = NetCdf::read("data/adapters/netcdf/tas_climatology_1991-2020.nc").unwrap();
+    /// let item = netcdf.to_item().unwrap();
+    /// assert_eq!(item.bbox.unwrap(), [-180.0, -90.0, 180.0, 90.0]);
+    /// ```
+    pub fn to_item(&self) -> Result<Item> {
+        let header = Header::read(&self.data)?;
+        let id = match text(&header.attributes, "id") {
+            Some(id) => id.to_string(),
+            None => self
+                .href
+                .as_deref()
+                .map(|href| {
+                    let file_name = href.rsplit('/').next().unwrap_or(href);
+                    file_name
+                        .rsplit_once('.')
+                        .map_or(file_name, |(stem, _)| stem)
+                        .to_string()
+                })
+                .ok_or_else(|| Error::InvalidMetadata("missing id".to_string()))?,
+        };
+        let mut item = Item::new(id);
+        item.extensions = Some(extension_uris(&EXTENSIONS));
+
+        let mut dimensions = Map::new();
+        let mut bbox = [None; 4];
+        let mut interval = (None, None);
+        for (i, dimension) in header.dimensions.iter().enumerate() {
+            let Some(variable) = header
+                .variables
+                .iter()
+                .find(|v| v.name == dimension.name && v.dimensions == [i])
+            else {
+                continue;
+            };
+            let Some((min, max)) = header.extent(&self.data, variable)? else {
+                continue;
+            };
+            let mut cube_dimension = Map::new();
+            let axis = variable.axis();
+            let units = text(&variable.attributes, "units");
+            match axis {
+                Axis::X | Axis::Y | Axis::Z => {
+                    let _ = cube_dimension.insert("type".to_string(), "spatial".into());
+                    let axis = match axis {
+                        Axis::X => "x",
+                        Axis::Y => "y",
+                        _ => "z",
+                    };
+                    let _ = cube_dimension.insert("axis".to_string(), axis.into());
+                    let _ = cube_dimension.insert("extent".to_string(), json!([min, max]));
+                    if is_degrees(units) {
+                        let _ = cube_dimension.insert("reference_system".to_string(), 4326.into());
+                        match axis {
+                            "x" => (bbox[0], bbox[2]) = (Some(min), Some(max)),
+                            "y" => (bbox[1], bbox[3]) = (Some(min), Some(max)),
+                            _ => {}
+                        }
+                    } else if let Some(units) = units {
+                        let _ = cube_dimension.insert("unit".to_string(), units.into());
+                    }
+                }
+                Axis::T => {
+                    let calendar = text(&variable.attributes, "calendar");
+                    let (Some(start), Some(end)) = (
+                        to_datetime(min, units, calendar),
+                        to_datetime(max, units, calendar),
+                    ) else {
+                        continue;
+                    };
+                    let _ = cube_dimension.insert("type".to_string(), "temporal".into());
+                    let _ = cube_dimension.insert(
+                        "extent".to_string(),
+                        json!([format_datetime(start), format_datetime(end)]),
+                    );
+                    interval = (Some(start), Some(end));
+                }
+                Axis::Other => {
+                    let _ = cube_dimension.insert("type".to_string(), "other".into());
+                    let _ = cube_dimension.insert("extent".to_string(), json!([min, max]));
+                    if let Some(units) = units {
+                        let _ = cube_dimension.insert("unit".to_string(), units.into());
+                    }
+                }
+            }
+            if let Some(long_name) = text(&variable.attributes, "long_name") {
+                let _ = cube_dimension.insert("description".to_string(), long_name.into());
+            }
+            let _ = dimensions.insert(dimension.name.clone(), cube_dimension.into());
+        }
+
+        let mut variables = Map::new();
+        for variable in &header.variables {
+            if variable.dimensions.len() == 1 && dimensions.contains_key(&variable.name) {
+                continue;
+            }
+            let auxiliary = variable.dimensions.is_empty()
+                || header.variables.iter().any(|other| {
+                    AUXILIARY_ATTRIBUTES.iter().any(|name| {
+                        text(&other.attributes, name).is_some_and(|names| {
+                            names.split_whitespace().any(|name| name == variable.name)
+                        })
+                    })
+                });
+            let mut cube_variable = Map::new();
+            let _ = cube_variable.insert(
+                "dimensions".to_string(),
+                variable
+                    .dimensions
+                    .iter()
+                    .map(|&i| header.dimensions[i].name.as_str())
+                    .collect::<Vec<_>>()
+                    .into(),
+            );
+            let _ = cube_variable.insert(
+                "type".to_string(),
+                if auxiliary { "auxiliary" } else { "data" }.into(),
+            );
+            if let Some(long_name) = text(&variable.attributes, "long_name") {
+                let _ = cube_variable.insert("description".to_string(), long_name.into());
+            }
+            if let Some(units) = text(&variable.attributes, "units") {
+                let _ = cube_variable.insert("unit".to_string(), units.into());
+            }
+            let _ = variables.insert(variable.name.clone(), cube_variable.into());
+        }
+
+        for (i, name) in [
+            "geospatial_lon_min",
+            "geospatial_lat_min",
+            "geospatial_lon_max",
+            "geospatial_lat_max",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if let Some(value) = number(&header.attributes, name) {
+                bbox[i] = Some(value);
+            }
+        }
+        if let [Some(west), Some(south), Some(east), Some(north)] = bbox {
+            let (west, east) = if east - west >= 360.0 {
+                (-180.0, 180.0)
+            } else {
+                (normalize_longitude(west), normalize_longitude(east))
+            };
+            item.geometry = Some(footprint(west, south, east, north));
+            item.bbox = Some(vec![west, south, east, north]);
+        }
+        for (i, name) in ["time_coverage_start", "time_coverage_end"]
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(datetime) = text(&header.attributes, name).and_then(parse_timestamp) {
+                if i == 0 {
+                    interval.0 = Some(datetime);
+                } else {
+                    interval.1 = Some(datetime);
+                }
+            }
+        }
+
+        let properties = &mut item.properties.additional_fields;
+        if let Some(title) = text(&header.attributes, "title") {
+            let _ = properties.insert("title".to_string(), title.into());
+        }
+        if let Some(summary) = text(&header.attributes, "summary") {
+            let _ = properties.insert("description".to_string(), summary.into());
+        }
+        match interval {
+            (Some(start), Some(end)) if start == end => {
+                item.properties.datetime = Some(format_datetime(start));
+            }
+            (start, end) if start.is_some() || end.is_some() => {
+                item.properties.datetime = None;
+                if let Some(start) = start {
+                    let _ = properties
+                        .insert("start_datetime".to_string(), format_datetime(start).into());
+                }
+                if let Some(end) = end {
+                    let _ =
+                        properties.insert("end_datetime".to_string(), format_datetime(end).into());
+                }
+            }
+            _ => {}
+        }
+        let _ = properties.insert("cube:dimensions".to_string(), dimensions.into());
+        let _ = properties.insert("cube:variables".to_string(), variables.into());
+
+        if let Some(href) = &self.href {
+            let mut asset = Asset::new(href);
+            asset.r#type = Some(media_type::NETCDF.into());
+            asset.roles = Some(vec!["data".to_string()]);
+            let _ = item.assets.insert("data".to_string(), asset);
+        }
+        Ok(item)
+    }
+}
+
+impl Header {
+    fn read(data: &[u8]) -> Result<Header> {
+        if data.starts_with(b"\x89HDF") {
+            return Err(Error::InvalidMetadata(
+                "NetCDF-4 (HDF5) files aren't supported, only the classic formats".to_string(),
+            ));
+        }
+        let version = match data {
+            [b'C', b'D', b'F', version @ (1 | 2 | 5), ..] => *version,
+            _ => return Err(Error::InvalidMetadata("not a NetCDF file".to_string())),
+        };
+        let mut cursor = Cursor {
+            data,
+            position: 4,
+            version,
+        };
+        let numrecs = match cursor.count()? {
+            0xFFFF_FFFF => 0,
+            numrecs => numrecs,
+        };
+        let mut dimensions = Vec::new();
+        for _ in 0..cursor.list(0x0A)? {
+            let name = cursor.name()?;
+            let length = cursor.count()?;
+            dimensions.push(Dimension {
+                name,
+                length: if length == 0 { numrecs } else { length },
+                unlimited: length == 0,
+            });
+        }
+        let attributes = cursor.attributes()?;
+        let mut variables = Vec::new();
+        for _ in 0..cursor.list(0x0B)? {
+            let name = cursor.name()?;
+            let mut variable_dimensions = Vec::new();
+            for _ in 0..cursor.count()? {
+                let id = cursor.count()? as usize;
+                if id >= dimensions.len() {
+                    return Err(Error::InvalidMetadata(format!(
+                        "invalid dimension id for {}: {}",
+                        name, id
+                    )));
+                }
+                variable_dimensions.push(id);
+            }
+            let attributes = cursor.attributes()?;
+            let nc_type = cursor.u32()?;
+            let _ = type_size(nc_type)?;
+            let vsize = cursor.count()?;
+            let begin = cursor.offset()?;
+            variables.push(Variable {
+                name,
+                dimensions: variable_dimensions,
+                attributes,
+                nc_type,
+                vsize,
+                begin,
+            });
+        }
+        let mut header = Header {
+            numrecs,
+            record_size: 0,
+            dimensions,
+            attributes,
+            variables,
+        };
+        let record_variables: Vec<_> = header
+            .variables
+            .iter()
+            .filter(|v| header.is_record(v))
+            .collect();
+        header.record_size = match record_variables.as_slice() {
+            // A lone record variable isn't padded between records.
+            [variable] => header
+                .record_length(variable)?
+                .checked_mul(type_size(variable.nc_type)? as u64)
+                .ok_or_else(overflow)?,
+            variables => variables
+                .iter()
+                .try_fold(0u64, |size, v| size.checked_add(v.vsize))
+                .ok_or_else(overflow)?,
+        };
+        Ok(header)
+    }
+
+    fn is_record(&self, variable: &Variable) -> bool {
+        variable
+            .dimensions
+            .first()
+            .is_some_and(|&i| self.dimensions[i].unlimited)
+    }
+
+    /// Returns the number of values in one record of a variable, or in the
+    /// whole variable if it isn't a record variable.
+    fn record_length(&self, variable: &Variable) -> Result<u64> {
+        let skip = usize::from(self.is_record(variable));
+        variable.dimensions[skip..]
+            .iter()
+            .try_fold(1u64, |length, &i| {
+                length.checked_mul(self.dimensions[i].length)
+            })
+            .ok_or_else(overflow)
+    }
+
+    /// Reads a numeric variable's values, with fill values removed and scale
+    /// and offset applied.
+    fn values(&self, data: &[u8], variable: &Variable) -> Result<Vec<f64>> {
+        if variable.nc_type == 2 {
+            return Ok(Vec::new());
+        }
+        let size = type_size(variable.nc_type)?;
+        let length = usize::try_from(self.record_length(variable)?)
+            .ok()
+            .and_then(|length| length.checked_mul(size))
+            .ok_or_else(overflow)?;
+        let truncated = || Error::InvalidMetadata(format!("truncated data for {}", variable.name));
+        let starts: Vec<u64> = if self.is_record(variable) {
+            // Every record has to fit in the data, so this bounds the number
+            // of records before we allocate for them.
+            if self
+                .numrecs
+                .checked_mul(length as u64)
+                .is_none_or(|total| total > data.len() as u64)
+            {
+                return Err(truncated());
+            }
+            (0..self.numrecs)
+                .map(|record| {
+                    record
+                        .checked_mul(self.record_size)
+                        .and_then(|offset| offset.checked_add(variable.begin))
+                        .ok_or_else(overflow)
+                })
+                .collect::<Result<_>>()?
+        } else {
+            vec![variable.begin]
+        };
+        let fill_values: Vec<f64> = ["_FillValue", "missing_value"]
+            .iter()
+            .filter_map(|name| match attribute(&variable.attributes, name) {
+                Some(AttributeValue::Numbers(numbers)) => Some(numbers.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let scale_factor = number(&variable.attributes, "scale_factor").unwrap_or(1.0);
+        let add_offset = number(&variable.attributes, "add_offset").unwrap_or(0.0);
+        let mut values = Vec::new();
+        for start in starts {
+            let bytes = usize::try_from(start)
+                .ok()
+                .and_then(|start| Some(start..start.checked_add(length)?))
+                .and_then(|range| data.get(range))
+                .ok_or_else(truncated)?;
+            values.extend(
+                numbers(bytes, variable.nc_type)?
+                    .into_iter()
+                    .filter(|value| !value.is_nan() && !fill_values.contains(value))
+                    .map(|value| value * scale_factor + add_offset),
+            );
+        }
+        Ok(values)
+    }
+
+    /// Returns the minimum and maximum of a coordinate variable, or of its
+    /// bounds variable if it has one.
+    fn extent(&self, data: &[u8], variable: &Variable) -> Result<Option<(f64, f64)>> {
+        let bounds = ["climatology", "bounds"]
+            .iter()
+            .filter_map(|name| text(&variable.attributes, name))
+            .find_map(|name| self.variables.iter().find(|v| v.name == name));
+        let values = self.values(data, bounds.unwrap_or(variable))?;
+        Ok(values.iter().fold(None, |extent, &value| match extent {
+            None => Some((value, value)),
+            Some((min, max)) => Some((f64::min(min, value), f64::max(max, value))),
+        }))
+    }
+}
+
+impl Variable {
+    fn axis(&self) -> Axis {
+        let axis = text(&self.attributes, "axis");
+        let standard_name = text(&self.attributes, "standard_name");
+        let units = text(&self.attributes, "units");
+        if axis == Some("X") || standard_name == Some("longitude") || is_degrees_east(units) {
+            Axis::X
+        } else if axis == Some("Y") || standard_name == Some("latitude") || is_degrees_north(units)
+        {
+            Axis::Y
+        } else if axis == Some("T")
+            || standard_name == Some("time")
+            || units.is_some_and(|units| units.contains(" since "))
+        {
+            Axis::T
+        } else if axis == Some("Z") || attribute(&self.attributes, "positive").is_some() {
+            Axis::Z
+        } else {
+            Axis::Other
+        }
+    }
+}
+
+impl Cursor<'_> {
+    fn bytes(&mut self, length: usize) -> Result<&[u8]> {
+        let end = self.position.checked_add(length).ok_or_else(overflow)?;
+        let bytes = self
+            .data
+            .get(self.position..end)
+            .ok_or_else(|| Error::InvalidMetadata("truncated NetCDF header".to_string()))?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// Reads bytes and the padding that aligns them to four bytes.
+    fn padded(&mut self, length: usize) -> Result<&[u8]> {
+        let start = self.position;
+        let _ = self.bytes(
+            length
+                .checked_add((4 - length % 4) % 4)
+                .ok_or_else(overflow)?,
+        )?;
+        Ok(&self.data[start..start + length])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().expect("four bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let bytes = self.bytes(8)?;
+        Ok(u64::from_be_bytes(bytes.try_into().expect("eight bytes")))
+    }
+
+    /// Reads a count, which is eight bytes in CDF-5 and four otherwise.
+    fn count(&mut self) -> Result<u64> {
+        if self.version == 5 {
+            self.u64()
+        } else {
+            self.u32().map(u64::from)
+        }
+    }
+
+    /// Reads a file offset, which is four bytes in CDF-1 and eight otherwise.
+    fn offset(&mut self) -> Result<u64> {
+        if self.version == 1 {
+            self.u32().map(u64::from)
+        } else {
+            self.u64()
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let length = self.count()? as usize;
+        let bytes = self.padded(length)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Reads a list's tag and returns its number of elements, or zero if it's absent.
+    fn list(&mut self, tag: u32) -> Result<u64> {
+        match (self.u32()?, self.count()?) {
+            (0, 0) => Ok(0),
+            (actual, count) if actual == tag => Ok(count),
+            (actual, _) => Err(Error::InvalidMetadata(format!(
+                "expected NetCDF tag {:#x}, got {:#x}",
+                tag, actual
+            ))),
+        }
+    }
+
+    fn attributes(&mut self) -> Result<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        for _ in 0..self.list(0x0C)? {
+            let name = self.name()?;
+            let nc_type = self.u32()?;
+            let length = self.count()? as usize;
+            let bytes = self.padded(
+                length
+                    .checked_mul(type_size(nc_type)?)
+                    .ok_or_else(overflow)?,
+            )?;
+            let value = if nc_type == 2 {
+                AttributeValue::Text(
+                    String::from_utf8_lossy(bytes)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                )
+            } else {
+                AttributeValue::Numbers(numbers(bytes, nc_type)?)
+            };
+            attributes.push(Attribute { name, value });
+        }
+        Ok(attributes)
+    }
+}
+
+/// Returns the error for sizes and offsets that overflow, which only happens
+/// in malformed files.
+fn overflow() -> Error {
+    Error::InvalidMetadata("NetCDF size or offset overflows".to_string())
+}
+
+/// Returns the size in bytes of a NetCDF type.
+fn type_size(nc_type: u32) -> Result<usize> {
+    match nc_type {
+        1 | 2 | 7 => Ok(1),
+        3 | 8 => Ok(2),
+        4 | 5 | 9 => Ok(4),
+        6 | 10 | 11 => Ok(8),
+        _ => Err(Error::InvalidMetadata(format!(
+            "invalid NetCDF type: {}",
+            nc_type
+        ))),
+    }
+}
+
+/// Decodes big-endian values of a numeric NetCDF type.
+fn numbers(bytes: &[u8], nc_type: u32) -> Result<Vec<f64>> {
+    let size = type_size(nc_type)?;
+    Ok(bytes
+        .chunks_exact(size)
+        .map(|b| match nc_type {
+            1 => f64::from(b[0] as i8),
+            3 => f64::from(i16::from_be_bytes([b[0], b[1]])),
+            4 => f64::from(i32::from_be_bytes([b[0], b[1], b[2], b[3]])),
+            5 => f64::from(f32::from_be_bytes([b[0], b[1], b[2], b[3]])),
+            6 => f64::from_be_bytes(b.try_into().expect("eight bytes")),
+            8 => f64::from(u16::from_be_bytes([b[0], b[1]])),
+            9 => f64::from(u32::from_be_bytes([b[0], b[1], b[2], b[3]])),
+            10 => i64::from_be_bytes(b.try_into().expect("eight bytes")) as f64,
+            11 => u64::from_be_bytes(b.try_into().expect("eight bytes")) as f64,
+            _ => f64::from(b[0]),
+        })
+        .collect())
+}
+
+fn attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a AttributeValue> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == name)
+        .map(|attribute| &attribute.value)
+}
+
+fn text<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a str> {
+    match attribute(attributes, name)? {
+        AttributeValue::Text(text) => Some(text.trim()),
+        AttributeValue::Numbers(_) => None,
+    }
+}
+
+/// Returns a numeric attribute's first value, parsing text attributes.
+fn number(attributes: &[Attribute], name: &str) -> Option<f64> {
+    match attribute(attributes, name)? {
+        AttributeValue::Text(text) => text.trim().parse().ok(),
+        AttributeValue::Numbers(numbers) => numbers.first().copied(),
+    }
+}
+
+fn is_degrees_east(units: Option<&str>) -> bool {
+    matches!(
+        units,
+        Some("degrees_east" | "degree_east" | "degree_E" | "degrees_E" | "degreeE" | "degreesE")
+    )
+}
+
+fn is_degrees_north(units: Option<&str>) -> bool {
+    matches!(
+        units,
+        Some("degrees_north" | "degree_north" | "degree_N" | "degrees_N" | "degreeN" | "degreesN")
+    )
+}
+
+fn is_degrees(units: Option<&str>) -> bool {
+    is_degrees_east(units) || is_degrees_north(units)
+}
+
+/// Converts a longitude in `[0, 360]` to `[-180, 180]`.
+fn normalize_longitude(longitude: f64) -> f64 {
+    if longitude > 180.0 {
+        longitude - 360.0
+    } else {
+        longitude
+    }
+}
+
+/// Creates a polygon for a bounding box, or two if it crosses the antimeridian.
+fn footprint(west: f64, south: f64, east: f64, north: f64) -> Geometry {
+    let polygon = |west: f64, east: f64| {
+        vec![vec![
+            vec![west, south],
+            vec![east, south],
+            vec![east, north],
+            vec![west, north],
+            vec![west, south],
+        ]]
+    };
+    if west > east {
+        Geometry::new(GeometryValue::MultiPolygon(vec![
+            polygon(west, 180.0),
+            polygon(-180.0, east),
+        ]))
+    } else {
+        Geometry::new(GeometryValue::Polygon(polygon(west, east)))
+    }
+}
+
+/// Converts a time coordinate to a datetime using its CF `units`, e.g. `days since 1970-01-01`.
+fn to_datetime(value: f64, units: Option<&str>, calendar: Option<&str>) -> Option<DateTime<Utc>> {
+    if !matches!(
+        calendar,
+        None | Some("standard" | "gregorian" | "proleptic_gregorian")
+    ) {
+        return None;
+    }
+    let (unit, reference) = units?.split_once(" since ")?;
+    let seconds = match unit.trim() {
+        "seconds" | "second" | "secs" | "sec" | "s" => 1.0,
+        "minutes" | "minute" | "mins" | "min" => 60.0,
+        "hours" | "hour" | "hrs" | "hr" | "h" => 3600.0,
+        "days" | "day" | "d" => 86400.0,
+        _ => return None,
+    };
+    let reference = parse_timestamp(reference)?;
+    let milliseconds = (value * seconds * 1000.0).round();
+    reference.checked_add_signed(Duration::try_milliseconds(milliseconds as i64)?)
+}
+
+/// Parses an RFC 3339 datetime, or the looser `2000-1-1 00:00:00` form of CF
+/// reference times and ACDD attributes, which are assumed to be in UTC.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    let timestamp = timestamp.trim();
+    if let Ok(datetime) = crate::datetime::parse(timestamp) {
+        return Some(datetime);
+    }
+    let timestamp = timestamp
+        .trim_end_matches("UTC")
+        .trim_end()
+        .trim_end_matches('Z');
+    let (date, time) = match timestamp.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (timestamp, None),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let time = match time {
+        Some(time) if !time.is_empty() => NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .ok()?,
+        _ => NaiveTime::MIN,
+    };
+    Some(NaiveDateTime::new(date, time).and_utc())
+}
+
+fn format_datetime(datetime: DateTime<Utc>) -> String {
+    datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NetCdf;
+    use crate::Error;
+    use serde_json::json;
+
+    const NC: &str = "data/adapters/netcdf/tas_climatology_1991-2020.nc";
+
+    #[test]
+    fn climatology() {
+        let item = NetCdf::read(NC).unwrap().to_item().unwrap();
+        assert_eq!(item.id, "tas_climatology_1991-2020");
+        assert_eq!(item.bbox.as_ref().unwrap(), &[-180.0, -90.0, 180.0, 90.0]);
+        assert!(item.properties.datetime.is_none());
+        let properties = &item.properties.additional_fields;
+        assert_eq!(properties["start_datetime"], "1991-01-01T00:00:00Z");
+        assert_eq!(properties["end_datetime"], "2021-01-01T00:00:00Z");
+        assert_eq!(
+            properties["title"],
+            "Monthly near-surface air temperature climatology, 1991-2020"
+        );
+        let dimensions = &properties["cube:dimensions"];
+        assert_eq!(
+            dimensions["lon"],
+            json!({
+                "type": "spatial",
+                "axis": "x",
+                "extent": [0.0, 360.0],
+                "reference_system": 4326,
+                "description": "longitude"
+            })
+        );
+        assert_eq!(dimensions["lat"]["extent"], json!([-90.0, 90.0]));
+        assert_eq!(dimensions["time"]["type"], "temporal");
+        assert!(dimensions.get("nv").is_none());
+        let variables = &properties["cube:variables"];
+        assert_eq!(
+            variables["tas"],
+            json!({
+                "dimensions": ["time", "lat", "lon"],
+                "type": "data",
+                "description": "Near-Surface Air Temperature",
+                "unit": "K"
+            })
+        );
+        assert_eq!(variables["lat_bnds"]["type"], "auxiliary");
+        assert_eq!(variables["climatology_bounds"]["type"], "auxiliary");
+        assert!(variables.get("lat").is_none());
+        assert_eq!(item.assets["data"].href, NC);
+    }
+
+    #[cfg(feature = "schemas")]
+    #[test]
+    fn valid() {
+        let item = NetCdf::read(NC).unwrap().to_item().unwrap();
+        let value = serde_json::to_value(item).unwrap();
+        let issues = crate::validate::Schemas::new().validate(&value).unwrap();
+        assert!(
+            issues.iter().all(|issue| issue.rule != "schema"),
+            "{:?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn not_classic() {
+        for data in [&b"\x89HDF\r\n\x1a\n"[..], b"not netcdf"] {
+            let error = NetCdf::new(data).to_item().unwrap_err();
+            assert!(matches!(error, Error::InvalidMetadata(_)));
+        }
+    }
+
+    #[test]
+    fn overflowing_counts() {
+        // CDF-5 headers with no dimensions and one global attribute, whose
+        // name or value length overflows.
+        let header = |rest: &[u8]| {
+            let mut data = b"CDF\x05".to_vec();
+            data.extend(0u64.to_be_bytes());
+            data.extend(0u32.to_be_bytes());
+            data.extend(0u64.to_be_bytes());
+            data.extend(0x0Cu32.to_be_bytes());
+            data.extend(1u64.to_be_bytes());
+            data.extend(rest);
+            data
+        };
+        let long_name = header(&u64::MAX.to_be_bytes());
+        let mut long_value = 1u64.to_be_bytes().to_vec();
+        long_value.extend(b"a\0\0\0");
+        long_value.extend(6u32.to_be_bytes());
+        long_value.extend((1u64 << 62).to_be_bytes());
+        let long_value = header(&long_value);
+        for data in [long_name, long_value] {
+            let error = NetCdf::new(&data[..]).to_item().unwrap_err();
+            assert!(matches!(error, Error::InvalidMetadata(_)), "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn parse_timestamp() {
+        assert_eq!(
+            super::parse_timestamp("1850-1-1 0:0:0")
+                .unwrap()
+                .to_rfc3339(),
+            "1850-01-01T00:00:00+00:00"
+        );
+        assert!(super::to_datetime(1.0, Some("days since 2000-01-01"), Some("noleap")).is_none());
+        assert_eq!(
+            super::to_datetime(36.0, Some("hours since 2000-01-01T00:00:00Z"), None)
+                .unwrap()
+                .to_rfc3339(),
+            "2000-01-02T12:00:00+00:00"
+        );
+    }
+}
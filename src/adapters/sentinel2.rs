@@ -0,0 +1,480 @@
+use super::{extension_uris, find, parse, text, Band};
+use crate::{geometry, media_type, Asset, Error, Item, Result};
+use geojson::{Geometry, Value as GeometryValue};
+use roxmltree::{Document, Node};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// The extensions used by Sentinel-2 items.
+const EXTENSIONS: [(&str, &str); 4] = [
+    ("eo", "1.1.0"),
+    ("projection", "1.1.0"),
+    ("sat", "1.0.0"),
+    ("view", "1.0.0"),
+];
+
+/// The MSI bands and their native resolutions, in meters.
+///
+/// Wavelengths are in micrometers, from the Sentinel-2A spectral response functions.
+const BANDS: [(Band, u32); 13] = [
+    (band("B01", "coastal", 0.443, 0.027), 60),
+    (band("B02", "blue", 0.490, 0.098), 10),
+    (band("B03", "green", 0.560, 0.045), 10),
+    (band("B04", "red", 0.665, 0.038), 10),
+    (band("B05", "rededge", 0.704, 0.019), 20),
+    (band("B06", "rededge", 0.740, 0.018), 20),
+    (band("B07", "rededge", 0.783, 0.028), 20),
+    (band("B08", "nir", 0.842, 0.145), 10),
+    (band("B8A", "nir08", 0.865, 0.033), 20),
+    (band("B09", "nir09", 0.945, 0.026), 60),
+    (band("B10", "cirrus", 1.3735, 0.075), 60),
+    (band("B11", "swir16", 1.610, 0.143), 20),
+    (band("B12", "swir22", 2.190, 0.242), 20),
+];
+
+/// The native resolutions of the non-spectral Level-2A products.
+const OTHER_RESOLUTIONS: [(&str, u32); 4] = [("AOT", 10), ("SCL", 20), ("TCI", 10), ("WVP", 10)];
+
+const fn band(
+    name: &'static str,
+    common_name: &'static str,
+    center_wavelength: f64,
+    full_width_half_max: f64,
+) -> Band {
+    Band {
+        name,
+        common_name,
+        center_wavelength,
+        full_width_half_max,
+    }
+}
+
+/// Creates [Items](Item) from the metadata of Sentinel-2 Level-1C and Level-2A products.
+///
+/// A product is a `.SAFE` directory with a product metadata file, e.g.
+/// `MTD_MSIL2A.xml`, and a single granule whose tile metadata is in
+/// `GRANULE/{granule}/MTD_TL.xml`. The item gets:
+///
+/// - the product footprint as its geometry
+/// - [eo](https://github.com/stac-extensions/eo) cloud cover and per-asset bands
+/// - [projection](https://github.com/stac-extensions/projection) EPSG code, and per-asset shapes and transforms
+/// - [view](https://github.com/stac-extensions/view) sun and mean viewing angles
+/// - [sat](https://github.com/stac-extensions/sat) orbit state and relative orbit
+/// - an asset for each image file, keyed by band, with a resolution suffix
+///   (e.g. `B02_20m`) for bands that aren't at their native resolution
+///
+/// # Examples
+///
+/// ```
+/// use stac::adapters::Sentinel2;
+/// let sentinel2 = Sentinel2::read(
+///     "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE",
+/// )
+/// .unwrap();
+/// let item = sentinel2.to_item().unwrap();
+/// assert_eq!(item.properties.additional_fields["platform"], "sentinel-2b");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sentinel2 {
+    product_metadata: String,
+    tile_metadata: String,
+    root: String,
+}
+
+impl Sentinel2 {
+    /// Reads the metadata files in a `.SAFE` directory.
+    ///
+    /// Asset hrefs are relative to the current directory, i.e. they start
+    /// with the `.SAFE` directory's path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Sentinel2;
+    /// let sentinel2 = Sentinel2::read(
+    ///     "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE",
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn read(safe: impl AsRef<Path>) -> Result<Sentinel2> {
+        let safe = safe.as_ref();
+        let product_metadata = std::fs::read_dir(safe)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .find(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("MTD_MSI") && name.ends_with(".xml"))
+            })
+            .ok_or_else(|| {
+                Error::InvalidMetadata(format!("no product metadata in {}", safe.display()))
+            })?;
+        let tile_metadata = std::fs::read_dir(safe.join("GRANULE"))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path().join("MTD_TL.xml")))
+            .find(|path| path.exists())
+            .ok_or_else(|| {
+                Error::InvalidMetadata(format!("no tile metadata in {}", safe.display()))
+            })?;
+        Ok(Sentinel2::new(
+            std::fs::read_to_string(product_metadata)?,
+            std::fs::read_to_string(tile_metadata)?,
+        )
+        .with_root(safe.to_string_lossy().replace('\\', "/")))
+    }
+
+    /// Creates a new adapter from the contents of the product and tile metadata files.
+    ///
+    /// Asset hrefs are relative to the `.SAFE` directory unless a root is set
+    /// with [with_root](Sentinel2::with_root).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Sentinel2;
+    /// let safe = "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE";
+    /// let product = std::fs::read_to_string(format!("{}/MTD_MSIL2A.xml", safe)).unwrap();
+    /// let tile = std::fs::read_to_string(format!(
+    ///     "{}/GRANULE/L2A_T33UUP_A033101_20230704T101555/MTD_TL.xml",
+    ///     safe
+    /// ))
+    /// .unwrap();
+    /// let item = Sentinel2::new(product, tile).to_item().unwrap();
+    /// assert_eq!(item.assets["product_metadata"].href, "MTD_MSIL2A.xml");
+    /// ```
+    pub fn new(product_metadata: impl ToString, tile_metadata: impl ToString) -> Sentinel2 {
+        Sentinel2 {
+            product_metadata: product_metadata.to_string(),
+            tile_metadata: tile_metadata.to_string(),
+            root: String::new(),
+        }
+    }
+
+    /// Sets the href of the `.SAFE` directory, which asset hrefs are joined to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Sentinel2;
+    /// # let safe = "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE";
+    /// # let product = std::fs::read_to_string(format!("{}/MTD_MSIL2A.xml", safe)).unwrap();
+    /// # let tile = std::fs::read_to_string(format!("{}/GRANULE/L2A_T33UUP_A033101_20230704T101555/MTD_TL.xml", safe)).unwrap();
+    /// let item = Sentinel2::new(product, tile)
+    ///     .with_root("s3://a-bucket/S2B_MSIL2A.SAFE")
+    ///     .to_item()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     item.assets["product_metadata"].href,
+    ///     "s3://a-bucket/S2B_MSIL2A.SAFE/MTD_MSIL2A.xml"
+    /// );
+    /// ```
+    pub fn with_root(mut self, root: impl ToString) -> Sentinel2 {
+        self.root = root.to_string().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Creates an item from the metadata.
+    ///
+    /// Returns [Error::InvalidMetadata] if a required field is missing or
+    /// can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::Sentinel2;
+    /// let sentinel2 = Sentinel2::read(
+    ///     "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE",
+    /// )
+    /// .unwrap();
+    /// let item = sentinel2.to_item().unwrap();
+    /// assert_eq!(item.id, "S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542");
+    /// ```
+    pub fn to_item(&self) -> Result<Item> {
+        let product = Document::parse(&self.product_metadata)?;
+        let tile = Document::parse(&self.tile_metadata)?;
+        let id = text(&product, "PRODUCT_URI")?.trim_end_matches(".SAFE");
+        let mut item = Item::new(id);
+        item.extensions = Some(extension_uris(&EXTENSIONS));
+        item.geometry = Some(footprint(text(&product, "EXT_POS_LIST")?)?);
+        item.bbox = item.geometry.as_ref().and_then(geometry::bbox);
+        item.properties.datetime = Some(text(&tile, "SENSING_TIME")?.to_string());
+
+        let properties = &mut item.properties.additional_fields;
+        let _ = properties.insert(
+            "platform".to_string(),
+            text(&product, "SPACECRAFT_NAME")?.to_lowercase().into(),
+        );
+        let _ = properties.insert("constellation".to_string(), "sentinel-2".into());
+        let _ = properties.insert("instruments".to_string(), json!(["msi"]));
+        let _ = properties.insert(
+            "eo:cloud_cover".to_string(),
+            parse::<f64>(product.root(), "Cloud_Coverage_Assessment")?.into(),
+        );
+        let _ = properties.insert("proj:epsg".to_string(), epsg(&tile)?.into());
+        let sun = find(tile.root(), "Mean_Sun_Angle")
+            .ok_or_else(|| Error::InvalidMetadata("missing Mean_Sun_Angle".to_string()))?;
+        let _ = properties.insert(
+            "view:sun_azimuth".to_string(),
+            parse::<f64>(sun, "AZIMUTH_ANGLE")?.into(),
+        );
+        let _ = properties.insert(
+            "view:sun_elevation".to_string(),
+            (90.0 - parse::<f64>(sun, "ZENITH_ANGLE")?).into(),
+        );
+        let (incidence_angle, azimuth) = mean_viewing_angles(&tile)?;
+        if let Some(incidence_angle) = incidence_angle {
+            let _ = properties.insert("view:incidence_angle".to_string(), incidence_angle.into());
+        }
+        if let Some(azimuth) = azimuth {
+            let _ = properties.insert("view:azimuth".to_string(), azimuth.into());
+        }
+        let _ = properties.insert(
+            "sat:orbit_state".to_string(),
+            text(&product, "SENSING_ORBIT_DIRECTION")?
+                .to_lowercase()
+                .into(),
+        );
+        let _ = properties.insert(
+            "sat:relative_orbit".to_string(),
+            parse::<u64>(product.root(), "SENSING_ORBIT_NUMBER")?.into(),
+        );
+
+        let mut granule = None;
+        for image_file in product
+            .descendants()
+            .filter(|node| node.has_tag_name("IMAGE_FILE"))
+            .filter_map(|node| node.text())
+        {
+            let image_file = image_file.trim();
+            if granule.is_none() {
+                granule = image_file.split('/').nth(1);
+            }
+            let (key, asset) = self.image_asset(image_file, &tile)?;
+            let _ = item.assets.insert(key, asset);
+        }
+
+        let product_type = text(&product, "PRODUCT_TYPE")?;
+        let level = product_type.trim_start_matches("S2MSI");
+        let mut asset = Asset::new(self.href(&format!("MTD_MSIL{}.xml", level)));
+        asset.r#type = Some(media_type::XML.into());
+        asset.roles = Some(vec!["metadata".to_string()]);
+        let _ = item.assets.insert("product_metadata".to_string(), asset);
+        if let Some(granule) = granule {
+            let mut asset = Asset::new(self.href(&format!("GRANULE/{}/MTD_TL.xml", granule)));
+            asset.r#type = Some(media_type::XML.into());
+            asset.roles = Some(vec!["metadata".to_string()]);
+            let _ = item.assets.insert("granule_metadata".to_string(), asset);
+        }
+        Ok(item)
+    }
+
+    fn href(&self, path: &str) -> String {
+        if self.root.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.root, path)
+        }
+    }
+
+    /// Creates the asset for an `IMAGE_FILE`, e.g. `GRANULE/.../R10m/T33UUP_20230704T101559_B02_10m`.
+    fn image_asset(&self, image_file: &str, tile: &Document<'_>) -> Result<(String, Asset)> {
+        let file_name = image_file.rsplit('/').next().unwrap_or(image_file);
+        let mut parts = file_name.split('_').skip(2);
+        let name = parts
+            .next()
+            .ok_or_else(|| Error::InvalidMetadata(format!("invalid image file: {}", image_file)))?;
+        let band = BANDS.iter().find(|(band, _)| band.name == name);
+        let native_resolution = band.map(|(_, resolution)| *resolution).or_else(|| {
+            OTHER_RESOLUTIONS
+                .iter()
+                .find(|(other, _)| *other == name)
+                .map(|(_, resolution)| *resolution)
+        });
+        let resolution = match parts.next() {
+            Some(resolution) => resolution
+                .trim_end_matches('m')
+                .parse::<u32>()
+                .map_err(|_| {
+                    Error::InvalidMetadata(format!("invalid image file: {}", image_file))
+                })?,
+            None => native_resolution.unwrap_or(10),
+        };
+        let key = if Some(resolution) == native_resolution {
+            name.to_string()
+        } else {
+            format!("{}_{}m", name, resolution)
+        };
+
+        let mut asset = Asset::new(self.href(&format!("{}.jp2", image_file)));
+        asset.r#type = Some(media_type::JP2.into());
+        asset.roles = Some(vec![
+            if name == "TCI" { "visual" } else { "data" }.to_string()
+        ]);
+        let fields = &mut asset.additional_fields;
+        let _ = fields.insert("gsd".to_string(), resolution.into());
+        if let Some((band, _)) = band {
+            let _ = fields.insert("eo:bands".to_string(), json!([band.to_value()]));
+        }
+        fields.extend(projection(tile, resolution)?);
+        Ok((key, asset))
+    }
+}
+
+/// Parses a footprint of space-separated latitude and longitude pairs into a polygon.
+fn footprint(positions: &str) -> Result<Geometry> {
+    let numbers = positions
+        .split_whitespace()
+        .map(|n| n.parse::<f64>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| Error::InvalidMetadata(format!("invalid footprint: {}", positions)))?;
+    if numbers.len() < 8 || numbers.len() % 2 != 0 {
+        return Err(Error::InvalidMetadata(format!(
+            "invalid footprint: {}",
+            positions
+        )));
+    }
+    let ring = numbers
+        .chunks(2)
+        .map(|lat_lon| vec![lat_lon[1], lat_lon[0]])
+        .collect();
+    Ok(Geometry::new(GeometryValue::Polygon(vec![ring])))
+}
+
+fn epsg(tile: &Document<'_>) -> Result<u32> {
+    let code = text(tile, "HORIZONTAL_CS_CODE")?;
+    code.trim_start_matches("EPSG:")
+        .parse()
+        .map_err(|_| Error::InvalidMetadata(format!("invalid HORIZONTAL_CS_CODE: {}", code)))
+}
+
+/// Returns the means of the per-band viewing zenith and azimuth angles.
+fn mean_viewing_angles(tile: &Document<'_>) -> Result<(Option<f64>, Option<f64>)> {
+    let mut zeniths = Vec::new();
+    let mut azimuths = Vec::new();
+    for node in tile
+        .descendants()
+        .filter(|node| node.has_tag_name("Mean_Viewing_Incidence_Angle"))
+    {
+        zeniths.push(parse::<f64>(node, "ZENITH_ANGLE")?);
+        azimuths.push(parse::<f64>(node, "AZIMUTH_ANGLE")?);
+    }
+    let mean = |values: Vec<f64>| {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        }
+    };
+    Ok((mean(zeniths), mean(azimuths)))
+}
+
+/// Returns the `proj:shape` and `proj:transform` of the tile at a resolution.
+fn projection(tile: &Document<'_>, resolution: u32) -> Result<Map<String, Value>> {
+    let with_resolution = |name: &str| -> Option<Node<'_, '_>> {
+        tile.descendants().find(|node| {
+            node.has_tag_name(name) && node.attribute("resolution") == Some(&resolution.to_string())
+        })
+    };
+    let mut fields = Map::new();
+    if let Some(size) = with_resolution("Size") {
+        let rows: u64 = parse(size, "NROWS")?;
+        let cols: u64 = parse(size, "NCOLS")?;
+        let _ = fields.insert("proj:shape".to_string(), json!([rows, cols]));
+    }
+    if let Some(geoposition) = with_resolution("Geoposition") {
+        let ulx: f64 = parse(geoposition, "ULX")?;
+        let uly: f64 = parse(geoposition, "ULY")?;
+        let xdim: f64 = parse(geoposition, "XDIM")?;
+        let ydim: f64 = parse(geoposition, "YDIM")?;
+        let _ = fields.insert(
+            "proj:transform".to_string(),
+            json!([xdim, 0.0, ulx, 0.0, ydim, uly]),
+        );
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sentinel2;
+    use crate::Error;
+
+    const SAFE: &str =
+        "data/adapters/sentinel-2/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T132542.SAFE";
+
+    #[test]
+    fn level_2a() {
+        let item = Sentinel2::read(SAFE).unwrap().to_item().unwrap();
+        assert_eq!(
+            item.properties.datetime.as_deref(),
+            Some("2023-07-04T10:17:38.447Z")
+        );
+        assert_eq!(
+            item.bbox.as_ref().unwrap(),
+            &[12.2156, 48.6379, 13.7444, 49.6495]
+        );
+        let properties = &item.properties.additional_fields;
+        assert_eq!(properties["eo:cloud_cover"], 12.345);
+        assert_eq!(properties["proj:epsg"], 32633);
+        assert_eq!(properties["view:sun_elevation"], 59.5);
+        assert_eq!(properties["view:incidence_angle"], 5.0);
+        assert_eq!(properties["view:azimuth"], 105.0);
+        assert_eq!(properties["sat:orbit_state"], "descending");
+        assert_eq!(properties["sat:relative_orbit"], 65);
+
+        let mut keys: Vec<_> = item.assets.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "B01",
+                "B02",
+                "B02_20m",
+                "B04",
+                "B05",
+                "B08",
+                "B11",
+                "SCL",
+                "TCI",
+                "granule_metadata",
+                "product_metadata"
+            ]
+        );
+        let b02 = &item.assets["B02_20m"];
+        assert_eq!(
+            b02.href,
+            format!(
+                "{}/GRANULE/L2A_T33UUP_A033101_20230704T101555/IMG_DATA/R20m/T33UUP_20230704T101559_B02_20m.jp2",
+                SAFE
+            )
+        );
+        assert_eq!(b02.additional_fields["gsd"], 20);
+        assert_eq!(b02.additional_fields["eo:bands"][0]["common_name"], "blue");
+        assert_eq!(
+            b02.additional_fields["proj:transform"],
+            serde_json::json!([20.0, 0.0, 300000.0, 0.0, -20.0, 5500020.0])
+        );
+        assert_eq!(
+            item.assets["SCL"].additional_fields["proj:shape"],
+            serde_json::json!([5490, 5490])
+        );
+        assert_eq!(item.assets["TCI"].roles.as_ref().unwrap(), &["visual"]);
+    }
+
+    #[cfg(feature = "schemas")]
+    #[test]
+    fn valid() {
+        let item = Sentinel2::read(SAFE).unwrap().to_item().unwrap();
+        let value = serde_json::to_value(item).unwrap();
+        let issues = crate::validate::Schemas::new().validate(&value).unwrap();
+        assert!(
+            issues.iter().all(|issue| issue.rule != "schema"),
+            "{:?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn missing_field() {
+        let error = Sentinel2::new("<a/>", "<b/>").to_item().unwrap_err();
+        assert!(matches!(error, Error::InvalidMetadata(_)));
+    }
+}
@@ -0,0 +1,390 @@
+use super::{
+    crawl::{self, Format},
+    Crawl,
+};
+use crate::{layout::Strategy, stac::Handle, Layout, Object, Read, Result, Stac, Walk, Write};
+use notify::{
+    event::{AccessKind, AccessMode, ModifyKind, RenameMode},
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+/// How long the directory has to be quiet before a batch of changes is applied.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// A change to a data file below a watched directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The file was created or changed.
+    Written(PathBuf),
+
+    /// The file was removed.
+    Removed(PathBuf),
+}
+
+/// Keeps a [Stac] built by a [Crawl] in sync with its directory.
+///
+/// This is enabled by the `watch` feature. A `Watch` listens for filesystem
+/// events below the crawled directory. New and changed data files are turned
+/// into items, or replace their existing items, and removed data files remove
+/// their items. Items are grouped into collections by the same rules as the
+/// crawl, and collections that don't exist yet are created. Each affected
+/// collection's extent is recomputed from its items.
+///
+/// After each batch of changes, only the subtrees that changed are
+/// re-rendered and written with [Layout::render_subtree], so a catalog can be
+/// kept up to date as an ingest station writes files.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stac::{adapters::{Crawl, Watch}, Layout, Writer};
+/// use std::time::Duration;
+///
+/// let crawl = Crawl::new("incoming");
+/// let (mut stac, _) = crawl.build().unwrap();
+/// let mut layout = Layout::new("catalog");
+/// let writer = Writer::default();
+/// layout.layout(&mut stac).unwrap();
+/// let mut watch = Watch::new(crawl, &mut stac).unwrap();
+/// loop {
+///     let count = watch
+///         .sync(&mut stac, &mut layout, &writer, Duration::from_secs(60))
+///         .unwrap();
+///     println!("wrote {} objects", count);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Watch {
+    crawl: Crawl,
+    root: PathBuf,
+    items: HashMap<PathBuf, Handle>,
+    receiver: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Watch {
+    /// Starts watching the crawl's directory for changes to this `Stac`.
+    ///
+    /// The `Stac` should have been built by the same [Crawl], and then
+    /// optionally laid out. Its items are matched to files by the href of
+    /// their `data` asset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::{Crawl, Watch};
+    /// let crawl = Crawl::new("data/adapters/pointcloud");
+    /// let (mut stac, _) = crawl.build().unwrap();
+    /// let watch = Watch::new(crawl, &mut stac).unwrap();
+    /// ```
+    pub fn new<R: Read>(crawl: Crawl, stac: &mut Stac<R>) -> Result<Watch> {
+        let root = crawl.canonical_root()?;
+        let mut items = HashMap::new();
+        for result in stac.walk(stac.root()).items_only().visit(|stac, handle| {
+            let object = stac.get(handle)?;
+            Ok(object
+                .as_item()
+                .and_then(|item| item.assets.get("data"))
+                .map(|asset| (PathBuf::from(asset.href.as_str()), handle)))
+        }) {
+            if let Some((path, handle)) = result? {
+                let _ = items.insert(path, handle);
+            }
+        }
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        Ok(Watch {
+            crawl,
+            root,
+            items,
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    /// Applies one change to the `Stac`.
+    ///
+    /// Returns the handle of the subtree that needs to be re-rendered, or
+    /// `None` if the file isn't a recognized data file below the watched
+    /// directory. Returns an error if the file can't be read by its adapter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::{Change, Crawl, Watch};
+    /// let crawl = Crawl::new("data/adapters/pointcloud");
+    /// let (mut stac, root) = crawl.build().unwrap();
+    /// let mut watch = Watch::new(crawl, &mut stac).unwrap();
+    /// let path = std::fs::canonicalize("data/adapters/pointcloud/points.las").unwrap();
+    /// let handle = watch.apply(&mut stac, Change::Removed(path)).unwrap();
+    /// assert_eq!(handle, Some(root));
+    /// assert_eq!(stac.children(root).len(), 1);
+    /// ```
+    pub fn apply<R: Read>(&mut self, stac: &mut Stac<R>, change: Change) -> Result<Option<Handle>> {
+        match change {
+            Change::Written(path) => self.write(stac, path),
+            Change::Removed(path) => self.remove(stac, &path),
+        }
+    }
+
+    /// Waits up to `timeout` for changes, and applies them to the `Stac`.
+    ///
+    /// Once a change arrives, events are collected until the directory has
+    /// been quiet for a moment, so a file that's still being written is
+    /// only read once. Returns the handles of the subtrees that need to be
+    /// re-rendered, which is empty if nothing changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::adapters::{Crawl, Watch};
+    /// use std::time::Duration;
+    /// let crawl = Crawl::new("data/adapters/pointcloud");
+    /// let (mut stac, _) = crawl.build().unwrap();
+    /// let mut watch = Watch::new(crawl, &mut stac).unwrap();
+    /// let handles = watch.poll(&mut stac, Duration::from_millis(10)).unwrap();
+    /// assert!(handles.is_empty());
+    /// ```
+    pub fn poll<R: Read>(&mut self, stac: &mut Stac<R>, timeout: Duration) -> Result<Vec<Handle>> {
+        let mut changes: Vec<Change> = Vec::new();
+        let mut wait = timeout;
+        while let Ok(event) = self.receiver.recv_timeout(wait) {
+            for change in changes_from(event?) {
+                let path = change.path();
+                changes.retain(|c| c.path() != path);
+                changes.push(change);
+            }
+            wait = SETTLE;
+        }
+        let mut handles = Vec::new();
+        for change in changes {
+            if let Some(handle) = self.apply(stac, change)? {
+                if !handles.contains(&handle) {
+                    handles.push(handle);
+                }
+            }
+        }
+        if handles.contains(&stac.root()) {
+            handles = vec![stac.root()];
+        }
+        Ok(handles)
+    }
+
+    /// Waits up to `timeout` for changes, applies them, and writes the
+    /// re-rendered subtrees.
+    ///
+    /// The `Stac` must already have been laid out with this layout. Returns
+    /// the number of objects that were written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{adapters::{Crawl, Watch}, Layout, NdjsonWriter};
+    /// use std::time::Duration;
+    /// let crawl = Crawl::new("data/adapters/pointcloud");
+    /// let (mut stac, _) = crawl.build().unwrap();
+    /// let mut layout = Layout::new("catalog");
+    /// layout.layout(&mut stac).unwrap();
+    /// let mut watch = Watch::new(crawl, &mut stac).unwrap();
+    /// let writer = NdjsonWriter::new(Vec::new());
+    /// let count = watch
+    ///     .sync(&mut stac, &mut layout, &writer, Duration::from_millis(10))
+    ///     .unwrap();
+    /// assert_eq!(count, 0);
+    /// ```
+    pub fn sync<R: Read, S: Strategy>(
+        &mut self,
+        stac: &mut Stac<R>,
+        layout: &mut Layout<S>,
+        writer: &impl Write,
+        timeout: Duration,
+    ) -> Result<usize> {
+        let mut count = 0;
+        for handle in self.poll(stac, timeout)? {
+            for result in layout.render_subtree(stac, handle) {
+                writer.write(result?)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn write<R: Read>(&mut self, stac: &mut Stac<R>, path: PathBuf) -> Result<Option<Handle>> {
+        let Some(format) = self.format(&path) else {
+            return Ok(None);
+        };
+        let mut item = format.to_item(&path)?;
+        let group = self.crawl.group(&self.root, &path, format);
+        item.collection = group.as_deref().map(Into::into);
+        if let Some(&handle) = self.items.get(&path) {
+            *stac.get_mut(handle)? = Object::Item(item);
+            let parent = stac.parent(handle).unwrap_or_else(|| stac.root());
+            update_extent(stac, parent)?;
+            return Ok(Some(parent));
+        }
+        let root = stac.root();
+        let (parent, affected) = match group {
+            Some(id) => {
+                let existing = stac.children(root).into_iter().find(|&child| {
+                    stac.get(child)
+                        .map(|object| object.is_collection() && object.id() == id)
+                        .unwrap_or(false)
+                });
+                match existing {
+                    Some(collection) => (collection, collection),
+                    None => (stac.add_child(root, crawl::collection(&id))?, root),
+                }
+            }
+            None => (root, root),
+        };
+        let handle = stac.add_items(parent, [item])?[0];
+        let _ = self.items.insert(path, handle);
+        update_extent(stac, parent)?;
+        Ok(Some(affected))
+    }
+
+    fn remove<R: Read>(&mut self, stac: &mut Stac<R>, path: &Path) -> Result<Option<Handle>> {
+        let Some(handle) = self.items.remove(path) else {
+            return Ok(None);
+        };
+        let parent = stac.parent(handle).unwrap_or_else(|| stac.root());
+        let _ = stac.remove(handle)?;
+        update_extent(stac, parent)?;
+        Ok(Some(parent))
+    }
+
+    /// Returns the format of a path if it's a visible data file below the root.
+    fn format(&self, path: &Path) -> Option<Format> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let hidden = relative.components().any(|component| match component {
+            Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+            _ => false,
+        });
+        if hidden || !path.is_file() {
+            None
+        } else {
+            Format::from_path(path)
+        }
+    }
+}
+
+impl Change {
+    fn path(&self) -> &Path {
+        match self {
+            Change::Written(path) | Change::Removed(path) => path,
+        }
+    }
+}
+
+/// Converts a filesystem event into changes.
+fn changes_from(event: Event) -> Vec<Change> {
+    let Event {
+        kind, mut paths, ..
+    } = event;
+    match kind {
+        EventKind::Create(_)
+        | EventKind::Modify(ModifyKind::Data(_))
+        | EventKind::Modify(ModifyKind::Name(RenameMode::To))
+        | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+            paths.into_iter().map(Change::Written).collect()
+        }
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            paths.into_iter().map(Change::Removed).collect()
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            let to = paths.pop().expect("there are two paths");
+            let from = paths.pop().expect("there are two paths");
+            vec![Change::Removed(from), Change::Written(to)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Recomputes a collection's extent from its items.
+fn update_extent<R: Read>(stac: &mut Stac<R>, handle: Handle) -> Result<()> {
+    if !stac.get(handle)?.is_collection() {
+        return Ok(());
+    }
+    let mut items = Vec::new();
+    for child in stac.children(handle) {
+        if let Object::Item(item) = stac.get(child)? {
+            items.push(item.clone());
+        }
+    }
+    let extent = crawl::extent(&items)?;
+    if let Object::Collection(collection) = stac.get_mut(handle)? {
+        collection.extent = extent;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, Watch};
+    use crate::{adapters::Crawl, Layout, NdjsonWriter};
+    use std::fs;
+
+    #[test]
+    fn write_and_remove() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(directory.path()).unwrap();
+        fs::create_dir(root.join("tiles")).unwrap();
+        let _ = fs::copy("data/adapters/geotiff/utm.tif", root.join("tiles/utm.tif")).unwrap();
+        let crawl = Crawl::new(&root);
+        let (mut stac, catalog) = crawl.build().unwrap();
+        let mut layout = Layout::new(root.join("catalog").to_string_lossy().into_owned());
+        layout.layout(&mut stac).unwrap();
+        let mut watch = Watch::new(crawl, &mut stac).unwrap();
+        let tiles = stac.children(catalog)[0];
+
+        let path = root.join("tiles/geographic.tif");
+        let _ = fs::copy("data/adapters/geotiff/geographic.tif", &path).unwrap();
+        let handle = watch
+            .apply(&mut stac, Change::Written(path.clone()))
+            .unwrap();
+        assert_eq!(handle, Some(tiles));
+        assert_eq!(stac.children(tiles).len(), 2);
+        let writer = NdjsonWriter::new(Vec::new());
+        for result in layout.render_subtree(&mut stac, tiles) {
+            crate::Write::write(&writer, result.unwrap()).unwrap();
+        }
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(output.lines().count(), 3);
+
+        let path = root.join("points/points.las");
+        fs::create_dir(root.join("points")).unwrap();
+        let _ = fs::copy("data/adapters/pointcloud/points.las", &path).unwrap();
+        let handle = watch.apply(&mut stac, Change::Written(path)).unwrap();
+        assert_eq!(handle, Some(catalog));
+        assert_eq!(stac.children(catalog).len(), 2);
+
+        fs::remove_file(root.join("tiles/utm.tif")).unwrap();
+        let handle = watch
+            .apply(&mut stac, Change::Removed(root.join("tiles/utm.tif")))
+            .unwrap();
+        assert_eq!(handle, Some(tiles));
+        assert_eq!(stac.children(tiles).len(), 1);
+    }
+
+    #[test]
+    fn ignore_unrecognized() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(directory.path()).unwrap();
+        let crawl = Crawl::new(&root);
+        let (mut stac, _) = crawl.build().unwrap();
+        let mut watch = Watch::new(crawl, &mut stac).unwrap();
+        fs::write(root.join("readme.txt"), "not data").unwrap();
+        fs::write(root.join(".hidden.tif"), "not data").unwrap();
+        for name in ["readme.txt", ".hidden.tif"] {
+            let change = Change::Written(root.join(name));
+            assert_eq!(watch.apply(&mut stac, change).unwrap(), None);
+        }
+    }
+}
@@ -0,0 +1,1358 @@
+//! A client for STAC APIs.
+//!
+//! This module is enabled by the `reqwest` feature.
+
+mod query;
+mod search;
+
+pub use {
+    query::{Operator, Query},
+    search::{CollectionSearch, Direction, ItemSearch, SortBy},
+};
+
+use crate::{
+    auth::Request,
+    media_type,
+    validate::{Issue, ReportEntry, Severity, ValidationReport, Validator},
+    CancellationToken, Collection, Error, HttpClient, Item, Link, Object, Result,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    sync::{
+        mpsc::{self, Receiver},
+        OnceLock,
+    },
+    thread,
+};
+use url::Url;
+
+/// The conformance class of the [Transaction
+/// extension](https://github.com/stac-api-extensions/transaction).
+pub const TRANSACTION_CONFORMANCE: &str =
+    "https://api.stacspec.org/v1.0.0/ogcapi-features/extensions/transaction";
+
+/// The conformance class of the [Sort
+/// extension](https://github.com/stac-api-extensions/sort).
+pub const SORT_CONFORMANCE: &str = "https://api.stacspec.org/v1.0.0/item-search#sort";
+
+/// The conformance class of the [Query
+/// extension](https://github.com/stac-api-extensions/query).
+pub const QUERY_CONFORMANCE: &str = "https://api.stacspec.org/v1.0.0/item-search#query";
+
+/// The conformance class of the [Filter
+/// extension](https://github.com/stac-api-extensions/filter).
+pub const FILTER_CONFORMANCE: &str = "https://api.stacspec.org/v1.0.0-rc.2/item-search#filter";
+
+/// The conformance class of the [Free-text search
+/// extension](https://github.com/stac-api-extensions/freetext-search) for item search.
+pub const FREE_TEXT_CONFORMANCE: &str =
+    "https://api.stacspec.org/v1.0.0-rc.1/item-search#free-text";
+
+/// The conformance class of the [Free-text search
+/// extension](https://github.com/stac-api-extensions/freetext-search) for
+/// collection search.
+pub const COLLECTION_FREE_TEXT_CONFORMANCE: &str =
+    "https://api.stacspec.org/v1.0.0-rc.1/collection-search#free-text";
+
+/// The conformance class of the [Collection Search
+/// extension](https://github.com/stac-api-extensions/collection-search).
+pub const COLLECTION_SEARCH_CONFORMANCE: &str =
+    "https://api.stacspec.org/v1.0.0-rc.1/collection-search";
+
+/// The conformance class for an API's `/queryables` endpoint, from [OGC API -
+/// Features - Part 3](https://docs.ogc.org/DRAFTS/19-079r1.html).
+pub const QUERYABLES_CONFORMANCE: &str =
+    "http://www.opengis.net/spec/ogcapi-features-3/1.0/conf/queryables";
+
+/// Fields that every API can sort by, whether or not they're queryables.
+const CORE_SORT_FIELDS: [&str; 3] = ["id", "collection", "datetime"];
+
+/// The media type of a [JSON Merge Patch](https://datatracker.ietf.org/doc/html/rfc7386).
+const MERGE_PATCH: &str = "application/merge-patch+json";
+
+/// A blocking client for a [STAC API](https://github.com/radiantearth/stac-api-spec).
+///
+/// This is enabled by the `reqwest` feature. Requests are sent with an
+/// [HttpClient], so they're authenticated and retried as configured there.
+/// The API's conformance classes are fetched the first time they're needed,
+/// and then reused.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{ApiClient, HttpClient};
+/// let client = ApiClient::new("http://stac.test/api")
+///     .unwrap()
+///     .with_http(HttpClient::new().user_agent("my-app/1.0"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    url: Url,
+    http: HttpClient,
+    conformance: OnceLock<Vec<String>>,
+    queryables: OnceLock<Vec<String>>,
+    validation: Option<Validation>,
+    cancellation: Option<CancellationToken>,
+    max_items_to_sort: Option<usize>,
+}
+
+/// What an [ApiClient] does with returned objects that aren't valid.
+///
+/// Either way, their issues are recorded in the results' report, e.g.
+/// [SearchResults::report].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnInvalid {
+    /// Return invalid objects anyway.
+    ///
+    /// Objects that can't be deserialized at all are returned as errors,
+    /// without stopping the results.
+    Warn,
+
+    /// Skip invalid objects, including ones that can't be deserialized.
+    Drop,
+}
+
+/// How an [ApiClient] validates returned objects.
+#[derive(Debug, Clone)]
+struct Validation {
+    validator: Validator,
+    on_invalid: OnInvalid,
+}
+
+impl ApiClient {
+    /// Creates a new client for the API at this root url.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("http://stac.test/api").unwrap();
+    /// assert!(ApiClient::new("not a url").is_err());
+    /// assert!(ApiClient::new("mailto:someone@stac.test").is_err());
+    /// ```
+    pub fn new(url: &str) -> Result<ApiClient> {
+        let mut url = Url::parse(url)?;
+        if url.cannot_be_a_base() {
+            return Err(url::ParseError::RelativeUrlWithCannotBeABaseBase.into());
+        }
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(ApiClient {
+            url,
+            http: HttpClient::default(),
+            conformance: OnceLock::new(),
+            queryables: OnceLock::new(),
+            validation: None,
+            cancellation: None,
+            max_items_to_sort: None,
+        })
+    }
+
+    /// Sets the [HttpClient] used to send requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{auth::Bearer, ApiClient, HttpClient};
+    /// let client = ApiClient::new("http://stac.test/api")
+    ///     .unwrap()
+    ///     .with_http(HttpClient::new().auth(Bearer::new("a-token")));
+    /// ```
+    pub fn with_http(mut self, http: HttpClient) -> ApiClient {
+        self.http = http;
+        self
+    }
+
+    /// Validates every item returned by a search.
+    ///
+    /// Some servers return malformed items, which can quietly break
+    /// downstream processing. Each item is checked with the [Validator], and
+    /// items with [errors](Severity::Error) are returned or skipped depending
+    /// on `on_invalid`. With the `schemas` feature, set the validator's
+    /// [schemas](crate::validate::Validator::schemas) to also check items
+    /// against their JSON schemas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{api_client::OnInvalid, validate::Validator, ApiClient};
+    /// let client = ApiClient::new("http://stac.test/api")
+    ///     .unwrap()
+    ///     .with_validation(Validator::default(), OnInvalid::Drop);
+    /// ```
+    pub fn with_validation(mut self, validator: Validator, on_invalid: OnInvalid) -> ApiClient {
+        self.validation = Some(Validation {
+            validator,
+            on_invalid,
+        });
+        self
+    }
+
+    /// Stops paging through results when the [CancellationToken] is cancelled.
+    ///
+    /// The token is checked before each page is fetched. Once it's
+    /// cancelled, the results return [Error::Cancelled] and then stop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{ApiClient, CancellationToken};
+    /// let token = CancellationToken::new();
+    /// let client = ApiClient::new("http://stac.test/api")
+    ///     .unwrap()
+    ///     .with_cancellation(token.clone());
+    /// token.cancel();
+    /// let mut results = client.search(&serde_json::json!({}));
+    /// assert!(results.next().unwrap().is_err());
+    /// assert!(results.next().is_none());
+    /// ```
+    pub fn with_cancellation(mut self, token: CancellationToken) -> ApiClient {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sorts [item searches](ApiClient::item_search) locally, up to
+    /// `max_items`, if the API doesn't conform to the [sort
+    /// extension](SORT_CONFORMANCE).
+    ///
+    /// Every page has to be fetched before the first item can be returned,
+    /// so this is off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("http://stac.test/api")
+    ///     .unwrap()
+    ///     .with_local_sort(10_000);
+    /// ```
+    pub fn with_local_sort(mut self, max_items: usize) -> ApiClient {
+        self.max_items_to_sort = Some(max_items);
+        self
+    }
+
+    /// Returns the conformance classes of the API, from its `/conformance` endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("https://planetarycomputer.microsoft.com/api/stac/v1").unwrap();
+    /// let conformance = client.conformance().unwrap();
+    /// ```
+    pub fn conformance(&self) -> Result<&[String]> {
+        if let Some(conformance) = self.conformance.get() {
+            return Ok(conformance);
+        }
+        let value = self
+            .http
+            .send_json_blocking(&Request::get(self.url.join("conformance")?))?
+            .unwrap_or_default();
+        let conformance = value
+            .get("conformsTo")
+            .and_then(Value::as_array)
+            .map(|classes| {
+                classes
+                    .iter()
+                    .filter_map(|class| class.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(self.conformance.get_or_init(|| conformance))
+    }
+
+    /// Returns true if the API conforms to this class.
+    ///
+    /// Classes under `https://api.stacspec.org/` match any version, so
+    /// `v1.0.0-rc.1` conforms to a `v1.0.0` class.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{api_client::TRANSACTION_CONFORMANCE, ApiClient};
+    /// let client = ApiClient::new("http://stac.test/api").unwrap();
+    /// let transactions = client.conforms_to(TRANSACTION_CONFORMANCE).unwrap();
+    /// ```
+    pub fn conforms_to(&self, class: &str) -> Result<bool> {
+        let class = unversioned(class);
+        Ok(self
+            .conformance()?
+            .iter()
+            .any(|candidate| unversioned(candidate) == class))
+    }
+
+    /// Returns the names of the API's queryables, from its `/queryables` endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("https://planetarycomputer.microsoft.com/api/stac/v1").unwrap();
+    /// let queryables = client.queryables().unwrap();
+    /// ```
+    pub fn queryables(&self) -> Result<&[String]> {
+        if let Some(queryables) = self.queryables.get() {
+            return Ok(queryables);
+        }
+        let value = self
+            .http
+            .send_json_blocking(&Request::get(self.url.join("queryables")?))?
+            .unwrap_or_default();
+        let queryables = value
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|properties| properties.keys().cloned().collect())
+            .unwrap_or_default();
+        Ok(self.queryables.get_or_init(|| queryables))
+    }
+
+    /// Updates some of an item's fields with a [JSON Merge
+    /// Patch](https://datatracker.ietf.org/doc/html/rfc7386), and returns
+    /// the updated item.
+    ///
+    /// This uses the `PATCH` endpoint of the [Transaction
+    /// extension](https://github.com/stac-api-extensions/transaction), so
+    /// small changes don't need the whole item to be fetched and sent back.
+    /// Returns [Error::MissingConformance] if the API doesn't conform to the
+    /// extension. If the server doesn't return the updated item, it is
+    /// fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde_json::json;
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("http://stac.test/api").unwrap();
+    /// let item = client
+    ///     .patch_item("a-collection", "an-item", &json!({"properties": {"eo:cloud_cover": 12}}))
+    ///     .unwrap();
+    /// ```
+    pub fn patch_item(&self, collection: &str, id: &str, merge_patch: &Value) -> Result<Item> {
+        if !self.conforms_to(TRANSACTION_CONFORMANCE)? {
+            return Err(Error::MissingConformance(
+                TRANSACTION_CONFORMANCE.to_string(),
+            ));
+        }
+        let url = self.item_url(collection, id);
+        let mut request = Request::patch(url.clone(), serde_json::to_vec(merge_patch)?);
+        request.set_header("Content-Type", MERGE_PATCH);
+        let value = match self.http.send_json_blocking(&request)? {
+            Some(value) => value,
+            None => self
+                .http
+                .send_json_blocking(&Request::get(url))?
+                .unwrap_or_default(),
+        };
+        serde_json::from_value(value).map_err(Error::from)
+    }
+
+    /// Searches the API's `/search` endpoint, returning an iterator over the matching items.
+    ///
+    /// The search parameters are sent as the body of a `POST` request, and
+    /// `next` links are followed until there are no more pages. `POST` next
+    /// links can carry their own body, which is merged into the parameters
+    /// if the link's `merge` field is true, and used instead of them
+    /// otherwise. If a page can't be fetched, the iterator returns the error
+    /// and then stops. Items are [validated](ApiClient::with_validation) if
+    /// the client is configured to.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde_json::json;
+    /// use stac::ApiClient;
+    /// let client = ApiClient::new("https://planetarycomputer.microsoft.com/api/stac/v1").unwrap();
+    /// let params = json!({"collections": ["sentinel-2-l2a"], "limit": 10});
+    /// for item in client.search(&params).take(20) {
+    ///     println!("{}", item.unwrap().id);
+    /// }
+    /// ```
+    pub fn search(&self, params: &Value) -> SearchResults {
+        let url = self.url.join("search").expect("search is a valid path");
+        self.results(url, "POST", params.clone(), "features")
+    }
+
+    /// Searches the API's collections, returning an iterator over the matching collections.
+    ///
+    /// The search is sent as query parameters of a `GET /collections`
+    /// request, and `next` links are followed as with
+    /// [search](ApiClient::search). If the search has any filters, the API
+    /// must conform to the [collection search
+    /// extension](COLLECTION_SEARCH_CONFORMANCE), or this returns
+    /// [Error::MissingConformance], since other APIs would ignore the
+    /// filters and return every collection. Likewise, a free-text `q`
+    /// requires the [free-text extension](COLLECTION_FREE_TEXT_CONFORMANCE).
+    /// Collections are
+    /// [validated](ApiClient::with_validation) if the client is configured to.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{api_client::CollectionSearch, ApiClient};
+    /// let client = ApiClient::new("https://planetarycomputer.microsoft.com/api/stac/v1").unwrap();
+    /// let search = CollectionSearch::new()
+    ///     .bbox([-105.0, 40.0, -104.0, 41.0])
+    ///     .datetime("2023-01-01T00:00:00Z/..");
+    /// for collection in client.collection_search(&search).unwrap() {
+    ///     println!("{}", collection.unwrap().id);
+    /// }
+    /// ```
+    pub fn collection_search(
+        &self,
+        search: &CollectionSearch,
+    ) -> Result<SearchResults<Collection>> {
+        let pairs = search.query_pairs();
+        if !pairs.iter().all(|(key, _)| *key == "limit")
+            && !self.conforms_to(COLLECTION_SEARCH_CONFORMANCE)?
+        {
+            return Err(Error::MissingConformance(
+                COLLECTION_SEARCH_CONFORMANCE.to_string(),
+            ));
+        }
+        if search.q.is_some() && !self.conforms_to(COLLECTION_FREE_TEXT_CONFORMANCE)? {
+            return Err(Error::MissingConformance(
+                COLLECTION_FREE_TEXT_CONFORMANCE.to_string(),
+            ));
+        }
+        let mut url = self.url.join("collections")?;
+        if !pairs.is_empty() {
+            let _ = url.query_pairs_mut().extend_pairs(pairs);
+        }
+        Ok(self.results(url, "GET", Value::Null, "collections"))
+    }
+
+    fn results<T>(
+        &self,
+        url: Url,
+        method: &'static str,
+        body: Value,
+        key: &'static str,
+    ) -> SearchResults<T> {
+        SearchResults {
+            http: self.http.clone(),
+            request: Some((url, method, body)),
+            key,
+            items: Vec::new().into_iter(),
+            validation: self.validation.clone(),
+            report: ValidationReport::new(),
+            cancellation: self.cancellation.clone(),
+        }
+    }
+
+    /// Searches for items with an [ItemSearch].
+    ///
+    /// If the search is sorted and the API has [queryables](ApiClient::queryables),
+    /// every sort field must be a queryable, or one of `id`, `collection`,
+    /// and `datetime`, or this returns [Error::UnknownQueryable]. If the API
+    /// doesn't conform to the [sort extension](SORT_CONFORMANCE), this
+    /// returns [Error::MissingConformance], unless [local
+    /// sorting](ApiClient::with_local_sort) is on. Then the search is sent
+    /// without `sortby`, and every page is fetched and
+    /// [sorted](ItemSearch::sort) locally before the first item is returned.
+    /// If there are more items than the limit, this returns
+    /// [Error::MissingConformance] too, since they can't all be sorted.
+    ///
+    /// A [Query] is sent as a CQL2 filter if the API conforms to the
+    /// [filter extension](FILTER_CONFORMANCE) but not the [query
+    /// extension](QUERY_CONFORMANCE), and a CQL2 JSON filter is sent as a
+    /// query in the opposite case, if it can be expressed as one.
+    /// A free-text `q` requires the [free-text
+    /// extension](FREE_TEXT_CONFORMANCE), or this returns
+    /// [Error::MissingConformance], since other APIs would ignore it.
+    /// Otherwise, this is the same as [search](ApiClient::search).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{api_client::ItemSearch, ApiClient};
+    /// let client = ApiClient::new("https://planetarycomputer.microsoft.com/api/stac/v1").unwrap();
+    /// let search = ItemSearch::new()
+    ///     .collections(["sentinel-2-l2a"])
+    ///     .limit(10)
+    ///     .sortby("-properties.datetime");
+    /// for item in client.item_search(&search).unwrap().take(10) {
+    ///     println!("{}", item.unwrap().id);
+    /// }
+    /// ```
+    pub fn item_search(&self, search: &ItemSearch) -> Result<SearchResults> {
+        if search.q.is_some() && !self.conforms_to(FREE_TEXT_CONFORMANCE)? {
+            return Err(Error::MissingConformance(FREE_TEXT_CONFORMANCE.to_string()));
+        }
+        let search = &self.translate_query(search)?;
+        if search.sortby.is_empty() {
+            return Ok(self.search(&serde_json::to_value(search)?));
+        }
+        if self.conforms_to(QUERYABLES_CONFORMANCE)? {
+            let queryables = self.queryables()?;
+            for sortby in &search.sortby {
+                let field = sortby.queryable();
+                if !CORE_SORT_FIELDS.contains(&field)
+                    && !queryables.iter().any(|queryable| queryable == field)
+                {
+                    return Err(Error::UnknownQueryable(sortby.field.clone()));
+                }
+            }
+        }
+        if self.conforms_to(SORT_CONFORMANCE)? {
+            return Ok(self.search(&serde_json::to_value(search)?));
+        }
+        let missing_sort = || Error::MissingConformance(SORT_CONFORMANCE.to_string());
+        let max_items = self.max_items_to_sort.ok_or_else(missing_sort)?;
+        let mut unsorted = search.clone();
+        unsorted.sortby.clear();
+        let mut results = self.search(&serde_json::to_value(&unsorted)?);
+        let mut items = results
+            .by_ref()
+            .take(max_items.saturating_add(1))
+            .collect::<Result<Vec<_>>>()?;
+        if items.len() > max_items {
+            return Err(missing_sort());
+        }
+        search.sort(&mut items);
+        results.items = items.into_iter().map(Ok).collect::<Vec<_>>().into_iter();
+        Ok(results)
+    }
+
+    /// Rewrites a search's query as a filter, or its filter as a query, to
+    /// match what the API supports.
+    fn translate_query(&self, search: &ItemSearch) -> Result<ItemSearch> {
+        let mut search = search.clone();
+        let has_query = search.query.as_ref().is_some_and(|query| !query.is_empty());
+        let has_cql2_filter = search.filter.is_some()
+            && search
+                .filter_lang
+                .as_deref()
+                .is_none_or(|lang| lang == "cql2-json");
+        if !has_query && !has_cql2_filter {
+            return Ok(search);
+        }
+        let query = self.conforms_to(QUERY_CONFORMANCE)?;
+        let filter = self.conforms_to(FILTER_CONFORMANCE)?;
+        if has_query && filter && !query {
+            let cql2 = search
+                .query
+                .take()
+                .and_then(|query| query.to_cql2())
+                .expect("the query isn't empty");
+            search.filter = Some(match search.filter.take() {
+                Some(filter) => serde_json::json!({"op": "and", "args": [filter, cql2]}),
+                None => cql2,
+            });
+            search.filter_lang = Some("cql2-json".to_string());
+        } else if has_cql2_filter && query && !filter {
+            let translated = Query::from_cql2(search.filter.as_ref().expect("checked above"))?;
+            search
+                .query
+                .get_or_insert_with(Query::new)
+                .extend(translated);
+            search.filter = None;
+            search.filter_lang = None;
+        }
+        Ok(search)
+    }
+
+    /// Returns the url of an item, with the collection id and item id
+    /// percent-encoded as path segments.
+    fn item_url(&self, collection: &str, id: &str) -> Url {
+        let mut url = self.url.clone();
+        let _ = url
+            .path_segments_mut()
+            .expect("checked when the client was created")
+            .pop_if_empty()
+            .extend(["collections", collection, "items", id]);
+        url
+    }
+}
+
+impl Validation {
+    /// Deserializes and validates a returned object, recording any issues in
+    /// the report, and returns `None` if it should be skipped.
+    fn check<T>(&self, value: Value, report: &mut ValidationReport) -> Option<Result<T>>
+    where
+        T: DeserializeOwned + Clone + Into<Object>,
+    {
+        let id = value
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let t: T = match serde_json::from_value(value) {
+            Ok(t) => t,
+            Err(err) => {
+                report.entries.push(ReportEntry {
+                    id,
+                    href: None,
+                    issue: Issue::error("deserialize", "", &err),
+                });
+                return match self.on_invalid {
+                    OnInvalid::Warn => Some(Err(err.into())),
+                    OnInvalid::Drop => None,
+                };
+            }
+        };
+        let object = t.clone().into();
+        let issues = self.validator.validate(&object);
+        let invalid = issues.iter().any(|issue| issue.severity == Severity::Error);
+        let href = object.self_link().map(|link| link.href.as_str().into());
+        report.add(&object, href.as_ref(), issues);
+        if invalid && self.on_invalid == OnInvalid::Drop {
+            None
+        } else {
+            Some(Ok(t))
+        }
+    }
+}
+
+/// An iterator over the items of an [ApiClient::search], or the collections
+/// of an [ApiClient::collection_search].
+#[derive(Debug)]
+pub struct SearchResults<T = Item> {
+    http: HttpClient,
+    request: Option<(Url, &'static str, Value)>,
+    key: &'static str,
+    items: std::vec::IntoIter<Result<T>>,
+    validation: Option<Validation>,
+    report: ValidationReport,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<T> SearchResults<T>
+where
+    T: DeserializeOwned + Clone + Into<Object>,
+{
+    /// Returns the issues found in the objects returned so far, if the client
+    /// [validates](ApiClient::with_validation) items.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde_json::json;
+    /// use stac::{api_client::OnInvalid, validate::Validator, ApiClient};
+    /// let client = ApiClient::new("http://stac.test/api")
+    ///     .unwrap()
+    ///     .with_validation(Validator::default(), OnInvalid::Warn);
+    /// let mut results = client.search(&json!({}));
+    /// let items: Vec<_> = results.by_ref().collect();
+    /// println!("{}", results.report());
+    /// ```
+    pub fn report(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    /// Fetches the next page, and remembers the request for the page after it.
+    fn next_page(&mut self) -> Option<Result<Vec<Result<T>>>> {
+        let (url, method, body) = self.request.take()?;
+        if let Some(token) = &self.cancellation {
+            if let Err(err) = token.check() {
+                return Some(Err(err));
+            }
+        }
+        let result = (|| {
+            let mut request = if method == "POST" {
+                let mut request = Request::post(url.clone(), serde_json::to_vec(&body)?);
+                request.set_header("Content-Type", media_type::JSON);
+                // Searches don't change anything, so they're safe to retry.
+                request.set_idempotent();
+                request
+            } else {
+                Request::get(url.clone())
+            };
+            if self.key == "features" {
+                request.set_header("Accept", media_type::GEOJSON);
+            } else {
+                request.set_header("Accept", media_type::JSON);
+            }
+            let mut value = self.http.send_json_blocking(&request)?.unwrap_or_default();
+            let objects = match value.get_mut(self.key).map(Value::take) {
+                Some(Value::Array(objects)) => objects,
+                Some(objects) => {
+                    return Err(Error::InvalidMetadata(format!(
+                        "{} is not an array: {}",
+                        self.key, objects
+                    )))
+                }
+                None => Vec::new(),
+            };
+            let links: Vec<Link> = match value.get_mut("links").map(Value::take) {
+                Some(links) => serde_json::from_value(links)?,
+                None => Vec::new(),
+            };
+            let objects = match self.validation.as_ref() {
+                Some(validation) => objects
+                    .into_iter()
+                    .filter_map(|object| validation.check(object, &mut self.report))
+                    .collect(),
+                None => objects
+                    .into_iter()
+                    .map(|object| serde_json::from_value(object).map(Ok))
+                    .collect::<serde_json::Result<_>>()?,
+            };
+            if let Some(link) = links.iter().find(|link| link.is_next()) {
+                let url = url.join(&link.href)?;
+                let post = link
+                    .additional_fields
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .is_some_and(|method| method.eq_ignore_ascii_case("POST"));
+                self.request = if post {
+                    let body = match (link.additional_fields.get("body"), body) {
+                        (Some(Value::Object(next)), Value::Object(mut body))
+                            if link.additional_fields.get("merge") == Some(&Value::Bool(true)) =>
+                        {
+                            body.extend(next.clone());
+                            Value::Object(body)
+                        }
+                        (Some(next), _) => next.clone(),
+                        (None, body) => body,
+                    };
+                    Some((url, "POST", body))
+                } else {
+                    Some((url, "GET", Value::Null))
+                };
+            }
+            Ok(objects)
+        })();
+        Some(result)
+    }
+}
+
+impl<T> Iterator for SearchResults<T>
+where
+    T: DeserializeOwned + Clone + Into<Object>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(item);
+            }
+            match self.next_page()? {
+                Ok(items) => self.items = items.into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Runs one search against several APIs, e.g. mirrors of the same archive,
+/// and merges their results.
+///
+/// Each API is searched on its own thread, and items are returned in the
+/// order they arrive. An item is only returned the first time its
+/// collection and id are seen, so an item that's in more than one API is
+/// returned once. If an API fails, its error is returned and the other
+/// APIs are still searched.
+///
+/// # Examples
+///
+/// ```no_run
+/// use serde_json::json;
+/// use stac::{api_client::FederatedSearch, ApiClient};
+/// let search = FederatedSearch::new([
+///     ApiClient::new("https://primary.stac.test").unwrap(),
+///     ApiClient::new("https://mirror.stac.test").unwrap(),
+/// ]);
+/// let mut results = search.search(&json!({"ids": ["an-item"]}));
+/// for item in &mut results {
+///     println!("{}", item.unwrap().id);
+/// }
+/// println!("skipped {} duplicates", results.duplicates());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FederatedSearch {
+    clients: Vec<ApiClient>,
+}
+
+impl FederatedSearch {
+    /// Creates a new federated search over these APIs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{api_client::FederatedSearch, ApiClient};
+    /// let search = FederatedSearch::new([ApiClient::new("http://stac.test").unwrap()]);
+    /// ```
+    pub fn new(clients: impl IntoIterator<Item = ApiClient>) -> FederatedSearch {
+        FederatedSearch {
+            clients: clients.into_iter().collect(),
+        }
+    }
+
+    /// Searches every API with these parameters, as [ApiClient::search] does.
+    ///
+    /// The searches start right away. Dropping the returned iterator stops
+    /// them after their current page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde_json::json;
+    /// use stac::{api_client::FederatedSearch, ApiClient};
+    /// let search = FederatedSearch::new([ApiClient::new("http://stac.test").unwrap()]);
+    /// let items: Vec<_> = search.search(&json!({"limit": 10})).collect();
+    /// ```
+    pub fn search(&self, params: &Value) -> FederatedResults {
+        let (sender, receiver) = mpsc::sync_channel(64);
+        for client in &self.clients {
+            let client = client.clone();
+            let params = params.clone();
+            let sender = sender.clone();
+            let _ = thread::spawn(move || {
+                for result in client.search(&params) {
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        FederatedResults {
+            receiver,
+            seen: HashSet::new(),
+            duplicates: 0,
+        }
+    }
+}
+
+/// An iterator over the deduplicated items of a [FederatedSearch].
+#[derive(Debug)]
+pub struct FederatedResults {
+    receiver: Receiver<Result<Item>>,
+    seen: HashSet<(Option<String>, String)>,
+    duplicates: usize,
+}
+
+impl FederatedResults {
+    /// Returns the number of duplicate items that have been skipped so far.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+}
+
+impl Iterator for FederatedResults {
+    type Item = Result<Item>;
+
+    fn next(&mut self) -> Option<Result<Item>> {
+        loop {
+            let item = match self.receiver.recv().ok()? {
+                Ok(item) => item,
+                Err(err) => return Some(Err(err)),
+            };
+            let key = (
+                item.collection.as_deref().map(String::from),
+                item.id.clone(),
+            );
+            if self.seen.insert(key) {
+                return Some(Ok(item));
+            }
+            self.duplicates += 1;
+        }
+    }
+}
+
+/// Removes the version from a STAC API conformance class.
+fn unversioned(class: &str) -> &str {
+    class
+        .strip_prefix("https://api.stacspec.org/")
+        .and_then(|rest| rest.split_once('/'))
+        .map_or(class, |(_, rest)| rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ApiClient, CollectionSearch, FederatedSearch, ItemSearch, OnInvalid, Query,
+        COLLECTION_SEARCH_CONFORMANCE, FILTER_CONFORMANCE, FREE_TEXT_CONFORMANCE,
+        QUERY_CONFORMANCE, SORT_CONFORMANCE,
+    };
+    use crate::{
+        http::test_server::{response, serve},
+        CancellationToken, Collection, Error, Item, ItemCollection, Link,
+    };
+    use serde_json::json;
+
+    fn conformance(classes: &[&str]) -> String {
+        response(200, &[], &json!({"conformsTo": classes}).to_string())
+    }
+
+    #[test]
+    fn patch_item() {
+        let mut item = Item::new("an item");
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("eo:cloud_cover".to_string(), 12.into());
+        let (url, handle) = serve(vec![
+            conformance(&[
+                "https://api.stacspec.org/v1.0.0-rc.2/ogcapi-features/extensions/transaction",
+            ]),
+            response(200, &[], &serde_json::to_string(&item).unwrap()),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let patched = client
+            .patch_item(
+                "a-collection",
+                "an item",
+                &json!({"properties": {"eo:cloud_cover": 12}}),
+            )
+            .unwrap();
+        assert_eq!(patched, item);
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[0].line, "GET /api/conformance HTTP/1.1");
+        assert_eq!(
+            requests[1].line,
+            "PATCH /api/collections/a-collection/items/an%20item HTTP/1.1"
+        );
+        assert_eq!(
+            requests[1].header("content-type"),
+            Some("application/merge-patch+json")
+        );
+        assert_eq!(requests[1].body, r#"{"properties":{"eo:cloud_cover":12}}"#);
+    }
+
+    #[test]
+    fn patch_item_without_body() {
+        let item = Item::new("an-item");
+        let (url, handle) = serve(vec![
+            conformance(&[super::TRANSACTION_CONFORMANCE]),
+            response(204, &[], ""),
+            response(200, &[], &serde_json::to_string(&item).unwrap()),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let patched = client
+            .patch_item("a-collection", "an-item", &json!({}))
+            .unwrap();
+        assert_eq!(patched, item);
+        let requests = handle.join().unwrap();
+        assert_eq!(
+            requests[2].line,
+            "GET /api/collections/a-collection/items/an-item HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn patch_item_without_transactions() {
+        let (url, handle) = serve(vec![conformance(&["https://api.stacspec.org/v1.0.0/core"])]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        assert!(matches!(
+            client
+                .patch_item("a-collection", "an-item", &json!({}))
+                .unwrap_err(),
+            Error::MissingConformance(_)
+        ));
+        let _ = handle.join().unwrap();
+    }
+
+    fn page(ids: &[&str], next: Option<Link>) -> String {
+        let mut page = ItemCollection::new(
+            ids.iter()
+                .map(|id| {
+                    let mut item = Item::new(*id);
+                    item.collection = Some("a-collection".into());
+                    item
+                })
+                .collect(),
+        );
+        page.links.extend(next);
+        response(200, &[], &serde_json::to_string(&page).unwrap())
+    }
+
+    #[test]
+    fn search_follows_next() {
+        let (url, handle) = serve(vec![page(&["a"], None)]);
+        let mut next = Link::new(format!("{}/api/search", url), "next");
+        let _ = next
+            .additional_fields
+            .insert("method".to_string(), "POST".into());
+        let _ = next
+            .additional_fields
+            .insert("body".to_string(), json!({"token": "page-2"}));
+        let _ = next
+            .additional_fields
+            .insert("merge".to_string(), true.into());
+        let (first_url, first_handle) = serve(vec![page(&["b", "c"], Some(next))]);
+        let client = ApiClient::new(&format!("{}/api", first_url)).unwrap();
+        let ids: Vec<_> = client
+            .search(&json!({"limit": 2}))
+            .map(|item| item.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["b", "c", "a"]);
+        let first = first_handle.join().unwrap();
+        assert_eq!(first[0].line, "POST /api/search HTTP/1.1");
+        assert_eq!(first[0].body, r#"{"limit":2}"#);
+        let second = handle.join().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&second[0].body).unwrap(),
+            json!({"limit": 2, "token": "page-2"})
+        );
+    }
+
+    #[test]
+    fn search_cancelled_between_pages() {
+        let next = Link::new("http://stac.test/api/search?page=2", "next");
+        let (url, handle) = serve(vec![page(&["a", "b"], Some(next))]);
+        let token = CancellationToken::new();
+        let client = ApiClient::new(&format!("{}/api", url))
+            .unwrap()
+            .with_cancellation(token.clone());
+        let mut results = client.search(&json!({}));
+        assert_eq!(results.next().unwrap().unwrap().id, "a");
+        token.cancel();
+        assert_eq!(results.next().unwrap().unwrap().id, "b");
+        assert!(matches!(
+            results.next().unwrap().unwrap_err(),
+            Error::Cancelled
+        ));
+        assert!(results.next().is_none());
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn federated_search_deduplicates() {
+        let (a, a_handle) = serve(vec![page(&["one", "two"], None)]);
+        let (b, b_handle) = serve(vec![page(&["two", "three"], None)]);
+        let (c, c_handle) = serve(vec![response(500, &[], "")]);
+        let search = FederatedSearch::new(
+            [a, b, c].map(|url| ApiClient::new(&format!("{}/api", url)).unwrap()),
+        );
+        let mut results = search.search(&json!({}));
+        let mut ids = Vec::new();
+        let mut errors = 0;
+        for result in &mut results {
+            match result {
+                Ok(item) => ids.push(item.id),
+                Err(_) => errors += 1,
+            }
+        }
+        ids.sort();
+        assert_eq!(ids, ["one", "three", "two"]);
+        assert_eq!(results.duplicates(), 1);
+        assert_eq!(errors, 1);
+        for handle in [a_handle, b_handle, c_handle] {
+            let _ = handle.join().unwrap();
+        }
+    }
+
+    fn queryables_conformance(sort: bool) -> String {
+        let mut classes = vec![super::QUERYABLES_CONFORMANCE];
+        if sort {
+            classes.push(SORT_CONFORMANCE);
+        }
+        conformance(&classes)
+    }
+
+    fn queryables() -> String {
+        response(
+            200,
+            &[],
+            &json!({"properties": {"eo:cloud_cover": {"type": "number"}}}).to_string(),
+        )
+    }
+
+    #[test]
+    fn item_search_sorted_by_server() {
+        let (url, handle) = serve(vec![
+            queryables_conformance(true),
+            queryables(),
+            page(&["b", "a"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let search = ItemSearch::new().sortby("-properties.eo:cloud_cover");
+        let ids: Vec<_> = client
+            .item_search(&search)
+            .unwrap()
+            .map(|item| item.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["b", "a"]);
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[1].line, "GET /api/queryables HTTP/1.1");
+        assert!(requests[2].body.contains("sortby"));
+    }
+
+    #[test]
+    fn item_search_sorted_locally() {
+        let (url, handle) = serve(vec![
+            queryables_conformance(false),
+            queryables(),
+            page(&["a", "c", "b"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url))
+            .unwrap()
+            .with_local_sort(3);
+        let ids: Vec<_> = client
+            .item_search(&ItemSearch::new().sortby("-id"))
+            .unwrap()
+            .map(|item| item.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[2].body, "{}");
+    }
+
+    #[test]
+    fn item_search_not_sorted_locally() {
+        let (url, handle) = serve(vec![queryables_conformance(false), queryables()]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        assert!(matches!(
+            client
+                .item_search(&ItemSearch::new().sortby("-id"))
+                .unwrap_err(),
+            Error::MissingConformance(class) if class == SORT_CONFORMANCE
+        ));
+        let _ = handle.join().unwrap();
+
+        let (url, handle) = serve(vec![
+            queryables_conformance(false),
+            queryables(),
+            page(&["a", "c", "b"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url))
+            .unwrap()
+            .with_local_sort(2);
+        assert!(matches!(
+            client
+                .item_search(&ItemSearch::new().sortby("-id"))
+                .unwrap_err(),
+            Error::MissingConformance(class) if class == SORT_CONFORMANCE
+        ));
+        let _ = handle.join().unwrap();
+    }
+
+    #[test]
+    fn item_search_unknown_queryable() {
+        let (url, handle) = serve(vec![queryables_conformance(true), queryables()]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        assert!(matches!(
+            client
+                .item_search(&ItemSearch::new().sortby("properties.gsd"))
+                .unwrap_err(),
+            Error::UnknownQueryable(field) if field == "properties.gsd"
+        ));
+        let _ = handle.join().unwrap();
+    }
+
+    #[test]
+    fn item_search_query_as_filter() {
+        let (url, handle) = serve(vec![conformance(&[FILTER_CONFORMANCE]), page(&["a"], None)]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let search = ItemSearch::new().query(Query::new().lt("eo:cloud_cover", 10));
+        let _ = client.item_search(&search).unwrap().count();
+        let requests = handle.join().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&requests[1].body).unwrap(),
+            json!({
+                "filter": {"op": "<", "args": [{"property": "eo:cloud_cover"}, 10]},
+                "filter-lang": "cql2-json"
+            })
+        );
+    }
+
+    #[test]
+    fn item_search_filter_as_query() {
+        let (url, handle) = serve(vec![conformance(&[QUERY_CONFORMANCE]), page(&["a"], None)]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let search = ItemSearch::new().filter(json!({
+            "op": "=", "args": [{"property": "platform"}, "landsat-8"]
+        }));
+        let _ = client.item_search(&search).unwrap().count();
+        let requests = handle.join().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&requests[1].body).unwrap(),
+            json!({"query": {"platform": {"eq": "landsat-8"}}})
+        );
+    }
+
+    fn invalid_page() -> String {
+        let mut invalid = Item::new("invalid");
+        invalid.geometry = Some(geojson::Geometry::new(geojson::Value::Point(vec![
+            -105.0, 100.0,
+        ])));
+        let page = json!({
+            "type": "FeatureCollection",
+            "features": [
+                Item::new("valid"),
+                invalid,
+                {"type": "Feature", "id": "malformed", "geometry": 42}
+            ]
+        });
+        response(200, &[], &page.to_string())
+    }
+
+    #[test]
+    fn validation() {
+        for (on_invalid, ids, errors) in [
+            (OnInvalid::Warn, vec!["valid", "invalid"], 1),
+            (OnInvalid::Drop, vec!["valid"], 0),
+        ] {
+            let (url, handle) = serve(vec![invalid_page()]);
+            let client = ApiClient::new(&format!("{}/api", url))
+                .unwrap()
+                .with_validation(Default::default(), on_invalid);
+            let mut results = client.search(&json!({}));
+            let results_vec: Vec<_> = results.by_ref().collect();
+            assert_eq!(
+                results_vec.iter().filter(|result| result.is_err()).count(),
+                errors
+            );
+            let returned: Vec<_> = results_vec
+                .into_iter()
+                .filter_map(|result| result.ok())
+                .map(|item| item.id)
+                .collect();
+            assert_eq!(returned, ids);
+            let report = results.report();
+            assert!(report.entries.iter().any(|entry| entry.id == "invalid"));
+            assert!(report
+                .entries
+                .iter()
+                .any(|entry| entry.id == "malformed" && entry.issue.rule == "deserialize"));
+            let _ = handle.join().unwrap();
+        }
+    }
+
+    #[cfg(feature = "schemas")]
+    #[test]
+    fn schema_validation() {
+        use crate::validate::{Schemas, Severity, Validator};
+
+        let mut invalid = serde_json::to_value(Item::new("invalid")).unwrap();
+        invalid["stac_extensions"] =
+            json!(["https://stac-extensions.github.io/eo/v1.1.0/schema.json"]);
+        invalid["properties"]["eo:cloud_cover"] = json!(101);
+        let page = json!({
+            "type": "FeatureCollection",
+            "features": [Item::new("valid"), invalid]
+        });
+        let (url, handle) = serve(vec![response(200, &[], &page.to_string())]);
+        let validator = Validator {
+            schemas: Some(Schemas::new()),
+            ..Default::default()
+        };
+        let client = ApiClient::new(&format!("{}/api", url))
+            .unwrap()
+            .with_validation(validator, OnInvalid::Drop);
+        let mut results = client.search(&json!({}));
+        let ids: Vec<_> = results.by_ref().map(|item| item.unwrap().id).collect();
+        assert_eq!(ids, ["valid"]);
+        let report = results.report();
+        assert!(report.entries.iter().all(|entry| entry.id == "invalid"));
+        assert!(report
+            .entries
+            .iter()
+            .any(|entry| entry.issue.rule == "schema" && entry.issue.severity == Severity::Error));
+        let _ = handle.join().unwrap();
+    }
+
+    #[test]
+    fn malformed_item_without_validation() {
+        let (url, handle) = serve(vec![invalid_page()]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let results: Vec<_> = client.search(&json!({})).collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        let _ = handle.join().unwrap();
+    }
+
+    fn collections_page(ids: &[&str], next: Option<String>) -> String {
+        let collections: Vec<_> = ids.iter().map(|id| Collection::new(*id)).collect();
+        let links: Vec<_> = next
+            .into_iter()
+            .map(|href| Link::new(href, "next"))
+            .collect();
+        response(
+            200,
+            &[],
+            &json!({"collections": collections, "links": links}).to_string(),
+        )
+    }
+
+    #[test]
+    fn collection_search_follows_next() {
+        let (url, handle) = serve(vec![
+            conformance(&[
+                COLLECTION_SEARCH_CONFORMANCE,
+                super::COLLECTION_FREE_TEXT_CONFORMANCE,
+            ]),
+            collections_page(&["a", "b"], Some("collections?token=page-2".to_string())),
+            collections_page(&["c"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api/", url)).unwrap();
+        let search = CollectionSearch::new()
+            .bbox([-105.0, 40.0, -104.0, 41.0])
+            .q("landsat");
+        let ids: Vec<_> = client
+            .collection_search(&search)
+            .unwrap()
+            .map(|collection| collection.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        let requests = handle.join().unwrap();
+        assert_eq!(
+            requests[1].line,
+            "GET /api/collections?bbox=-105%2C40%2C-104%2C41&q=landsat HTTP/1.1"
+        );
+        assert_eq!(
+            requests[2].line,
+            "GET /api/collections?token=page-2 HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn collection_search_without_conformance() {
+        let (url, handle) = serve(vec![
+            conformance(&["https://api.stacspec.org/v1.0.0/core"]),
+            collections_page(&["a"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        assert!(matches!(
+            client
+                .collection_search(&CollectionSearch::new().q("landsat"))
+                .unwrap_err(),
+            Error::MissingConformance(_)
+        ));
+        let ids: Vec<_> = client
+            .collection_search(&CollectionSearch::new().limit(1))
+            .unwrap()
+            .map(|collection| collection.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a"]);
+        let _ = handle.join().unwrap();
+    }
+
+    #[test]
+    fn item_search_free_text() {
+        let (url, handle) = serve(vec![
+            conformance(&[FREE_TEXT_CONFORMANCE]),
+            page(&["a"], None),
+        ]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        let ids: Vec<_> = client
+            .item_search(&ItemSearch::new().q("wildfire"))
+            .unwrap()
+            .map(|item| item.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a"]);
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[1].body, r#"{"q":"wildfire"}"#);
+    }
+
+    #[test]
+    fn free_text_without_conformance() {
+        let (url, handle) = serve(vec![conformance(&[COLLECTION_SEARCH_CONFORMANCE])]);
+        let client = ApiClient::new(&format!("{}/api", url)).unwrap();
+        assert!(matches!(
+            client.item_search(&ItemSearch::new().q("wildfire")).unwrap_err(),
+            Error::MissingConformance(class) if class == FREE_TEXT_CONFORMANCE
+        ));
+        assert!(matches!(
+            client
+                .collection_search(&CollectionSearch::new().q("wildfire"))
+                .unwrap_err(),
+            Error::MissingConformance(class) if class.ends_with("collection-search#free-text")
+        ));
+        let _ = handle.join().unwrap();
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// A search's `query`, from the [query
+/// extension](https://github.com/stac-api-extensions/query).
+///
+/// Many older APIs only support `query`, while newer ones use
+/// [CQL2](https://docs.ogc.org/DRAFTS/21-065.html) filters. A `Query` can be
+/// translated to and from CQL2 JSON, so one search can target either.
+///
+/// # Examples
+///
+/// ```
+/// use stac::api_client::Query;
+/// use serde_json::json;
+/// let query = Query::new()
+///     .lt("eo:cloud_cover", 10)
+///     .one_of("platform", ["sentinel-2a", "sentinel-2b"]);
+/// assert_eq!(
+///     serde_json::to_value(&query).unwrap(),
+///     json!({
+///         "eo:cloud_cover": {"lt": 10},
+///         "platform": {"in": ["sentinel-2a", "sentinel-2b"]}
+///     })
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Query(BTreeMap<String, BTreeMap<Operator, Value>>);
+
+/// A comparison operator of a [Query].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operator {
+    /// Equal to.
+    Eq,
+
+    /// Not equal to.
+    Neq,
+
+    /// Less than.
+    Lt,
+
+    /// Less than or equal to.
+    Lte,
+
+    /// Greater than.
+    Gt,
+
+    /// Greater than or equal to.
+    Gte,
+
+    /// Equal to one of a list of values.
+    In,
+}
+
+impl Query {
+    /// Creates a new, empty query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// assert!(Query::new().is_empty());
+    /// ```
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Returns true if this query doesn't have any conditions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// assert!(!Query::new().eq("platform", "landsat-8").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds a condition on a property, replacing any condition with the same operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::{Operator, Query};
+    /// let query = Query::new().op("gsd", Operator::Lte, 10);
+    /// ```
+    pub fn op(
+        mut self,
+        property: impl ToString,
+        operator: Operator,
+        value: impl Into<Value>,
+    ) -> Query {
+        let _ = self
+            .0
+            .entry(property.to_string())
+            .or_default()
+            .insert(operator, value.into());
+        self
+    }
+
+    /// Only matches items whose property is equal to this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().eq("platform", "landsat-8");
+    /// ```
+    pub fn eq(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Eq, value)
+    }
+
+    /// Only matches items whose property isn't equal to this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().neq("platform", "landsat-7");
+    /// ```
+    pub fn neq(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Neq, value)
+    }
+
+    /// Only matches items whose property is less than this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().lt("eo:cloud_cover", 10);
+    /// ```
+    pub fn lt(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Lt, value)
+    }
+
+    /// Only matches items whose property is less than or equal to this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().lte("eo:cloud_cover", 10);
+    /// ```
+    pub fn lte(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Lte, value)
+    }
+
+    /// Only matches items whose property is greater than this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().gt("view:sun_elevation", 30);
+    /// ```
+    pub fn gt(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Gt, value)
+    }
+
+    /// Only matches items whose property is greater than or equal to this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().gte("view:sun_elevation", 30);
+    /// ```
+    pub fn gte(self, property: impl ToString, value: impl Into<Value>) -> Query {
+        self.op(property, Operator::Gte, value)
+    }
+
+    /// Only matches items whose property is equal to one of these values,
+    /// with the `in` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().one_of("platform", ["sentinel-2a", "sentinel-2b"]);
+    /// ```
+    pub fn one_of<I, V>(self, property: impl ToString, values: I) -> Query
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+        self.op(property, Operator::In, values)
+    }
+
+    /// Returns the properties that this query has conditions on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// let query = Query::new().eq("platform", "landsat-8").lt("eo:cloud_cover", 10);
+    /// assert_eq!(query.properties().collect::<Vec<_>>(), ["eo:cloud_cover", "platform"]);
+    /// ```
+    pub fn properties(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Converts this query to a CQL2 JSON filter, or `None` if it's empty.
+    ///
+    /// Conditions are combined with `and`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// use serde_json::json;
+    /// let filter = Query::new().lt("eo:cloud_cover", 10).to_cql2().unwrap();
+    /// assert_eq!(
+    ///     filter,
+    ///     json!({"op": "<", "args": [{"property": "eo:cloud_cover"}, 10]})
+    /// );
+    /// ```
+    pub fn to_cql2(&self) -> Option<Value> {
+        let mut args: Vec<Value> = self
+            .0
+            .iter()
+            .flat_map(|(property, conditions)| {
+                conditions.iter().map(move |(operator, value)| {
+                    json!({"op": operator.cql2(), "args": [{"property": property}, value]})
+                })
+            })
+            .collect();
+        match args.len() {
+            0 => None,
+            1 => args.pop(),
+            _ => Some(json!({"op": "and", "args": args})),
+        }
+    }
+
+    /// Converts a CQL2 JSON filter to a query.
+    ///
+    /// Only filters that a query can express are supported: comparisons
+    /// between a property and a value, optionally combined with `and`.
+    /// Anything else returns [Error::UnsupportedFilter].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::Query;
+    /// use serde_json::json;
+    /// let query = Query::from_cql2(&json!({
+    ///     "op": "and",
+    ///     "args": [
+    ///         {"op": "=", "args": [{"property": "platform"}, "landsat-8"]},
+    ///         {"op": "<", "args": [{"property": "eo:cloud_cover"}, 10]}
+    ///     ]
+    /// }))
+    /// .unwrap();
+    /// assert_eq!(query, Query::new().eq("platform", "landsat-8").lt("eo:cloud_cover", 10));
+    /// assert!(Query::from_cql2(&json!({"op": "or", "args": []})).is_err());
+    /// ```
+    pub fn from_cql2(filter: &Value) -> Result<Query> {
+        let mut query = Query::new();
+        query.add_cql2(filter)?;
+        Ok(query)
+    }
+
+    /// Adds all of another query's conditions to this one.
+    pub(crate) fn extend(&mut self, other: Query) {
+        for (property, conditions) in other.0 {
+            self.0.entry(property).or_default().extend(conditions);
+        }
+    }
+
+    fn add_cql2(&mut self, filter: &Value) -> Result<()> {
+        let unsupported = || Error::UnsupportedFilter(filter.to_string());
+        let op = filter
+            .get("op")
+            .and_then(Value::as_str)
+            .ok_or_else(unsupported)?;
+        let args = filter
+            .get("args")
+            .and_then(Value::as_array)
+            .ok_or_else(unsupported)?;
+        if op == "and" {
+            for arg in args {
+                self.add_cql2(arg)?;
+            }
+            return Ok(());
+        }
+        let operator = Operator::from_cql2(op).ok_or_else(unsupported)?;
+        let [property, value] = args.as_slice() else {
+            return Err(unsupported());
+        };
+        let property = property
+            .get("property")
+            .and_then(Value::as_str)
+            .ok_or_else(unsupported)?;
+        if value.is_object() || (operator == Operator::In) != value.is_array() {
+            return Err(unsupported());
+        }
+        let _ = self
+            .0
+            .entry(property.to_string())
+            .or_default()
+            .insert(operator, value.clone());
+        Ok(())
+    }
+}
+
+impl Operator {
+    fn cql2(self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::Neq => "<>",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::In => "in",
+        }
+    }
+
+    fn from_cql2(op: &str) -> Option<Operator> {
+        [
+            Operator::Eq,
+            Operator::Neq,
+            Operator::Lt,
+            Operator::Lte,
+            Operator::Gt,
+            Operator::Gte,
+            Operator::In,
+        ]
+        .into_iter()
+        .find(|operator| operator.cql2() == op)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use serde_json::json;
+
+    #[test]
+    fn cql2_round_trip() {
+        let query = Query::new()
+            .gte("eo:cloud_cover", 0)
+            .lt("eo:cloud_cover", 10)
+            .neq("platform", "landsat-7")
+            .one_of("instruments", ["oli", "tirs"]);
+        let filter = query.to_cql2().unwrap();
+        assert_eq!(filter["op"], "and");
+        assert_eq!(filter["args"].as_array().unwrap().len(), 4);
+        assert_eq!(Query::from_cql2(&filter).unwrap(), query);
+    }
+
+    #[test]
+    fn deserialize() {
+        let query: Query = serde_json::from_value(json!({"gsd": {"lte": 10, "gt": 1}})).unwrap();
+        assert_eq!(query, Query::new().lte("gsd", 10).gt("gsd", 1));
+    }
+
+    #[test]
+    fn unsupported_cql2() {
+        for filter in [
+            json!({"op": "not", "args": [{"op": "=", "args": [{"property": "a"}, 1]}]}),
+            json!({"op": "=", "args": [1, {"property": "a"}]}),
+            json!({"op": "in", "args": [{"property": "a"}, 1]}),
+            json!({"op": "like", "args": [{"property": "a"}, "b%"]}),
+        ] {
+            assert!(Query::from_cql2(&filter).is_err(), "{}", filter);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Query::new().to_cql2().is_none());
+    }
+}
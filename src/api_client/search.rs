@@ -0,0 +1,589 @@
+use super::Query;
+use crate::Item;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{cmp::Ordering, convert::Infallible, fmt::Display, str::FromStr};
+
+/// A builder for the parameters of an item search.
+///
+/// Serializes to the body of a `POST /search` request. Fields that aren't
+/// covered by the builder, e.g. extension parameters, can be set in
+/// `additional_fields`.
+///
+/// # Examples
+///
+/// ```
+/// use stac::api_client::ItemSearch;
+/// let search = ItemSearch::new()
+///     .collections(["sentinel-2-l2a"])
+///     .bbox([-105.0, 40.0, -104.0, 41.0])
+///     .limit(10)
+///     .sortby("-properties.datetime");
+/// let value = serde_json::to_value(&search).unwrap();
+/// assert_eq!(value["sortby"][0]["direction"], "desc");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemSearch {
+    /// Only return items whose geometry intersects this bbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+
+    /// Only return items whose datetimes intersect this datetime or interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<String>,
+
+    /// Only return items with these ids.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<String>>,
+
+    /// Only return items in these collections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<String>>,
+
+    /// The maximum number of items in each page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// Only return items that match this free-text search, from the
+    /// [free-text extension](https://github.com/stac-api-extensions/freetext-search).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    /// The fields to sort by, from the [sort
+    /// extension](https://github.com/stac-api-extensions/sort).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sortby: Vec<SortBy>,
+
+    /// Conditions on item properties, from the [query
+    /// extension](https://github.com/stac-api-extensions/query).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<Query>,
+
+    /// A CQL2 filter, from the [filter
+    /// extension](https://github.com/stac-api-extensions/filter).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+
+    /// The language of the filter, e.g. `cql2-json`.
+    #[serde(rename = "filter-lang", skip_serializing_if = "Option::is_none")]
+    pub filter_lang: Option<String>,
+
+    /// Additional parameters, e.g. from search extensions.
+    #[serde(flatten)]
+    pub additional_fields: Map<String, Value>,
+}
+
+/// A builder for the parameters of a collection search, from the [collection
+/// search extension](https://github.com/stac-api-extensions/collection-search).
+///
+/// Sent as the query of a `GET /collections` request.
+///
+/// # Examples
+///
+/// ```
+/// use stac::api_client::CollectionSearch;
+/// let search = CollectionSearch::new()
+///     .bbox([-105.0, 40.0, -104.0, 41.0])
+///     .q("sentinel")
+///     .limit(10);
+/// assert_eq!(search.q.as_deref(), Some("sentinel"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionSearch {
+    /// Only return collections whose spatial extent intersects this bbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+
+    /// Only return collections whose temporal extent intersects this datetime or interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<String>,
+
+    /// Only return collections that match this free-text search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    /// The maximum number of collections in each page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// A field to sort search results by.
+///
+/// Parses from the `GET` form, where a `-` prefix sorts descending and a
+/// `+` prefix (or no prefix) sorts ascending.
+///
+/// # Examples
+///
+/// ```
+/// use stac::api_client::{Direction, SortBy};
+/// let sortby: SortBy = "-properties.datetime".parse().unwrap();
+/// assert_eq!(sortby, SortBy::desc("properties.datetime"));
+/// assert_eq!(sortby.to_string(), "-properties.datetime");
+/// assert_eq!(SortBy::from("id").direction, Direction::Asc);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortBy {
+    /// The field, e.g. `properties.datetime` or `id`.
+    pub field: String,
+
+    /// The direction to sort in.
+    pub direction: Direction,
+}
+
+/// The direction of a [SortBy].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Smallest first.
+    #[default]
+    Asc,
+
+    /// Largest first.
+    Desc,
+}
+
+impl ItemSearch {
+    /// Creates a new, empty search, which matches every item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new();
+    /// assert_eq!(serde_json::to_string(&search).unwrap(), "{}");
+    /// ```
+    pub fn new() -> ItemSearch {
+        ItemSearch::default()
+    }
+
+    /// Only returns items whose geometry intersects this bbox.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().bbox([-105.0, 40.0, -104.0, 41.0]);
+    /// ```
+    pub fn bbox(mut self, bbox: impl Into<Vec<f64>>) -> ItemSearch {
+        self.bbox = Some(bbox.into());
+        self
+    }
+
+    /// Only returns items whose datetimes intersect this datetime or interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().datetime("2023-01-01T00:00:00Z/..");
+    /// ```
+    pub fn datetime(mut self, datetime: impl ToString) -> ItemSearch {
+        self.datetime = Some(datetime.to_string());
+        self
+    }
+
+    /// Only returns items with these ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().ids(["an-item", "another-item"]);
+    /// ```
+    pub fn ids<I, S>(mut self, ids: I) -> ItemSearch
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.ids = Some(ids.into_iter().map(|id| id.to_string()).collect());
+        self
+    }
+
+    /// Only returns items in these collections.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().collections(["a-collection"]);
+    /// ```
+    pub fn collections<I, S>(mut self, collections: I) -> ItemSearch
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.collections = Some(
+            collections
+                .into_iter()
+                .map(|collection| collection.to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets the maximum number of items in each page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().limit(100);
+    /// ```
+    pub fn limit(mut self, limit: usize) -> ItemSearch {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only returns items whose title, description, or keywords match this free-text search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// let search = ItemSearch::new().q("wildfire,burn");
+    /// assert_eq!(serde_json::to_value(&search).unwrap()["q"], "wildfire,burn");
+    /// ```
+    pub fn q(mut self, q: impl ToString) -> ItemSearch {
+        self.q = Some(q.to_string());
+        self
+    }
+
+    /// Sorts by a field, after any fields that were already added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::{ItemSearch, SortBy};
+    /// let search = ItemSearch::new()
+    ///     .sortby("-properties.eo:cloud_cover")
+    ///     .sortby(SortBy::asc("id"));
+    /// assert_eq!(search.sortby.len(), 2);
+    /// ```
+    pub fn sortby(mut self, sortby: impl Into<SortBy>) -> ItemSearch {
+        self.sortby.push(sortby.into());
+        self
+    }
+
+    /// Only returns items that match this [Query].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::{ItemSearch, Query};
+    /// let search = ItemSearch::new().query(Query::new().lt("eo:cloud_cover", 10));
+    /// ```
+    pub fn query(mut self, query: Query) -> ItemSearch {
+        self.query = Some(query);
+        self
+    }
+
+    /// Only returns items that match this CQL2 JSON filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::ItemSearch;
+    /// use serde_json::json;
+    /// let search = ItemSearch::new()
+    ///     .filter(json!({"op": "<", "args": [{"property": "eo:cloud_cover"}, 10]}));
+    /// assert_eq!(search.filter_lang.as_deref(), Some("cql2-json"));
+    /// ```
+    pub fn filter(mut self, filter: Value) -> ItemSearch {
+        self.filter = Some(filter);
+        self.filter_lang = Some("cql2-json".to_string());
+        self
+    }
+
+    /// Sorts items in place by this search's [sortby](ItemSearch::sortby) fields.
+    ///
+    /// This is how results are sorted when an API doesn't support the sort
+    /// extension. Fields are looked up on the item, e.g. `id`, and then in
+    /// its properties, with or without a `properties.` prefix. Numbers are
+    /// compared as numbers and everything else as strings, and items without
+    /// a field sort after items with it, in either direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{api_client::ItemSearch, Item};
+    /// let mut items = vec![Item::new("a"), Item::new("b")];
+    /// ItemSearch::new().sortby("-id").sort(&mut items);
+    /// assert_eq!(items[0].id, "b");
+    /// ```
+    pub fn sort(&self, items: &mut [Item]) {
+        if self.sortby.is_empty() {
+            return;
+        }
+        items.sort_by(|a, b| {
+            self.sortby
+                .iter()
+                .map(|sortby| sortby.compare(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+impl CollectionSearch {
+    /// Creates a new, empty search, which matches every collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::CollectionSearch;
+    /// let search = CollectionSearch::new();
+    /// ```
+    pub fn new() -> CollectionSearch {
+        CollectionSearch::default()
+    }
+
+    /// Only returns collections whose spatial extent intersects this bbox.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::CollectionSearch;
+    /// let search = CollectionSearch::new().bbox([-105.0, 40.0, -104.0, 41.0]);
+    /// ```
+    pub fn bbox(mut self, bbox: impl Into<Vec<f64>>) -> CollectionSearch {
+        self.bbox = Some(bbox.into());
+        self
+    }
+
+    /// Only returns collections whose temporal extent intersects this datetime or interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::CollectionSearch;
+    /// let search = CollectionSearch::new().datetime("2023-01-01T00:00:00Z/..");
+    /// ```
+    pub fn datetime(mut self, datetime: impl ToString) -> CollectionSearch {
+        self.datetime = Some(datetime.to_string());
+        self
+    }
+
+    /// Only returns collections that match this free-text search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::CollectionSearch;
+    /// let search = CollectionSearch::new().q("landsat");
+    /// ```
+    pub fn q(mut self, q: impl ToString) -> CollectionSearch {
+        self.q = Some(q.to_string());
+        self
+    }
+
+    /// Sets the maximum number of collections in each page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::CollectionSearch;
+    /// let search = CollectionSearch::new().limit(100);
+    /// ```
+    pub fn limit(mut self, limit: usize) -> CollectionSearch {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns this search as `GET` query parameters.
+    pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(bbox) = &self.bbox {
+            let bbox: Vec<String> = bbox.iter().map(f64::to_string).collect();
+            pairs.push(("bbox", bbox.join(",")));
+        }
+        if let Some(datetime) = &self.datetime {
+            pairs.push(("datetime", datetime.clone()));
+        }
+        if let Some(q) = &self.q {
+            pairs.push(("q", q.clone()));
+        }
+        if let Some(limit) = self.limit {
+            pairs.push(("limit", limit.to_string()));
+        }
+        pairs
+    }
+}
+
+impl SortBy {
+    /// Sorts by this field, smallest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::SortBy;
+    /// let sortby = SortBy::asc("id");
+    /// ```
+    pub fn asc(field: impl ToString) -> SortBy {
+        SortBy {
+            field: field.to_string(),
+            direction: Direction::Asc,
+        }
+    }
+
+    /// Sorts by this field, largest first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::SortBy;
+    /// let sortby = SortBy::desc("properties.datetime");
+    /// ```
+    pub fn desc(field: impl ToString) -> SortBy {
+        SortBy {
+            field: field.to_string(),
+            direction: Direction::Desc,
+        }
+    }
+
+    /// Returns this field's name without a `properties.` prefix, as used by queryables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::api_client::SortBy;
+    /// assert_eq!(SortBy::asc("properties.eo:cloud_cover").queryable(), "eo:cloud_cover");
+    /// assert_eq!(SortBy::asc("id").queryable(), "id");
+    /// ```
+    pub fn queryable(&self) -> &str {
+        self.field
+            .strip_prefix("properties.")
+            .unwrap_or(&self.field)
+    }
+
+    fn compare(&self, a: &Item, b: &Item) -> Ordering {
+        let ordering = match (self.value(a), self.value(b)) {
+            (Some(a), Some(b)) => compare_values(&a, &b),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        match self.direction {
+            Direction::Asc => ordering,
+            Direction::Desc => ordering.reverse(),
+        }
+    }
+
+    fn value(&self, item: &Item) -> Option<Value> {
+        match self.field.as_str() {
+            "id" => return Some(item.id.clone().into()),
+            "collection" => return item.collection.as_deref().map(Value::from),
+            _ => {}
+        }
+        match self.queryable() {
+            "datetime" => item.properties.datetime.clone().map(Value::from),
+            field => item.properties.additional_fields.get(field).cloned(),
+        }
+        .filter(|value| !value.is_null())
+    }
+}
+
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => a.to_string().cmp(&b.to_string()),
+        },
+    }
+}
+
+impl From<&str> for SortBy {
+    fn from(s: &str) -> SortBy {
+        if let Some(field) = s.strip_prefix('-') {
+            SortBy::desc(field)
+        } else {
+            SortBy::asc(s.strip_prefix('+').unwrap_or(s))
+        }
+    }
+}
+
+impl FromStr for SortBy {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<SortBy, Infallible> {
+        Ok(SortBy::from(s))
+    }
+}
+
+impl Display for SortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.direction {
+            Direction::Asc => write!(f, "{}", self.field),
+            Direction::Desc => write!(f, "-{}", self.field),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollectionSearch, ItemSearch, SortBy};
+    use crate::Item;
+    use serde_json::json;
+
+    fn item(id: &str, cloud_cover: Option<f64>) -> Item {
+        let mut item = Item::new(id);
+        if let Some(cloud_cover) = cloud_cover {
+            let _ = item
+                .properties
+                .additional_fields
+                .insert("eo:cloud_cover".to_string(), cloud_cover.into());
+        }
+        item
+    }
+
+    #[test]
+    fn serialize() {
+        let search = ItemSearch::new()
+            .ids(["a"])
+            .sortby("-properties.datetime")
+            .sortby("+id");
+        assert_eq!(
+            serde_json::to_value(&search).unwrap(),
+            json!({
+                "ids": ["a"],
+                "sortby": [
+                    {"field": "properties.datetime", "direction": "desc"},
+                    {"field": "id", "direction": "asc"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn sort_by_several_fields() {
+        let mut items = vec![
+            item("c", Some(10.0)),
+            item("missing", None),
+            item("a", Some(2.0)),
+            item("b", Some(10.0)),
+        ];
+        ItemSearch::new()
+            .sortby(SortBy::desc("properties.eo:cloud_cover"))
+            .sortby("id")
+            .sort(&mut items);
+        let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c", "a", "missing"]);
+    }
+
+    #[test]
+    fn collection_search_query_pairs() {
+        let search = CollectionSearch::new()
+            .bbox([-105.0, 40.5, -104.0, 41.0])
+            .q("landsat")
+            .limit(2);
+        assert_eq!(
+            search.query_pairs(),
+            [
+                ("bbox", "-105,40.5,-104,41".to_string()),
+                ("q", "landsat".to_string()),
+                ("limit", "2".to_string())
+            ]
+        );
+        assert!(CollectionSearch::new().query_pairs().is_empty());
+    }
+}
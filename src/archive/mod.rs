@@ -0,0 +1,78 @@
+//! Read and write STAC catalogs in tar and zip archives.
+//!
+//! An [ArchiveWriter] implements [Write](crate::Write), so a whole rendered
+//! catalog can be written into a single distributable file instead of
+//! thousands of small ones. Entry timestamps are fixed, so the same catalog
+//! always produces the same archive.
+//!
+//! An [ArchiveReader] implements [Read](crate::Read) for hrefs like
+//! `archive.zip!/catalog.json`, which point at entries inside an archive.
+//! Relative links resolve within the archive, so a downloaded catalog bundle
+//! can be walked without extracting it.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::{archive::{ArchiveWriter, Format}, Catalog, Item, Layout, Stac};
+//! let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+//! let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+//! let mut layout = Layout::new("catalog");
+//! let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+//! stac.write(&mut layout, &writer).unwrap();
+//! let bytes = writer.finish().unwrap();
+//! assert!(bytes.starts_with(b"PK"));
+//! ```
+
+// Archives are read and written by hand instead of with the `zip` and `tar`
+// crates. We only need deflated and stored entries, which `flate2` (already a
+// dependency) handles, and writing the headers ourselves keeps archives
+// byte-for-byte reproducible without pulling in the `zip` crate's other
+// compression and encryption dependencies.
+mod reader;
+mod writer;
+
+use std::path::Path;
+pub use {
+    reader::{ArchiveReader, SEPARATOR},
+    writer::ArchiveWriter,
+};
+
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// The kinds of archive that can be read and written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// An uncompressed tarball.
+    Tar,
+
+    /// A gzipped tarball.
+    TarGz,
+
+    /// A zip file with deflated entries.
+    Zip,
+}
+
+impl Format {
+    /// Returns the format for a file name, based on its extension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::Format;
+    /// assert_eq!(Format::from_path("catalog.tar.gz"), Some(Format::TarGz));
+    /// assert_eq!(Format::from_path("catalog.zip"), Some(Format::Zip));
+    /// assert_eq!(Format::from_path("catalog.json"), None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Format> {
+        let name = path.as_ref().file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Format::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Format::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Format::Zip)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,574 @@
+use super::TAR_BLOCK_SIZE;
+use crate::{Error, Href, Read, Reader, Result};
+use flate2::read::{DeflateDecoder, GzDecoder};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read as _, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use url::Url;
+
+/// Separates an archive's path from the path of an entry inside it, e.g.
+/// `catalog.zip!/catalog.json`.
+pub const SEPARATOR: &str = "!/";
+
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP_CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+const ZIP_LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR: u32 = 0x0706_4b50;
+const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+const ZIP32_MAX: u64 = 0xffff_ffff;
+
+/// Reads STAC objects out of tar and zip archives.
+///
+/// Hrefs like `catalog.zip!/catalog.json` are read from the entry after the
+/// [SEPARATOR] in the archive before it. Since the href is still a path,
+/// relative links from that object resolve to other entries in the same
+/// archive. Other hrefs are read with the wrapped [Reader].
+///
+/// Zip files (including Zip64), tarballs, and gzipped tarballs are supported, and are detected
+/// from their contents rather than their extension. Each archive's table of
+/// contents is read once and cached. Gzipped tarballs can't be seeked, so the
+/// contents of their `.json` entries are cached instead.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{archive::ArchiveReader, Read, Stac};
+/// let reader = ArchiveReader::default();
+/// let catalog = reader.read("data/archives/catalog.zip!/catalog.json").unwrap();
+/// let (mut stac, root) = Stac::new_with_reader(catalog, reader).unwrap();
+/// let collection = stac.children(root)[0];
+/// assert_eq!(stac.get(collection).unwrap().id(), "archived-collection");
+/// assert_eq!(
+///     stac.href(collection).unwrap().as_str(),
+///     "data/archives/catalog.zip!/collection/collection.json"
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct ArchiveReader {
+    /// The reader for hrefs that aren't inside of an archive.
+    pub reader: Reader,
+
+    indices: Mutex<HashMap<PathBuf, Arc<Index>>>,
+}
+
+#[derive(Debug)]
+enum Index {
+    /// Entry names to their data's offset and size.
+    Tar(HashMap<String, (u64, u64)>),
+
+    /// Entry names to their contents, for `.json` entries.
+    TarGz(HashMap<String, Vec<u8>>),
+
+    Zip(HashMap<String, ZipEntry>),
+}
+
+#[derive(Debug)]
+struct ZipEntry {
+    method: u16,
+    compressed_size: u64,
+    offset: u64,
+}
+
+impl ArchiveReader {
+    /// Creates a new archive reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::ArchiveReader;
+    /// let reader = ArchiveReader::new();
+    /// ```
+    pub fn new() -> ArchiveReader {
+        ArchiveReader::default()
+    }
+
+    /// Reads the raw bytes of an archive entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::ArchiveReader;
+    /// let reader = ArchiveReader::new();
+    /// let data = reader
+    ///     .read_entry(
+    ///         "data/archives/catalog.tar",
+    ///         "collection/S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T130415-archived/data.txt",
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(data, b"some data\n");
+    /// ```
+    pub fn read_entry(&self, archive: impl AsRef<Path>, entry: &str) -> Result<Vec<u8>> {
+        let archive = archive.as_ref();
+        let index = self.index(archive)?;
+        let name = entry_name(entry);
+        let not_found = || Error::Archive(format!("{} is not in {}", entry, archive.display()));
+        match &*index {
+            Index::Tar(entries) => {
+                let &(offset, size) = entries.get(name).ok_or_else(not_found)?;
+                let mut file = File::open(archive)?;
+                let _ = file.seek(SeekFrom::Start(offset))?;
+                let mut data = Vec::new();
+                let _ = file.take(size).read_to_end(&mut data)?;
+                Ok(data)
+            }
+            Index::TarGz(entries) => entries.get(name).cloned().ok_or_else(|| {
+                Error::Archive(format!(
+                    "{} is not a .json entry in {}",
+                    entry,
+                    archive.display()
+                ))
+            }),
+            Index::Zip(entries) => {
+                let entry = entries.get(name).ok_or_else(not_found)?;
+                read_zip_entry(archive, entry)
+            }
+        }
+    }
+
+    fn index(&self, archive: &Path) -> Result<Arc<Index>> {
+        if let Some(index) = self
+            .indices
+            .lock()
+            .expect("indices lock poisoned")
+            .get(archive)
+        {
+            return Ok(index.clone());
+        }
+        let index = Arc::new(Index::read(archive)?);
+        let _ = self
+            .indices
+            .lock()
+            .expect("indices lock poisoned")
+            .insert(archive.to_path_buf(), index.clone());
+        Ok(index)
+    }
+
+    fn read_json_from_str(&self, path: &str) -> Result<Value> {
+        if let Some((archive, entry)) = path.split_once(SEPARATOR) {
+            let data = self.read_entry(archive, entry)?;
+            serde_json::from_slice(&data).map_err(Error::from)
+        } else {
+            self.reader.read_json_from_path(path)
+        }
+    }
+}
+
+impl Read for ArchiveReader {
+    fn read_json(&self, href: &Href) -> Result<Value> {
+        match href {
+            Href::Url(url) => self.read_json_from_url(url),
+            Href::Path(path) => self.read_json_from_str(path),
+        }
+    }
+
+    fn read_json_from_url(&self, url: &Url) -> Result<Value> {
+        self.reader.read_json_from_url(url)
+    }
+
+    fn read_json_from_path(&self, path: impl AsRef<Path>) -> Result<Value> {
+        let path = path.as_ref();
+        let path = path
+            .to_str()
+            .ok_or_else(|| Error::Archive(format!("non-UTF-8 path: {}", path.display())))?;
+        self.read_json_from_str(&path.replace('\\', "/"))
+    }
+}
+
+impl Index {
+    fn read(path: &Path) -> Result<Index> {
+        let mut file = File::open(path)?;
+        let mut magic = [0; 4];
+        let n = file.read(&mut magic)?;
+        file.rewind()?;
+        match &magic[..n] {
+            [0x50, 0x4b, 0x03, 0x04] | [0x50, 0x4b, 0x05, 0x06] => read_zip_index(file),
+            [0x1f, 0x8b, ..] => {
+                let mut entries = HashMap::new();
+                read_tar(GzDecoder::new(BufReader::new(file)), |name, _, data| {
+                    if name.to_lowercase().ends_with(".json") {
+                        let mut buffer = Vec::new();
+                        let _ = data.read_to_end(&mut buffer)?;
+                        let _ = entries.insert(name, buffer);
+                    }
+                    Ok(())
+                })?;
+                Ok(Index::TarGz(entries))
+            }
+            _ => {
+                let mut entries = HashMap::new();
+                read_tar(BufReader::new(file), |name, offset, data| {
+                    let _ = entries.insert(name, (offset, data.limit()));
+                    Ok(())
+                })?;
+                Ok(Index::Tar(entries))
+            }
+        }
+    }
+}
+
+/// Calls `f` with the name, data offset, and data of every file in a tarball.
+fn read_tar<R: std::io::Read>(
+    mut reader: R,
+    mut f: impl FnMut(String, u64, &mut std::io::Take<&mut R>) -> Result<()>,
+) -> Result<()> {
+    let mut offset = 0;
+    let mut long_name = None;
+    loop {
+        let mut header = [0; TAR_BLOCK_SIZE];
+        if let Err(err) = reader.read_exact(&mut header) {
+            if err.kind() == std::io::ErrorKind::UnexpectedEof && offset > 0 {
+                // Some writers leave off the end-of-archive blocks.
+                return Ok(());
+            }
+            return Err(Error::Archive(format!("invalid tar header: {}", err)));
+        }
+        offset += TAR_BLOCK_SIZE as u64;
+        if header.iter().all(|&b| b == 0) {
+            return Ok(());
+        }
+        let size = tar_size(&header[124..136])?;
+        let padded = size
+            .div_ceil(TAR_BLOCK_SIZE as u64)
+            .checked_mul(TAR_BLOCK_SIZE as u64)
+            .ok_or_else(|| Error::Archive(format!("invalid tar entry size: {}", size)))?;
+        let mut data = reader.by_ref().take(size);
+        match header[156] {
+            b'L' => {
+                let mut name = Vec::new();
+                let _ = data.read_to_end(&mut name)?;
+                long_name = Some(c_string(&name));
+            }
+            b'x' => {
+                let mut records = Vec::new();
+                let _ = data.read_to_end(&mut records)?;
+                long_name = pax_path(&String::from_utf8_lossy(&records));
+            }
+            b'0' | b'\0' | b'7' => {
+                let name = long_name.take().unwrap_or_else(|| {
+                    let name = c_string(&header[..100]);
+                    let prefix = if &header[257..262] == b"ustar" {
+                        c_string(&header[345..500])
+                    } else {
+                        String::new()
+                    };
+                    if prefix.is_empty() {
+                        name
+                    } else {
+                        format!("{}/{}", prefix, name)
+                    }
+                });
+                f(entry_name(&name).to_string(), offset, &mut data)?;
+            }
+            _ => long_name = None,
+        }
+        let _ = std::io::copy(&mut data, &mut std::io::sink())?;
+        let _ = std::io::copy(
+            &mut reader.by_ref().take(padded - size),
+            &mut std::io::sink(),
+        )?;
+        offset += padded;
+    }
+}
+
+fn tar_size(field: &[u8]) -> Result<u64> {
+    if field[0] & 0x80 != 0 {
+        // GNU base-256 encoding, for files of 8 GiB or more.
+        return Ok(field[1..]
+            .iter()
+            .fold(u64::from(field[0] & 0x7f), |size, &b| {
+                (size << 8) | u64::from(b)
+            }));
+    }
+    let octal = c_string(field);
+    let octal = octal.trim();
+    if octal.is_empty() {
+        Ok(0)
+    } else {
+        u64::from_str_radix(octal, 8)
+            .map_err(|_| Error::Archive(format!("invalid tar entry size: {:?}", octal)))
+    }
+}
+
+/// Returns the `path` from pax extended header records, e.g. `30 path=a/b.json\n`.
+fn pax_path(records: &str) -> Option<String> {
+    records.lines().find_map(|record| {
+        let (_, keyword_value) = record.split_once(' ')?;
+        keyword_value.strip_prefix("path=").map(String::from)
+    })
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn entry_name(name: &str) -> &str {
+    let mut name = name;
+    while let Some(stripped) = name.strip_prefix("./") {
+        name = stripped;
+    }
+    name.trim_start_matches('/')
+}
+
+fn read_zip_index(mut file: File) -> Result<Index> {
+    let len = file.seek(SeekFrom::End(0))?;
+    // The end of central directory record is 22 bytes, plus a comment of up
+    // to 64 KiB, and Zip64 files have a 20 byte locator before it.
+    let tail_len = len.min(20 + 22 + 0xffff);
+    let _ = file.seek(SeekFrom::Start(len - tail_len))?;
+    let mut tail = Vec::new();
+    let _ = file.read_to_end(&mut tail)?;
+    let end = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&i| u32_at(&tail, i) == ZIP_END_OF_CENTRAL_DIRECTORY)
+        .ok_or_else(|| Error::Archive("no zip end of central directory record".to_string()))?;
+    let mut count = u64::from(u16_at(&tail, end + 10));
+    let mut size = u64::from(u32_at(&tail, end + 12));
+    let mut start = u64::from(u32_at(&tail, end + 16));
+    if end >= 20 && u32_at(&tail, end - 20) == ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR {
+        let _ = file.seek(SeekFrom::Start(u64_at(&tail, end - 12)))?;
+        let mut record = [0; 56];
+        file.read_exact(&mut record)?;
+        if u32_at(&record, 0) != ZIP64_END_OF_CENTRAL_DIRECTORY {
+            return Err(Error::Archive(
+                "invalid zip64 end of central directory record".to_string(),
+            ));
+        }
+        count = u64_at(&record, 32);
+        size = u64_at(&record, 40);
+        start = u64_at(&record, 48);
+    }
+    if start.checked_add(size).is_none_or(|end| end > len) {
+        return Err(Error::Archive(format!(
+            "zip central directory ({} bytes at {}) is outside of the file ({} bytes)",
+            size, start, len
+        )));
+    }
+    let _ = file.seek(SeekFrom::Start(start))?;
+    let mut directory = vec![0; size as usize];
+    file.read_exact(&mut directory)?;
+    let mut entries = HashMap::new();
+    let mut i = 0;
+    for _ in 0..count {
+        if directory.len() < i + 46 || u32_at(&directory, i) != ZIP_CENTRAL_DIRECTORY_HEADER {
+            return Err(Error::Archive("invalid zip central directory".to_string()));
+        }
+        let name_len = usize::from(u16_at(&directory, i + 28));
+        let extra_len = usize::from(u16_at(&directory, i + 30));
+        let comment_len = usize::from(u16_at(&directory, i + 32));
+        let name = directory
+            .get(i + 46..i + 46 + name_len)
+            .ok_or_else(|| Error::Archive("invalid zip central directory".to_string()))?;
+        let name = String::from_utf8_lossy(name);
+        let extra = directory
+            .get(i + 46 + name_len..i + 46 + name_len + extra_len)
+            .ok_or_else(|| Error::Archive("invalid zip central directory".to_string()))?;
+        let mut uncompressed_size = u64::from(u32_at(&directory, i + 24));
+        let mut compressed_size = u64::from(u32_at(&directory, i + 20));
+        let mut offset = u64::from(u32_at(&directory, i + 42));
+        // Fields that don't fit are set to the maximum, and their values
+        // are in the Zip64 extra field, in this order.
+        let mut values = zip64_extra(extra).chunks_exact(8).map(|v| u64_at(v, 0));
+        for field in [&mut uncompressed_size, &mut compressed_size, &mut offset] {
+            if *field == ZIP32_MAX {
+                *field = values
+                    .next()
+                    .ok_or_else(|| Error::Archive("missing zip64 extra field".to_string()))?;
+            }
+        }
+        if !name.ends_with('/') {
+            let _ = entries.insert(
+                entry_name(&name).to_string(),
+                ZipEntry {
+                    method: u16_at(&directory, i + 10),
+                    compressed_size,
+                    offset,
+                },
+            );
+        }
+        i += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(Index::Zip(entries))
+}
+
+/// Returns the data of the Zip64 extra field, or nothing if there isn't one.
+fn zip64_extra(mut extra: &[u8]) -> &[u8] {
+    while extra.len() >= 4 {
+        let id = u16_at(extra, 0);
+        let len = usize::from(u16_at(extra, 2));
+        let data = &extra[4..extra.len().min(4 + len)];
+        if id == ZIP64_EXTRA_FIELD {
+            return data;
+        }
+        extra = &extra[4 + data.len()..];
+    }
+    &[]
+}
+
+fn read_zip_entry(archive: &Path, entry: &ZipEntry) -> Result<Vec<u8>> {
+    let mut file = File::open(archive)?;
+    let _ = file.seek(SeekFrom::Start(entry.offset))?;
+    let mut header = [0; 30];
+    file.read_exact(&mut header)?;
+    if u32_at(&header, 0) != ZIP_LOCAL_FILE_HEADER {
+        return Err(Error::Archive("invalid zip local file header".to_string()));
+    }
+    let skip = i64::from(u16_at(&header, 26)) + i64::from(u16_at(&header, 28));
+    let _ = file.seek(SeekFrom::Current(skip))?;
+    let compressed = file.take(entry.compressed_size);
+    let mut data = Vec::new();
+    match entry.method {
+        0 => {
+            let _ = BufReader::new(compressed).read_to_end(&mut data)?;
+        }
+        8 => {
+            let _ = DeflateDecoder::new(BufReader::new(compressed)).read_to_end(&mut data)?;
+        }
+        method => {
+            return Err(Error::Archive(format!(
+                "unsupported zip compression method: {}",
+                method
+            )))
+        }
+    }
+    Ok(data)
+}
+
+fn u16_at(bytes: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([bytes[i], bytes[i + 1]])
+}
+
+fn u32_at(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+}
+
+fn u64_at(bytes: &[u8], i: usize) -> u64 {
+    let mut value = [0; 8];
+    value.copy_from_slice(&bytes[i..i + 8]);
+    u64::from_le_bytes(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archive::{ArchiveReader, ArchiveWriter, Format},
+        Catalog, Item, Layout, Read, Stac,
+    };
+
+    const ITEM_ID: &str = "S2B_MSIL2A_20230704T101559_N0509_R065_T33UUP_20230704T130415-archived";
+
+    fn walk(href: &str) -> Vec<String> {
+        let reader = ArchiveReader::default();
+        let catalog = reader.read(href).unwrap();
+        let (mut stac, root) = Stac::new_with_reader(catalog, reader).unwrap();
+        stac.walk(root)
+            .visit(|stac, handle| Ok(stac.get(handle)?.id().to_string()))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn read_archives() {
+        for archive in ["catalog.zip", "catalog.tar", "catalog.tar.gz"] {
+            let ids = walk(&format!("data/archives/{}!/catalog.json", archive));
+            assert_eq!(
+                ids,
+                ["archived", "archived-collection", ITEM_ID],
+                "{}",
+                archive
+            );
+        }
+    }
+
+    #[test]
+    fn read_entry() {
+        let reader = ArchiveReader::default();
+        let name = format!("collection/{}/data.txt", ITEM_ID);
+        for archive in ["catalog.zip", "catalog.tar"] {
+            let data = reader
+                .read_entry(format!("data/archives/{}", archive), &name)
+                .unwrap();
+            assert_eq!(data, b"some data\n");
+        }
+        assert!(reader
+            .read_entry("data/archives/catalog.tar.gz", &name)
+            .is_err());
+        assert!(reader
+            .read_entry("data/archives/catalog.zip", "not-an-entry.json")
+            .is_err());
+    }
+
+    #[test]
+    fn outside_of_archive() {
+        let reader = ArchiveReader::default();
+        let catalog = reader.read("data/catalog.json").unwrap();
+        assert_eq!(catalog.object.id(), "examples");
+    }
+
+    #[test]
+    fn round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        for (file_name, format) in [
+            ("catalog.tar", Format::Tar),
+            ("catalog.tar.gz", Format::TarGz),
+            ("catalog.zip", Format::Zip),
+        ] {
+            let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+            let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+            let writer = ArchiveWriter::new(Vec::new(), format);
+            stac.write(&mut Layout::new("catalog"), &writer).unwrap();
+            let path = directory.path().join(file_name);
+            std::fs::write(&path, writer.finish().unwrap()).unwrap();
+            let href = format!("{}!/catalog/catalog.json", path.display());
+            assert_eq!(walk(&href), ["root", "an-item"], "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn zip64_entry_count() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("many.zip");
+        let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+        for i in 0..0x10000 {
+            writer.append(format!("{}.json", i), b"{}").unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        let end = &bytes[bytes.len() - 22..];
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 0xffff);
+        std::fs::write(&path, bytes).unwrap();
+        let reader = ArchiveReader::default();
+        assert_eq!(reader.read_entry(&path, "0.json").unwrap(), b"{}");
+        assert_eq!(reader.read_entry(&path, "65535.json").unwrap(), b"{}");
+    }
+
+    #[test]
+    fn zip_directory_outside_of_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("bad.zip");
+        let mut end = vec![0x50, 0x4b, 0x05, 0x06, 0, 0, 0, 0, 1, 0, 1, 0];
+        end.extend(u32::MAX.to_le_bytes()); // central directory size
+        end.extend(0u32.to_le_bytes()); // central directory offset
+        end.extend([0, 0]);
+        std::fs::write(&path, end).unwrap();
+        let reader = ArchiveReader::default();
+        assert!(matches!(
+            reader.read_entry(&path, "catalog.json").unwrap_err(),
+            crate::Error::Archive(_)
+        ));
+    }
+
+    #[test]
+    fn pax_path() {
+        assert_eq!(
+            super::pax_path("17 mtime=1234567\n30 path=a/very/long/b.json\n").unwrap(),
+            "a/very/long/b.json"
+        );
+        assert!(super::pax_path("17 mtime=1234567\n").is_none());
+    }
+}
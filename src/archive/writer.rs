@@ -0,0 +1,586 @@
+use super::{Format, TAR_BLOCK_SIZE};
+use crate::{write, Error, HrefObject, Result, Version, Write};
+use flate2::{write::DeflateEncoder, write::GzEncoder, Compression, Crc};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+};
+use url::Url;
+
+/// The modification time of every tar entry, 2000-01-01T00:00:00Z, in seconds
+/// since the epoch.
+const TAR_MTIME: u64 = 946_684_800;
+
+/// The DOS date of every zip entry (2000-01-01).
+const ZIP_DATE: u16 = (20 << 9) | (1 << 5) | 1;
+
+/// Sizes, offsets, and counts at or above these don't fit in a zip field, so
+/// the field is set to the maximum and the value is written in a Zip64 record.
+const ZIP32_MAX: u64 = 0xffff_ffff;
+const ZIP16_MAX: u64 = 0xffff;
+
+/// Writes STAC objects as entries in a tar or zip archive.
+///
+/// Entry names are the written paths, relative to the root set with
+/// [with_root](ArchiveWriter::with_root) if there is one. Paths must be
+/// relative and can't contain `..`, so lay out the catalog under a relative
+/// root or set the writer's root to the layout's. Objects can't be written to
+/// urls. Writing the same entry twice is an error, since archive entries
+/// can't be overwritten. Zip files switch to Zip64 records when they have
+/// more than 65,534 entries or are bigger than 4 GiB.
+///
+/// Call [finish](ArchiveWriter::finish) to complete the archive.
+#[derive(Debug)]
+pub struct ArchiveWriter<W: std::io::Write> {
+    /// Pretty-print json?
+    pub pretty: bool,
+
+    /// If set, every object's `stac_version` is normalized to this version before writing.
+    pub version: Option<Version>,
+
+    /// Sort links by `rel` and `href`, and assets by key, before writing?
+    pub sort: bool,
+
+    root: Option<PathBuf>,
+    archive: Mutex<Archive<W>>,
+    names: Mutex<HashSet<String>>,
+}
+
+#[derive(Debug)]
+enum Archive<W: std::io::Write> {
+    Tar(W),
+    TarGz(GzEncoder<W>),
+    Zip(Zip<W>),
+}
+
+#[derive(Debug)]
+struct Zip<W> {
+    writer: W,
+    offset: u64,
+    entries: Vec<ZipEntry>,
+}
+
+#[derive(Debug)]
+struct ZipEntry {
+    name: String,
+    crc: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    offset: u64,
+}
+
+impl ArchiveWriter<BufWriter<File>> {
+    /// Creates an archive file, choosing the [Format] from its extension.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::archive::ArchiveWriter;
+    /// let writer = ArchiveWriter::create("catalog.tar.gz").unwrap();
+    /// ```
+    pub fn create(path: impl AsRef<Path>) -> Result<ArchiveWriter<BufWriter<File>>> {
+        let path = path.as_ref();
+        let format = Format::from_path(path).ok_or_else(|| {
+            Error::Archive(format!("unknown archive extension: {}", path.display()))
+        })?;
+        let file = File::create(path)?;
+        Ok(ArchiveWriter::new(BufWriter::new(file), format))
+    }
+}
+
+impl<W: std::io::Write> ArchiveWriter<W> {
+    /// Creates a new archive writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::{ArchiveWriter, Format};
+    /// let writer = ArchiveWriter::new(Vec::new(), Format::TarGz);
+    /// ```
+    pub fn new(writer: W, format: Format) -> ArchiveWriter<W> {
+        let archive = match format {
+            Format::Tar => Archive::Tar(writer),
+            Format::TarGz => Archive::TarGz(GzEncoder::new(writer, Compression::default())),
+            Format::Zip => Archive::Zip(Zip {
+                writer,
+                offset: 0,
+                entries: Vec::new(),
+            }),
+        };
+        ArchiveWriter {
+            pretty: true,
+            version: None,
+            sort: false,
+            root: None,
+            archive: Mutex::new(archive),
+            names: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Sets the directory that entry names are relative to.
+    ///
+    /// Writing a path outside of this directory is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{archive::{ArchiveWriter, Format}, Catalog, Layout, Stac};
+    /// let (mut stac, _) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut layout = Layout::new("/tmp/catalog");
+    /// let writer = ArchiveWriter::new(Vec::new(), Format::Tar).with_root("/tmp/catalog");
+    /// stac.write(&mut layout, &writer).unwrap();
+    /// let bytes = writer.finish().unwrap();
+    /// assert!(bytes.starts_with(b"catalog.json"));
+    /// ```
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> ArchiveWriter<W> {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Adds a file to the archive, e.g. an asset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::{ArchiveWriter, Format};
+    /// let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+    /// writer.append("catalog/item/data.txt", b"some data").unwrap();
+    /// ```
+    pub fn append(&self, path: impl AsRef<Path>, data: &[u8]) -> Result<()> {
+        let name = self.entry_name(path.as_ref())?;
+        if !self
+            .names
+            .lock()
+            .expect("names lock poisoned")
+            .insert(name.clone())
+        {
+            return Err(Error::Archive(format!("duplicate entry: {}", name)));
+        }
+        let mut archive = self.archive.lock().expect("archive lock poisoned");
+        match &mut *archive {
+            Archive::Tar(writer) => append_tar(writer, &name, data),
+            Archive::TarGz(writer) => append_tar(writer, &name, data),
+            Archive::Zip(zip) => zip.append(name, data),
+        }
+    }
+
+    /// Completes the archive and returns the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::archive::{ArchiveWriter, Format};
+    /// let writer = ArchiveWriter::new(Vec::new(), Format::Tar);
+    /// let bytes = writer.finish().unwrap();
+    /// assert_eq!(bytes.len(), 1024);
+    /// ```
+    pub fn finish(self) -> Result<W> {
+        let mut writer = match self.archive.into_inner().expect("archive lock poisoned") {
+            Archive::Tar(mut writer) => {
+                writer.write_all(&[0; 2 * TAR_BLOCK_SIZE])?;
+                writer
+            }
+            Archive::TarGz(mut writer) => {
+                writer.write_all(&[0; 2 * TAR_BLOCK_SIZE])?;
+                writer.finish()?
+            }
+            Archive::Zip(zip) => zip.finish()?,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn entry_name(&self, path: &Path) -> Result<String> {
+        let relative = if let Some(root) = self.root.as_ref() {
+            path.strip_prefix(root).map_err(|_| {
+                Error::Archive(format!(
+                    "{} is not under {}",
+                    path.display(),
+                    root.display()
+                ))
+            })?
+        } else {
+            path
+        };
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_str().ok_or_else(|| {
+                    Error::Archive(format!("non-UTF-8 path: {}", path.display()))
+                })?),
+                Component::CurDir => {}
+                _ => {
+                    return Err(Error::Archive(format!(
+                        "entry paths must be relative and can't contain '..': {}",
+                        path.display()
+                    )))
+                }
+            }
+        }
+        if parts.is_empty() {
+            Err(Error::Archive(format!(
+                "empty entry path: {}",
+                path.display()
+            )))
+        } else {
+            Ok(parts.join("/"))
+        }
+    }
+}
+
+impl<W: std::io::Write> Write for ArchiveWriter<W> {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        let value = write::prepare(object.object, self.version.as_ref(), self.sort)?;
+        self.write_json(value, &object.href)
+    }
+
+    fn write_json_to_url(&self, _: Value, url: &Url) -> Result<()> {
+        Err(Error::CannotWriteUrl(url.clone()))
+    }
+
+    fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()> {
+        let data = if self.pretty {
+            serde_json::to_vec_pretty(&value)?
+        } else {
+            serde_json::to_vec(&value)?
+        };
+        self.append(path, &data)
+    }
+
+    fn settings(&self) -> Value {
+        write::settings(self.pretty, self.version.as_ref(), self.sort)
+    }
+}
+
+fn append_tar(writer: &mut impl std::io::Write, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = [0u8; TAR_BLOCK_SIZE];
+    let (prefix, name) = split_tar_name(name)?;
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", TAR_MTIME).as_bytes());
+    header[148..156].copy_from_slice(b"        ");
+    header[156] = b'0';
+    header[257..265].copy_from_slice(b"ustar\x0000");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(data)?;
+    let padding = (TAR_BLOCK_SIZE - data.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+    writer.write_all(&[0; TAR_BLOCK_SIZE][..padding])?;
+    Ok(())
+}
+
+/// Splits a name into the ustar prefix and name fields.
+fn split_tar_name(name: &str) -> Result<(&str, &str)> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+    name.match_indices('/')
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100)
+        .ok_or_else(|| Error::Archive(format!("path too long for a tar entry: {}", name)))
+}
+
+impl<W: std::io::Write> Zip<W> {
+    fn append(&mut self, name: String, data: &[u8]) -> Result<()> {
+        if name.len() > ZIP16_MAX as usize {
+            return Err(Error::Archive(format!(
+                "path too long for a zip entry: {}",
+                name
+            )));
+        }
+        let mut crc = Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        let entry = ZipEntry {
+            crc: crc.sum(),
+            compressed_size: compressed.len() as u64,
+            uncompressed_size: data.len() as u64,
+            offset: self.offset,
+            name,
+        };
+        let extra = entry.zip64_extra(true);
+        let mut header = Vec::with_capacity(30 + entry.name.len() + extra.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&version_needed(&extra).to_le_bytes());
+        entry.write_common(&mut header, true);
+        header.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+        header.extend_from_slice(entry.name.as_bytes());
+        header.extend_from_slice(&extra);
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&compressed)?;
+        self.offset += (header.len() + compressed.len()) as u64;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W> {
+        let count = self.entries.len() as u64;
+        let start = self.offset;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            let extra = entry.zip64_extra(false);
+            let version = version_needed(&extra);
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            // Made on unix, so the permissions are used.
+            directory.extend_from_slice(&(0x0300 | version).to_le_bytes());
+            directory.extend_from_slice(&version.to_le_bytes());
+            entry.write_common(&mut directory, false);
+            directory.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 6]);
+            directory.extend_from_slice(&(0o100_644u32 << 16).to_le_bytes());
+            directory.extend_from_slice(&zip32(entry.offset).to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+            directory.extend_from_slice(&extra);
+        }
+        let size = directory.len() as u64;
+        if count >= ZIP16_MAX || size >= ZIP32_MAX || start >= ZIP32_MAX {
+            let record = start + size;
+            directory.extend_from_slice(&0x0606_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&44u64.to_le_bytes());
+            directory.extend_from_slice(&0x032du16.to_le_bytes());
+            directory.extend_from_slice(&45u16.to_le_bytes());
+            directory.extend_from_slice(&[0; 8]);
+            directory.extend_from_slice(&count.to_le_bytes());
+            directory.extend_from_slice(&count.to_le_bytes());
+            directory.extend_from_slice(&size.to_le_bytes());
+            directory.extend_from_slice(&start.to_le_bytes());
+            directory.extend_from_slice(&0x0706_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&0u32.to_le_bytes());
+            directory.extend_from_slice(&record.to_le_bytes());
+            directory.extend_from_slice(&1u32.to_le_bytes());
+        }
+        let count = count.min(ZIP16_MAX) as u16;
+        directory.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        directory.extend_from_slice(&[0; 4]);
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&zip32(size).to_le_bytes());
+        directory.extend_from_slice(&zip32(start).to_le_bytes());
+        directory.extend_from_slice(&0u16.to_le_bytes());
+        self.writer.write_all(&directory)?;
+        Ok(self.writer)
+    }
+}
+
+impl ZipEntry {
+    /// Writes the fields shared by the local and central headers, from the
+    /// flags through the file name length.
+    fn write_common(&self, buffer: &mut Vec<u8>, local: bool) {
+        // A local Zip64 extra field has both sizes, so both are set to the
+        // maximum if either is.
+        let (compressed_size, uncompressed_size) = if local && self.has_zip64_sizes() {
+            (ZIP32_MAX as u32, ZIP32_MAX as u32)
+        } else {
+            (zip32(self.compressed_size), zip32(self.uncompressed_size))
+        };
+        // UTF-8 names, deflated, at midnight.
+        buffer.extend_from_slice(&0x0800u16.to_le_bytes());
+        buffer.extend_from_slice(&8u16.to_le_bytes());
+        buffer.extend_from_slice(&0u16.to_le_bytes());
+        buffer.extend_from_slice(&ZIP_DATE.to_le_bytes());
+        buffer.extend_from_slice(&self.crc.to_le_bytes());
+        buffer.extend_from_slice(&compressed_size.to_le_bytes());
+        buffer.extend_from_slice(&uncompressed_size.to_le_bytes());
+        buffer.extend_from_slice(&(self.name.len() as u16).to_le_bytes());
+    }
+
+    fn has_zip64_sizes(&self) -> bool {
+        self.compressed_size >= ZIP32_MAX || self.uncompressed_size >= ZIP32_MAX
+    }
+
+    /// Returns the Zip64 extra field for the local or central header, or
+    /// nothing if every value fits in its header field.
+    fn zip64_extra(&self, local: bool) -> Vec<u8> {
+        let values = if local {
+            if self.has_zip64_sizes() {
+                vec![self.uncompressed_size, self.compressed_size]
+            } else {
+                Vec::new()
+            }
+        } else {
+            [self.uncompressed_size, self.compressed_size, self.offset]
+                .into_iter()
+                .filter(|&value| value >= ZIP32_MAX)
+                .collect()
+        };
+        if values.is_empty() {
+            return Vec::new();
+        }
+        let mut extra = Vec::with_capacity(4 + 8 * values.len());
+        extra.extend_from_slice(&1u16.to_le_bytes());
+        extra.extend_from_slice(&(8 * values.len() as u16).to_le_bytes());
+        for value in values {
+            extra.extend_from_slice(&value.to_le_bytes());
+        }
+        extra
+    }
+}
+
+/// Returns the value of a 32-bit zip field, which is the maximum if the value
+/// is in a Zip64 record instead.
+fn zip32(n: u64) -> u32 {
+    n.min(ZIP32_MAX) as u32
+}
+
+/// Version 2.0, or 4.5 for entries with Zip64 extra fields.
+fn version_needed(extra: &[u8]) -> u16 {
+    if extra.is_empty() {
+        20
+    } else {
+        45
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::archive::{ArchiveWriter, Format};
+    use crate::{Catalog, Item, Layout, Stac, Write};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn stac() -> Stac<crate::Reader> {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+        stac
+    }
+
+    #[test]
+    fn tar() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Tar);
+        stac().write(&mut Layout::new("catalog"), &writer).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len() % 512, 0);
+        assert!(bytes.starts_with(b"catalog/catalog.json\0"));
+        assert_eq!(&bytes[257..263], b"ustar\0");
+        let checksum: u32 = bytes[..512]
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    u32::from(b)
+                }
+            })
+            .sum();
+        let stored = std::str::from_utf8(&bytes[148..154]).unwrap();
+        assert_eq!(u32::from_str_radix(stored, 8).unwrap(), checksum);
+        let size = std::str::from_utf8(&bytes[124..135]).unwrap();
+        let size = usize::from_str_radix(size, 8).unwrap();
+        let catalog: Catalog = serde_json::from_slice(&bytes[512..512 + size]).unwrap();
+        assert_eq!(catalog.id, "root");
+        let next = 512 + size.div_ceil(512) * 512;
+        assert!(bytes[next..].starts_with(b"catalog/an-item/an-item.json\0"));
+    }
+
+    #[test]
+    fn tar_gz() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::TarGz);
+        stac().write(&mut Layout::new("catalog"), &writer).unwrap();
+        let bytes = writer.finish().unwrap();
+        let mut tar = Vec::new();
+        let _ = GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut tar)
+            .unwrap();
+        assert!(tar.starts_with(b"catalog/catalog.json\0"));
+    }
+
+    #[test]
+    fn long_tar_name() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Tar);
+        let name = format!("{}/{}", "a".repeat(120), "b".repeat(90));
+        writer.append(&name, b"data").unwrap();
+        let bytes = writer.finish().unwrap();
+        assert!(bytes.starts_with("b".repeat(90).as_bytes()));
+        assert!(bytes[345..].starts_with("a".repeat(120).as_bytes()));
+        let writer = ArchiveWriter::new(Vec::new(), Format::Tar);
+        assert!(writer.append("c".repeat(101), b"data").is_err());
+    }
+
+    #[test]
+    fn zip() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+        stac().write(&mut Layout::new("catalog"), &writer).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert!(bytes.starts_with(&[0x50, 0x4b, 0x03, 0x04]));
+        let end = &bytes[bytes.len() - 22..];
+        assert!(end.starts_with(&[0x50, 0x4b, 0x05, 0x06]));
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        let offset = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert!(bytes[offset..].starts_with(&[0x50, 0x4b, 0x01, 0x02]));
+        assert_eq!(&bytes[offset + 46..offset + 66], b"catalog/catalog.json");
+    }
+
+    #[test]
+    fn zip64_offsets() {
+        use super::Archive;
+        use crate::archive::ArchiveReader;
+        use std::io::{Seek, SeekFrom};
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("big.zip");
+        let writer = ArchiveWriter::new(std::fs::File::create(&path).unwrap(), Format::Zip);
+        writer.append("a.json", b"{}").unwrap();
+        {
+            // Skip past 4 GiB without writing anything, so the file is sparse.
+            let mut archive = writer.archive.lock().unwrap();
+            let Archive::Zip(zip) = &mut *archive else {
+                unreachable!()
+            };
+            let _ = zip.writer.seek(SeekFrom::Current(1 << 32)).unwrap();
+            zip.offset += 1 << 32;
+        }
+        writer.append("b.json", b"[]").unwrap();
+        let _ = writer.finish().unwrap();
+        let reader = ArchiveReader::default();
+        assert_eq!(reader.read_entry(&path, "a.json").unwrap(), b"{}");
+        assert_eq!(reader.read_entry(&path, "b.json").unwrap(), b"[]");
+    }
+
+    #[test]
+    fn long_zip_name() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+        assert!(writer.append("a".repeat(0x10000), b"data").is_err());
+    }
+
+    #[test]
+    fn duplicate() {
+        for format in [Format::Tar, Format::Zip] {
+            let writer = ArchiveWriter::new(Vec::new(), format);
+            writer.append("a.txt", b"a").unwrap();
+            assert!(writer.append("./a.txt", b"a").is_err());
+        }
+    }
+
+    #[test]
+    fn entry_names() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Tar).with_root("out");
+        writer.append("out/catalog.json", b"{}").unwrap();
+        assert!(writer.append("elsewhere/catalog.json", b"{}").is_err());
+        let writer = ArchiveWriter::new(Vec::new(), Format::Tar);
+        assert!(writer.append("/abs/catalog.json", b"{}").is_err());
+        assert!(writer.append("../catalog.json", b"{}").is_err());
+    }
+
+    #[test]
+    fn url() {
+        let writer = ArchiveWriter::new(Vec::new(), Format::Zip);
+        assert!(writer
+            .write_json_to_url(
+                serde_json::json!({}),
+                &"http://stac.test/catalog.json".parse().unwrap()
+            )
+            .is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use crate::{band, intern::Interned, Band, Collection, Item, Object, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 
 /// An Asset is an object that contains a URI to data associated with the [Item](crate::Item) that can be downloaded or streamed.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -23,12 +25,19 @@ pub struct Asset {
     ///
     /// See the [common media types](https://github.com/radiantearth/stac-spec/blob/master/best-practices.md#common-media-types-in-stac) in the best practice doc for commonly used asset types.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub r#type: Option<String>,
+    pub r#type: Option<Interned>,
 
     /// The semantic roles of the asset, similar to the use of rel in [Links](crate::Link).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<String>>,
 
+    /// The bands in this asset, in the unified STAC 1.1 layout.
+    ///
+    /// See [Asset::merge_bands] and [Asset::split_bands] to convert to and
+    /// from the older `eo:bands` and `raster:bands` fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bands: Option<Vec<Band>>,
+
     /// Additional fields on the asset.
     #[serde(flatten)]
     pub additional_fields: Map<String, Value>,
@@ -55,14 +64,188 @@ impl Asset {
             description: None,
             r#type: None,
             roles: None,
+            bands: None,
             additional_fields: Map::new(),
         }
     }
+
+    /// Returns true if this asset has this role.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Asset;
+    /// let mut asset = Asset::new("thumbnail.png");
+    /// asset.roles = Some(vec!["thumbnail".to_string()]);
+    /// assert!(asset.has_role("thumbnail"));
+    /// assert!(!asset.has_role("data"));
+    /// ```
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles
+            .iter()
+            .flatten()
+            .any(|asset_role| asset_role == role)
+    }
+
+    /// Moves this asset's `eo:bands` and `raster:bands` into unified [bands](Asset::bands).
+    ///
+    /// Entries are paired by position, and extension fields are prefixed,
+    /// e.g. `common_name` becomes `eo:common_name`. Does nothing if this
+    /// asset already has unified bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Asset;
+    /// use serde_json::json;
+    /// let mut asset = Asset::new("data.tif");
+    /// let _ = asset.additional_fields.insert(
+    ///     "eo:bands".to_string(),
+    ///     json!([{"name": "B04", "common_name": "red"}]),
+    /// );
+    /// asset.merge_bands().unwrap();
+    /// let bands = asset.bands.unwrap();
+    /// assert_eq!(bands[0].get_common_name(), Some("red"));
+    /// assert!(asset.additional_fields.is_empty());
+    /// ```
+    pub fn merge_bands(&mut self) -> Result<()> {
+        band::merge(&mut self.additional_fields, &mut self.bands)
+    }
+
+    /// Moves this asset's unified [bands](Asset::bands) into `eo:bands` and `raster:bands`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Band};
+    /// let mut asset = Asset::new("data.tif");
+    /// asset.bands = Some(vec![Band::new("B04").common_name("red")]);
+    /// asset.split_bands().unwrap();
+    /// assert!(asset.bands.is_none());
+    /// assert_eq!(asset.additional_fields["eo:bands"][0]["common_name"], "red");
+    /// ```
+    pub fn split_bands(&mut self) -> Result<()> {
+        band::split(&mut self.additional_fields, &mut self.bands)
+    }
+}
+
+/// Implemented by all STAC objects that can have assets.
+///
+/// [Items](Item) always have assets; [Collections](Collection) have them if
+/// their `assets` field is set. Lookups that can match more than one asset
+/// return them sorted by key, so results don't depend on map order.
+pub trait Assets {
+    /// Returns a reference to this object's assets, if it has any.
+    fn assets(&self) -> Option<&HashMap<String, Asset>>;
+
+    /// Returns a mutable reference to this object's assets, if it has any.
+    fn assets_mut(&mut self) -> Option<&mut HashMap<String, Asset>>;
+
+    /// Returns the keys and assets that have this role, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Assets, Collection};
+    /// let mut collection = Collection::new("an-id");
+    /// let mut asset = Asset::new("metadata.xml");
+    /// asset.roles = Some(vec!["metadata".to_string()]);
+    /// collection.assets = Some([("metadata".to_string(), asset)].into());
+    /// let assets = collection.assets_with_role("metadata");
+    /// assert_eq!(assets[0].0, "metadata");
+    /// assert!(collection.assets_with_role("data").is_empty());
+    /// ```
+    fn assets_with_role(&self, role: &str) -> Vec<(&str, &Asset)> {
+        sorted(self.assets(), |asset| asset.has_role(role))
+    }
+
+    /// Returns the first asset, by key, that has this role.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Assets, Item};
+    /// let mut item = Item::new("an-id");
+    /// let mut asset = Asset::new("thumbnail.png");
+    /// asset.roles = Some(vec!["thumbnail".to_string()]);
+    /// let _ = item.assets.insert("thumbnail".to_string(), asset);
+    /// assert_eq!(item.find_asset_by_role("thumbnail").unwrap().href, "thumbnail.png");
+    /// ```
+    fn find_asset_by_role(&self, role: &str) -> Option<&Asset> {
+        self.assets_with_role(role)
+            .into_iter()
+            .next()
+            .map(|(_, asset)| asset)
+    }
+
+    /// Returns the keys and assets that have this [media type](crate::media_type), sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{media_type, Asset, Assets, Item};
+    /// let mut item = Item::new("an-id");
+    /// let mut asset = Asset::new("data.tif");
+    /// asset.r#type = Some(media_type::COG.into());
+    /// let _ = item.assets.insert("data".to_string(), asset);
+    /// assert_eq!(item.assets_with_media_type(media_type::COG).len(), 1);
+    /// assert!(item.assets_with_media_type(media_type::JSON).is_empty());
+    /// ```
+    fn assets_with_media_type(&self, media_type: &str) -> Vec<(&str, &Asset)> {
+        sorted(self.assets(), |asset| {
+            asset.r#type.as_deref() == Some(media_type)
+        })
+    }
+}
+
+fn sorted(
+    assets: Option<&HashMap<String, Asset>>,
+    filter: impl Fn(&Asset) -> bool,
+) -> Vec<(&str, &Asset)> {
+    let mut assets: Vec<_> = assets
+        .into_iter()
+        .flatten()
+        .filter(|(_, asset)| filter(asset))
+        .map(|(key, asset)| (key.as_str(), asset))
+        .collect();
+    assets.sort_by_key(|(key, _)| *key);
+    assets
+}
+
+impl Assets for Item {
+    fn assets(&self) -> Option<&HashMap<String, Asset>> {
+        Some(&self.assets)
+    }
+
+    fn assets_mut(&mut self) -> Option<&mut HashMap<String, Asset>> {
+        Some(&mut self.assets)
+    }
+}
+
+impl Assets for Collection {
+    fn assets(&self) -> Option<&HashMap<String, Asset>> {
+        self.assets.as_ref()
+    }
+
+    fn assets_mut(&mut self) -> Option<&mut HashMap<String, Asset>> {
+        self.assets.as_mut()
+    }
+}
+
+impl Assets for Object {
+    fn assets(&self) -> Option<&HashMap<String, Asset>> {
+        Object::assets(self)
+    }
+
+    fn assets_mut(&mut self) -> Option<&mut HashMap<String, Asset>> {
+        Object::assets_mut(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Asset;
+    use super::{Asset, Assets};
+    use crate::{media_type, Collection, Object};
 
     #[test]
     fn new() {
@@ -82,5 +265,30 @@ mod tests {
         assert!(value.get("description").is_none());
         assert!(value.get("type").is_none());
         assert!(value.get("roles").is_none());
+        assert!(value.get("bands").is_none());
+    }
+
+    #[test]
+    fn collection_assets() {
+        let mut collection = Collection::new("an-id");
+        assert!(collection.assets_with_role("data").is_empty());
+        let mut assets = std::collections::HashMap::new();
+        for key in ["b", "a"] {
+            let mut asset = Asset::new(format!("{}.tif", key));
+            asset.roles = Some(vec!["data".to_string(), "overview".to_string()]);
+            asset.r#type = Some(media_type::COG.into());
+            let _ = assets.insert(key.to_string(), asset);
+        }
+        collection.assets = Some(assets);
+        let keys: Vec<_> = collection
+            .assets_with_role("overview")
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(collection.find_asset_by_role("data").unwrap().href, "a.tif");
+
+        let object = Object::from(collection);
+        assert_eq!(object.assets_with_media_type(media_type::COG).len(), 2);
     }
 }
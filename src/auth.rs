@@ -0,0 +1,829 @@
+//! Authenticate requests for STAC objects.
+//!
+//! An [Auth] modifies a [Request] before it is sent, e.g. by adding an
+//! `Authorization` header. Register them on an [HttpClient](crate::HttpClient),
+//! either for every host or for specific hosts, so a crawl that spans several
+//! providers sends each one its own credentials:
+//!
+//! ```
+//! use stac::{auth::{ApiKey, Bearer}, HttpClient, Reader};
+//! let http = HttpClient::new()
+//!     .auth_for_host("stac.example.com", Bearer::new("a-token"))
+//!     .auth_for_host("*.provider.test", ApiKey::query("api_key", "a-key"));
+//! let reader = Reader::default().with_http(http);
+//! ```
+//!
+//! With the `reqwest` feature, [Basic] authenticates with a username and
+//! password, and with the `aws-sigv4` feature, [SigV4] signs requests to AWS
+//! services.
+
+#[cfg(feature = "aws-sigv4")]
+use crate::Error;
+use crate::Result;
+#[cfg(feature = "reqwest")]
+use base64::Engine;
+use std::fmt::Debug;
+use url::Url;
+
+/// Adds credentials to requests.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{auth::{Auth, Request}, Result};
+///
+/// #[derive(Debug)]
+/// struct Cookie(String);
+///
+/// impl Auth for Cookie {
+///     fn apply(&self, request: &mut Request) -> Result<()> {
+///         request.set_header("Cookie", &self.0);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+/// Cookie("session=42".to_string()).apply(&mut request).unwrap();
+/// assert_eq!(request.header("cookie"), Some("session=42"));
+/// ```
+pub trait Auth: Debug + Send + Sync {
+    /// Adds credentials to a request.
+    fn apply(&self, request: &mut Request) -> Result<()>;
+}
+
+/// A request that is about to be sent.
+///
+/// This is a minimal, client-independent view of a request, so [Auth]
+/// implementations don't depend on a specific HTTP library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    method: &'static str,
+    idempotent: bool,
+
+    /// The url, including any query parameters.
+    pub url: Url,
+
+    /// The headers, in the order they were set.
+    pub headers: Vec<(String, String)>,
+
+    /// The body.
+    pub body: Vec<u8>,
+}
+
+/// Authenticates with a bearer token, e.g. an OAuth2 access token.
+///
+/// # Examples
+///
+/// ```
+/// use stac::auth::{Auth, Bearer, Request};
+/// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+/// Bearer::new("a-token").apply(&mut request).unwrap();
+/// assert_eq!(request.header("Authorization"), Some("Bearer a-token"));
+/// ```
+#[derive(Clone)]
+pub struct Bearer {
+    token: String,
+}
+
+/// Authenticates with a username and password.
+///
+/// # Examples
+///
+/// ```
+/// use stac::auth::{Auth, Basic, Request};
+/// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+/// Basic::new("user", Some("password")).apply(&mut request).unwrap();
+/// assert_eq!(request.header("Authorization"), Some("Basic dXNlcjpwYXNzd29yZA=="));
+/// ```
+#[cfg(feature = "reqwest")]
+#[derive(Clone)]
+pub struct Basic {
+    username: String,
+    password: Option<String>,
+}
+
+/// Authenticates with an API key, sent either as a header or as a query parameter.
+///
+/// # Examples
+///
+/// ```
+/// use stac::auth::{ApiKey, Auth, Request};
+/// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+/// ApiKey::header("X-Api-Key", "a-key").apply(&mut request).unwrap();
+/// ApiKey::query("api_key", "a-key").apply(&mut request).unwrap();
+/// assert_eq!(request.header("x-api-key"), Some("a-key"));
+/// assert_eq!(request.url.as_str(), "http://stac.test/catalog.json?api_key=a-key");
+/// ```
+#[derive(Clone)]
+pub struct ApiKey {
+    name: String,
+    value: String,
+    location: ApiKeyLocation,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ApiKeyLocation {
+    Header,
+    Query,
+}
+
+/// Adds a fixed set of headers to every request.
+///
+/// # Examples
+///
+/// ```
+/// use stac::auth::{Auth, Headers, Request};
+/// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+/// Headers::new()
+///     .header("X-Api-Key", "a-key")
+///     .header("X-Tenant", "a-tenant")
+///     .apply(&mut request)
+///     .unwrap();
+/// assert_eq!(request.header("x-tenant"), Some("a-tenant"));
+/// ```
+#[derive(Clone, Default)]
+pub struct Headers {
+    headers: Vec<(String, String)>,
+}
+
+/// Signs requests with [AWS Signature Version 4](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_aws-signing.html).
+///
+/// # Examples
+///
+/// ```
+/// use stac::auth::{Auth, Request, SigV4};
+/// let sigv4 = SigV4::new("AKIDEXAMPLE", "a-secret", "us-west-2", "s3");
+/// let mut request = Request::get("https://bucket.s3.us-west-2.amazonaws.com/catalog.json".parse().unwrap());
+/// sigv4.apply(&mut request).unwrap();
+/// assert!(request.header("Authorization").unwrap().starts_with("AWS4-HMAC-SHA256"));
+/// ```
+#[cfg(feature = "aws-sigv4")]
+#[derive(Clone)]
+pub struct SigV4 {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    region: String,
+    service: String,
+}
+
+impl Request {
+    /// Creates a new `GET` request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+    /// assert_eq!(request.method(), "GET");
+    /// ```
+    pub fn get(url: Url) -> Request {
+        Request {
+            method: "GET",
+            idempotent: true,
+            url,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Creates a new `PATCH` request with this body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let request = Request::patch(
+    ///     "http://stac.test/collections/a/items/b".parse().unwrap(),
+    ///     br#"{"properties": {}}"#.to_vec(),
+    /// );
+    /// assert_eq!(request.method(), "PATCH");
+    /// ```
+    pub fn patch(url: Url, body: Vec<u8>) -> Request {
+        Request {
+            method: "PATCH",
+            idempotent: false,
+            url,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Creates a new `POST` request with this body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let request = Request::post(
+    ///     "http://stac.test/search".parse().unwrap(),
+    ///     br#"{"limit": 10}"#.to_vec(),
+    /// );
+    /// assert_eq!(request.method(), "POST");
+    /// ```
+    pub fn post(url: Url, body: Vec<u8>) -> Request {
+        Request {
+            method: "POST",
+            idempotent: false,
+            url,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Creates a new `PUT` request with this body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let request = Request::put(
+    ///     "http://stac.test/catalog.json".parse().unwrap(),
+    ///     br#"{"type": "Catalog"}"#.to_vec(),
+    /// );
+    /// assert_eq!(request.method(), "PUT");
+    /// ```
+    pub fn put(url: Url, body: Vec<u8>) -> Request {
+        Request {
+            method: "PUT",
+            idempotent: false,
+            url,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Returns this request's method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+    /// assert_eq!(request.method(), "GET");
+    /// ```
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    /// Returns true if sending this request twice is the same as sending it
+    /// once, so it's safe to retry.
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn is_idempotent(&self) -> bool {
+        self.idempotent
+    }
+
+    /// Marks a request as safe to retry, e.g. a `POST` search.
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn set_idempotent(&mut self) {
+        self.idempotent = true;
+    }
+
+    /// Returns the value of a header, ignoring the case of its name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+    /// request.set_header("Accept", "application/json");
+    /// assert_eq!(request.header("accept"), Some("application/json"));
+    /// ```
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a header, replacing any existing values with the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Request;
+    /// let mut request = Request::get("http://stac.test/catalog.json".parse().unwrap());
+    /// request.set_header("Accept", "text/html");
+    /// request.set_header("accept", "application/json");
+    /// assert_eq!(request.headers.len(), 1);
+    /// ```
+    pub fn set_header(&mut self, name: impl ToString, value: impl ToString) {
+        let name = name.to_string();
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.to_string()));
+    }
+}
+
+impl Bearer {
+    /// Creates a new bearer token authentication.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Bearer;
+    /// let bearer = Bearer::new("a-token");
+    /// ```
+    pub fn new(token: impl ToString) -> Bearer {
+        Bearer {
+            token: token.to_string(),
+        }
+    }
+}
+
+impl Debug for Bearer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bearer").finish_non_exhaustive()
+    }
+}
+
+impl Auth for Bearer {
+    fn apply(&self, request: &mut Request) -> Result<()> {
+        request.set_header("Authorization", format!("Bearer {}", self.token));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Basic {
+    /// Creates a new basic authentication.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Basic;
+    /// let basic = Basic::new("user", Some("password"));
+    /// ```
+    pub fn new(username: impl ToString, password: Option<impl ToString>) -> Basic {
+        Basic {
+            username: username.to_string(),
+            password: password.map(|password| password.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Debug for Basic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Basic")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Auth for Basic {
+    fn apply(&self, request: &mut Request) -> Result<()> {
+        let credentials = format!(
+            "{}:{}",
+            self.username,
+            self.password.as_deref().unwrap_or_default()
+        );
+        let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+        request.set_header("Authorization", format!("Basic {}", encoded));
+        Ok(())
+    }
+}
+
+impl ApiKey {
+    /// Sends the key in a header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::ApiKey;
+    /// let api_key = ApiKey::header("X-Api-Key", "a-key");
+    /// ```
+    pub fn header(name: impl ToString, value: impl ToString) -> ApiKey {
+        ApiKey {
+            name: name.to_string(),
+            value: value.to_string(),
+            location: ApiKeyLocation::Header,
+        }
+    }
+
+    /// Sends the key as a query parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::ApiKey;
+    /// let api_key = ApiKey::query("api_key", "a-key");
+    /// ```
+    pub fn query(name: impl ToString, value: impl ToString) -> ApiKey {
+        ApiKey {
+            name: name.to_string(),
+            value: value.to_string(),
+            location: ApiKeyLocation::Query,
+        }
+    }
+}
+
+impl Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("name", &self.name)
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Auth for ApiKey {
+    fn apply(&self, request: &mut Request) -> Result<()> {
+        match self.location {
+            ApiKeyLocation::Header => request.set_header(&self.name, &self.value),
+            ApiKeyLocation::Query => {
+                let pairs: Vec<(String, String)> = request
+                    .url
+                    .query_pairs()
+                    .filter(|(key, _)| key != &self.name)
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect();
+                let _ = request
+                    .url
+                    .query_pairs_mut()
+                    .clear()
+                    .extend_pairs(pairs)
+                    .append_pair(&self.name, &self.value);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Headers {
+    /// Creates an empty set of headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Headers;
+    /// let headers = Headers::new();
+    /// ```
+    pub fn new() -> Headers {
+        Headers::default()
+    }
+
+    /// Adds a header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::Headers;
+    /// let headers = Headers::new().header("X-Api-Key", "a-key");
+    /// ```
+    pub fn header(mut self, name: impl ToString, value: impl ToString) -> Headers {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl Debug for Headers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values are often credentials, so only the names are shown.
+        let names: Vec<_> = self.headers.iter().map(|(name, _)| name).collect();
+        f.debug_struct("Headers")
+            .field("names", &names)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Auth for Headers {
+    fn apply(&self, request: &mut Request) -> Result<()> {
+        for (name, value) in &self.headers {
+            request.set_header(name, value);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "aws-sigv4")]
+impl SigV4 {
+    /// Creates a new signer from an access key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::SigV4;
+    /// let sigv4 = SigV4::new("AKIDEXAMPLE", "a-secret", "us-west-2", "s3");
+    /// ```
+    pub fn new(
+        access_key_id: impl ToString,
+        secret_access_key: impl ToString,
+        region: impl ToString,
+        service: impl ToString,
+    ) -> SigV4 {
+        SigV4 {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: None,
+            region: region.to_string(),
+            service: service.to_string(),
+        }
+    }
+
+    /// Creates a new signer from the `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY`, and (optional) `AWS_SESSION_TOKEN`
+    /// environment variables.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::auth::SigV4;
+    /// let sigv4 = SigV4::from_env("us-west-2", "s3").unwrap();
+    /// ```
+    pub fn from_env(region: impl ToString, service: impl ToString) -> Result<SigV4> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| Error::Auth(format!("{} is not set", name)))
+        };
+        let sigv4 = SigV4::new(
+            var("AWS_ACCESS_KEY_ID")?,
+            var("AWS_SECRET_ACCESS_KEY")?,
+            region,
+            service,
+        );
+        if let Ok(session_token) = std::env::var("AWS_SESSION_TOKEN") {
+            Ok(sigv4.session_token(session_token))
+        } else {
+            Ok(sigv4)
+        }
+    }
+
+    /// Sets the session token for temporary credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::auth::SigV4;
+    /// let sigv4 = SigV4::new("AKIDEXAMPLE", "a-secret", "us-west-2", "s3").session_token("a-token");
+    /// ```
+    pub fn session_token(mut self, session_token: impl ToString) -> SigV4 {
+        self.session_token = Some(session_token.to_string());
+        self
+    }
+
+    fn sign(&self, request: &mut Request, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        use hmac::{Hmac, Mac};
+        use sha2::{Digest, Sha256};
+
+        fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+            mac.update(data.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        let host = request
+            .url
+            .host_str()
+            .ok_or_else(|| Error::Auth(format!("url has no host: {}", request.url)))?;
+        let host = match request.url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let datetime = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(&Sha256::digest(&request.body));
+
+        request.set_header("host", &host);
+        request.set_header("x-amz-date", &datetime);
+        if self.service == "s3" {
+            request.set_header("x-amz-content-sha256", &payload_hash);
+        }
+        if let Some(session_token) = self.session_token.as_ref() {
+            request.set_header("x-amz-security-token", session_token);
+        }
+        let mut headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .filter(|(key, _)| {
+                let key = key.to_ascii_lowercase();
+                key == "host" || key.starts_with("x-amz-")
+            })
+            .map(|(key, value)| (key.to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        headers.sort();
+        let signed_headers = headers
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(key, value)| format!("{}:{}\n", key, value))
+            .collect();
+
+        let mut query: Vec<(String, String)> = request
+            .url
+            .query_pairs()
+            .map(|(key, value)| (uri_encode(&key), uri_encode(&value)))
+            .collect();
+        query.sort();
+        let canonical_query = query
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let path = match request.url.path() {
+            "" => "/",
+            path => path,
+        };
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method, path, canonical_query, canonical_headers, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            datetime,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = hmac(format!("AWS4{}", self.secret_access_key).as_bytes(), &date);
+        let key = hmac(&key, &self.region);
+        let key = hmac(&key, &self.service);
+        let key = hmac(&key, "aws4_request");
+        let signature = hex(&hmac(&key, &string_to_sign));
+        request.set_header(
+            "Authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key_id, scope, signed_headers, signature
+            ),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "aws-sigv4")]
+impl Debug for SigV4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigV4")
+            .field("access_key_id", &self.access_key_id)
+            .field("region", &self.region)
+            .field("service", &self.service)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "aws-sigv4")]
+impl Auth for SigV4 {
+    fn apply(&self, request: &mut Request) -> Result<()> {
+        self.sign(request, chrono::Utc::now())
+    }
+}
+
+#[cfg(feature = "aws-sigv4")]
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(feature = "aws-sigv4")]
+fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Returns true if a host matches a pattern, either exactly or, for patterns
+/// that start with `*.`, as a subdomain.
+pub(crate) fn host_matches(pattern: &str, host: &str) -> bool {
+    if let Some(domain) = pattern.strip_prefix("*.") {
+        host.len() > domain.len()
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+    } else {
+        pattern.eq_ignore_ascii_case(host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiKey, Auth, Bearer, Headers, Request};
+
+    fn request(url: &str) -> Request {
+        Request::get(url.parse().unwrap())
+    }
+
+    #[test]
+    fn bearer_replaces_authorization() {
+        let mut request = request("http://stac.test/catalog.json");
+        request.set_header("authorization", "Basic old");
+        Bearer::new("a-token").apply(&mut request).unwrap();
+        assert_eq!(
+            request.headers,
+            vec![("Authorization".to_string(), "Bearer a-token".to_string())]
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn basic_without_password() {
+        let mut request = request("http://stac.test/catalog.json");
+        super::Basic::new("user", None::<String>)
+            .apply(&mut request)
+            .unwrap();
+        assert_eq!(request.header("Authorization"), Some("Basic dXNlcjo="));
+    }
+
+    #[test]
+    fn api_key_query_replaces() {
+        let mut request = request("http://stac.test/search?limit=10&api_key=old");
+        ApiKey::query("api_key", "a key")
+            .apply(&mut request)
+            .unwrap();
+        assert_eq!(
+            request.url.as_str(),
+            "http://stac.test/search?limit=10&api_key=a+key"
+        );
+    }
+
+    #[test]
+    fn host_matches() {
+        assert!(super::host_matches("stac.test", "STAC.test"));
+        assert!(!super::host_matches("stac.test", "api.stac.test"));
+        assert!(super::host_matches("*.stac.test", "api.stac.test"));
+        assert!(!super::host_matches("*.stac.test", "stac.test"));
+        assert!(!super::host_matches("*.stac.test", "notstac.test"));
+    }
+
+    #[cfg(feature = "aws-sigv4")]
+    mod sigv4 {
+        use super::request;
+        use crate::auth::SigV4;
+        use chrono::{TimeZone, Utc};
+
+        fn sigv4() -> SigV4 {
+            SigV4::new(
+                "AKIDEXAMPLE",
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "us-east-1",
+                "service",
+            )
+        }
+
+        #[test]
+        fn get_vanilla() {
+            // From the AWS Signature Version 4 test suite.
+            let mut request = request("https://example.amazonaws.com/");
+            let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+            sigv4().sign(&mut request, now).unwrap();
+            assert_eq!(
+                request.header("Authorization").unwrap(),
+                "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+            );
+        }
+
+        #[test]
+        fn get_vanilla_query_order() {
+            // From the AWS Signature Version 4 test suite.
+            let mut request = request("https://example.amazonaws.com/?Param2=value2&Param1=value1");
+            let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+            sigv4().sign(&mut request, now).unwrap();
+            assert!(request.header("Authorization").unwrap().ends_with(
+                "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+            ));
+        }
+
+        #[test]
+        fn session_token() {
+            let mut request = request("https://bucket.s3.amazonaws.com/catalog.json");
+            let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+            sigv4()
+                .session_token("a-token")
+                .sign(&mut request, now)
+                .unwrap();
+            assert_eq!(request.header("x-amz-security-token"), Some("a-token"));
+            assert!(request
+                .header("Authorization")
+                .unwrap()
+                .contains("SignedHeaders=host;x-amz-date;x-amz-security-token,"));
+        }
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        for debug in [
+            format!("{:?}", Bearer::new("a-secret")),
+            format!("{:?}", ApiKey::header("X-Api-Key", "a-secret")),
+            format!("{:?}", ApiKey::query("api_key", "a-secret")),
+            format!("{:?}", Headers::new().header("X-Api-Key", "a-secret")),
+        ] {
+            assert!(!debug.contains("a-secret"), "{}", debug);
+        }
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn basic_debug_redacts_password() {
+        let debug = format!("{:?}", super::Basic::new("user", Some("a-secret")));
+        assert!(!debug.contains("a-secret"), "{}", debug);
+        assert!(debug.contains("user"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Bands, as unified in STAC 1.1.
+//!
+//! STAC 1.0 describes bands twice: spectral information in `eo:bands`, and
+//! pixel information in `raster:bands`, as two parallel arrays. STAC 1.1
+//! merges them into a single `bands` array, where extension fields keep
+//! their prefix, e.g. `eo:common_name` and `raster:scale`. Fields that both
+//! versions share, like `nodata` and `data_type`, are unprefixed.
+//!
+//! [Asset::merge_bands](crate::Asset::merge_bands) and
+//! [Asset::split_bands](crate::Asset::split_bands) (and their [Item](crate::Item)
+//! counterparts) convert between the two layouts, and writers with a target
+//! `version` convert automatically, so a catalog can be authored once and
+//! written for either version.
+//!
+//! A [Composite] names a combination of bands by common name, and
+//! [Item::select_bands](crate::Item::select_bands) finds the assets that hold
+//! them, so processing code doesn't need to know each mission's asset keys.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Fields of `eo:bands` entries, and their names in unified bands.
+const EO_FIELDS: [(&str, &str); 4] = [
+    ("common_name", "eo:common_name"),
+    ("center_wavelength", "eo:center_wavelength"),
+    ("full_width_half_max", "eo:full_width_half_max"),
+    ("solar_illumination", "eo:solar_illumination"),
+];
+
+/// Fields of `raster:bands` entries, and their names in unified bands.
+const RASTER_FIELDS: [(&str, &str); 6] = [
+    ("sampling", "raster:sampling"),
+    ("bits_per_sample", "raster:bits_per_sample"),
+    ("spatial_resolution", "raster:spatial_resolution"),
+    ("scale", "raster:scale"),
+    ("offset", "raster:offset"),
+    ("histogram", "raster:histogram"),
+];
+
+/// Unprefixed fields that come from `eo:bands` entries.
+const EO_SHARED_FIELDS: [&str; 2] = ["name", "description"];
+
+/// A band in a unified, STAC 1.1-style `bands` array.
+///
+/// # Examples
+///
+/// ```
+/// use stac::Band;
+/// let band = Band::new("B04").common_name("red");
+/// assert_eq!(band.name.as_deref(), Some("B04"));
+/// assert_eq!(band.additional_fields["eo:common_name"], "red");
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Band {
+    /// The name of the band, e.g. `B01` or `red`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A description of the band.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The value used for missing data, either a number or one of `"nan"`,
+    /// `"inf"`, and `"-inf"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodata: Option<Value>,
+
+    /// The data type of the band's values, e.g. `uint16`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+
+    /// Statistics of the band's values, e.g. `minimum` and `maximum`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<Map<String, Value>>,
+
+    /// The unit of the band's values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+
+    /// Additional fields, e.g. `eo:common_name` or `raster:scale`.
+    #[serde(flatten)]
+    pub additional_fields: Map<String, Value>,
+}
+
+impl Band {
+    /// Creates a new band with a name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Band;
+    /// let band = Band::new("B01");
+    /// ```
+    pub fn new(name: impl ToString) -> Band {
+        Band {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets this band's `eo:common_name`, e.g. `red` or `nir`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Band;
+    /// let band = Band::new("B04").common_name("red");
+    /// assert_eq!(band.get_common_name(), Some("red"));
+    /// ```
+    pub fn common_name(mut self, common_name: impl ToString) -> Band {
+        let _ = self
+            .additional_fields
+            .insert("eo:common_name".to_string(), common_name.to_string().into());
+        self
+    }
+
+    /// Returns this band's `eo:common_name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Band;
+    /// assert_eq!(Band::new("B04").get_common_name(), None);
+    /// ```
+    pub fn get_common_name(&self) -> Option<&str> {
+        self.additional_fields
+            .get("eo:common_name")
+            .and_then(Value::as_str)
+    }
+}
+
+/// A named combination of bands, by their `eo:common_name`.
+///
+/// # Examples
+///
+/// ```
+/// use stac::Composite;
+/// let composite = Composite::new("swir", ["swir22", "swir16", "red"]);
+/// assert_eq!(composite.common_names, ["swir22", "swir16", "red"]);
+/// assert_eq!(Composite::ndvi().common_names, ["nir", "red"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Composite {
+    /// The name of the composite, e.g. `visual`.
+    pub name: String,
+
+    /// The common names of the bands, in order.
+    pub common_names: Vec<String>,
+}
+
+/// A band of a [Composite], and the asset that holds it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectedBand {
+    /// The common name of the band.
+    pub common_name: String,
+
+    /// The key of the asset.
+    pub key: String,
+
+    /// The href of the asset.
+    pub href: String,
+
+    /// The position of the band in the asset's bands.
+    pub index: usize,
+}
+
+impl Composite {
+    /// Creates a new composite from common names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Composite;
+    /// let composite = Composite::new("coastal", ["coastal", "blue"]);
+    /// ```
+    pub fn new(
+        name: impl ToString,
+        common_names: impl IntoIterator<Item = impl ToString>,
+    ) -> Composite {
+        Composite {
+            name: name.to_string(),
+            common_names: common_names
+                .into_iter()
+                .map(|common_name| common_name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Red, green, and blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Composite;
+    /// assert_eq!(Composite::visual().common_names, ["red", "green", "blue"]);
+    /// ```
+    pub fn visual() -> Composite {
+        Composite::new("visual", ["red", "green", "blue"])
+    }
+
+    /// Near infrared, red, and green, which shows vegetation as red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Composite;
+    /// assert_eq!(Composite::false_color().common_names, ["nir", "red", "green"]);
+    /// ```
+    pub fn false_color() -> Composite {
+        Composite::new("false-color", ["nir", "red", "green"])
+    }
+
+    /// Near infrared and red, the inputs to NDVI, `(nir - red) / (nir + red)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Composite;
+    /// assert_eq!(Composite::ndvi().common_names, ["nir", "red"]);
+    /// ```
+    pub fn ndvi() -> Composite {
+        Composite::new("ndvi", ["nir", "red"])
+    }
+}
+
+/// Returns the common name of each band, from unified `bands` or from the `eo:bands` array in `fields`.
+pub(crate) fn common_names<'a>(
+    fields: &'a Map<String, Value>,
+    bands: &'a Option<Vec<Band>>,
+) -> Vec<Option<&'a str>> {
+    if let Some(bands) = bands {
+        bands.iter().map(Band::get_common_name).collect()
+    } else {
+        fields
+            .get("eo:bands")
+            .and_then(Value::as_array)
+            .map(|bands| {
+                bands
+                    .iter()
+                    .map(|band| band.get("common_name").and_then(Value::as_str))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Moves the `eo:bands` and `raster:bands` arrays in `fields` into unified `bands`.
+///
+/// Does nothing if `bands` are already set.
+pub(crate) fn merge(fields: &mut Map<String, Value>, bands: &mut Option<Vec<Band>>) -> Result<()> {
+    if bands.is_some() {
+        return Ok(());
+    }
+    let eo = take_array(fields, "eo:bands")?;
+    let raster = take_array(fields, "raster:bands")?;
+    if eo.is_none() && raster.is_none() {
+        return Ok(());
+    }
+    let eo = eo.unwrap_or_default();
+    let raster = raster.unwrap_or_default();
+    let mut merged = Vec::with_capacity(eo.len().max(raster.len()));
+    for i in 0..eo.len().max(raster.len()) {
+        let mut band = Map::new();
+        for (entry, renames) in [
+            (eo.get(i), &EO_FIELDS[..]),
+            (raster.get(i), &RASTER_FIELDS[..]),
+        ] {
+            let Some(entry) = entry else {
+                continue;
+            };
+            let Value::Object(entry) = entry else {
+                return Err(Error::InvalidMetadata(format!(
+                    "band is not an object: {}",
+                    entry
+                )));
+            };
+            for (key, value) in entry {
+                let key = renames
+                    .iter()
+                    .find(|(legacy, _)| legacy == key)
+                    .map_or(key.as_str(), |(_, unified)| unified);
+                if !band.contains_key(key) {
+                    let _ = band.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+        merged.push(serde_json::from_value(Value::Object(band))?);
+    }
+    *bands = Some(merged);
+    Ok(())
+}
+
+/// Moves unified `bands` into `eo:bands` and `raster:bands` arrays in `fields`.
+///
+/// Each array is only written if at least one band has one of its fields.
+/// Fields that belong to neither extension are kept on the `raster:bands`
+/// entries.
+pub(crate) fn split(fields: &mut Map<String, Value>, bands: &mut Option<Vec<Band>>) -> Result<()> {
+    let Some(unified) = bands.take() else {
+        return Ok(());
+    };
+    let mut eo = Vec::with_capacity(unified.len());
+    let mut raster = Vec::with_capacity(unified.len());
+    for band in unified {
+        let Value::Object(band) = serde_json::to_value(band)? else {
+            unreachable!("bands serialize to objects");
+        };
+        let mut eo_entry = Map::new();
+        let mut raster_entry = Map::new();
+        for (key, value) in band {
+            if let Some((legacy, _)) = EO_FIELDS.iter().find(|(_, unified)| *unified == key) {
+                let _ = eo_entry.insert(legacy.to_string(), value);
+            } else if EO_SHARED_FIELDS.contains(&key.as_str()) {
+                let _ = eo_entry.insert(key, value);
+            } else if let Some((legacy, _)) =
+                RASTER_FIELDS.iter().find(|(_, unified)| *unified == key)
+            {
+                let _ = raster_entry.insert(legacy.to_string(), value);
+            } else {
+                let _ = raster_entry.insert(key, value);
+            }
+        }
+        eo.push(Value::Object(eo_entry));
+        raster.push(Value::Object(raster_entry));
+    }
+    for (key, entries) in [("eo:bands", eo), ("raster:bands", raster)] {
+        if entries
+            .iter()
+            .any(|entry| entry.as_object().is_some_and(|entry| !entry.is_empty()))
+        {
+            let _ = fields.insert(key.to_string(), Value::Array(entries));
+        }
+    }
+    Ok(())
+}
+
+fn take_array(fields: &mut Map<String, Value>, key: &str) -> Result<Option<Vec<Value>>> {
+    match fields.remove(key) {
+        Some(Value::Array(array)) => Ok(Some(array)),
+        Some(value) => Err(Error::InvalidMetadata(format!(
+            "{} is not an array: {}",
+            key, value
+        ))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, split};
+    use serde_json::{json, Map, Value};
+
+    #[test]
+    fn round_trip() {
+        let legacy = json!({
+            "eo:bands": [
+                {"name": "B04", "common_name": "red", "center_wavelength": 0.665},
+                {"name": "B08", "common_name": "nir"}
+            ],
+            "raster:bands": [
+                {"nodata": 0, "data_type": "uint16", "scale": 0.0001},
+                {"nodata": 0, "data_type": "uint16", "unit": "m"}
+            ]
+        });
+        let Value::Object(mut fields) = legacy.clone() else {
+            unreachable!()
+        };
+        let mut bands = None;
+        merge(&mut fields, &mut bands).unwrap();
+        assert!(fields.is_empty());
+        let merged = bands.as_ref().unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].name.as_deref(), Some("B04"));
+        assert_eq!(merged[0].get_common_name(), Some("red"));
+        assert_eq!(merged[0].additional_fields["raster:scale"], 0.0001);
+        assert_eq!(merged[0].data_type.as_deref(), Some("uint16"));
+        assert_eq!(merged[1].unit.as_deref(), Some("m"));
+
+        split(&mut fields, &mut bands).unwrap();
+        assert!(bands.is_none());
+        assert_eq!(Value::Object(fields), legacy);
+    }
+
+    #[test]
+    fn split_eo_only() {
+        let mut fields = Map::new();
+        let mut bands = Some(vec![super::Band::new("B01").common_name("coastal")]);
+        split(&mut fields, &mut bands).unwrap();
+        assert_eq!(
+            Value::Object(fields),
+            json!({"eo:bands": [{"name": "B01", "common_name": "coastal"}]})
+        );
+    }
+
+    #[test]
+    fn merge_invalid() {
+        let Value::Object(mut fields) = json!({"eo:bands": {"name": "B01"}}) else {
+            unreachable!()
+        };
+        assert!(merge(&mut fields, &mut None).is_err());
+    }
+}
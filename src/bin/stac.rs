@@ -0,0 +1,10 @@
+use clap::Parser;
+use stac::cli::Args;
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = Args::parse().run().await {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Error, Result};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A handle for cooperatively cancelling long-running operations.
+///
+/// Clones share state, so you can hand a clone to a walk or a
+/// [Layout](crate::Layout) and cancel it from somewhere else, e.g. when a
+/// server's client disconnects. Cancellation is checked between objects, and a
+/// cancelled operation returns [Error::Cancelled].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{CancellationToken, Stac, Walk};
+/// let token = CancellationToken::new();
+/// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+/// let mut walk = stac.walk(root).cancel_on(token.clone());
+/// assert!(walk.next().unwrap().is_ok());
+/// token.cancel();
+/// assert!(walk.next().unwrap().is_err());
+/// assert!(walk.next().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new, uncancelled token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// assert!(!token.is_cancelled());
+    /// ```
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels this token and all of its clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// let clone = token.clone();
+    /// token.cancel();
+    /// assert!(clone.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns [Error::Cancelled] if this token has been cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// token.check().unwrap();
+    /// token.cancel();
+    /// token.check().unwrap_err();
+    /// ```
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
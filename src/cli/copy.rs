@@ -0,0 +1,267 @@
+use crate::{
+    id,
+    layout::{Flat, Rebase, Strategy},
+    Asset, Handle, Href, Layout, NdjsonWriter, Object, Reader, Result, Stac, Version, Writer,
+    STDOUT,
+};
+use clap::{Args, ValueEnum};
+use path_slash::PathBufExt;
+use std::{collections::HashSet, path::PathBuf};
+
+/// Arguments for `stac copy`, which copies a catalog to a new location.
+///
+/// The catalog is read, optionally migrated to a new STAC version, laid out
+/// under the destination directory, and written. Relative asset hrefs are
+/// rewritten so they still point at the original files, unless the assets
+/// are copied too.
+#[derive(Debug, Args)]
+pub struct Copy {
+    /// The href of the root catalog, or a directory with a `catalog.json`.
+    pub src: String,
+
+    /// The directory to write the catalog to, or `-` to write it to standard
+    /// output as newline-delimited JSON.
+    pub dst: String,
+
+    /// How to lay out the copied catalog.
+    #[arg(long, value_enum, default_value_t = LayoutKind::BestPractices)]
+    pub layout: LayoutKind,
+
+    /// Copy local asset files next to their copied items and collections.
+    ///
+    /// Ignored when writing to standard output.
+    #[arg(long)]
+    pub copy_assets: bool,
+
+    /// Migrate every object to this STAC version, e.g. `1.1`.
+    #[arg(long, value_parser = parse_version)]
+    pub target_version: Option<Version>,
+
+    /// Sort links and assets so that regenerated catalogs diff cleanly.
+    #[arg(long)]
+    pub sort: bool,
+}
+
+/// The [layout strategies](crate::layout::Strategy) available to `stac copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LayoutKind {
+    /// [BestPractices](crate::layout::BestPractices), the default.
+    BestPractices,
+
+    /// [Rebase], which keeps the catalog's existing structure.
+    Rebase,
+
+    /// [Flat], which puts every object in the destination directory.
+    Flat,
+}
+
+impl Copy {
+    /// Copies the catalog.
+    ///
+    /// Migration sets each object's `stac_version` and upgrades its
+    /// `stac_extensions` to their newest known compatible versions.
+    ///
+    /// When assets are copied, each file goes into its object's new
+    /// directory. If two assets would land on the same file, e.g. with the
+    /// [Flat] layout, the later one is prefixed with its object's id. Only
+    /// local assets are copied; remote hrefs are left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Copy(copy) = Args::parse_from(["stac", "copy", "data", "out", "--layout", "flat"]).command else {
+    ///     unreachable!()
+    /// };
+    /// copy.run().unwrap();
+    /// ```
+    pub fn run(self) -> Result<()> {
+        let (mut stac, root) = super::read(&self.src)?;
+        let mut hrefs = Vec::new();
+        for result in stac.walk(root).visit(|stac, handle| {
+            let _ = stac.get(handle)?;
+            Ok((handle, stac.href(handle).cloned()))
+        }) {
+            hrefs.push(result?);
+        }
+        if self.target_version.is_some() {
+            for (handle, _) in &hrefs {
+                stac.get_mut(*handle)?.upgrade_extensions();
+            }
+        }
+        // Objects written to standard output are laid out as if they were
+        // written to the working directory.
+        let root = if self.dst == STDOUT {
+            "."
+        } else {
+            self.dst.as_str()
+        };
+        match self.layout {
+            LayoutKind::BestPractices => self.write(stac, hrefs, Layout::new(root)),
+            LayoutKind::Rebase => self.write(
+                stac,
+                hrefs,
+                Layout::new(root).with_strategy(Rebase::default()),
+            ),
+            LayoutKind::Flat => self.write(stac, hrefs, Layout::new(root).with_strategy(Flat)),
+        }
+    }
+
+    fn write<S: Strategy>(
+        &self,
+        mut stac: Stac<Reader>,
+        hrefs: Vec<(Handle, Option<Href>)>,
+        mut layout: Layout<S>,
+    ) -> Result<()> {
+        layout.layout(&mut stac)?;
+        let mut copied = HashSet::new();
+        for (handle, old_href) in hrefs {
+            let (Some(old_href), Some(new_href)) = (old_href, stac.href(handle).cloned()) else {
+                continue;
+            };
+            let object = stac.get_mut(handle)?;
+            let id = id::slugify(object.id());
+            let assets: Vec<&mut Asset> = match object {
+                Object::Item(item) => item.assets.values_mut().collect(),
+                Object::Collection(collection) => collection
+                    .assets
+                    .iter_mut()
+                    .flat_map(|assets| assets.values_mut())
+                    .collect(),
+                Object::Catalog(_) => Vec::new(),
+            };
+            for asset in assets {
+                let href = old_href.join(asset.href.as_str())?;
+                if self.copy_assets && href.is_path() && self.dst != STDOUT {
+                    let mut file_name = href.file_name().to_string();
+                    if !copied.insert(new_href.join(file_name.as_str())?.to_string()) {
+                        file_name = format!("{}-{}", id, file_name);
+                        let _ = copied.insert(new_href.join(file_name.as_str())?.to_string());
+                    }
+                    let dst = PathBuf::from_slash(new_href.join(file_name.as_str())?.as_str());
+                    if let Some(parent) = dst.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    let _ = std::fs::copy(PathBuf::from_slash(href.as_str()), dst)?;
+                    asset.href = format!("./{}", file_name);
+                } else if href.is_absolute() {
+                    asset.href = href.to_string();
+                } else {
+                    asset.href = new_href.make_relative(&href).to_string();
+                }
+            }
+        }
+        if self.dst == STDOUT {
+            let mut writer = NdjsonWriter::stdout();
+            writer.version = self.target_version.clone();
+            writer.sort = self.sort;
+            return stac.write(&mut layout, &writer);
+        }
+        let writer = Writer {
+            version: self.target_version.clone(),
+            sort: self.sort,
+            ..Default::default()
+        };
+        stac.write(&mut layout, &writer)
+    }
+}
+
+/// Parses a STAC version, allowing the patch version to be left off.
+fn parse_version(s: &str) -> Result<Version> {
+    if s.split('.').count() == 2 {
+        format!("{}.0", s).parse()
+    } else {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Copy, LayoutKind};
+    use crate::{Asset, Catalog, Collection, Item, Layout, Stac, Writer};
+    use std::path::Path;
+
+    fn write_src(dir: &Path) {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut collection = Collection::new("a-collection");
+        collection.assets = Some([("mosaic".to_string(), Asset::new("./mosaic.txt"))].into());
+        let collection = stac.add_child(root, collection).unwrap();
+        for id in ["item-a", "item-b"] {
+            let mut item = Item::new(id);
+            let _ = item
+                .assets
+                .insert("data".to_string(), Asset::new("./data.txt"));
+            let _ = stac.add_child(collection, item).unwrap();
+        }
+        stac.write(
+            &mut Layout::new(dir.join("src").to_str().unwrap()),
+            &Writer::default(),
+        )
+        .unwrap();
+        for id in ["item-a", "item-b"] {
+            std::fs::write(dir.join("src/a-collection").join(id).join("data.txt"), id).unwrap();
+        }
+        std::fs::write(dir.join("src/a-collection/mosaic.txt"), "mosaic").unwrap();
+    }
+
+    fn read_item(path: &Path) -> Item {
+        serde_json::from_reader(std::fs::File::open(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn flat_with_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        write_src(dir.path());
+        Copy {
+            src: dir.path().join("src").to_string_lossy().into_owned(),
+            dst: dir.path().join("dst").to_string_lossy().into_owned(),
+            layout: LayoutKind::Flat,
+            copy_assets: true,
+            target_version: Some(super::parse_version("1.1").unwrap()),
+            sort: true,
+        }
+        .run()
+        .unwrap();
+        let dst = dir.path().join("dst");
+        let a = read_item(&dst.join("item-a.json"));
+        let b = read_item(&dst.join("item-b.json"));
+        assert_eq!(a.version, "1.1.0");
+        assert_eq!(a.assets["data"].href, "./data.txt");
+        assert_eq!(b.assets["data"].href, "./item-b-data.txt");
+        assert_eq!(
+            std::fs::read_to_string(dst.join("item-b-data.txt")).unwrap(),
+            "item-b"
+        );
+    }
+
+    #[test]
+    fn asset_hrefs_point_at_originals() {
+        let dir = tempfile::tempdir().unwrap();
+        write_src(dir.path());
+        Copy {
+            src: dir.path().join("src").to_string_lossy().into_owned(),
+            dst: dir.path().join("dst").to_string_lossy().into_owned(),
+            layout: LayoutKind::BestPractices,
+            copy_assets: false,
+            target_version: None,
+            sort: false,
+        }
+        .run()
+        .unwrap();
+        let item = read_item(&dir.path().join("dst/a-collection/item-a/item-a.json"));
+        assert_eq!(
+            Path::new(&item.assets["data"].href),
+            dir.path().join("src/a-collection/item-a/data.txt")
+        );
+        assert!(!dir.path().join("dst/a-collection/item-a/data.txt").exists());
+        let collection: Collection = serde_json::from_reader(
+            std::fs::File::open(dir.path().join("dst/a-collection/collection.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            Path::new(&collection.assets.unwrap()["mosaic"].href),
+            dir.path().join("src/a-collection/mosaic.txt")
+        );
+    }
+}
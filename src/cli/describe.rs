@@ -0,0 +1,191 @@
+use crate::{Handle, Object, Read, Result, Stac};
+use clap::Args;
+use serde::Serialize;
+use std::{
+    fmt::{Display, Formatter},
+    io::Write,
+};
+
+/// Arguments for `stac describe`, which prints a summary of a catalog.
+#[derive(Debug, Args)]
+pub struct Describe {
+    /// The href of the root catalog, or a directory with a `catalog.json`.
+    pub href: String,
+
+    /// Print the description as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The tree and statistics of a catalog, as printed by `stac describe`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Description {
+    /// The catalogs and collections, with the number of items directly below each.
+    pub tree: Tree,
+
+    /// Object counts for the whole catalog.
+    pub statistics: Statistics,
+}
+
+/// A catalog or collection in a [Description].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tree {
+    /// The object's id.
+    pub id: String,
+
+    /// The object's type, e.g. `Collection`.
+    pub r#type: String,
+
+    /// The number of items directly below this object.
+    pub items: usize,
+
+    /// The catalogs and collections directly below this object.
+    pub children: Vec<Tree>,
+}
+
+/// Object counts in a [Description].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Statistics {
+    /// The number of catalogs, including the root if it's a catalog.
+    pub catalogs: usize,
+
+    /// The number of collections, including the root if it's a collection.
+    pub collections: usize,
+
+    /// The number of items.
+    pub items: usize,
+
+    /// The number of assets on items and collections.
+    pub assets: usize,
+}
+
+impl Describe {
+    /// Describes the catalog and prints the description to standard output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Describe(describe) = Args::parse_from(["stac", "describe", "data"]).command else {
+    ///     unreachable!()
+    /// };
+    /// describe.run().unwrap();
+    /// ```
+    pub fn run(self) -> Result<()> {
+        let description = self.describe()?;
+        let mut stdout = std::io::stdout().lock();
+        if self.json {
+            serde_json::to_writer_pretty(&mut stdout, &description)?;
+            writeln!(stdout)?;
+        } else {
+            write!(stdout, "{}", description)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the whole catalog and describes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Describe(describe) = Args::parse_from(["stac", "describe", "data"]).command else {
+    ///     unreachable!()
+    /// };
+    /// let description = describe.describe().unwrap();
+    /// assert_eq!(description.statistics.items, 2);
+    /// ```
+    pub fn describe(&self) -> Result<Description> {
+        let (mut stac, root) = super::read(&self.href)?;
+        let mut statistics = Statistics::default();
+        let tree = tree(&mut stac, root, &mut statistics)?;
+        Ok(Description { tree, statistics })
+    }
+}
+
+fn tree<R: Read>(stac: &mut Stac<R>, handle: Handle, statistics: &mut Statistics) -> Result<Tree> {
+    let object = stac.get(handle)?;
+    let mut tree = Tree {
+        id: object.id().to_string(),
+        r#type: object.r#type().to_string(),
+        items: 0,
+        children: Vec::new(),
+    };
+    match object {
+        Object::Catalog(_) => statistics.catalogs += 1,
+        Object::Collection(collection) => {
+            statistics.collections += 1;
+            statistics.assets += collection.assets.as_ref().map_or(0, |assets| assets.len());
+        }
+        Object::Item(_) => unreachable!("items aren't described as trees"),
+    }
+    for child in stac.children(handle) {
+        if let Object::Item(item) = stac.get(child)? {
+            tree.items += 1;
+            statistics.items += 1;
+            statistics.assets += item.assets.len();
+        } else {
+            tree.children.push(self::tree(stac, child, statistics)?);
+        }
+    }
+    Ok(tree)
+}
+
+impl Display for Description {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.tree.fmt_with_prefix(f, "", "")?;
+        writeln!(f)?;
+        writeln!(f, "catalogs: {}", self.statistics.catalogs)?;
+        writeln!(f, "collections: {}", self.statistics.collections)?;
+        writeln!(f, "items: {}", self.statistics.items)?;
+        writeln!(f, "assets: {}", self.statistics.assets)
+    }
+}
+
+impl Tree {
+    fn fmt_with_prefix(&self, f: &mut Formatter<'_>, first: &str, rest: &str) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}{} ({}, {} item{})",
+            first,
+            self.id,
+            self.r#type,
+            self.items,
+            if self.items == 1 { "" } else { "s" }
+        )?;
+        for (i, child) in self.children.iter().enumerate() {
+            if i + 1 == self.children.len() {
+                child.fmt_with_prefix(f, &format!("{}└── ", rest), &format!("{}    ", rest))?;
+            } else {
+                child.fmt_with_prefix(f, &format!("{}├── ", rest), &format!("{}│   ", rest))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Describe;
+
+    #[test]
+    fn describe() {
+        let description = Describe {
+            href: "data".to_string(),
+            json: false,
+        }
+        .describe()
+        .unwrap();
+        assert_eq!(description.tree.id, "examples");
+        assert_eq!(description.tree.children.len(), 3);
+        assert_eq!(description.statistics.catalogs, 1);
+        assert_eq!(description.statistics.collections, 3);
+        let text = description.to_string();
+        assert!(text.starts_with("examples (Catalog, 1 item)\n├── "));
+        assert!(text.ends_with("items: 2\nassets: 8\n"), "{}", text);
+        let value = serde_json::to_value(&description).unwrap();
+        assert_eq!(value["tree"]["type"], "Catalog");
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{Error, Result};
+use clap::Args;
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Map, Serializer, Value};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The order of the top-level keys of catalogs, collections, and items.
+///
+/// Other keys come after these, sorted.
+const KEY_ORDER: [&str; 18] = [
+    "type",
+    "stac_version",
+    "stac_extensions",
+    "id",
+    "title",
+    "description",
+    "keywords",
+    "license",
+    "providers",
+    "extent",
+    "summaries",
+    "geometry",
+    "bbox",
+    "properties",
+    "links",
+    "assets",
+    "item_assets",
+    "collection",
+];
+
+/// The order of the keys of links.
+const LINK_KEY_ORDER: [&str; 4] = ["rel", "href", "type", "title"];
+
+/// The order of the keys of assets.
+const ASSET_KEY_ORDER: [&str; 5] = ["href", "title", "description", "type", "roles"];
+
+/// Links with these rels come first, in this order.
+///
+/// Other links are sorted by rel, and links with the same rel by href.
+const REL_ORDER: [&str; 6] = ["self", "root", "parent", "collection", "child", "item"];
+
+/// Arguments for `stac fmt`, which rewrites STAC JSON files in a canonical form.
+///
+/// Top-level keys are written in the order of the STAC specification, other
+/// keys are sorted, and links are sorted by rel and href, so regenerated
+/// catalogs diff cleanly. JSON files that aren't catalogs, collections, or
+/// items are left alone.
+#[derive(Debug, Args)]
+pub struct Fmt {
+    /// A STAC JSON file, a directory to search for STAC JSON files, or `-` to
+    /// format standard input to standard output.
+    pub href: String,
+
+    /// The number of spaces to indent with, or zero to write compact JSON.
+    #[arg(long, default_value_t = 2)]
+    pub indent: usize,
+
+    /// Don't write anything, but fail if any file isn't formatted.
+    #[arg(long)]
+    pub check: bool,
+}
+
+impl Fmt {
+    /// Formats the files in place, or checks them.
+    ///
+    /// Returns [Error::NotFormatted] with the unformatted paths if checking.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Fmt(fmt) = Args::parse_from(["stac", "fmt", "data", "--check"]).command else {
+    ///     unreachable!()
+    /// };
+    /// fmt.run().unwrap();
+    /// ```
+    pub fn run(self) -> Result<()> {
+        if self.href == crate::STDIN {
+            let mut original = String::new();
+            let _ = std::io::stdin().read_to_string(&mut original)?;
+            let formatted = self.format(&original)?.unwrap_or(original.clone());
+            if self.check && formatted != original {
+                return Err(Error::NotFormatted(vec![self.href]));
+            } else if !self.check {
+                std::io::stdout().write_all(formatted.as_bytes())?;
+            }
+            return Ok(());
+        }
+        let mut paths = Vec::new();
+        json_files(Path::new(&self.href), &mut paths)?;
+        let mut unformatted = Vec::new();
+        for path in paths {
+            let original = std::fs::read_to_string(&path)?;
+            let Some(formatted) = self.format(&original)? else {
+                continue;
+            };
+            if formatted != original {
+                if self.check {
+                    unformatted.push(path.to_string_lossy().into_owned());
+                } else {
+                    std::fs::write(&path, formatted)?;
+                }
+            }
+        }
+        if unformatted.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::NotFormatted(unformatted))
+        }
+    }
+
+    /// Formats STAC JSON, or returns `None` if it isn't a catalog, collection, or item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Fmt(fmt) = Args::parse_from(["stac", "fmt", "data"]).command else {
+    ///     unreachable!()
+    /// };
+    /// let formatted = fmt
+    ///     .format(r#"{"id": "an-id", "type": "Catalog", "stac_version": "1.0.0"}"#)
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert!(formatted.starts_with("{\n  \"type\": \"Catalog\""));
+    /// ```
+    pub fn format(&self, json: &str) -> Result<Option<String>> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let is_stac = value.get("stac_version").is_some()
+            && matches!(
+                value.get("type").and_then(Value::as_str),
+                Some(crate::ITEM_TYPE | crate::CATALOG_TYPE | crate::COLLECTION_TYPE)
+            );
+        if !is_stac {
+            return Ok(None);
+        }
+        canonicalize_stac(&mut value);
+        let mut buf = Vec::new();
+        if self.indent == 0 {
+            serde_json::to_writer(&mut buf, &value)?;
+        } else {
+            let indent = vec![b' '; self.indent];
+            let mut serializer =
+                Serializer::with_formatter(&mut buf, PrettyFormatter::with_indent(&indent));
+            value.serialize(&mut serializer)?;
+        }
+        buf.push(b'\n');
+        Ok(Some(
+            String::from_utf8(buf).expect("serde_json writes utf-8"),
+        ))
+    }
+}
+
+/// Collects the `.json` files at or below a path, in sorted order.
+fn json_files(path: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|e| e == "json") {
+                json_files(&entry, paths)?;
+            }
+        }
+    } else {
+        paths.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Orders the keys of a catalog, collection, or item, its links, and its assets.
+fn canonicalize_stac(value: &mut Value) {
+    if let Value::Object(object) = value {
+        sort_keys(object, &KEY_ORDER);
+        for (key, value) in object.iter_mut() {
+            match key.as_str() {
+                "links" => canonicalize_links(value),
+                "assets" | "item_assets" => {
+                    if let Value::Object(assets) = value {
+                        sort_keys(assets, &[]);
+                        for asset in assets.values_mut() {
+                            canonicalize(asset, &ASSET_KEY_ORDER);
+                        }
+                    }
+                }
+                _ => canonicalize(value, &[]),
+            }
+        }
+    }
+}
+
+/// Orders an object's keys, then sorts the keys of every nested object.
+fn canonicalize(value: &mut Value, order: &[&str]) {
+    match value {
+        Value::Object(object) => {
+            sort_keys(object, order);
+            for value in object.values_mut() {
+                canonicalize(value, &[]);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                canonicalize(value, &[]);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Puts the keys in `order` first, then the rest sorted.
+fn sort_keys(object: &mut Map<String, Value>, order: &[&str]) {
+    let mut entries: Vec<_> = std::mem::take(object).into_iter().collect();
+    entries.sort_by_cached_key(|(key, _)| {
+        let position = order.iter().position(|k| k == key);
+        (position.unwrap_or(order.len()), key.clone())
+    });
+    *object = entries.into_iter().collect();
+}
+
+fn canonicalize_links(links: &mut Value) {
+    if let Value::Array(links) = links {
+        for link in links.iter_mut() {
+            canonicalize(link, &LINK_KEY_ORDER);
+        }
+        links.sort_by_cached_key(|link| {
+            let field = |key| {
+                link.get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let rel = field("rel");
+            let position = REL_ORDER.iter().position(|r| *r == rel);
+            (position.unwrap_or(REL_ORDER.len()), rel, field("href"))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fmt;
+    use crate::Error;
+    use serde_json::{json, Value};
+
+    fn fmt(href: &str, check: bool) -> Fmt {
+        Fmt {
+            href: href.to_string(),
+            indent: 2,
+            check,
+        }
+    }
+
+    #[test]
+    fn canonical_order() {
+        let json = json!({
+            "links": [
+                {"href": "./b.json", "rel": "item"},
+                {"href": "./a.json", "rel": "item"},
+                {"title": "Root", "href": "./catalog.json", "rel": "root"},
+            ],
+            "id": "an-item",
+            "assets": {"b": {"type": "image/tiff", "href": "b.tif"}, "a": {"href": "a.tif"}},
+            "stac_version": "1.0.0",
+            "type": "Feature",
+            "properties": {"z": 1, "datetime": null},
+        })
+        .to_string();
+        let formatted = fmt("", false).format(&json).unwrap().unwrap();
+        let value: Value = serde_json::from_str(&formatted).unwrap();
+        let keys: Vec<_> = value.as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            [
+                "type",
+                "stac_version",
+                "id",
+                "properties",
+                "links",
+                "assets"
+            ]
+        );
+        let hrefs: Vec<_> = value["links"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|link| link["href"].as_str().unwrap())
+            .collect();
+        assert_eq!(hrefs, ["./catalog.json", "./a.json", "./b.json"]);
+        let link_keys: Vec<_> = value["links"][0].as_object().unwrap().keys().collect();
+        assert_eq!(link_keys, ["rel", "href", "title"]);
+        let asset_keys: Vec<_> = value["assets"].as_object().unwrap().keys().collect();
+        assert_eq!(asset_keys, ["a", "b"]);
+        let property_keys: Vec<_> = value["properties"].as_object().unwrap().keys().collect();
+        assert_eq!(property_keys, ["datetime", "z"]);
+        assert_eq!(
+            fmt("", false).format(&formatted).unwrap().unwrap(),
+            formatted
+        );
+    }
+
+    #[test]
+    fn not_stac() {
+        assert!(fmt("", false).format(r#"{"a": 1}"#).unwrap().is_none());
+    }
+
+    #[test]
+    fn check_then_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("catalog.json");
+        std::fs::write(
+            &path,
+            r#"{"id": "root", "type": "Catalog", "stac_version": "1.0.0", "description": "", "links": []}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("other.json"), r#"{"b": 1, "a": 2}"#).unwrap();
+        let href = dir.path().to_string_lossy().into_owned();
+        assert!(matches!(
+            fmt(&href, true).run().unwrap_err(),
+            Error::NotFormatted(paths) if paths.len() == 1
+        ));
+        fmt(&href, false).run().unwrap();
+        fmt(&href, true).run().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("other.json")).unwrap(),
+            r#"{"b": 1, "a": 2}"#
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! The `stac` command-line interface.
+//!
+//! This module is enabled by the `cli` feature, which also builds the `stac`
+//! binary:
+//!
+//! ```shell
+//! cargo install stac --features cli
+//! stac serve path/to/catalog --port 8080
+//! stac copy path/to/catalog path/to/copy --layout flat --copy-assets
+//! stac describe path/to/catalog --json
+//! stac fmt path/to/catalog --check
+//! ```
+//!
+//! Use `-` for standard input or output to compose `stac` with other tools:
+//!
+//! ```shell
+//! stac copy path/to/catalog - | jq -c 'select(.type == "Feature")'
+//! cat item.json | stac fmt - > formatted.json
+//! stac describe - < catalog.json
+//! ```
+//!
+//! Urls are read with an [HttpClient](crate::HttpClient) configured by
+//! environment variables, e.g. `STAC_API_TOKEN` and `STAC_TIMEOUT`, see
+//! [HttpClient::from_env](crate::HttpClient::from_env).
+
+mod copy;
+mod describe;
+mod fmt;
+mod serve;
+
+pub use {
+    copy::{Copy, LayoutKind},
+    describe::{Describe, Description, Statistics, Tree},
+    fmt::Fmt,
+    serve::Serve,
+};
+
+use crate::{Handle, Read, Reader, Result, Stac};
+use clap::{Parser, Subcommand};
+use std::path::Path;
+
+/// The command-line arguments of the `stac` binary.
+///
+/// # Examples
+///
+/// ```
+/// use clap::Parser;
+/// use stac::cli::Args;
+/// let args = Args::parse_from(["stac", "serve", "data"]);
+/// ```
+#[derive(Debug, Parser)]
+#[command(version, about = "Read, write, and serve STAC catalogs", long_about = None)]
+pub struct Args {
+    /// The subcommand to run.
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// A `stac` subcommand.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Serves a catalog as a STAC API.
+    Serve(Serve),
+
+    /// Copies a catalog, optionally changing its layout and STAC version.
+    #[command(alias = "migrate")]
+    Copy(Copy),
+
+    /// Prints a catalog's tree and statistics.
+    Describe(Describe),
+
+    /// Rewrites STAC JSON files with canonical key and link ordering.
+    Fmt(Fmt),
+}
+
+impl Args {
+    /// Runs the subcommand.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use stac::cli::Args;
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// Args::parse_from(["stac", "serve", "data"]).run().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Command::Serve(serve) => serve.run().await,
+            Command::Copy(copy) => blocking(move || copy.run()).await,
+            Command::Describe(describe) => blocking(move || describe.run()).await,
+            Command::Fmt(fmt) => blocking(move || fmt.run()).await,
+        }
+    }
+}
+
+/// Runs synchronous work on tokio's blocking pool.
+///
+/// The [Reader]'s blocking http client can't be used or dropped on an async
+/// worker thread, so every subcommand that reads does so in here.
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Reads the catalog at an href, with a [Reader] configured by environment variables.
+fn read(href: &str) -> Result<(Stac<Reader>, Handle)> {
+    let reader = Reader::from_env()?;
+    let href_object = reader.read(catalog_href(href))?;
+    Stac::new_with_reader(href_object, reader)
+}
+
+/// Returns the href of the catalog in a directory, or the href itself if it
+/// isn't a local directory.
+fn catalog_href(href: &str) -> String {
+    let path = Path::new(href);
+    if path.is_dir() {
+        path.join("catalog.json").to_string_lossy().into_owned()
+    } else {
+        href.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, Command, LayoutKind};
+    use clap::Parser;
+
+    #[test]
+    fn catalog_href() {
+        assert_eq!(
+            super::catalog_href("data"),
+            std::path::Path::new("data")
+                .join("catalog.json")
+                .to_string_lossy()
+        );
+        assert_eq!(
+            super::catalog_href("data/collection.json"),
+            "data/collection.json"
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn describe_url() {
+        use crate::{
+            http::test_server::{response, serve},
+            Catalog,
+        };
+        let catalog = serde_json::to_string(&Catalog::new("an-id")).unwrap();
+        let (url, server) = serve(vec![response(200, &[], &catalog)]);
+        Args::parse_from(["stac", "describe", &format!("{}/catalog.json", url)])
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(server.join().unwrap()[0].line, "GET /catalog.json HTTP/1.1");
+    }
+
+    #[test]
+    fn serve() {
+        let args = Args::try_parse_from(["stac", "serve", "data", "--port", "8080"]).unwrap();
+        let Command::Serve(serve) = args.command else {
+            panic!("should be serve");
+        };
+        assert_eq!(serve.href, "data");
+        assert_eq!(serve.port, 8080);
+        assert_eq!(serve.url(), "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn migrate() {
+        let args = Args::try_parse_from([
+            "stac",
+            "migrate",
+            "data",
+            "out",
+            "--layout",
+            "rebase",
+            "--target-version",
+            "1.1",
+        ])
+        .unwrap();
+        let Command::Copy(copy) = args.command else {
+            panic!("should be copy");
+        };
+        assert_eq!(copy.layout, LayoutKind::Rebase);
+        assert_eq!(copy.target_version.unwrap().to_string(), "1.1.0");
+        assert!(
+            Args::try_parse_from(["stac", "copy", "a", "b", "--target-version", "0.9"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    server::{self, Api},
+    Result,
+};
+use clap::Args;
+
+/// Arguments for `stac serve`, which serves a catalog as a STAC API.
+///
+/// The whole catalog is read into memory before the server starts, and
+/// changes to the files aren't picked up until it's restarted.
+#[derive(Debug, Args)]
+pub struct Serve {
+    /// The href of the root catalog, or a directory with a `catalog.json`.
+    pub href: String,
+
+    /// The host to bind to.
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// The port to bind to.
+    #[arg(short, long, default_value_t = 7822)]
+    pub port: u16,
+}
+
+impl Serve {
+    /// Reads the catalog and serves it until the process is stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Serve(serve) = Args::parse_from(["stac", "serve", "data"]).command else {
+    ///     unreachable!()
+    /// };
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// serve.run().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn run(self) -> Result<()> {
+        let (href, url) = (self.href.clone(), self.url());
+        let api = super::blocking(move || {
+            let (stac, root) = super::read(&href)?;
+            Api::from_stac(stac, root, &url)
+        })
+        .await?;
+        let listener = tokio::net::TcpListener::bind((self.host.as_str(), self.port)).await?;
+        eprintln!("serving {} at {}", self.href, self.url());
+        axum::serve(listener, server::router(api)).await?;
+        Ok(())
+    }
+
+    /// Returns the root url of the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clap::Parser;
+    /// use stac::cli::{Args, Command};
+    /// let Command::Serve(serve) = Args::parse_from(["stac", "serve", "data"]).command else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(serve.url(), "http://127.0.0.1:7822");
+    /// ```
+    pub fn url(&self) -> String {
+        format!("http://{}:{}", self.host, self.port)
+    }
+}
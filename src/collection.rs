@@ -1,4 +1,4 @@
-use crate::{Asset, Extent, Link, Provider, STAC_VERSION};
+use crate::{Asset, Catalog, Extent, Link, Provider, STAC_VERSION};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
@@ -109,6 +109,55 @@ impl Collection {
             additional_fields: Map::new(),
         }
     }
+
+    /// Promotes a [Catalog] to a `Collection`.
+    ///
+    /// The catalog's id, title, description, links, extensions, version, and
+    /// additional fields are carried over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Collection, Extent};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.description = "A description".to_string();
+    /// let collection = Collection::from_catalog(catalog, Extent::default(), "CC-BY-4.0");
+    /// assert_eq!(collection.id, "an-id");
+    /// assert_eq!(collection.description, "A description");
+    /// assert_eq!(collection.license, "CC-BY-4.0");
+    /// ```
+    pub fn from_catalog(catalog: Catalog, extent: Extent, license: impl ToString) -> Collection {
+        Collection {
+            version: catalog.version,
+            extensions: catalog.extensions,
+            title: catalog.title,
+            description: catalog.description,
+            license: license.to_string(),
+            extent,
+            links: catalog.links,
+            additional_fields: catalog.additional_fields,
+            ..Collection::new(catalog.id)
+        }
+    }
+}
+
+impl From<Collection> for Catalog {
+    /// Demotes a `Collection` to a [Catalog].
+    ///
+    /// The collection's id, title, description, links, extensions, version,
+    /// and additional fields are carried over. Collection-only fields (e.g.
+    /// `extent`, `license`, and `providers`) are dropped.
+    fn from(collection: Collection) -> Catalog {
+        Catalog {
+            version: collection.version,
+            extensions: collection.extensions,
+            title: collection.title,
+            description: collection.description,
+            links: collection.links,
+            additional_fields: collection.additional_fields,
+            ..Catalog::new(collection.id)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +182,21 @@ mod tests {
         assert!(collection.links.is_empty());
     }
 
+    #[test]
+    fn catalog_roundtrip() {
+        use crate::{Catalog, Link};
+
+        let mut catalog = Catalog::new("an-id");
+        catalog.title = Some("A title".to_string());
+        catalog.description = "A description".to_string();
+        catalog.links.push(Link::root("./catalog.json"));
+        let collection = Collection::from_catalog(catalog.clone(), Extent::default(), "MIT");
+        assert_eq!(collection.r#type, "Collection");
+        assert_eq!(collection.title, catalog.title);
+        assert_eq!(collection.links, catalog.links);
+        assert_eq!(Catalog::from(collection), catalog);
+    }
+
     #[test]
     fn skip_serializing() {
         let collection = Collection::new("an-id");
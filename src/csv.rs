@@ -0,0 +1,407 @@
+//! Convert between [Items](Item) and comma- (or tab-) separated values.
+//!
+//! Many data providers deliver scene inventories as CSV files, with one row per scene.
+//! Each row is converted into an [Item]: the id, datetime, and geometry columns are mapped onto the corresponding item fields, and every other column becomes an item property.
+//! Geometries are stored as [WKT](https://en.wikipedia.org/wiki/Well-known_text_representation_of_geometry).
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::csv::Columns;
+//! let data = "id,datetime,geometry,platform\n\
+//!     scene-1,2022-03-01T00:00:00Z,POINT(-105 40),landsat-8\n";
+//! let items = stac::csv::from_reader(data.as_bytes(), &Columns::default()).unwrap();
+//! assert_eq!(items[0].id, "scene-1");
+//! assert_eq!(items[0].properties.additional_fields["platform"], "landsat-8");
+//!
+//! let mut buffer = Vec::new();
+//! stac::csv::to_writer(&mut buffer, &items, &Columns::default()).unwrap();
+//! ```
+
+use crate::{Error, Item, Result};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use indexmap::IndexSet;
+use serde_json::Value;
+use std::{collections::HashMap, fs::File, path::Path};
+
+/// Properties that are read as numbers unless [Columns::types] says otherwise.
+const NUMERIC_PROPERTIES: &[&str] = &[
+    "gsd",
+    "eo:cloud_cover",
+    "eo:snow_cover",
+    "proj:epsg",
+    "sat:absolute_orbit",
+    "sat:relative_orbit",
+    "view:azimuth",
+    "view:incidence_angle",
+    "view:off_nadir",
+    "view:sun_azimuth",
+    "view:sun_elevation",
+];
+
+/// The mapping between CSV columns and [Item] fields.
+///
+/// The same mapping is used when reading and when writing.
+#[derive(Debug, Clone)]
+pub struct Columns {
+    /// The column that holds the item id.
+    pub id: String,
+
+    /// The column that holds the item datetime.
+    pub datetime: String,
+
+    /// The column that holds the item geometry as WKT.
+    pub geometry: String,
+
+    /// The property columns to read or write.
+    ///
+    /// If `None`, every column that isn't the id, datetime, or geometry is
+    /// treated as a property when reading, and every property found on the
+    /// items is written when writing.
+    pub properties: Option<Vec<String>>,
+
+    /// The field delimiter, e.g. `b','` or `b'\t'`.
+    pub delimiter: u8,
+
+    /// The types of property columns, for reading.
+    ///
+    /// Columns that aren't listed are read as strings, except for well-known
+    /// numeric STAC properties like `eo:cloud_cover` and `gsd`, which are
+    /// read as numbers.
+    pub types: HashMap<String, ColumnType>,
+}
+
+/// How the cells of a property column are read.
+///
+/// Cells that can't be read as their column's type are kept as strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Cells are strings, or JSON arrays or objects if they look like one.
+    String,
+
+    /// Cells are numbers.
+    Number,
+
+    /// Cells are `true` or `false`.
+    Boolean,
+}
+
+impl Columns {
+    /// Creates the default column mapping for tab-separated values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::csv::Columns;
+    /// let columns = Columns::tsv();
+    /// assert_eq!(columns.delimiter, b'\t');
+    /// ```
+    pub fn tsv() -> Columns {
+        Columns {
+            delimiter: b'\t',
+            ..Default::default()
+        }
+    }
+
+    fn is_property(&self, column: &str) -> bool {
+        if let Some(properties) = self.properties.as_ref() {
+            properties.iter().any(|property| property == column)
+        } else {
+            column != self.id && column != self.datetime && column != self.geometry
+        }
+    }
+
+    fn column_type(&self, column: &str) -> ColumnType {
+        if let Some(column_type) = self.types.get(column) {
+            *column_type
+        } else if NUMERIC_PROPERTIES.contains(&column) {
+            ColumnType::Number
+        } else {
+            ColumnType::String
+        }
+    }
+}
+
+impl Default for Columns {
+    fn default() -> Columns {
+        Columns {
+            id: "id".to_string(),
+            datetime: "datetime".to_string(),
+            geometry: "geometry".to_string(),
+            properties: None,
+            delimiter: b',',
+            types: HashMap::new(),
+        }
+    }
+}
+
+/// Reads [Items](Item) from a CSV file.
+///
+/// If the file extension is `.tsv`, the delimiter is set to a tab.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stac::csv::Columns;
+/// let items = stac::csv::read("scenes.csv", &Columns::default()).unwrap();
+/// ```
+pub fn read(path: impl AsRef<Path>, columns: &Columns) -> Result<Vec<Item>> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    if path.extension().map(|e| e == "tsv").unwrap_or(false) {
+        let columns = Columns {
+            delimiter: b'\t',
+            ..columns.clone()
+        };
+        from_reader(file, &columns)
+    } else {
+        from_reader(file, columns)
+    }
+}
+
+/// Reads [Items](Item) from CSV data.
+///
+/// Empty cells are skipped. Property cells are read according to their
+/// [ColumnType], so values like `00123` or `true` stay strings unless their
+/// column is declared in [Columns::types] or is a well-known numeric property.
+/// Cells that are JSON arrays or objects, like those written by [to_writer],
+/// are stored as such.
+///
+/// # Examples
+///
+/// ```
+/// use stac::csv::{ColumnType, Columns};
+/// let data = "id\tdatetime\tgeometry\teo:cloud_cover\tsun_angle\tpath\n\
+///     scene-1\t2022-03-01 12:00:00\tPOINT(-105 40)\t4.2\t32.5\t042\n";
+/// let mut columns = Columns::tsv();
+/// let _ = columns.types.insert("sun_angle".to_string(), ColumnType::Number);
+/// let items = stac::csv::from_reader(data.as_bytes(), &columns).unwrap();
+/// let properties = &items[0].properties;
+/// assert_eq!(properties.datetime.as_ref().unwrap(), "2022-03-01T12:00:00+00:00");
+/// assert_eq!(properties.additional_fields["eo:cloud_cover"], 4.2);
+/// assert_eq!(properties.additional_fields["sun_angle"], 32.5);
+/// assert_eq!(properties.additional_fields["path"], "042");
+/// ```
+pub fn from_reader(reader: impl std::io::Read, columns: &Columns) -> Result<Vec<Item>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(columns.delimiter)
+        .from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let id_index = headers
+        .iter()
+        .position(|header| header == columns.id)
+        .ok_or_else(|| Error::MissingColumn(columns.id.clone()))?;
+    let mut items = Vec::new();
+    for result in reader.records() {
+        let record = result?;
+        let mut item = Item::new(&record[id_index]);
+        item.properties.datetime = None;
+        for (header, field) in headers.iter().zip(record.iter()) {
+            if field.is_empty() {
+                continue;
+            }
+            if header == columns.datetime {
+                item.properties.datetime = Some(parse_datetime(field)?);
+            } else if header == columns.geometry {
+                item.set_geometry_from_wkt(field)?;
+            } else if header != columns.id && columns.is_property(header) {
+                let value = parse_property(field, columns.column_type(header));
+                let _ = item
+                    .properties
+                    .additional_fields
+                    .insert(header.to_string(), value);
+            }
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Writes [Items](Item) as CSV data.
+///
+/// Geometries are written as WKT. String properties are written as-is, and
+/// all other property values are written as JSON. To read numbers and
+/// booleans back as such, declare their columns in [Columns::types].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Item, csv::Columns};
+/// let mut item = Item::new("an-id");
+/// item.properties.datetime = None;
+/// let mut buffer = Vec::new();
+/// stac::csv::to_writer(&mut buffer, &[item], &Columns::default()).unwrap();
+/// assert_eq!(String::from_utf8(buffer).unwrap(), "id,datetime,geometry\nan-id,,\n");
+/// ```
+pub fn to_writer(writer: impl std::io::Write, items: &[Item], columns: &Columns) -> Result<()> {
+    let properties: Vec<String> = if let Some(properties) = columns.properties.as_ref() {
+        properties.clone()
+    } else {
+        items
+            .iter()
+            .flat_map(|item| item.properties.additional_fields.keys())
+            .filter(|key| columns.is_property(key))
+            .cloned()
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect()
+    };
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(columns.delimiter)
+        .from_writer(writer);
+    writer.write_record(
+        [&columns.id, &columns.datetime, &columns.geometry]
+            .into_iter()
+            .chain(properties.iter()),
+    )?;
+    for item in items {
+        let mut record = vec![
+            item.id.clone(),
+            item.properties.datetime.clone().unwrap_or_default(),
+            item.geometry_wkt()?.unwrap_or_default(),
+        ];
+        for property in &properties {
+            let field = match item.properties.additional_fields.get(property) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(s)) => s.clone(),
+                Some(value) => value.to_string(),
+            };
+            record.push(field);
+        }
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn parse_property(field: &str, column_type: ColumnType) -> Value {
+    let value = match column_type {
+        ColumnType::String if field.starts_with(['[', '{']) => serde_json::from_str(field)
+            .ok()
+            .filter(|value: &Value| value.is_array() || value.is_object()),
+        ColumnType::String => None,
+        ColumnType::Number => serde_json::from_str(field).ok().filter(Value::is_number),
+        ColumnType::Boolean => serde_json::from_str(field).ok().filter(Value::is_boolean),
+    };
+    value.unwrap_or_else(|| Value::String(field.to_string()))
+}
+
+fn parse_datetime(s: &str) -> Result<String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        Ok(datetime.with_timezone(&Utc).to_rfc3339())
+    } else {
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .map(|datetime| Utc.from_utc_datetime(&datetime).to_rfc3339())
+            .ok_or_else(|| Error::InvalidDatetime(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnType, Columns};
+    use crate::Item;
+    use geojson::{Geometry, Value};
+    use serde_json::json;
+
+    #[test]
+    fn from_reader() {
+        let data = "id,datetime,geometry,platform,eo:cloud_cover,empty\n\
+            a,2022-03-01T00:00:00Z,\"POLYGON((0 0,1 0,1 1,0 0))\",landsat-8,4,\n\
+            b,,,sentinel-2,,\n";
+        let items = super::from_reader(data.as_bytes(), &Columns::default()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "a");
+        assert_eq!(
+            items[0].properties.datetime.as_ref().unwrap(),
+            "2022-03-01T00:00:00+00:00"
+        );
+        assert_eq!(items[0].bbox.as_ref().unwrap(), &[0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(items[0].properties.additional_fields["eo:cloud_cover"], 4);
+        assert!(!items[0].properties.additional_fields.contains_key("empty"));
+        assert!(items[1].properties.datetime.is_none());
+        assert!(items[1].geometry.is_none());
+    }
+
+    #[test]
+    fn column_types() {
+        let data = "id,path,downlinked,sun_angle,eo:cloud_cover,gsd\n\
+            a,042,true,32.5,cloudy,30\n";
+        let items = super::from_reader(data.as_bytes(), &Columns::default()).unwrap();
+        let properties = &items[0].properties.additional_fields;
+        assert_eq!(properties["path"], "042");
+        assert_eq!(properties["downlinked"], "true");
+        assert_eq!(properties["sun_angle"], "32.5");
+        assert_eq!(properties["eo:cloud_cover"], "cloudy");
+        assert_eq!(properties["gsd"], 30);
+
+        let mut columns = Columns::default();
+        let _ = columns
+            .types
+            .insert("downlinked".to_string(), ColumnType::Boolean);
+        let _ = columns
+            .types
+            .insert("sun_angle".to_string(), ColumnType::Number);
+        let _ = columns.types.insert("gsd".to_string(), ColumnType::String);
+        let items = super::from_reader(data.as_bytes(), &columns).unwrap();
+        let properties = &items[0].properties.additional_fields;
+        assert_eq!(properties["path"], "042");
+        assert_eq!(properties["downlinked"], true);
+        assert_eq!(properties["sun_angle"], 32.5);
+        assert_eq!(properties["gsd"], "30");
+    }
+
+    #[test]
+    fn missing_id_column() {
+        let data = "name,datetime\na,2022-03-01T00:00:00Z\n";
+        let _ = super::from_reader(data.as_bytes(), &Columns::default()).unwrap_err();
+    }
+
+    #[test]
+    fn invalid_datetime() {
+        let data = "id,datetime\na,yesterday\n";
+        let _ = super::from_reader(data.as_bytes(), &Columns::default()).unwrap_err();
+    }
+
+    #[test]
+    fn custom_columns() {
+        let data = "scene_id,acquired,footprint,platform,notes\n\
+            a,2022-03-01T00:00:00Z,POINT(1 2),landsat-8,cloudy\n";
+        let columns = Columns {
+            id: "scene_id".to_string(),
+            datetime: "acquired".to_string(),
+            geometry: "footprint".to_string(),
+            properties: Some(vec!["platform".to_string()]),
+            ..Default::default()
+        };
+        let items = super::from_reader(data.as_bytes(), &columns).unwrap();
+        assert_eq!(items[0].id, "a");
+        assert_eq!(
+            items[0].geometry.as_ref().unwrap().value,
+            Value::Point(vec![1.0, 2.0])
+        );
+        assert_eq!(items[0].properties.additional_fields.len(), 1);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut item = Item::new("an-id");
+        item.properties.datetime = Some("2022-03-01T00:00:00+00:00".to_string());
+        item.geometry = Some(Geometry::new(Value::Point(vec![-105.0, 40.0])));
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("eo:bands".to_string(), json!([{"name": "red"}]));
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("platform".to_string(), json!("landsat-8"));
+        let mut buffer = Vec::new();
+        super::to_writer(&mut buffer, &[item.clone()], &Columns::tsv()).unwrap();
+        let items = super::from_reader(buffer.as_slice(), &Columns::tsv()).unwrap();
+        assert_eq!(items[0].id, item.id);
+        assert_eq!(items[0].properties, item.properties);
+        assert_eq!(items[0].geometry, item.geometry);
+    }
+}
@@ -0,0 +1,21 @@
+//! Parsing for STAC datetimes.
+
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+
+/// Parses an RFC 3339 datetime.
+pub(crate) fn parse(datetime: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(datetime)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .map_err(|_| Error::InvalidDatetime(datetime.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse() {
+        assert!(super::parse("2023-01-01T00:00:00Z").is_ok());
+        assert!(super::parse("2023-01-01T00:00:00+01:00").is_ok());
+        assert!(super::parse("yesterday").is_err());
+    }
+}
@@ -6,6 +6,22 @@ use url::Url;
 /// Error enum for crate-specific errors.
 #[derive(Error, Debug)]
 pub enum Error {
+    /// Returned when an archive can't be written or read.
+    #[error("archive error: {0}")]
+    Archive(String),
+
+    /// Returned when a request can't be authenticated.
+    #[error("auth error: {0}")]
+    Auth(String),
+
+    /// Returned when a [Stac](crate::Stac) inside an [AsyncStac](crate::AsyncStac) tries to read synchronously.
+    #[error("cannot read {0} synchronously, use the AsyncStac")]
+    BlockingRead(String),
+
+    /// Returned when an operation is cancelled with a [CancellationToken](crate::CancellationToken).
+    #[error("cancelled")]
+    Cancelled,
+
     /// Returned when you try to remove the root object from a [Stac](crate::Stac).
     #[error("cannot remove root")]
     CannotRemoveRoot,
@@ -14,14 +30,90 @@ pub enum Error {
     #[error("cannot write url: {0}")]
     CannotWriteUrl(Url),
 
+    /// Returned when a collection with the given id doesn't exist.
+    #[error("collection not found: {0}")]
+    CollectionNotFound(String),
+
+    /// [csv::Error]
+    #[cfg(feature = "csv")]
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// Returned when renaming an asset key would collide with another asset.
+    #[error("duplicate asset key {key:?} on {id}")]
+    DuplicateAssetKey {
+        /// The id of the object with the duplicate key.
+        id: String,
+        /// The duplicate key.
+        key: String,
+    },
+
+    /// Returned when adding an object whose id is already taken.
+    #[error("duplicate id: {0}")]
+    DuplicateId(String),
+
+    /// [geojson::Error]
+    #[error("geojson error: {0}")]
+    Geojson(#[from] Box<geojson::Error>),
+
+    /// Returned when a bbox doesn't have four or six numbers, or its south bound is above its north bound.
+    #[error("invalid bbox: {0}")]
+    InvalidBbox(String),
+
+    /// Returned when a datetime string cannot be parsed.
+    #[error("invalid datetime: {0}")]
+    InvalidDatetime(String),
+
+    /// Returned when an environment variable used for configuration has an invalid value.
+    #[error("invalid environment variable: {0}")]
+    InvalidEnv(String),
+
     /// [std::io::Error]
     #[error("std::io error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Returned when an id is not safe to use in an href.
+    #[error("invalid id {id:?}: {reason}")]
+    InvalidId {
+        /// The invalid id.
+        id: String,
+        /// Why the id is invalid.
+        reason: String,
+    },
+
     /// Returned when trying to access data in a [Stac](crate::Stac) with an invalid [Handle].
     #[error("invalid handle: {0:?}")]
     InvalidHandle(Handle),
 
+    /// Returned when sensor metadata is missing a required field, or a field can't be parsed.
+    #[error("invalid metadata: {0}")]
+    InvalidMetadata(String),
+
+    /// Returned when a JSON Patch can't be applied, or the patched object isn't valid.
+    #[error("invalid patch: {0}")]
+    InvalidPatch(String),
+
+    /// Returned when a value that's set as an item's properties isn't a JSON object.
+    #[error("properties must be a JSON object: {0}")]
+    InvalidProperties(Value),
+
+    /// Returned when a JSON schema cannot be compiled.
+    #[error("invalid schema {uri}: {message}")]
+    InvalidSchema {
+        /// The URI of the schema.
+        uri: String,
+        /// Why the schema is invalid.
+        message: String,
+    },
+
+    /// Returned when a [Stac](crate::Stac) snapshot can't be loaded.
+    #[error("invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    /// Returned when an [ItemTemplate](crate::ItemTemplate) can't be parsed or rendered.
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+
     /// Returned when the `type` field of a STAC object is not a [String].
     #[error("invalid \"type\" field: {0}")]
     InvalidTypeField(Value),
@@ -30,6 +122,22 @@ pub enum Error {
     #[error("invalid \"type\" value: {0}")]
     InvalidTypeValue(String),
 
+    /// Returned when none of an item's assets have a band with this common name.
+    #[error("no asset has a band with common name: {0}")]
+    MissingBand(String),
+
+    /// Returned when a required column is not present in a CSV header.
+    #[error("missing column: {0}")]
+    MissingColumn(String),
+
+    /// Returned when a STAC API doesn't declare a conformance class that a request needs.
+    #[error("missing conformance class: {0}")]
+    MissingConformance(String),
+
+    /// Returned when an [ItemTemplate](crate::ItemTemplate) placeholder has no variable.
+    #[error("missing template variable: {0}")]
+    MissingVariable(String),
+
     /// Returned when there is not a `type` field on a STAC object
     #[error("no \"type\" field in the JSON object")]
     MissingType,
@@ -38,6 +146,25 @@ pub enum Error {
     #[error("object has no href, cannot write")]
     MissingHref,
 
+    /// [notify::Error]
+    #[cfg(feature = "watch")]
+    #[error("notify error: {0}")]
+    Notify(#[from] notify::Error),
+
+    /// Returned by `stac fmt --check` when files aren't formatted.
+    #[error("not formatted: {}", .0.join(", "))]
+    NotFormatted(Vec<String>),
+
+    /// Returned when a conditional write to a url fails because the object
+    /// there was created or changed by someone else.
+    #[error("precondition failed: {0}")]
+    PreconditionFailed(Url),
+
+    /// [regex::Error]
+    #[cfg(feature = "regex")]
+    #[error("regex error: {0}")]
+    Regex(#[from] regex::Error),
+
     /// Returned when trying to read from a url but the `reqwest` feature is not enabled.
     #[error("reqwest is not enabled")]
     ReqwestNotEnabled,
@@ -47,6 +174,10 @@ pub enum Error {
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
 
+    /// Returned when a JSON schema can't be found in any of the [Schemas](crate::validate::Schemas) sources.
+    #[error("schema not found: {0}")]
+    SchemaNotFound(String),
+
     /// [serde_json::Error]
     #[error("serde_json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
@@ -60,11 +191,32 @@ pub enum Error {
         actual: String,
     },
 
+    /// Returned when a search uses a field that isn't one of an API's queryables.
+    #[error("unknown queryable: {0}")]
+    UnknownQueryable(String),
+
     /// Returned if a node doesn't have an href or an object.
     #[error("unresolvable node")]
     UnresolvableNode,
 
+    /// Returned when a CQL2 filter can't be expressed as a query.
+    #[error("unsupported filter: {0}")]
+    UnsupportedFilter(String),
+
+    /// Returned when a `stac_version` is not a supported `1.x.y` version.
+    #[error("unsupported STAC version: {0}")]
+    UnsupportedVersion(String),
+
     /// [url::ParseError]
     #[error("url parse error: {0}")]
     Url(#[from] url::ParseError),
+
+    /// Returned when a WKT string cannot be converted into a geometry.
+    #[error("wkt error: {0}")]
+    Wkt(String),
+
+    /// [roxmltree::Error]
+    #[cfg(feature = "adapters")]
+    #[error("xml error: {0}")]
+    Xml(#[from] roxmltree::Error),
 }
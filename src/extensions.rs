@@ -0,0 +1,371 @@
+//! A registry of known [STAC extensions](https://stac-extensions.github.io/).
+//!
+//! Extensions are declared in an object's `stac_extensions` field by their schema URI, e.g. `https://stac-extensions.github.io/eo/v1.0.0/schema.json`.
+//! The registry knows every published version of the common extensions, which lets you detect outdated schema URIs and upgrade them.
+//! Some legacy catalogs list extensions by a shorthand identifier (e.g. `"eo"` or `"projection"`) instead of a URI; [resolve] maps these shorthands to canonical schema URIs.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::extensions;
+//! let uri = "https://stac-extensions.github.io/eo/v1.0.0/schema.json";
+//! assert!(extensions::is_outdated(uri));
+//! assert_eq!(
+//!     extensions::upgrade(uri).unwrap(),
+//!     "https://stac-extensions.github.io/eo/v1.1.0/schema.json"
+//! );
+//! ```
+
+const SCHEMA_PREFIX: &str = "https://stac-extensions.github.io/";
+const SCHEMA_SUFFIX: &str = "/schema.json";
+
+/// Legacy shorthands that don't match a registered extension identifier.
+const ALIASES: &[(&str, &str)] = &[
+    ("checksum", "file"),
+    ("pc", "pointcloud"),
+    ("proj", "projection"),
+    ("sci", "scientific"),
+];
+
+/// A known STAC extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extension {
+    /// The extension identifier, as used in its schema URI, e.g. `"eo"`.
+    pub identifier: &'static str,
+
+    /// All published versions of the extension, oldest first.
+    pub versions: &'static [&'static str],
+}
+
+/// Every extension in the registry.
+pub const EXTENSIONS: &[Extension] = &[
+    Extension {
+        identifier: "alternate-assets",
+        versions: &["1.1.0", "1.2.0"],
+    },
+    Extension {
+        identifier: "classification",
+        versions: &["1.0.0", "1.1.0", "2.0.0"],
+    },
+    Extension {
+        identifier: "datacube",
+        versions: &["1.0.0", "2.0.0", "2.1.0", "2.2.0"],
+    },
+    Extension {
+        identifier: "eo",
+        versions: &["1.0.0", "1.1.0"],
+    },
+    Extension {
+        identifier: "file",
+        versions: &["1.0.0", "2.0.0", "2.1.0"],
+    },
+    Extension {
+        identifier: "grid",
+        versions: &["1.0.0", "1.1.0"],
+    },
+    Extension {
+        identifier: "item-assets",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "label",
+        versions: &["1.0.0", "1.0.1"],
+    },
+    Extension {
+        identifier: "landsat",
+        versions: &["1.1.0", "2.0.0"],
+    },
+    Extension {
+        identifier: "mgrs",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "ml-model",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "pointcloud",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "processing",
+        versions: &["1.0.0", "1.1.0", "1.2.0"],
+    },
+    Extension {
+        identifier: "projection",
+        versions: &["1.0.0", "1.1.0", "1.2.0", "2.0.0"],
+    },
+    Extension {
+        identifier: "raster",
+        versions: &["1.0.0", "1.1.0"],
+    },
+    Extension {
+        identifier: "render",
+        versions: &["1.0.0", "2.0.0"],
+    },
+    Extension {
+        identifier: "sar",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "sat",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "scientific",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "stats",
+        versions: &["0.1.0", "0.2.0"],
+    },
+    Extension {
+        identifier: "storage",
+        versions: &["1.0.0", "2.0.0"],
+    },
+    Extension {
+        identifier: "table",
+        versions: &["1.0.0", "1.1.0", "1.2.0"],
+    },
+    Extension {
+        identifier: "tiled-assets",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "timestamps",
+        versions: &["1.0.0", "1.1.0"],
+    },
+    Extension {
+        identifier: "version",
+        versions: &["1.0.0", "1.1.0", "1.2.0"],
+    },
+    Extension {
+        identifier: "view",
+        versions: &["1.0.0"],
+    },
+    Extension {
+        identifier: "xarray-assets",
+        versions: &["1.0.0"],
+    },
+];
+
+impl Extension {
+    /// Returns the schema URI for a version of this extension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let eo = stac::extensions::get("eo").unwrap();
+    /// assert_eq!(eo.uri("1.0.0"), "https://stac-extensions.github.io/eo/v1.0.0/schema.json");
+    /// ```
+    pub fn uri(&self, version: &str) -> String {
+        format!(
+            "{}{}/v{}{}",
+            SCHEMA_PREFIX, self.identifier, version, SCHEMA_SUFFIX
+        )
+    }
+
+    /// Returns the latest published version of this extension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let eo = stac::extensions::get("eo").unwrap();
+    /// assert_eq!(eo.latest_version(), "1.1.0");
+    /// ```
+    pub fn latest_version(&self) -> &'static str {
+        self.versions.last().expect("every extension has a version")
+    }
+
+    /// Returns the latest published version with the same major version as `version`.
+    ///
+    /// For `0.x` versions, where any minor release may be breaking, the minor
+    /// version must match as well. Returns `None` if there are no compatible
+    /// published versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let projection = stac::extensions::get("projection").unwrap();
+    /// assert_eq!(projection.latest_compatible_version("1.0.0").unwrap(), "1.2.0");
+    /// assert_eq!(projection.latest_compatible_version("2.0.0").unwrap(), "2.0.0");
+    ///
+    /// let stats = stac::extensions::get("stats").unwrap();
+    /// assert_eq!(stats.latest_compatible_version("0.1.0").unwrap(), "0.1.0");
+    /// ```
+    pub fn latest_compatible_version(&self, version: &str) -> Option<&'static str> {
+        let required = compatibility(version)?;
+        self.versions
+            .iter()
+            .rev()
+            .find(|v| compatibility(v) == Some(required))
+            .copied()
+    }
+}
+
+/// Returns the registered extension with the given identifier.
+///
+/// # Examples
+///
+/// ```
+/// assert!(stac::extensions::get("eo").is_some());
+/// assert!(stac::extensions::get("not-an-extension").is_none());
+/// ```
+pub fn get(identifier: &str) -> Option<&'static Extension> {
+    EXTENSIONS
+        .iter()
+        .find(|extension| extension.identifier == identifier)
+}
+
+/// Parses an extension schema URI into its registered [Extension] and version.
+///
+/// Returns `None` if the URI doesn't belong to a registered extension.
+///
+/// # Examples
+///
+/// ```
+/// let (extension, version) = stac::extensions::parse(
+///     "https://stac-extensions.github.io/projection/v1.0.0/schema.json"
+/// ).unwrap();
+/// assert_eq!(extension.identifier, "projection");
+/// assert_eq!(version, "1.0.0");
+/// ```
+pub fn parse(uri: &str) -> Option<(&'static Extension, &str)> {
+    let path = uri
+        .strip_prefix(SCHEMA_PREFIX)?
+        .strip_suffix(SCHEMA_SUFFIX)?;
+    let (identifier, version) = path.split_once("/v")?;
+    get(identifier).map(|extension| (extension, version))
+}
+
+/// Resolves a shorthand extension identifier to the schema URI of the extension's latest version.
+///
+/// Returns `None` if `shorthand` is not a registered identifier or a known legacy alias.
+///
+/// # Examples
+///
+/// ```
+/// use stac::extensions;
+/// assert_eq!(
+///     extensions::resolve("eo").unwrap(),
+///     "https://stac-extensions.github.io/eo/v1.1.0/schema.json"
+/// );
+/// assert_eq!(
+///     extensions::resolve("proj").unwrap(),
+///     extensions::resolve("projection").unwrap()
+/// );
+/// assert!(extensions::resolve("https://stac-extensions.github.io/eo/v1.1.0/schema.json").is_none());
+/// ```
+pub fn resolve(shorthand: &str) -> Option<String> {
+    let identifier = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == shorthand)
+        .map(|(_, identifier)| *identifier)
+        .unwrap_or(shorthand);
+    get(identifier).map(|extension| extension.uri(extension.latest_version()))
+}
+
+/// Returns true if the URI points to a registered extension and a newer compatible version exists.
+///
+/// # Examples
+///
+/// ```
+/// use stac::extensions;
+/// assert!(extensions::is_outdated("https://stac-extensions.github.io/eo/v1.0.0/schema.json"));
+/// assert!(!extensions::is_outdated("https://stac-extensions.github.io/eo/v1.1.0/schema.json"));
+/// assert!(!extensions::is_outdated("https://example.com/my-extension/v1.0.0/schema.json"));
+/// ```
+pub fn is_outdated(uri: &str) -> bool {
+    upgrade(uri).is_some()
+}
+
+/// Returns the URI of the latest compatible version of a registered extension.
+///
+/// Returns `None` if the extension is unknown or is already at its latest compatible version.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///     stac::extensions::upgrade("https://stac-extensions.github.io/projection/v1.0.0/schema.json").unwrap(),
+///     "https://stac-extensions.github.io/projection/v1.2.0/schema.json",
+/// );
+/// ```
+pub fn upgrade(uri: &str) -> Option<String> {
+    let (extension, version) = parse(uri)?;
+    let latest = extension.latest_compatible_version(version)?;
+    if latest != version {
+        Some(extension.uri(latest))
+    } else {
+        None
+    }
+}
+
+/// Returns the parts of a version that must match for two versions to be compatible.
+fn compatibility(version: &str) -> Option<(u64, Option<u64>)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    if major == 0 {
+        let minor = parts.next()?.parse().ok()?;
+        Some((major, Some(minor)))
+    } else {
+        Some((major, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EXTENSIONS;
+
+    #[test]
+    fn versions_are_sorted() {
+        for extension in EXTENSIONS {
+            let versions: Vec<Vec<u64>> = extension
+                .versions
+                .iter()
+                .map(|v| v.split('.').map(|p| p.parse().unwrap()).collect())
+                .collect();
+            let mut sorted = versions.clone();
+            sorted.sort();
+            assert_eq!(versions, sorted, "{}", extension.identifier);
+        }
+    }
+
+    #[test]
+    fn upgrade_unknown_version() {
+        assert!(
+            super::upgrade("https://stac-extensions.github.io/eo/v3.0.0/schema.json").is_none()
+        );
+    }
+
+    #[test]
+    fn zero_major_versions_require_equal_minor() {
+        let stats = super::get("stats").unwrap();
+        assert_eq!(stats.latest_compatible_version("0.1.0").unwrap(), "0.1.0");
+        assert_eq!(stats.latest_compatible_version("0.2.0").unwrap(), "0.2.0");
+        assert!(stats.latest_compatible_version("0.3.0").is_none());
+        assert!(
+            super::upgrade("https://stac-extensions.github.io/stats/v0.1.0/schema.json").is_none()
+        );
+    }
+
+    #[test]
+    fn aliases_are_registered() {
+        for (alias, identifier) in super::ALIASES {
+            assert!(super::get(identifier).is_some(), "{}", alias);
+            assert!(super::get(alias).is_none(), "{}", alias);
+        }
+    }
+
+    #[test]
+    fn roundtrip_uri() {
+        for extension in EXTENSIONS {
+            for version in extension.versions {
+                let uri = extension.uri(version);
+                let (parsed, parsed_version) = super::parse(&uri).unwrap();
+                assert_eq!(parsed, extension);
+                assert_eq!(parsed_version, *version);
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use geojson::{Geometry, Value};
+
+/// Computes the two-dimensional bounding box of a geometry.
+///
+/// Returns `None` if the geometry has no coordinates.
+pub(crate) fn bbox(geometry: &Geometry) -> Option<Vec<f64>> {
+    let mut bounds: Option<[f64; 4]> = None;
+    for_each_position(&geometry.value, &mut |position| {
+        if position.len() < 2 {
+            return;
+        }
+        let (x, y) = (position[0], position[1]);
+        let bounds = bounds.get_or_insert([x, y, x, y]);
+        bounds[0] = bounds[0].min(x);
+        bounds[1] = bounds[1].min(y);
+        bounds[2] = bounds[2].max(x);
+        bounds[3] = bounds[3].max(y);
+    });
+    bounds.map(|bounds| bounds.to_vec())
+}
+
+fn for_each_position(value: &Value, f: &mut impl FnMut(&[f64])) {
+    match value {
+        Value::Point(position) => f(position),
+        Value::MultiPoint(positions) | Value::LineString(positions) => {
+            positions.iter().for_each(|position| f(position))
+        }
+        Value::MultiLineString(lines) | Value::Polygon(lines) => {
+            lines.iter().flatten().for_each(|position| f(position))
+        }
+        Value::MultiPolygon(polygons) => polygons
+            .iter()
+            .flatten()
+            .flatten()
+            .for_each(|position| f(position)),
+        Value::GeometryCollection(geometries) => {
+            for geometry in geometries {
+                for_each_position(&geometry.value, f);
+            }
+        }
+    }
+}
+
+/// Returns the west, south, east, and north bounds of a two or three dimensional bbox.
+pub(crate) fn horizontal(bbox: &[f64]) -> Option<[f64; 4]> {
+    match bbox.len() {
+        4 => Some([bbox[0], bbox[1], bbox[2], bbox[3]]),
+        6 => Some([bbox[0], bbox[1], bbox[3], bbox[4]]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geojson::{Geometry, Value};
+
+    #[test]
+    fn bbox_polygon() {
+        let geometry = Geometry::new(Value::Polygon(vec![vec![
+            vec![-105.0, 40.0],
+            vec![-104.0, 40.0],
+            vec![-104.0, 41.0],
+            vec![-105.0, 40.0],
+        ]]));
+        assert_eq!(
+            super::bbox(&geometry).unwrap(),
+            vec![-105.0, 40.0, -104.0, 41.0]
+        );
+    }
+
+    #[test]
+    fn bbox_empty() {
+        let geometry = Geometry::new(Value::MultiPoint(Vec::new()));
+        assert!(super::bbox(&geometry).is_none());
+    }
+}
@@ -6,7 +6,7 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
 };
-use url::Url;
+use url::{form_urlencoded, Position, Url};
 
 /// An href can be an absolute url, an absolute path, or a relative path.
 ///
@@ -114,13 +114,14 @@ impl Href {
         match self {
             Href::Url(base) => base.join(href.as_str()).map(Href::Url).map_err(Error::from),
             Href::Path(base) => {
-                let (path, _) = extract_path_filename(base);
+                let (path, _) = extract_path_filename(split_suffix(base).0);
+                let (href, suffix) = split_suffix(href.as_str());
                 let path = if path.is_empty() {
-                    href.into()
+                    href.to_string()
                 } else {
-                    format!("{}/{}", path, href.as_str())
+                    format!("{}/{}", path, href)
                 };
-                let path = normalize_path(path);
+                let path = normalize_path(path) + suffix;
                 Ok(Href::Path(path))
             }
         }
@@ -240,9 +241,9 @@ impl Href {
     ///
     /// - Adds an `"./"` to the front of "downward" hrefs.
     /// - If both paths don't share a common base, they are assumed to be in the
-    /// same parent directory.
+    ///   same parent directory.
     /// - If both paths are absolute and they do not share a common base,
-    /// returns the original href unchanged.
+    ///   returns the original href unchanged.
     ///
     /// # Examples
     ///
@@ -251,18 +252,18 @@ impl Href {
     /// let catalog = Href::new("data/catalog.json");
     /// let collection = Href::new("data/collection/collection.json");
     /// assert_eq!(
-    ///     catalog.make_relative(collection.clone()).as_str(),
+    ///     catalog.make_relative(&collection).as_str(),
     ///     "./collection/collection.json"
     /// );
     /// assert_eq!(
-    ///     collection.make_relative(catalog).as_str(),
+    ///     collection.make_relative(&catalog).as_str(),
     ///     "../catalog.json"
     /// );
-    pub fn make_relative(&self, href: Href) -> Href {
+    pub fn make_relative(&self, href: &Href) -> Href {
         match self {
             Href::Url(base) => match href {
                 Href::Url(url) => base
-                    .make_relative(&url)
+                    .make_relative(url)
                     .map(|path| {
                         if path.is_empty() {
                             let (_, file_name) = extract_path_filename(url.path());
@@ -271,23 +272,23 @@ impl Href {
                             Href::Path(path)
                         }
                     })
-                    .unwrap_or_else(|| Href::Url(url)),
+                    .unwrap_or_else(|| Href::Url(url.clone())),
                 // We skip the leading slash on the path to get make relative to go.
                 Href::Path(path) => {
-                    if is_absolute(&path) {
-                        Href::Path(path)
+                    if is_absolute(path) {
+                        Href::Path(path.clone())
                     } else {
-                        Href::Path(make_relative(&base.path()[1..], &path))
+                        Href::Path(make_relative(&base.path()[1..], path))
                     }
                 }
             },
             Href::Path(base) => match href {
-                Href::Url(url) => Href::Url(url),
+                Href::Url(url) => Href::Url(url.clone()),
                 Href::Path(path) => {
-                    if is_absolute(base) && is_absolute(&path) {
-                        Href::Path(path)
+                    if is_absolute(base) && is_absolute(path) {
+                        Href::Path(path.clone())
                     } else {
-                        Href::Path(make_relative(base, &path))
+                        Href::Path(make_relative(base, path))
                     }
                 }
             },
@@ -328,7 +329,7 @@ impl Href {
     /// assert_eq!(href.file_name(), "item.json");
     /// ```
     pub fn file_name(&self) -> &str {
-        extract_path_filename(self.as_str()).1
+        extract_path_filename(self.without_suffix()).1
     }
 
     /// Returns this href's directory.
@@ -341,7 +342,181 @@ impl Href {
     /// assert_eq!(href.directory(), "a/path");
     /// ```
     pub fn directory(&self) -> &str {
-        extract_path_filename(self.as_str()).0
+        extract_path_filename(self.without_suffix()).0
+    }
+
+    /// Returns this href's query string, without the leading `?`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let href = Href::new("http://example.com/search?limit=10#results");
+    /// assert_eq!(href.query(), Some("limit=10"));
+    /// let href = Href::new("item.json?token=abc");
+    /// assert_eq!(href.query(), Some("token=abc"));
+    /// assert_eq!(Href::new("item.json").query(), None);
+    /// ```
+    pub fn query(&self) -> Option<&str> {
+        match self {
+            Href::Url(url) => url.query(),
+            Href::Path(path) => {
+                let (path, _) = path.split_once('#').unwrap_or((path, ""));
+                path.split_once('?').map(|(_, query)| query)
+            }
+        }
+    }
+
+    /// Returns this href's fragment, without the leading `#`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let href = Href::new("http://example.com/search?limit=10#results");
+    /// assert_eq!(href.fragment(), Some("results"));
+    /// assert_eq!(Href::new("item.json#band-1").fragment(), Some("band-1"));
+    /// ```
+    pub fn fragment(&self) -> Option<&str> {
+        match self {
+            Href::Url(url) => url.fragment(),
+            Href::Path(path) => path.split_once('#').map(|(_, fragment)| fragment),
+        }
+    }
+
+    /// Returns this href's decoded query parameters, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let href = Href::new("http://example.com/search?collections=a%2Cb&limit=10");
+    /// assert_eq!(
+    ///     href.query_pairs(),
+    ///     vec![
+    ///         ("collections".to_string(), "a,b".to_string()),
+    ///         ("limit".to_string(), "10".to_string())
+    ///     ]
+    /// );
+    /// ```
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        self.query()
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets a query parameter, replacing any existing values for that key.
+    ///
+    /// The key and value are encoded, so they can contain any characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let mut href = Href::new("http://example.com/search?limit=10&page=1");
+    /// href.set_query_pair("page", "2");
+    /// href.set_query_pair("bbox", "0,0,1,1");
+    /// assert_eq!(href.as_str(), "http://example.com/search?limit=10&page=2&bbox=0%2C0%2C1%2C1");
+    /// ```
+    pub fn set_query_pair(&mut self, key: &str, value: &str) {
+        let mut replaced = false;
+        let mut pairs = Vec::new();
+        for (k, v) in self.query_pairs() {
+            if k != key {
+                pairs.push((k, v));
+            } else if !replaced {
+                pairs.push((k, value.to_string()));
+                replaced = true;
+            }
+        }
+        if !replaced {
+            pairs.push((key.to_string(), value.to_string()));
+        }
+        self.set_query_pairs(pairs);
+    }
+
+    /// Removes all query parameters with this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let mut href = Href::new("./item.json?token=abc&x=1");
+    /// href.remove_query_pair("token");
+    /// assert_eq!(href.as_str(), "./item.json?x=1");
+    /// href.remove_query_pair("x");
+    /// assert_eq!(href.as_str(), "./item.json");
+    /// ```
+    pub fn remove_query_pair(&mut self, key: &str) {
+        let pairs: Vec<_> = self
+            .query_pairs()
+            .into_iter()
+            .filter(|(k, _)| k != key)
+            .collect();
+        self.set_query_pairs(pairs);
+    }
+
+    /// Sets or removes this href's fragment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Href;
+    /// let mut href = Href::new("item.json?x=1");
+    /// href.set_fragment(Some("band-1"));
+    /// assert_eq!(href.as_str(), "item.json?x=1#band-1");
+    /// href.set_fragment(None);
+    /// assert_eq!(href.as_str(), "item.json?x=1");
+    /// ```
+    pub fn set_fragment(&mut self, fragment: Option<&str>) {
+        match self {
+            Href::Url(url) => url.set_fragment(fragment),
+            Href::Path(path) => {
+                if let Some(i) = path.find('#') {
+                    path.truncate(i);
+                }
+                if let Some(fragment) = fragment {
+                    path.push('#');
+                    path.push_str(fragment);
+                }
+            }
+        }
+    }
+
+    fn set_query_pairs(&mut self, pairs: Vec<(String, String)>) {
+        let query = (!pairs.is_empty()).then(|| {
+            form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(pairs)
+                .finish()
+        });
+        match self {
+            Href::Url(url) => url.set_query(query.as_deref()),
+            Href::Path(path) => {
+                let (without_suffix, _) = split_suffix(path);
+                let mut new_path = without_suffix.to_string();
+                if let Some(query) = query {
+                    new_path.push('?');
+                    new_path.push_str(&query);
+                }
+                if let Some((_, fragment)) = path.split_once('#') {
+                    new_path.push('#');
+                    new_path.push_str(fragment);
+                }
+                *path = new_path;
+            }
+        }
+    }
+
+    /// Returns this href without its query and fragment.
+    fn without_suffix(&self) -> &str {
+        match self {
+            Href::Url(url) => &url[..Position::AfterPath],
+            Href::Path(path) => split_suffix(path).0,
+        }
     }
 
     pub(crate) fn ensure_ends_in_slash(&mut self) {
@@ -374,6 +549,12 @@ impl From<PathBuf> for Href {
     }
 }
 
+impl From<&Href> for Href {
+    fn from(href: &Href) -> Href {
+        href.clone()
+    }
+}
+
 impl From<&str> for Href {
     fn from(s: &str) -> Href {
         Href::new(s)
@@ -421,7 +602,7 @@ fn normalize_path(path: String) -> String {
     } else {
         Vec::new()
     };
-    for part in path.split('/') {
+    for part in path.split('/').skip(parts.len()) {
         match part {
             "." => {}
             ".." => {
@@ -433,6 +614,13 @@ fn normalize_path(path: String) -> String {
     parts.join("/")
 }
 
+/// Splits a path into the path itself and its `?query#fragment`, if any.
+fn split_suffix(path: &str) -> (&str, &str) {
+    path.find(['?', '#'])
+        .map(|i| path.split_at(i))
+        .unwrap_or((path, ""))
+}
+
 fn is_absolute(path: &str) -> bool {
     path.starts_with('/')
 }
@@ -542,6 +730,17 @@ mod tests {
         assert_eq!(href.join("catalog.json").unwrap().as_str(), "catalog.json",);
     }
 
+    #[test]
+    fn join_to_absolute_path() {
+        let href = Href::new("/data/catalog.json");
+        let item = href.join("./item/item.json").unwrap();
+        assert_eq!(item.as_str(), "/data/item/item.json");
+        assert_eq!(
+            item.join("../catalog.json").unwrap().as_str(),
+            "/data/catalog.json"
+        );
+    }
+
     #[test]
     fn join_absolute_path() {
         let href = Href::new("data/catalog.json");
@@ -593,16 +792,13 @@ mod tests {
         let base = Href::new("data/catalog.json");
         let target = Href::new("data/extensions-collection/collection.json");
         assert_eq!(
-            base.make_relative(target.clone()).as_str(),
+            base.make_relative(&target).as_str(),
             "./extensions-collection/collection.json"
         );
+        assert_eq!(target.make_relative(&base).as_str(), "../catalog.json");
+        assert_eq!(base.make_relative(&base).as_str(), "./catalog.json");
         assert_eq!(
-            target.make_relative(base.clone()).as_str(),
-            "../catalog.json"
-        );
-        assert_eq!(base.make_relative(base.clone()).as_str(), "./catalog.json");
-        assert_eq!(
-            base.make_relative(Href::new("http://example.com/item.json"))
+            base.make_relative(&Href::new("http://example.com/item.json"))
                 .as_str(),
             "http://example.com/item.json"
         );
@@ -613,17 +809,17 @@ mod tests {
         let base = Href::new("data/catalog.json");
         let target = Href::new("other/extensions-collection/collection.json");
         assert_eq!(
-            base.make_relative(target.clone()).as_str(),
+            base.make_relative(&target).as_str(),
             "../other/extensions-collection/collection.json"
         );
 
         let base = Href::new("/data/catalog.json");
         let target = Href::new("/other/extensions-collection/collection.json");
-        assert_eq!(base.make_relative(target.clone()), target);
+        assert_eq!(base.make_relative(&target), target);
 
         let base = Href::new("http://example.com/catalog.json");
         let target = Href::new("http://example.org/item/item.json");
-        assert_eq!(base.make_relative(target.clone()), target);
+        assert_eq!(base.make_relative(&target), target);
     }
 
     #[test]
@@ -631,13 +827,13 @@ mod tests {
         let base = Href::new("http://example.com/data/catalog.json");
         let target = Href::new("data/extensions-collection/collection.json");
         assert_eq!(
-            base.make_relative(target.clone()).as_str(),
+            base.make_relative(&target).as_str(),
             "./extensions-collection/collection.json"
         );
-        assert_eq!(base.make_relative(base.clone()).as_str(), "./catalog.json");
+        assert_eq!(base.make_relative(&base).as_str(), "./catalog.json");
 
         let target = Href::new("/data/extensions-collection/collection.json");
-        assert_eq!(base.make_relative(target.clone()), target);
+        assert_eq!(base.make_relative(&target), target);
     }
 
     #[test]
@@ -655,4 +851,24 @@ mod tests {
         href.ensure_ends_in_slash();
         assert_eq!(href.as_str(), "https://stac.test/v0/");
     }
+
+    #[test]
+    fn join_path_with_query() {
+        let href = Href::new("data/catalog.json?sig=a/b&x=1");
+        assert_eq!(href.directory(), "data");
+        assert_eq!(href.file_name(), "catalog.json");
+        assert_eq!(
+            href.join("./item/../item.json?token=c/../d#frag")
+                .unwrap()
+                .as_str(),
+            "data/item.json?token=c/../d#frag"
+        );
+    }
+
+    #[test]
+    fn url_file_name_ignores_query() {
+        let href = Href::new("http://example.com/stac/item.json?token=a/b");
+        assert_eq!(href.file_name(), "item.json");
+        assert_eq!(href.directory(), "http://example.com/stac");
+    }
 }
@@ -0,0 +1,298 @@
+//! Utilities for working with STAC object ids.
+//!
+//! Ids are used to build hrefs (e.g. by the [BestPractices](crate::layout::BestPractices) layout strategy), so they should be safe to use as file and directory names.
+//! Ids derived from file names or titles often aren't, so this module provides [slugify] to turn any string into a safe id, and [validate] to check an existing one.
+//! For pipelines that are re-run, the `uuid` feature adds [from_href], [from_name], and [from_properties], which derive stable UUIDv5 ids, so the same input always produces the same id.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::id;
+//! let id = id::slugify("My Scene: 2022/03/01");
+//! assert_eq!(id, "My-Scene-2022-03-01");
+//! assert!(id::validate(&id).is_ok());
+//! assert!(id::validate("a/b").is_err());
+//! ```
+
+use crate::{Error, Result};
+use serde_json::Value;
+#[cfg(feature = "uuid")]
+use {crate::Item, serde_json::Map, uuid::Uuid};
+
+/// The maximum length of an id, in bytes.
+///
+/// Leaves room for a `.json` extension within common file name length limits.
+pub const MAX_LENGTH: usize = 200;
+
+/// Converts a string into a safe id.
+///
+/// Runs of characters other than ASCII letters, digits, `-`, `_`, and `.`
+/// are replaced with a single `-`, leading and trailing `-` and `.` are
+/// removed, and the result is truncated to [MAX_LENGTH]. Case is preserved.
+///
+/// If nothing is left, e.g. for `"日本"` or `"..."`, the slug is the string's
+/// UTF-8 bytes in hex, truncated to [MAX_LENGTH], so it's still non-empty
+/// and stable.
+///
+/// # Examples
+///
+/// ```
+/// use stac::id;
+/// assert_eq!(id::slugify("LC08_L1TP_2022-03-01.TIF"), "LC08_L1TP_2022-03-01.TIF");
+/// assert_eq!(id::slugify("  a title, with spaces  "), "a-title-with-spaces");
+/// assert_eq!(id::slugify("../../etc/passwd"), "etc-passwd");
+/// assert_eq!(id::slugify("日本"), "e697a5e69cac");
+/// ```
+pub fn slugify(s: &str) -> String {
+    let mut slug = String::with_capacity(s.len());
+    for c in s.chars() {
+        if is_valid_char(c) {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let mut slug = slug.trim_matches(['-', '.']).to_string();
+    if slug.len() > MAX_LENGTH {
+        slug.truncate(MAX_LENGTH);
+        slug = slug.trim_end_matches(['-', '.']).to_string();
+    }
+    if slug.is_empty() {
+        slug = s.bytes().map(|byte| format!("{:02x}", byte)).collect();
+        slug.truncate(MAX_LENGTH);
+    }
+    slug
+}
+
+/// Returns true if `id` is a valid id.
+///
+/// # Examples
+///
+/// ```
+/// use stac::id;
+/// assert!(id::is_valid("an-id"));
+/// assert!(!id::is_valid("an id"));
+/// ```
+pub fn is_valid(id: &str) -> bool {
+    validate(id).is_ok()
+}
+
+/// Validates an id.
+///
+/// A valid id is non-empty, no longer than [MAX_LENGTH], only contains ASCII
+/// letters, digits, `-`, `_`, and `.`, and isn't `.` or `..`.
+///
+/// # Examples
+///
+/// ```
+/// use stac::id;
+/// id::validate("an-id").unwrap();
+/// id::validate("").unwrap_err();
+/// id::validate("path/to/an-id").unwrap_err();
+/// ```
+pub fn validate(id: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(Error::InvalidId {
+            id: id.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+    if id.is_empty() {
+        invalid("empty")
+    } else if id.len() > MAX_LENGTH {
+        invalid("too long")
+    } else if id.contains('/') || id.contains('\\') {
+        invalid("contains a path separator")
+    } else if id == "." || id == ".." {
+        invalid("is a relative path")
+    } else if let Some(c) = id.chars().find(|&c| !is_valid_char(c)) {
+        invalid(&format!("contains an invalid character: {:?}", c))
+    } else {
+        Ok(())
+    }
+}
+
+/// Derives a stable id from an href, e.g. the href of an item's main asset.
+///
+/// The id is the UUIDv5 of the href in the URL namespace. Hrefs are used as
+/// is, so use absolute, normalized hrefs to get the same id on every machine.
+///
+/// # Examples
+///
+/// ```
+/// use stac::id;
+/// let id = id::from_href("s3://bucket/scenes/LC08_001/data.tif");
+/// assert_eq!(id, id::from_href("s3://bucket/scenes/LC08_001/data.tif"));
+/// assert_ne!(id, id::from_href("s3://bucket/scenes/LC08_002/data.tif"));
+/// assert!(id::is_valid(&id));
+/// ```
+#[cfg(feature = "uuid")]
+pub fn from_href(href: impl AsRef<str>) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_URL, href.as_ref().as_bytes()).to_string()
+}
+
+/// Derives a stable id from a name within a namespace.
+///
+/// The namespace is typically a URL that identifies the pipeline or
+/// provider, e.g. `https://example.com/landsat`, so the same name in two
+/// pipelines produces two ids.
+///
+/// # Examples
+///
+/// ```
+/// use stac::id;
+/// let a = id::from_name("https://example.com/landsat", "LC08_001");
+/// let b = id::from_name("https://example.com/sentinel", "LC08_001");
+/// assert_ne!(a, b);
+/// ```
+#[cfg(feature = "uuid")]
+pub fn from_name(namespace: &str, name: &str) -> String {
+    let namespace = Uuid::new_v5(&Uuid::NAMESPACE_URL, namespace.as_bytes());
+    Uuid::new_v5(&namespace, name.as_bytes()).to_string()
+}
+
+/// Derives a stable id from some of an item's properties.
+///
+/// The properties are serialized with sorted keys, and missing properties
+/// are `null`, so the id only changes when one of these properties does.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{id, Item};
+/// let mut item = Item::new("an-id");
+/// item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+/// let _ = item
+///     .properties
+///     .additional_fields
+///     .insert("platform".to_string(), "landsat-8".into());
+/// let id = id::from_properties("https://example.com/landsat", &item, &["platform", "datetime"]).unwrap();
+/// item.id = "another-id".to_string();
+/// assert_eq!(
+///     id,
+///     id::from_properties("https://example.com/landsat", &item, &["datetime", "platform"]).unwrap()
+/// );
+/// ```
+#[cfg(feature = "uuid")]
+pub fn from_properties(namespace: &str, item: &Item, keys: &[&str]) -> Result<String> {
+    let Value::Object(properties) = serde_json::to_value(&item.properties)? else {
+        unreachable!("properties serialize to objects");
+    };
+    let mut keys = keys.to_vec();
+    keys.sort_unstable();
+    keys.dedup();
+    let selected: Map<String, Value> = keys
+        .into_iter()
+        .map(|key| {
+            let value = properties.get(key).map(sorted).unwrap_or(Value::Null);
+            (key.to_string(), value)
+        })
+        .collect();
+    Ok(from_name(namespace, &serde_json::to_string(&selected)?))
+}
+
+/// Returns a copy of a value with every object's keys in sorted order.
+pub(crate) fn sorted(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), sorted(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.iter().map(sorted).collect()),
+        _ => value.clone(),
+    }
+}
+
+fn is_valid_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_LENGTH;
+
+    #[test]
+    fn slugify_is_valid() {
+        for s in [
+            "a b c",
+            "ümlaut",
+            "-leading",
+            "trailing.",
+            "a//b",
+            "x".repeat(300).as_str(),
+        ] {
+            let slug = super::slugify(s);
+            super::validate(&slug).unwrap();
+        }
+    }
+
+    #[test]
+    fn slugify_empty() {
+        for s in ["日本", "...", "-"] {
+            let slug = super::slugify(s);
+            super::validate(&slug).unwrap();
+            assert_eq!(slug, super::slugify(s));
+        }
+        assert_ne!(super::slugify("日本"), super::slugify("中国"));
+    }
+
+    #[test]
+    fn slugify_truncates() {
+        let slug = super::slugify(&"a".repeat(MAX_LENGTH + 1));
+        assert_eq!(slug.len(), MAX_LENGTH);
+    }
+
+    #[test]
+    fn slugify_valid_is_noop() {
+        for id in [
+            "simple-item",
+            "CS3-20160503_132131_08",
+            "20201211_223832_CS2",
+        ] {
+            assert_eq!(super::slugify(id), id);
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let _ = super::validate("..").unwrap_err();
+        let _ = super::validate("a\\b").unwrap_err();
+        let _ = super::validate(&"a".repeat(MAX_LENGTH + 1)).unwrap_err();
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn from_href_is_uuid_v5() {
+        assert_eq!(
+            super::from_href("s3://bucket/a.tif"),
+            "84d833c6-a6f3-56c6-b1bf-0e7dfefa1c80"
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn from_properties_ignores_key_order() {
+        let mut a = crate::Item::new("a");
+        let mut b = crate::Item::new("b");
+        b.properties.datetime = a.properties.datetime.clone();
+        let _ = a
+            .properties
+            .additional_fields
+            .insert("nested".to_string(), serde_json::json!({"x": 1, "y": 2}));
+        let _ = b
+            .properties
+            .additional_fields
+            .insert("nested".to_string(), serde_json::json!({"y": 2, "x": 1}));
+        let keys = ["nested", "datetime", "missing"];
+        assert_eq!(
+            super::from_properties("ns", &a, &keys).unwrap(),
+            super::from_properties("ns", &b, &keys).unwrap()
+        );
+    }
+}
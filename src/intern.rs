@@ -0,0 +1,282 @@
+//! Interned strings for values that repeat across many objects.
+//!
+//! Big catalogs repeat the same few strings millions of times: link `rel`s and
+//! media types, asset media types, and item `collection` ids. Those fields are
+//! stored as [Interned] strings, which share a single [`Arc<str>`](Arc) per distinct
+//! value while any object holds it, so repeating a value costs a pointer
+//! instead of a new allocation.
+//!
+//! The pool only keeps weak references, so a value is freed once the last
+//! [Interned] that holds it is dropped. The pool is split into shards, each
+//! behind its own lock, so threads that intern at the same time rarely wait
+//! on each other.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::Link;
+//! let a = Link::new("a.json", "child");
+//! let b = Link::new("b.json", "child");
+//! assert!(a.rel.ptr_eq(&b.rel));
+//! assert_eq!(a.rel, "child");
+//! ```
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+    hash::{BuildHasher, BuildHasherDefault, DefaultHasher},
+    ops::Deref,
+    sync::{Arc, Mutex, OnceLock, Weak},
+};
+
+const SHARDS: usize = 16;
+
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+struct Pool {
+    hasher: BuildHasherDefault<DefaultHasher>,
+    shards: [Mutex<Shard>; SHARDS],
+}
+
+/// Weak references to interned values, bucketed by hash.
+#[derive(Default)]
+struct Shard {
+    buckets: HashMap<u64, Vec<Weak<str>>>,
+    inserts_since_sweep: usize,
+}
+
+impl Pool {
+    fn intern(&self, s: &str) -> Arc<str> {
+        let hash = self.hasher.hash_one(s);
+        let mut shard = self.shards[hash as usize % SHARDS]
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        shard.intern(hash, s)
+    }
+}
+
+impl Shard {
+    fn intern(&mut self, hash: u64, s: &str) -> Arc<str> {
+        let bucket = self.buckets.entry(hash).or_default();
+        bucket.retain(|weak| weak.strong_count() > 0);
+        if let Some(interned) = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|interned| &**interned == s)
+        {
+            return interned;
+        }
+        let interned: Arc<str> = Arc::from(s);
+        bucket.push(Arc::downgrade(&interned));
+        self.inserts_since_sweep += 1;
+        if self.inserts_since_sweep > self.buckets.len() {
+            // Buckets of values that nobody holds anymore are only pruned
+            // when they're looked up again, so sweep the rest every so often.
+            self.buckets.retain(|_, bucket| {
+                bucket.retain(|weak| weak.strong_count() > 0);
+                !bucket.is_empty()
+            });
+            self.inserts_since_sweep = 0;
+        }
+        interned
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buckets
+            .values()
+            .flatten()
+            .filter(|weak| weak.strong_count() > 0)
+            .count()
+    }
+}
+
+/// A shared, immutable string from the intern pool.
+///
+/// Dereferences to [str] and compares equal to string slices and [Strings](String).
+///
+/// # Examples
+///
+/// ```
+/// use stac::intern::Interned;
+/// let interned = Interned::from("application/json");
+/// assert_eq!(interned, "application/json");
+/// assert!(interned.starts_with("application"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interned(Arc<str>);
+
+impl Interned {
+    /// Interns a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::intern::Interned;
+    /// let a = Interned::new("item");
+    /// let b = Interned::new("item");
+    /// assert!(a.ptr_eq(&b));
+    /// ```
+    pub fn new(s: &str) -> Interned {
+        let pool = POOL.get_or_init(|| Pool {
+            hasher: Default::default(),
+            shards: Default::default(),
+        });
+        Interned(pool.intern(s))
+    }
+
+    /// Returns this value as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns true if both values point to the same allocation.
+    pub fn ptr_eq(&self, other: &Interned) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Interned {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Interned {
+    fn from(s: &str) -> Interned {
+        Interned::new(s)
+    }
+}
+
+impl From<String> for Interned {
+    fn from(s: String) -> Interned {
+        Interned::new(&s)
+    }
+}
+
+impl From<Interned> for String {
+    fn from(interned: Interned) -> String {
+        interned.0.to_string()
+    }
+}
+
+impl PartialEq<str> for Interned {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Interned {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Interned {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl Debug for Interned {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for Interned {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl Serialize for Interned {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interned {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Interned, D::Error> {
+        deserializer.deserialize_str(InternedVisitor)
+    }
+}
+
+struct InternedVisitor;
+
+impl Visitor<'_> for InternedVisitor {
+    type Value = Interned;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Interned, E> {
+        Ok(Interned::new(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interned, Shard};
+    use crate::Item;
+    use std::sync::Arc;
+
+    #[test]
+    fn deserialize_shares() {
+        let a: Item = crate::read_item("data/simple-item.json").unwrap();
+        let b: Item = crate::read_item("data/simple-item.json").unwrap();
+        assert!(a.links[0].rel.ptr_eq(&b.links[0].rel));
+    }
+
+    #[test]
+    fn freed_when_dropped() {
+        let mut shard = Shard::default();
+        let a = shard.intern(0, "a-value-nobody-else-uses");
+        let b = shard.intern(0, "a-value-nobody-else-uses");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(shard.len(), 1);
+        let weak = Arc::downgrade(&a);
+        drop(a);
+        drop(b);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(shard.len(), 0);
+        let _ = shard.intern(0, "another-value");
+        assert_eq!(shard.buckets[&0].len(), 1);
+    }
+
+    #[test]
+    fn hash_collisions() {
+        let mut shard = Shard::default();
+        let a = shard.intern(0, "a");
+        let b = shard.intern(0, "b");
+        assert_eq!(&*a, "a");
+        assert_eq!(&*b, "b");
+        assert!(Arc::ptr_eq(&a, &shard.intern(0, "a")));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let interned = Interned::new("root");
+        let value = serde_json::to_value(&interned).unwrap();
+        assert_eq!(value, "root");
+        let interned: Interned = serde_json::from_value(value).unwrap();
+        assert_eq!(interned, "root");
+    }
+}
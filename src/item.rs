@@ -1,6 +1,10 @@
-use crate::{Asset, Link, Properties, STAC_VERSION};
+use crate::{
+    band, datetime, geometry, intern::Interned, Asset, Composite, Error, Link, Object, Properties,
+    Result, SelectedBand, STAC_VERSION,
+};
+use chrono::{DateTime, Utc};
 use geojson::Geometry;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 
@@ -67,7 +71,7 @@ pub struct Item {
     /// for any `Item`s that belong in a specified `Collection`. Must be a non-empty
     /// string.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub collection: Option<String>,
+    pub collection: Option<Interned>,
 
     /// Additional fields not part of the Item specification.
     #[serde(flatten)]
@@ -102,6 +106,403 @@ impl Item {
             additional_fields: Map::new(),
         }
     }
+
+    /// Duplicates this item with a new id, resetting its identity.
+    ///
+    /// The duplicate has no structural links (`child`, `item`, `parent`,
+    /// `root`, and `self`), no `created` or `updated` timestamps, and its
+    /// `bbox` is recomputed from its geometry. This is the common base
+    /// operation for generating derivative items, e.g. one per tile or band.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, Link};
+    /// let mut item = Item::new("an-id");
+    /// item.links.push(Link::parent("../catalog.json"));
+    /// item.links.push(Link::new("http://example.com/license", "license"));
+    /// let duplicate = item.duplicate_with_id("another-id");
+    /// assert_eq!(duplicate.id, "another-id");
+    /// assert_eq!(duplicate.links.len(), 1);
+    /// ```
+    pub fn duplicate_with_id(&self, id: impl ToString) -> Item {
+        let mut item = self.clone();
+        item.id = id.to_string();
+        item.links.retain(|link| !link.is_structural());
+        for key in ["created", "updated"] {
+            let _ = item.properties.additional_fields.remove(key);
+        }
+        if let Some(geometry) = item.geometry.as_ref() {
+            item.bbox = geometry::bbox(geometry);
+        }
+        item
+    }
+
+    /// Returns true if this item has the same content as another item.
+    ///
+    /// Items are compared by their canonical JSON, as described in
+    /// [Object::content_hash](crate::Object::content_hash), so key order,
+    /// link order, structural links, and `updated` timestamps don't matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, Link};
+    /// let item = Item::new("an-id");
+    /// let mut rewritten = item.clone();
+    /// rewritten.links.push(Link::new("../catalog.json", "root"));
+    /// let _ = rewritten
+    ///     .properties
+    ///     .additional_fields
+    ///     .insert("updated".to_string(), "2023-01-01T00:00:00Z".into());
+    /// assert!(item.equivalent(&rewritten));
+    /// ```
+    pub fn equivalent(&self, other: &Item) -> bool {
+        match (
+            Object::from(self.clone()).canonical_value(),
+            Object::from(other.clone()).canonical_value(),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Moves `eo:bands` and `raster:bands` into unified `bands`, in this
+    /// item's properties and in all of its assets.
+    ///
+    /// See [Asset::merge_bands] for how bands are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Item};
+    /// use serde_json::json;
+    /// let mut item = Item::new("an-id");
+    /// let mut asset = Asset::new("data.tif");
+    /// let _ = asset
+    ///     .additional_fields
+    ///     .insert("raster:bands".to_string(), json!([{"data_type": "uint8"}]));
+    /// let _ = item.assets.insert("data".to_string(), asset);
+    /// item.merge_bands().unwrap();
+    /// let bands = item.assets["data"].bands.as_ref().unwrap();
+    /// assert_eq!(bands[0].data_type.as_deref(), Some("uint8"));
+    /// ```
+    pub fn merge_bands(&mut self) -> Result<()> {
+        band::merge(
+            &mut self.properties.additional_fields,
+            &mut self.properties.bands,
+        )?;
+        for asset in self.assets.values_mut() {
+            asset.merge_bands()?;
+        }
+        Ok(())
+    }
+
+    /// Moves unified `bands` into `eo:bands` and `raster:bands`, in this
+    /// item's properties and in all of its assets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Band, Item};
+    /// let mut item = Item::new("an-id");
+    /// item.properties.bands = Some(vec![Band::new("B04").common_name("red")]);
+    /// item.split_bands().unwrap();
+    /// assert!(item.properties.bands.is_none());
+    /// assert!(item.properties.additional_fields.contains_key("eo:bands"));
+    /// ```
+    pub fn split_bands(&mut self) -> Result<()> {
+        band::split(
+            &mut self.properties.additional_fields,
+            &mut self.properties.bands,
+        )?;
+        for asset in self.assets.values_mut() {
+            asset.split_bands()?;
+        }
+        Ok(())
+    }
+
+    /// Finds the asset that holds each band of a [Composite].
+    ///
+    /// Bands are matched by common name, from each asset's unified `bands`
+    /// or its `eo:bands`. If more than one asset has a band, the asset with
+    /// the fewest bands is used, e.g. a single-band `B04` asset rather than a
+    /// three-band `visual` asset, with ties broken by asset key. Returns
+    /// [Error::MissingBand] if no asset has one of the bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Band, Composite, Item};
+    /// let mut item = Item::new("an-id");
+    /// for (key, common_name) in [("B04", "red"), ("B08", "nir")] {
+    ///     let mut asset = Asset::new(format!("./{}.tif", key));
+    ///     asset.bands = Some(vec![Band::new(key).common_name(common_name)]);
+    ///     let _ = item.assets.insert(key.to_string(), asset);
+    /// }
+    /// let bands = item.select_bands(&Composite::ndvi()).unwrap();
+    /// assert_eq!(bands[0].href, "./B08.tif");
+    /// assert_eq!(bands[1].href, "./B04.tif");
+    /// assert!(item.select_bands(&Composite::visual()).is_err());
+    /// ```
+    pub fn select_bands(&self, composite: &Composite) -> Result<Vec<SelectedBand>> {
+        let mut assets: Vec<_> = self
+            .assets
+            .iter()
+            .map(|(key, asset)| {
+                let common_names = band::common_names(&asset.additional_fields, &asset.bands);
+                (key, asset, common_names)
+            })
+            .collect();
+        assets.sort_by(|a, b| a.2.len().cmp(&b.2.len()).then_with(|| a.0.cmp(b.0)));
+        composite
+            .common_names
+            .iter()
+            .map(|common_name| {
+                assets
+                    .iter()
+                    .find_map(|(key, asset, common_names)| {
+                        common_names
+                            .iter()
+                            .position(|name| *name == Some(common_name.as_str()))
+                            .map(|index| SelectedBand {
+                                common_name: common_name.clone(),
+                                key: key.to_string(),
+                                href: asset.href.clone(),
+                                index,
+                            })
+                    })
+                    .ok_or_else(|| Error::MissingBand(common_name.clone()))
+            })
+            .collect()
+    }
+
+    /// Deserializes this item's properties into your own type.
+    ///
+    /// The type sees all of the properties, including `datetime`, so it can
+    /// pick out the fields it knows about and ignore (or
+    /// [flatten](https://serde.rs/attr-flatten.html)) the rest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use stac::Item;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Mission {
+    ///     platform: String,
+    ///     gsd: f64,
+    /// }
+    ///
+    /// let item: Item = stac::read_item("data/core-item.json").unwrap();
+    /// let mission: Mission = item.properties_as().unwrap();
+    /// assert_eq!(mission.platform, "cool_sat1");
+    /// ```
+    pub fn properties_as<T: DeserializeOwned>(&self) -> Result<T> {
+        let value = serde_json::to_value(&self.properties)?;
+        serde_json::from_value(value).map_err(Error::from)
+    }
+
+    /// Serializes your own type into this item's properties.
+    ///
+    /// Each field replaces the property with the same name, and properties
+    /// that the type doesn't have are kept. Returns
+    /// [Error::InvalidProperties] if the type doesn't serialize to a JSON
+    /// object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use stac::Item;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Mission {
+    ///     platform: String,
+    /// }
+    ///
+    /// let mut item = Item::new("an-id");
+    /// item.set_properties_from(&Mission { platform: "cool_sat1".to_string() })
+    ///     .unwrap();
+    /// assert_eq!(item.properties.additional_fields["platform"], "cool_sat1");
+    /// assert!(item.properties.datetime.is_some());
+    /// ```
+    pub fn set_properties_from<T: Serialize>(&mut self, properties: &T) -> Result<()> {
+        let fields = match serde_json::to_value(properties)? {
+            Value::Object(fields) => fields,
+            value => return Err(Error::InvalidProperties(value)),
+        };
+        let Value::Object(mut merged) = serde_json::to_value(&self.properties)? else {
+            unreachable!("properties serialize to an object");
+        };
+        merged.extend(fields);
+        self.properties = serde_json::from_value(Value::Object(merged))?;
+        Ok(())
+    }
+
+    /// Returns this item's geometry as [WKT](https://en.wikipedia.org/wiki/Well-known_text_representation_of_geometry).
+    ///
+    /// Returns `None` if this item doesn't have a geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geojson::{Geometry, Value};
+    /// use stac::Item;
+    /// let mut item = Item::new("an-id");
+    /// assert!(item.geometry_wkt().unwrap().is_none());
+    /// item.geometry = Some(Geometry::new(Value::Point(vec![-105.0, 40.0])));
+    /// assert_eq!(item.geometry_wkt().unwrap().unwrap(), "POINT(-105 40)");
+    /// ```
+    #[cfg(feature = "geo")]
+    pub fn geometry_wkt(&self) -> Result<Option<String>> {
+        use wkt::ToWkt;
+
+        self.geometry
+            .clone()
+            .map(|geometry| {
+                geo_types::Geometry::<f64>::try_from(geometry)
+                    .map(|geometry| geometry.wkt_string())
+                    .map_err(|err| Error::from(Box::new(err)))
+            })
+            .transpose()
+    }
+
+    /// Sets this item's geometry from [WKT](https://en.wikipedia.org/wiki/Well-known_text_representation_of_geometry), and its bbox to the geometry's bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Item;
+    /// let mut item = Item::new("an-id");
+    /// item.set_geometry_from_wkt("LINESTRING(-105 40, -104 41)").unwrap();
+    /// assert_eq!(item.bbox, Some(vec![-105.0, 40.0, -104.0, 41.0]));
+    /// assert!(item.set_geometry_from_wkt("not wkt").is_err());
+    /// ```
+    #[cfg(feature = "geo")]
+    pub fn set_geometry_from_wkt(&mut self, wkt: &str) -> Result<()> {
+        use wkt::TryFromWkt;
+
+        let geometry = geo_types::Geometry::<f64>::try_from_wkt_str(wkt)
+            .map_err(|err| Error::Wkt(err.to_string()))?;
+        let geometry = Geometry::new((&geometry).into());
+        self.bbox = geometry::bbox(&geometry);
+        self.geometry = Some(geometry);
+        Ok(())
+    }
+
+    /// Returns the west, south, east, and north bounds of this item's bbox,
+    /// or of its geometry if it doesn't have a bbox.
+    pub(crate) fn horizontal_bbox(&self) -> Option<[f64; 4]> {
+        self.bbox
+            .as_deref()
+            .and_then(geometry::horizontal)
+            .or_else(|| {
+                self.geometry
+                    .as_ref()
+                    .and_then(geometry::bbox)
+                    .as_deref()
+                    .and_then(geometry::horizontal)
+            })
+    }
+
+    /// Sets `start_datetime` and `end_datetime`, and sets `datetime` to null.
+    ///
+    /// This is the spec's way of describing an item that covers a range of
+    /// time instead of a single instant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use stac::Item;
+    /// let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// let end = Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+    /// let item = Item::new("an-id").with_interval(start, end);
+    /// assert!(item.properties.datetime.is_none());
+    /// assert_eq!(item.interval().unwrap(), Some((start, end)));
+    /// ```
+    pub fn with_interval(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Item {
+        self.set_interval(start, end);
+        self
+    }
+
+    /// Sets `start_datetime` and `end_datetime`, and sets `datetime` to null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use stac::Item;
+    /// let mut item = Item::new("an-id");
+    /// let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// item.set_interval(start, start);
+    /// assert_eq!(item.datetime().unwrap(), None);
+    /// ```
+    pub fn set_interval(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) {
+        self.properties.datetime = None;
+        for (key, datetime) in [("start_datetime", start), ("end_datetime", end)] {
+            let _ = self
+                .properties
+                .additional_fields
+                .insert(key.to_string(), datetime.to_rfc3339().into());
+        }
+    }
+
+    /// Returns this item's `datetime`, or `None` if it's null.
+    ///
+    /// Returns an error if the datetime can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Item;
+    /// let mut item = Item::new("an-id");
+    /// item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+    /// assert!(item.datetime().unwrap().is_some());
+    /// item.properties.datetime = None;
+    /// assert!(item.datetime().unwrap().is_none());
+    /// ```
+    pub fn datetime(&self) -> Result<Option<DateTime<Utc>>> {
+        self.properties
+            .datetime
+            .as_deref()
+            .map(datetime::parse)
+            .transpose()
+    }
+
+    /// Returns this item's `start_datetime` and `end_datetime`, falling back
+    /// to its `datetime`.
+    ///
+    /// Returns `None` if the item has neither, and an error if any of them
+    /// can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Item;
+    /// let mut item = Item::new("an-id");
+    /// item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+    /// let (start, end) = item.interval().unwrap().unwrap();
+    /// assert_eq!(start, end);
+    /// ```
+    pub fn interval(&self) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        let field = |key: &str| {
+            self.properties
+                .additional_fields
+                .get(key)
+                .and_then(|value| value.as_str())
+        };
+        let datetime = self.properties.datetime.as_deref();
+        match (
+            field("start_datetime").or(datetime),
+            field("end_datetime").or(datetime),
+        ) {
+            (Some(start), Some(end)) => Ok(Some((datetime::parse(start)?, datetime::parse(end)?))),
+            _ => Ok(None),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -123,6 +524,39 @@ mod tests {
         assert!(item.links.is_empty());
     }
 
+    #[test]
+    fn duplicate_with_id() {
+        use crate::Link;
+        use geojson::{Geometry, Value};
+        use serde_json::json;
+
+        let mut item = Item::new("an-id");
+        item.geometry = Some(Geometry::new(Value::Point(vec![1.0, 2.0])));
+        item.bbox = Some(vec![-180.0, -90.0, 180.0, 90.0]);
+        item.links.push(Link::new("./an-id.json", "self"));
+        item.links.push(Link::root("../catalog.json"));
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("created".to_string(), json!("2022-03-01T00:00:00Z"));
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("platform".to_string(), json!("landsat-8"));
+        let duplicate = item.duplicate_with_id("another-id");
+        assert_eq!(duplicate.id, "another-id");
+        assert!(duplicate.links.is_empty());
+        assert_eq!(duplicate.bbox.unwrap(), vec![1.0, 2.0, 1.0, 2.0]);
+        assert!(!duplicate
+            .properties
+            .additional_fields
+            .contains_key("created"));
+        assert_eq!(
+            duplicate.properties.additional_fields["platform"],
+            "landsat-8"
+        );
+    }
+
     #[test]
     fn skip_serializing() {
         let item = Item::new("an-id");
@@ -145,4 +579,110 @@ mod tests {
             Item
         );
     }
+
+    #[test]
+    fn select_bands() {
+        use crate::{Composite, Error};
+
+        let mut item: Item = crate::read_item("data/extended-item.json").unwrap();
+        let visual = item.select_bands(&Composite::visual()).unwrap();
+        assert!(visual.iter().all(|band| band.key == "visual"));
+        assert_eq!(
+            visual.iter().map(|band| band.index).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        let ndvi = item.select_bands(&Composite::ndvi()).unwrap();
+        assert_eq!((ndvi[0].key.as_str(), ndvi[0].index), ("analytic", 3));
+        assert_eq!((ndvi[1].key.as_str(), ndvi[1].index), ("visual", 0));
+        assert!(ndvi[0].href.ends_with("_analytic.tif"));
+
+        item.merge_bands().unwrap();
+        assert_eq!(item.select_bands(&Composite::ndvi()).unwrap(), ndvi);
+        assert!(matches!(
+            item.select_bands(&Composite::new("swir", ["swir16"]))
+                .unwrap_err(),
+            Error::MissingBand(name) if name == "swir16"
+        ));
+    }
+
+    #[test]
+    fn typed_properties() {
+        use crate::Error;
+        use serde::{Deserialize, Serialize};
+        use serde_json::{Map, Value};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Mission {
+            platform: String,
+            #[serde(rename = "eo:cloud_cover")]
+            cloud_cover: f64,
+            #[serde(flatten)]
+            rest: Map<String, Value>,
+        }
+
+        let mut item: Item = crate::read_item("data/extended-item.json").unwrap();
+        let mut mission: Mission = item.properties_as().unwrap();
+        assert_eq!(mission.platform, "cool_sat2");
+        assert_eq!(mission.cloud_cover, 1.2);
+        assert!(mission.rest.contains_key("datetime"));
+        assert!(mission.rest.contains_key("gsd"));
+
+        let before = item.clone();
+        item.set_properties_from(&mission).unwrap();
+        assert_eq!(item, before);
+
+        mission.cloud_cover = 3.4;
+        mission.rest.clear();
+        item.set_properties_from(&mission).unwrap();
+        assert_eq!(item.properties.additional_fields["eo:cloud_cover"], 3.4);
+        assert_eq!(item.properties.additional_fields["gsd"], 0.66);
+        assert_eq!(item.properties.datetime, before.properties.datetime);
+
+        assert!(matches!(
+            item.set_properties_from(&42).unwrap_err(),
+            Error::InvalidProperties(_)
+        ));
+        assert!(item.properties_as::<Vec<String>>().is_err());
+    }
+
+    #[test]
+    fn equivalent() {
+        use crate::{Asset, Link};
+
+        let mut item = Item::new("an-id");
+        let mut asset = Asset::new("data.tif");
+        let _ = asset
+            .additional_fields
+            .insert("updated".to_string(), "2023-01-01T00:00:00Z".into());
+        let _ = item.assets.insert("data".to_string(), asset);
+        item.links.push(Link::new("a", "license"));
+        item.links.push(Link::new("b", "license"));
+
+        let mut other = item.clone();
+        other.links.reverse();
+        other.links.push(Link::new("./an-id.json", "self"));
+        let _ = other
+            .assets
+            .get_mut("data")
+            .unwrap()
+            .additional_fields
+            .insert("updated".to_string(), "2024-01-01T00:00:00Z".into());
+        assert!(item.equivalent(&other));
+        #[cfg(feature = "hash")]
+        assert_eq!(
+            crate::Object::from(item.clone()).content_hash().unwrap(),
+            crate::Object::from(other.clone()).content_hash().unwrap()
+        );
+
+        let _ = other
+            .properties
+            .additional_fields
+            .insert("eo:cloud_cover".to_string(), 12.into());
+        assert!(!item.equivalent(&other));
+        #[cfg(feature = "hash")]
+        assert_ne!(
+            crate::Object::from(item).content_hash().unwrap(),
+            crate::Object::from(other).content_hash().unwrap()
+        );
+    }
 }
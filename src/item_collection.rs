@@ -0,0 +1,83 @@
+use crate::{Item, Link};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The type field for [ItemCollections](ItemCollection).
+pub const ITEM_COLLECTION_TYPE: &str = "FeatureCollection";
+
+/// A GeoJSON FeatureCollection of [Items](Item).
+///
+/// `ItemCollections` are returned by STAC APIs, e.g. from a collection's
+/// `items` endpoint or from a search. Large result sets are paginated with a
+/// `next` link.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ItemCollection {
+    /// Type of the GeoJSON Object. MUST be set to `"FeatureCollection"`.
+    pub r#type: String,
+
+    /// The items in this collection.
+    pub features: Vec<Item>,
+
+    /// List of link objects to resources and related URLs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+
+    /// Additional fields not part of the ItemCollection specification.
+    #[serde(flatten)]
+    pub additional_fields: Map<String, Value>,
+}
+
+impl ItemCollection {
+    /// Creates a new `ItemCollection` from a vector of items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, ItemCollection};
+    /// let item_collection = ItemCollection::new(vec![Item::new("an-id")]);
+    /// assert_eq!(item_collection.features.len(), 1);
+    /// ```
+    pub fn new(features: Vec<Item>) -> ItemCollection {
+        ItemCollection {
+            r#type: ITEM_COLLECTION_TYPE.to_string(),
+            features,
+            links: Vec::new(),
+            additional_fields: Map::new(),
+        }
+    }
+
+    /// Returns the `next` link if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{ItemCollection, Link};
+    /// let mut item_collection = ItemCollection::new(Vec::new());
+    /// assert!(item_collection.next_link().is_none());
+    /// item_collection.links.push(Link::new("./page-2.json", "next"));
+    /// assert!(item_collection.next_link().is_some());
+    /// ```
+    pub fn next_link(&self) -> Option<&Link> {
+        self.links.iter().find(|link| link.is_next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemCollection;
+    use crate::Item;
+
+    #[test]
+    fn new() {
+        let item_collection = ItemCollection::new(vec![Item::new("an-id")]);
+        assert_eq!(item_collection.r#type, "FeatureCollection");
+        assert!(item_collection.links.is_empty());
+    }
+
+    #[test]
+    fn skip_serializing() {
+        let item_collection = ItemCollection::new(Vec::new());
+        let value = serde_json::to_value(item_collection).unwrap();
+        assert!(value.get("links").is_none());
+    }
+}
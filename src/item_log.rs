@@ -0,0 +1,491 @@
+//! Append items to logs, for ingest systems that lay out their catalogs later.
+//!
+//! Writing one file per item, and updating its collection's links, is slow
+//! when items arrive one at a time. An [ItemLogWriter] instead appends each
+//! item as a line of newline-delimited JSON to a log for its collection.
+//! Later, [compact] reads the logs back into a [Stac], keeping the latest
+//! version of each item, and the catalog can be rewritten with a
+//! [Layout](crate::Layout).
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::{item_log::{self, ItemLogWriter}, Catalog, Collection, Item, Layout, Stac, Writer};
+//! let directory = tempfile::tempdir().unwrap();
+//! let logs = directory.path().join("logs");
+//! let writer = ItemLogWriter::new(Writer::default(), &logs);
+//! let mut item = Item::new("an-item");
+//! item.collection = Some("a-collection".into());
+//! writer.append(item).unwrap();
+//! assert!(logs.join("a-collection/items.ndjson").exists());
+//!
+//! let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+//! let _ = stac.add_child(root, Collection::new("a-collection")).unwrap();
+//! let items = item_log::compact(&mut stac, &logs).unwrap();
+//! assert_eq!(items.len(), 1);
+//! let mut layout = Layout::new(directory.path().join("stac").to_str().unwrap());
+//! stac.write(&mut layout, &Writer::default()).unwrap();
+//! assert!(directory.path().join("stac/a-collection/an-item/an-item.json").exists());
+//! ```
+
+use crate::{
+    id, intern::Interned, Collection, Error, Handle, Href, HrefObject, Item, Links, Object, Read,
+    Result, Stac, Write,
+};
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::{File, OpenOptions},
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use url::Url;
+
+/// The file name of each item log.
+pub const LOG_FILE_NAME: &str = "items.ndjson";
+
+/// Wraps a [Write], appending items to a log per collection instead of
+/// writing them to their own files.
+///
+/// An item is appended to `{collection}/items.ndjson` below the log
+/// directory, where `{collection}` is its slugified `collection` field.
+/// Items without a `collection` are appended to `items.ndjson` in the log
+/// directory itself. Catalogs, collections, and any other JSON are passed
+/// through to the wrapped writer.
+///
+/// Structural links (`self`, `root`, `parent`, `child`, and `item`) are
+/// dropped, since they're recreated when the logs are laid out. When an item
+/// is written with an href, e.g. by [Stac::write](crate::Stac::write), its
+/// other links and its assets are made absolute first, so they still
+/// resolve after it has moved.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{item_log::ItemLogWriter, Catalog, Item, Layout, Stac, Writer};
+/// let directory = tempfile::tempdir().unwrap();
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+/// let writer = ItemLogWriter::new(Writer::default(), directory.path().join("logs"));
+/// let mut layout = Layout::new(directory.path().join("stac").to_str().unwrap());
+/// stac.write(&mut layout, &writer).unwrap();
+/// assert!(directory.path().join("stac/catalog.json").exists());
+/// assert!(directory.path().join("logs/items.ndjson").exists());
+/// assert!(!directory.path().join("stac/an-item").exists());
+/// ```
+#[derive(Debug)]
+pub struct ItemLogWriter<W: Write> {
+    writer: W,
+    directory: PathBuf,
+    logs: Mutex<HashMap<PathBuf, File>>,
+}
+
+impl<W: Write> ItemLogWriter<W> {
+    /// Creates a writer that appends items to logs below a directory.
+    ///
+    /// The directory and logs are created as items are appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{item_log::ItemLogWriter, Writer};
+    /// let writer = ItemLogWriter::new(Writer::default(), "logs");
+    /// ```
+    pub fn new(writer: W, directory: impl AsRef<Path>) -> ItemLogWriter<W> {
+        ItemLogWriter {
+            writer,
+            directory: directory.as_ref().to_path_buf(),
+            logs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the path of the log for a collection id, or for items without a collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use stac::{item_log::ItemLogWriter, Writer};
+    /// let writer = ItemLogWriter::new(Writer::default(), "logs");
+    /// assert_eq!(
+    ///     writer.log_path(Some("a collection")),
+    ///     Path::new("logs/a-collection/items.ndjson")
+    /// );
+    /// assert_eq!(writer.log_path(None), Path::new("logs/items.ndjson"));
+    /// ```
+    pub fn log_path(&self, collection: Option<&str>) -> PathBuf {
+        match collection {
+            Some(collection) => self
+                .directory
+                .join(id::slugify(collection))
+                .join(LOG_FILE_NAME),
+            None => self.directory.join(LOG_FILE_NAME),
+        }
+    }
+
+    /// Appends an item to the log for its collection.
+    ///
+    /// The line's data is synced to disk with [File::sync_data] before this
+    /// returns, so an item that has been appended survives a crash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{item_log::ItemLogWriter, Item, Writer};
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let writer = ItemLogWriter::new(Writer::default(), directory.path());
+    /// writer.append(Item::new("an-item")).unwrap();
+    /// writer.append(Item::new("another-item")).unwrap();
+    /// let log = std::fs::read_to_string(writer.log_path(None)).unwrap();
+    /// assert_eq!(log.lines().count(), 2);
+    /// ```
+    pub fn append(&self, mut item: Item) -> Result<()> {
+        item.links.retain(|link| !link.is_structural());
+        let path = self.log_path(item.collection.as_deref());
+        let mut line = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+        let mut logs = self.logs.lock().expect("log lock poisoned");
+        let file = match logs.entry(path) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                if let Some(parent) = entry.key().parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(entry.key())?;
+                // Sync the directory too, so a newly created log isn't lost.
+                #[cfg(unix)]
+                if let Some(parent) = entry.key().parent().filter(|p| !p.as_os_str().is_empty()) {
+                    File::open(parent)?.sync_all()?;
+                }
+                entry.insert(file)
+            }
+        };
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for ItemLogWriter<W> {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        match object {
+            HrefObject {
+                object: Object::Item(mut item),
+                href,
+            } => {
+                make_absolute(&mut item, &href)?;
+                self.append(item)
+            }
+            object => self.writer.write(object),
+        }
+    }
+
+    fn write_json_to_url(&self, value: Value, url: &Url) -> Result<()> {
+        self.writer.write_json_to_url(value, url)
+    }
+
+    fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()> {
+        self.writer.write_json_to_path(value, path)
+    }
+
+    fn settings(&self) -> Value {
+        self.writer.settings()
+    }
+}
+
+/// Returns the paths of the item logs in a directory, sorted.
+///
+/// These are the log for items without a collection, and the log in each
+/// subdirectory, as written by an [ItemLogWriter]. Use this to remove the
+/// logs once they've been [compacted](compact) and the catalog has been
+/// written.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{item_log::{self, ItemLogWriter}, Item, Writer};
+/// let directory = tempfile::tempdir().unwrap();
+/// let writer = ItemLogWriter::new(Writer::default(), directory.path());
+/// writer.append(Item::new("an-item")).unwrap();
+/// assert_eq!(item_log::log_paths(directory.path()).unwrap().len(), 1);
+/// ```
+pub fn log_paths(directory: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let directory = directory.as_ref();
+    let mut paths = Vec::new();
+    if !directory.is_dir() {
+        return Ok(paths);
+    }
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let log = path.join(LOG_FILE_NAME);
+            if log.is_file() {
+                paths.push(log);
+            }
+        } else if path.file_name().is_some_and(|name| name == LOG_FILE_NAME) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads the item logs in a directory, keeping the latest version of each item.
+///
+/// Items are identified by their `collection` and `id`, and a line that's
+/// appended later replaces an earlier one. Items are returned in the order
+/// they first appear in the [logs](log_paths). An unfinished last line, e.g.
+/// from a writer that crashed, is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{item_log::{self, ItemLogWriter}, Item, Writer};
+/// let directory = tempfile::tempdir().unwrap();
+/// let writer = ItemLogWriter::new(Writer::default(), directory.path());
+/// let mut item = Item::new("an-item");
+/// writer.append(item.clone()).unwrap();
+/// item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+/// writer.append(item).unwrap();
+/// let items = item_log::read(directory.path()).unwrap();
+/// assert_eq!(items.len(), 1);
+/// assert_eq!(items[0].properties.datetime.as_deref(), Some("2023-01-01T00:00:00Z"));
+/// ```
+pub fn read(directory: impl AsRef<Path>) -> Result<Vec<Item>> {
+    let mut items = IndexMap::new();
+    for path in log_paths(directory)? {
+        let log = std::fs::read_to_string(&path)?;
+        let finished = log.ends_with('\n');
+        let mut lines = log
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .peekable();
+        while let Some(line) = lines.next() {
+            let item: Item = match serde_json::from_str(line) {
+                Ok(item) => item,
+                Err(_) if !finished && lines.peek().is_none() => break,
+                Err(err) => return Err(Error::from(err)),
+            };
+            let key = (item.collection.clone(), item.id.clone());
+            let _ = items.insert(key, item);
+        }
+    }
+    Ok(items.into_values().collect())
+}
+
+/// Adds the items in the logs in a directory to a [Stac], replacing any
+/// older versions that are already in it.
+///
+/// Items are [read](read) from the logs, and matched to items in the
+/// [Stac] by their `collection` and `id`. An item that's already in the
+/// [Stac] is replaced where it is, so items that a [Layout](crate::Layout)
+/// strategy has moved into sub-catalogs stay there. A new item is added to
+/// the collection with the id of its `collection` field, which is created
+/// below the root if there isn't one, or to the root if it has no
+/// collection. This reads the whole tree. Collection extents aren't
+/// updated; use [Validator::fix_extents](crate::validate::Validator::fix_extents)
+/// to expand them. Returns the handles of the compacted items.
+///
+/// The logs are left in place, so remove the [log paths](log_paths) once
+/// the catalog has been written.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{item_log::{self, ItemLogWriter}, Catalog, Item, Stac, Writer};
+/// let directory = tempfile::tempdir().unwrap();
+/// let writer = ItemLogWriter::new(Writer::default(), directory.path());
+/// let mut item = Item::new("an-item");
+/// item.collection = Some("a-collection".into());
+/// writer.append(item).unwrap();
+///
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let items = item_log::compact(&mut stac, directory.path()).unwrap();
+/// let collection = stac.parent(items[0]).unwrap();
+/// assert_eq!(stac.get(collection).unwrap().id(), "a-collection");
+/// assert_eq!(stac.parent(collection), Some(root));
+/// ```
+pub fn compact<R: Read>(stac: &mut Stac<R>, directory: impl AsRef<Path>) -> Result<Vec<Handle>> {
+    let items = read(directory)?;
+    let root = stac.root();
+    let mut existing: HashMap<(Option<Interned>, String), Handle> = HashMap::new();
+    let mut collections: HashMap<String, Handle> = HashMap::new();
+    for result in stac.walk(root).visit(|stac, handle| {
+        match stac.get(handle)? {
+            Object::Item(item) => {
+                let _ = existing.insert((item.collection.clone(), item.id.clone()), handle);
+            }
+            Object::Collection(collection) => {
+                let _ = collections.entry(collection.id.clone()).or_insert(handle);
+            }
+            Object::Catalog(_) => {}
+        }
+        Ok(())
+    }) {
+        result?;
+    }
+    let mut handles = Vec::with_capacity(items.len());
+    for item in items {
+        let key = (item.collection.clone(), item.id.clone());
+        let parent = if let Some(&old) = existing.get(&key) {
+            let parent = stac.parent(old).unwrap_or(root);
+            let _ = stac.remove(old)?;
+            parent
+        } else if let Some(collection) = item.collection.as_deref() {
+            match collections.get(collection) {
+                Some(&handle) => handle,
+                None => {
+                    let handle = stac.add_child(root, Collection::new(collection))?;
+                    let _ = collections.insert(collection.to_string(), handle);
+                    handle
+                }
+            }
+        } else {
+            root
+        };
+        let handle = stac.add_child(parent, item)?;
+        let _ = existing.insert(key, handle);
+        handles.push(handle);
+    }
+    Ok(handles)
+}
+
+/// Makes an item's links and asset hrefs absolute, relative to its href.
+///
+/// Relative paths are resolved against the current directory, since the
+/// item's file is never written.
+fn make_absolute(item: &mut Item, href: &Href) -> Result<()> {
+    let base = if href.is_absolute() {
+        href.clone()
+    } else {
+        Href::from(std::env::current_dir()?.join(href.as_str()))
+    };
+    item.make_links_absolute(base.clone())?;
+    for asset in item.assets.values_mut() {
+        asset.href = base.join(asset.href.as_str())?.into();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemLogWriter;
+    use crate::{Asset, Catalog, Collection, Item, Layout, Link, Stac, Writer};
+    use std::{fs, io::Write};
+
+    fn item(id: &str, collection: Option<&str>, title: &str) -> Item {
+        let mut item = Item::new(id);
+        item.collection = collection.map(Into::into);
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("title".to_string(), title.into());
+        item
+    }
+
+    #[test]
+    fn write_through_stac() {
+        let directory = tempfile::tempdir().unwrap();
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        let mut item = Item::new("an-item");
+        item.collection = Some("a-collection".into());
+        item.links.push(Link::new("./license.txt", "license"));
+        let _ = item
+            .assets
+            .insert("data".to_string(), Asset::new("./data.tif"));
+        let _ = stac.add_child(collection, item).unwrap();
+        let _ = stac.add_child(root, Item::new("loose")).unwrap();
+
+        let stac_directory = directory.path().join("stac");
+        let writer = ItemLogWriter::new(Writer::default(), directory.path().join("logs"));
+        let mut layout = Layout::new(stac_directory.to_str().unwrap());
+        stac.write(&mut layout, &writer).unwrap();
+        assert!(stac_directory.join("a-collection/collection.json").exists());
+
+        let log = fs::read_to_string(writer.log_path(Some("a-collection"))).unwrap();
+        let items: Vec<Item> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].links.len(), 1);
+        let license = &items[0].links[0].href;
+        assert!(license.ends_with("stac/a-collection/an-item/license.txt"));
+        assert!(items[0].assets["data"]
+            .href
+            .ends_with("stac/a-collection/an-item/data.tif"));
+        let log = fs::read_to_string(writer.log_path(None)).unwrap();
+        assert_eq!(log.lines().count(), 1);
+    }
+
+    #[test]
+    fn compact() {
+        let directory = tempfile::tempdir().unwrap();
+        let writer = ItemLogWriter::new(Writer::default(), directory.path());
+        writer.append(item("a", Some("c"), "new")).unwrap();
+        writer.append(item("b", Some("c"), "old")).unwrap();
+        writer.append(item("b", Some("c"), "new")).unwrap();
+        writer.append(item("loose", None, "new")).unwrap();
+        writer.append(item("a", Some("d"), "new")).unwrap();
+        let mut log = fs::OpenOptions::new()
+            .append(true)
+            .open(writer.log_path(Some("c")))
+            .unwrap();
+        write!(log, "{{\"type\": \"Feat").unwrap();
+
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac.add_child(root, Collection::new("c")).unwrap();
+        let part = stac.add_child(collection, Catalog::new("part")).unwrap();
+        let _ = stac.add_child(part, item("a", Some("c"), "old")).unwrap();
+
+        let handles = super::compact(&mut stac, directory.path()).unwrap();
+        assert_eq!(handles.len(), 4);
+        let mut found = Vec::new();
+        for handle in handles {
+            let parent = stac.parent(handle).unwrap();
+            let parent = stac.get(parent).unwrap().id().to_string();
+            let item = stac.get(handle).unwrap();
+            assert_eq!(item.title(), Some("new"));
+            found.push((item.id().to_string(), parent));
+        }
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("a".to_string(), "d".to_string()),
+                ("a".to_string(), "part".to_string()),
+                ("b".to_string(), "c".to_string()),
+                ("loose".to_string(), "root".to_string()),
+            ]
+        );
+        assert_eq!(stac.children(part).len(), 1);
+        assert_eq!(stac.children(root).len(), 3);
+    }
+
+    #[test]
+    fn corrupt_line() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(
+            directory.path().join(super::LOG_FILE_NAME),
+            "not json\n{}\n",
+        )
+        .unwrap();
+        assert!(super::read(directory.path()).is_err());
+        assert!(super::read(directory.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -6,6 +6,7 @@
 //! 2. Creating links between objects in the `Stac`.
 //!
 //! There are various [Strategies](Strategy) that you can use to lay out `Stacs`; the [BestPractices] strategy is the default.
+//! Some strategies, like [Mgrs] and [Tiles], also reorganize the tree by grouping items into sub-catalogs before setting hrefs.
 //!
 //! # Examples
 //!
@@ -39,7 +40,20 @@
 //!     "my/stac/v0/the-collection/an-item/an-item.json"
 //! );
 //! ```
-use crate::{Error, Handle, Href, HrefObject, Link, Object, Read, Result, Stac};
+use crate::{
+    id, media_type,
+    stac::{Stats, Walk},
+    utm, CancellationToken, Catalog, Error, Handle, Href, HrefObject, HrefRewriter, Item,
+    ItemCollection, Link, Links, Object, Provenance, Read, Result, Stac,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The latitude limit of the web mercator projection, in degrees.
+const MAX_LATITUDE: f64 = 85.051_128_779_806_59;
 
 /// Lay out a [Stac].
 ///
@@ -51,6 +65,50 @@ use crate::{Error, Handle, Href, HrefObject, Link, Object, Read, Result, Stac};
 pub struct Layout<S: Strategy> {
     root: Href,
     strategy: S,
+    cancellation: Option<CancellationToken>,
+    provenance: Option<Provenance>,
+    preserve_provenance: bool,
+    stats: Option<HashMap<Handle, Stats>>,
+    with_stats: bool,
+    rewriter: Option<HrefRewriter>,
+    link_decorator: Option<fn(&Object) -> Vec<Link>>,
+    item_page_size: Option<usize>,
+    item_pages: ItemPages,
+    hrefs: Hrefs,
+}
+
+/// The [item pages](Layout::with_item_pages) of a [Layout].
+///
+/// A page is pending until all of its items have been laid out, and is then
+/// ready to be written.
+#[derive(Debug, Default)]
+struct ItemPages {
+    /// The href of the page of each item that hasn't been laid out yet.
+    items: HashMap<Handle, Href>,
+    pending: HashMap<Href, (ItemCollection, usize)>,
+    ready: Vec<(Href, ItemCollection)>,
+}
+
+/// The hrefs that a [Layout] has set, so two objects aren't given the same
+/// one.
+///
+/// Hrefs are compared case-insensitively, since `A-B.json` and `a-b.json`
+/// are the same file on some file systems.
+#[derive(Debug, Default)]
+struct Hrefs(HashMap<String, Handle>);
+
+/// An object rendered by a [Layout], along with its place in the tree.
+#[derive(Debug)]
+pub(crate) struct RenderedNode {
+    pub(crate) handle: Handle,
+    pub(crate) parent: Option<Handle>,
+    pub(crate) href_object: HrefObject,
+
+    /// The href of the item page that this object is a feature of.
+    pub(crate) page: Option<Href>,
+
+    /// The item pages that were finished by laying out this object.
+    pub(crate) pages: Vec<(Href, ItemCollection)>,
 }
 
 /// Sets the [Href] for [Objects](Object) in a [Stac].
@@ -88,10 +146,44 @@ pub trait Strategy {
     fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
     where
         R: Read;
+
+    /// Reorganizes the children of a catalog or collection before they are laid out.
+    ///
+    /// This is called on every catalog and collection before its children's
+    /// hrefs are set, so a strategy can move children into new intermediate
+    /// catalogs, which are then laid out like any other object. The default
+    /// implementation does nothing.
+    ///
+    /// # Examples
+    ///
+    /// [Mgrs] groups items by their grid square:
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac, layout::{Mgrs, Strategy}};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.bbox = Some(vec![14.3, 50.0, 14.5, 50.2]);
+    /// let item = stac.add_child(root, item).unwrap();
+    /// Mgrs::new().partition(&mut stac, root).unwrap();
+    /// let square = stac.parent(item).unwrap();
+    /// assert_eq!(stac.get(square).unwrap().id(), "33UVR");
+    /// ```
+    fn partition<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let _ = (stac, handle);
+        Ok(())
+    }
 }
 
 /// Sets [Hrefs](Href) according to the STAC [best practices](https://github.com/radiantearth/stac-spec/blob/master/best-practices.md#catalog-layout).
 ///
+/// Ids are passed through [id::slugify] before being used as directory and
+/// file names. A [Layout] gives siblings whose ids slugify to the same name,
+/// e.g. `A B` and `a-b`, different hrefs by adding a suffix to the later
+/// one's, e.g. `a-b-2/a-b-2.json`.
+///
 /// # Examples
 ///
 /// ```
@@ -122,6 +214,122 @@ pub struct Rebase {
     old_root: Option<Href>,
 }
 
+/// Puts every object in the root directory.
+///
+/// The root is written to `catalog.json` or `collection.json`, and every
+/// other object to its slugified id, so ids must be unique across the tree.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Catalog, Collection, Href, Item, Stac, layout::{Flat, Strategy}};
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let collection = stac.add_child(root, Collection::new("a-collection")).unwrap();
+/// let item = stac.add_child(collection, Item::new("an-item")).unwrap();
+/// let root_href = Href::new("a/new/root/");
+/// let mut flat = Flat;
+/// for handle in [root, collection, item] {
+///     flat.set_href(&root_href, &mut stac, handle).unwrap();
+/// }
+/// assert_eq!(stac.href(collection).unwrap().as_str(), "a/new/root/a-collection.json");
+/// assert_eq!(stac.href(item).unwrap().as_str(), "a/new/root/an-item.json");
+/// ```
+#[derive(Debug)]
+pub struct Flat;
+
+/// Groups items into sub-catalogs by [MGRS](https://en.wikipedia.org/wiki/Military_Grid_Reference_System) grid square.
+///
+/// Items are moved into a `{zone}/{zone}{band}/{zone}{band}{square}` catalog
+/// hierarchy below their original parent, e.g. `33/33U/33UUP`, and the
+/// intermediate catalogs are created as needed. Hrefs are then set with
+/// [BestPractices]. The grid square comes from the item's `mgrs:utm_zone`,
+/// `mgrs:latitude_band`, and `mgrs:grid_square` properties if they are all
+/// present, or else from the center of its bbox. Items without either, or
+/// outside of the UTM latitude limits, are left where they are.
+///
+/// Use [Mgrs::utm_zones] to group items by UTM zone and hemisphere only.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Catalog, Item, Layout, Stac, layout::Mgrs};
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let mut item = Item::new("an-item");
+/// item.bbox = Some(vec![14.3, 50.0, 14.5, 50.2]);
+/// let item = stac.add_child(root, item).unwrap();
+/// let mut layout = Layout::new("a/new/root").with_strategy(Mgrs::new());
+/// layout.layout(&mut stac).unwrap();
+/// assert_eq!(
+///     stac.href(item).unwrap().as_str(),
+///     "a/new/root/33/33U/33UVR/an-item/an-item.json"
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct Mgrs {
+    utm_zones: bool,
+}
+
+/// Groups items into sub-catalogs by web mercator tile, for catalogs consumed by map tile services.
+///
+/// Each item is put in the tile that contains the center of its bbox, at a
+/// configurable zoom level. [Tiles::xyz] creates a `{z}/{x}/{y}` catalog
+/// hierarchy below the item's original parent, and [Tiles::quadkey] a single
+/// catalog named by the tile's [quadkey](https://learn.microsoft.com/en-us/bingmaps/articles/bing-maps-tile-system).
+/// The intermediate catalogs are created as needed, and hrefs are then set
+/// with [BestPractices]. Items without a bbox are left where they are.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Catalog, Item, Layout, Stac, layout::Tiles};
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let mut item = Item::new("an-item");
+/// item.bbox = Some(vec![-105.1, 39.9, -105.0, 40.0]);
+/// let item = stac.add_child(root, item).unwrap();
+/// let mut layout = Layout::new("a/new/root").with_strategy(Tiles::xyz(4));
+/// layout.layout(&mut stac).unwrap();
+/// assert_eq!(
+///     stac.href(item).unwrap().as_str(),
+///     "a/new/root/4/3/6/an-item/an-item.json"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Tiles {
+    zoom: u8,
+    quadkey: bool,
+}
+
+/// Wraps another strategy, splitting catalogs with too many children into
+/// intermediate sub-catalogs, as recommended by the [best
+/// practices](https://github.com/radiantearth/stac-spec/blob/master/best-practices.md#catalog-layout).
+///
+/// After the wrapped strategy has partitioned a catalog or collection, if it
+/// has more than `max_children` children they're moved, in order, into
+/// sub-catalogs named `{id}-1`, `{id}-2`, and so on. The sub-catalogs are
+/// nested as deeply as needed to keep every catalog at or under the limit.
+/// Hrefs are set by the wrapped strategy. Use
+/// [Layout::with_max_children] to wrap a layout's strategy.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Catalog, Item, Stac, layout::{BestPractices, Split, Strategy}};
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let items: Vec<_> = (0..3)
+///     .map(|i| stac.add_child(root, Item::new(format!("item-{}", i))).unwrap())
+///     .collect();
+/// Split::new(BestPractices, 2).partition(&mut stac, root).unwrap();
+/// let part = stac.parent(items[0]).unwrap();
+/// assert_eq!(stac.get(part).unwrap().id(), "root-1");
+/// assert_eq!(stac.parent(items[2]), Some(stac.children(root)[1]));
+/// ```
+#[derive(Debug)]
+pub struct Split<S: Strategy> {
+    strategy: S,
+    max_children: usize,
+    parts: HashSet<Handle>,
+}
+
 impl Layout<BestPractices> {
     /// Creates a new `Layout`.
     ///
@@ -137,8 +345,18 @@ impl Layout<BestPractices> {
         let mut root = root.into();
         root.ensure_ends_in_slash();
         Self {
-            root: root.into(),
+            root,
             strategy: BestPractices,
+            cancellation: None,
+            provenance: None,
+            preserve_provenance: false,
+            stats: None,
+            with_stats: false,
+            rewriter: None,
+            link_decorator: None,
+            item_page_size: None,
+            item_pages: ItemPages::default(),
+            hrefs: Hrefs::default(),
         }
     }
 }
@@ -153,15 +371,266 @@ impl<S: Strategy> Layout<S> {
     /// let layout = Layout::new("a/new/root").with_strategy(Rebase::default());
     /// ```
     pub fn with_strategy<T>(self, strategy: T) -> Layout<T>
+    where
+        T: Strategy,
+    {
+        self.map_strategy(|_| strategy)
+    }
+
+    /// Splits the children of any catalog or collection with more than
+    /// `max_children` children into intermediate sub-catalogs.
+    ///
+    /// This wraps the current strategy in a [Split], so it composes with
+    /// any strategy, and should be called after
+    /// [with_strategy](Layout::with_strategy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Layout, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// for i in 0..5 {
+    ///     let _ = stac.add_child(root, Item::new(format!("item-{}", i))).unwrap();
+    /// }
+    /// let mut layout = Layout::new("a/new/root").with_max_children(2);
+    /// layout.layout(&mut stac).unwrap();
+    /// assert_eq!(stac.children(root).len(), 2);
+    /// ```
+    pub fn with_max_children(self, max_children: usize) -> Layout<Split<S>> {
+        self.map_strategy(|strategy| Split::new(strategy, max_children))
+    }
+
+    fn map_strategy<T>(self, f: impl FnOnce(S) -> T) -> Layout<T>
     where
         T: Strategy,
     {
         Layout {
             root: self.root,
-            strategy,
+            strategy: f(self.strategy),
+            cancellation: self.cancellation,
+            provenance: self.provenance,
+            preserve_provenance: self.preserve_provenance,
+            stats: None,
+            with_stats: self.with_stats,
+            rewriter: self.rewriter,
+            link_decorator: self.link_decorator,
+            item_page_size: self.item_page_size,
+            item_pages: self.item_pages,
+            hrefs: self.hrefs,
         }
     }
 
+    /// Stops laying out or rendering when the [CancellationToken] is cancelled.
+    ///
+    /// The token is checked before each object, and a cancelled layout returns
+    /// [Error::Cancelled]. Since [Stac::write] renders with its layout, this
+    /// also cancels writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{CancellationToken, Error, Layout, Stac};
+    /// let token = CancellationToken::new();
+    /// let mut layout = Layout::new("a/new/root").with_cancellation(token.clone());
+    /// let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// token.cancel();
+    /// assert!(matches!(layout.layout(&mut stac).unwrap_err(), Error::Cancelled));
+    /// ```
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Layout<S> {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns this layout's [CancellationToken], if one is set.
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    /// Stamps a [Provenance] link to each object's [source
+    /// href](Stac::source_href) before giving it a new one.
+    ///
+    /// Use this when harvesting or mirroring a catalog, so each copied object
+    /// links back to its source. Objects that weren't read from an href, e.g.
+    /// ones that were created in memory, aren't stamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Href, Layout, Links, Provenance, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let mut layout = Layout::new("a/new/root").with_provenance(Provenance::Canonical);
+    /// layout.layout(&mut stac).unwrap();
+    /// let canonical = stac.get(root).unwrap().find_link("canonical").unwrap();
+    /// assert!(Href::new(&canonical.href).is_absolute());
+    /// assert!(canonical.href.ends_with("catalog.json"));
+    /// ```
+    pub fn with_provenance(mut self, provenance: Provenance) -> Layout<S> {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Leaves existing provenance links untouched.
+    ///
+    /// When set, objects that already have a link with the
+    /// [provenance](Layout::with_provenance) rel aren't stamped again, so a
+    /// mirror of a mirror keeps pointing at the original source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Layout, Link, Links, Provenance, Stac};
+    /// let mut catalog = Catalog::new("root");
+    /// catalog.links.push(Link::canonical("http://example.com/catalog.json"));
+    /// let (mut stac, root) = Stac::new(catalog).unwrap();
+    /// stac.set_href(root, "mirror/catalog.json");
+    /// let mut layout = Layout::new("a/new/root")
+    ///     .with_provenance(Provenance::Canonical)
+    ///     .preserve_provenance(true);
+    /// layout.layout(&mut stac).unwrap();
+    /// let canonical = stac.get(root).unwrap().find_link("canonical").unwrap();
+    /// assert_eq!(canonical.href, "http://example.com/catalog.json");
+    /// ```
+    pub fn preserve_provenance(mut self, preserve: bool) -> Layout<S> {
+        self.preserve_provenance = preserve;
+        self
+    }
+
+    /// Sets [Stats] on every catalog and collection, so published catalogs can advertise their size.
+    ///
+    /// The whole tree is partitioned and read before anything is laid out, so
+    /// this doesn't save memory when used with [stream](Layout::stream).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Layout, Object, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let mut layout = Layout::new("a/new/root").with_stats();
+    /// layout.layout(&mut stac).unwrap();
+    /// let Object::Catalog(catalog) = stac.get(root).unwrap() else { unreachable!() };
+    /// assert_eq!(catalog.additional_fields["stats:items"]["count"], 2);
+    /// ```
+    pub fn with_stats(mut self) -> Layout<S> {
+        self.with_stats = true;
+        self
+    }
+
+    /// Rewrites the hrefs of every object's links and assets with an [HrefRewriter].
+    ///
+    /// Structural links are created by the layout, so they aren't rewritten.
+    /// Links are rewritten before [provenance](Layout::with_provenance) is
+    /// stamped, so provenance links still point at where each object was
+    /// read from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Catalog, HrefRewriter, Item, Layout, Stac};
+    /// let mut item = Item::new("an-item");
+    /// let _ = item
+    ///     .assets
+    ///     .insert("data".to_string(), Asset::new("http://origin.test/data.tif"));
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let item = stac.add_child(root, item).unwrap();
+    /// let mut layout = Layout::new("a/new/root").with_rewriter(
+    ///     HrefRewriter::new().prefix("http://origin.test/", "https://mirror.test/"),
+    /// );
+    /// layout.layout(&mut stac).unwrap();
+    /// let item = stac.get(item).unwrap().as_item().unwrap();
+    /// assert_eq!(item.assets["data"].href, "https://mirror.test/data.tif");
+    /// ```
+    pub fn with_rewriter(mut self, rewriter: HrefRewriter) -> Layout<S> {
+        self.rewriter = Some(rewriter);
+        self
+    }
+
+    /// Adds the links returned by a function to every laid-out object.
+    ///
+    /// Use this to inject links that every object in a published catalog
+    /// should have, e.g. to documentation, a web map preview, or a license.
+    /// The function is called with each object after its old structural
+    /// links are removed. A returned link is skipped if the object already
+    /// has a link with the same rel and href, so laying out a catalog again
+    /// doesn't duplicate links. Decorated links aren't
+    /// [rewritten](Layout::with_rewriter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Layout, Link, Links, Object, Stac};
+    /// fn license(object: &Object) -> Vec<Link> {
+    ///     if object.is_item() {
+    ///         vec![Link::new("https://example.com/license.html", "license")]
+    ///     } else {
+    ///         Vec::new()
+    ///     }
+    /// }
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let item = stac.add_child(root, Item::new("an-item")).unwrap();
+    /// let mut layout = Layout::new("a/new/root").with_link_decorator(license);
+    /// layout.layout(&mut stac).unwrap();
+    /// assert!(stac.get(item).unwrap().find_link("license").is_some());
+    /// assert!(stac.get(root).unwrap().find_link("license").is_none());
+    /// ```
+    pub fn with_link_decorator(mut self, decorator: fn(&Object) -> Vec<Link>) -> Layout<S> {
+        self.link_decorator = Some(decorator);
+        self
+    }
+
+    /// Links the items of large catalogs and collections through pages, instead of from the object itself.
+    ///
+    /// If a catalog or collection has more than `page_size` items, it gets a
+    /// single `items` link instead of an `item` link to each of them. The
+    /// `items` link points to the first of a chain of [ItemCollection] pages,
+    /// each with up to `page_size` items as its features and a `next` link to
+    /// the following page. Each feature has a `self` link to the item's own
+    /// file, which is still written. The pages are named after the object's
+    /// file, e.g. `collection-items.json`, `collection-items-2.json`, and so
+    /// on.
+    ///
+    /// A page is finished once all of its items have been laid out.
+    /// [Stac::write] writes each page as soon as it is finished, so only the
+    /// pages being filled are held in memory. Otherwise, finished pages are
+    /// kept until they're [taken](Layout::take_item_pages). Reading the
+    /// catalog back with a [Stac] follows the pages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Collection, Item, Layout, Links, Stac};
+    /// let (mut stac, root) = Stac::new(Collection::new("a-collection")).unwrap();
+    /// for i in 0..5 {
+    ///     let _ = stac.add_child(root, Item::new(format!("item-{}", i))).unwrap();
+    /// }
+    /// let mut layout = Layout::new("a/new/root").with_item_pages(2);
+    /// layout.layout(&mut stac).unwrap();
+    /// let collection = stac.get(root).unwrap();
+    /// assert_eq!(collection.find_link("items").unwrap().href, "./collection-items.json");
+    /// assert!(collection.find_link("item").is_none());
+    /// assert_eq!(layout.take_item_pages().len(), 3);
+    /// ```
+    pub fn with_item_pages(mut self, page_size: usize) -> Layout<S> {
+        self.item_page_size = Some(page_size.max(1));
+        self
+    }
+
+    /// Returns the [item pages](Layout::with_item_pages) finished since they were last taken, with their hrefs.
+    ///
+    /// A page is finished when the last of its items is laid out, so when
+    /// [rendering](Layout::render), a page is ready as soon as its last item
+    /// has been yielded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Layout;
+    /// let mut layout = Layout::new("a/new/root").with_item_pages(100);
+    /// assert!(layout.take_item_pages().is_empty());
+    /// ```
+    pub fn take_item_pages(&mut self) -> Vec<(Href, ItemCollection)> {
+        std::mem::take(&mut self.item_pages.ready)
+    }
+
     /// Lays out a [Stac].
     ///
     /// Note that this function will load the entire STAC catalog into memory.
@@ -180,13 +649,88 @@ impl<S: Strategy> Layout<S> {
     where
         R: Read,
     {
+        let mut walk = stac.walk(stac.root());
+        if let Some(token) = self.cancellation.clone() {
+            walk = walk.cancel_on(token);
+        }
+        for result in walk.visit(|stac, handle| self.layout_one(stac, handle)) {
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Returns the `.json` files below this layout's root that it wouldn't write for a [Stac].
+    ///
+    /// The `Stac` is [laid out](Layout::layout) first, so every object has
+    /// the href it will be written to. Use this before publishing to a
+    /// directory that an earlier version of the catalog was written to, to
+    /// find the files that removed objects left behind. Returns
+    /// [Error::CannotWriteUrl] if the root is a url.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{Layout, Stac};
+    /// let (mut stac, _) = Stac::read("stac/v0/catalog.json").unwrap();
+    /// let mut layout = Layout::new("stac/v0");
+    /// for orphan in layout.orphans(&mut stac).unwrap() {
+    ///     println!("{}", orphan.display());
+    /// }
+    /// ```
+    pub fn orphans<R>(&mut self, stac: &mut Stac<R>) -> Result<Vec<PathBuf>>
+    where
+        R: Read,
+    {
+        let root = match &self.root {
+            Href::Url(url) => return Err(Error::CannotWriteUrl(url.clone())),
+            Href::Path(path) => PathBuf::from(path),
+        };
+        self.layout(stac)?;
+        let mut hrefs = HashSet::new();
         for result in stac
             .walk(stac.root())
-            .visit(|stac, handle| self.layout_one(stac, handle))
+            .visit(|stac, handle| Ok(stac.href(handle).map(|href| PathBuf::from(href.as_str()))))
         {
-            let _ = result?;
+            hrefs.extend(result?);
         }
-        Ok(())
+        let mut paths = Vec::new();
+        json_files(&root, &mut paths)?;
+        paths.retain(|path| !hrefs.contains(path));
+        Ok(paths)
+    }
+
+    /// Deletes the [orphans](Layout::orphans) below this layout's root, and returns them.
+    ///
+    /// Directories that are left empty are deleted too, up to the root.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{Layout, Stac, Writer};
+    /// let (mut stac, _) = Stac::read("stac/v0/catalog.json").unwrap();
+    /// let mut layout = Layout::new("stac/v0");
+    /// let removed = layout.remove_orphans(&mut stac).unwrap();
+    /// stac.write(&mut layout, &Writer::default()).unwrap();
+    /// ```
+    pub fn remove_orphans<R>(&mut self, stac: &mut Stac<R>) -> Result<Vec<PathBuf>>
+    where
+        R: Read,
+    {
+        let orphans = self.orphans(stac)?;
+        let root = PathBuf::from(self.root.as_str());
+        for orphan in &orphans {
+            fs::remove_file(orphan)?;
+            let mut directory = orphan.parent();
+            while let Some(path) = directory.filter(|&path| path != root && path.starts_with(&root))
+            {
+                if fs::remove_dir(path).is_err() {
+                    // Not empty.
+                    break;
+                }
+                directory = path.parent();
+            }
+        }
+        Ok(orphans)
     }
 
     /// Renders a [Stac], consuming it.
@@ -209,76 +753,530 @@ impl<S: Strategy> Layout<S> {
     where
         R: Read + 'a,
     {
-        let root = stac.root();
-        stac.into_walk(root).visit(|stac, handle| {
-            self.layout_one(stac, handle)?;
-            let (href, object) = if handle == stac.root() {
-                (
-                    stac.href(handle).expect("href set during layout").clone(),
-                    stac.get(handle).expect("resolved during layout").clone(),
-                )
-            } else {
-                (
-                    stac.take_href(handle).expect("href set during layout"),
-                    stac.take(handle).expect("resolved during layout"),
-                )
-            };
-            Ok(HrefObject { href, object })
-        })
+        self.render_nodes(stac, false)
+            .map(|result| result.map(|node| node.href_object))
     }
 
-    fn layout_one<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    /// Renders a [Stac], consuming it, and returns each object with its
+    /// place in the tree.
+    ///
+    /// Parents are always returned before their children. If `take_pages`
+    /// is true, finished item pages are returned with the object that
+    /// finished them instead of being kept for [Layout::take_item_pages].
+    pub(crate) fn render_nodes<'a, R>(
+        &'a mut self,
+        stac: Stac<R>,
+        take_pages: bool,
+    ) -> impl Iterator<Item = Result<RenderedNode>> + 'a
     where
-        R: Read,
+        R: Read + 'a,
     {
-        if handle == stac.root() {
-            stac.remove_structural_links(handle)?;
-            self.set_href(stac, handle)?;
-            let root_link = self.create_link(stac, handle, handle, Link::root)?;
-            stac.add_link(handle, root_link)?;
-        }
-        for child in stac.children(handle) {
-            stac.remove_structural_links(child)?;
-            self.set_href(stac, child)?;
-            let child_link = self.create_link(stac, handle, child, Link::child)?;
-            stac.add_link(handle, child_link)?;
-            let root_link = self.create_link(stac, child, stac.root(), Link::root)?;
-            stac.add_link(child, root_link)?;
-            let parent_link = self.create_link(stac, child, handle, Link::parent)?;
-            stac.add_link(child, parent_link)?;
+        let root = stac.root();
+        let mut walk = stac.into_walk(root);
+        if let Some(token) = self.cancellation.clone() {
+            walk = walk.cancel_on(token);
         }
-        // TODO allow for self hrefs
-        Ok(())
+        walk.visit(move |stac, handle| self.render_one(stac, handle, take_pages))
     }
 
-    fn set_href<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    /// Renders a [Stac] in bounded memory, consuming it.
+    ///
+    /// Like [render](Layout::render), but each object's node is dropped from
+    /// the `Stac` once the object has been yielded, and the tree is walked
+    /// depth-first to keep the queue of pending nodes small. Use this to
+    /// render very large catalogs, where keeping every node around isn't
+    /// feasible. The root is kept, since every object links to it, as are
+    /// the hrefs that have been used, so no two objects share one. A node
+    /// with children is kept until its last child has been yielded, so
+    /// every object's parent is known, just like when rendering.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Stac, Layout};
+    /// let (stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// let mut layout = Layout::new("a/new/root");
+    /// let href_objects = layout.stream(stac).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(href_objects.len(), 6);
+    /// ```
+    pub fn stream<'a, R>(
+        &'a mut self,
+        stac: Stac<R>,
+    ) -> impl Iterator<Item = Result<HrefObject>> + 'a
     where
-        R: Read,
+        R: Read + 'a,
     {
-        self.strategy.set_href(&self.root, stac, handle)
+        self.stream_nodes(stac, false)
+            .map(|result| result.map(|node| node.href_object))
     }
 
-    fn create_link<R>(
-        &self,
-        stac: &mut Stac<R>,
-        from: Handle,
-        to: Handle,
-        mut f: impl FnMut(String) -> Link,
-    ) -> Result<Link>
+    /// Renders a [Stac] in bounded memory, consuming it, and returns each
+    /// object with its place in the tree.
+    ///
+    /// If `take_pages` is true, finished item pages are returned with the
+    /// object that finished them, so they can be written right away.
+    pub(crate) fn stream_nodes<'a, R>(
+        &'a mut self,
+        stac: Stac<R>,
+        take_pages: bool,
+    ) -> impl Iterator<Item = Result<RenderedNode>> + 'a
     where
-        R: Read,
+        R: Read + 'a,
     {
-        let from_href = stac.href(from).ok_or(Error::MissingHref)?;
-        let to_href = stac.href(to).ok_or(Error::MissingHref)?;
-        // TODO allow for absolute hrefs
-        let href = from_href.make_relative(to_href.clone());
-        let mut link = f(href.into());
-        link.title = stac.get(to)?.title().map(String::from);
-        Ok(link)
+        let root = stac.root();
+        let mut walk = stac.into_walk(root).depth_first();
+        if let Some(token) = self.cancellation.clone() {
+            walk = walk.cancel_on(token);
+        }
+        let mut previous = None;
+        walk.visit(move |stac, handle| {
+            // The previous node's children were queued after it was visited,
+            // so it's safe to drop it now, unless it still has children to
+            // yield, since they need its handle as their parent. Dropping a
+            // node can leave its own parent childless, so walk up the tree.
+            if let Some(previous) = previous.replace(handle) {
+                let mut next = Some(previous);
+                while let Some(handle) = next.filter(|&handle| stac.children(handle).is_empty()) {
+                    next = stac.parent(handle);
+                    stac.release(handle);
+                }
+            }
+            self.render_one(stac, handle, take_pages)
+        })
     }
-}
 
-impl Strategy for BestPractices {
+    /// Lays out one object and takes it out of the [Stac].
+    ///
+    /// The root is cloned instead, since every object links to it.
+    fn render_one<R>(
+        &mut self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+        take_pages: bool,
+    ) -> Result<RenderedNode>
+    where
+        R: Read,
+    {
+        let page = self.item_pages.items.get(&handle).cloned();
+        self.layout_one(stac, handle)?;
+        let (href, object) = if handle == stac.root() {
+            (
+                stac.href(handle).expect("href set during layout").clone(),
+                stac.get(handle).expect("resolved during layout").clone(),
+            )
+        } else {
+            (
+                stac.take_unique_href(handle)
+                    .expect("href set during layout"),
+                stac.take(handle).expect("resolved during layout"),
+            )
+        };
+        Ok(RenderedNode {
+            handle,
+            parent: stac.parent(handle),
+            href_object: HrefObject { href, object },
+            page,
+            pages: if take_pages {
+                self.take_item_pages()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
+    /// Re-renders the subtree below an object that has already been laid out.
+    ///
+    /// The object keeps its href, its structural links are rebuilt, and it
+    /// and its descendants are laid out and yielded, while the rest of the
+    /// [Stac] is left alone. Use this to rewrite only the part of a catalog
+    /// that changed, e.g. a collection that gained an item. Returns
+    /// [Error::MissingHref] if the object or its parent has no href.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Collection, Item, Layout, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let collection = stac.add_child(root, Collection::new("a-collection")).unwrap();
+    /// let mut layout = Layout::new("a/new/root");
+    /// layout.layout(&mut stac).unwrap();
+    ///
+    /// let _ = stac.add_child(collection, Item::new("an-item")).unwrap();
+    /// let href_objects = layout
+    ///     .render_subtree(&mut stac, collection)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(href_objects.len(), 2);
+    /// ```
+    pub fn render_subtree<'a, R>(
+        &'a mut self,
+        stac: &'a mut Stac<R>,
+        handle: Handle,
+    ) -> impl Iterator<Item = Result<HrefObject>> + 'a
+    where
+        R: Read + 'a,
+    {
+        let relink = if handle == stac.root() {
+            Ok(())
+        } else {
+            self.relink(stac, handle)
+        };
+        let mut walk = stac.walk(handle);
+        if let Some(token) = self.cancellation.clone() {
+            walk = walk.cancel_on(token);
+        }
+        let mut relink = Some(relink);
+        walk.visit(move |stac, handle| {
+            if let Some(relink) = relink.take() {
+                relink?;
+            }
+            self.layout_one(stac, handle)?;
+            Ok(HrefObject {
+                href: stac.href(handle).ok_or(Error::MissingHref)?.clone(),
+                object: stac.get(handle)?.clone(),
+            })
+        })
+    }
+
+    /// Rebuilds the `root` and `parent` links of a laid-out, non-root object.
+    fn relink<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let parent = stac.parent(handle).ok_or(Error::MissingHref)?;
+        stac.remove_structural_links(handle)?;
+        let root_link = self.create_link(stac, handle, stac.root(), Link::root)?;
+        stac.add_link(handle, root_link)?;
+        let parent_link = self.create_link(stac, handle, parent, Link::parent)?;
+        stac.add_link(handle, parent_link)
+    }
+
+    fn layout_one<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        if handle == stac.root() {
+            stac.remove_structural_links(handle)?;
+            self.rewrite(stac, handle)?;
+            self.decorate(stac, handle)?;
+            self.stamp_provenance(stac, handle)?;
+            self.set_href(stac, handle)?;
+            let root_link = self.create_link(stac, handle, handle, Link::root)?;
+            stac.add_link(handle, root_link)?;
+            if self.with_stats {
+                self.prepare_stats(stac, handle)?;
+            }
+        }
+        if !stac.get(handle)?.is_item() {
+            match self.stats.as_mut() {
+                Some(stats) => {
+                    if let Some(stats) = stats.remove(&handle) {
+                        stats.apply(stac.get_mut(handle)?)?;
+                    }
+                }
+                None => self.strategy.partition(stac, handle)?,
+            }
+        }
+        let children = stac.children(handle);
+        let mut paged_items = Vec::new();
+        let page_size = match self.item_page_size {
+            Some(page_size) if children.len() > page_size => {
+                let mut items = 0;
+                for &child in &children {
+                    if stac.get(child)?.is_item() {
+                        items += 1;
+                    }
+                }
+                (items > page_size).then_some(page_size)
+            }
+            _ => None,
+        };
+        for child in children {
+            stac.remove_structural_links(child)?;
+            self.rewrite(stac, child)?;
+            self.decorate(stac, child)?;
+            self.stamp_provenance(stac, child)?;
+            self.set_href(stac, child)?;
+            if page_size.is_some() && stac.get(child)?.is_item() {
+                paged_items.push(child);
+            } else {
+                let child_link = self.create_link(stac, handle, child, Link::child)?;
+                stac.add_link(handle, child_link)?;
+            }
+            let root_link = self.create_link(stac, child, stac.root(), Link::root)?;
+            stac.add_link(child, root_link)?;
+            let parent_link = self.create_link(stac, child, handle, Link::parent)?;
+            stac.add_link(child, parent_link)?;
+        }
+        if let Some(page_size) = page_size {
+            self.add_item_pages(stac, handle, &paged_items, page_size)?;
+        }
+        if let Some(page_href) = self.item_pages.items.remove(&handle) {
+            self.add_to_item_page(stac, handle, page_href)?;
+        }
+        // TODO allow for self hrefs
+        Ok(())
+    }
+
+    /// Links `items` from `handle` through a chain of item pages.
+    fn add_item_pages<R>(
+        &mut self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+        items: &[Handle],
+        page_size: usize,
+    ) -> Result<()>
+    where
+        R: Read,
+    {
+        let href = stac.href(handle).ok_or(Error::MissingHref)?.clone();
+        let stem = href.file_name().trim_end_matches(".json").to_string();
+        let page_hrefs = (1..=items.len().div_ceil(page_size))
+            .map(|n| {
+                if n == 1 {
+                    href.join(format!("./{}-items.json", stem))
+                } else {
+                    href.join(format!("./{}-items-{}.json", stem, n))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.item_pages
+            .items
+            .retain(|_, page_href| !page_hrefs.contains(page_href));
+        self.item_pages
+            .pending
+            .retain(|page_href, _| !page_hrefs.contains(page_href));
+        self.item_pages
+            .ready
+            .retain(|(page_href, _)| !page_hrefs.contains(page_href));
+        let mut items_link = Link::new(href.make_relative(&page_hrefs[0]), "items");
+        items_link.r#type = Some(media_type::GEOJSON.into());
+        let object = stac.get_mut(handle)?;
+        object.remove_links("items");
+        object.add_link(items_link);
+        for (i, chunk) in items.chunks(page_size).enumerate() {
+            let page_href = &page_hrefs[i];
+            let mut page = ItemCollection::new(Vec::with_capacity(chunk.len()));
+            if let Some(next_href) = page_hrefs.get(i + 1) {
+                let mut next_link = Link::new(page_href.make_relative(next_href), "next");
+                next_link.r#type = Some(media_type::GEOJSON.into());
+                page.links.push(next_link);
+            }
+            for &item in chunk {
+                let _ = self.item_pages.items.insert(item, page_href.clone());
+            }
+            let _ = self
+                .item_pages
+                .pending
+                .insert(page_href.clone(), (page, chunk.len()));
+        }
+        Ok(())
+    }
+
+    /// Adds a laid-out item to its item page, which is ready once it has all of its items.
+    fn add_to_item_page<R>(
+        &mut self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+        page_href: Href,
+    ) -> Result<()>
+    where
+        R: Read,
+    {
+        let Some((page, remaining)) = self.item_pages.pending.get_mut(&page_href) else {
+            return Ok(());
+        };
+        let item_href = stac.href(handle).ok_or(Error::MissingHref)?;
+        let mut self_link = Link::new(page_href.make_relative(item_href), "self");
+        self_link.r#type = Some(media_type::GEOJSON.into());
+        let mut item = stac
+            .get(handle)?
+            .clone()
+            .into_item()
+            .expect("only items are paged");
+        item.links.push(self_link);
+        page.features.push(item);
+        *remaining -= 1;
+        if *remaining == 0 {
+            let (page, _) = self
+                .item_pages
+                .pending
+                .remove(&page_href)
+                .expect("just found");
+            self.item_pages.ready.push((page_href, page));
+        }
+        Ok(())
+    }
+
+    fn set_href<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        if handle == stac.root() {
+            self.hrefs = Hrefs::default();
+        }
+        self.strategy.set_href(&self.root, stac, handle)?;
+        let slug = id::slugify(stac.get(handle)?.id());
+        let href = stac.href(handle).ok_or(Error::MissingHref)?;
+        if let Some(href) = self.hrefs.insert(handle, href, &slug) {
+            stac.set_href(handle, href);
+        }
+        Ok(())
+    }
+
+    /// Partitions the whole tree up front, so the stats include any catalogs the strategy adds.
+    fn prepare_stats<R>(&mut self, stac: &mut Stac<R>, root: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let strategy = &mut self.strategy;
+        let mut walk = stac.walk(root).skip_items();
+        if let Some(token) = self.cancellation.clone() {
+            walk = walk.cancel_on(token);
+        }
+        for result in walk.visit(|stac, handle| {
+            if stac.get(handle)?.is_item() {
+                Ok(())
+            } else {
+                strategy.partition(stac, handle)
+            }
+        }) {
+            result?;
+        }
+        self.stats = Some(stac.stats_by_catalog(root)?);
+        Ok(())
+    }
+
+    fn rewrite<R>(&self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        if let Some(rewriter) = &self.rewriter {
+            let _ = rewriter.rewrite_object(stac.get_mut(handle)?);
+        }
+        Ok(())
+    }
+
+    fn decorate<R>(&self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let Some(decorator) = self.link_decorator else {
+            return Ok(());
+        };
+        for link in decorator(stac.get_mut(handle)?) {
+            if !stac
+                .links_by_rel(handle, &link.rel)?
+                .any(|existing| existing.href == link.href)
+            {
+                stac.get_mut(handle)?.links_mut().push(link);
+            }
+        }
+        Ok(())
+    }
+
+    fn stamp_provenance<R>(&self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let (Some(provenance), Some(source)) = (self.provenance, stac.source_href(handle).cloned())
+        else {
+            return Ok(());
+        };
+        if self.preserve_provenance
+            && stac
+                .links_by_rel(handle, provenance.rel())?
+                .next()
+                .is_some()
+        {
+            return Ok(());
+        }
+        provenance.stamp(stac.get_mut(handle)?, &source)
+    }
+
+    fn create_link<R>(
+        &self,
+        stac: &mut Stac<R>,
+        from: Handle,
+        to: Handle,
+        mut f: impl FnMut(String) -> Link,
+    ) -> Result<Link>
+    where
+        R: Read,
+    {
+        let from_href = stac.href(from).ok_or(Error::MissingHref)?;
+        let to_href = stac.href(to).ok_or(Error::MissingHref)?;
+        // TODO allow for absolute hrefs
+        let href = from_href.make_relative(to_href);
+        let mut link = f(href.into());
+        link.title = stac.get(to)?.title().map(String::from);
+        Ok(link)
+    }
+}
+
+impl Hrefs {
+    /// Records an object's href.
+    ///
+    /// If another object already has it, returns a new href where the parts
+    /// named after the object's `slug` get a `-2`, `-3`, etc. suffix.
+    fn insert(&mut self, handle: Handle, href: &Href, slug: &str) -> Option<Href> {
+        let is_taken = |href: &str| {
+            self.0
+                .get(&href.to_lowercase())
+                .is_some_and(|&other| other != handle)
+        };
+        let deduplicated = is_taken(href.as_str()).then(|| {
+            (2..)
+                .map(|n| with_suffix(href.as_str(), slug, n))
+                .find(|href| !is_taken(href))
+                .expect("there are always more suffixes")
+        });
+        let key = deduplicated
+            .as_deref()
+            .unwrap_or(href.as_str())
+            .to_lowercase();
+        // Streaming reuses the handles of released objects, so an href stays
+        // taken even if its handle gets a new one.
+        let _ = self.0.insert(key, handle);
+        deduplicated.map(Href::from)
+    }
+}
+
+/// Adds `-{n}` to the directory and file stem of an href that are named after
+/// `slug`, or to the file stem if neither is.
+fn with_suffix(href: &str, slug: &str, n: usize) -> String {
+    let suffix = |name: &str| format!("{}-{}", name, n);
+    let (directory, file_name) = href.rsplit_once('/').unwrap_or(("", href));
+    let (stem, extension) = file_name
+        .rsplit_once('.')
+        .map(|(stem, extension)| (stem, format!(".{}", extension)))
+        .unwrap_or((file_name, String::new()));
+    let (parent, last) = directory.rsplit_once('/').unwrap_or(("", directory));
+    let (last, stem) = if last == slug || stem == slug {
+        let suffix_if_slug = |name: &str| {
+            if name == slug {
+                suffix(name)
+            } else {
+                name.to_string()
+            }
+        };
+        (suffix_if_slug(last), suffix_if_slug(stem))
+    } else {
+        (last.to_string(), suffix(stem))
+    };
+    let directory = if directory.contains('/') {
+        format!("{}/{}", parent, last)
+    } else {
+        last
+    };
+    if href.contains('/') {
+        format!("{}/{}{}", directory, stem, extension)
+    } else {
+        format!("{}{}", stem, extension)
+    }
+}
+
+impl Strategy for BestPractices {
     fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
     where
         R: Read,
@@ -287,19 +1285,20 @@ impl Strategy for BestPractices {
             let mut directory =
                 String::from(stac.href(parent).ok_or(Error::MissingHref)?.directory());
             directory.push('/');
-            directory.push_str(stac.get(handle)?.id());
+            directory.push_str(&id::slugify(stac.get(handle)?.id()));
             directory.push('/');
             directory
         } else {
             String::from(root.as_str())
         };
         match stac.get(handle)? {
-            Object::Item(item) => href.push_str(&item.id),
+            Object::Item(item) => href.push_str(&id::slugify(&item.id)),
             Object::Catalog(_) => href.push_str("catalog"),
             Object::Collection(_) => href.push_str("collection"),
         }
         href.push_str(".json");
-        Ok(stac.set_href(handle, href))
+        stac.set_href(handle, href);
+        Ok(())
     }
 }
 
@@ -310,24 +1309,437 @@ impl Strategy for Rebase {
     {
         if handle == stac.root() {
             let old_root = stac.take_href(handle).ok_or(Error::MissingHref)?;
-            self.old_root = Some(old_root.clone());
-            Ok(stac.set_href(
-                handle,
-                root.join(self.old_root.as_ref().unwrap().file_name())?,
-            ))
+            stac.set_href(handle, root.join(old_root.file_name())?);
+            self.old_root = Some(old_root);
+            Ok(())
         } else {
             let mut href = stac.href(handle).ok_or(Error::MissingHref)?.clone();
             let root_href = self.old_root.as_ref().ok_or(Error::MissingHref)?;
             href.rebase(root_href, root)?;
-            Ok(stac.set_href(handle, href))
+            stac.set_href(handle, href);
+            Ok(())
+        }
+    }
+}
+
+impl Strategy for Flat {
+    fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        let file_name = if handle == stac.root() {
+            match stac.get(handle)? {
+                Object::Collection(_) => "collection".to_string(),
+                _ => "catalog".to_string(),
+            }
+        } else {
+            id::slugify(stac.get(handle)?.id())
+        };
+        stac.set_href(handle, root.join(format!("{}.json", file_name))?);
+        Ok(())
+    }
+}
+
+impl Mgrs {
+    /// Creates a strategy that groups items by MGRS grid square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::layout::{Layout, Mgrs};
+    /// let layout = Layout::new("a/new/root").with_strategy(Mgrs::new());
+    /// ```
+    pub fn new() -> Mgrs {
+        Mgrs::default()
+    }
+
+    /// Creates a strategy that groups items by UTM zone and hemisphere, e.g. `33N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac, layout::{Mgrs, Strategy}};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.bbox = Some(vec![151.1, -33.9, 151.3, -33.8]);
+    /// let item = stac.add_child(root, item).unwrap();
+    /// Mgrs::utm_zones().partition(&mut stac, root).unwrap();
+    /// let zone = stac.parent(item).unwrap();
+    /// assert_eq!(stac.get(zone).unwrap().id(), "56S");
+    /// ```
+    pub fn utm_zones() -> Mgrs {
+        Mgrs { utm_zones: true }
+    }
+
+    fn catalogs(&self, item: &Item) -> Option<Vec<(String, String)>> {
+        let mgrs = mgrs_properties(item).or_else(|| {
+            let (longitude, latitude) = bbox_center(item.bbox.as_ref()?)?;
+            utm::mgrs(longitude, latitude)
+        })?;
+        let zone = mgrs.zone.to_string();
+        if self.utm_zones {
+            let (hemisphere, name) = if mgrs.latitude_band >= 'N' {
+                ('N', "north")
+            } else {
+                ('S', "south")
+            };
+            return Some(vec![(
+                format!("{}{}", zone, hemisphere),
+                format!("UTM zone {} {}", zone, name),
+            )]);
+        }
+        let band = format!("{}{}", zone, mgrs.latitude_band);
+        let square = format!("{}{}", band, mgrs.grid_square);
+        Some(vec![
+            (zone.clone(), format!("MGRS grid zone {}", zone)),
+            (band.clone(), format!("MGRS grid zone designation {}", band)),
+            (square.clone(), format!("MGRS grid square {}", square)),
+        ])
+    }
+}
+
+impl Strategy for Mgrs {
+    fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        BestPractices.set_href(root, stac, handle)
+    }
+
+    fn partition<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        partition_items(stac, handle, |item| self.catalogs(item))
+    }
+}
+
+impl Tiles {
+    /// The maximum zoom level, at which tiles are a few centimeters across.
+    pub const MAX_ZOOM: u8 = 30;
+
+    /// Creates a strategy that groups items into `{z}/{x}/{y}` tile catalogs.
+    ///
+    /// Zoom levels above [Tiles::MAX_ZOOM] are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::layout::{Layout, Tiles};
+    /// let layout = Layout::new("a/new/root").with_strategy(Tiles::xyz(8));
+    /// ```
+    pub fn xyz(zoom: u8) -> Tiles {
+        Tiles {
+            zoom: zoom.min(Tiles::MAX_ZOOM),
+            quadkey: false,
+        }
+    }
+
+    /// Creates a strategy that groups items into catalogs named by quadkey.
+    ///
+    /// Zoom levels above [Tiles::MAX_ZOOM] are clamped. At zoom zero the
+    /// quadkey is empty, so items are left where they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac, layout::{Strategy, Tiles}};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.bbox = Some(vec![-105.1, 39.9, -105.0, 40.0]);
+    /// let item = stac.add_child(root, item).unwrap();
+    /// Tiles::quadkey(4).partition(&mut stac, root).unwrap();
+    /// let tile = stac.parent(item).unwrap();
+    /// assert_eq!(stac.get(tile).unwrap().id(), "0231");
+    /// ```
+    pub fn quadkey(zoom: u8) -> Tiles {
+        Tiles {
+            zoom: zoom.min(Tiles::MAX_ZOOM),
+            quadkey: true,
+        }
+    }
+
+    fn catalogs(&self, item: &Item) -> Option<Vec<(String, String)>> {
+        let (longitude, latitude) = bbox_center(item.bbox.as_ref()?)?;
+        let (x, y) = tile(longitude, latitude, self.zoom)?;
+        let z = self.zoom;
+        if self.quadkey {
+            let quadkey: String = (1..=z)
+                .rev()
+                .map(|i| {
+                    let digit = ((x >> (i - 1)) & 1) + 2 * ((y >> (i - 1)) & 1);
+                    char::from(b'0' + digit as u8)
+                })
+                .collect();
+            if quadkey.is_empty() {
+                None
+            } else {
+                let title = format!("Tile {}", quadkey);
+                Some(vec![(quadkey, title)])
+            }
+        } else {
+            Some(vec![
+                (z.to_string(), format!("Zoom level {}", z)),
+                (x.to_string(), format!("Tile column {}/{}", z, x)),
+                (y.to_string(), format!("Tile {}/{}/{}", z, x, y)),
+            ])
+        }
+    }
+}
+
+impl Strategy for Tiles {
+    fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        BestPractices.set_href(root, stac, handle)
+    }
+
+    fn partition<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        partition_items(stac, handle, |item| self.catalogs(item))
+    }
+}
+
+impl<S: Strategy> Split<S> {
+    /// Wraps a strategy, splitting catalogs with more than `max_children` children.
+    ///
+    /// A `max_children` less than two is treated as two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::layout::{Mgrs, Split};
+    /// let split = Split::new(Mgrs::new(), 1000);
+    /// ```
+    pub fn new(strategy: S, max_children: usize) -> Split<S> {
+        Split {
+            strategy,
+            max_children: max_children.max(2),
+            parts: HashSet::new(),
+        }
+    }
+
+    /// Moves `children` into `parent`, or into new sub-catalogs of `parent`
+    /// named after `prefix` if there are too many of them.
+    fn split<R>(
+        &mut self,
+        stac: &mut Stac<R>,
+        parent: Handle,
+        children: Vec<Handle>,
+        prefix: &str,
+        taken: &mut HashSet<String>,
+    ) -> Result<()>
+    where
+        R: Read,
+    {
+        if children.len() <= self.max_children {
+            for child in children {
+                stac.connect(parent, child);
+            }
+            return Ok(());
+        }
+        let mut size = self.max_children;
+        while size.saturating_mul(self.max_children) < children.len() {
+            size *= self.max_children;
+        }
+        let mut n = 0;
+        for chunk in children.chunks(size) {
+            let id = loop {
+                n += 1;
+                let id = format!("{}-{}", prefix, n);
+                if taken.insert(id.clone()) {
+                    break id;
+                }
+            };
+            let first = stac.get(chunk[0])?.id().to_string();
+            let last = stac.get(chunk[chunk.len() - 1])?.id().to_string();
+            let mut catalog = Catalog::new(&id);
+            catalog.title = Some(format!("{} to {}", first, last));
+            catalog.description = format!("Objects from {} to {}", first, last);
+            let catalog = stac.add_child(parent, catalog)?;
+            let _ = self.parts.insert(catalog);
+            self.split(stac, catalog, chunk.to_vec(), &id, taken)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Strategy> Strategy for Split<S> {
+    fn set_href<R>(&mut self, root: &Href, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        self.strategy.set_href(root, stac, handle)
+    }
+
+    fn partition<R>(&mut self, stac: &mut Stac<R>, handle: Handle) -> Result<()>
+    where
+        R: Read,
+    {
+        // The sub-catalogs hold what the wrapped strategy already partitioned.
+        if !self.parts.remove(&handle) {
+            self.strategy.partition(stac, handle)?;
+        }
+        let children = stac.children(handle);
+        if children.len() <= self.max_children {
+            return Ok(());
+        }
+        let mut taken = HashSet::new();
+        for &child in &children {
+            let _ = taken.insert(stac.get(child)?.id().to_string());
         }
+        let prefix = stac.get(handle)?.id().to_string();
+        self.split(stac, handle, children, &prefix, &mut taken)
     }
 }
 
+/// Moves the item children of `handle` into the catalogs returned by `path`,
+/// creating the catalogs as needed.
+///
+/// `path` returns the id and title of each catalog, outermost first. Items
+/// whose catalogs are already `handle` and its ancestors are not moved.
+fn partition_items<R>(
+    stac: &mut Stac<R>,
+    handle: Handle,
+    mut path: impl FnMut(&Item) -> Option<Vec<(String, String)>>,
+) -> Result<()>
+where
+    R: Read,
+{
+    let mut catalogs = HashMap::new();
+    let mut scanned = HashSet::new();
+    for child in stac.children(handle) {
+        let path = match stac.get(child)? {
+            Object::Item(item) => path(item),
+            _ => None,
+        };
+        let Some(path) = path else {
+            continue;
+        };
+        if is_partitioned(stac, handle, &path)? {
+            // E.g. by a previous layout.
+            continue;
+        }
+        let mut parent = handle;
+        for (id, title) in path {
+            if scanned.insert(parent) {
+                for existing in stac.children(parent) {
+                    if let Object::Catalog(catalog) = stac.get(existing)? {
+                        let _ = catalogs.insert((parent, catalog.id.clone()), existing);
+                    }
+                }
+            }
+            parent = if let Some(&catalog) = catalogs.get(&(parent, id.clone())) {
+                catalog
+            } else {
+                let mut catalog = Catalog::new(&id);
+                catalog.description = format!("Items in {}", title);
+                catalog.title = Some(title);
+                let catalog = stac.add_child(parent, catalog)?;
+                let _ = catalogs.insert((parent, id), catalog);
+                catalog
+            };
+        }
+        stac.connect(parent, child);
+    }
+    Ok(())
+}
+
+fn is_partitioned<R>(stac: &mut Stac<R>, handle: Handle, path: &[(String, String)]) -> Result<bool>
+where
+    R: Read,
+{
+    let mut ancestor = Some(handle);
+    for (id, _) in path.iter().rev() {
+        let Some(handle) = ancestor else {
+            return Ok(false);
+        };
+        if stac.get(handle)?.id() != id {
+            return Ok(false);
+        }
+        ancestor = stac.parent(handle);
+    }
+    Ok(true)
+}
+
+/// Collects the `.json` files at or below a directory, in sorted order.
+fn json_files(directory: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if !directory.is_dir() {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            json_files(&entry, paths)?;
+        } else if entry
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            paths.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the web mercator tile that contains a longitude and latitude.
+fn tile(longitude: f64, latitude: f64, zoom: u8) -> Option<(u32, u32)> {
+    if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+        return None;
+    }
+    let n = f64::from(1u32 << zoom);
+    let latitude = latitude.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let x = ((longitude + 180.0) / 360.0 * n).floor();
+    let y = ((1.0 - latitude.tan().asinh() / std::f64::consts::PI) / 2.0 * n).floor();
+    Some((x.min(n - 1.0) as u32, y.clamp(0.0, n - 1.0) as u32))
+}
+
+fn mgrs_properties(item: &Item) -> Option<utm::Mgrs> {
+    let fields = &item.properties.additional_fields;
+    let zone = fields.get("mgrs:utm_zone")?.as_u64()?;
+    let latitude_band = fields.get("mgrs:latitude_band")?.as_str()?.chars().next()?;
+    let grid_square = fields.get("mgrs:grid_square")?.as_str()?.to_string();
+    Some(utm::Mgrs {
+        zone: u8::try_from(zone).ok()?,
+        latitude_band,
+        grid_square,
+    })
+}
+
+fn bbox_center(bbox: &[f64]) -> Option<(f64, f64)> {
+    let (west, south, east, north) = match *bbox {
+        [west, south, east, north] | [west, south, _, east, north, _] => (west, south, east, north),
+        _ => return None,
+    };
+    let mut longitude = if west > east {
+        // Crosses the antimeridian.
+        (west + east + 360.0) / 2.0
+    } else {
+        (west + east) / 2.0
+    };
+    if longitude > 180.0 {
+        longitude -= 360.0;
+    }
+    Some((longitude, (south + north) / 2.0))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Layout, Rebase};
-    use crate::{Catalog, Collection, HrefObject, Item, Link, Stac};
+    use super::{Flat, Layout, Mgrs, Rebase, Tiles};
+    use crate::{
+        Catalog, Collection, Handle, Href, HrefObject, Item, ItemCollection, Link, Links, Object,
+        Observer, Provenance, Stac, Writer,
+    };
+    use std::{
+        collections::HashMap,
+        fs::File,
+        sync::{Arc, Mutex},
+    };
 
     #[test]
     fn layout_best_practices() {
@@ -338,7 +1750,7 @@ mod tests {
             .unwrap();
         let item = stac.add_child(collection, Item::new("an-item")).unwrap();
         let mut layout = Layout::new("stac/root");
-        let _ = layout.layout(&mut stac).unwrap();
+        layout.layout(&mut stac).unwrap();
 
         assert_eq!(stac.href(root).unwrap().as_str(), "stac/root/catalog.json");
         let root = stac.get(root).unwrap();
@@ -383,6 +1795,65 @@ mod tests {
         assert_eq!(item.child_links().count(), 0);
     }
 
+    #[test]
+    fn layout_best_practices_unsafe_id() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let item = stac.add_child(root, Item::new("../an item")).unwrap();
+        let mut layout = Layout::new("stac/root");
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(item).unwrap().as_str(),
+            "stac/root/an-item/an-item.json"
+        );
+    }
+
+    #[test]
+    fn layout_best_practices_non_ascii_id() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let item = stac.add_child(root, Item::new("日本")).unwrap();
+        let mut layout = Layout::new("stac/root");
+        layout.layout(&mut stac).unwrap();
+        let slug = crate::id::slugify("日本");
+        assert_eq!(
+            stac.href(item).unwrap().as_str(),
+            format!("stac/root/{}/{}.json", slug, slug)
+        );
+    }
+
+    #[test]
+    fn layout_duplicate_href() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let first = stac.add_child(root, Item::new("A B")).unwrap();
+        let second = stac.add_child(root, Item::new("a-b")).unwrap();
+        let collection = stac.add_child(root, Collection::new("a-b")).unwrap();
+        let nested = stac.add_child(collection, Item::new("a-b")).unwrap();
+        let mut layout = Layout::new("stac/root");
+        for _ in 0..2 {
+            layout.layout(&mut stac).unwrap();
+            assert_eq!(stac.href(first).unwrap().as_str(), "stac/root/A-B/A-B.json");
+            assert_eq!(
+                stac.href(second).unwrap().as_str(),
+                "stac/root/a-b-2/a-b-2.json"
+            );
+            assert_eq!(
+                stac.href(collection).unwrap().as_str(),
+                "stac/root/a-b/collection.json"
+            );
+            assert_eq!(
+                stac.href(nested).unwrap().as_str(),
+                "stac/root/a-b/a-b/a-b.json"
+            );
+        }
+
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let first = stac.add_child(root, Item::new("a b")).unwrap();
+        let second = stac.add_child(root, Item::new("a/b")).unwrap();
+        let mut layout = Layout::new("stac/root").with_strategy(Flat);
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(stac.href(first).unwrap().as_str(), "stac/root/a-b.json");
+        assert_eq!(stac.href(second).unwrap().as_str(), "stac/root/a-b-2.json");
+    }
+
     #[test]
     fn remove_previous_structural() {
         let mut catalog = Catalog::new("root");
@@ -397,6 +1868,112 @@ mod tests {
         assert_eq!(root.child_links().count(), 0);
     }
 
+    #[test]
+    fn item_pages() {
+        let (mut stac, root) = Stac::new(Collection::new("root")).unwrap();
+        let _ = stac.add_child(root, Catalog::new("a-catalog")).unwrap();
+        for i in 0..5 {
+            let _ = stac
+                .add_child(root, Item::new(format!("item-{}", i)))
+                .unwrap();
+        }
+        let directory = tempfile::tempdir().unwrap();
+        let mut layout = Layout::new(directory.path().to_str().unwrap()).with_item_pages(2);
+        stac.write(&mut layout, &Writer::default()).unwrap();
+        assert!(layout.take_item_pages().is_empty());
+
+        let (mut stac, root) = Stac::read(directory.path().join("collection.json")).unwrap();
+        assert_eq!(stac.children(root).len(), 6);
+        let collection = stac.get(root).unwrap();
+        assert!(collection
+            .find_link("items")
+            .unwrap()
+            .href
+            .ends_with("/collection-items.json"));
+        assert!(collection.find_link("item").is_none());
+        assert_eq!(collection.child_links().count(), 1);
+
+        let page: ItemCollection = serde_json::from_reader(
+            File::open(directory.path().join("collection-items.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(page.features.len(), 2);
+        assert_eq!(page.features[0].id, "item-0");
+        let self_link = page.features[0]
+            .links
+            .iter()
+            .find(|link| link.is_self())
+            .unwrap();
+        assert!(self_link.href.ends_with("/item-0/item-0.json"));
+        assert!(page
+            .next_link()
+            .unwrap()
+            .href
+            .ends_with("collection-items-2.json"));
+        assert!(directory.path().join("item-0/item-0.json").exists());
+        let page: ItemCollection = serde_json::from_reader(
+            File::open(directory.path().join("collection-items-3.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(page.features.len(), 1);
+        assert!(page.next_link().is_none());
+    }
+
+    #[test]
+    fn item_pages_are_finished_by_their_last_item() {
+        let (mut stac, root) = Stac::new(Collection::new("root")).unwrap();
+        for i in 0..5 {
+            let _ = stac
+                .add_child(root, Item::new(format!("item-{}", i)))
+                .unwrap();
+        }
+        let mut layout = Layout::new("a/new/root").with_item_pages(2);
+        let finished: Vec<_> = layout
+            .stream_nodes(stac, true)
+            .map(|result| {
+                let node = result.unwrap();
+                (node.href_object.object.id().to_string(), node.pages.len())
+            })
+            .collect();
+        assert_eq!(
+            finished,
+            [
+                ("root".to_string(), 0),
+                ("item-0".to_string(), 0),
+                ("item-1".to_string(), 1),
+                ("item-2".to_string(), 0),
+                ("item-3".to_string(), 1),
+                ("item-4".to_string(), 1),
+            ]
+        );
+        assert!(layout.take_item_pages().is_empty());
+    }
+
+    #[test]
+    fn item_link_title_from_properties() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("an-item");
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("title".to_string(), "An item".into());
+        let _ = stac.add_child(root, item).unwrap();
+        Layout::new("a/new/root").layout(&mut stac).unwrap();
+        let root = stac.get(root).unwrap();
+        let link = root.child_links().next().unwrap();
+        assert_eq!(link.title.as_deref(), Some("An item"));
+    }
+
+    #[test]
+    fn item_pages_under_limit() {
+        let (mut stac, root) = Stac::new(Collection::new("root")).unwrap();
+        let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+        let mut layout = Layout::new("a/new/root").with_item_pages(2);
+        layout.layout(&mut stac).unwrap();
+        assert!(stac.get(root).unwrap().find_link("items").is_none());
+        assert!(layout.take_item_pages().is_empty());
+    }
+
     #[test]
     fn render_spec() {
         let (stac, _) = Stac::read("data/catalog.json").unwrap();
@@ -405,6 +1982,98 @@ mod tests {
         assert_eq!(href_objects.len(), 6);
     }
 
+    #[test]
+    fn stream_matches_render() {
+        let hrefs = |href_objects: Vec<HrefObject>| {
+            let mut hrefs: Vec<_> = href_objects
+                .into_iter()
+                .map(|href_object| href_object.href.as_str().to_string())
+                .collect();
+            hrefs.sort();
+            hrefs
+        };
+        let (stac, _) = Stac::read("data/catalog.json").unwrap();
+        let rendered = Layout::new("a/new/root")
+            .render(stac)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let (stac, _) = Stac::read("data/catalog.json").unwrap();
+        let streamed = Layout::new("a/new/root")
+            .stream(stac)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(hrefs(rendered), hrefs(streamed));
+    }
+
+    #[test]
+    fn stream_keeps_parents_and_bounds_resident_nodes() {
+        #[derive(Debug, Default)]
+        struct Resident {
+            count: usize,
+            max: usize,
+        }
+
+        impl Observer for Resident {
+            fn on_add(&mut self, _: Handle, _: Option<&Href>) {
+                self.count += 1;
+                self.max = self.max.max(self.count);
+            }
+
+            fn on_remove(&mut self, _: Handle, _: Option<&Object>) {
+                self.count -= 1;
+            }
+        }
+
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        for i in 0..4 {
+            let collection = stac
+                .add_child(root, Collection::new(format!("collection-{}", i)))
+                .unwrap();
+            for j in 0..4 {
+                let catalog = stac
+                    .add_child(collection, Catalog::new(format!("catalog-{}-{}", i, j)))
+                    .unwrap();
+                for k in 0..10 {
+                    let _ = stac
+                        .add_child(catalog, Item::new(format!("item-{}-{}-{}", i, j, k)))
+                        .unwrap();
+                }
+            }
+        }
+        let directory = tempfile::tempdir().unwrap();
+        let mut layout = Layout::new(directory.path().to_str().unwrap());
+        stac.write(&mut layout, &Writer::default()).unwrap();
+
+        let (mut stac, _) = Stac::read(directory.path().join("catalog.json")).unwrap();
+        let resident = Arc::new(Mutex::new(Resident::default()));
+        stac.add_observer(resident.clone());
+        let mut layout = Layout::new("a/new/root");
+        let mut ids = HashMap::new();
+        let mut count = 0;
+        for node in layout.stream_nodes(stac, false) {
+            let node = node.unwrap();
+            let id = node.href_object.object.id().to_string();
+            if let Some(parent) = node.parent {
+                let expected = if let Some(rest) = id.strip_prefix("item-") {
+                    format!("catalog-{}", &rest[..rest.rfind('-').unwrap()])
+                } else if let Some(rest) = id.strip_prefix("catalog-") {
+                    format!("collection-{}", &rest[..rest.find('-').unwrap()])
+                } else {
+                    "root".to_string()
+                };
+                assert_eq!(ids[&parent], expected, "parent of {}", id);
+            } else {
+                assert_eq!(id, "root");
+            }
+            let _ = ids.insert(node.handle, id);
+            count += 1;
+        }
+        assert_eq!(count, 181);
+        // Laying out a collection reads its catalogs, which add nodes for
+        // their items, but never more than one collection's items at once.
+        assert!(resident.lock().unwrap().max < 1 + 4 + 4 * 4 + 2 * 4 * 10);
+    }
+
     #[test]
     fn rebase() {
         let catalog = HrefObject::new(Catalog::new("root"), "old/path/catalog.json");
@@ -429,4 +2098,355 @@ mod tests {
             "the/new/root/many/sub/dirs/weird-item-name.json"
         );
     }
+
+    #[test]
+    fn flat() {
+        let (mut stac, root) = Stac::new(Collection::new("root")).unwrap();
+        let child = stac.add_child(root, Catalog::new("child")).unwrap();
+        let item = stac.add_child(child, Item::new("an-item")).unwrap();
+        let mut layout = Layout::new("the/new/root").with_strategy(Flat);
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(root).unwrap().as_str(),
+            "the/new/root/collection.json"
+        );
+        assert_eq!(
+            stac.href(item).unwrap().as_str(),
+            "the/new/root/an-item.json"
+        );
+        let child = stac.get(child).unwrap();
+        assert_eq!(child.child_links().next().unwrap().href, "./an-item.json");
+        assert_eq!(child.parent_link().unwrap().href, "./collection.json");
+    }
+
+    #[test]
+    fn mgrs() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("from-properties");
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("mgrs:utm_zone".to_string(), 33.into());
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("mgrs:latitude_band".to_string(), "U".into());
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("mgrs:grid_square".to_string(), "UP".into());
+        let from_properties = stac.add_child(root, item).unwrap();
+        let mut item = Item::new("from-bbox");
+        item.bbox = Some(vec![13.5, 48.5, 0.0, 13.6, 48.6, 10.0]);
+        let from_bbox = stac.add_child(root, item).unwrap();
+        let no_bbox = stac.add_child(root, Item::new("no-bbox")).unwrap();
+        let mut layout = Layout::new("root").with_strategy(Mgrs::new());
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(from_properties).unwrap().as_str(),
+            "root/33/33U/33UUP/from-properties/from-properties.json"
+        );
+        assert_eq!(
+            stac.href(from_bbox).unwrap().as_str(),
+            "root/33/33U/33UUP/from-bbox/from-bbox.json"
+        );
+        assert_eq!(
+            stac.href(no_bbox).unwrap().as_str(),
+            "root/no-bbox/no-bbox.json"
+        );
+        assert_eq!(stac.parent(from_properties), stac.parent(from_bbox));
+        let zone = stac
+            .parent(stac.parent(stac.parent(from_bbox).unwrap()).unwrap())
+            .unwrap();
+        assert_eq!(stac.get(zone).unwrap().id(), "33");
+        assert_eq!(stac.parent(zone), Some(root));
+        assert_eq!(stac.children(root).len(), 2);
+
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(from_bbox).unwrap().as_str(),
+            "root/33/33U/33UUP/from-bbox/from-bbox.json"
+        );
+        assert_eq!(stac.children(root).len(), 2);
+    }
+
+    #[test]
+    fn mgrs_utm_zones() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("antimeridian");
+        item.bbox = Some(vec![179.5, -17.0, -179.9, -16.5]);
+        let item = stac.add_child(root, item).unwrap();
+        let mut layout = Layout::new("root").with_strategy(Mgrs::utm_zones());
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(item).unwrap().as_str(),
+            "root/60S/antimeridian/antimeridian.json"
+        );
+        let zone = stac.get(stac.parent(item).unwrap()).unwrap();
+        assert_eq!(zone.title(), Some("UTM zone 60 south"));
+    }
+
+    #[test]
+    fn tiles() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("null-island");
+        item.bbox = Some(vec![0.1, 0.1, 0.2, 0.2]);
+        let null_island = stac.add_child(root, item).unwrap();
+        let mut item = Item::new("pole");
+        item.bbox = Some(vec![179.0, 89.0, 180.0, 90.0]);
+        let pole = stac.add_child(root, item).unwrap();
+        let mut layout = Layout::new("root").with_strategy(Tiles::xyz(2));
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(null_island).unwrap().as_str(),
+            "root/2/2/1/null-island/null-island.json"
+        );
+        assert_eq!(
+            stac.href(pole).unwrap().as_str(),
+            "root/2/3/0/pole/pole.json"
+        );
+        let tile = stac.get(stac.parent(pole).unwrap()).unwrap();
+        assert_eq!(tile.title(), Some("Tile 2/3/0"));
+
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(pole).unwrap().as_str(),
+            "root/2/3/0/pole/pole.json"
+        );
+        assert_eq!(stac.children(root).len(), 1);
+    }
+
+    #[test]
+    fn quadkey() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("an-item");
+        item.bbox = Some(vec![-0.2, -0.2, -0.1, -0.1]);
+        let item = stac.add_child(root, item).unwrap();
+        let mut layout = Layout::new("root").with_strategy(Tiles::quadkey(3));
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(item).unwrap().as_str(),
+            "root/211/an-item/an-item.json"
+        );
+
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut item = Item::new("an-item");
+        item.bbox = Some(vec![-0.2, -0.2, -0.1, -0.1]);
+        let item = stac.add_child(root, item).unwrap();
+        let mut layout = Layout::new("root").with_strategy(Tiles::quadkey(0));
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(stac.parent(item), Some(root));
+    }
+
+    #[test]
+    fn via_provenance() {
+        let catalog = HrefObject::new(Catalog::new("root"), "http://example.com/catalog.json");
+        let (mut stac, root) = Stac::new(catalog).unwrap();
+        let mut item = Item::new("an-item");
+        item.links
+            .push(Link::via("http://example.com/original/an-item.json"));
+        let item = stac
+            .add_child(
+                root,
+                HrefObject::new(item, "http://example.com/an-item/an-item.json"),
+            )
+            .unwrap();
+        let mut layout = Layout::new("the/new/root").with_provenance(Provenance::Via);
+        layout.layout(&mut stac).unwrap();
+        let item = stac.get(item).unwrap();
+        let via: Vec<_> = item
+            .links()
+            .iter()
+            .filter(|link| link.is_via())
+            .map(|link| link.href.as_str())
+            .collect();
+        assert_eq!(
+            via,
+            [
+                "http://example.com/original/an-item.json",
+                "http://example.com/an-item/an-item.json"
+            ]
+        );
+        assert_eq!(
+            stac.get(root).unwrap().find_link("via").unwrap().href,
+            "http://example.com/catalog.json"
+        );
+    }
+
+    #[test]
+    fn stats_include_partitions() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        for id in ["a", "b"] {
+            let mut item = Item::new(id);
+            item.bbox = Some(vec![13.5, 48.5, 13.6, 48.6]);
+            let _ = stac.add_child(collection, item).unwrap();
+        }
+        let mut layout = Layout::new("root").with_strategy(Mgrs::new()).with_stats();
+        layout.layout(&mut stac).unwrap();
+        let Object::Catalog(catalog) = stac.get(root).unwrap() else {
+            panic!("not a catalog");
+        };
+        assert_eq!(catalog.additional_fields["stats:catalogs"]["count"], 3);
+        assert_eq!(catalog.additional_fields["stats:collections"]["count"], 1);
+        assert_eq!(catalog.additional_fields["stats:items"]["count"], 2);
+        let Object::Collection(collection) = stac.get(collection).unwrap() else {
+            panic!("not a collection");
+        };
+        assert_eq!(collection.additional_fields["stats:catalogs"]["count"], 3);
+        assert_eq!(collection.additional_fields["stats:items"]["count"], 2);
+    }
+
+    #[test]
+    fn link_decorator() {
+        fn preview(object: &Object) -> Vec<Link> {
+            vec![Link::new(
+                format!("https://example.com/preview/{}", object.id()),
+                "preview",
+            )]
+        }
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        let item = stac.add_child(collection, Item::new("an-item")).unwrap();
+        let mut layout = Layout::new("root").with_link_decorator(preview);
+        layout.layout(&mut stac).unwrap();
+        layout.layout(&mut stac).unwrap();
+        for (handle, id) in [
+            (root, "root"),
+            (collection, "a-collection"),
+            (item, "an-item"),
+        ] {
+            let links: Vec<_> = stac
+                .get(handle)
+                .unwrap()
+                .links()
+                .iter()
+                .filter(|link| link.rel == "preview")
+                .map(|link| link.href.as_str())
+                .collect();
+            assert_eq!(links, [format!("https://example.com/preview/{}", id)]);
+        }
+    }
+
+    #[test]
+    fn split_nested() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let items: Vec<_> = (0..10)
+            .map(|i| {
+                stac.add_child(root, Item::new(format!("item-{}", i)))
+                    .unwrap()
+            })
+            .collect();
+        let mut layout = Layout::new("a/new/root").with_max_children(3);
+        layout.layout(&mut stac).unwrap();
+        let parts: Vec<_> = stac
+            .children(root)
+            .into_iter()
+            .map(|part| stac.get(part).unwrap().id().to_string())
+            .collect();
+        assert_eq!(parts, ["root-1", "root-2"]);
+        assert_eq!(
+            stac.href(items[0]).unwrap().as_str(),
+            "a/new/root/root-1/root-1-1/item-0/item-0.json"
+        );
+        assert_eq!(
+            stac.href(items[9]).unwrap().as_str(),
+            "a/new/root/root-2/item-9/item-9.json"
+        );
+        let mut catalogs = vec![root];
+        while let Some(catalog) = catalogs.pop() {
+            let children = stac.children(catalog);
+            assert!(children.len() <= 3);
+            catalogs.extend(
+                children
+                    .into_iter()
+                    .filter(|&child| !stac.get(child).unwrap().is_item()),
+            );
+        }
+
+        let _ = stac.add_child(root, Item::new("item-10")).unwrap();
+        let _ = stac.add_child(root, Item::new("item-11")).unwrap();
+        layout.layout(&mut stac).unwrap();
+        let parts: Vec<_> = stac
+            .children(root)
+            .into_iter()
+            .map(|part| stac.get(part).unwrap().id().to_string())
+            .collect();
+        assert_eq!(parts, ["root-3", "root-4"]);
+    }
+
+    #[test]
+    fn split_with_mgrs() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let items: Vec<_> = (0..3)
+            .map(|i| {
+                let mut item = Item::new(format!("item-{}", i));
+                item.bbox = Some(vec![14.3, 50.0, 14.5, 50.2]);
+                stac.add_child(root, item).unwrap()
+            })
+            .collect();
+        let mut layout = Layout::new("a/new/root")
+            .with_strategy(Mgrs::new())
+            .with_max_children(2);
+        layout.layout(&mut stac).unwrap();
+        assert_eq!(
+            stac.href(items[2]).unwrap().as_str(),
+            "a/new/root/33/33U/33UVR/33UVR-2/item-2/item-2.json"
+        );
+    }
+
+    #[test]
+    fn orphans() {
+        let directory = tempfile::tempdir().unwrap();
+        let root_directory = directory.path().join("stac").to_string_lossy().into_owned();
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        for id in ["kept", "removed"] {
+            let _ = stac.add_child(collection, Item::new(id)).unwrap();
+        }
+        stac.write(
+            &mut Layout::new(root_directory.as_str()),
+            &Writer::default(),
+        )
+        .unwrap();
+        let notes = directory.path().join("stac").join("notes.txt");
+        std::fs::write(&notes, "not stac").unwrap();
+
+        let (mut stac, _) =
+            Stac::read(format!("{}/catalog.json", root_directory).as_str()).unwrap();
+        let mut layout = Layout::new(root_directory.as_str());
+        assert!(layout.orphans(&mut stac).unwrap().is_empty());
+        let removed = stac
+            .find(stac.root(), |object| object.id() == "removed")
+            .unwrap()
+            .unwrap();
+        let _ = stac.remove(removed).unwrap();
+        let orphan = directory
+            .path()
+            .join("stac/a-collection/removed/removed.json");
+        assert_eq!(layout.orphans(&mut stac).unwrap(), vec![orphan.clone()]);
+        assert_eq!(
+            layout.remove_orphans(&mut stac).unwrap(),
+            vec![orphan.clone()]
+        );
+        assert!(!orphan.exists());
+        assert!(!orphan.parent().unwrap().exists());
+        assert!(directory.path().join("stac/a-collection").exists());
+        assert!(notes.exists());
+        assert!(layout.orphans(&mut stac).unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphans_of_url() {
+        let (mut stac, _) = Stac::new(Catalog::new("root")).unwrap();
+        assert!(Layout::new("http://stac.test/root")
+            .orphans(&mut stac)
+            .is_err());
+    }
 }
@@ -0,0 +1,242 @@
+use crate::{Error, Item, Link, Result};
+use indexmap::IndexMap;
+use serde::{
+    de::{DeserializeOwned, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::value::RawValue;
+use std::fmt::Formatter;
+
+/// An [Item] that only parses its `id` and `links`.
+///
+/// Every other field, e.g. `properties`, `assets`, and `geometry`, is kept as
+/// unparsed JSON, so serializing a `LazyItem` copies those fields verbatim
+/// instead of going through a full parse and serialize cycle. This is useful
+/// for copy or re-layout workflows that only need to change an item's links.
+///
+/// Because the unparsed fields borrow from the input when they are read,
+/// `LazyItem`s must be deserialized directly from JSON text, e.g. with
+/// [serde_json::from_str], not from a [serde_json::Value].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{LazyItem, Link};
+/// let json = std::fs::read_to_string("data/simple-item.json").unwrap();
+/// let mut item: LazyItem = serde_json::from_str(&json).unwrap();
+/// assert_eq!(item.id, "20201211_223832_CS2");
+/// item.links.clear();
+/// item.links.push(Link::root("../catalog.json"));
+/// let json = serde_json::to_string(&item).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyItem {
+    /// Provider identifier.
+    pub id: String,
+
+    /// List of link objects to resources and related URLs.
+    pub links: Vec<Link>,
+
+    /// Every field, in its original order. `id` and `links` are kept as
+    /// `None` placeholders so they're written back in the same place.
+    fields: IndexMap<String, Option<Box<RawValue>>>,
+}
+
+impl LazyItem {
+    /// Returns the unparsed JSON of a field.
+    ///
+    /// Returns `None` for `id` and `links`, which are always parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::LazyItem;
+    /// let json = std::fs::read_to_string("data/simple-item.json").unwrap();
+    /// let item: LazyItem = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(item.raw("type").unwrap().get(), "\"Feature\"");
+    /// ```
+    pub fn raw(&self, key: &str) -> Option<&RawValue> {
+        self.fields.get(key).and_then(|value| value.as_deref())
+    }
+
+    /// Parses a field.
+    ///
+    /// Returns `Ok(None)` if the field doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{LazyItem, Properties};
+    /// let json = std::fs::read_to_string("data/simple-item.json").unwrap();
+    /// let item: LazyItem = serde_json::from_str(&json).unwrap();
+    /// let properties: Properties = item.parse("properties").unwrap().unwrap();
+    /// ```
+    pub fn parse<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.raw(key)
+            .map(|raw| serde_json::from_str(raw.get()))
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// Sets a field to a value, serializing it.
+    ///
+    /// Setting `id` or `links` updates the parsed field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::LazyItem;
+    /// let json = std::fs::read_to_string("data/simple-item.json").unwrap();
+    /// let mut item: LazyItem = serde_json::from_str(&json).unwrap();
+    /// item.set("collection", "a-collection").unwrap();
+    /// assert_eq!(item.raw("collection").unwrap().get(), "\"a-collection\"");
+    /// ```
+    pub fn set(&mut self, key: impl Into<String>, value: impl Serialize) -> Result<()> {
+        let key = key.into();
+        match key.as_str() {
+            "id" => self.id = serde_json::from_value(serde_json::to_value(value)?)?,
+            "links" => self.links = serde_json::from_value(serde_json::to_value(value)?)?,
+            _ => {
+                let raw = serde_json::value::to_raw_value(&value)?;
+                let _ = self.fields.insert(key, Some(raw));
+                return Ok(());
+            }
+        }
+        let _ = self.fields.entry(key).or_insert(None);
+        Ok(())
+    }
+}
+
+impl TryFrom<LazyItem> for Item {
+    type Error = Error;
+
+    fn try_from(lazy_item: LazyItem) -> Result<Item> {
+        let json = serde_json::to_string(&lazy_item)?;
+        serde_json::from_str(&json).map_err(Error::from)
+    }
+}
+
+impl TryFrom<Item> for LazyItem {
+    type Error = Error;
+
+    fn try_from(item: Item) -> Result<LazyItem> {
+        let json = serde_json::to_string(&item)?;
+        serde_json::from_str(&json).map_err(Error::from)
+    }
+}
+
+impl Serialize for LazyItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let mut has_id = false;
+        let mut has_links = false;
+        for (key, value) in &self.fields {
+            match (key.as_str(), value) {
+                ("id", _) => {
+                    has_id = true;
+                    map.serialize_entry(key, &self.id)?;
+                }
+                ("links", _) => {
+                    has_links = true;
+                    map.serialize_entry(key, &self.links)?;
+                }
+                (_, Some(value)) => map.serialize_entry(key, value)?,
+                (_, None) => {}
+            }
+        }
+        if !has_id {
+            map.serialize_entry("id", &self.id)?;
+        }
+        if !has_links {
+            map.serialize_entry("links", &self.links)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyItem {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<LazyItem, D::Error> {
+        deserializer.deserialize_map(LazyItemVisitor)
+    }
+}
+
+struct LazyItemVisitor;
+
+impl<'de> Visitor<'de> for LazyItemVisitor {
+    type Value = LazyItem;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a STAC item")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut access: A,
+    ) -> std::result::Result<LazyItem, A::Error> {
+        use serde::de::Error;
+        let mut id = None;
+        let mut links = None;
+        let mut fields = IndexMap::new();
+        while let Some(key) = access.next_key::<String>()? {
+            match key.as_str() {
+                "id" => {
+                    id = Some(access.next_value()?);
+                    let _ = fields.insert(key, None);
+                }
+                "links" => {
+                    links = Some(access.next_value()?);
+                    let _ = fields.insert(key, None);
+                }
+                _ => {
+                    let value: Box<RawValue> = access.next_value()?;
+                    let _ = fields.insert(key, Some(value));
+                }
+            }
+        }
+        Ok(LazyItem {
+            id: id.ok_or_else(|| A::Error::missing_field("id"))?,
+            links: links.ok_or_else(|| A::Error::missing_field("links"))?,
+            fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LazyItem;
+    use crate::Item;
+
+    #[test]
+    fn roundtrip_is_verbatim() {
+        let json = std::fs::read_to_string("data/extended-item.json").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let compact = serde_json::to_string(&value).unwrap();
+        let item: LazyItem = serde_json::from_str(&compact).unwrap();
+        let output = serde_json::to_string(&item).unwrap();
+        assert!(output.contains(item.raw("properties").unwrap().get()));
+        assert!(output.contains(item.raw("assets").unwrap().get()));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn into_item() {
+        let json = std::fs::read_to_string("data/simple-item.json").unwrap();
+        let expected: Item = serde_json::from_str(&json).unwrap();
+        let item: LazyItem = serde_json::from_str(&json).unwrap();
+        let item: Item = item.try_into().unwrap();
+        assert_eq!(item, expected);
+        let item: LazyItem = item.try_into().unwrap();
+        assert_eq!(item.id, expected.id);
+    }
+
+    #[test]
+    fn missing_links() {
+        let _ = serde_json::from_str::<LazyItem>(r#"{"id": "an-id"}"#).unwrap_err();
+    }
+}
@@ -134,7 +134,6 @@
     missing_docs,
     non_ascii_idents,
     noop_method_call,
-    pointer_structural_match,
     rust_2021_incompatible_closure_captures,
     rust_2021_incompatible_or_patterns,
     rust_2021_prefixes_incompatible_syntax,
@@ -154,39 +153,95 @@
 )]
 #![warn(rustdoc::missing_doc_code_examples)]
 
+#[cfg(feature = "adapters")]
+pub mod adapters;
+#[cfg(feature = "reqwest")]
+pub mod api_client;
+#[cfg(feature = "archive")]
+pub mod archive;
 mod asset;
+pub mod auth;
+mod band;
+mod cancel;
 mod catalog;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod collection;
+#[cfg(feature = "csv")]
+pub mod csv;
+mod datetime;
 mod error;
+pub mod extensions;
 mod extent;
+mod geometry;
 mod href;
+mod http;
+pub mod id;
+pub mod intern;
 mod item;
+mod item_collection;
+pub mod item_log;
 pub mod layout;
+mod lazy_item;
 mod link;
 pub mod media_type;
 mod object;
 mod properties;
 mod provider;
 mod read;
+pub mod retry;
+mod rewrite;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod sidecar;
 pub mod stac;
+mod template;
+pub mod temporal;
+mod utm;
+pub mod validate;
+mod version;
 mod write;
 
 pub use {
-    crate::stac::{Handle, Stac, Walk},
-    asset::Asset,
+    crate::stac::{Estimate, Handle, Observer, Stac, Walk, WriteOrder},
+    asset::{Asset, Assets},
+    band::{Band, Composite, SelectedBand},
+    cancel::CancellationToken,
     catalog::{Catalog, CATALOG_TYPE},
     collection::{Collection, COLLECTION_TYPE},
     error::Error,
     extent::{Extent, SpatialExtent, TemporalExtent},
     href::Href,
+    http::HttpClient,
     item::{Item, ITEM_TYPE},
+    item_collection::{ItemCollection, ITEM_COLLECTION_TYPE},
     layout::Layout,
-    link::Link,
+    lazy_item::LazyItem,
+    link::{Link, LinkIndex, Links, Provenance},
     object::{HrefObject, Object, ObjectHrefTuple},
     properties::Properties,
     provider::Provider,
-    read::{Read, Reader},
-    write::{Write, Writer},
+    read::{Read, Reader, STDIN},
+    rewrite::HrefRewriter,
+    template::ItemTemplate,
+    version::Version,
+    write::{NdjsonWriter, Write, Writer, STDOUT},
+};
+
+#[cfg(feature = "async")]
+pub use {
+    crate::stac::{AsyncOnly, AsyncStac},
+    read::{AsyncRead, AsyncReader},
+    write::{AsyncWrite, AsyncWriter},
+};
+
+#[cfg(feature = "hash")]
+pub use write::CheckpointWriter;
+
+#[cfg(feature = "reqwest")]
+pub use {
+    api_client::ApiClient,
+    write::{HttpWriter, Precondition},
 };
 
 /// The default STAC version supported by this library.
@@ -207,48 +262,67 @@ pub fn read(href: impl Into<Href>) -> Result<HrefObject> {
     reader.read(href)
 }
 
-/// Reads a [Catalog] from an [Href].
+/// Reads a [Catalog] from an href.
+///
+/// Returns [Error::TypeMismatch] if the href is a collection or an item.
 ///
 /// # Examples
 ///
 /// ```
-/// use stac::Href;
-/// let catalog = stac::read_catalog(&Href::new("data/catalog.json")).unwrap();
+/// let catalog = stac::read_catalog("data/catalog.json").unwrap();
+/// assert!(stac::read_catalog("data/collection.json").is_err());
 /// ```
-pub fn read_catalog(href: &Href) -> Result<Catalog> {
+pub fn read_catalog(href: impl Into<Href>) -> Result<Catalog> {
     let reader = Reader::default();
-    reader.read_object(href)
+    reader.read_object(&href.into())
 }
 
-/// Reads a [Collection] from an [Href].
+/// Reads a [Collection] from an href.
+///
+/// Returns [Error::TypeMismatch] if the href is a catalog or an item.
 ///
 /// # Examples
 ///
 /// ```
-/// use stac::Href;
-/// let collection = stac::read_collection(&Href::new("data/collection.json")).unwrap();
+/// let collection = stac::read_collection("data/collection.json").unwrap();
+/// assert!(stac::read_collection("data/catalog.json").is_err());
 /// ```
-pub fn read_collection(href: &Href) -> Result<Collection> {
+pub fn read_collection(href: impl Into<Href>) -> Result<Collection> {
     let reader = Reader::default();
-    reader.read_object(href)
+    reader.read_object(&href.into())
 }
 
-/// Reads an [Item] from an [Href].
+/// Reads an [Item] from an href.
+///
+/// Returns [Error::TypeMismatch] if the href is a catalog or a collection.
 ///
 /// # Examples
 ///
 /// ```
-/// use stac::Href;
-/// let item = stac::read_item(&Href::new("data/simple-item.json")).unwrap();
+/// let item = stac::read_item("data/simple-item.json").unwrap();
+/// assert!(stac::read_item("data/collection.json").is_err());
 /// ```
-pub fn read_item(href: &Href) -> Result<Item> {
+pub fn read_item(href: impl Into<Href>) -> Result<Item> {
     let reader = Reader::default();
-    reader.read_object(href)
+    reader.read_object(&href.into())
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::Error;
     use criterion as _;
+    use tokio as _;
+
+    #[test]
+    fn read_wrong_type() {
+        match crate::read_item("data/catalog.json").unwrap_err() {
+            Error::TypeMismatch { expected, actual } => {
+                assert_eq!(expected, "Feature");
+                assert_eq!(actual, "Catalog");
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
 
     macro_rules! roundtrip {
         ($function:ident, $filename:expr, $object:ident) => {
@@ -1,12 +1,19 @@
-use crate::media_type;
+use crate::{
+    intern::Interned, media_type, Catalog, Collection, Href, Item, ItemCollection, Object, Result,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 
+const CANONICAL_REL: &str = "canonical";
 const CHILD_REL: &str = "child";
 const ITEM_REL: &str = "item";
+const ITEMS_REL: &str = "items";
+const NEXT_REL: &str = "next";
 const PARENT_REL: &str = "parent";
 const ROOT_REL: &str = "root";
 const SELF_REL: &str = "self";
+const VIA_REL: &str = "via";
 
 /// This object describes a relationship with another entity.
 ///
@@ -27,12 +34,12 @@ pub struct Link {
     /// See the chapter on ["Relation
     /// types"](https://github.com/radiantearth/stac-spec/blob/master/item-spec/item-spec.md#relation-types)
     /// in the STAC spec for more information.
-    pub rel: String,
+    pub rel: Interned,
 
     /// [Media type](crate::media_type) of the referenced entity.
     #[serde(rename = "type")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub r#type: Option<String>,
+    pub r#type: Option<Interned>,
 
     /// A human readable title to be used in rendered displays of the link.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -57,7 +64,7 @@ impl Link {
     pub fn new(href: impl ToString, rel: impl ToString) -> Link {
         Link {
             href: href.to_string(),
-            rel: rel.to_string(),
+            rel: rel.to_string().into(),
             r#type: None,
             title: None,
             additional_fields: Map::new(),
@@ -67,8 +74,8 @@ impl Link {
     fn new_json(href: impl ToString, rel: impl ToString) -> Link {
         Link {
             href: href.to_string(),
-            rel: rel.to_string(),
-            r#type: Some(media_type::JSON.to_string()),
+            rel: rel.to_string().into(),
+            r#type: Some(media_type::JSON.into()),
             title: None,
             additional_fields: Map::new(),
         }
@@ -116,6 +123,39 @@ impl Link {
         Link::new_json(href, ITEM_REL)
     }
 
+    /// Creates a new canonical link with JSON media type.
+    ///
+    /// A `canonical` link points to the authoritative copy of an object, e.g.
+    /// the source of a mirrored catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Link, media_type};
+    /// let canonical = Link::canonical("http://example.com/catalog.json");
+    /// assert!(canonical.is_canonical());
+    /// assert_eq!(canonical.r#type.as_ref().unwrap(), media_type::JSON);
+    /// ```
+    pub fn canonical(href: impl ToString) -> Link {
+        Link::new_json(href, CANONICAL_REL)
+    }
+
+    /// Creates a new via link with JSON media type.
+    ///
+    /// A `via` link points to a source of the information in an object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Link, media_type};
+    /// let via = Link::via("http://example.com/catalog.json");
+    /// assert!(via.is_via());
+    /// assert_eq!(via.r#type.as_ref().unwrap(), media_type::JSON);
+    /// ```
+    pub fn via(href: impl ToString) -> Link {
+        Link::new_json(href, VIA_REL)
+    }
+
     /// Creates a new parent link with JSON media type.
     ///
     /// # Examples
@@ -145,6 +185,54 @@ impl Link {
         self.rel == ITEM_REL
     }
 
+    /// Returns true if this link's rel is `"items"`.
+    ///
+    /// STAC APIs use `items` links to point to a (possibly paginated)
+    /// [ItemCollection](crate::ItemCollection).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Link;
+    /// let link = Link::new("an-href", "items");
+    /// assert!(link.is_items());
+    /// let link = Link::new("an-href", "item");
+    /// assert!(!link.is_items());
+    /// ```
+    pub fn is_items(&self) -> bool {
+        self.rel == ITEMS_REL
+    }
+
+    /// Returns true if this link's rel is `"next"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Link;
+    /// let link = Link::new("an-href", "next");
+    /// assert!(link.is_next());
+    /// let link = Link::new("an-href", "prev");
+    /// assert!(!link.is_next());
+    /// ```
+    pub fn is_next(&self) -> bool {
+        self.rel == NEXT_REL
+    }
+
+    /// Returns true if this link's rel is `"canonical"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Link;
+    /// let link = Link::new("an-href", "canonical");
+    /// assert!(link.is_canonical());
+    /// let link = Link::new("an-href", "via");
+    /// assert!(!link.is_canonical());
+    /// ```
+    pub fn is_canonical(&self) -> bool {
+        self.rel == CANONICAL_REL
+    }
+
     /// Returns true if this link's rel is `"child"`.
     ///
     /// # Examples
@@ -205,6 +293,21 @@ impl Link {
         self.rel == SELF_REL
     }
 
+    /// Returns true if this link's rel is `"via"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Link;
+    /// let link = Link::new("an-href", "via");
+    /// assert!(link.is_via());
+    /// let link = Link::new("an-href", "canonical");
+    /// assert!(!link.is_via());
+    /// ```
+    pub fn is_via(&self) -> bool {
+        self.rel == VIA_REL
+    }
+
     /// Returns true if this link is structural (i.e. not child, parent, item,
     /// root, or self).
     ///
@@ -223,9 +326,345 @@ impl Link {
     }
 }
 
+/// Finds, replaces, and rewrites the links of a STAC object.
+///
+/// Implemented for [Item], [Catalog], [Collection], [ItemCollection], and [Object].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Item, Links};
+/// let mut item = Item::new("an-id");
+/// item.set_link("self", "http://example.com/stac/item.json");
+/// item.set_link("parent", "http://example.com/stac/catalog.json");
+/// item.make_links_relative("http://example.com/stac/item.json").unwrap();
+/// assert_eq!(item.find_link("parent").unwrap().href, "catalog.json");
+/// ```
+pub trait Links {
+    /// Returns a reference to this object's links.
+    fn links(&self) -> &[Link];
+
+    /// Returns a mutable reference to this object's links.
+    fn links_mut(&mut self) -> &mut Vec<Link>;
+
+    /// Returns the first link with this rel, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Link, Links};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.links.push(Link::child("./child.json"));
+    /// assert_eq!(catalog.find_link("child").unwrap().href, "./child.json");
+    /// assert!(catalog.find_link("parent").is_none());
+    /// ```
+    fn find_link(&self, rel: &str) -> Option<&Link> {
+        self.links().iter().find(|link| link.rel == rel)
+    }
+
+    /// Removes all links with this rel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Link, Links};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.links.push(Link::child("./a.json"));
+    /// catalog.links.push(Link::child("./b.json"));
+    /// catalog.remove_links("child");
+    /// assert!(catalog.links.is_empty());
+    /// ```
+    fn remove_links(&mut self, rel: &str) {
+        self.links_mut().retain(|link| link.rel != rel);
+    }
+
+    /// Sets the href of the link with this rel, adding the link if there isn't one.
+    ///
+    /// The first link with this rel keeps its other fields, e.g. its type and
+    /// title, and any other links with this rel are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, Links};
+    /// let mut item = Item::new("an-id");
+    /// item.set_link("self", "./item.json");
+    /// item.set_link("self", "./moved/item.json");
+    /// assert_eq!(item.links.len(), 1);
+    /// assert_eq!(item.links[0].href, "./moved/item.json");
+    /// ```
+    fn set_link(&mut self, rel: impl ToString, href: impl ToString) {
+        let rel = rel.to_string();
+        let links = self.links_mut();
+        if let Some(first) = links.iter().position(|link| link.rel == rel.as_str()) {
+            links[first].href = href.to_string();
+            let mut i = 0;
+            links.retain(|link| {
+                i += 1;
+                i <= first + 1 || link.rel != rel.as_str()
+            });
+        } else {
+            links.push(Link::new(href, rel));
+        }
+    }
+
+    /// Makes every link's href, except for `self`, relative to `base`.
+    ///
+    /// Relative hrefs are first resolved against `base`. The `self` link is
+    /// made absolute instead, since it should always be absolute. Hrefs that
+    /// can't be made relative to `base`, e.g. urls on another host, are left
+    /// absolute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Link, Links};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.links.push(Link::child("http://example.com/stac/child/catalog.json"));
+    /// catalog.make_links_relative("http://example.com/stac/catalog.json").unwrap();
+    /// assert_eq!(catalog.links[0].href, "child/catalog.json");
+    /// ```
+    fn make_links_relative(&mut self, base: impl Into<Href>) -> Result<()> {
+        let base = base.into();
+        for link in self.links_mut() {
+            let href = base.join(link.href.as_str())?;
+            link.href = if link.is_self() {
+                href.into()
+            } else {
+                base.make_relative(&href).into()
+            };
+        }
+        Ok(())
+    }
+
+    /// Resolves every link's href against `base`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Link, Links};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.links.push(Link::child("./child/catalog.json"));
+    /// catalog.make_links_absolute("http://example.com/stac/catalog.json").unwrap();
+    /// assert_eq!(catalog.links[0].href, "http://example.com/stac/child/catalog.json");
+    /// ```
+    fn make_links_absolute(&mut self, base: impl Into<Href>) -> Result<()> {
+        let base = base.into();
+        for link in self.links_mut() {
+            link.href = base.join(link.href.as_str())?.into();
+        }
+        Ok(())
+    }
+}
+
+/// The link that records where a copied object came from.
+///
+/// Harvesting or mirroring a catalog gives every object a new href. Stamping
+/// a provenance link that points at the object's source href keeps track of
+/// the original, e.g. with [Layout::with_provenance](crate::Layout::with_provenance).
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Href, Item, Links, Provenance};
+/// let mut item = Item::new("an-id");
+/// Provenance::Canonical
+///     .stamp(&mut item, &Href::new("http://example.com/item.json"))
+///     .unwrap();
+/// assert_eq!(item.find_link("canonical").unwrap().href, "http://example.com/item.json");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provenance {
+    /// A `canonical` link, for exact mirrors.
+    ///
+    /// Stamping replaces any existing `canonical` link.
+    Canonical,
+
+    /// A `via` link, for copies that may be modified.
+    ///
+    /// Stamping adds a `via` link next to any existing ones, unless there
+    /// already is one to the same href.
+    Via,
+}
+
+impl Provenance {
+    /// Returns this provenance link's rel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Provenance;
+    /// assert_eq!(Provenance::Via.rel(), "via");
+    /// ```
+    pub fn rel(&self) -> &'static str {
+        match self {
+            Provenance::Canonical => CANONICAL_REL,
+            Provenance::Via => VIA_REL,
+        }
+    }
+
+    /// Adds a link to `source` to an object.
+    ///
+    /// Relative paths are made absolute first, so the link stays valid after
+    /// the object moves. This fails if the path doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Href, Item, Provenance};
+    /// let mut item = Item::new("an-id");
+    /// let source = Href::new("http://example.com/item.json");
+    /// Provenance::Via.stamp(&mut item, &source).unwrap();
+    /// Provenance::Via.stamp(&mut item, &source).unwrap();
+    /// assert_eq!(item.links.len(), 1);
+    /// ```
+    pub fn stamp(&self, object: &mut impl Links, source: &Href) -> Result<()> {
+        let mut source = source.clone();
+        source.make_absolute()?;
+        let link = Link::new_json(source, self.rel());
+        match self {
+            Provenance::Canonical => object.remove_links(CANONICAL_REL),
+            Provenance::Via => {
+                if object
+                    .links()
+                    .iter()
+                    .any(|existing| existing.is_via() && existing.href == link.href)
+                {
+                    return Ok(());
+                }
+            }
+        }
+        object.links_mut().push(link);
+        Ok(())
+    }
+}
+
+/// The positions of an object's links, grouped by rel.
+///
+/// Objects with hundreds of links (e.g. API pages or long version chains) are
+/// slow to search with a linear scan for every lookup. A `LinkIndex` is built
+/// once from the links and then answers rel lookups directly. The index
+/// stores positions, not links, so it must be rebuilt if the links change.
+///
+/// # Examples
+///
+/// ```
+/// # use stac::{Link, LinkIndex};
+/// let links = vec![Link::root("./catalog.json"), Link::child("./a.json"), Link::child("./b.json")];
+/// let index = LinkIndex::new(&links);
+/// assert_eq!(index.get("child"), &[1, 2]);
+/// assert!(index.get("parent").is_empty());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkIndex {
+    rels: HashMap<Interned, Vec<usize>>,
+}
+
+impl LinkIndex {
+    /// Creates a new index of these links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Link, LinkIndex};
+    /// let index = LinkIndex::new(&[Link::root("./catalog.json")]);
+    /// ```
+    pub fn new(links: &[Link]) -> LinkIndex {
+        let mut rels: HashMap<Interned, Vec<usize>> = HashMap::new();
+        for (i, link) in links.iter().enumerate() {
+            rels.entry(link.rel.clone()).or_default().push(i);
+        }
+        LinkIndex { rels }
+    }
+
+    /// Returns the positions of the links with this rel, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Link, LinkIndex};
+    /// let index = LinkIndex::new(&[Link::root("./catalog.json")]);
+    /// assert_eq!(index.get("root"), &[0]);
+    /// ```
+    pub fn get(&self, rel: &str) -> &[usize] {
+        self.rels.get(rel).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the links with this rel from `links`, which must be the links
+    /// that this index was built from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Link, LinkIndex};
+    /// let links = vec![Link::root("./catalog.json"), Link::child("./a.json")];
+    /// let index = LinkIndex::new(&links);
+    /// let children: Vec<_> = index.links(&links, "child").collect();
+    /// assert_eq!(children[0].href, "./a.json");
+    /// ```
+    pub fn links<'a>(&'a self, links: &'a [Link], rel: &str) -> impl Iterator<Item = &'a Link> {
+        self.get(rel).iter().map(move |&i| &links[i])
+    }
+}
+
+impl Links for Item {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    fn links_mut(&mut self) -> &mut Vec<Link> {
+        &mut self.links
+    }
+}
+
+impl Links for Catalog {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    fn links_mut(&mut self) -> &mut Vec<Link> {
+        &mut self.links
+    }
+}
+
+impl Links for Collection {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    fn links_mut(&mut self) -> &mut Vec<Link> {
+        &mut self.links
+    }
+}
+
+impl Links for ItemCollection {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    fn links_mut(&mut self) -> &mut Vec<Link> {
+        &mut self.links
+    }
+}
+
+impl Links for Object {
+    fn links(&self) -> &[Link] {
+        Object::links(self)
+    }
+
+    fn links_mut(&mut self) -> &mut Vec<Link> {
+        match self {
+            Object::Item(item) => &mut item.links,
+            Object::Catalog(catalog) => &mut catalog.links,
+            Object::Collection(collection) => &mut collection.links,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Link;
+    use super::{Link, LinkIndex, Links};
+    use crate::Item;
 
     #[test]
     fn new() {
@@ -243,4 +682,53 @@ mod tests {
         assert!(value.get("type").is_none());
         assert!(value.get("title").is_none());
     }
+
+    #[test]
+    fn index() {
+        let links = vec![
+            Link::root("./catalog.json"),
+            Link::child("./a.json"),
+            Link::new("./other.json", "related"),
+            Link::child("./b.json"),
+        ];
+        let index = LinkIndex::new(&links);
+        let children: Vec<_> = index
+            .links(&links, "child")
+            .map(|link| link.href.as_str())
+            .collect();
+        assert_eq!(children, ["./a.json", "./b.json"]);
+        assert_eq!(index.get("related"), &[2]);
+        assert!(index.get("item").is_empty());
+    }
+
+    #[test]
+    fn set_link() {
+        let mut item = Item::new("an-id");
+        item.links.push(Link::root("./root.json"));
+        item.links.push(Link::new("./related.json", "related"));
+        item.links.push(Link::root("./other-root.json"));
+        item.set_link("root", "./new-root.json");
+        assert_eq!(item.links.len(), 2);
+        assert_eq!(item.links[0].href, "./new-root.json");
+        assert_eq!(item.links[0].r#type.as_deref(), Some("application/json"));
+        item.set_link("parent", "./catalog.json");
+        assert!(item.links[2].is_parent());
+    }
+
+    #[test]
+    fn relative_and_absolute() {
+        let base = "http://example.com/stac/item/item.json";
+        let mut item = Item::new("an-id");
+        item.links.push(Link::new("./item.json", "self"));
+        item.links.push(Link::root("../catalog.json"));
+        item.links
+            .push(Link::new("http://other.com/other.json", "related"));
+        item.make_links_absolute(base).unwrap();
+        assert_eq!(item.links[0].href, base);
+        assert_eq!(item.links[1].href, "http://example.com/stac/catalog.json");
+        item.make_links_relative(base).unwrap();
+        assert_eq!(item.links[0].href, base);
+        assert_eq!(item.links[1].href, "../catalog.json");
+        assert_eq!(item.links[2].href, "http://other.com/other.json");
+    }
 }
@@ -46,3 +46,12 @@ pub const HDF5: &str = "application/x-hdf5";
 
 /// Hierarchical Data Format versions 4 and earlier.
 pub const HDF: &str = "application/x-hdf";
+
+/// [NetCDF](https://www.unidata.ucar.edu/software/netcdf/)
+pub const NETCDF: &str = "application/netcdf";
+
+/// [LAS](https://www.asprs.org/divisions-committees/lidar-division/laser-las-file-format-exchange-activities) point clouds
+pub const LAS: &str = "application/vnd.las";
+
+/// [LAZ](https://laszip.org/), compressed LAS point clouds
+pub const LAZ: &str = "application/vnd.laszip";
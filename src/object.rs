@@ -1,11 +1,21 @@
+#[cfg(feature = "patch")]
+use crate::validate::{Severity, Validator};
 use crate::{
-    Catalog, Collection, Error, Href, Item, Link, Result, CATALOG_TYPE, COLLECTION_TYPE, ITEM_TYPE,
+    id, write, Asset, Catalog, Collection, Error, Href, Item, Link, Links, Result, Version,
+    CATALOG_TYPE, COLLECTION_TYPE, ITEM_TYPE,
 };
+use serde_json::Value;
+#[cfg(feature = "hash")]
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
 /// A type used to pass either an [Object] or an [HrefObject] into functions.
 pub type ObjectHrefTuple = (Object, Option<Href>);
 const TYPE_FIELD: &str = "type";
 
+/// Fields that change when an object is rewritten, even if its content doesn't.
+const VOLATILE_FIELDS: [&str; 1] = ["updated"];
+
 /// A wrapper around any of the three main STAC entities: [Item], [Catalog], and [Collection].
 #[derive(Debug, PartialEq, Clone)]
 pub enum Object {
@@ -41,7 +51,7 @@ impl Object {
     /// let value: serde_json::Value = serde_json::from_reader(reader).unwrap();
     /// let object = Object::from_value(value).unwrap();
     /// ```
-    pub fn from_value(value: serde_json::Value) -> Result<Object> {
+    pub fn from_value(value: Value) -> Result<Object> {
         if let Some(type_) = value.get(TYPE_FIELD) {
             if let Some(type_) = type_.as_str() {
                 match type_ {
@@ -79,6 +89,22 @@ impl Object {
         }
     }
 
+    /// Converts this object into a [Catalog], or None if it is not a catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Object};
+    /// let object = Object::from(Catalog::new("an-id"));
+    /// assert_eq!(object.into_catalog().unwrap().id, "an-id");
+    /// ```
+    pub fn into_catalog(self) -> Option<Catalog> {
+        match self {
+            Object::Catalog(catalog) => Some(catalog),
+            _ => None,
+        }
+    }
+
     /// Returns true if this object is a [Collection].
     pub fn is_collection(&self) -> bool {
         matches!(self, Object::Collection(_))
@@ -100,6 +126,23 @@ impl Object {
         }
     }
 
+    /// Converts this object into a [Collection], or None if it is not a collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Collection, Object};
+    /// let object = Object::from(Collection::new("an-id"));
+    /// assert_eq!(object.into_collection().unwrap().id, "an-id");
+    /// assert!(Object::from(Catalog::new("an-id")).into_collection().is_none());
+    /// ```
+    pub fn into_collection(self) -> Option<Collection> {
+        match self {
+            Object::Collection(collection) => Some(collection),
+            _ => None,
+        }
+    }
+
     /// Returns true if this object is an [Item].
     pub fn is_item(&self) -> bool {
         matches!(self, Object::Item(_))
@@ -120,6 +163,27 @@ impl Object {
             _ => None,
         }
     }
+
+    /// Converts this object into an [Item], or None if it is not an item.
+    ///
+    /// Use [TryFrom] instead to get an [Error::TypeMismatch] that says what
+    /// the object is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, Object};
+    /// let object = Object::from(Item::new("an-id"));
+    /// assert_eq!(object.clone().into_item().unwrap().id, "an-id");
+    /// let item = Item::try_from(object).unwrap();
+    /// ```
+    pub fn into_item(self) -> Option<Item> {
+        match self {
+            Object::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
     /// Returns this object's type field.
     pub fn r#type(&self) -> &str {
         match &self {
@@ -129,6 +193,161 @@ impl Object {
         }
     }
 
+    /// Parses this object's `stac_version` field.
+    ///
+    /// Returns an error if the version is not a `1.x.y` version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// let mut item = Item::new("an-id");
+    /// item.version = "1.1.0".to_string();
+    /// assert_eq!(Object::from(item).version().unwrap().minor, 1);
+    /// ```
+    pub fn version(&self) -> Result<Version> {
+        match &self {
+            Object::Item(item) => item.version.parse(),
+            Object::Catalog(catalog) => catalog.version.parse(),
+            Object::Collection(collection) => collection.version.parse(),
+        }
+    }
+
+    /// Sets this object's `stac_version` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// let mut object = Object::from(Item::new("an-id"));
+    /// object.set_version(&"1.1.0".parse().unwrap());
+    /// assert_eq!(object.as_item().unwrap().version, "1.1.0");
+    /// ```
+    pub fn set_version(&mut self, version: &Version) {
+        let version = version.to_string();
+        match self {
+            Object::Item(item) => item.version = version,
+            Object::Catalog(catalog) => catalog.version = version,
+            Object::Collection(collection) => collection.version = version,
+        }
+    }
+
+    /// Converts this object's bands to the layout used by a STAC version.
+    ///
+    /// STAC 1.1 and later use unified `bands`, so they're merged with
+    /// [Item::merge_bands]; earlier versions use `eo:bands` and
+    /// `raster:bands`, so they're split with [Item::split_bands]. Collection
+    /// assets are converted too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Band, Item, Object};
+    /// let mut item = Item::new("an-id");
+    /// item.properties.bands = Some(vec![Band::new("B04")]);
+    /// let mut object = Object::from(item);
+    /// object.convert_bands(&"1.0.0".parse().unwrap()).unwrap();
+    /// let item = object.as_item().unwrap();
+    /// assert!(item.properties.additional_fields.contains_key("eo:bands"));
+    /// ```
+    pub fn convert_bands(&mut self, version: &Version) -> Result<()> {
+        let merge = version.minor >= 1;
+        if let Object::Item(item) = self {
+            return if merge {
+                item.merge_bands()
+            } else {
+                item.split_bands()
+            };
+        }
+        for asset in self
+            .assets_mut()
+            .into_iter()
+            .flat_map(|assets| assets.values_mut())
+        {
+            if merge {
+                asset.merge_bands()?;
+            } else {
+                asset.split_bands()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns this object's `stac_extensions`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// let object = Object::from(Item::new("an-id"));
+    /// assert!(object.extensions().is_none());
+    /// ```
+    pub fn extensions(&self) -> Option<&[String]> {
+        match &self {
+            Object::Item(item) => item.extensions.as_deref(),
+            Object::Catalog(catalog) => catalog.extensions.as_deref(),
+            Object::Collection(collection) => collection.extensions.as_deref(),
+        }
+    }
+
+    /// Returns the extension schema URIs on this object that have a newer compatible version.
+    ///
+    /// See the [extensions](crate::extensions) module for the registry of known extensions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// let mut item = Item::new("an-id");
+    /// item.extensions = Some(vec!["https://stac-extensions.github.io/eo/v1.0.0/schema.json".to_string()]);
+    /// assert_eq!(Object::from(item).outdated_extensions().len(), 1);
+    /// ```
+    pub fn outdated_extensions(&self) -> Vec<&str> {
+        self.extensions()
+            .unwrap_or_default()
+            .iter()
+            .map(String::as_str)
+            .filter(|uri| crate::extensions::is_outdated(uri))
+            .collect()
+    }
+
+    /// Upgrades every outdated extension schema URI to its latest compatible version.
+    ///
+    /// Shorthand extension identifiers, e.g. `"eo"`, are resolved to the
+    /// schema URI of the extension's latest version. Unknown extensions are
+    /// left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// let mut item = Item::new("an-id");
+    /// item.extensions = Some(vec![
+    ///     "https://stac-extensions.github.io/eo/v1.0.0/schema.json".to_string(),
+    ///     "view".to_string(),
+    /// ]);
+    /// let mut object = Object::from(item);
+    /// object.upgrade_extensions();
+    /// assert_eq!(
+    ///     object.extensions().unwrap(),
+    ///     [
+    ///         "https://stac-extensions.github.io/eo/v1.1.0/schema.json",
+    ///         "https://stac-extensions.github.io/view/v1.0.0/schema.json",
+    ///     ]
+    /// );
+    /// ```
+    pub fn upgrade_extensions(&mut self) {
+        if let Some(extensions) = self.extensions_mut() {
+            for uri in extensions.iter_mut() {
+                if let Some(upgraded) =
+                    crate::extensions::resolve(uri).or_else(|| crate::extensions::upgrade(uri))
+                {
+                    *uri = upgraded;
+                }
+            }
+        }
+    }
+
     /// Returns a reference to this object's id.
     ///
     /// # Examples
@@ -148,8 +367,9 @@ impl Object {
 
     /// Returns a reference to this object's title.
     ///
-    /// For [Items](Item), this checks for a `title` field in the
-    /// `additional_fields` attribute and returns it as a string if possible.
+    /// For [Items](Item), this checks for a `title` field in the item's
+    /// properties, and then in its `additional_fields`, and returns it as a
+    /// string if possible.
     ///
     /// # Examples
     ///
@@ -161,15 +381,119 @@ impl Object {
     /// ```
     pub fn title(&self) -> Option<&str> {
         match &self {
-            Object::Item(item) => item
-                .additional_fields
-                .get("title")
-                .and_then(|value| value.as_str()),
+            Object::Item(item) => item_field(item, "title"),
             Object::Catalog(catalog) => catalog.title.as_deref(),
             Object::Collection(collection) => collection.title.as_deref(),
         }
     }
 
+    /// Returns a reference to this object's description.
+    ///
+    /// For [Items](Item), the description is optional and is looked up like
+    /// the [title](Object::title).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Catalog, Item, Object};
+    /// let mut catalog = Catalog::new("id");
+    /// catalog.description = "An example catalog".to_string();
+    /// assert_eq!(Object::from(catalog).description().unwrap(), "An example catalog");
+    ///
+    /// let mut item = Item::new("an-id");
+    /// assert!(Object::from(item.clone()).description().is_none());
+    /// let _ = item
+    ///     .properties
+    ///     .additional_fields
+    ///     .insert("description".to_string(), "An example item".into());
+    /// assert_eq!(Object::from(item).description().unwrap(), "An example item");
+    /// ```
+    pub fn description(&self) -> Option<&str> {
+        match &self {
+            Object::Item(item) => item_field(item, "description"),
+            Object::Catalog(catalog) => Some(&catalog.description),
+            Object::Collection(collection) => Some(&collection.description),
+        }
+    }
+
+    /// Returns the name of this object's type, i.e. `"Feature"`, `"Catalog"`, or `"Collection"`.
+    ///
+    /// Unlike [r#type](Object::r#type), this doesn't depend on the object's
+    /// `type` field, which might have been changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Item, Object};
+    /// assert_eq!(Object::from(Item::new("an-id")).type_name(), "Feature");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        match &self {
+            Object::Item(_) => ITEM_TYPE,
+            Object::Catalog(_) => CATALOG_TYPE,
+            Object::Collection(_) => COLLECTION_TYPE,
+        }
+    }
+
+    /// Returns a reference to this object's assets.
+    ///
+    /// Returns `None` for [Catalogs](Catalog), which can't have assets, and
+    /// for [Collections](Collection) without an `assets` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Asset, Catalog, Item, Object};
+    /// let mut item = Item::new("an-id");
+    /// let _ = item.assets.insert("data".to_string(), Asset::new("data.tif"));
+    /// assert_eq!(Object::from(item).assets().unwrap()["data"].href, "data.tif");
+    /// assert!(Object::from(Catalog::new("an-id")).assets().is_none());
+    /// ```
+    pub fn assets(&self) -> Option<&HashMap<String, Asset>> {
+        match &self {
+            Object::Item(item) => Some(&item.assets),
+            Object::Catalog(_) => None,
+            Object::Collection(collection) => collection.assets.as_ref(),
+        }
+    }
+
+    /// Returns a mutable reference to this object's assets.
+    ///
+    /// Returns `None` for [Catalogs](Catalog) and for
+    /// [Collections](Collection) without an `assets` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Asset, Item, Object};
+    /// let mut object = Object::from(Item::new("an-id"));
+    /// let _ = object
+    ///     .assets_mut()
+    ///     .unwrap()
+    ///     .insert("data".to_string(), Asset::new("data.tif"));
+    /// ```
+    pub fn assets_mut(&mut self) -> Option<&mut HashMap<String, Asset>> {
+        match self {
+            Object::Item(item) => Some(&mut item.assets),
+            Object::Catalog(_) => None,
+            Object::Collection(collection) => collection.assets.as_mut(),
+        }
+    }
+
+    /// Returns true if this object has any `item` links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let object = stac::read("data/catalog.json").unwrap().object;
+    /// assert!(object.has_items());
+    /// let object = stac::read("data/simple-item.json").unwrap().object;
+    /// assert!(!object.has_items());
+    /// ```
+    pub fn has_items(&self) -> bool {
+        self.links().iter().any(Link::is_item)
+    }
+
     /// Returns a reference to this object's links.
     ///
     /// # Examples
@@ -189,6 +513,9 @@ impl Object {
 
     /// Returns the root link if there is one.
     ///
+    /// For objects in a [Stac](crate::Stac), [Stac::root_link](crate::Stac::root_link)
+    /// looks the links up in the object's [LinkIndex](crate::LinkIndex).
+    ///
     /// # Examples
     ///
     /// ```
@@ -228,6 +555,9 @@ impl Object {
 
     /// Iterates over the child links.
     ///
+    /// For objects in a [Stac](crate::Stac), [Stac::child_links](crate::Stac::child_links)
+    /// looks the links up in the object's [LinkIndex](crate::LinkIndex).
+    ///
     /// # Examples
     ///
     /// ```
@@ -267,7 +597,7 @@ impl Object {
     /// let object = Object::from(Item::new("an-id"));
     /// let value = object.into_value().unwrap();
     /// ```
-    pub fn into_value(self) -> Result<serde_json::Value> {
+    pub fn into_value(self) -> Result<Value> {
         match self {
             Object::Item(item) => serde_json::to_value(item).map_err(Error::from),
             Object::Catalog(catalog) => serde_json::to_value(catalog).map_err(Error::from),
@@ -275,11 +605,157 @@ impl Object {
         }
     }
 
-    pub(crate) fn links_mut(&mut self) -> &mut Vec<Link> {
+    /// Applies a [JSON Patch](https://datatracker.ietf.org/doc/html/rfc6902) to this object.
+    ///
+    /// The patch is applied to the object's JSON, and the result must still
+    /// be an object of the same type that doesn't have any new
+    /// [validation](crate::validate::Validator::validate) errors. If the
+    /// patch fails, returns [Error::InvalidPatch] and leaves the object
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::{Item, Object};
+    /// let mut object = Object::from(Item::new("an-id"));
+    /// object
+    ///     .apply_patch(json!([
+    ///         {"op": "add", "path": "/properties/eo:cloud_cover", "value": 12.5},
+    ///         {"op": "replace", "path": "/id", "value": "another-id"}
+    ///     ]))
+    ///     .unwrap();
+    /// assert_eq!(object.id(), "another-id");
+    ///
+    /// let patch = json!([{"op": "replace", "path": "/type", "value": "Catalog"}]);
+    /// assert!(object.apply_patch(patch).is_err());
+    /// let patch = json!([{"op": "replace", "path": "/stac_version", "value": "0.9.0"}]);
+    /// assert!(object.apply_patch(patch).is_err());
+    /// assert_eq!(object.id(), "another-id");
+    /// ```
+    #[cfg(feature = "patch")]
+    pub fn apply_patch(&mut self, patch: Value) -> Result<()> {
+        let patch: json_patch::Patch =
+            serde_json::from_value(patch).map_err(|err| Error::InvalidPatch(err.to_string()))?;
+        let mut value = self.clone().into_value()?;
+        json_patch::patch(&mut value, &patch.0)
+            .map_err(|err| Error::InvalidPatch(err.to_string()))?;
+        self.replace_with_patched(value)
+    }
+
+    /// Applies a [JSON Merge Patch](https://datatracker.ietf.org/doc/html/rfc7386) to this object.
+    ///
+    /// Members of the patch replace the object's members, and `null` members
+    /// remove them. The result is checked like
+    /// [apply_patch](Object::apply_patch).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::{Item, Object};
+    /// let mut object = Object::from(Item::new("an-id"));
+    /// object
+    ///     .apply_merge_patch(json!({"properties": {"platform": "landsat-8"}}))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     object.as_item().unwrap().properties.additional_fields["platform"],
+    ///     "landsat-8"
+    /// );
+    /// ```
+    #[cfg(feature = "patch")]
+    pub fn apply_merge_patch(&mut self, patch: Value) -> Result<()> {
+        let mut value = self.clone().into_value()?;
+        json_patch::merge(&mut value, &patch);
+        self.replace_with_patched(value)
+    }
+
+    /// Replaces this object with a patched version of itself, if it's still valid.
+    #[cfg(feature = "patch")]
+    fn replace_with_patched(&mut self, value: Value) -> Result<()> {
+        let object =
+            Object::from_value(value).map_err(|err| Error::InvalidPatch(err.to_string()))?;
+        if object.r#type() != self.r#type() {
+            return Err(Error::InvalidPatch(format!(
+                "type changed from {} to {}",
+                self.r#type(),
+                object.r#type()
+            )));
+        }
+        let validator = Validator::default();
+        let before = validator.validate(self);
+        let errors: Vec<String> = validator
+            .validate(&object)
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error && !before.contains(issue))
+            .map(|issue| format!("{}: {}", issue.pointer, issue.message))
+            .collect();
+        if !errors.is_empty() {
+            return Err(Error::InvalidPatch(errors.join("; ")));
+        }
+        *self = object;
+        Ok(())
+    }
+
+    /// Returns a hash of this object's content, as a hex-encoded SHA-256 digest.
+    ///
+    /// The hash is computed from canonical JSON, so it doesn't depend on key
+    /// order or link order. Structural links (`child`, `item`, `parent`,
+    /// `root`, and `self`) and `updated` timestamps, whether on the object,
+    /// in an item's properties, or on an asset, are ignored. Use this to tell
+    /// whether an object really changed, e.g. when syncing a catalog that has
+    /// been moved or rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Item, Link, Object};
+    /// let item = Item::new("an-id");
+    /// let mut moved = item.clone();
+    /// moved.links.push(Link::new("s3://bucket/an-id.json", "self"));
+    /// assert_eq!(
+    ///     Object::from(item).content_hash().unwrap(),
+    ///     Object::from(moved).content_hash().unwrap()
+    /// );
+    /// ```
+    #[cfg(feature = "hash")]
+    pub fn content_hash(&self) -> Result<String> {
+        let bytes = serde_json::to_vec(&self.canonical_value()?)?;
+        Ok(format!("{:x}", Sha256::digest(bytes)))
+    }
+
+    /// Returns this object as JSON, without structural links or volatile
+    /// fields, and with its keys and links sorted.
+    pub(crate) fn canonical_value(&self) -> Result<Value> {
+        let mut object = self.clone();
+        object.links_mut().retain(|link| !link.is_structural());
+        let mut value = object.into_value()?;
+        write::sort(&mut value);
+        if let Value::Object(map) = &mut value {
+            for field in VOLATILE_FIELDS {
+                let _ = map.remove(field);
+            }
+            if let Some(Value::Object(properties)) = map.get_mut("properties") {
+                for field in VOLATILE_FIELDS {
+                    let _ = properties.remove(field);
+                }
+            }
+            if let Some(Value::Object(assets)) = map.get_mut("assets") {
+                for asset in assets.values_mut().filter_map(Value::as_object_mut) {
+                    for field in VOLATILE_FIELDS {
+                        let _ = asset.remove(field);
+                    }
+                }
+            }
+        }
+        Ok(id::sorted(&value))
+    }
+
+    pub(crate) fn extensions_mut(&mut self) -> Option<&mut Vec<String>> {
         match self {
-            Object::Item(item) => &mut item.links,
-            Object::Catalog(catalog) => &mut catalog.links,
-            Object::Collection(collection) => &mut collection.links,
+            Object::Item(item) => item.extensions.as_mut(),
+            Object::Catalog(catalog) => catalog.extensions.as_mut(),
+            Object::Collection(collection) => collection.extensions.as_mut(),
         }
     }
 }
@@ -393,3 +869,12 @@ impl TryFrom<Object> for Item {
         }
     }
 }
+
+/// Returns a string field of an item, from its properties or its top-level fields.
+fn item_field<'a>(item: &'a Item, key: &str) -> Option<&'a str> {
+    item.properties
+        .additional_fields
+        .get(key)
+        .or_else(|| item.additional_fields.get(key))
+        .and_then(Value::as_str)
+}
@@ -1,3 +1,4 @@
+use crate::Band;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -11,6 +12,10 @@ pub struct Properties {
     /// requires `start_datetime` and `end_datetime` from common metadata to be set.
     pub datetime: Option<String>,
 
+    /// The bands of the item, in the unified STAC 1.1 layout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bands: Option<Vec<Band>>,
+
     /// Additional fields on the properties.
     #[serde(flatten)]
     pub additional_fields: Map<String, Value>,
@@ -20,6 +25,7 @@ impl Default for Properties {
     fn default() -> Properties {
         Properties {
             datetime: Some(Utc::now().to_rfc3339()),
+            bands: None,
             additional_fields: Map::new(),
         }
     }
@@ -1,6 +1,10 @@
-use crate::{Error, Href, HrefObject, Object, Result};
+use crate::{Error, Href, HrefObject, HrefRewriter, HttpClient, Object, Result};
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
 use path_slash::PathBufExt;
 use serde_json::Value;
+#[cfg(feature = "async")]
+use std::future::Future;
 use std::{
     fs::File,
     io::BufReader,
@@ -85,6 +89,9 @@ pub trait Read {
     fn read_json_from_path(&self, path: impl AsRef<Path>) -> Result<Value>;
 }
 
+/// The path that [Reader] reads from standard input.
+pub const STDIN: &str = "-";
+
 /// A basic reader for STAC objects.
 ///
 /// This reader uses the standard library to read from the filesystem. If the
@@ -99,15 +106,90 @@ pub trait Read {
 /// let reader = Reader::default();
 /// let object = reader.read("data/catalog.json").unwrap();
 /// ```
+///
+/// The path `-` is read from standard input, so objects can be piped in:
+///
+/// ```no_run
+/// use stac::{Read, Reader};
+/// let object = Reader::default().read("-").unwrap();
+/// ```
 #[derive(Debug, Default)]
-pub struct Reader();
+pub struct Reader {
+    /// Memory-map local files instead of reading them through a buffer.
+    ///
+    /// Large local files, e.g. multi-hundred-megabyte
+    /// [ItemCollections](crate::ItemCollection), are then deserialized
+    /// directly from the mapped bytes. The file must not be modified while it
+    /// is being read. Requires the `mmap` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Read, Reader};
+    /// let reader = Reader { mmap: true, ..Default::default() };
+    /// let object = reader.read("data/catalog.json").unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub mmap: bool,
+
+    /// The client used to read urls.
+    pub http: HttpClient,
+
+    /// If set, hrefs are rewritten before they're read, e.g. to read a
+    /// mirror instead of the original host.
+    ///
+    /// Objects keep the href they were asked for, so links between them
+    /// still resolve.
+    pub rewriter: Option<HrefRewriter>,
+}
+
+impl Reader {
+    /// Creates a reader whose [HttpClient] is configured by environment
+    /// variables, see [HttpClient::from_env].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Reader;
+    /// let reader = Reader::from_env().unwrap();
+    /// ```
+    pub fn from_env() -> Result<Reader> {
+        Ok(Reader::default().with_http(HttpClient::from_env()?))
+    }
+
+    /// Sets the [HttpClient] used to read urls, e.g. to configure timeouts or a proxy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{HttpClient, Reader};
+    /// let reader = Reader::default().with_http(HttpClient::new().user_agent("my-crawler/1.0"));
+    /// ```
+    pub fn with_http(mut self, http: HttpClient) -> Reader {
+        self.http = http;
+        self
+    }
+}
 
 impl Read for Reader {
+    fn read_json(&self, href: &Href) -> Result<Value> {
+        let rewritten;
+        let href = match &self.rewriter {
+            Some(rewriter) => {
+                rewritten = rewriter.rewrite_href(href);
+                &rewritten
+            }
+            None => href,
+        };
+        match href {
+            Href::Url(url) => self.read_json_from_url(url),
+            Href::Path(path) => self.read_json_from_path(PathBuf::from_slash(path)),
+        }
+    }
+
     #[cfg(feature = "reqwest")]
     fn read_json_from_url(&self, url: &Url) -> Result<Value> {
-        reqwest::blocking::get(url.as_str())
-            .and_then(|response| response.json())
-            .map_err(Error::from)
+        self.http.get_json_blocking(url)
     }
 
     #[cfg(not(feature = "reqwest"))]
@@ -116,12 +198,155 @@ impl Read for Reader {
     }
 
     fn read_json_from_path(&self, path: impl AsRef<Path>) -> Result<Value> {
+        if path.as_ref() == Path::new(STDIN) {
+            return serde_json::from_reader(std::io::stdin().lock()).map_err(Error::from);
+        }
         let file = File::open(path)?;
+        #[cfg(feature = "mmap")]
+        if self.mmap {
+            // SAFETY: the caller opted into mapping, and is responsible for
+            // not modifying the file while it's being read.
+            #[allow(unsafe_code)]
+            let mmap = unsafe { Mmap::map(&file)? };
+            return serde_json::from_slice(&mmap).map_err(Error::from);
+        }
         let reader = BufReader::new(file);
         serde_json::from_reader(reader).map_err(Error::from)
     }
 }
 
+/// Read STAC objects from hrefs asynchronously.
+///
+/// This is the async counterpart to [Read], and is used by
+/// [AsyncStac](crate::AsyncStac).
+///
+/// # Examples
+///
+/// [AsyncReader] implements `AsyncRead`:
+///
+/// ```
+/// use stac::{AsyncRead, AsyncReader};
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let reader = AsyncReader::default();
+/// let object = reader.read("data/catalog.json".into()).await.unwrap();
+/// # })
+/// ```
+#[cfg(feature = "async")]
+pub trait AsyncRead: Sync {
+    /// Reads a STAC object from an href.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncRead, AsyncReader};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let reader = AsyncReader::default();
+    /// let catalog = reader.read("data/catalog.json".into()).await.unwrap();
+    /// assert_eq!(catalog.object.id(), "examples");
+    /// # })
+    /// ```
+    fn read(&self, href: Href) -> impl Future<Output = Result<HrefObject>> + Send {
+        async move {
+            let value = self.read_json(&href).await?;
+            let object = Object::from_value(value)?;
+            Ok(HrefObject::new(object, href))
+        }
+    }
+
+    /// Reads JSON data from an href.
+    fn read_json(&self, href: &Href) -> impl Future<Output = Result<Value>> + Send {
+        async move {
+            match href {
+                Href::Url(url) => self.read_json_from_url(url).await,
+                Href::Path(path) => self.read_json_from_path(&PathBuf::from_slash(path)).await,
+            }
+        }
+    }
+
+    /// Reads JSON data from a [Url].
+    fn read_json_from_url(&self, url: &Url) -> impl Future<Output = Result<Value>> + Send;
+
+    /// Reads JSON data from a [Path].
+    fn read_json_from_path(&self, path: &Path) -> impl Future<Output = Result<Value>> + Send;
+}
+
+/// A basic async reader for STAC objects.
+///
+/// This reader uses [tokio](https://docs.rs/tokio/latest/tokio/) to read from
+/// the filesystem. If the `reqwest` feature is enabled, async
+/// [reqwest](https://docs.rs/reqwest/latest/reqwest/) calls are used to read
+/// from urls.
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+pub struct AsyncReader {
+    /// The client used to read urls.
+    pub http: HttpClient,
+
+    /// If set, hrefs are rewritten before they're read, like [Reader::rewriter].
+    pub rewriter: Option<HrefRewriter>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncReader {
+    /// Creates a reader whose [HttpClient] is configured by environment
+    /// variables, see [HttpClient::from_env].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::AsyncReader;
+    /// let reader = AsyncReader::from_env().unwrap();
+    /// ```
+    pub fn from_env() -> Result<AsyncReader> {
+        Ok(AsyncReader::default().with_http(HttpClient::from_env()?))
+    }
+
+    /// Sets the [HttpClient] used to read urls, e.g. to configure timeouts or a proxy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncReader, HttpClient};
+    /// use std::time::Duration;
+    /// let reader = AsyncReader::default().with_http(HttpClient::new().timeout(Duration::from_secs(30)));
+    /// ```
+    pub fn with_http(mut self, http: HttpClient) -> AsyncReader {
+        self.http = http;
+        self
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncRead for AsyncReader {
+    fn read_json(&self, href: &Href) -> impl Future<Output = Result<Value>> + Send {
+        let href = match &self.rewriter {
+            Some(rewriter) => rewriter.rewrite_href(href),
+            None => href.clone(),
+        };
+        async move {
+            match &href {
+                Href::Url(url) => self.read_json_from_url(url).await,
+                Href::Path(path) => self.read_json_from_path(&PathBuf::from_slash(path)).await,
+            }
+        }
+    }
+
+    #[cfg(feature = "reqwest")]
+    async fn read_json_from_url(&self, url: &Url) -> Result<Value> {
+        self.http.get_json(url).await
+    }
+
+    #[cfg(not(feature = "reqwest"))]
+    async fn read_json_from_url(&self, _: &Url) -> Result<Value> {
+        Err(Error::ReqwestNotEnabled)
+    }
+
+    async fn read_json_from_path(&self, path: &Path) -> Result<Value> {
+        let bytes = tokio::fs::read(path).await?;
+        serde_json::from_slice(&bytes).map_err(Error::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Read, Reader};
@@ -151,4 +376,27 @@ mod tests {
             .read("https://planetarycomputer.microsoft.com/api/stac/v1")
             .unwrap_err();
     }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn read_fs_mmap() {
+        let reader = Reader {
+            mmap: true,
+            ..Default::default()
+        };
+        let catalog = reader.read("data/catalog.json").unwrap();
+        assert_eq!(
+            catalog,
+            Reader::default().read("data/catalog.json").unwrap()
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_read_fs() {
+        use super::{AsyncRead, AsyncReader};
+        let reader = AsyncReader::default();
+        let catalog = reader.read("data/catalog.json".into()).await.unwrap();
+        assert_eq!(catalog.href.as_str(), "data/catalog.json");
+    }
 }
@@ -0,0 +1,379 @@
+//! Retry failed reads and writes.
+//!
+//! A [RetryPolicy] looks at an error and decides whether to try again, and
+//! how long to wait first. [HttpClient](crate::HttpClient) uses [HttpRetry]
+//! by default, and [Writer](crate::Writer) and, with the `async` feature,
+//! `AsyncWriter` can be given a policy such as [IoRetry] for network
+//! filesystems and object-store mounts.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::{retry::IoRetry, Writer};
+//! use std::sync::Arc;
+//! let writer = Writer {
+//!     retry: Some(Arc::new(IoRetry::default())),
+//!     ..Default::default()
+//! };
+//! ```
+
+use crate::{Error, Result};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::{fmt::Debug, io::ErrorKind, time::Duration};
+
+/// Decides whether, and when, to retry after an error.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{retry::RetryPolicy, Error};
+/// use std::time::Duration;
+///
+/// /// Retries io errors once, immediately.
+/// #[derive(Debug)]
+/// struct Once;
+///
+/// impl RetryPolicy for Once {
+///     fn retry(&self, error: &Error, attempt: u32) -> Option<Duration> {
+///         (attempt == 1 && matches!(error, Error::Io(_))).then_some(Duration::ZERO)
+///     }
+/// }
+/// ```
+pub trait RetryPolicy: Debug + Send + Sync {
+    /// Returns how long to wait before retrying, or `None` to give up.
+    ///
+    /// `attempt` is the number of attempts that have failed so far, starting at one.
+    fn retry(&self, error: &Error, attempt: u32) -> Option<Duration>;
+
+    /// Returns true if `POST`, `PATCH`, and `PUT` requests should be retried.
+    ///
+    /// These might have changed something on the server before failing, so
+    /// by default they're only retried if they couldn't connect.
+    fn retry_non_idempotent(&self) -> bool {
+        false
+    }
+}
+
+/// Never retries.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{retry::{NoRetry, RetryPolicy}, Error};
+/// let error = Error::Io(std::io::ErrorKind::TimedOut.into());
+/// assert_eq!(NoRetry.retry(&error, 1), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+/// Retries transient HTTP errors with exponential backoff.
+///
+/// Timeouts, connection failures, and `408`, `429`, `500`, `502`, `503`, and
+/// `504` responses are retried. Anything else, e.g. a `404`, fails
+/// immediately. `POST`, `PATCH`, and `PUT` requests are only retried after
+/// connection failures, unless `non_idempotent` is true.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{retry::HttpRetry, HttpClient};
+/// use std::time::Duration;
+/// let http = HttpClient::new().retry(HttpRetry {
+///     max_attempts: 5,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HttpRetry {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, which doubles for each retry after that.
+    pub initial_delay: Duration,
+
+    /// The longest delay between attempts.
+    pub max_delay: Duration,
+
+    /// Whether to retry `POST`, `PATCH`, and `PUT` requests after any
+    /// transient error.
+    pub non_idempotent: bool,
+}
+
+/// Only retries errors where the request never reached the server.
+#[cfg(feature = "reqwest")]
+#[derive(Debug)]
+pub(crate) struct ConnectOnly<'a>(pub(crate) &'a dyn RetryPolicy);
+
+/// Retries transient io errors with exponential backoff.
+///
+/// Network filesystems and object-store mounts can fail with timeouts,
+/// interruptions, and dropped connections that succeed when tried again. Other
+/// io errors, e.g. a missing file, fail immediately.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{retry::{IoRetry, RetryPolicy}, Error};
+/// let retry = IoRetry::default();
+/// assert!(retry.retry(&Error::Io(std::io::ErrorKind::TimedOut.into()), 1).is_some());
+/// assert!(retry.retry(&Error::Io(std::io::ErrorKind::NotFound.into()), 1).is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IoRetry {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, which doubles for each retry after that.
+    pub initial_delay: Duration,
+
+    /// The longest delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy for NoRetry {
+    fn retry(&self, _: &Error, _: u32) -> Option<Duration> {
+        None
+    }
+}
+
+impl HttpRetry {
+    /// The default policy: three attempts, starting with a half-second delay.
+    pub const DEFAULT: HttpRetry = HttpRetry {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(30),
+        non_idempotent: false,
+    };
+}
+
+impl Default for HttpRetry {
+    fn default() -> HttpRetry {
+        HttpRetry::DEFAULT
+    }
+}
+
+impl RetryPolicy for HttpRetry {
+    fn retry(&self, error: &Error, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient_http(error) {
+            None
+        } else {
+            Some(backoff(self.initial_delay, self.max_delay, attempt))
+        }
+    }
+
+    fn retry_non_idempotent(&self) -> bool {
+        self.non_idempotent
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl RetryPolicy for ConnectOnly<'_> {
+    fn retry(&self, error: &Error, attempt: u32) -> Option<Duration> {
+        if is_connect(error) {
+            self.0.retry(error, attempt)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for IoRetry {
+    fn default() -> IoRetry {
+        IoRetry {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy for IoRetry {
+    fn retry(&self, error: &Error, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts || !is_transient_io(error) {
+            None
+        } else {
+            Some(backoff(self.initial_delay, self.max_delay, attempt))
+        }
+    }
+}
+
+/// Calls a function until it succeeds or the policy gives up, sleeping between attempts.
+///
+/// # Examples
+///
+/// ```
+/// use stac::retry::NoRetry;
+/// let value = stac::retry::retry(&NoRetry, || Ok(42)).unwrap();
+/// assert_eq!(value, 42);
+/// ```
+pub fn retry<T>(policy: &dyn RetryPolicy, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                attempt += 1;
+                match policy.retry(&error, attempt) {
+                    Some(delay) => std::thread::sleep(delay),
+                    None => return Err(error),
+                }
+            }
+        }
+    }
+}
+
+/// Calls an async function until it succeeds or the policy gives up, sleeping between attempts.
+///
+/// # Examples
+///
+/// ```
+/// use stac::retry::NoRetry;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let value = stac::retry::retry_async(&NoRetry, || async { Ok(42) }).await.unwrap();
+/// assert_eq!(value, 42);
+/// # })
+/// ```
+#[cfg(feature = "async")]
+pub async fn retry_async<T, F, Fut>(policy: &dyn RetryPolicy, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                attempt += 1;
+                match policy.retry(&error, attempt) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return Err(error),
+                }
+            }
+        }
+    }
+}
+
+fn backoff(initial: Duration, max: Duration, attempt: u32) -> Duration {
+    initial
+        .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .map_or(max, |delay| delay.min(max))
+}
+
+fn is_transient_io(error: &Error) -> bool {
+    match error {
+        Error::Io(error) => matches!(
+            error.kind(),
+            ErrorKind::Interrupted
+                | ErrorKind::TimedOut
+                | ErrorKind::WouldBlock
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(feature = "reqwest")]
+fn is_transient_http(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(error) => {
+            error.is_timeout()
+                || error.is_connect()
+                || error.status().is_some_and(|status| {
+                    matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504)
+                })
+        }
+        _ => is_transient_io(error),
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn is_transient_http(error: &Error) -> bool {
+    is_transient_io(error)
+}
+
+#[cfg(feature = "reqwest")]
+fn is_connect(error: &Error) -> bool {
+    matches!(error, Error::Reqwest(error) if error.is_connect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HttpRetry, IoRetry, NoRetry, RetryPolicy};
+    use crate::Error;
+    use std::{io::ErrorKind, time::Duration};
+
+    #[derive(Debug)]
+    struct Immediately(u32);
+
+    impl RetryPolicy for Immediately {
+        fn retry(&self, _: &Error, attempt: u32) -> Option<Duration> {
+            (attempt < self.0).then_some(Duration::ZERO)
+        }
+    }
+
+    fn flaky(failures: u32) -> impl FnMut() -> crate::Result<u32> {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            if calls <= failures {
+                Err(Error::Io(ErrorKind::TimedOut.into()))
+            } else {
+                Ok(calls)
+            }
+        }
+    }
+
+    #[test]
+    fn retry() {
+        assert_eq!(super::retry(&Immediately(3), flaky(2)).unwrap(), 3);
+        assert!(super::retry(&Immediately(3), flaky(3)).is_err());
+        assert!(super::retry(&NoRetry, flaky(1)).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn retry_async() {
+        let mut f = flaky(2);
+        let value = super::retry_async(&Immediately(3), || std::future::ready(f()))
+            .await
+            .unwrap();
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn backoff() {
+        let retry = IoRetry {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        let error = Error::Io(ErrorKind::Interrupted.into());
+        assert_eq!(retry.retry(&error, 1), Some(Duration::from_millis(100)));
+        assert_eq!(retry.retry(&error, 2), Some(Duration::from_millis(200)));
+        assert_eq!(retry.retry(&error, 4), Some(Duration::from_millis(500)));
+        assert_eq!(retry.retry(&error, 200), None);
+    }
+
+    #[test]
+    fn http_gives_up() {
+        let retry = HttpRetry::default();
+        let error = Error::Io(ErrorKind::TimedOut.into());
+        assert!(retry.retry(&error, 2).is_some());
+        assert!(retry.retry(&error, 3).is_none());
+        assert!(retry.retry(&Error::MissingType, 1).is_none());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn http_connection_refused() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let error = reqwest::blocking::get(format!("http://{}", address)).unwrap_err();
+        assert!(HttpRetry::default().retry(&error.into(), 1).is_some());
+    }
+}
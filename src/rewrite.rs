@@ -0,0 +1,291 @@
+#[cfg(feature = "regex")]
+use crate::Result;
+use crate::{Href, Links, Object};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+/// Rewrites hrefs with an ordered list of rules.
+///
+/// Use this to redirect a mirrored catalog whose links still point at the
+/// original host. Every rule is applied in order, each to the output of the
+/// previous one, so later rules can refine earlier ones. There are three
+/// kinds of rule:
+///
+/// - [regex](HrefRewriter::regex) replaces every match of a regular
+///   expression, with `$1`-style references to capture groups (requires the
+///   `regex` feature)
+/// - [prefix](HrefRewriter::prefix) swaps one prefix for another, e.g. an
+///   origin host for a mirror
+/// - [scheme](HrefRewriter::scheme) changes an href's scheme, e.g. `http` to
+///   `https`
+///
+/// A rewriter can be set on a [Reader](crate::Reader), to read from the new
+/// location, and on a [Layout](crate::Layout), to rewrite links and asset
+/// hrefs in the output.
+///
+/// # Examples
+///
+/// ```
+/// use stac::HrefRewriter;
+/// let rewriter = HrefRewriter::new()
+///     .scheme("http", "https")
+///     .prefix("https://origin.test/", "https://mirror.test/stac/");
+/// assert_eq!(
+///     rewriter.rewrite("http://origin.test/v2/catalog.json"),
+///     "https://mirror.test/stac/v2/catalog.json"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HrefRewriter {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    #[cfg(feature = "regex")]
+    Regex {
+        regex: Regex,
+        replacement: String,
+    },
+    Prefix {
+        from: String,
+        to: String,
+    },
+    Scheme {
+        from: String,
+        to: String,
+    },
+}
+
+impl HrefRewriter {
+    /// Creates a rewriter without any rules, which leaves every href as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// let rewriter = HrefRewriter::new();
+    /// assert!(rewriter.is_empty());
+    /// assert_eq!(rewriter.rewrite("catalog.json"), "catalog.json");
+    /// ```
+    pub fn new() -> HrefRewriter {
+        HrefRewriter::default()
+    }
+
+    /// Adds a rule that replaces every match of a regular expression.
+    ///
+    /// The replacement can refer to capture groups, e.g. `$1` or `$name`.
+    /// Returns an error if the pattern isn't a valid regular expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// let rewriter = HrefRewriter::new()
+    ///     .regex(r"^s3://([^/]+)/", "https://$1.s3.amazonaws.com/")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rewriter.rewrite("s3://a-bucket/item.json"),
+    ///     "https://a-bucket.s3.amazonaws.com/item.json"
+    /// );
+    /// assert!(HrefRewriter::new().regex("(", "").is_err());
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn regex(mut self, pattern: &str, replacement: impl ToString) -> Result<HrefRewriter> {
+        self.rules.push(Rule::Regex {
+            regex: Regex::new(pattern)?,
+            replacement: replacement.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Adds a rule that replaces a prefix.
+    ///
+    /// Hrefs that don't start with `from` are left as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// let rewriter = HrefRewriter::new().prefix("https://origin.test/", "mirror/");
+    /// assert_eq!(rewriter.rewrite("https://origin.test/catalog.json"), "mirror/catalog.json");
+    /// assert_eq!(rewriter.rewrite("https://other.test/catalog.json"), "https://other.test/catalog.json");
+    /// ```
+    pub fn prefix(mut self, from: impl ToString, to: impl ToString) -> HrefRewriter {
+        self.rules.push(Rule::Prefix {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+        self
+    }
+
+    /// Adds a rule that changes the scheme of urls, e.g. from `http` to `https`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// let rewriter = HrefRewriter::new().scheme("s3", "gs");
+    /// assert_eq!(rewriter.rewrite("s3://bucket/item.json"), "gs://bucket/item.json");
+    /// assert_eq!(rewriter.rewrite("s3bucket/item.json"), "s3bucket/item.json");
+    /// ```
+    pub fn scheme(mut self, from: impl ToString, to: impl ToString) -> HrefRewriter {
+        self.rules.push(Rule::Scheme {
+            from: format!("{}:", from.to_string()),
+            to: format!("{}:", to.to_string()),
+        });
+        self
+    }
+
+    /// Returns true if this rewriter doesn't have any rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// assert!(HrefRewriter::new().is_empty());
+    /// assert!(!HrefRewriter::new().scheme("http", "https").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rewrites an href.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::HrefRewriter;
+    /// let rewriter = HrefRewriter::new().scheme("http", "https");
+    /// assert_eq!(rewriter.rewrite("http://stac.test"), "https://stac.test");
+    /// ```
+    pub fn rewrite(&self, href: &str) -> String {
+        let mut href = href.to_string();
+        for rule in &self.rules {
+            match rule {
+                #[cfg(feature = "regex")]
+                Rule::Regex { regex, replacement } => {
+                    href = regex.replace_all(&href, replacement.as_str()).into_owned();
+                }
+                Rule::Prefix { from, to } | Rule::Scheme { from, to } => {
+                    if let Some(rest) = href.strip_prefix(from.as_str()) {
+                        href = format!("{}{}", to, rest);
+                    }
+                }
+            }
+        }
+        href
+    }
+
+    /// Rewrites an [Href], which can turn a url into a path or vice versa.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Href, HrefRewriter};
+    /// let rewriter = HrefRewriter::new().prefix("https://origin.test/", "data/");
+    /// let href = rewriter.rewrite_href(&Href::new("https://origin.test/catalog.json"));
+    /// assert!(href.is_path());
+    /// ```
+    pub fn rewrite_href(&self, href: &Href) -> Href {
+        if self.is_empty() {
+            href.clone()
+        } else {
+            Href::new(self.rewrite(href.as_str()))
+        }
+    }
+
+    /// Rewrites the hrefs of all of an object's links and assets.
+    ///
+    /// Returns the number of hrefs that were changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, HrefRewriter, Item, Link, Object};
+    /// let mut item = Item::new("an-item");
+    /// item.links.push(Link::new("https://origin.test/license.txt", "license"));
+    /// let _ = item
+    ///     .assets
+    ///     .insert("data".to_string(), Asset::new("https://origin.test/data.tif"));
+    /// let mut object = Object::from(item);
+    /// let rewriter = HrefRewriter::new().prefix("https://origin.test/", "https://mirror.test/");
+    /// assert_eq!(rewriter.rewrite_object(&mut object), 2);
+    /// let item = object.as_item().unwrap();
+    /// assert_eq!(item.assets["data"].href, "https://mirror.test/data.tif");
+    /// ```
+    pub fn rewrite_object(&self, object: &mut Object) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut count = 0;
+        let mut rewrite = |href: &mut String| {
+            let rewritten = self.rewrite(href);
+            if rewritten != *href {
+                *href = rewritten;
+                count += 1;
+            }
+        };
+        for link in object.links_mut() {
+            rewrite(&mut link.href);
+        }
+        for asset in object
+            .assets_mut()
+            .into_iter()
+            .flat_map(|assets| assets.values_mut())
+        {
+            rewrite(&mut asset.href);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HrefRewriter;
+    use crate::{Layout, Link, Links, Read, Reader, Stac};
+
+    #[test]
+    fn rules_apply_in_order() {
+        let rewriter = HrefRewriter::new()
+            .prefix("https://a.test/", "https://b.test/")
+            .prefix("https://b.test/", "https://c.test/");
+        assert_eq!(
+            rewriter.rewrite("https://a.test/item.json"),
+            "https://c.test/item.json"
+        );
+    }
+
+    #[test]
+    fn reader() {
+        let reader = Reader {
+            rewriter: Some(HrefRewriter::new().prefix("https://origin.test/", "data/")),
+            ..Default::default()
+        };
+        let href_object = reader.read("https://origin.test/catalog.json").unwrap();
+        assert_eq!(href_object.object.id(), "examples");
+        assert_eq!(
+            href_object.href.as_str(),
+            "https://origin.test/catalog.json"
+        );
+    }
+
+    #[test]
+    fn layout() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        stac.get_mut(root)
+            .unwrap()
+            .links_mut()
+            .push(Link::new("https://origin.test/license.txt", "license"));
+        let mut layout = Layout::new("a/new/root").with_rewriter(
+            HrefRewriter::new().prefix("https://origin.test/", "https://mirror.test/"),
+        );
+        layout.layout(&mut stac).unwrap();
+        let object = stac.get(root).unwrap();
+        assert_eq!(
+            object.find_link("license").unwrap().href,
+            "https://mirror.test/license.txt"
+        );
+        assert!(!object.find_link("root").unwrap().href.contains("mirror"));
+    }
+}
@@ -0,0 +1,503 @@
+use super::{search::Interval, Page, Search};
+use crate::{Collection, Error, Handle, Item, Read, Result, Stac, Walk};
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::Bound,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Storage for the collections and items served by an [Api](super::Api).
+///
+/// Backends store and return objects as-is: the [Api](super::Api) rewrites
+/// their links to point at its own endpoints. Clones of a backend share the
+/// same storage.
+pub trait Backend: Clone + Send + Sync + 'static {
+    /// Returns every collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// assert_eq!(backend.collections().await.unwrap().len(), 3);
+    /// # })
+    /// ```
+    fn collections(&self) -> impl Future<Output = Result<Vec<Collection>>> + Send;
+
+    /// Returns a collection, or `None` if there isn't a collection with that id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let collection = backend.collection("extensions-collection").await.unwrap().unwrap();
+    /// # })
+    /// ```
+    fn collection(&self, id: &str) -> impl Future<Output = Result<Option<Collection>>> + Send;
+
+    /// Adds a collection.
+    ///
+    /// Returns [Error::DuplicateId] if there's already a collection with the same id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend}, Catalog, Collection, Stac};
+    /// let (stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// backend.add_collection(Collection::new("a-collection")).await.unwrap();
+    /// # })
+    /// ```
+    fn add_collection(&mut self, collection: Collection)
+        -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns an item, or `None` if there isn't an item with that id in that collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let item = backend
+    ///     .item("extensions-collection", "proj-example")
+    ///     .await
+    ///     .unwrap()
+    ///     .unwrap();
+    /// # })
+    /// ```
+    fn item(
+        &self,
+        collection_id: &str,
+        item_id: &str,
+    ) -> impl Future<Output = Result<Option<Item>>> + Send;
+
+    /// Adds an item to the collection named by its `collection` field.
+    ///
+    /// Returns [Error::CollectionNotFound] if there isn't such a collection,
+    /// and [Error::DuplicateId] if the collection already has an item with
+    /// the same id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend}, Catalog, Collection, Item, Stac};
+    /// let (stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// backend.add_collection(Collection::new("a-collection")).await.unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.collection = Some("a-collection".into());
+    /// backend.add_item(item).await.unwrap();
+    /// # })
+    /// ```
+    fn add_item(&mut self, item: Item) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns a page of the items that match a search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Backend, MemoryBackend, Search}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let page = backend.search(Search::default()).await.unwrap();
+    /// assert_eq!(page.items.len(), 1);
+    /// # })
+    /// ```
+    fn search(&self, search: Search) -> impl Future<Output = Result<Page>> + Send;
+}
+
+/// A [Backend] that keeps its collections and items in a [Stac].
+///
+/// Items belong to the nearest collection they are below in the tree,
+/// regardless of their `collection` field. The [Stac] is fully resolved when the backend is
+/// created, so requests never read from the filesystem or the network. Added
+/// collections become children of the root, and added items become children
+/// of their collection. Item datetimes are
+/// [indexed](Stac::index_datetimes), so searches with a datetime don't check
+/// every item.
+#[derive(Debug)]
+pub struct MemoryBackend<R: Read> {
+    stac: Arc<Mutex<Stac<R>>>,
+    root: Handle,
+
+    /// Maps (collection handle, item id) to each item's handle.
+    ///
+    /// Always lock `stac` before this.
+    items: Arc<Mutex<HashMap<(Handle, String), Handle>>>,
+}
+
+impl<R: Read> MemoryBackend<R> {
+    /// Creates a new backend from the tree below (and including) `root`.
+    ///
+    /// Every object in the tree is read, and items are indexed by the
+    /// collection they belong to. The objects themselves aren't changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::MemoryBackend, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// ```
+    pub fn new(mut stac: Stac<R>, root: Handle) -> Result<MemoryBackend<R>> {
+        stac.index_datetimes();
+        for result in stac
+            .walk(root)
+            .visit(|stac, handle| stac.get(handle).map(|_| ()))
+        {
+            result?;
+        }
+        let backend = MemoryBackend {
+            stac: Arc::new(Mutex::new(stac)),
+            root,
+            items: Default::default(),
+        };
+        {
+            let mut stac = backend.lock();
+            let mut items = backend.items.lock().expect("not poisoned");
+            for collection in backend.collection_handles(&mut stac)? {
+                for item in Self::collection_item_handles(&mut stac, collection)? {
+                    let id = stac.get(item)?.id().to_string();
+                    let _ = items.insert((collection, id), item);
+                }
+            }
+        }
+        Ok(backend)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Stac<R>> {
+        self.stac.lock().expect("not poisoned")
+    }
+
+    fn collection_handles(&self, stac: &mut Stac<R>) -> Result<Vec<Handle>> {
+        let mut handles = Vec::new();
+        for result in stac
+            .walk(self.root)
+            .skip_items()
+            .visit(|stac, handle| Ok((handle, stac.get(handle)?.is_collection())))
+        {
+            let (handle, is_collection) = result?;
+            if is_collection {
+                handles.push(handle);
+            }
+        }
+        Ok(handles)
+    }
+
+    fn find_collection(&self, stac: &mut Stac<R>, id: &str) -> Result<Option<Handle>> {
+        for handle in self.collection_handles(stac)? {
+            if stac.get(handle)?.id() == id {
+                return Ok(Some(handle));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the nearest collection above an object, which is the collection it belongs to.
+    fn nearest_collection(stac: &mut Stac<R>, handle: Handle) -> Result<Option<Handle>> {
+        let mut parent = stac.parent(handle);
+        while let Some(handle) = parent {
+            if stac.get(handle)?.is_collection() {
+                return Ok(Some(handle));
+            }
+            parent = stac.parent(handle);
+        }
+        Ok(None)
+    }
+
+    /// Returns the handles of the items that belong to a collection.
+    ///
+    /// Items in a nested collection belong to that collection, not this one.
+    fn collection_item_handles(stac: &mut Stac<R>, handle: Handle) -> Result<Vec<Handle>> {
+        let mut items = Vec::new();
+        for result in stac.walk(handle).items_only().visit(|stac, item| {
+            Ok((Self::nearest_collection(stac, item)? == Some(handle)).then_some(item))
+        }) {
+            items.extend(result?);
+        }
+        Ok(items)
+    }
+
+    /// Returns the items that belong to a collection, with their `collection` field set.
+    fn collection_items(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+        collection_id: &str,
+    ) -> Result<Vec<Item>> {
+        let mut items = Vec::new();
+        for item in Self::collection_item_handles(stac, handle)? {
+            items.extend(item_in(stac, item, collection_id)?);
+        }
+        Ok(items)
+    }
+
+    /// Returns the items that belong to a collection whose datetimes
+    /// intersect an interval, with their `collection` field set.
+    fn collection_items_between(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+        collection_id: &str,
+        (start, end): Interval,
+    ) -> Result<Vec<Item>> {
+        let bound = |datetime: Option<_>| datetime.map_or(Bound::Unbounded, Bound::Included);
+        let mut items = Vec::new();
+        for item in stac.query_datetime(handle, (bound(start), bound(end)))? {
+            if Self::nearest_collection(stac, item)? != Some(handle) {
+                continue;
+            }
+            items.extend(item_in(stac, item, collection_id)?);
+        }
+        Ok(items)
+    }
+}
+
+/// Returns a copy of an item with its `collection` field set to the collection it belongs to.
+fn item_in<R: Read>(
+    stac: &mut Stac<R>,
+    handle: Handle,
+    collection_id: &str,
+) -> Result<Option<Item>> {
+    Ok(stac.get(handle)?.as_item().map(|item| {
+        let mut item = item.clone();
+        item.collection = Some(collection_id.into());
+        item
+    }))
+}
+
+impl<R: Read> Clone for MemoryBackend<R> {
+    fn clone(&self) -> MemoryBackend<R> {
+        MemoryBackend {
+            stac: self.stac.clone(),
+            root: self.root,
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<R: Read + Send + 'static> Backend for MemoryBackend<R> {
+    async fn collections(&self) -> Result<Vec<Collection>> {
+        let mut stac = self.lock();
+        let mut collections = Vec::new();
+        for handle in self.collection_handles(&mut stac)? {
+            if let Some(collection) = stac.get(handle)?.as_collection() {
+                collections.push(collection.clone());
+            }
+        }
+        Ok(collections)
+    }
+
+    async fn collection(&self, id: &str) -> Result<Option<Collection>> {
+        let mut stac = self.lock();
+        if let Some(handle) = self.find_collection(&mut stac, id)? {
+            Ok(stac.get(handle)?.as_collection().cloned())
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn add_collection(&mut self, collection: Collection) -> Result<()> {
+        let mut stac = self.lock();
+        if self.find_collection(&mut stac, &collection.id)?.is_some() {
+            return Err(Error::DuplicateId(collection.id));
+        }
+        let _ = stac.add_child(self.root, collection)?;
+        Ok(())
+    }
+
+    async fn item(&self, collection_id: &str, item_id: &str) -> Result<Option<Item>> {
+        let mut stac = self.lock();
+        let Some(collection) = self.find_collection(&mut stac, collection_id)? else {
+            return Ok(None);
+        };
+        let handle = self
+            .items
+            .lock()
+            .expect("not poisoned")
+            .get(&(collection, item_id.to_string()))
+            .copied();
+        if let Some(handle) = handle {
+            item_in(&mut stac, handle, collection_id)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn add_item(&mut self, item: Item) -> Result<()> {
+        let mut stac = self.lock();
+        let collection_id = item.collection.as_deref().unwrap_or_default().to_string();
+        let handle = self
+            .find_collection(&mut stac, &collection_id)?
+            .ok_or(Error::CollectionNotFound(collection_id.clone()))?;
+        let mut items = self.items.lock().expect("not poisoned");
+        let key = (handle, item.id.clone());
+        if items.contains_key(&key) {
+            return Err(Error::DuplicateId(item.id));
+        }
+        let item = stac.add_child(handle, item)?;
+        let _ = items.insert(key, item);
+        Ok(())
+    }
+
+    async fn search(&self, search: Search) -> Result<Page> {
+        let mut stac = self.lock();
+        let interval = search.interval()?;
+        let mut items = Vec::new();
+        for handle in self.collection_handles(&mut stac)? {
+            let collection_id = stac.get(handle)?.id().to_string();
+            if search
+                .collections
+                .as_ref()
+                .map(|collections| collections.contains(&collection_id))
+                .unwrap_or(true)
+            {
+                if let Some(interval) = interval {
+                    items.extend(self.collection_items_between(
+                        &mut stac,
+                        handle,
+                        &collection_id,
+                        interval,
+                    )?);
+                } else {
+                    items.extend(self.collection_items(&mut stac, handle, &collection_id)?);
+                }
+            }
+        }
+        let items = search.filter_items(items)?;
+        Ok(Page::from_offset(items, &search))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, MemoryBackend};
+    use crate::{server::Search, Catalog, Collection, Error, Item, Stac};
+
+    #[tokio::test]
+    async fn item_without_collection_field() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        let item = stac.add_child(collection, Item::new("an-item")).unwrap();
+        let backend = MemoryBackend::new(stac, root).unwrap();
+        assert!(backend
+            .lock()
+            .get(item)
+            .unwrap()
+            .as_item()
+            .unwrap()
+            .collection
+            .is_none());
+        let item = backend
+            .item("a-collection", "an-item")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.collection.unwrap(), "a-collection");
+        assert!(backend
+            .item("another-collection", "an-item")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(backend
+            .item("a-collection", "another-item")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn nested_collections() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let outer = stac.add_child(root, Collection::new("outer")).unwrap();
+        let inner = stac.add_child(outer, Collection::new("inner")).unwrap();
+        let _ = stac.add_child(outer, Item::new("outer-item")).unwrap();
+        let _ = stac.add_child(inner, Item::new("inner-item")).unwrap();
+        let backend = MemoryBackend::new(stac, root).unwrap();
+        let page = backend.search(Search::default()).await.unwrap();
+        let mut items: Vec<_> = page
+            .items
+            .iter()
+            .map(|item| (item.collection.as_deref().unwrap(), item.id.as_str()))
+            .collect();
+        items.sort();
+        assert_eq!(items, [("inner", "inner-item"), ("outer", "outer-item")]);
+        let search = Search {
+            datetime: Some("../..".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(backend.search(search).await.unwrap().items.len(), 2);
+        assert!(backend.item("inner", "inner-item").await.unwrap().is_some());
+        assert!(backend.item("outer", "inner-item").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn add_item() {
+        let (stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut backend = MemoryBackend::new(stac, root).unwrap();
+        let mut item = Item::new("an-item");
+        item.collection = Some("a-collection".into());
+        assert!(matches!(
+            backend.add_item(item.clone()).await.unwrap_err(),
+            Error::CollectionNotFound(_)
+        ));
+        backend
+            .add_collection(Collection::new("a-collection"))
+            .await
+            .unwrap();
+        backend.add_item(item.clone()).await.unwrap();
+        assert!(matches!(
+            backend.add_item(item).await.unwrap_err(),
+            Error::DuplicateId(_)
+        ));
+        assert!(backend
+            .item("a-collection", "an-item")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn search_datetime() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac
+            .add_child(root, Collection::new("a-collection"))
+            .unwrap();
+        for (id, datetime) in [
+            ("january", "2023-01-15T00:00:00Z"),
+            ("june", "2023-06-15T00:00:00Z"),
+        ] {
+            let mut item = Item::new(id);
+            item.properties.datetime = Some(datetime.to_string());
+            let _ = stac.add_child(collection, item).unwrap();
+        }
+        let backend = MemoryBackend::new(stac, root).unwrap();
+        let page = backend
+            .search(Search {
+                datetime: Some("2023-06-01T00:00:00Z/..".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, "june");
+        assert_eq!(page.items[0].collection.as_deref(), Some("a-collection"));
+        assert_eq!(
+            backend.search(Search::default()).await.unwrap().items.len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::{media_type, Catalog, Error, Link, Result};
+use serde_json::json;
+use std::collections::BTreeSet;
+use url::Url;
+
+/// A part of the STAC API specification that a server implements.
+///
+/// Each capability adds its conformance classes and links to a [LandingPage].
+/// [Core](Capability::Core) is always implemented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Capability {
+    /// [STAC API - Core](https://github.com/radiantearth/stac-api-spec/tree/main/core).
+    Core,
+
+    /// [STAC API - Collections](https://github.com/radiantearth/stac-api-spec/tree/main/collections),
+    /// i.e. `/collections` and `/collections/{collection_id}`.
+    Collections,
+
+    /// [STAC API - Features](https://github.com/radiantearth/stac-api-spec/tree/main/ogcapi-features),
+    /// i.e. `/collections/{collection_id}/items`.
+    Features,
+
+    /// [STAC API - Item Search](https://github.com/radiantearth/stac-api-spec/tree/main/item-search),
+    /// i.e. `/search`.
+    ItemSearch,
+
+    /// [Free-text search](https://github.com/stac-api-extensions/freetext-search),
+    /// i.e. the `q` parameter of `/search`.
+    FreeText,
+}
+
+/// Builds the landing page, the root document of a STAC API.
+///
+/// The landing page is a [Catalog] with the `conformsTo` classes of its
+/// [Capabilities](Capability), links to the endpoints of those
+/// capabilities, and a `child` link for each collection. Links are absolute,
+/// relative to the API's root url. Other fields, e.g. the id, title, and
+/// description, come from the catalog that the builder was created with.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{server::{Capability, LandingPage}, Catalog};
+/// let catalog = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+///     .unwrap()
+///     .with_capability(Capability::ItemSearch)
+///     .with_collections(["a-collection"])
+///     .build()
+///     .unwrap();
+/// assert!(catalog.links.iter().any(|link| link.rel == "search"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LandingPage {
+    catalog: Catalog,
+    url: Url,
+    capabilities: BTreeSet<Capability>,
+    collections: Vec<String>,
+    service_desc: Option<Link>,
+    service_doc: Option<Link>,
+}
+
+impl Capability {
+    /// Returns this capability's conformance classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::server::Capability;
+    /// assert_eq!(
+    ///     Capability::Core.conformance_classes(),
+    ///     ["https://api.stacspec.org/v1.0.0/core"]
+    /// );
+    /// ```
+    pub fn conformance_classes(&self) -> &'static [&'static str] {
+        match self {
+            Capability::Core => &["https://api.stacspec.org/v1.0.0/core"],
+            Capability::Collections => &["https://api.stacspec.org/v1.0.0/collections"],
+            Capability::Features => &[
+                "https://api.stacspec.org/v1.0.0/ogcapi-features",
+                "http://www.opengis.net/spec/ogcapi-features-1/1.0/conf/core",
+                "http://www.opengis.net/spec/ogcapi-features-1/1.0/conf/geojson",
+            ],
+            Capability::ItemSearch => &["https://api.stacspec.org/v1.0.0/item-search"],
+            Capability::FreeText => &["https://api.stacspec.org/v1.0.0-rc.1/item-search#free-text"],
+        }
+    }
+}
+
+impl LandingPage {
+    /// Creates a new landing page builder for an API served from `url`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::LandingPage, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test").unwrap();
+    /// ```
+    pub fn new(catalog: Catalog, url: &str) -> Result<LandingPage> {
+        let mut url = Url::parse(url)?;
+        if url.cannot_be_a_base() {
+            return Err(url::ParseError::RelativeUrlWithCannotBeABaseBase.into());
+        }
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(LandingPage {
+            catalog,
+            url,
+            capabilities: BTreeSet::from([Capability::Core]),
+            collections: Vec::new(),
+            service_desc: None,
+            service_doc: None,
+        })
+    }
+
+    /// Declares that the API implements a capability.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Capability, LandingPage}, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_capability(Capability::Collections);
+    /// ```
+    pub fn with_capability(mut self, capability: Capability) -> LandingPage {
+        let _ = self.capabilities.insert(capability);
+        self
+    }
+
+    /// Declares that the API implements every one of these capabilities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Capability, LandingPage}, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_capabilities([Capability::Collections, Capability::Features]);
+    /// ```
+    pub fn with_capabilities(
+        mut self,
+        capabilities: impl IntoIterator<Item = Capability>,
+    ) -> LandingPage {
+        self.capabilities.extend(capabilities);
+        self
+    }
+
+    /// Adds a `child` link for each of these collection ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::LandingPage, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_collections(["a-collection", "another-collection"]);
+    /// ```
+    pub fn with_collections(mut self, ids: impl IntoIterator<Item = impl ToString>) -> LandingPage {
+        self.collections
+            .extend(ids.into_iter().map(|id| id.to_string()));
+        self
+    }
+
+    /// Adds a `service-desc` link to the API's description, e.g. an OpenAPI document.
+    ///
+    /// Relative hrefs are resolved against the API's url.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::LandingPage, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_service_desc("api", "application/vnd.oai.openapi+json;version=3.0")
+    ///     .unwrap();
+    /// ```
+    pub fn with_service_desc(mut self, href: &str, media_type: &str) -> Result<LandingPage> {
+        self.service_desc = Some(self.link(href, "service-desc", media_type)?);
+        Ok(self)
+    }
+
+    /// Adds a `service-doc` link to the API's human-readable documentation.
+    ///
+    /// Relative hrefs are resolved against the API's url.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::LandingPage, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_service_doc("api.html", "text/html")
+    ///     .unwrap();
+    /// ```
+    pub fn with_service_doc(mut self, href: &str, media_type: &str) -> Result<LandingPage> {
+        self.service_doc = Some(self.link(href, "service-doc", media_type)?);
+        Ok(self)
+    }
+
+    /// Returns the conformance classes of the declared capabilities.
+    ///
+    /// This is also the body of the `/conformance` endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Capability, LandingPage}, Catalog};
+    /// let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .with_capability(Capability::ItemSearch);
+    /// assert_eq!(landing_page.conforms_to().len(), 2);
+    /// ```
+    pub fn conforms_to(&self) -> Vec<&'static str> {
+        self.capabilities
+            .iter()
+            .flat_map(|capability| capability.conformance_classes())
+            .copied()
+            .collect()
+    }
+
+    /// Builds the landing page.
+    ///
+    /// Any links on the original catalog are replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::LandingPage, Catalog};
+    /// let catalog = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(catalog.links[0].href, "http://stac.test/");
+    /// ```
+    pub fn build(self) -> Result<Catalog> {
+        let mut links = vec![
+            self.link("", "self", media_type::JSON)?,
+            self.link("", "root", media_type::JSON)?,
+        ];
+        links.extend(self.service_desc.clone());
+        links.extend(self.service_doc.clone());
+        if self.capabilities.contains(&Capability::Collections)
+            || self.capabilities.contains(&Capability::Features)
+        {
+            links.push(self.link("conformance", "conformance", media_type::JSON)?);
+            links.push(self.link("collections", "data", media_type::JSON)?);
+        }
+        if self.capabilities.contains(&Capability::ItemSearch) {
+            for method in ["GET", "POST"] {
+                let mut link = self.link("search", "search", media_type::GEOJSON)?;
+                let _ = link
+                    .additional_fields
+                    .insert("method".to_string(), method.into());
+                links.push(link);
+            }
+        }
+        for id in &self.collections {
+            let mut url = self.url.clone();
+            let _ = url
+                .path_segments_mut()
+                .expect("checked when the landing page was created")
+                .pop_if_empty()
+                .extend(["collections", id]);
+            let mut link = Link::new(url, "child");
+            link.r#type = Some(media_type::JSON.into());
+            links.push(link);
+        }
+        let conforms_to = self.conforms_to();
+        let mut catalog = self.catalog;
+        let _ = catalog
+            .additional_fields
+            .insert("conformsTo".to_string(), json!(conforms_to));
+        catalog.links = links;
+        Ok(catalog)
+    }
+
+    fn link(&self, href: &str, rel: &str, media_type: &str) -> Result<Link> {
+        let url = self.url.join(href).map_err(Error::from)?;
+        let mut link = Link::new(url, rel);
+        link.r#type = Some(media_type.into());
+        Ok(link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capability, LandingPage};
+    use crate::Catalog;
+
+    #[test]
+    fn core_only() {
+        let catalog = LandingPage::new(Catalog::new("an-api"), "http://stac.test/api")
+            .unwrap()
+            .with_collections(["a-collection"])
+            .build()
+            .unwrap();
+        let rels: Vec<_> = catalog.links.iter().map(|link| link.rel.as_str()).collect();
+        assert_eq!(rels, ["self", "root", "child"]);
+        assert_eq!(
+            catalog.links[2].href,
+            "http://stac.test/api/collections/a-collection"
+        );
+        assert_eq!(
+            catalog.additional_fields["conformsTo"],
+            serde_json::json!(["https://api.stacspec.org/v1.0.0/core"])
+        );
+    }
+
+    #[test]
+    fn capabilities() {
+        let landing_page = LandingPage::new(Catalog::new("an-api"), "http://stac.test")
+            .unwrap()
+            .with_capabilities([Capability::ItemSearch, Capability::Features])
+            .with_service_desc("api", "application/vnd.oai.openapi+json;version=3.0")
+            .unwrap();
+        let conforms_to = landing_page.conforms_to();
+        assert_eq!(conforms_to[0], "https://api.stacspec.org/v1.0.0/core");
+        assert_eq!(conforms_to.len(), 5);
+        let catalog = landing_page.build().unwrap();
+        let rels: Vec<_> = catalog.links.iter().map(|link| link.rel.as_str()).collect();
+        assert_eq!(
+            rels,
+            [
+                "self",
+                "root",
+                "service-desc",
+                "conformance",
+                "data",
+                "search",
+                "search"
+            ]
+        );
+    }
+}
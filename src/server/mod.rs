@@ -0,0 +1,794 @@
+//! Serve STAC collections and items as a [STAC API](https://github.com/radiantearth/stac-api-spec).
+//!
+//! This module is enabled by the `server` feature. An [Api] serves the
+//! collections and items of a [Backend], and [router] builds an [axum] router
+//! for it:
+//!
+//! - `/`: the landing page
+//! - `/conformance`: the conformance classes
+//! - `/collections`: every collection
+//! - `/collections/{collection_id}`: one collection
+//! - `/collections/{collection_id}/items`: a collection's items, paginated
+//! - `/collections/{collection_id}/items/{item_id}`: one item
+//! - `/search`: item search across every collection, with `GET` or `POST`,
+//!   including free-text search with `q`
+//!
+//! [MemoryBackend] keeps everything in a [Stac](crate::Stac), so serving a
+//! static catalog directory only takes a few lines:
+//!
+//! ```no_run
+//! use stac::{server::Api, Stac};
+//!
+//! # async fn run() {
+//! let (stac, root) = Stac::read("data/catalog.json").unwrap();
+//! let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+//! let listener = tokio::net::TcpListener::bind("localhost:7822").await.unwrap();
+//! axum::serve(listener, stac::server::router(api)).await.unwrap();
+//! # }
+//! ```
+
+mod backend;
+mod landing_page;
+mod search;
+
+pub use {
+    backend::{Backend, MemoryBackend},
+    landing_page::{Capability, LandingPage},
+    search::{GetSearch, Search},
+};
+
+use crate::{
+    media_type, Catalog, Collection, Error, Handle, Item, ItemCollection, Link, Object, Read,
+    Result, Stac,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+/// The default number of items in a page.
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// The capabilities of an [Api].
+const CAPABILITIES: [Capability; 4] = [
+    Capability::Collections,
+    Capability::Features,
+    Capability::ItemSearch,
+    Capability::FreeText,
+];
+
+/// A STAC API that serves the collections and items of a [Backend].
+#[derive(Debug, Clone)]
+pub struct Api<B: Backend> {
+    backend: B,
+    root: Catalog,
+    url: Url,
+}
+
+/// The response of the `/collections` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collections {
+    /// The collections.
+    pub collections: Vec<Collection>,
+
+    /// Links to this document and the landing page.
+    pub links: Vec<Link>,
+}
+
+/// The query parameters of the `/collections/{collection_id}/items` endpoint.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Items {
+    /// The maximum number of items in the page, [DEFAULT_LIMIT] if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// The token for the page, from a `next` link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// One page of the items that match a [Search], as returned by a [Backend].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Page {
+    /// The items in this page.
+    pub items: Vec<Item>,
+
+    /// The number of items that match the search across all pages, if known.
+    pub number_matched: Option<usize>,
+
+    /// The token for the next page, if there is one.
+    pub next: Option<String>,
+}
+
+impl<R: Read + Send + 'static> Api<MemoryBackend<R>> {
+    /// Creates a new API that serves the collections and items of a [Stac]
+    /// from `url`, with a [MemoryBackend].
+    ///
+    /// The landing page takes its id, title, and description from `root`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// ```
+    pub fn from_stac(mut stac: Stac<R>, root: Handle, url: &str) -> Result<Api<MemoryBackend<R>>> {
+        let mut catalog = Catalog::new(stac.get(root)?.id());
+        match stac.get(root)? {
+            Object::Catalog(root) => {
+                catalog.title = root.title.clone();
+                catalog.description = root.description.clone();
+            }
+            Object::Collection(root) => {
+                catalog.title = root.title.clone();
+                catalog.description = root.description.clone();
+            }
+            Object::Item(_) => {}
+        }
+        Api::new(MemoryBackend::new(stac, root)?, catalog, url)
+    }
+}
+
+impl<B: Backend> Api<B> {
+    /// Creates a new API that serves a [Backend] from `url`.
+    ///
+    /// The landing page takes its id, title, and description from `root`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Api, MemoryBackend}, Catalog, Stac};
+    /// let (stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let backend = MemoryBackend::new(stac, root).unwrap();
+    /// let api = Api::new(backend, Catalog::new("an-api"), "http://localhost:7822").unwrap();
+    /// ```
+    pub fn new(backend: B, root: Catalog, url: &str) -> Result<Api<B>> {
+        let mut url = Url::parse(url)?;
+        if url.cannot_be_a_base() {
+            return Err(url::ParseError::RelativeUrlWithCannotBeABaseBase.into());
+        }
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(Api { backend, root, url })
+    }
+
+    /// Returns this API's backend.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// let backend = api.backend();
+    /// ```
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the landing page.
+    ///
+    /// The landing page is built by a [LandingPage] with the
+    /// [Collections](Capability::Collections),
+    /// [Features](Capability::Features),
+    /// [ItemSearch](Capability::ItemSearch), and
+    /// [FreeText](Capability::FreeText) capabilities, and a `child` link
+    /// for each collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let catalog = api.landing_page().await.unwrap();
+    /// assert_eq!(catalog.id, "examples");
+    /// # })
+    /// ```
+    pub async fn landing_page(&self) -> Result<Catalog> {
+        let ids: Vec<_> = self
+            .backend
+            .collections()
+            .await?
+            .into_iter()
+            .map(|collection| collection.id)
+            .collect();
+        self.landing_page_builder()?.with_collections(ids).build()
+    }
+
+    /// Returns the body of the `/conformance` endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// let conformance = api.conformance().unwrap();
+    /// assert!(conformance["conformsTo"].is_array());
+    /// ```
+    pub fn conformance(&self) -> Result<Value> {
+        let conforms_to = self.landing_page_builder()?.conforms_to();
+        Ok(json!({ "conformsTo": conforms_to }))
+    }
+
+    /// Returns every collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let collections = api.collections().await.unwrap();
+    /// assert_eq!(collections.collections.len(), 3);
+    /// # })
+    /// ```
+    pub async fn collections(&self) -> Result<Collections> {
+        let collections = self
+            .backend
+            .collections()
+            .await?
+            .into_iter()
+            .map(|collection| self.collection_with_links(collection))
+            .collect();
+        Ok(Collections {
+            collections,
+            links: vec![
+                self.link(&["collections"], "self", media_type::JSON),
+                self.link(&[], "root", media_type::JSON),
+            ],
+        })
+    }
+
+    /// Returns a collection, or `None` if there isn't a collection with that id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let collection = api.collection("extensions-collection").await.unwrap().unwrap();
+    /// assert!(api.collection("not-a-collection").await.unwrap().is_none());
+    /// # })
+    /// ```
+    pub async fn collection(&self, id: &str) -> Result<Option<Collection>> {
+        Ok(self
+            .backend
+            .collection(id)
+            .await?
+            .map(|collection| self.collection_with_links(collection)))
+    }
+
+    /// Returns a page of a collection's items, or `None` if there isn't a
+    /// collection with that id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Api, Items}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let items = api
+    ///     .items("extensions-collection", Items::default())
+    ///     .await
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(items.features.len(), 1);
+    /// # })
+    /// ```
+    pub async fn items(&self, collection_id: &str, items: Items) -> Result<Option<ItemCollection>> {
+        if self.backend.collection(collection_id).await?.is_none() {
+            return Ok(None);
+        }
+        let search = Search {
+            collections: Some(vec![collection_id.to_string()]),
+            limit: items.limit,
+            token: items.token,
+            ..Default::default()
+        };
+        let page = self.backend.search(search.clone()).await?;
+        let mut item_collection = self.item_collection(
+            page,
+            &["collections", collection_id, "items"],
+            &[],
+            &search,
+            None,
+        )?;
+        item_collection.links.push(self.link(
+            &["collections", collection_id],
+            "collection",
+            media_type::JSON,
+        ));
+        Ok(Some(item_collection))
+    }
+
+    /// Returns an item, or `None` if there isn't an item with that id in that collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Api, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let item = api
+    ///     .item("extensions-collection", "proj-example")
+    ///     .await
+    ///     .unwrap()
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn item(&self, collection_id: &str, item_id: &str) -> Result<Option<Item>> {
+        Ok(self
+            .backend
+            .item(collection_id, item_id)
+            .await?
+            .map(|item| self.item_with_links(item)))
+    }
+
+    /// Searches for items across every collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Api, Search}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// let search = Search {
+    ///     ids: Some(vec!["proj-example".to_string()]),
+    ///     ..Default::default()
+    /// };
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let items = api.search(search).await.unwrap();
+    /// assert_eq!(items.features.len(), 1);
+    /// # })
+    /// ```
+    pub async fn search(&self, search: Search) -> Result<ItemCollection> {
+        let page = self.backend.search(search.clone()).await?;
+        let query = GetSearch::from(&search).filter_pairs();
+        self.item_collection(page, &["search"], &query, &search, None)
+    }
+
+    /// Searches for items across every collection, like
+    /// [search](Api::search), but with a `next` link for `POST /search`.
+    ///
+    /// The `next` link has the `POST` method and the whole search, with the
+    /// next page's token, as its body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Api, Search}, Stac};
+    /// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let items = api.post_search(Search::default()).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn post_search(&self, search: Search) -> Result<ItemCollection> {
+        let page = self.backend.search(search.clone()).await?;
+        self.item_collection(page, &["search"], &[], &search, Some(&search))
+    }
+
+    fn landing_page_builder(&self) -> Result<LandingPage> {
+        LandingPage::new(self.root.clone(), self.url.as_str())
+            .map(|landing_page| landing_page.with_capabilities(CAPABILITIES))
+    }
+
+    /// Returns the url of a path below the root, with each segment, e.g. an
+    /// id, percent-encoded.
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.url.clone();
+        if !segments.is_empty() {
+            let _ = url
+                .path_segments_mut()
+                .expect("checked when the api was created")
+                .pop_if_empty()
+                .extend(segments);
+        }
+        url
+    }
+
+    fn link(&self, segments: &[&str], rel: &str, media_type: &str) -> Link {
+        let mut link = Link::new(self.url(segments), rel);
+        link.r#type = Some(media_type.into());
+        link
+    }
+
+    fn item_with_links(&self, mut item: Item) -> Item {
+        let collection_id = item.collection.as_deref().unwrap_or_default();
+        item.links = vec![
+            self.link(
+                &["collections", collection_id, "items", &item.id],
+                "self",
+                media_type::GEOJSON,
+            ),
+            self.link(&[], "root", media_type::JSON),
+            self.link(&["collections", collection_id], "parent", media_type::JSON),
+            self.link(
+                &["collections", collection_id],
+                "collection",
+                media_type::JSON,
+            ),
+        ];
+        item
+    }
+
+    fn collection_with_links(&self, mut collection: Collection) -> Collection {
+        let id = collection.id.as_str();
+        collection.links = vec![
+            self.link(&["collections", id], "self", media_type::JSON),
+            self.link(&[], "root", media_type::JSON),
+            self.link(&[], "parent", media_type::JSON),
+            self.link(&["collections", id, "items"], "items", media_type::GEOJSON),
+        ];
+        collection
+    }
+
+    /// Converts a page into an item collection with `self` and `next` links.
+    ///
+    /// If there's a `body`, the `next` link is a `POST` with that body.
+    fn item_collection(
+        &self,
+        page: Page,
+        segments: &[&str],
+        query: &[(&str, String)],
+        search: &Search,
+        body: Option<&Search>,
+    ) -> Result<ItemCollection> {
+        let limit = search.limit.unwrap_or(DEFAULT_LIMIT);
+        let features = page
+            .items
+            .into_iter()
+            .map(|item| self.item_with_links(item))
+            .collect();
+        let mut item_collection = ItemCollection::new(features);
+        if let Some(number_matched) = page.number_matched {
+            let _ = item_collection
+                .additional_fields
+                .insert("numberMatched".to_string(), number_matched.into());
+        }
+        let _ = item_collection.additional_fields.insert(
+            "numberReturned".to_string(),
+            item_collection.features.len().into(),
+        );
+        let mut url = self.url(segments);
+        let _ = url
+            .query_pairs_mut()
+            .extend_pairs(query)
+            .append_pair("limit", &limit.to_string());
+        let mut self_url = url.clone();
+        if let Some(token) = search.token.as_deref() {
+            let _ = self_url.query_pairs_mut().append_pair("token", token);
+        }
+        let mut link = Link::new(self_url, "self");
+        link.r#type = Some(media_type::GEOJSON.into());
+        item_collection.links.push(link);
+        item_collection
+            .links
+            .push(self.link(&[], "root", media_type::JSON));
+        if let Some(next) = page.next {
+            let mut link = if let Some(body) = body {
+                let mut link = Link::new(self.url(segments), "next");
+                let body = Search {
+                    token: Some(next),
+                    ..body.clone()
+                };
+                let _ = link
+                    .additional_fields
+                    .insert("method".to_string(), "POST".into());
+                let _ = link
+                    .additional_fields
+                    .insert("body".to_string(), serde_json::to_value(body)?);
+                link
+            } else {
+                let _ = url.query_pairs_mut().append_pair("token", &next);
+                Link::new(url, "next")
+            };
+            link.r#type = Some(media_type::GEOJSON.into());
+            item_collection.links.push(link);
+        }
+        Ok(item_collection)
+    }
+}
+
+impl Page {
+    /// Returns the page of `items` selected by a search's `limit` and `token`,
+    /// where the token is the index of the first item in the page.
+    ///
+    /// Useful for backends that find every matching item in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::{Page, Search}, Item};
+    /// let items = vec![Item::new("a"), Item::new("b"), Item::new("c")];
+    /// let search = Search {
+    ///     limit: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let page = Page::from_offset(items, &search);
+    /// assert_eq!(page.items.len(), 2);
+    /// assert_eq!(page.next.unwrap(), "2");
+    /// ```
+    pub fn from_offset(items: Vec<Item>, search: &Search) -> Page {
+        let limit = search.limit.unwrap_or(DEFAULT_LIMIT);
+        let start = search
+            .token
+            .as_deref()
+            .and_then(|token| token.parse::<usize>().ok())
+            .unwrap_or(0);
+        let number_matched = items.len();
+        let items: Vec<_> = items.into_iter().skip(start).take(limit).collect();
+        let end = start + items.len();
+        Page {
+            items,
+            number_matched: Some(number_matched),
+            next: if start < end && end < number_matched {
+                Some(end.to_string())
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Creates an [axum] router for an [Api].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{server::Api, Stac};
+/// let (stac, root) = Stac::read("data/catalog.json").unwrap();
+/// let api = Api::from_stac(stac, root, "http://localhost:7822").unwrap();
+/// let router = stac::server::router(api);
+/// ```
+pub fn router<B: Backend>(api: Api<B>) -> Router {
+    Router::new()
+        .route("/", get(landing_page))
+        .route("/conformance", get(conformance))
+        .route("/collections", get(collections))
+        .route("/collections/:collection_id", get(collection))
+        .route("/collections/:collection_id/items", get(items))
+        .route("/collections/:collection_id/items/:item_id", get(item))
+        .route("/search", get(search).post(post_search))
+        .with_state(api)
+}
+
+async fn landing_page<B: Backend>(State(api): State<Api<B>>) -> Result<Json<Catalog>> {
+    api.landing_page().await.map(Json)
+}
+
+async fn conformance<B: Backend>(State(api): State<Api<B>>) -> Result<Json<Value>> {
+    api.conformance().map(Json)
+}
+
+async fn collections<B: Backend>(State(api): State<Api<B>>) -> Result<Json<Collections>> {
+    api.collections().await.map(Json)
+}
+
+async fn collection<B: Backend>(
+    State(api): State<Api<B>>,
+    Path(collection_id): Path<String>,
+) -> Result<Response> {
+    Ok(api
+        .collection(&collection_id)
+        .await?
+        .map(|collection| Json(collection).into_response())
+        .unwrap_or_else(|| not_found(format!("no collection with id {}", collection_id))))
+}
+
+async fn items<B: Backend>(
+    State(api): State<Api<B>>,
+    Path(collection_id): Path<String>,
+    Query(items): Query<Items>,
+) -> Result<Response> {
+    Ok(api
+        .items(&collection_id, items)
+        .await?
+        .map(geojson)
+        .unwrap_or_else(|| not_found(format!("no collection with id {}", collection_id))))
+}
+
+async fn item<B: Backend>(
+    State(api): State<Api<B>>,
+    Path((collection_id, item_id)): Path<(String, String)>,
+) -> Result<Response> {
+    Ok(api
+        .item(&collection_id, &item_id)
+        .await?
+        .map(geojson)
+        .unwrap_or_else(|| {
+            not_found(format!(
+                "no item with id {} in collection {}",
+                item_id, collection_id
+            ))
+        }))
+}
+
+async fn search<B: Backend>(
+    State(api): State<Api<B>>,
+    Query(search): Query<GetSearch>,
+) -> Result<Response> {
+    api.search(search.try_into()?).await.map(geojson)
+}
+
+async fn post_search<B: Backend>(
+    State(api): State<Api<B>>,
+    Json(search): Json<Search>,
+) -> Result<Response> {
+    api.post_search(search).await.map(geojson)
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let (status, code) = match self {
+            Error::InvalidBbox(_) | Error::InvalidDatetime(_) => {
+                (StatusCode::BAD_REQUEST, "BadRequest")
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "InternalServerError"),
+        };
+        (
+            status,
+            Json(json!({ "code": code, "description": self.to_string() })),
+        )
+            .into_response()
+    }
+}
+
+fn not_found(description: String) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "code": "NotFound", "description": description })),
+    )
+        .into_response()
+}
+
+fn geojson(value: impl Serialize) -> Response {
+    ([(CONTENT_TYPE, media_type::GEOJSON)], Json(value)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Api, Items, MemoryBackend, Search};
+    use crate::{Collection, Item, Reader, Stac};
+
+    fn api() -> Api<MemoryBackend<Reader>> {
+        let (stac, root) = Stac::read("data/catalog.json").unwrap();
+        Api::from_stac(stac, root, "http://stac.test/api").unwrap()
+    }
+
+    #[tokio::test]
+    async fn landing_page_links() {
+        let catalog = api().landing_page().await.unwrap();
+        let links = &catalog.links;
+        assert_eq!(links[0].href, "http://stac.test/api/");
+        assert!(links.iter().any(|link| link.is_child()
+            && link.href == "http://stac.test/api/collections/extensions-collection"));
+        assert!(catalog.additional_fields.contains_key("conformsTo"));
+    }
+
+    #[tokio::test]
+    async fn pagination() {
+        let (mut stac, root) = Stac::new(Collection::new("a-collection")).unwrap();
+        for i in 0..3 {
+            let _ = stac
+                .add_child(root, Item::new(format!("item-{}", i)))
+                .unwrap();
+        }
+        let api = Api::from_stac(stac, root, "http://stac.test").unwrap();
+        let items = |token: Option<&str>| Items {
+            limit: Some(2),
+            token: token.map(String::from),
+        };
+        let first = api
+            .items("a-collection", items(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.features.len(), 2);
+        assert_eq!(
+            first.features[0].links[0].href,
+            "http://stac.test/collections/a-collection/items/item-0"
+        );
+        let next = first.next_link().unwrap();
+        assert_eq!(
+            next.href,
+            "http://stac.test/collections/a-collection/items?limit=2&token=2"
+        );
+        let second = api
+            .items("a-collection", items(Some("2")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.features.len(), 1);
+        assert!(second.next_link().is_none());
+    }
+
+    #[tokio::test]
+    async fn ids_are_encoded() {
+        let (mut stac, root) = Stac::new(Collection::new("a b#c?d/e")).unwrap();
+        let _ = stac.add_child(root, Item::new("f g#h?i/j")).unwrap();
+        let api = Api::from_stac(stac, root, "http://stac.test/api").unwrap();
+        let collection = api.collection("a b#c?d/e").await.unwrap().unwrap();
+        assert_eq!(
+            collection.links[0].href,
+            "http://stac.test/api/collections/a%20b%23c%3Fd%2Fe"
+        );
+        assert_eq!(
+            collection.links[3].href,
+            "http://stac.test/api/collections/a%20b%23c%3Fd%2Fe/items"
+        );
+        let item = api.item("a b#c?d/e", "f g#h?i/j").await.unwrap().unwrap();
+        assert_eq!(
+            item.links[0].href,
+            "http://stac.test/api/collections/a%20b%23c%3Fd%2Fe/items/f%20g%23h%3Fi%2Fj"
+        );
+        let landing_page = api.landing_page().await.unwrap();
+        assert!(landing_page.links.iter().any(|link| link.is_child()
+            && link.href == "http://stac.test/api/collections/a%20b%23c%3Fd%2Fe"));
+    }
+
+    #[tokio::test]
+    async fn search_collections() {
+        let api = api();
+        let search = Search {
+            collections: Some(vec!["not-a-collection".to_string()]),
+            ..Default::default()
+        };
+        assert!(api.search(search).await.unwrap().features.is_empty());
+        assert!(api
+            .items("not-a-collection", Items::default())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn post_search_pagination() {
+        let (mut stac, root) = Stac::new(Collection::new("a-collection")).unwrap();
+        for (i, west) in [0.0, 10.0, 20.0].into_iter().enumerate() {
+            let mut item = Item::new(format!("item-{}", i));
+            item.bbox = Some(vec![west, 0.0, west + 1.0, 1.0]);
+            item.properties.datetime = Some(format!("2023-0{}-01T00:00:00Z", i + 1));
+            let _ = stac.add_child(root, item).unwrap();
+        }
+        let api = Api::from_stac(stac, root, "http://stac.test").unwrap();
+        let search = Search {
+            bbox: Some(vec![5.0, 0.0, 25.0, 1.0]),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let first = api.post_search(search.clone()).await.unwrap();
+        assert_eq!(first.features[0].id, "item-1");
+        let next = first.next_link().unwrap();
+        assert_eq!(next.additional_fields["method"], "POST");
+        let body: Search = serde_json::from_value(next.additional_fields["body"].clone()).unwrap();
+        assert_eq!(body.token.as_deref(), Some("1"));
+        let second = api.post_search(body).await.unwrap();
+        assert_eq!(second.features[0].id, "item-2");
+        assert!(second.next_link().is_none());
+
+        let search = Search {
+            datetime: Some("2023-02-15T00:00:00Z/..".to_string()),
+            ..search
+        };
+        let items = api.search(search).await.unwrap();
+        assert_eq!(items.features[0].id, "item-2");
+        assert!(items.links[0].href.contains("datetime="));
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{datetime, Error, Item, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An item search, e.g. the body of a `POST /search` request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Search {
+    /// Only return items whose bbox intersects this bbox.
+    ///
+    /// Two-dimensional (west, south, east, north) and three-dimensional
+    /// bboxes are supported, but only the horizontal bounds are compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+
+    /// Only return items whose datetimes intersect this datetime or interval.
+    ///
+    /// Intervals are separated by a `/`, and either end can be open with
+    /// `..`, e.g. `2023-01-01T00:00:00Z/..`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<String>,
+
+    /// Only return items with these ids.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<String>>,
+
+    /// Only return items in these collections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<String>>,
+
+    /// Only return items whose title, description, or keywords match this
+    /// free-text search.
+    ///
+    /// Terms are separated by commas, and an item matches if any term is a
+    /// case-insensitive substring of one of those fields, e.g.
+    /// `wildfire,"burn scar"`. Quotes around a term are ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    /// The maximum number of items in the page, [DEFAULT_LIMIT](super::DEFAULT_LIMIT) if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// The token for the page, from a `next` link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// The query parameters of the `GET /search` endpoint.
+///
+/// Lists are comma-separated, e.g. `?ids=an-item,another-item`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetSearch {
+    /// Comma-separated bbox bounds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<String>,
+
+    /// A datetime or interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<String>,
+
+    /// Comma-separated item ids.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<String>,
+
+    /// Comma-separated collection ids.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collections: Option<String>,
+
+    /// A free-text search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    /// The maximum number of items in the page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// The token for the page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// A search's parsed bbox, datetime interval, and free-text terms.
+#[derive(Debug)]
+struct Filter {
+    bbox: Option<[f64; 4]>,
+    interval: Option<Interval>,
+    terms: Option<Vec<String>>,
+}
+
+/// A datetime interval, where open ends are `None`.
+pub(super) type Interval = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+impl Search {
+    /// Returns true if an item matches this search.
+    ///
+    /// The `limit` and `token` aren't used. Items are matched to
+    /// `collections` by their `collection` field. Items without a bbox or
+    /// geometry don't match a bbox, and items without datetimes don't match
+    /// a datetime.
+    ///
+    /// Returns [Error::InvalidBbox] or [Error::InvalidDatetime] if the
+    /// search's bbox or datetime can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Search, Item};
+    /// let mut item = Item::new("an-item");
+    /// item.bbox = Some(vec![-105.0, 40.0, -104.0, 41.0]);
+    /// let search = Search {
+    ///     bbox: Some(vec![-104.5, 40.5, -103.0, 42.0]),
+    ///     ..Default::default()
+    /// };
+    /// assert!(search.matches(&item).unwrap());
+    /// ```
+    pub fn matches(&self, item: &Item) -> Result<bool> {
+        self.filter()?.matches(self, item)
+    }
+
+    /// Returns only the items that match this search, in order.
+    ///
+    /// The search is parsed once, so this is cheaper than calling
+    /// [matches](Search::matches) on each item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{server::Search, Item};
+    /// let search = Search {
+    ///     ids: Some(vec!["b".to_string()]),
+    ///     ..Default::default()
+    /// };
+    /// let items = search.filter_items(vec![Item::new("a"), Item::new("b")]).unwrap();
+    /// assert_eq!(items[0].id, "b");
+    /// ```
+    pub fn filter_items(&self, items: Vec<Item>) -> Result<Vec<Item>> {
+        let filter = self.filter()?;
+        let mut matches = Vec::new();
+        for item in items {
+            if filter.matches(self, &item)? {
+                matches.push(item);
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Returns the parsed datetime interval, if there is one.
+    pub(super) fn interval(&self) -> Result<Option<Interval>> {
+        self.datetime.as_deref().map(parse_interval).transpose()
+    }
+
+    fn filter(&self) -> Result<Filter> {
+        let bbox = self
+            .bbox
+            .as_ref()
+            .map(|bbox| {
+                crate::geometry::horizontal(bbox)
+                    .filter(|bbox| bbox[1] <= bbox[3])
+                    .ok_or_else(|| Error::InvalidBbox(format!("{:?}", bbox)))
+            })
+            .transpose()?;
+        let interval = self.interval()?;
+        let terms = self.q.as_deref().map(parse_terms);
+        Ok(Filter {
+            bbox,
+            interval,
+            terms,
+        })
+    }
+}
+
+impl Filter {
+    fn matches(&self, search: &Search, item: &Item) -> Result<bool> {
+        if let Some(ids) = search.ids.as_ref() {
+            if !ids.contains(&item.id) {
+                return Ok(false);
+            }
+        }
+        if let Some(collections) = search.collections.as_ref() {
+            if !item
+                .collection
+                .as_deref()
+                .map(|collection| collections.iter().any(|c| c == collection))
+                .unwrap_or(false)
+            {
+                return Ok(false);
+            }
+        }
+        if let Some(bbox) = self.bbox {
+            match item.horizontal_bbox() {
+                Some(item_bbox) if intersects(bbox, item_bbox) => {}
+                _ => return Ok(false),
+            }
+        }
+        if let Some((start, end)) = self.interval {
+            match item.interval()? {
+                Some((item_start, item_end))
+                    if start.map(|start| item_end >= start).unwrap_or(true)
+                        && end.map(|end| item_start <= end).unwrap_or(true) => {}
+                _ => return Ok(false),
+            }
+        }
+        if let Some(terms) = self.terms.as_ref() {
+            if !matches_terms(terms, item) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl TryFrom<GetSearch> for Search {
+    type Error = Error;
+
+    fn try_from(get_search: GetSearch) -> Result<Search> {
+        let bbox = get_search
+            .bbox
+            .map(|bbox| {
+                bbox.split(',')
+                    .map(|s| s.trim().parse::<f64>())
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|_| Error::InvalidBbox(bbox.clone()))
+            })
+            .transpose()?;
+        Ok(Search {
+            bbox,
+            datetime: get_search.datetime,
+            ids: split(get_search.ids),
+            collections: split(get_search.collections),
+            q: get_search.q,
+            limit: get_search.limit,
+            token: get_search.token,
+        })
+    }
+}
+
+impl From<&Search> for GetSearch {
+    fn from(search: &Search) -> GetSearch {
+        let join = |list: &Vec<String>| list.join(",");
+        GetSearch {
+            bbox: search.bbox.as_ref().map(|bbox| {
+                bbox.iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            datetime: search.datetime.clone(),
+            ids: search.ids.as_ref().map(join),
+            collections: search.collections.as_ref().map(join),
+            q: search.q.clone(),
+            limit: search.limit,
+            token: search.token.clone(),
+        }
+    }
+}
+
+impl GetSearch {
+    /// Returns the non-empty query parameters, except the `limit` and `token`.
+    pub(super) fn filter_pairs(&self) -> Vec<(&'static str, String)> {
+        [
+            ("bbox", &self.bbox),
+            ("datetime", &self.datetime),
+            ("ids", &self.ids),
+            ("collections", &self.collections),
+            ("q", &self.q),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|value| (key, value)))
+        .collect()
+    }
+}
+
+/// Parses a single datetime or a `start/end` interval, where either end can be
+/// open (`..` or empty).
+///
+/// A single datetime is parsed as an interval that starts and ends at that datetime.
+fn parse_interval(interval: &str) -> Result<Interval> {
+    if let Some((start, end)) = interval.split_once('/') {
+        let bound = |s: &str| match s {
+            "" | ".." => Ok(None),
+            _ => datetime::parse(s).map(Some),
+        };
+        let (start, end) = (bound(start)?, bound(end)?);
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(Error::InvalidDatetime(interval.to_string()));
+            }
+        }
+        Ok((start, end))
+    } else {
+        let datetime = datetime::parse(interval)?;
+        Ok((Some(datetime), Some(datetime)))
+    }
+}
+
+/// Splits a free-text search into lowercase terms, without surrounding quotes.
+fn parse_terms(q: &str) -> Vec<String> {
+    q.split(',')
+        .map(|term| term.trim().trim_matches('"').trim().to_lowercase())
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// Returns true if any term is in an item's title, description, or keywords.
+fn matches_terms(terms: &[String], item: &Item) -> bool {
+    let properties = &item.properties.additional_fields;
+    let mut text: Vec<&str> = ["title", "description"]
+        .into_iter()
+        .filter_map(|key| properties.get(key).and_then(Value::as_str))
+        .collect();
+    if let Some(keywords) = properties.get("keywords").and_then(Value::as_array) {
+        text.extend(keywords.iter().filter_map(Value::as_str));
+    }
+    let text: Vec<String> = text.into_iter().map(str::to_lowercase).collect();
+    terms
+        .iter()
+        .any(|term| text.iter().any(|text| text.contains(term.as_str())))
+}
+
+/// Returns true if two bboxes intersect, including bboxes that cross the antimeridian.
+fn intersects(a: [f64; 4], b: [f64; 4]) -> bool {
+    let ranges = |bbox: [f64; 4]| {
+        if bbox[0] > bbox[2] {
+            vec![(bbox[0], 180.0), (-180.0, bbox[2])]
+        } else {
+            vec![(bbox[0], bbox[2])]
+        }
+    };
+    a[1] <= b[3]
+        && b[1] <= a[3]
+        && ranges(a).into_iter().any(|(a_west, a_east)| {
+            ranges(b)
+                .into_iter()
+                .any(|(b_west, b_east)| a_west <= b_east && b_west <= a_east)
+        })
+}
+
+fn split(list: Option<String>) -> Option<Vec<String>> {
+    list.map(|list| list.split(',').map(|s| s.trim().to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GetSearch, Search};
+    use crate::Item;
+    use serde_json::json;
+
+    fn item(datetime: &str) -> Item {
+        let mut item = Item::new("an-item");
+        item.bbox = Some(vec![170.0, 0.0, 175.0, 5.0]);
+        item.properties.datetime = Some(datetime.to_string());
+        item
+    }
+
+    #[test]
+    fn datetime_intervals() {
+        let item = item("2023-06-01T00:00:00Z");
+        for (datetime, expected) in [
+            ("2023-06-01T00:00:00Z", true),
+            ("2023-01-01T00:00:00Z/..", true),
+            ("../2023-01-01T00:00:00Z", false),
+            ("2023-01-01T00:00:00Z/2023-12-31T00:00:00Z", true),
+            ("2023-07-01T00:00:00Z/", false),
+        ] {
+            let search = Search {
+                datetime: Some(datetime.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(search.matches(&item).unwrap(), expected, "{}", datetime);
+        }
+        let search = Search {
+            datetime: Some("2023-12-31T00:00:00Z/2023-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        assert!(search.matches(&item).is_err());
+    }
+
+    #[test]
+    fn bbox_across_antimeridian() {
+        let item = item("2023-06-01T00:00:00Z");
+        let search = Search {
+            bbox: Some(vec![172.0, 1.0, -170.0, 2.0]),
+            ..Default::default()
+        };
+        assert!(search.matches(&item).unwrap());
+        let search = Search {
+            bbox: Some(vec![-179.0, 1.0, -170.0, 2.0]),
+            ..Default::default()
+        };
+        assert!(!search.matches(&item).unwrap());
+    }
+
+    #[test]
+    fn collections() {
+        let mut item = item("2023-06-01T00:00:00Z");
+        let search = Search {
+            collections: Some(vec!["a-collection".to_string()]),
+            ..Default::default()
+        };
+        assert!(!search.matches(&item).unwrap());
+        item.collection = Some("a-collection".into());
+        assert!(search.matches(&item).unwrap());
+    }
+
+    #[test]
+    fn free_text() {
+        let mut item = item("2023-06-01T00:00:00Z");
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("title".to_string(), "Creek Fire".into());
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("keywords".to_string(), json!(["wildfire", "burn scar"]));
+        for (q, expected) in [
+            ("fire", true),
+            ("FLOOD,Wildfire", true),
+            ("\"burn scar\"", true),
+            ("flood", false),
+            ("", false),
+        ] {
+            let search = Search {
+                q: Some(q.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(search.matches(&item).unwrap(), expected, "{}", q);
+        }
+    }
+
+    #[test]
+    fn get_roundtrip() {
+        let get_search: GetSearch = serde_json::from_value(json!({
+            "bbox": "-105,40,-104,41",
+            "ids": "a,b",
+            "q": "wildfire",
+            "limit": 2,
+        }))
+        .unwrap();
+        let search = Search::try_from(get_search.clone()).unwrap();
+        assert_eq!(search.bbox.as_ref().unwrap(), &[-105.0, 40.0, -104.0, 41.0]);
+        assert_eq!(search.ids.as_ref().unwrap(), &["a", "b"]);
+        assert_eq!(GetSearch::from(&search), get_search);
+        assert!(Search::try_from(GetSearch {
+            bbox: Some("west,south".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Register sidecar files, like thumbnails and overviews, as assets.
+//!
+//! Many pipelines write small files next to their data, named by convention,
+//! e.g. `LC08_001_thumb.png` or `B04.tif.ovr`. A [Sidecar] describes one of
+//! these conventions, and [Item::add_sidecar_assets] finds the matching files
+//! in a directory and adds them to an item with the right roles and media
+//! types.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use stac::Item;
+//! let mut item = Item::new("LC08_001");
+//! let keys = item.add_sidecar_assets("scenes/LC08_001").unwrap();
+//! ```
+
+use crate::{id, media_type, Asset, Href, Item, Result};
+use std::{fs, path::Path};
+
+/// A naming convention for sidecar files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sidecar {
+    /// The end of the file name, e.g. `"_thumb.png"`.
+    pub suffix: &'static str,
+
+    /// The asset key, e.g. `"thumbnail"`.
+    ///
+    /// If the item already has an asset with this key, the start of the file
+    /// name is used as a prefix, e.g. `"B04.tif-overviews"`.
+    pub key: &'static str,
+
+    /// The asset role, e.g. `"thumbnail"`.
+    pub role: &'static str,
+
+    /// The asset media type.
+    pub media_type: &'static str,
+}
+
+/// The conventions used by [Item::add_sidecar_assets].
+pub const DEFAULTS: &[Sidecar] = &[
+    Sidecar {
+        suffix: "_thumb.png",
+        key: "thumbnail",
+        role: "thumbnail",
+        media_type: media_type::PNG,
+    },
+    Sidecar {
+        suffix: "_preview.jpg",
+        key: "preview",
+        role: "overview",
+        media_type: media_type::JPEG,
+    },
+    Sidecar {
+        suffix: ".ovr",
+        key: "overviews",
+        role: "overview",
+        media_type: media_type::GEOTIFF,
+    },
+];
+
+impl Item {
+    /// Adds the sidecar files in a directory as assets, using the [DEFAULTS] conventions.
+    ///
+    /// Returns the keys of the new assets. Files that are already the href of
+    /// one of this item's assets are skipped, so this can be run more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::Item;
+    /// let mut item = Item::new("LC08_001");
+    /// let keys = item.add_sidecar_assets("scenes/LC08_001").unwrap();
+    /// ```
+    pub fn add_sidecar_assets(&mut self, directory: impl AsRef<Path>) -> Result<Vec<String>> {
+        self.add_sidecar_assets_with(directory, DEFAULTS)
+    }
+
+    /// Adds the sidecar files in a directory as assets, using custom conventions.
+    ///
+    /// Files are matched against the conventions in order, and the first
+    /// match wins. Files are visited in name order, so keys are stable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{media_type, sidecar::Sidecar, Item};
+    /// let mut item = Item::new("an-id");
+    /// let keys = item
+    ///     .add_sidecar_assets_with(
+    ///         "scenes/an-id",
+    ///         &[Sidecar {
+    ///             suffix: ".aux.xml",
+    ///             key: "metadata",
+    ///             role: "metadata",
+    ///             media_type: media_type::XML,
+    ///         }],
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn add_sidecar_assets_with(
+        &mut self,
+        directory: impl AsRef<Path>,
+        sidecars: &[Sidecar],
+    ) -> Result<Vec<String>> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort();
+        let mut keys = Vec::new();
+        for path in paths {
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some((prefix, sidecar)) = sidecars.iter().find_map(|sidecar| {
+                file_name
+                    .strip_suffix(sidecar.suffix)
+                    .map(|prefix| (prefix, sidecar))
+            }) else {
+                continue;
+            };
+            let href = String::from(Href::from(path.as_path()));
+            if self.assets.values().any(|asset| asset.href == href) {
+                continue;
+            }
+            let key = if self.assets.contains_key(sidecar.key) {
+                format!("{}-{}", id::slugify(prefix), sidecar.key)
+            } else {
+                sidecar.key.to_string()
+            };
+            if self.assets.contains_key(&key) {
+                continue;
+            }
+            let mut asset = Asset::new(href);
+            asset.r#type = Some(sidecar.media_type.into());
+            asset.roles = Some(vec![sidecar.role.to_string()]);
+            let _ = self.assets.insert(key.clone(), asset);
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{media_type, Item};
+    use std::fs::File;
+
+    #[test]
+    fn add_sidecar_assets() {
+        let directory = tempfile::tempdir().unwrap();
+        for name in [
+            "scene_thumb.png",
+            "scene_preview.jpg",
+            "B04.tif.ovr",
+            "B03.tif.ovr",
+            "B04.tif",
+        ] {
+            let _ = File::create(directory.path().join(name)).unwrap();
+        }
+        let mut item = Item::new("scene");
+        let keys = item.add_sidecar_assets(directory.path()).unwrap();
+        assert_eq!(
+            keys,
+            ["overviews", "B04.tif-overviews", "preview", "thumbnail"]
+        );
+        let thumbnail = &item.assets["thumbnail"];
+        assert_eq!(thumbnail.r#type.as_deref(), Some(media_type::PNG));
+        assert_eq!(thumbnail.roles.as_ref().unwrap(), &["thumbnail"]);
+        assert!(thumbnail.href.ends_with("/scene_thumb.png"));
+        assert!(item
+            .add_sidecar_assets(directory.path())
+            .unwrap()
+            .is_empty());
+    }
+}
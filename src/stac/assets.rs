@@ -0,0 +1,190 @@
+use super::{Handle, Stac};
+use crate::{Error, Object, Read, Result};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+impl<R: Read> Stac<R> {
+    /// Renames asset keys, e.g. `B04` to `red`, on every object in a subtree.
+    ///
+    /// Item and collection assets are renamed, as are the keys of a
+    /// collection's `item_assets` and any summaries that are keyed by asset. Keys can be
+    /// swapped, but if a new key collides with another asset on the same
+    /// object, this returns [Error::DuplicateAssetKey]. Each object is checked
+    /// before it is changed, but objects that were visited before the error
+    /// keep their new keys.
+    ///
+    /// Returns the number of objects that were changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Asset, Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// let _ = item.assets.insert("B04".to_string(), Asset::new("./B04.tif"));
+    /// let item = stac.add_child(root, item).unwrap();
+    /// assert_eq!(stac.rename_asset_keys(root, [("B04", "red")]).unwrap(), 1);
+    /// let Some(item) = stac.get(item).unwrap().as_item() else { unreachable!() };
+    /// assert_eq!(item.assets["red"].href, "./B04.tif");
+    /// ```
+    pub fn rename_asset_keys<K, V>(
+        &mut self,
+        handle: Handle,
+        renames: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<usize>
+    where
+        K: ToString,
+        V: ToString,
+    {
+        let renames: HashMap<String, String> = renames
+            .into_iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        let mut count = 0;
+        for result in self
+            .walk(handle)
+            .visit(|stac, handle| rename_asset_keys(stac.get_mut(handle)?, &renames))
+        {
+            if result? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+fn rename_asset_keys(object: &mut Object, renames: &HashMap<String, String>) -> Result<bool> {
+    match object {
+        Object::Item(item) => {
+            if !check(&item.id, item.assets.keys(), renames)? {
+                return Ok(false);
+            }
+            item.assets = std::mem::take(&mut item.assets)
+                .into_iter()
+                .map(|(key, asset)| (rename(key, renames), asset))
+                .collect();
+            Ok(true)
+        }
+        Object::Collection(collection) => {
+            let mut maps = Vec::new();
+            if let Some(Value::Object(item_assets)) =
+                collection.additional_fields.get_mut("item_assets")
+            {
+                maps.push(item_assets);
+            }
+            if let Some(summaries) = collection.summaries.as_mut() {
+                maps.push(summaries);
+            }
+            let mut changed = false;
+            if let Some(assets) = collection.assets.as_ref() {
+                changed |= check(&collection.id, assets.keys(), renames)?;
+            }
+            for map in &maps {
+                changed |= check(&collection.id, map.keys(), renames)?;
+            }
+            if let Some(assets) = collection.assets.as_mut() {
+                *assets = std::mem::take(assets)
+                    .into_iter()
+                    .map(|(key, asset)| (rename(key, renames), asset))
+                    .collect();
+            }
+            for map in maps {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, value)| (rename(key, renames), value))
+                    .collect();
+            }
+            Ok(changed)
+        }
+        Object::Catalog(_) => Ok(false),
+    }
+}
+
+/// Returns true if any of these keys will be renamed, or an error if renaming them would create duplicates.
+fn check<K: AsRef<str>>(
+    id: &str,
+    keys: impl Iterator<Item = K>,
+    renames: &HashMap<String, String>,
+) -> Result<bool> {
+    let mut renamed = false;
+    let mut new_keys = HashSet::new();
+    for key in keys {
+        let key = key.as_ref();
+        let new_key = renames.get(key).map(String::as_str).unwrap_or(key);
+        renamed |= new_key != key;
+        if !new_keys.insert(new_key.to_string()) {
+            return Err(Error::DuplicateAssetKey {
+                id: id.to_string(),
+                key: new_key.to_string(),
+            });
+        }
+    }
+    Ok(renamed)
+}
+
+fn rename(key: String, renames: &HashMap<String, String>) -> String {
+    renames.get(&key).cloned().unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Asset, Catalog, Collection, Error, Item, Object, Stac};
+    use serde_json::json;
+
+    fn item(keys: &[&str]) -> Item {
+        let mut item = Item::new(keys.join("-"));
+        for key in keys {
+            let _ = item
+                .assets
+                .insert(key.to_string(), Asset::new(format!("./{}.tif", key)));
+        }
+        item
+    }
+
+    #[test]
+    fn rename_collection_and_items() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut collection = Collection::new("a-collection");
+        let _ = collection.additional_fields.insert(
+            "item_assets".to_string(),
+            json!({"B04": {"title": "Red"}, "B03": {"title": "Green"}}),
+        );
+        collection.summaries = Some(json!({"B04": {"minimum": 0}}).as_object().unwrap().clone());
+        collection.assets = Some([("B04".to_string(), Asset::new("./mosaic-B04.tif"))].into());
+        let collection = stac.add_child(root, collection).unwrap();
+        let swapped = stac.add_child(collection, item(&["a", "b"])).unwrap();
+        let _ = stac.add_child(collection, item(&["other"])).unwrap();
+        let renamed = stac
+            .rename_asset_keys(root, [("B04", "red"), ("a", "b"), ("b", "a")])
+            .unwrap();
+        assert_eq!(renamed, 2);
+
+        let Object::Collection(collection) = stac.get(collection).unwrap() else {
+            panic!("not a collection");
+        };
+        assert_eq!(
+            collection.additional_fields["item_assets"],
+            json!({"red": {"title": "Red"}, "B03": {"title": "Green"}})
+        );
+        assert!(collection.summaries.as_ref().unwrap().contains_key("red"));
+        assert_eq!(
+            collection.assets.as_ref().unwrap()["red"].href,
+            "./mosaic-B04.tif"
+        );
+        let item = stac.get(swapped).unwrap().as_item().unwrap();
+        assert_eq!(item.assets["a"].href, "./b.tif");
+        assert_eq!(item.assets["b"].href, "./a.tif");
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let handle = stac.add_child(root, item(&["B04", "red"])).unwrap();
+        assert!(matches!(
+            stac.rename_asset_keys(root, [("B04", "red")]).unwrap_err(),
+            Error::DuplicateAssetKey { id, key } if id == "B04-red" && key == "red"
+        ));
+        let item = stac.get(handle).unwrap().as_item().unwrap();
+        assert!(item.assets.contains_key("B04"));
+    }
+}
@@ -0,0 +1,447 @@
+use super::{join, Handle, Stac, ROOT_HANDLE};
+use crate::{
+    layout::Strategy, AsyncRead, AsyncReader, AsyncWrite, Error, Href, Layout, Object,
+    ObjectHrefTuple, Read, Result,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::Arc,
+};
+use url::Url;
+
+/// An async [Stac].
+///
+/// An `AsyncStac` resolves objects with an [AsyncRead], so it can be used
+/// inside of async services without wrapping every resolution in
+/// `spawn_blocking`. Methods that might read, e.g. [get](AsyncStac::get),
+/// [walk](AsyncStac::walk), and [write](AsyncStac::write), are `async`.
+///
+/// An `AsyncStac` dereferences to a [Stac] for everything that doesn't read,
+/// e.g. [Stac::children] or [Stac::href]. That `Stac` uses the [AsyncOnly]
+/// reader, so calling one of its reading methods on an unresolved object
+/// returns an error. Use [AsyncStac::resolve] to read a whole subtree before
+/// working with it synchronously, e.g. with a [Layout].
+///
+/// # Examples
+///
+/// ```
+/// use stac::AsyncStac;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+/// let child = stac.children(root)[0];
+/// assert_eq!(stac.get(child).await.unwrap().id(), "extensions-collection");
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct AsyncStac<R: AsyncRead> {
+    pub(super) stac: Stac<AsyncOnly>,
+    pub(super) reader: R,
+}
+
+/// A [Read] that refuses to read.
+///
+/// Used by the [Stac] inside an [AsyncStac], so that all reads go through the
+/// `AsyncStac`'s [AsyncRead].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsyncOnly;
+
+impl AsyncStac<AsyncReader> {
+    /// Creates a new `AsyncStac` with the provided object and configured to use [AsyncReader].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, Catalog};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, root) = AsyncStac::new(Catalog::new("an-id")).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn new(
+        object: impl Into<ObjectHrefTuple>,
+    ) -> Result<(AsyncStac<AsyncReader>, Handle)> {
+        AsyncStac::new_with_reader(object, AsyncReader::default()).await
+    }
+
+    /// Reads an [Href] with [AsyncReader].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::AsyncStac;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn read(href: impl Into<Href>) -> Result<(AsyncStac<AsyncReader>, Handle)> {
+        let reader = AsyncReader::default();
+        let href_object = reader.read(href.into()).await?;
+        AsyncStac::new_with_reader(href_object, reader).await
+    }
+}
+
+impl<R: AsyncRead> AsyncStac<R> {
+    /// Creates a new `AsyncStac` from the [Object] and [AsyncRead].
+    ///
+    /// Like [Stac::new_with_reader], if the object has a `root` link that
+    /// isn't the object itself, the root is read and used as the root of the
+    /// `AsyncStac`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, AsyncReader, Catalog};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, root) = AsyncStac::new_with_reader(Catalog::new("an-id"), AsyncReader::default())
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn new_with_reader(
+        object: impl Into<ObjectHrefTuple>,
+        reader: R,
+    ) -> Result<(AsyncStac<R>, Handle)> {
+        let (object, href) = object.into();
+        if let Some(link) = object.root_link() {
+            let root_href = join(href.as_ref(), &link.href)?;
+            if href.as_ref() != Some(&root_href) {
+                let root = reader.read(root_href).await?;
+                let mut stac = AsyncStac::rooted(root, reader).await?;
+                let handle = stac.add((object, href)).await?;
+                return Ok((stac, handle));
+            }
+        }
+        let stac = AsyncStac::rooted((object, href), reader).await?;
+        Ok((stac, ROOT_HANDLE))
+    }
+
+    async fn rooted(object: impl Into<ObjectHrefTuple>, reader: R) -> Result<AsyncStac<R>> {
+        let mut stac = AsyncStac {
+            stac: Stac::empty(AsyncOnly),
+            reader,
+        };
+        stac.set_object(ROOT_HANDLE, object).await?;
+        Ok(stac)
+    }
+
+    /// Returns a reference to an [Object], reading it if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::AsyncStac;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// assert_eq!(stac.get(root).await.unwrap().id(), "examples");
+    /// # })
+    /// ```
+    pub async fn get(&mut self, handle: Handle) -> Result<&Object> {
+        self.ensure_resolved(handle).await?;
+        Ok(self
+            .stac
+            .node(handle)
+            .object
+            .as_ref()
+            .expect("should be resolved"))
+    }
+
+    /// Adds an [Object] to the `AsyncStac`.
+    ///
+    /// See [Stac::add].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, Catalog};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::new(Catalog::new("root")).await.unwrap();
+    /// let handle = stac.add(Catalog::new("unattached")).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn add(&mut self, object: impl Into<ObjectHrefTuple>) -> Result<Handle> {
+        let (object, href) = object.into();
+        let handle = href
+            .as_ref()
+            .and_then(|href| self.stac.hrefs.get(href).cloned())
+            .unwrap_or_else(|| self.stac.add_node(href.as_ref()));
+        self.set_object(handle, (object, href)).await?;
+        Ok(handle)
+    }
+
+    /// Adds an [Object] to the `AsyncStac` as a child of the provided handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, Catalog, Item};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::new(Catalog::new("root")).await.unwrap();
+    /// let child = stac.add_child(root, Item::new("an-item")).await.unwrap();
+    /// assert_eq!(stac.parent(child).unwrap(), root);
+    /// # })
+    /// ```
+    pub async fn add_child(
+        &mut self,
+        parent: Handle,
+        object: impl Into<ObjectHrefTuple>,
+    ) -> Result<Handle> {
+        let child = self.add(object).await?;
+        self.stac.connect(parent, child);
+        Ok(child)
+    }
+
+    /// Adds many [Objects](Object) to the `AsyncStac` as children of the provided handle.
+    ///
+    /// As with [Stac::add_children], space for the new nodes is reserved up front.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, Catalog, Item};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::new(Catalog::new("root")).await.unwrap();
+    /// let children = stac
+    ///     .add_children(root, (0..3).map(|i| Item::new(format!("item-{}", i))))
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(stac.children(root), children);
+    /// # })
+    /// ```
+    pub async fn add_children<T>(
+        &mut self,
+        parent: Handle,
+        objects: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<Handle>>
+    where
+        T: Into<ObjectHrefTuple>,
+    {
+        let objects = objects.into_iter();
+        let (additional, _) = objects.size_hint();
+        self.stac.reserve(parent, additional);
+        let mut children = Vec::with_capacity(additional);
+        for object in objects {
+            children.push(self.add_child(parent, object).await?);
+        }
+        Ok(children)
+    }
+
+    /// Walks the subtree below an object breadth-first, calling `visit` on each object.
+    ///
+    /// Each object is read before it is visited. As with [Stac::walk], the
+    /// `visit` function is called before the object's children are queued, so
+    /// it can modify the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::AsyncStac;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// let ids = stac
+    ///     .walk(root, |stac, handle| stac.get(handle).map(|object| object.id().to_string()))
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(ids.len(), 6);
+    /// # })
+    /// ```
+    pub async fn walk<T>(
+        &mut self,
+        handle: Handle,
+        mut visit: impl FnMut(&mut Stac<AsyncOnly>, Handle) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut handles = VecDeque::from([handle]);
+        let mut values = Vec::new();
+        while let Some(handle) = handles.pop_front() {
+            self.ensure_resolved(handle).await?;
+            values.push(visit(&mut self.stac, handle)?);
+            handles.extend(self.stac.node(handle).children.iter().copied());
+        }
+        Ok(values)
+    }
+
+    /// Reads every object in the subtree below an object.
+    ///
+    /// After this, the subtree can be used with synchronous APIs like [Layout].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncStac, Layout};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// stac.resolve(root).await.unwrap();
+    /// Layout::new("a/new/root").layout(&mut stac).unwrap();
+    /// # })
+    /// ```
+    pub async fn resolve(&mut self, handle: Handle) -> Result<()> {
+        let _ = self.walk(handle, |_, _| Ok(())).await?;
+        Ok(())
+    }
+
+    /// Writes this `AsyncStac`, consuming it.
+    ///
+    /// The whole tree is read before it is laid out and written. If the layout
+    /// has a [CancellationToken](crate::CancellationToken), it is checked
+    /// while reading, too.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{AsyncStac, AsyncWriter, Layout};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, _) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// let mut layout = Layout::new("my/stac/v0");
+    /// stac.write(&mut layout, &AsyncWriter::default()).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn write<S>(mut self, layout: &mut Layout<S>, writer: &impl AsyncWrite) -> Result<()>
+    where
+        S: Strategy,
+    {
+        let mut handles = VecDeque::from([ROOT_HANDLE]);
+        while let Some(handle) = handles.pop_front() {
+            if let Some(token) = layout.cancellation() {
+                token.check()?;
+            }
+            self.ensure_resolved(handle).await?;
+            handles.extend(self.stac.node(handle).children.iter().copied());
+        }
+        for result in layout.stream_nodes(self.stac, true) {
+            let node = result?;
+            writer.write(node.href_object).await?;
+            for (href, page) in node.pages {
+                writer
+                    .write_json(serde_json::to_value(page)?, &href)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn ensure_resolved(&mut self, handle: Handle) -> Result<()> {
+        if self.stac.node(handle).object.is_none() {
+            if let Some(href) = self.stac.node_mut(handle).href.take() {
+                let value = self.reader.read_json(&href).await?;
+                let object = Object::from_value(value)?;
+                self.set_shared_object(handle, object, Some(href)).await?;
+            } else {
+                return Err(Error::UnresolvableNode);
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_object(
+        &mut self,
+        handle: Handle,
+        object: impl Into<ObjectHrefTuple>,
+    ) -> Result<()> {
+        let (object, href) = object.into();
+        self.set_shared_object(handle, object, href.map(Arc::new))
+            .await
+    }
+
+    async fn set_shared_object(
+        &mut self,
+        handle: Handle,
+        object: Object,
+        href: Option<Arc<Href>>,
+    ) -> Result<()> {
+        for page in self.stac.attach_object(handle, object, href)? {
+            let mut visited = HashSet::new();
+            let mut next = Some(page);
+            while let Some(page) = next.take() {
+                if !self.stac.should_read_page(handle, &page, &mut visited) {
+                    break;
+                }
+                let value = self.reader.read_json(page.href()).await?;
+                next = self.stac.add_page(handle, page, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: AsyncRead> Deref for AsyncStac<R> {
+    type Target = Stac<AsyncOnly>;
+
+    fn deref(&self) -> &Stac<AsyncOnly> {
+        &self.stac
+    }
+}
+
+impl<R: AsyncRead> DerefMut for AsyncStac<R> {
+    fn deref_mut(&mut self) -> &mut Stac<AsyncOnly> {
+        &mut self.stac
+    }
+}
+
+impl Read for AsyncOnly {
+    fn read_json_from_url(&self, url: &Url) -> Result<Value> {
+        Err(Error::BlockingRead(url.to_string()))
+    }
+
+    fn read_json_from_path(&self, path: impl AsRef<Path>) -> Result<Value> {
+        Err(Error::BlockingRead(path.as_ref().display().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncStac;
+    use crate::{AsyncWriter, Error, Layout, Stac};
+
+    #[tokio::test]
+    async fn walk_matches_sync() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let expected = stac
+            .walk(root)
+            .visit(|stac, handle| stac.get(handle).map(|object| object.id().to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+        let ids = stac
+            .walk(root, |stac, handle| {
+                stac.get(handle).map(|object| object.id().to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn sync_get_does_not_read() {
+        let (mut stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+        let child = stac.children(root)[0];
+        assert!(matches!(
+            Stac::get(&mut stac, child).unwrap_err(),
+            Error::BlockingRead(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn write() {
+        let directory = tempfile::tempdir().unwrap();
+        let (stac, _) = AsyncStac::read("data/catalog.json").await.unwrap();
+        let mut layout = Layout::new(directory.path().to_str().unwrap());
+        stac.write(&mut layout, &AsyncWriter::default())
+            .await
+            .unwrap();
+        let (mut stac, root) = Stac::read(directory.path().join("catalog.json")).unwrap();
+        let hrefs = stac
+            .walk(root)
+            .visit(|stac, handle| Ok(stac.href(handle).unwrap().to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        // Both of the `sentinel-2` collections get their own file.
+        assert_eq!(hrefs.len(), 6);
+        assert!(hrefs
+            .iter()
+            .any(|href| href.ends_with("/sentinel-2/collection.json")));
+        assert!(hrefs
+            .iter()
+            .any(|href| href.ends_with("/sentinel-2-2/collection.json")));
+    }
+}
@@ -0,0 +1,186 @@
+use super::{Handle, Node, Stac};
+use crate::{Href, Read};
+use std::{
+    collections::{HashMap, HashSet},
+    mem::size_of,
+    sync::Arc,
+};
+
+/// How much memory the arena of a [Stac] is using.
+///
+/// Returned by [Stac::memory_usage]. Only the arena itself is counted, i.e.
+/// its nodes, their child lists, and the href index, not the objects and
+/// hrefs they point to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The number of nodes in use.
+    pub nodes: usize,
+
+    /// The number of freed nodes that are waiting to be reused.
+    pub free_nodes: usize,
+
+    /// The number of nodes that fit in the arena without growing it.
+    pub capacity: usize,
+
+    /// The number of hrefs in the href index.
+    pub hrefs: usize,
+
+    /// The number of hrefs that fit in the href index without growing it.
+    pub href_capacity: usize,
+
+    /// The approximate number of bytes allocated by the arena.
+    pub bytes: usize,
+}
+
+impl<R: Read> Stac<R> {
+    /// Reclaims freed nodes and shrinks the arena, returning how handles moved.
+    ///
+    /// Removing objects frees their nodes for reuse, but never shrinks the
+    /// arena, so a long-running service that keeps editing a catalog can
+    /// hold on to far more memory than it needs. Compacting moves the nodes
+    /// that are in use to the front of the arena, keeping their order, and
+    /// then releases the unused capacity.
+    ///
+    /// The returned map has the new handle for every node that's still in
+    /// use, including ones that didn't move. Handles that aren't in the map
+    /// were freed and must not be used. The root never moves. [Observers](super::Observer)
+    /// are told about the new handles with
+    /// [on_compact](super::Observer::on_compact).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let a = stac.add_child(root, Item::new("a")).unwrap();
+    /// let b = stac.add_child(root, Item::new("b")).unwrap();
+    /// let _ = stac.remove(a).unwrap();
+    /// let handles = stac.compact();
+    /// assert_eq!(handles[&root], root);
+    /// assert!(!handles.contains_key(&a));
+    /// assert_eq!(stac.get(handles[&b]).unwrap().id(), "b");
+    /// assert_eq!(stac.memory_usage().free_nodes, 0);
+    /// ```
+    pub fn compact(&mut self) -> HashMap<Handle, Handle> {
+        let free_nodes: HashSet<Handle> = self.free_nodes.drain(..).collect();
+        let mut handles = HashMap::with_capacity(self.nodes.len() - free_nodes.len());
+        let mut nodes = Vec::with_capacity(self.nodes.len() - free_nodes.len());
+        for (i, node) in std::mem::take(&mut self.nodes).into_iter().enumerate() {
+            if free_nodes.contains(&Handle(i)) {
+                continue;
+            }
+            let _ = handles.insert(Handle(i), Handle(nodes.len()));
+            nodes.push(node);
+        }
+        for node in &mut nodes {
+            node.parent = node.parent.map(|parent| handles[&parent]);
+            node.children = node.children.iter().map(|child| handles[child]).collect();
+        }
+        self.nodes = nodes;
+        self.free_nodes.shrink_to_fit();
+        for handle in self.hrefs.values_mut() {
+            *handle = handles[handle];
+        }
+        self.hrefs.shrink_to_fit();
+        for observer in &mut self.observers {
+            observer.on_compact(&handles);
+        }
+        handles
+    }
+
+    /// Returns how much memory the arena is using.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+    /// let usage = stac.memory_usage();
+    /// assert_eq!(usage.nodes, 2);
+    /// assert!(usage.capacity >= usage.nodes);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let children: usize = self.nodes.iter().map(|node| node.children.capacity()).sum();
+        MemoryUsage {
+            nodes: self.nodes.len() - self.free_nodes.len(),
+            free_nodes: self.free_nodes.len(),
+            capacity: self.nodes.capacity(),
+            hrefs: self.hrefs.len(),
+            href_capacity: self.hrefs.capacity(),
+            bytes: self.nodes.capacity() * size_of::<Node>()
+                + children * size_of::<Handle>()
+                + self.free_nodes.capacity() * size_of::<Handle>()
+                + self.hrefs.capacity() * size_of::<(Arc<Href>, Handle)>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Handle, Item, Observer, Stac};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Debug, Default)]
+    struct Handles(Vec<Handle>);
+
+    impl Observer for Handles {
+        fn on_add(&mut self, handle: Handle, _: Option<&crate::Href>) {
+            self.0.push(handle);
+        }
+
+        fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+            self.0 = self
+                .0
+                .iter()
+                .filter_map(|handle| handles.get(handle).copied())
+                .collect();
+        }
+    }
+
+    #[test]
+    fn compact() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let handles = Arc::new(Mutex::new(Handles::default()));
+        stac.add_observer(handles.clone());
+        let mut children = Vec::new();
+        for i in 0..100 {
+            let catalog = stac
+                .add_child(root, Catalog::new(format!("catalog-{}", i)))
+                .unwrap();
+            let _ = stac
+                .add_child(catalog, Item::new(format!("item-{}", i)))
+                .unwrap();
+            children.push(catalog);
+        }
+        for &child in &children[1..] {
+            let item = stac.children(child)[0];
+            let _ = stac.remove(item).unwrap();
+            let _ = stac.remove(child).unwrap();
+        }
+        let before = stac.memory_usage();
+        assert_eq!(before.nodes, 3);
+        assert_eq!(before.free_nodes, 198);
+
+        let map = stac.compact();
+        assert_eq!(map.len(), 3);
+        let after = stac.memory_usage();
+        assert_eq!(after.nodes, 3);
+        assert_eq!(after.free_nodes, 0);
+        assert!(after.capacity < before.capacity);
+        assert!(after.bytes < before.bytes);
+
+        let catalog = map[&children[0]];
+        assert_eq!(stac.children(root), vec![catalog]);
+        let item = stac.children(catalog)[0];
+        assert_eq!(stac.parent(item), Some(catalog));
+        assert_eq!(stac.get(item).unwrap().id(), "item-0");
+        assert_eq!(handles.lock().unwrap().0, vec![root, catalog, item]);
+
+        let _ = stac.add_child(root, Item::new("new")).unwrap();
+        assert_eq!(stac.memory_usage().nodes, 4);
+    }
+}
@@ -0,0 +1,205 @@
+use super::{Handle, Observer, Stac};
+use crate::{intern::Interned, Href, Object, Read, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Maps (collection id, item id) to the [Handle] of each item that has been resolved.
+#[derive(Debug, Default)]
+pub(super) struct ItemIndex {
+    handles: HashMap<(Option<Interned>, String), Handle>,
+    keys: HashMap<Handle, (Option<Interned>, String)>,
+    unresolved: HashSet<Handle>,
+}
+
+impl<R: Read> Stac<R> {
+    /// Keeps an index of items by collection id and item id, so
+    /// [get_by_id](Stac::get_by_id) doesn't have to walk the tree.
+    ///
+    /// The index is filled in as objects are added and read, and kept up to
+    /// date when they're removed or the tree is
+    /// [compacted](Stac::compact). Items are keyed by their `collection`
+    /// field, not by where they are in the tree. Changing an item's id or
+    /// collection through [get_mut](Stac::get_mut) isn't tracked. Calling
+    /// this more than once does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// stac.index_items();
+    /// ```
+    pub fn index_items(&mut self) {
+        if self.item_index.is_none() {
+            let index = Arc::new(Mutex::new(ItemIndex::default()));
+            self.add_observer(index.clone());
+            self.item_index = Some(index);
+        }
+    }
+
+    /// Returns the item with this collection id and item id.
+    ///
+    /// If the items are [indexed](Stac::index_items) and the item has already
+    /// been read, this doesn't walk the tree. Otherwise, the tree is walked
+    /// from the root until the item is found, and any objects that are read
+    /// along the way are added to the index. After a lookup has walked the
+    /// whole tree, missing items are reported without a walk until a node is
+    /// added that hasn't been read yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// stac.index_items();
+    /// let item = stac.get_by_id("landsat-8-l1", "proj-example").unwrap().unwrap();
+    /// assert_eq!(stac.get(item).unwrap().id(), "proj-example");
+    /// ```
+    pub fn get_by_id(&mut self, collection: &str, id: &str) -> Result<Option<Handle>> {
+        if let Some(index) = &self.item_index {
+            let index = index.lock().unwrap_or_else(PoisonError::into_inner);
+            let key = (Some(Interned::from(collection)), id.to_string());
+            if let Some(&handle) = index.handles.get(&key) {
+                return Ok(Some(handle));
+            } else if index.unresolved.is_empty() {
+                return Ok(None);
+            }
+        }
+        let root = self.root();
+        let handle = self.find(root, |object| {
+            object
+                .as_item()
+                .is_some_and(|item| item.id == id && item.collection.as_deref() == Some(collection))
+        })?;
+        if let (None, Some(index)) = (handle, &self.item_index) {
+            // The whole tree was walked, so anything that's still unresolved
+            // isn't reachable from the root.
+            index
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .unresolved
+                .clear();
+        }
+        Ok(handle)
+    }
+}
+
+impl ItemIndex {
+    fn remove(&mut self, handle: Handle) {
+        if let Some(key) = self.keys.remove(&handle) {
+            if self.handles.get(&key) == Some(&handle) {
+                let _ = self.handles.remove(&key);
+            }
+        }
+    }
+}
+
+impl Observer for ItemIndex {
+    fn on_add(&mut self, handle: Handle, _: Option<&Href>) {
+        let _ = self.unresolved.insert(handle);
+    }
+
+    fn on_resolve(&mut self, handle: Handle, object: &Object) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+        if let Some(item) = object.as_item() {
+            let key = (item.collection.clone(), item.id.clone());
+            let _ = self.handles.insert(key.clone(), handle);
+            let _ = self.keys.insert(handle, key);
+        }
+    }
+
+    fn on_remove(&mut self, handle: Handle, _: Option<&Object>) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+    }
+
+    fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+        for handle in self.handles.values_mut() {
+            *handle = handles[handle];
+        }
+        self.keys = self
+            .keys
+            .drain()
+            .map(|(handle, key)| (handles[&handle], key))
+            .collect();
+        self.unresolved = self
+            .unresolved
+            .drain()
+            .map(|handle| handles[&handle])
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Item, Stac};
+
+    fn item(id: &str, collection: &str) -> Item {
+        let mut item = Item::new(id);
+        item.collection = Some(collection.into());
+        item
+    }
+
+    #[test]
+    fn read_through() {
+        let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+        stac.index_items();
+        assert!(!stac
+            .item_index
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .unresolved
+            .is_empty());
+        let item = stac
+            .get_by_id("landsat-8-l1", "proj-example")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stac.get_by_id("landsat-8-l1", "proj-example").unwrap(),
+            Some(item)
+        );
+        assert_eq!(stac.get_by_id("landsat-8-l1", "not-an-item").unwrap(), None);
+        assert!(stac
+            .item_index
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .unresolved
+            .is_empty());
+        let _ = stac.remove(item).unwrap();
+        assert_eq!(
+            stac.get_by_id("landsat-8-l1", "proj-example").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn without_index() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let a = stac.add_child(root, item("a", "c")).unwrap();
+        assert_eq!(stac.get_by_id("c", "a").unwrap(), Some(a));
+        assert_eq!(stac.get_by_id("d", "a").unwrap(), None);
+    }
+
+    #[test]
+    fn replay_and_compact() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let a = stac.add_child(root, item("a", "c")).unwrap();
+        let b = stac.add_child(root, item("b", "c")).unwrap();
+        stac.index_items();
+        stac.index_items();
+        assert_eq!(stac.get_by_id("c", "a").unwrap(), Some(a));
+        let _ = stac.remove(a).unwrap();
+        let handles = stac.compact();
+        assert_eq!(stac.get_by_id("c", "b").unwrap(), Some(handles[&b]));
+        assert_eq!(stac.get_by_id("c", "a").unwrap(), None);
+        let c = stac.add_child(root, item("c", "c")).unwrap();
+        assert_eq!(stac.get_by_id("c", "c").unwrap(), Some(c));
+    }
+}
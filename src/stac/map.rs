@@ -0,0 +1,157 @@
+use super::{Handle, Stac};
+use crate::{Item, Object, Read, Result, Walk};
+use std::{collections::HashSet, thread};
+
+impl<R: Read> Stac<R> {
+    /// Applies a function to every item under `handle`, in place.
+    ///
+    /// Items are read as they are reached, just like a [walk](Stac::walk).
+    /// Stops at the first error, leaving items that were already visited
+    /// changed. Returns the number of items that were visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let count = stac
+    ///     .map_items(root, |item| {
+    ///         let _ = item
+    ///             .properties
+    ///             .additional_fields
+    ///             .insert("processing:level".to_string(), "L2".into());
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(count, 2);
+    /// ```
+    pub fn map_items(
+        &mut self,
+        handle: Handle,
+        mut f: impl FnMut(&mut Item) -> Result<()>,
+    ) -> Result<usize> {
+        let mut count = 0;
+        for result in
+            self.walk(handle)
+                .items_only()
+                .visit(|stac, handle| match stac.get_mut(handle)? {
+                    Object::Item(item) => f(item),
+                    _ => Ok(()),
+                })
+        {
+            result?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Applies a function to every item under `handle`, in place, on multiple threads.
+    ///
+    /// Like [map_items](Stac::map_items), but the subtree is first read with a
+    /// [parallel walk](super::BorrowedWalk::parallel), and then the items are
+    /// split between `threads` threads. If the function fails for any item,
+    /// the first error is returned, but other items may already be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let count = stac
+    ///     .map_items_parallel(root, 2, |item| {
+    ///         item.id = item.id.to_uppercase();
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(count, 2);
+    /// ```
+    pub fn map_items_parallel(
+        &mut self,
+        handle: Handle,
+        threads: usize,
+        f: impl Fn(&mut Item) -> Result<()> + Sync,
+    ) -> Result<usize>
+    where
+        R: Sync,
+    {
+        let handles = self
+            .walk(handle)
+            .items_only()
+            .parallel(threads)
+            .collect::<Result<HashSet<_>>>()?;
+        let mut items: Vec<&mut Item> = self
+            .nodes
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| handles.contains(&Handle(*i)))
+            .filter_map(|(_, node)| {
+                let _ = node.links_by_rel.take();
+                match node.object.as_mut() {
+                    Some(Object::Item(item)) => Some(item),
+                    _ => None,
+                }
+            })
+            .collect();
+        let count = items.len();
+        let chunk_size = count.div_ceil(threads.max(1)).max(1);
+        let f = &f;
+        thread::scope(|scope| {
+            let workers: Vec<_> = items
+                .chunks_mut(chunk_size)
+                .map(|chunk| scope.spawn(move || chunk.iter_mut().try_for_each(|item| f(item))))
+                .collect();
+            workers
+                .into_iter()
+                .try_for_each(|worker| worker.join().expect("map should not panic"))
+        })?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Error, Item, Stac};
+
+    #[test]
+    fn map_items_error() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let child = stac.add_child(root, Catalog::new("child")).unwrap();
+        let _ = stac.add_child(child, Item::new("a")).unwrap();
+        let _ = stac.add_child(child, Item::new("b")).unwrap();
+        let mut visited = Vec::new();
+        let err = stac
+            .map_items(root, |item| {
+                visited.push(item.id.clone());
+                if item.id == "a" {
+                    Err(Error::MissingHref)
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingHref));
+        assert_eq!(visited, ["a"]);
+    }
+
+    #[test]
+    fn map_items_parallel() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let items: Vec<_> = (0..10)
+            .map(|i| stac.add_child(root, Item::new(i.to_string())).unwrap())
+            .collect();
+        let count = stac
+            .map_items_parallel(root, 3, |item| {
+                item.collection = Some("a-collection".into());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count, 10);
+        for handle in items {
+            let item = stac.get(handle).unwrap().as_item().unwrap();
+            assert_eq!(item.collection.as_deref(), Some("a-collection"));
+        }
+        assert!(stac
+            .map_items_parallel(root, 3, |_| Err(Error::MissingHref))
+            .is_err());
+    }
+}
@@ -79,17 +79,78 @@
 //! let writer = Writer::default();
 //! stac.write(&mut layout, &writer).unwrap();
 //! ```
+//!
+//! ## Observing
+//!
+//! An [Observer] is told when nodes are added, resolved, and removed, so secondary indexes can follow the tree as it is lazily read:
+//!
+//! ```
+//! use stac::{Handle, Object, Observer, Stac};
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Debug, Default)]
+//! struct Resolved(usize);
+//!
+//! impl Observer for Resolved {
+//!     fn on_resolve(&mut self, _: Handle, _: &Object) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let resolved = Arc::new(Mutex::new(Resolved::default()));
+//! let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+//! stac.add_observer(resolved.clone());
+//! assert_eq!(resolved.lock().unwrap().0, 1); // only the root has been read
+//! let children = stac.children(root);
+//! let _ = stac.get(children[0]).unwrap();
+//! assert_eq!(resolved.lock().unwrap().0, 2);
+//! ```
+//!
+//! ## Async
+//!
+//! With the `async` feature, [AsyncStac] reads objects with an [AsyncRead](crate::AsyncRead), so it can be used in async services.
+//! It dereferences to a `Stac` for everything that doesn't read.
 
+mod assets;
+#[cfg(feature = "async")]
+mod async_stac;
+mod compact;
+mod item_index;
+mod map;
+mod observe;
+mod roots;
+mod schedule;
+mod snapshot;
+#[cfg(feature = "geo")]
+mod spatial_index;
+mod stats;
+mod temporal_index;
+mod unique;
 pub mod walk;
 
-pub use walk::{BorrowedWalk, OwnedWalk, Walk};
+#[cfg(feature = "async")]
+pub use async_stac::{AsyncOnly, AsyncStac};
+pub use compact::MemoryUsage;
+pub use observe::Observer;
+pub use schedule::WriteOrder;
+pub use stats::{Count, Stats};
+pub use walk::{BorrowedWalk, OwnedWalk, ParallelWalk, Walk};
+
+use item_index::ItemIndex;
+#[cfg(feature = "geo")]
+use spatial_index::SpatialIndex;
+use temporal_index::TemporalIndex;
 
 use crate::{
-    layout::Strategy, Error, Href, Layout, Link, Object, ObjectHrefTuple, Read, Reader, Result,
-    Write,
+    layout::Strategy, Error, Href, Item, ItemCollection, Layout, Link, LinkIndex, Links, Object,
+    ObjectHrefTuple, Read, Reader, Result, Write,
 };
 use indexmap::IndexSet;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, OnceLock},
+};
 
 const ROOT_HANDLE: Handle = Handle(0);
 
@@ -101,6 +162,12 @@ const ROOT_HANDLE: Handle = Handle(0);
 /// [Object] and [HrefObject](crate::HrefObject) both implement [Into] for
 /// [ObjectHrefTuple].
 ///
+/// `items` links, as exposed by STAC APIs, are followed when an object is
+/// resolved: every page of the linked [ItemCollection] is read (following
+/// `next` links) and its features are added as children of the object.
+/// Similarly, if a catalog or collection has a `next` link, the `child` and
+/// `item` links of every linked page are merged into the object's children.
+///
 /// A `root` link is only used when creating a new `Stac`: if the initial object
 /// has a `root` link, it is used to set the root of the `Stac`. After that, all
 /// `root` links are ignored since the `root` is already set.
@@ -128,7 +195,14 @@ pub struct Stac<R: Read> {
     reader: R,
     nodes: Vec<Node>,
     free_nodes: Vec<Handle>,
-    hrefs: HashMap<Href, Handle>,
+    hrefs: HashMap<Arc<Href>, Handle>,
+    observers: Vec<Box<dyn Observer>>,
+    reject_duplicate_ids: bool,
+    child_rels: HashSet<String>,
+    item_index: Option<Arc<Mutex<ItemIndex>>>,
+    #[cfg(feature = "geo")]
+    spatial_index: Option<Arc<Mutex<SpatialIndex>>>,
+    temporal_index: Option<Arc<Mutex<TemporalIndex>>>,
 }
 
 /// A pointer to an [Object] in a [Stac] tree.
@@ -138,13 +212,34 @@ pub struct Stac<R: Read> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Handle(usize);
 
+/// An estimate of the size of a subtree of a [Stac].
+///
+/// Returned by [Stac::estimate].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Estimate {
+    /// The number of catalog and collection descendants.
+    pub catalogs: usize,
+
+    /// The number of item descendants.
+    pub items: usize,
+
+    /// The number of item descendants that have not been read yet.
+    pub unresolved: usize,
+}
+
 #[derive(Debug, Default)]
 struct Node {
     object: Option<Object>,
     children: IndexSet<Handle>,
     parent: Option<Handle>,
-    href: Option<Href>,
+    /// The current href, which the node is found by.
+    href: Option<Arc<Href>>,
+    /// The href the object was read from, which [Stac::set_href] doesn't change.
+    source_href: Option<Arc<Href>>,
+    /// True if the href was set with [Stac::set_href], e.g. by a [Layout].
+    relocated: bool,
     is_from_item_link: bool,
+    links_by_rel: OnceLock<LinkIndex>,
 }
 
 impl Stac<Reader> {
@@ -219,16 +314,108 @@ impl<R: Read> Stac<R> {
     }
 
     fn rooted(object: impl Into<ObjectHrefTuple>, reader: R) -> Result<(Stac<R>, Handle)> {
-        let handle = ROOT_HANDLE;
-        let node = Node::default();
-        let mut stac = Stac {
+        let mut stac = Stac::empty(reader);
+        stac.set_object(ROOT_HANDLE, object)?;
+        Ok((stac, ROOT_HANDLE))
+    }
+
+    /// Creates a `Stac` with an empty root node.
+    fn empty(reader: R) -> Stac<R> {
+        Stac {
             reader,
-            nodes: vec![node],
+            nodes: vec![Node::default()],
             free_nodes: Vec::new(),
             hrefs: HashMap::new(),
-        };
-        stac.set_object(handle, object)?;
-        Ok((stac, handle))
+            observers: Vec::new(),
+            reject_duplicate_ids: false,
+            child_rels: HashSet::new(),
+            item_index: None,
+            #[cfg(feature = "geo")]
+            spatial_index: None,
+            temporal_index: None,
+        }
+    }
+
+    /// Adds an [Observer] that is told about changes to this `Stac`'s nodes.
+    ///
+    /// The observer is first told about every node that is already in the
+    /// tree, as if each had just been added (and resolved, if it has been).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Handle, Object, Observer, Stac};
+    ///
+    /// #[derive(Debug)]
+    /// struct Log;
+    ///
+    /// impl Observer for Log {
+    ///     fn on_resolve(&mut self, handle: Handle, object: &Object) {
+    ///         println!("resolved {}", object.id());
+    ///     }
+    /// }
+    ///
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// stac.add_observer(Log);
+    /// ```
+    pub fn add_observer(&mut self, mut observer: impl Observer + 'static) {
+        let free_nodes: HashSet<Handle> = self.free_nodes.iter().copied().collect();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let handle = Handle(i);
+            if free_nodes.contains(&handle) {
+                continue;
+            }
+            observer.on_add(handle, node.href.as_deref());
+            if let Some(object) = node.object.as_ref() {
+                observer.on_resolve(handle, object);
+            }
+        }
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Treats links with this rel like `child` links.
+    ///
+    /// Some producers use `related` or custom rels for their children. Once
+    /// a rel is treated as a child, links with that rel connect their
+    /// targets into the tree when objects are read, including objects that
+    /// have already been read. When the tree is [written](Stac::write), these
+    /// links are replaced by `child` and `item` links, like any other
+    /// structural link.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, HrefObject, Link, Stac};
+    /// let mut catalog = Catalog::new("root");
+    /// catalog.links.push(Link::new("./extensions-collection/collection.json", "related"));
+    /// let (mut stac, root) = Stac::new(HrefObject::new(catalog, "data/catalog.json")).unwrap();
+    /// assert!(stac.children(root).is_empty());
+    /// stac.treat_as_child("related").unwrap();
+    /// let child = stac.children(root)[0];
+    /// assert_eq!(stac.get(child).unwrap().id(), "extensions-collection");
+    /// ```
+    pub fn treat_as_child(&mut self, rel: impl ToString) -> Result<()> {
+        let rel = rel.to_string();
+        if !self.child_rels.insert(rel.clone()) {
+            return Ok(());
+        }
+        for i in 0..self.nodes.len() {
+            let handle = Handle(i);
+            let node = self.node(handle);
+            let Some(object) = node.object.as_ref() else {
+                continue;
+            };
+            let base = node.source_href.clone();
+            let index = node
+                .links_by_rel
+                .get_or_init(|| LinkIndex::new(object.links()));
+            let links: Vec<Link> = index.links(object.links(), &rel).cloned().collect();
+            for link in links {
+                let child = self.link_target(base.as_deref(), &link)?;
+                self.connect(handle, child);
+            }
+        }
+        Ok(())
     }
 
     /// Returns the root [Handle] of this `Stac`.
@@ -266,6 +453,25 @@ impl<R: Read> Stac<R> {
             .expect("should be resolved"))
     }
 
+    /// Returns a mutable reference to an [Object] in this `Stac`.
+    ///
+    /// The object is resolved first, as with [get](Stac::get). Changes to the
+    /// object's structural links are not reflected in the tree; use
+    /// [connect](Stac::connect) and [remove](Stac::remove) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// stac.get_mut(root).unwrap().as_mut_catalog().unwrap().title = None;
+    /// assert!(stac.get(root).unwrap().as_catalog().unwrap().title.is_none());
+    /// ```
+    pub fn get_mut(&mut self, handle: Handle) -> Result<&mut Object> {
+        self.ensure_resolved(handle)?;
+        Ok(self.object_mut(handle))
+    }
+
     /// Returns the parent [Handle] of this object, if one is set.
     ///
     /// # Examples
@@ -284,6 +490,30 @@ impl<R: Read> Stac<R> {
         self.node(handle).parent
     }
 
+    /// Returns true if this handle is the ancestor or one of its descendants.
+    fn is_within(&self, mut handle: Handle, ancestor: Handle) -> bool {
+        loop {
+            if handle == ancestor {
+                return true;
+            }
+            match self.parent(handle) {
+                Some(parent) => handle = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Reads every object at or under this handle.
+    fn read_all(&mut self, handle: Handle) -> Result<()> {
+        for result in self
+            .walk(handle)
+            .visit(|stac, handle| stac.get(handle).map(|_| ()))
+        {
+            result?;
+        }
+        Ok(())
+    }
+
     /// Adds an [Object] to the [Stac].
     ///
     /// If this object has links, the links will be resolved and the object will
@@ -315,15 +545,17 @@ impl<R: Read> Stac<R> {
         let (object, href) = object.into();
         let handle = href
             .as_ref()
-            .and_then(|href| self.hrefs.get(&href).cloned())
-            .unwrap_or_else(|| self.add_node());
+            .and_then(|href| self.hrefs.get(href).cloned())
+            .unwrap_or_else(|| self.add_node(href.as_ref()));
         self.set_object(handle, (object, href))?;
         Ok(handle)
     }
 
     /// Adds an [Object] to the [Stac] as a child of the provided handle.
     ///
-    /// If there is a `parent` link on the `Object`, it will be ignored.
+    /// If there is a `parent` link on the `Object`, it will be ignored. If
+    /// [reject_duplicate_ids](Stac::reject_duplicate_ids) is set and a sibling
+    /// has the same id, returns [Error::DuplicateId].
     ///
     /// # Examples
     ///
@@ -343,11 +575,91 @@ impl<R: Read> Stac<R> {
         parent: Handle,
         object: impl Into<ObjectHrefTuple>,
     ) -> Result<Handle> {
-        let child = self.add(object)?;
+        let (object, href) = object.into();
+        if self.reject_duplicate_ids {
+            self.check_sibling_id(parent, object.id())?;
+        }
+        let child = self.add((object, href))?;
         self.connect(parent, child);
         Ok(child)
     }
 
+    /// Adds many [Objects](Object) to the [Stac] as children of the provided handle.
+    ///
+    /// This is equivalent to calling [add_child](Stac::add_child) for each
+    /// object, but space for the new nodes is reserved up front, which makes a
+    /// difference when adding hundreds of thousands of objects. Returns the
+    /// handles of the new children, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let children = stac
+    ///     .add_children(root, (0..3).map(|i| Item::new(format!("item-{}", i))))
+    ///     .unwrap();
+    /// assert_eq!(stac.children(root), children);
+    /// ```
+    pub fn add_children<T>(
+        &mut self,
+        parent: Handle,
+        objects: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<Handle>>
+    where
+        T: Into<ObjectHrefTuple>,
+    {
+        let objects = objects.into_iter();
+        let (additional, _) = objects.size_hint();
+        self.reserve(parent, additional);
+        let mut children = Vec::with_capacity(additional);
+        for object in objects {
+            children.push(self.add_child(parent, object)?);
+        }
+        Ok(children)
+    }
+
+    /// Adds many [Items](Item) to the [Stac] as children of the provided handle.
+    ///
+    /// Like [add_children](Stac::add_children), but the items don't have
+    /// hrefs, so there's no need to look them up by href. This is the fastest
+    /// way to add items that were created in memory, e.g. from a CSV
+    /// inventory. Returns the handles of the new items, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let items = stac
+    ///     .add_items(root, (0..3).map(|i| Item::new(format!("item-{}", i))))
+    ///     .unwrap();
+    /// assert_eq!(items.len(), 3);
+    /// ```
+    pub fn add_items(
+        &mut self,
+        parent: Handle,
+        items: impl IntoIterator<Item = Item>,
+    ) -> Result<Vec<Handle>> {
+        let items = items.into_iter();
+        let (additional, _) = items.size_hint();
+        self.reserve(parent, additional);
+        let mut handles = Vec::with_capacity(additional);
+        let mut ids = self.reject_duplicate_ids.then(|| self.sibling_ids(parent));
+        for item in items {
+            if let Some(ids) = ids.as_mut() {
+                if !ids.insert(item.id.clone()) {
+                    return Err(Error::DuplicateId(item.id));
+                }
+            }
+            let handle = self.add_node(None);
+            self.set_shared_object(handle, Object::Item(item), None)?;
+            self.connect(parent, handle);
+            handles.push(handle);
+        }
+        Ok(handles)
+    }
+
     /// Connects a parent and a child.
     ///
     /// This will disconnect the child from its current parent, if there is one.
@@ -362,6 +674,10 @@ impl<R: Read> Stac<R> {
     /// assert_eq!(stac.parent(disconnected).unwrap(), root);
     /// ```
     pub fn connect(&mut self, parent: Handle, child: Handle) {
+        if self.node(child).parent == Some(parent) {
+            // Reconnecting would move the child to the end of its siblings.
+            return;
+        }
         if let Some(parent) = self.node(child).parent {
             if !self.node_mut(parent).children.remove(&child) {
                 panic!("the child thought it had a parent but the parent didn't know about it");
@@ -400,16 +716,63 @@ impl<R: Read> Stac<R> {
             self.disconnect(parent, handle);
         }
         let href = if let Some(href) = self.node_mut(handle).href.take() {
-            let _ = self.hrefs.remove(&href);
-            Some(href)
+            let _ = self.hrefs.remove(&*href);
+            Some(unwrap_or_clone(href))
         } else {
             None
         };
+        let node = self.node_mut(handle);
+        node.source_href = None;
+        node.relocated = false;
         self.free_nodes.push(handle);
-        let object = self.node_mut(handle).object.take();
+        let object = self.take(handle);
+        for observer in &mut self.observers {
+            observer.on_remove(handle, object.as_ref());
+        }
         Ok((object, href))
     }
 
+    /// Estimates the size of the subtree below an object.
+    ///
+    /// Catalogs and collections are resolved to discover their children, but
+    /// items are not, so this is much cheaper than walking the whole tree.
+    /// Use it to predict the cost of a crawl or to display progress totals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let estimate = stac.estimate(root).unwrap();
+    /// assert_eq!(estimate.catalogs, 3);
+    /// assert_eq!(estimate.items, 2);
+    /// assert_eq!(estimate.unresolved, 2);
+    /// ```
+    pub fn estimate(&mut self, handle: Handle) -> Result<Estimate> {
+        let mut estimate = Estimate::default();
+        for result in self.walk(handle).skip_items().visit(|stac, visited| {
+            let mut estimate = Estimate::default();
+            if visited != handle {
+                estimate.catalogs += 1;
+            }
+            for child in stac.children(visited) {
+                if stac.is_item(child) {
+                    estimate.items += 1;
+                    if stac.node(child).object.is_none() {
+                        estimate.unresolved += 1;
+                    }
+                }
+            }
+            Ok(estimate)
+        }) {
+            let partial = result?;
+            estimate.catalogs += partial.catalogs;
+            estimate.items += partial.items;
+            estimate.unresolved += partial.unresolved;
+        }
+        Ok(estimate)
+    }
+
     /// Returns a vector of this object's children.
     ///
     /// # Examples
@@ -425,6 +788,11 @@ impl<R: Read> Stac<R> {
 
     /// Returns the [Href] of an [Object].
     ///
+    /// This is the object's current href: where it was read from, or, once
+    /// it has been [set](Stac::set_href) (e.g. by a [Layout]), where it's
+    /// going. Use [source_href](Stac::source_href) and
+    /// [next_href](Stac::next_href) to tell the two apart.
+    ///
     /// # Examples
     ///
     /// ```
@@ -435,7 +803,7 @@ impl<R: Read> Stac<R> {
     /// assert!(stac.href(catalog).is_none());
     /// ```
     pub fn href(&self, handle: Handle) -> Option<&Href> {
-        self.node(handle).href.as_ref()
+        self.node(handle).href.as_deref()
     }
 
     /// Sets the [Href] of an [Object].
@@ -450,11 +818,94 @@ impl<R: Read> Stac<R> {
     /// assert_eq!(stac.href(root).unwrap().as_str(), "path/to/the/root.catalog");
     /// ```
     pub fn set_href(&mut self, handle: Handle, href: impl Into<Href>) {
-        let href = href.into();
+        self.set_shared_href(handle, Arc::new(href.into()));
+        self.node_mut(handle).relocated = true;
+    }
+
+    /// Returns the [Href] that an [Object] was read from.
+    ///
+    /// Unlike [href](Stac::href), this doesn't change when the object is
+    /// given a new href, so it's where the object came from. Objects that
+    /// were added without an href don't have one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Catalog, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// stac.set_href(root, "a/new/root/catalog.json");
+    /// assert_eq!(stac.source_href(root).unwrap().as_str(), "data/catalog.json");
+    /// let catalog = stac.add(Catalog::new("unattached")).unwrap();
+    /// assert!(stac.source_href(catalog).is_none());
+    /// ```
+    pub fn source_href(&self, handle: Handle) -> Option<&Href> {
+        self.node(handle).source_href.as_deref()
+    }
+
+    /// Returns the [Href] that an [Object] is going to, if it has been
+    /// given one with [set_href](Stac::set_href), e.g. by a [Layout].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// assert!(stac.next_href(root).is_none());
+    /// stac.set_href(root, "a/new/root/catalog.json");
+    /// assert_eq!(stac.next_href(root).unwrap().as_str(), "a/new/root/catalog.json");
+    /// ```
+    pub fn next_href(&self, handle: Handle) -> Option<&Href> {
+        let node = self.node(handle);
+        node.href.as_deref().filter(|_| node.relocated)
+    }
+
+    /// Returns the href of an [Object]'s `self` link, if it has one.
+    ///
+    /// A relative `self` href is resolved against the object's
+    /// [href](Stac::href). This is what the object says about itself, which
+    /// can differ from both where it was read from and where it's going.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::{Catalog, Link, Stac};
+    /// let mut catalog = Catalog::new("root");
+    /// catalog.links.push(Link::new("http://stac.test/catalog.json", "self"));
+    /// let (mut stac, root) = Stac::new(catalog).unwrap();
+    /// assert_eq!(
+    ///     stac.self_href(root).unwrap().unwrap().as_str(),
+    ///     "http://stac.test/catalog.json"
+    /// );
+    /// ```
+    pub fn self_href(&mut self, handle: Handle) -> Result<Option<Href>> {
+        self.ensure_resolved(handle)?;
+        let node = self.node(handle);
+        node.object
+            .as_ref()
+            .expect("resolved")
+            .self_link()
+            .map(|link| join(node.href.as_deref(), &link.href))
+            .transpose()
+    }
+
+    /// Sets the href without copying it, since the node and the `hrefs` map share it.
+    fn set_shared_href(&mut self, handle: Handle, href: Arc<Href>) {
         let _ = self.hrefs.insert(href.clone(), handle);
         let _ = self.node_mut(handle).href.replace(href);
     }
 
+    /// Sets the href that an object is read from, which is also its current href.
+    fn set_source_href(&mut self, handle: Handle, href: Option<Arc<Href>>) {
+        if let Some(href) = href.clone() {
+            self.set_shared_href(handle, href);
+        } else {
+            self.node_mut(handle).href = None;
+        }
+        let node = self.node_mut(handle);
+        node.source_href = href;
+        node.relocated = false;
+    }
+
     /// Finds an [Object] in the tree using a filter function.
     ///
     /// # Examples
@@ -504,14 +955,69 @@ impl<R: Read> Stac<R> {
     /// ```
     pub fn add_link(&mut self, handle: Handle, link: Link) -> Result<()> {
         self.ensure_resolved(handle)?;
-        self.node_mut(handle)
-            .object
-            .as_mut()
-            .expect("resolved")
-            .add_link(link);
+        self.object_mut(handle).add_link(link);
         Ok(())
     }
 
+    /// Returns an object's links with the provided rel.
+    ///
+    /// The links are looked up in a [LinkIndex] that is built the first time
+    /// the object's links are searched and dropped whenever they change, so
+    /// repeated lookups on objects with many links aren't linear scans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let child_links: Vec<_> = stac.links_by_rel(root, "child").unwrap().collect();
+    /// assert_eq!(child_links.len(), 3);
+    /// assert_eq!(stac.links_by_rel(root, "root").unwrap().count(), 1);
+    /// ```
+    pub fn links_by_rel<'a>(
+        &'a mut self,
+        handle: Handle,
+        rel: &'a str,
+    ) -> Result<impl Iterator<Item = &'a Link>> {
+        self.ensure_resolved(handle)?;
+        let node = self.node(handle);
+        let links = node.object.as_ref().expect("resolved").links();
+        let index = node.links_by_rel.get_or_init(|| LinkIndex::new(links));
+        Ok(index.links(links, rel))
+    }
+
+    /// Returns an object's root link, if it has one.
+    ///
+    /// Like [Stac::links_by_rel], this uses the object's [LinkIndex] instead
+    /// of scanning its links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// assert_eq!(stac.root_link(root).unwrap().unwrap().href, "./catalog.json");
+    /// ```
+    pub fn root_link(&mut self, handle: Handle) -> Result<Option<&Link>> {
+        Ok(self.links_by_rel(handle, "root")?.next())
+    }
+
+    /// Returns an object's child links.
+    ///
+    /// Like [Stac::links_by_rel], this uses the object's [LinkIndex] instead
+    /// of scanning its links.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// assert_eq!(stac.child_links(root).unwrap().count(), 3);
+    /// ```
+    pub fn child_links(&mut self, handle: Handle) -> Result<impl Iterator<Item = &Link>> {
+        self.links_by_rel(handle, "child")
+    }
+
     /// Takes the object out of the [Stac].
     ///
     /// # Examples
@@ -523,16 +1029,37 @@ impl<R: Read> Stac<R> {
     /// assert_eq!(stac.take(root).unwrap().as_catalog().unwrap(), &catalog);
     /// ```
     pub fn take(&mut self, handle: Handle) -> Option<Object> {
-        self.node_mut(handle).object.take()
+        let node = self.node_mut(handle);
+        let _ = node.links_by_rel.take();
+        node.object.take()
     }
 
     /// Takes the [Href] from the [Object].
+    ///
+    /// The href can still be used to look up the object, so it is copied.
     pub fn take_href(&mut self, handle: Handle) -> Option<Href> {
-        self.node_mut(handle).href.take()
+        self.node_mut(handle).href.take().map(unwrap_or_clone)
+    }
+
+    /// Takes the [Href] from the [Object] and forgets it, so that it
+    /// doesn't need to be copied.
+    ///
+    /// Only use this when nothing will look the object up by this href again,
+    /// e.g. when rendering an object that has just been laid out.
+    pub(crate) fn take_unique_href(&mut self, handle: Handle) -> Option<Href> {
+        let href = self.node_mut(handle).href.take()?;
+        if self.hrefs.get(&*href) == Some(&handle) {
+            let _ = self.hrefs.remove(&*href);
+        }
+        Some(unwrap_or_clone(href))
     }
 
     /// Writes this [Stac], consuming it.
     ///
+    /// Objects are rendered with [Layout::stream], so each object is dropped
+    /// after it is written. Each [item page](Layout::with_item_pages) is
+    /// written right after its last item.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -546,44 +1073,95 @@ impl<R: Read> Stac<R> {
     where
         S: Strategy,
     {
-        for result in layout.render(self) {
-            let href_object = result?;
-            writer.write(href_object)?;
+        for result in layout.stream_nodes(self, true) {
+            let node = result?;
+            writer.write(node.href_object)?;
+            for (href, page) in node.pages {
+                writer.write_json(serde_json::to_value(page)?, &href)?;
+            }
         }
         Ok(())
     }
 
+    /// Drops a node and frees its slot for reuse.
+    ///
+    /// Unlike [remove](Stac::remove), the node's children are kept in the
+    /// tree, but they no longer have a parent. The root is never released.
+    pub(crate) fn release(&mut self, handle: Handle) {
+        if handle == self.root() {
+            return;
+        }
+        let node = std::mem::take(self.node_mut(handle));
+        for child in node.children {
+            self.node_mut(child).parent = None;
+        }
+        if let Some(parent) = node.parent {
+            let _ = self.node_mut(parent).children.swap_remove(&handle);
+        }
+        if let Some(href) = node.href {
+            if self.hrefs.get(&*href) == Some(&handle) {
+                let _ = self.hrefs.remove(&*href);
+            }
+        }
+        for observer in &mut self.observers {
+            observer.on_remove(handle, node.object.as_ref());
+        }
+        self.free_nodes.push(handle);
+    }
+
     pub(crate) fn remove_structural_links(&mut self, handle: Handle) -> Result<()> {
         self.ensure_resolved(handle)?;
-        self.node_mut(handle)
-            .object
+        let child_rels = &self.child_rels;
+        let node = &mut self.nodes[handle.0];
+        let _ = node.links_by_rel.take();
+        node.object
             .as_mut()
             .expect("resolved")
             .links_mut()
-            .retain(|link| !link.is_structural());
+            .retain(|link| !link.is_structural() && !child_rels.contains(&*link.rel));
         Ok(())
     }
 
+    /// Returns a mutable reference to a resolved object, dropping its link index.
+    fn object_mut(&mut self, handle: Handle) -> &mut Object {
+        let node = self.node_mut(handle);
+        let _ = node.links_by_rel.take();
+        node.object.as_mut().expect("resolved")
+    }
+
     fn disconnect(&mut self, parent: Handle, child: Handle) {
         self.node_mut(child).parent = None;
         let _ = self.node_mut(parent).children.shift_remove(&child);
     }
 
-    fn add_node(&mut self) -> Handle {
-        if let Some(handle) = self.free_nodes.pop() {
+    /// Reserves space for `additional` new children of `parent`.
+    pub(crate) fn reserve(&mut self, parent: Handle, additional: usize) {
+        self.nodes
+            .reserve(additional.saturating_sub(self.free_nodes.len()));
+        self.hrefs.reserve(additional);
+        self.node_mut(parent).children.reserve(additional);
+    }
+
+    fn add_node(&mut self, href: Option<&Href>) -> Handle {
+        let handle = if let Some(handle) = self.free_nodes.pop() {
             handle
         } else {
             let handle = Handle(self.nodes.len());
             self.nodes.push(Node::default());
             handle
+        };
+        for observer in &mut self.observers {
+            observer.on_add(handle, href);
         }
+        handle
     }
 
     fn ensure_resolved(&mut self, handle: Handle) -> Result<()> {
         if self.node(handle).object.is_none() {
             if let Some(href) = self.node_mut(handle).href.take() {
-                let href_object = self.reader.read(href)?;
-                self.set_object(handle, href_object)?;
+                let value = self.reader.read_json(&href)?;
+                let object = Object::from_value(value)?;
+                self.set_shared_object(handle, object, Some(href))?;
             } else {
                 return Err(Error::UnresolvableNode);
             }
@@ -593,42 +1171,151 @@ impl<R: Read> Stac<R> {
 
     fn set_object(&mut self, handle: Handle, object: impl Into<ObjectHrefTuple>) -> Result<()> {
         let (object, href) = object.into();
+        self.set_shared_object(handle, object, href.map(Arc::new))
+    }
+
+    fn set_shared_object(
+        &mut self,
+        handle: Handle,
+        object: Object,
+        href: Option<Arc<Href>>,
+    ) -> Result<()> {
+        for page in self.attach_object(handle, object, href)? {
+            self.read_pages(handle, page)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the object of a node and connects its structural links, returning
+    /// the first [Page] of any paged links that still need to be read.
+    fn attach_object(
+        &mut self,
+        handle: Handle,
+        object: Object,
+        href: Option<Arc<Href>>,
+    ) -> Result<Vec<Page>> {
+        let mut items_hrefs = Vec::new();
+        let mut next_href = None;
         for link in object.links() {
-            if !link.is_structural() {
+            if link.is_items() {
+                items_hrefs.push(join(href.as_deref(), &link.href)?);
+            } else if link.is_next() && !object.is_item() {
+                next_href = Some(join(href.as_deref(), &link.href)?);
+            }
+            let is_child = self.is_child_link(link);
+            if !link.is_structural() && !is_child {
                 continue;
             }
-            let other_href = if let Some(href) = href.as_ref() {
-                href.join(&link.href)?
-            } else {
-                link.href.clone().into()
-            };
-            let other = if let Some(other) = self.hrefs.get(&other_href) {
-                *other
-            } else {
-                let other = self.add_node();
-                self.set_href(other, other_href);
-                other
-            };
-            if link.is_child() || link.is_item() {
-                if link.is_item() {
-                    self.node_mut(other).is_from_item_link = true;
-                }
+            let other = self.link_target(href.as_deref(), link)?;
+            if is_child {
                 self.connect(handle, other);
             } else if link.is_parent() {
                 // TODO what to do if there is already a parent?
                 self.connect(other, handle);
             }
         }
-        if let Some(href) = href {
-            self.set_href(handle, href);
+        self.set_source_href(handle, href);
+        let node = &mut self.nodes[handle.0];
+        let _ = node.links_by_rel.take();
+        let object = node.object.insert(object);
+        for observer in &mut self.observers {
+            observer.on_resolve(handle, object);
+        }
+        Ok(next_href
+            .map(Page::Links)
+            .into_iter()
+            .chain(items_hrefs.into_iter().map(Page::Items))
+            .collect())
+    }
+
+    /// Returns the handle of the node that a structural link points to,
+    /// creating an unresolved node if needed.
+    fn link_target(&mut self, base: Option<&Href>, link: &Link) -> Result<Handle> {
+        let other_href = join(base, &link.href)?;
+        let other = if let Some(other) = self.hrefs.get(&other_href) {
+            *other
         } else {
-            self.node_mut(handle).href = None;
+            let other = self.add_node(Some(&other_href));
+            self.set_source_href(other, Some(Arc::new(other_href)));
+            other
+        };
+        if link.is_item() {
+            self.node_mut(other).is_from_item_link = true;
+        }
+        Ok(other)
+    }
+
+    /// Reads `page` and every page linked from it by `next` links.
+    fn read_pages(&mut self, handle: Handle, page: Page) -> Result<()> {
+        let mut visited = HashSet::new();
+        let mut next = Some(page);
+        while let Some(page) = next.take() {
+            if !self.should_read_page(handle, &page, &mut visited) {
+                break;
+            }
+            let value = self.reader.read_json(page.href())?;
+            next = self.add_page(handle, page, value)?;
         }
-        let node = self.node_mut(handle);
-        node.object = Some(object);
         Ok(())
     }
 
+    /// Returns false if a page has already been read, or if it is the object itself.
+    fn should_read_page(&self, handle: Handle, page: &Page, visited: &mut HashSet<Href>) -> bool {
+        visited.insert(page.href().clone())
+            && !(matches!(page, Page::Links(_)) && self.hrefs.get(page.href()) == Some(&handle))
+    }
+
+    /// Adds the contents of a read page to `handle` and returns the next page, if any.
+    ///
+    /// The `child` and `item` links of a [Page::Links] page are connected to
+    /// `handle`, and the features of a [Page::Items] page (an
+    /// [ItemCollection]) are added as children of `handle`.
+    fn add_page(&mut self, handle: Handle, page: Page, mut value: Value) -> Result<Option<Page>> {
+        match page {
+            Page::Links(href) => {
+                let links: Vec<Link> = value
+                    .get_mut("links")
+                    .map(|links| serde_json::from_value(links.take()))
+                    .transpose()?
+                    .unwrap_or_default();
+                let mut next = None;
+                for link in &links {
+                    if link.is_next() {
+                        next = Some(Page::Links(href.join(&link.href)?));
+                    } else if self.is_child_link(link) {
+                        let child = self.link_target(Some(&href), link)?;
+                        self.connect(handle, child);
+                    }
+                }
+                Ok(next)
+            }
+            Page::Items(href) => {
+                let item_collection: ItemCollection = serde_json::from_value(value)?;
+                let next = item_collection
+                    .next_link()
+                    .map(|link| href.join(&link.href).map(Page::Items))
+                    .transpose()?;
+                for item in item_collection.features {
+                    let item_href = item
+                        .links
+                        .iter()
+                        .find(|link| link.is_self())
+                        .map(|link| href.join(&link.href))
+                        .transpose()?;
+                    let child = self.add((Object::Item(item), item_href))?;
+                    self.connect(handle, child);
+                }
+                Ok(next)
+            }
+        }
+    }
+
+    /// Returns true if a link points to a child, including links whose rel
+    /// is [treated as a child](Stac::treat_as_child).
+    fn is_child_link(&self, link: &Link) -> bool {
+        link.is_child() || link.is_item() || self.child_rels.contains(&*link.rel)
+    }
+
     fn is_item(&self, handle: Handle) -> bool {
         if let Some(object) = self.node(handle).object.as_ref() {
             object.is_item()
@@ -646,10 +1333,40 @@ impl<R: Read> Stac<R> {
     }
 }
 
+/// A page of links or items that is linked from an object.
+#[derive(Debug)]
+enum Page {
+    /// A document whose `child` and `item` links belong to the object, from a `next` link.
+    Links(Href),
+
+    /// An [ItemCollection] whose features belong to the object, from an `items` link.
+    Items(Href),
+}
+
+impl Page {
+    fn href(&self) -> &Href {
+        match self {
+            Page::Links(href) | Page::Items(href) => href,
+        }
+    }
+}
+
+fn unwrap_or_clone(href: Arc<Href>) -> Href {
+    Arc::try_unwrap(href).unwrap_or_else(|href| (*href).clone())
+}
+
+fn join(base: Option<&Href>, href: &str) -> Result<Href> {
+    if let Some(base) = base {
+        base.join(href)
+    } else {
+        Ok(href.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Stac;
-    use crate::{Catalog, HrefObject, Item, Link};
+    use crate::{Catalog, Collection, HrefObject, Item, Layout, Link};
 
     #[test]
     fn new() {
@@ -706,6 +1423,27 @@ mod tests {
         assert_eq!(stac.get(stac.root()).unwrap().id(), "examples");
     }
 
+    #[test]
+    fn source_and_next_hrefs() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let child = stac.children(root)[0];
+        let source = stac.href(child).unwrap().clone();
+        assert_eq!(stac.source_href(child), Some(&source));
+        assert!(stac.next_href(child).is_none());
+        let added = stac.add_child(root, Item::new("an-item")).unwrap();
+        Layout::new("a/new/root").layout(&mut stac).unwrap();
+        assert_eq!(stac.source_href(child), Some(&source));
+        assert_eq!(stac.next_href(child), stac.href(child));
+        assert!(stac
+            .next_href(child)
+            .unwrap()
+            .as_str()
+            .starts_with("a/new/root/"));
+        assert!(stac.source_href(added).is_none());
+        assert!(stac.next_href(added).is_some());
+        assert!(stac.self_href(child).unwrap().is_none());
+    }
+
     #[test]
     fn remove_returns_same_object() {
         let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
@@ -727,6 +1465,148 @@ mod tests {
         );
     }
 
+    #[test]
+    fn follow_items_link() {
+        use crate::{ItemCollection, Write, Writer};
+        use serde_json::json;
+
+        let directory = tempfile::tempdir().unwrap();
+        let writer = Writer::default();
+        let mut collection = Collection::new("a-collection");
+        collection.links.push(Link::new("./items.json", "items"));
+        writer
+            .write(HrefObject::new(
+                collection,
+                directory.path().join("collection.json"),
+            ))
+            .unwrap();
+        let mut page = ItemCollection::new(vec![Item::new("item-a"), Item::new("item-b")]);
+        page.links.push(Link::new("./items-2.json", "next"));
+        writer
+            .write_json(
+                serde_json::to_value(page).unwrap(),
+                &directory.path().join("items.json").into(),
+            )
+            .unwrap();
+        let mut item = Item::new("item-c");
+        item.links.push(Link::new("./item-c.json", "self"));
+        let mut page = ItemCollection::new(vec![item]);
+        page.links.push(Link::new("./items.json", "next"));
+        writer
+            .write_json(json!(page), &directory.path().join("items-2.json").into())
+            .unwrap();
+
+        let (mut stac, root) = Stac::read(directory.path().join("collection.json")).unwrap();
+        let children = stac.children(root);
+        assert_eq!(children.len(), 3);
+        assert_eq!(stac.get(children[2]).unwrap().id(), "item-c");
+        assert_eq!(
+            stac.href(children[2]).unwrap().as_str(),
+            directory.path().join("item-c.json").to_str().unwrap()
+        );
+    }
+
+    #[test]
+    fn follow_next_link() {
+        use crate::{Write, Writer};
+        use serde_json::json;
+
+        let directory = tempfile::tempdir().unwrap();
+        let writer = Writer::default();
+        let mut catalog = Catalog::new("root");
+        catalog.links.push(Link::item("./item-a.json"));
+        catalog.links.push(Link::new("./page-2.json", "next"));
+        writer
+            .write(HrefObject::new(
+                catalog,
+                directory.path().join("catalog.json"),
+            ))
+            .unwrap();
+        writer
+            .write_json(
+                json!({
+                    "links": [
+                        {"href": "./item-b.json", "rel": "item"},
+                        {"href": "./sub/catalog.json", "rel": "child"},
+                        {"href": "./catalog.json", "rel": "next"},
+                    ]
+                }),
+                &directory.path().join("page-2.json").into(),
+            )
+            .unwrap();
+
+        let (stac, root) = Stac::read(directory.path().join("catalog.json")).unwrap();
+        let children = stac.children(root);
+        assert_eq!(children.len(), 3);
+        assert_eq!(
+            stac.href(children[2]).unwrap().as_str(),
+            directory.path().join("sub/catalog.json").to_str().unwrap()
+        );
+    }
+
+    #[test]
+    fn treat_as_child() {
+        use crate::{Links, Write, Writer};
+
+        let directory = tempfile::tempdir().unwrap();
+        let writer = Writer::default();
+        let mut catalog = Catalog::new("root");
+        catalog
+            .links
+            .push(Link::new("./sub/catalog.json", "related"));
+        catalog.links.push(Link::new("./license.txt", "license"));
+        writer
+            .write(HrefObject::new(
+                catalog,
+                directory.path().join("catalog.json"),
+            ))
+            .unwrap();
+        let mut sub = Catalog::new("sub");
+        sub.links
+            .push(Link::new("../an-item/an-item.json", "related"));
+        writer
+            .write(HrefObject::new(
+                sub,
+                directory.path().join("sub/catalog.json"),
+            ))
+            .unwrap();
+        writer
+            .write(HrefObject::new(
+                Item::new("an-item"),
+                directory.path().join("an-item/an-item.json"),
+            ))
+            .unwrap();
+
+        let (mut stac, root) = Stac::read(directory.path().join("catalog.json")).unwrap();
+        stac.treat_as_child("related").unwrap();
+        stac.treat_as_child("related").unwrap();
+        let sub = stac.children(root)[0];
+        assert_eq!(stac.get(sub).unwrap().id(), "sub");
+        let item = stac.children(sub)[0];
+        assert_eq!(stac.get(item).unwrap().id(), "an-item");
+
+        let out = tempfile::tempdir().unwrap();
+        stac.write(&mut Layout::new(out.path().to_str().unwrap()), &writer)
+            .unwrap();
+        let (mut stac, root) = Stac::read(out.path().join("catalog.json")).unwrap();
+        let object = stac.get(root).unwrap();
+        assert!(object.find_link("related").is_none());
+        assert!(object.find_link("license").is_some());
+        assert!(object.find_link("child").is_some());
+    }
+
+    #[test]
+    fn estimate_does_not_resolve_items() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let first = stac.estimate(root).unwrap();
+        let item = stac.find(root, |o| o.is_item()).unwrap().unwrap();
+        let _ = stac.get(item).unwrap();
+        let second = stac.estimate(root).unwrap();
+        assert_eq!(first.items, second.items);
+        assert_eq!(first.unresolved, 2);
+        assert_eq!(second.unresolved, 1);
+    }
+
     #[test]
     fn disconnect_old_parent() {
         let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
@@ -735,4 +1615,121 @@ mod tests {
         stac.connect(child1, child2);
         assert_eq!(stac.children(root).len(), 1);
     }
+
+    #[test]
+    fn links_by_rel_after_add_link() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        assert_eq!(stac.links_by_rel(root, "related").unwrap().count(), 0);
+        stac.add_link(root, Link::new("./other.json", "related"))
+            .unwrap();
+        let links: Vec<_> = stac.links_by_rel(root, "related").unwrap().collect();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].href, "./other.json");
+        stac.remove_structural_links(root).unwrap();
+        assert_eq!(stac.links_by_rel(root, "related").unwrap().count(), 1);
+    }
+
+    #[test]
+    fn root_and_child_links_use_the_index() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        assert!(stac.node(root).links_by_rel.get().is_none());
+        assert_eq!(
+            stac.root_link(root).unwrap().unwrap().href,
+            "./catalog.json"
+        );
+        assert!(stac.node(root).links_by_rel.get().is_some());
+        assert_eq!(stac.child_links(root).unwrap().count(), 3);
+        stac.get_mut(root)
+            .unwrap()
+            .add_link(Link::child("./another-child.json"));
+        assert!(stac.node(root).links_by_rel.get().is_none());
+        assert_eq!(stac.child_links(root).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn observe() {
+        use crate::{Handle, Href, Object, Observer};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug, Default)]
+        struct Events(Vec<String>);
+
+        impl Observer for Events {
+            fn on_add(&mut self, handle: Handle, href: Option<&Href>) {
+                self.0.push(format!(
+                    "add {:?} {}",
+                    handle,
+                    href.map(|href| href.as_str()).unwrap_or_default()
+                ));
+            }
+
+            fn on_resolve(&mut self, handle: Handle, object: &Object) {
+                self.0.push(format!("resolve {:?} {}", handle, object.id()));
+            }
+
+            fn on_remove(&mut self, handle: Handle, object: Option<&Object>) {
+                self.0.push(format!(
+                    "remove {:?} {}",
+                    handle,
+                    object.map(|object| object.id()).unwrap_or_default()
+                ));
+            }
+        }
+
+        let events = Arc::new(Mutex::new(Events::default()));
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        stac.add_observer(events.clone());
+        let child = stac.add_child(root, Catalog::new("child")).unwrap();
+        let _ = stac.remove(child).unwrap();
+        assert_eq!(
+            events.lock().unwrap().0,
+            vec![
+                format!("add {:?} ", root),
+                format!("resolve {:?} root", root),
+                format!("add {:?} ", child),
+                format!("resolve {:?} child", child),
+                format!("remove {:?} child", child),
+            ]
+        );
+
+        let events = Arc::new(Mutex::new(Events::default()));
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        stac.add_observer(events.clone());
+        let child = stac
+            .find(root, |object| object.id() == "extensions-collection")
+            .unwrap()
+            .unwrap();
+        let events = &events.lock().unwrap().0;
+        assert!(events.contains(&format!(
+            "add {:?} data/extensions-collection/collection.json",
+            child
+        )));
+        assert!(events.contains(&format!("resolve {:?} extensions-collection", child)));
+    }
+
+    #[test]
+    fn add_children() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let removed = stac.add_child(root, Item::new("removed")).unwrap();
+        let _ = stac.remove(removed).unwrap();
+        let children = stac
+            .add_children(
+                root,
+                vec![
+                    HrefObject::new(Item::new("a"), "items/a.json"),
+                    HrefObject::new(Item::new("b"), "items/b.json"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(children[0], removed);
+        assert_eq!(stac.children(root), children);
+        assert_eq!(stac.hrefs[&crate::Href::from("items/b.json")], children[1]);
+
+        let items = stac
+            .add_items(children[0], (0..1000).map(|i| Item::new(i.to_string())))
+            .unwrap();
+        assert_eq!(items.len(), 1000);
+        assert_eq!(stac.children(children[0]), items);
+        assert_eq!(stac.get(items[999]).unwrap().id(), "999");
+    }
 }
@@ -0,0 +1,99 @@
+use super::Handle;
+use crate::{Href, Object};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Observes changes to the nodes of a [Stac](super::Stac).
+///
+/// Because a `Stac` resolves objects lazily, nodes appear and fill in while
+/// the tree is walked or queried. An `Observer` is told about each change, so
+/// an embedding application can keep secondary indexes, e.g. from id to
+/// [Handle] or a spatial index, in sync with the tree.
+///
+/// Every method does nothing by default. An observer that is shared through
+/// an [Arc]<[Mutex]> is also an observer, so the application can keep a clone
+/// to query the index.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Handle, Object, Observer, Stac};
+/// use std::{collections::HashMap, sync::{Arc, Mutex}};
+///
+/// #[derive(Debug, Default)]
+/// struct Ids(HashMap<String, Handle>);
+///
+/// impl Observer for Ids {
+///     fn on_resolve(&mut self, handle: Handle, object: &Object) {
+///         let _ = self.0.insert(object.id().to_string(), handle);
+///     }
+///
+///     fn on_remove(&mut self, _: Handle, object: Option<&Object>) {
+///         if let Some(object) = object {
+///             let _ = self.0.remove(object.id());
+///         }
+///     }
+/// }
+///
+/// let ids = Arc::new(Mutex::new(Ids::default()));
+/// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+/// stac.add_observer(ids.clone());
+/// let child = stac.find(root, |object| object.id() == "extensions-collection").unwrap().unwrap();
+/// assert_eq!(ids.lock().unwrap().0["extensions-collection"], child);
+/// ```
+pub trait Observer: Debug + Send + Sync {
+    /// Called when a node is added to the tree.
+    ///
+    /// Nodes that are added from a link only have an href until they are resolved.
+    fn on_add(&mut self, handle: Handle, href: Option<&Href>) {
+        let _ = (handle, href);
+    }
+
+    /// Called when a node's object is set, either because it was added with
+    /// an object or because it was read from its href.
+    fn on_resolve(&mut self, handle: Handle, object: &Object) {
+        let _ = (handle, object);
+    }
+
+    /// Called when a node is removed from the tree, with its object if it was resolved.
+    ///
+    /// The handle may be reused for a later node.
+    fn on_remove(&mut self, handle: Handle, object: Option<&Object>) {
+        let _ = (handle, object);
+    }
+
+    /// Called when the tree is [compacted](super::Stac::compact), with the
+    /// new handle for every node that's still in use.
+    fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+        let _ = handles;
+    }
+}
+
+impl<T: Observer> Observer for Arc<Mutex<T>> {
+    fn on_add(&mut self, handle: Handle, href: Option<&Href>) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_add(handle, href)
+    }
+
+    fn on_resolve(&mut self, handle: Handle, object: &Object) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_resolve(handle, object)
+    }
+
+    fn on_remove(&mut self, handle: Handle, object: Option<&Object>) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_remove(handle, object)
+    }
+
+    fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_compact(handles)
+    }
+}
@@ -0,0 +1,152 @@
+use super::{join, Handle, Stac};
+use crate::{Error, Link, Links, Read, Result};
+
+impl<R: Read> Stac<R> {
+    /// Returns every object whose `root` link doesn't resolve to the root's href.
+    ///
+    /// This reads the whole tree. Objects without a `root` link, and objects
+    /// with more than one, are included. Objects without an href are skipped,
+    /// since their relative links can't be resolved. Returns
+    /// [Error::MissingHref] if the root doesn't have an href.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, HrefObject, Item, Link, Stac};
+    /// let (mut stac, root) =
+    ///     Stac::new(HrefObject::new(Catalog::new("root"), "stac/catalog.json")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.links.push(Link::root("../old/catalog.json"));
+    /// let item = stac
+    ///     .add_child(root, HrefObject::new(item, "stac/an-item/an-item.json"))
+    ///     .unwrap();
+    /// assert_eq!(stac.check_root_links().unwrap(), vec![root, item]);
+    /// ```
+    pub fn check_root_links(&mut self) -> Result<Vec<Handle>> {
+        let root = self.root();
+        let root_href = self.href(root).ok_or(Error::MissingHref)?.clone();
+        let mut mismatched = Vec::new();
+        for result in self.walk(root).visit(|stac, handle| {
+            let Some(href) = stac.href(handle).cloned() else {
+                return Ok(None);
+            };
+            let mut root_links = stac.links_by_rel(handle, "root")?;
+            let matches = match (root_links.next(), root_links.next()) {
+                (Some(link), None) => join(Some(&href), &link.href)? == root_href,
+                _ => false,
+            };
+            Ok((!matches).then_some(handle))
+        }) {
+            mismatched.extend(result?);
+        }
+        Ok(mismatched)
+    }
+
+    /// Replaces missing, stale, or duplicate `root` links with one that points to the root.
+    ///
+    /// The objects are found with [check_root_links](Stac::check_root_links),
+    /// and each gets a single `root` link, relative to its own href, with
+    /// the root's title. Returns the objects that were repaired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, HrefObject, Item, Link, Links, Stac};
+    /// let (mut stac, root) =
+    ///     Stac::new(HrefObject::new(Catalog::new("root"), "stac/catalog.json")).unwrap();
+    /// let mut item = Item::new("an-item");
+    /// item.links.push(Link::root("../old/catalog.json"));
+    /// let item = stac
+    ///     .add_child(root, HrefObject::new(item, "stac/an-item/an-item.json"))
+    ///     .unwrap();
+    /// assert_eq!(stac.repair_root_links().unwrap().len(), 2);
+    /// assert_eq!(stac.get(item).unwrap().find_link("root").unwrap().href, "../catalog.json");
+    /// assert!(stac.check_root_links().unwrap().is_empty());
+    /// ```
+    pub fn repair_root_links(&mut self) -> Result<Vec<Handle>> {
+        let mismatched = self.check_root_links()?;
+        let root = self.root();
+        let root_href = self.href(root).ok_or(Error::MissingHref)?.clone();
+        let title = self.get(root)?.title().map(String::from);
+        for &handle in &mismatched {
+            let href = self
+                .href(handle)
+                .expect("objects without hrefs aren't checked")
+                .make_relative(&root_href);
+            let mut link = Link::root(href);
+            link.title = title.clone();
+            let object = self.get_mut(handle)?;
+            object.remove_links("root");
+            object.add_link(link);
+        }
+        Ok(mismatched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Collection, HrefObject, Item, Link, Links, Stac};
+
+    #[test]
+    fn repair() {
+        let mut root = Catalog::new("root");
+        root.title = Some("The root".to_string());
+        root.links.push(Link::root("./catalog.json"));
+        let (mut stac, root) =
+            Stac::new(HrefObject::new(root, "http://stac.test/catalog.json")).unwrap();
+
+        let mut good = Collection::new("good");
+        good.links.push(Link::root("../catalog.json"));
+        let good = stac
+            .add_child(
+                root,
+                HrefObject::new(good, "http://stac.test/good/collection.json"),
+            )
+            .unwrap();
+        let mut duplicated = Item::new("duplicated");
+        duplicated.links.push(Link::root("../../catalog.json"));
+        duplicated
+            .links
+            .push(Link::root("http://stac.test/catalog.json"));
+        let duplicated = stac
+            .add_child(
+                good,
+                HrefObject::new(
+                    duplicated,
+                    "http://stac.test/good/duplicated/duplicated.json",
+                ),
+            )
+            .unwrap();
+        let mut absolute = Item::new("absolute");
+        absolute
+            .links
+            .push(Link::root("http://stac.test/catalog.json"));
+        let absolute = stac
+            .add_child(
+                good,
+                HrefObject::new(absolute, "http://stac.test/good/absolute/absolute.json"),
+            )
+            .unwrap();
+        let _ = stac.add_child(good, Item::new("no-href")).unwrap();
+
+        assert_eq!(stac.check_root_links().unwrap(), vec![duplicated]);
+        assert_eq!(stac.repair_root_links().unwrap(), vec![duplicated]);
+        let object = stac.get(duplicated).unwrap();
+        let root_links: Vec<_> = object
+            .links()
+            .iter()
+            .filter(|link| link.is_root())
+            .collect();
+        assert_eq!(root_links.len(), 1);
+        assert_eq!(root_links[0].href, "../../catalog.json");
+        assert_eq!(root_links[0].title.as_deref(), Some("The root"));
+        assert!(stac.get(absolute).unwrap().find_link("root").is_some());
+        assert!(stac.check_root_links().unwrap().is_empty());
+    }
+
+    #[test]
+    fn root_without_href() {
+        let (mut stac, _) = Stac::new(Catalog::new("root")).unwrap();
+        assert!(stac.check_root_links().is_err());
+    }
+}
@@ -0,0 +1,407 @@
+use super::{Handle, Stac};
+use crate::{layout::Strategy, Error, Href, HrefObject, Layout, Read, Result, Write};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex},
+    thread,
+};
+
+/// The order in which [Stac::write_ordered] writes parents and their children.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteOrder {
+    /// Write a parent only after all of its descendants have been written,
+    /// so a reader never finds a link to an object that doesn't exist yet.
+    ///
+    /// The root is written last, which makes it a natural commit point when
+    /// publishing a new catalog.
+    #[default]
+    ChildrenFirst,
+
+    /// Write a parent before any of its children, e.g. when children are
+    /// found by listing and a parent must exist before its children appear.
+    ParentsFirst,
+}
+
+/// An object or item page waiting to be written.
+#[derive(Debug)]
+struct Task {
+    payload: Option<Payload>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    remaining: usize,
+}
+
+/// What a [Task] writes.
+#[derive(Debug)]
+enum Payload {
+    Object(Box<HrefObject>),
+    Page(Href, Value),
+}
+
+/// The state shared between the writing threads.
+#[derive(Debug)]
+struct Schedule {
+    tasks: Vec<Task>,
+    ready: Vec<usize>,
+    in_flight: usize,
+    error: Option<Error>,
+}
+
+impl<R: Read> Stac<R> {
+    /// Writes this [Stac] on multiple threads, consuming it.
+    ///
+    /// Unlike [write](Stac::write), objects are written concurrently by
+    /// `threads` threads, but the order between parents and their children
+    /// is guaranteed by `order`: with [WriteOrder::ChildrenFirst] a parent is
+    /// only written once all of its descendants have been written, and with
+    /// [WriteOrder::ParentsFirst] a child is only written once its parent
+    /// has been written. Objects that don't depend on each other, e.g. the
+    /// items of a collection, are written in any order.
+    ///
+    /// The whole [Stac] is rendered before anything is written. If any write
+    /// fails, no new writes are started and the first error is returned;
+    /// objects that were already written are left in place. With
+    /// [WriteOrder::ChildrenFirst] that means the root is never written
+    /// after a failure.
+    ///
+    /// [Item pages](Layout::with_item_pages) are ordered like objects that
+    /// sit between a catalog or collection and its paged items: with
+    /// [WriteOrder::ChildrenFirst] a page is written after its items and
+    /// before its parent, and with [WriteOrder::ParentsFirst] it's written
+    /// after its parent and before its items.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{Catalog, Item, Layout, Stac, WriteOrder, Writer};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+    /// let mut layout = Layout::new("stac/v0");
+    /// stac.write_ordered(&mut layout, &Writer::default(), 4, WriteOrder::ChildrenFirst)
+    ///     .unwrap();
+    /// ```
+    pub fn write_ordered<S>(
+        self,
+        layout: &mut Layout<S>,
+        writer: &(impl Write + Sync),
+        threads: usize,
+        order: WriteOrder,
+    ) -> Result<()>
+    where
+        S: Strategy,
+    {
+        let mut indices: HashMap<Handle, usize> = HashMap::new();
+        let mut pages: HashMap<Href, usize> = HashMap::new();
+        let mut tasks: Vec<Task> = Vec::new();
+        for result in layout.render_nodes(self, true) {
+            let node = result?;
+            let mut parent = node.parent.and_then(|parent| indices.get(&parent).copied());
+            if let Some(page_href) = node.page {
+                // Paged items hang off their page, which hangs off their parent.
+                let page = *pages.entry(page_href).or_insert_with(|| {
+                    add_task(&mut tasks, None, parent);
+                    tasks.len() - 1
+                });
+                parent = Some(page);
+            }
+            let _ = indices.insert(node.handle, tasks.len());
+            add_task(
+                &mut tasks,
+                Some(Payload::Object(Box::new(node.href_object))),
+                parent,
+            );
+            for (href, page) in node.pages {
+                let index = pages[&href];
+                tasks[index].payload = Some(Payload::Page(href, serde_json::to_value(page)?));
+            }
+        }
+        schedule(tasks, writer, threads, order)
+    }
+}
+
+/// Adds a task below `parent`.
+fn add_task(tasks: &mut Vec<Task>, payload: Option<Payload>, parent: Option<usize>) {
+    let index = tasks.len();
+    if let Some(parent) = parent {
+        let task = &mut tasks[parent];
+        task.children.push(index);
+        task.remaining += 1;
+    }
+    tasks.push(Task {
+        payload,
+        parent,
+        children: Vec::new(),
+        remaining: 0,
+    });
+}
+
+/// Writes the tasks on `threads` threads, in `order`.
+fn schedule(
+    tasks: Vec<Task>,
+    writer: &(impl Write + Sync),
+    threads: usize,
+    order: WriteOrder,
+) -> Result<()> {
+    let ready = match order {
+        WriteOrder::ChildrenFirst => (0..tasks.len())
+            .filter(|&index| tasks[index].children.is_empty())
+            .collect(),
+        WriteOrder::ParentsFirst => (0..tasks.len())
+            .filter(|&index| tasks[index].parent.is_none())
+            .collect(),
+    };
+    let schedule = Mutex::new(Schedule {
+        tasks,
+        ready,
+        in_flight: 0,
+        error: None,
+    });
+    let condvar = Condvar::new();
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let _ = scope.spawn(|| work(&schedule, &condvar, writer, order));
+        }
+    });
+    match schedule
+        .into_inner()
+        .expect("writers should not panic")
+        .error
+    {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Writes ready objects until there's nothing left to write, or a write fails.
+fn work(schedule: &Mutex<Schedule>, condvar: &Condvar, writer: &impl Write, order: WriteOrder) {
+    loop {
+        let (index, payload) = {
+            let mut schedule = schedule.lock().expect("writers should not panic");
+            loop {
+                if schedule.error.is_some() {
+                    return;
+                }
+                if let Some(index) = schedule.ready.pop() {
+                    schedule.in_flight += 1;
+                    let payload = schedule.tasks[index]
+                        .payload
+                        .take()
+                        .expect("each task is scheduled once, after its page is finished");
+                    break (index, payload);
+                }
+                if schedule.in_flight == 0 {
+                    return;
+                }
+                schedule = condvar.wait(schedule).expect("writers should not panic");
+            }
+        };
+        let result = match payload {
+            Payload::Object(href_object) => writer.write(*href_object),
+            Payload::Page(href, value) => writer.write_json(value, &href),
+        };
+        let mut schedule = schedule.lock().expect("writers should not panic");
+        schedule.in_flight -= 1;
+        match result {
+            Ok(()) => match order {
+                WriteOrder::ChildrenFirst => {
+                    if let Some(parent) = schedule.tasks[index].parent {
+                        let task = &mut schedule.tasks[parent];
+                        task.remaining -= 1;
+                        if task.remaining == 0 {
+                            schedule.ready.push(parent);
+                        }
+                    }
+                }
+                WriteOrder::ParentsFirst => {
+                    let children = std::mem::take(&mut schedule.tasks[index].children);
+                    schedule.ready.extend(children);
+                }
+            },
+            Err(err) => {
+                if schedule.error.is_none() {
+                    schedule.error = Some(err);
+                }
+            }
+        }
+        condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WriteOrder;
+    use crate::{Catalog, Collection, Error, HrefObject, Item, Layout, Result, Stac, Write};
+    use serde_json::Value;
+    use std::{path::Path, sync::Mutex};
+    use url::Url;
+
+    /// Records the ids of written objects, and the file names of any other
+    /// JSON written to paths, in order.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        written: Mutex<Vec<String>>,
+        fail: Option<&'static str>,
+    }
+
+    impl Write for Recorder {
+        fn write(&self, object: HrefObject) -> Result<()> {
+            let id = object.object.id().to_string();
+            if self.fail == Some(id.as_str()) {
+                return Err(Error::MissingHref);
+            }
+            self.written.lock().unwrap().push(id);
+            Ok(())
+        }
+
+        fn write_json_to_url(&self, _: Value, url: &Url) -> Result<()> {
+            Err(Error::CannotWriteUrl(url.clone()))
+        }
+
+        fn write_json_to_path(&self, _: Value, path: impl AsRef<Path>) -> Result<()> {
+            let file_name = path.as_ref().file_name().unwrap_or_default();
+            self.written
+                .lock()
+                .unwrap()
+                .push(file_name.to_string_lossy().into_owned());
+            Ok(())
+        }
+    }
+
+    fn stac() -> Stac<crate::Reader> {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        for i in 0..3 {
+            let child = stac
+                .add_child(root, Catalog::new(format!("child-{}", i)))
+                .unwrap();
+            for j in 0..4 {
+                let _ = stac
+                    .add_child(child, Item::new(format!("item-{}-{}", i, j)))
+                    .unwrap();
+            }
+        }
+        stac
+    }
+
+    fn position(written: &[String], id: &str) -> usize {
+        written.iter().position(|written| written == id).unwrap()
+    }
+
+    #[test]
+    fn children_first() {
+        let recorder = Recorder::default();
+        stac()
+            .write_ordered(
+                &mut Layout::new("a/root"),
+                &recorder,
+                4,
+                WriteOrder::ChildrenFirst,
+            )
+            .unwrap();
+        let written = recorder.written.into_inner().unwrap();
+        assert_eq!(written.len(), 16);
+        assert_eq!(written.last().unwrap(), "root");
+        for i in 0..3 {
+            let child = position(&written, &format!("child-{}", i));
+            for j in 0..4 {
+                assert!(position(&written, &format!("item-{}-{}", i, j)) < child);
+            }
+        }
+    }
+
+    #[test]
+    fn parents_first() {
+        let recorder = Recorder::default();
+        stac()
+            .write_ordered(
+                &mut Layout::new("a/root"),
+                &recorder,
+                4,
+                WriteOrder::ParentsFirst,
+            )
+            .unwrap();
+        let written = recorder.written.into_inner().unwrap();
+        assert_eq!(written.len(), 16);
+        assert_eq!(written[0], "root");
+        for i in 0..3 {
+            let child = position(&written, &format!("child-{}", i));
+            for j in 0..4 {
+                assert!(position(&written, &format!("item-{}-{}", i, j)) > child);
+            }
+        }
+    }
+
+    /// Writes a collection with five items on pages of two.
+    fn write_paged(order: WriteOrder) -> Vec<String> {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let collection = stac.add_child(root, Collection::new("col")).unwrap();
+        for i in 0..5 {
+            let _ = stac
+                .add_child(collection, Item::new(format!("item-{}", i)))
+                .unwrap();
+        }
+        let recorder = Recorder::default();
+        stac.write_ordered(
+            &mut Layout::new("a/root").with_item_pages(2),
+            &recorder,
+            4,
+            order,
+        )
+        .unwrap();
+        let written = recorder.written.into_inner().unwrap();
+        assert_eq!(written.len(), 10);
+        written
+    }
+
+    const PAGES: [(&str, &[&str]); 3] = [
+        ("collection-items.json", &["item-0", "item-1"]),
+        ("collection-items-2.json", &["item-2", "item-3"]),
+        ("collection-items-3.json", &["item-4"]),
+    ];
+
+    #[test]
+    fn children_first_pages() {
+        let written = write_paged(WriteOrder::ChildrenFirst);
+        let collection = position(&written, "col");
+        for (page, items) in PAGES {
+            let page = position(&written, page);
+            assert!(page < collection);
+            for item in items {
+                assert!(position(&written, item) < page);
+            }
+        }
+    }
+
+    #[test]
+    fn parents_first_pages() {
+        let written = write_paged(WriteOrder::ParentsFirst);
+        let collection = position(&written, "col");
+        for (page, items) in PAGES {
+            let page = position(&written, page);
+            assert!(page > collection);
+            for item in items {
+                assert!(position(&written, item) > page);
+            }
+        }
+    }
+
+    #[test]
+    fn error_stops_ancestors() {
+        let recorder = Recorder {
+            fail: Some("item-1-2"),
+            ..Default::default()
+        };
+        let err = stac()
+            .write_ordered(
+                &mut Layout::new("a/root"),
+                &recorder,
+                2,
+                WriteOrder::ChildrenFirst,
+            )
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingHref));
+        let written = recorder.written.into_inner().unwrap();
+        assert!(!written.contains(&"child-1".to_string()));
+        assert!(!written.contains(&"root".to_string()));
+    }
+}
@@ -0,0 +1,368 @@
+use super::{Handle, Node, Stac};
+use crate::{Catalog, Collection, Error, Href, Item, Object, Read, Reader, Result};
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Write as _},
+    path::Path,
+    sync::Arc,
+};
+#[cfg(feature = "async")]
+use {
+    super::{AsyncOnly, AsyncStac},
+    crate::{AsyncRead, AsyncReader},
+};
+
+const FORMAT: &str = "stac-rs-snapshot";
+const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    format: &'static str,
+    version: u32,
+    nodes: Vec<NodeRef<'a>>,
+    free_nodes: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct NodeRef<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    object: Option<ObjectRef<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    href: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_href: Option<&'a str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    relocated: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_from_item_link: bool,
+    /// True if another node is found by this node's href.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    shadowed: bool,
+}
+
+/// Serializes an object without copying it into a [Value].
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ObjectRef<'a> {
+    Item(&'a Item),
+    Catalog(&'a Catalog),
+    Collection(&'a Collection),
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    format: String,
+    version: u32,
+    nodes: Vec<SnapshotNode>,
+    #[serde(default)]
+    free_nodes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotNode {
+    #[serde(default)]
+    object: Option<Value>,
+    #[serde(default)]
+    children: Vec<usize>,
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(default)]
+    href: Option<String>,
+    #[serde(default)]
+    source_href: Option<String>,
+    #[serde(default)]
+    relocated: bool,
+    #[serde(default)]
+    is_from_item_link: bool,
+    #[serde(default)]
+    shadowed: bool,
+}
+
+impl Stac<Reader> {
+    /// Loads a `Stac` from a snapshot written by [Stac::save_snapshot], configured to use [Reader].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let path = directory.path().join("crawl.json");
+    /// stac.save_snapshot(&path).unwrap();
+    /// let (stac, root) = Stac::load_snapshot(&path).unwrap();
+    /// assert_eq!(stac.href(root).unwrap().as_str(), "data/catalog.json");
+    /// ```
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<(Stac<Reader>, Handle)> {
+        Stac::load_snapshot_with_reader(path, Reader::default())
+    }
+}
+
+impl<R: Read> Stac<R> {
+    /// Saves this `Stac`'s tree to a single file.
+    ///
+    /// The snapshot holds every node's href, parent, and children, and every
+    /// object that has been resolved, so a long crawl can be checkpointed and
+    /// resumed with [Stac::load_snapshot] without reading those objects again.
+    /// [Handles](Handle) are preserved. Observers and the reader are not saved.
+    ///
+    /// The file is written next to `path` and then renamed, so an interrupted
+    /// save doesn't clobber an earlier snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let child = stac.children(root)[0];
+    /// let _ = stac.get(child).unwrap();
+    /// let directory = tempfile::tempdir().unwrap();
+    /// stac.save_snapshot(directory.path().join("crawl.json")).unwrap();
+    /// ```
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let snapshot = SnapshotRef {
+            format: FORMAT,
+            version: VERSION,
+            nodes: self
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(index, node)| NodeRef {
+                    object: node.object.as_ref().map(|object| match object {
+                        Object::Item(item) => ObjectRef::Item(item),
+                        Object::Catalog(catalog) => ObjectRef::Catalog(catalog),
+                        Object::Collection(collection) => ObjectRef::Collection(collection),
+                    }),
+                    children: node.children.iter().map(|child| child.0).collect(),
+                    parent: node.parent.map(|parent| parent.0),
+                    href: node.href.as_deref().map(Href::as_str),
+                    source_href: node.source_href.as_deref().map(Href::as_str),
+                    relocated: node.relocated,
+                    is_from_item_link: node.is_from_item_link,
+                    shadowed: node
+                        .href
+                        .as_ref()
+                        .is_some_and(|href| self.hrefs.get(href.as_ref()) != Some(&Handle(index))),
+                })
+                .collect(),
+            free_nodes: self.free_nodes.iter().map(|handle| handle.0).collect(),
+        };
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        serde_json::to_writer(&mut writer, &snapshot)?;
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&partial, path).map_err(Error::from)
+    }
+
+    /// Loads a `Stac` from a snapshot written by [Stac::save_snapshot], using the provided [Read].
+    ///
+    /// Returns the `Stac` and the handle to its root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Reader, Stac};
+    /// let (stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let path = directory.path().join("crawl.json");
+    /// stac.save_snapshot(&path).unwrap();
+    /// let (stac, root) = Stac::load_snapshot_with_reader(&path, Reader::default()).unwrap();
+    /// ```
+    pub fn load_snapshot_with_reader(
+        path: impl AsRef<Path>,
+        reader: R,
+    ) -> Result<(Stac<R>, Handle)> {
+        let file = BufReader::new(File::open(path)?);
+        let snapshot: Snapshot = serde_json::from_reader(file)?;
+        let mut stac = Stac::empty(reader);
+        stac.restore(snapshot)?;
+        let root = stac.root();
+        Ok((stac, root))
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) -> Result<()> {
+        if snapshot.format != FORMAT || snapshot.version != VERSION {
+            return Err(Error::InvalidSnapshot(format!(
+                "unsupported format: {} version {}",
+                snapshot.format, snapshot.version
+            )));
+        }
+        let len = snapshot.nodes.len();
+        if len == 0 {
+            return Err(Error::InvalidSnapshot("there is no root node".to_string()));
+        }
+        let handle = |index: usize| {
+            if index < len {
+                Ok(Handle(index))
+            } else {
+                Err(Error::InvalidSnapshot(format!(
+                    "node {} is out of bounds",
+                    index
+                )))
+            }
+        };
+        let mut nodes = Vec::with_capacity(len);
+        let mut hrefs = HashMap::new();
+        for (index, node) in snapshot.nodes.into_iter().enumerate() {
+            let href = node.href.map(|href| Arc::new(Href::from(href)));
+            let source_href = node.source_href.map(|source_href| match href.as_ref() {
+                Some(href) if href.as_str() == source_href => href.clone(),
+                _ => Arc::new(Href::from(source_href)),
+            });
+            if let Some(href) = href.as_ref().filter(|_| !node.shadowed) {
+                let _ = hrefs.insert(href.clone(), Handle(index));
+            }
+            nodes.push(Node {
+                object: node.object.map(Object::from_value).transpose()?,
+                children: node
+                    .children
+                    .into_iter()
+                    .map(handle)
+                    .collect::<Result<IndexSet<_>>>()?,
+                parent: node.parent.map(handle).transpose()?,
+                href,
+                source_href,
+                relocated: node.relocated,
+                is_from_item_link: node.is_from_item_link,
+                links_by_rel: Default::default(),
+            });
+        }
+        let free_nodes = snapshot
+            .free_nodes
+            .into_iter()
+            .map(handle)
+            .collect::<Result<Vec<_>>>()?;
+        // A relocated node is still found by the href it was read from, unless
+        // another node has taken that href.
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(source_href) = node.source_href.as_ref().filter(|_| node.relocated) {
+                let _ = hrefs.entry(source_href.clone()).or_insert(Handle(index));
+            }
+        }
+        self.nodes = nodes;
+        self.free_nodes = free_nodes;
+        self.hrefs = hrefs;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncStac<AsyncReader> {
+    /// Loads an `AsyncStac` from a snapshot written by [Stac::save_snapshot], configured to use [AsyncReader].
+    ///
+    /// An `AsyncStac` dereferences to a [Stac], so it is saved with [Stac::save_snapshot].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::AsyncStac;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, root) = AsyncStac::read("data/catalog.json").await.unwrap();
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let path = directory.path().join("crawl.json");
+    /// stac.save_snapshot(&path).unwrap();
+    /// let (stac, root) = AsyncStac::load_snapshot(&path).unwrap();
+    /// # })
+    /// ```
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<(AsyncStac<AsyncReader>, Handle)> {
+        AsyncStac::load_snapshot_with_reader(path, AsyncReader::default())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead> AsyncStac<R> {
+    /// Loads an `AsyncStac` from a snapshot written by [Stac::save_snapshot], using the provided [AsyncRead].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{AsyncReader, AsyncStac, Catalog};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (stac, _) = AsyncStac::new(Catalog::new("root")).await.unwrap();
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let path = directory.path().join("crawl.json");
+    /// stac.save_snapshot(&path).unwrap();
+    /// let (stac, root) = AsyncStac::load_snapshot_with_reader(&path, AsyncReader::default()).unwrap();
+    /// # })
+    /// ```
+    pub fn load_snapshot_with_reader(
+        path: impl AsRef<Path>,
+        reader: R,
+    ) -> Result<(AsyncStac<R>, Handle)> {
+        let (stac, root) = Stac::load_snapshot_with_reader(path, AsyncOnly)?;
+        Ok((AsyncStac { stac, reader }, root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Error, Item, Stac};
+
+    #[test]
+    fn round_trip() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let child = stac
+            .find(root, |object| object.id() == "extensions-collection")
+            .unwrap()
+            .unwrap();
+        let removed = stac.children(root)[1];
+        let _ = stac.remove(removed).unwrap();
+        let added = stac.add_child(child, Item::new("an-item")).unwrap();
+        stac.set_href(child, "moved/collection.json");
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("snapshot.json");
+        stac.save_snapshot(&path).unwrap();
+        let (mut loaded, loaded_root) = Stac::load_snapshot(&path).unwrap();
+
+        assert_eq!(loaded_root, root);
+        assert_eq!(loaded.children(root), stac.children(root));
+        assert_eq!(loaded.parent(added), Some(child));
+        assert_eq!(loaded.get(added).unwrap().id(), "an-item");
+        assert_eq!(loaded.href(child), stac.href(child));
+        assert_eq!(loaded.source_href(child), stac.source_href(child));
+        assert_eq!(loaded.next_href(child), stac.next_href(child));
+        assert_eq!(loaded.next_href(root), None);
+        assert_eq!(loaded.free_nodes, stac.free_nodes);
+        assert_eq!(loaded.hrefs, stac.hrefs);
+        assert!(loaded.node(child).object.is_some());
+        assert_eq!(
+            loaded.node(removed).object.is_some(),
+            stac.node(removed).object.is_some()
+        );
+        assert!(!directory.path().join("snapshot.json.partial").exists());
+    }
+
+    #[test]
+    fn invalid() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("snapshot.json");
+        std::fs::write(
+            &path,
+            r#"{"format":"stac-rs-snapshot","version":1,"nodes":[{"children":[1]}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Stac::load_snapshot(&path).unwrap_err(),
+            Error::InvalidSnapshot(_)
+        ));
+        std::fs::write(&path, r#"{"format":"other","version":1,"nodes":[]}"#).unwrap();
+        assert!(matches!(
+            Stac::load_snapshot(&path).unwrap_err(),
+            Error::InvalidSnapshot(_)
+        ));
+        let (stac, _) = Stac::new(Catalog::new("root")).unwrap();
+        stac.save_snapshot(&path).unwrap();
+        assert!(Stac::load_snapshot(&path).is_ok());
+    }
+}
@@ -0,0 +1,357 @@
+use super::{Handle, Observer, Stac};
+use crate::{Error, Href, Object, Read, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// The most children of any node in the tree.
+const NODE_CAPACITY: usize = 16;
+
+/// An R-tree over the bboxes of each item that has been resolved.
+///
+/// The tree is packed with the sort-tile-recursive algorithm, and rebuilt on
+/// the next query after any item changes.
+#[derive(Debug, Default)]
+pub(super) struct SpatialIndex {
+    bboxes: HashMap<Handle, [f64; 4]>,
+    unresolved: HashSet<Handle>,
+    tree: Option<RTree>,
+}
+
+#[derive(Debug)]
+struct RTree {
+    entries: Vec<([f64; 4], Handle)>,
+    levels: Vec<Vec<[f64; 4]>>,
+}
+
+impl<R: Read> Stac<R> {
+    /// Keeps an R-tree of item bboxes, so [query_bbox](Stac::query_bbox)
+    /// doesn't have to walk the tree.
+    ///
+    /// The index is filled in as items are added and read, and kept up to
+    /// date when they're removed or the tree is [compacted](Stac::compact).
+    /// Items without a bbox use the bounds of their geometry. Changing an
+    /// item's bbox through [get_mut](Stac::get_mut) isn't tracked. Calling
+    /// this more than once does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// stac.index_bboxes();
+    /// ```
+    pub fn index_bboxes(&mut self) {
+        if self.spatial_index.is_none() {
+            let index = Arc::new(Mutex::new(SpatialIndex::default()));
+            self.add_observer(index.clone());
+            self.spatial_index = Some(index);
+        }
+    }
+
+    /// Returns the items at or under this handle whose bbox intersects this one.
+    ///
+    /// The bbox is west, south, east, north, and may cross the antimeridian.
+    /// If the bboxes are [indexed](Stac::index_bboxes), the tree is only
+    /// walked to read objects that haven't been read yet. Otherwise, every
+    /// item's bbox is checked. The items are in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// stac.index_bboxes();
+    /// let items = stac.query_bbox(root, [-180.0, -90.0, 180.0, 90.0]).unwrap();
+    /// assert!(!items.is_empty());
+    /// assert!(stac.query_bbox(root, [0.0, 0.0, 0.0, 0.0]).unwrap().is_empty());
+    /// ```
+    pub fn query_bbox(&mut self, handle: Handle, bbox: [f64; 4]) -> Result<Vec<Handle>> {
+        if bbox[1] > bbox[3] {
+            return Err(Error::InvalidBbox(format!("{:?}", bbox)));
+        }
+        let Some(index) = self.spatial_index.clone() else {
+            let mut handles = Vec::new();
+            for result in self.walk(handle).visit(|stac, handle| {
+                Ok(stac
+                    .get(handle)?
+                    .as_item()
+                    .and_then(|item| item.horizontal_bbox())
+                    .filter(|&item_bbox| intersects(bbox, item_bbox))
+                    .map(|_| handle))
+            }) {
+                handles.extend(result?);
+            }
+            return Ok(handles);
+        };
+        let unresolved = index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .unresolved
+            .iter()
+            .any(|&unresolved| self.is_within(unresolved, handle));
+        if unresolved {
+            self.read_all(handle)?;
+        }
+        let handles = index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .query(bbox);
+        Ok(handles
+            .into_iter()
+            .filter(|&item| self.is_within(item, handle))
+            .collect())
+    }
+}
+
+impl SpatialIndex {
+    fn query(&mut self, bbox: [f64; 4]) -> Vec<Handle> {
+        let bboxes = &self.bboxes;
+        let tree = self.tree.get_or_insert_with(|| {
+            RTree::new(
+                bboxes
+                    .iter()
+                    .flat_map(|(&handle, &bbox)| split(bbox).map(move |bbox| (bbox, handle)))
+                    .collect(),
+            )
+        });
+        let mut handles = Vec::new();
+        for bbox in split(bbox) {
+            tree.search(bbox, &mut handles);
+        }
+        handles.sort_by_key(|handle| handle.0);
+        handles.dedup();
+        handles
+    }
+
+    fn remove(&mut self, handle: Handle) {
+        if self.bboxes.remove(&handle).is_some() {
+            self.tree = None;
+        }
+    }
+}
+
+impl Observer for SpatialIndex {
+    fn on_add(&mut self, handle: Handle, _: Option<&Href>) {
+        let _ = self.unresolved.insert(handle);
+    }
+
+    fn on_resolve(&mut self, handle: Handle, object: &Object) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+        if let Some(bbox) = object.as_item().and_then(|item| item.horizontal_bbox()) {
+            let _ = self.bboxes.insert(handle, bbox);
+            self.tree = None;
+        }
+    }
+
+    fn on_remove(&mut self, handle: Handle, _: Option<&Object>) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+    }
+
+    fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+        self.bboxes = self
+            .bboxes
+            .drain()
+            .map(|(handle, bbox)| (handles[&handle], bbox))
+            .collect();
+        self.unresolved = self
+            .unresolved
+            .drain()
+            .map(|handle| handles[&handle])
+            .collect();
+        self.tree = None;
+    }
+}
+
+impl RTree {
+    fn new(mut entries: Vec<([f64; 4], Handle)>) -> RTree {
+        let leaves = entries.len().div_ceil(NODE_CAPACITY);
+        let slices = (leaves as f64).sqrt().ceil().max(1.0) as usize;
+        let slice_len = slices * NODE_CAPACITY;
+        entries.sort_by(|a, b| center(a.0, 0).total_cmp(&center(b.0, 0)));
+        for slice in entries.chunks_mut(slice_len) {
+            slice.sort_by(|a, b| center(a.0, 1).total_cmp(&center(b.0, 1)));
+        }
+        let mut levels = Vec::new();
+        let mut bboxes: Vec<[f64; 4]> = entries.iter().map(|(bbox, _)| *bbox).collect();
+        while bboxes.len() > 1 {
+            bboxes = bboxes.chunks(NODE_CAPACITY).map(union).collect();
+            levels.push(bboxes.clone());
+        }
+        RTree { entries, levels }
+    }
+
+    fn search(&self, bbox: [f64; 4], handles: &mut Vec<Handle>) {
+        for index in 0..self.len(self.levels.len()) {
+            self.search_node(self.levels.len(), index, bbox, handles);
+        }
+    }
+
+    fn search_node(&self, level: usize, index: usize, bbox: [f64; 4], handles: &mut Vec<Handle>) {
+        if level == 0 {
+            let (entry, handle) = self.entries[index];
+            if overlaps(entry, bbox) {
+                handles.push(handle);
+            }
+        } else if overlaps(self.levels[level - 1][index], bbox) {
+            let start = index * NODE_CAPACITY;
+            let end = (start + NODE_CAPACITY).min(self.len(level - 1));
+            for child in start..end {
+                self.search_node(level - 1, child, bbox, handles);
+            }
+        }
+    }
+
+    fn len(&self, level: usize) -> usize {
+        if level == 0 {
+            self.entries.len()
+        } else {
+            self.levels[level - 1].len()
+        }
+    }
+}
+
+/// Splits a bbox that crosses the antimeridian into its east and west halves.
+fn split(bbox: [f64; 4]) -> impl Iterator<Item = [f64; 4]> {
+    let [west, south, east, north] = bbox;
+    if west > east {
+        vec![[west, south, 180.0, north], [-180.0, south, east, north]]
+    } else {
+        vec![bbox]
+    }
+    .into_iter()
+}
+
+fn intersects(a: [f64; 4], b: [f64; 4]) -> bool {
+    split(a).any(|a| split(b).any(|b| overlaps(a, b)))
+}
+
+fn overlaps(a: [f64; 4], b: [f64; 4]) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+fn center(bbox: [f64; 4], axis: usize) -> f64 {
+    (bbox[axis] + bbox[axis + 2]) / 2.0
+}
+
+fn union(bboxes: &[[f64; 4]]) -> [f64; 4] {
+    bboxes.iter().fold(
+        [
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ],
+        |a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, RTree};
+    use crate::{Catalog, Item, Stac};
+
+    fn item(id: &str, bbox: [f64; 4]) -> Item {
+        let mut item = Item::new(id);
+        item.bbox = Some(bbox.to_vec());
+        item
+    }
+
+    #[test]
+    fn rtree() {
+        let entries: Vec<_> = (0..1000)
+            .map(|i| {
+                let (x, y) = ((i % 40) as f64, (i / 40) as f64);
+                ([x, y, x + 0.5, y + 0.5], Handle(i))
+            })
+            .collect();
+        let tree = RTree::new(entries);
+        assert_eq!(tree.levels.len(), 3);
+        let mut handles = Vec::new();
+        tree.search([10.2, 3.2, 12.2, 4.2], &mut handles);
+        handles.sort_by_key(|handle| handle.0);
+        assert_eq!(
+            handles,
+            vec![
+                Handle(130),
+                Handle(131),
+                Handle(132),
+                Handle(170),
+                Handle(171),
+                Handle(172)
+            ]
+        );
+    }
+
+    #[test]
+    fn query() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let a = stac.add_child(root, Catalog::new("a")).unwrap();
+        let west = stac
+            .add_child(a, item("west", [-10.0, 0.0, -5.0, 5.0]))
+            .unwrap();
+        let east = stac
+            .add_child(root, item("east", [5.0, 0.0, 10.0, 5.0]))
+            .unwrap();
+        let across = stac
+            .add_child(root, item("across", [170.0, 0.0, -170.0, 5.0]))
+            .unwrap();
+        let _ = stac.add_child(root, Item::new("no-bbox")).unwrap();
+        let expected = |stac: &mut Stac<crate::Reader>, handle, bbox| {
+            let mut handles = stac.query_bbox(handle, bbox).unwrap();
+            handles.sort_by_key(|handle: &Handle| handle.0);
+            handles
+        };
+        for indexed in [false, true] {
+            if indexed {
+                stac.index_bboxes();
+            }
+            assert_eq!(
+                expected(&mut stac, root, [-20.0, -20.0, 20.0, 20.0]),
+                vec![west, east]
+            );
+            assert_eq!(
+                expected(&mut stac, a, [-20.0, -20.0, 20.0, 20.0]),
+                vec![west]
+            );
+            assert_eq!(
+                expected(&mut stac, root, [175.0, 1.0, -175.0, 2.0]),
+                vec![across]
+            );
+            assert_eq!(
+                expected(&mut stac, root, [-179.0, 1.0, -178.0, 2.0]),
+                vec![across]
+            );
+            assert!(stac.query_bbox(root, [0.0, 1.0, 0.0, 0.0]).is_err());
+        }
+        let _ = stac.remove(west).unwrap();
+        let handles = stac.compact();
+        assert_eq!(
+            stac.query_bbox(root, [-20.0, -20.0, 20.0, 20.0]).unwrap(),
+            vec![handles[&east]]
+        );
+    }
+
+    #[test]
+    fn read_through() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let mut expected = stac.query_bbox(root, [-180.0, -90.0, 180.0, 90.0]).unwrap();
+        expected.sort_by_key(|handle| handle.0);
+        assert!(!expected.is_empty());
+
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        stac.index_bboxes();
+        let items = stac.query_bbox(root, [-180.0, -90.0, 180.0, 90.0]).unwrap();
+        assert_eq!(items, expected);
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Handle, Stac};
+use crate::{extensions, Error, Object, Read, Result, Walk};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Counts of the objects below a catalog or collection, as described by the
+/// [stats extension](https://github.com/stac-extensions/stats).
+///
+/// Returned by [Stac::stats], and attached to every catalog and collection by
+/// [Layout::with_stats](crate::Layout::with_stats).
+///
+/// # Examples
+///
+/// ```
+/// use stac::Stac;
+/// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+/// let stats = stac.stats(root).unwrap();
+/// assert_eq!(stats.items.count, 2);
+/// assert_eq!(stats.collections.count, 3);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// The catalogs.
+    #[serde(rename = "stats:catalogs")]
+    pub catalogs: Count,
+
+    /// The collections.
+    #[serde(rename = "stats:collections")]
+    pub collections: Count,
+
+    /// The items.
+    #[serde(rename = "stats:items")]
+    pub items: Count,
+
+    /// The number of objects that use each extension, by schema URI.
+    #[serde(rename = "stats:extensions")]
+    pub extensions: BTreeMap<String, usize>,
+}
+
+/// The number of objects of one type, in total and by STAC version.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Count {
+    /// The total number of objects.
+    pub count: usize,
+
+    /// The number of objects with each STAC version.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub versions: BTreeMap<String, usize>,
+}
+
+impl<R: Read> Stac<R> {
+    /// Counts the objects below `handle`.
+    ///
+    /// This reads every object in the subtree. The object at `handle` isn't counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let stats = stac.stats(root).unwrap();
+    /// assert_eq!(stats.catalogs.count, 0);
+    /// ```
+    pub fn stats(&mut self, handle: Handle) -> Result<Stats> {
+        Ok(self
+            .stats_by_catalog(handle)?
+            .remove(&handle)
+            .unwrap_or_default())
+    }
+
+    /// Counts the objects below every catalog and collection under, and including, `handle`.
+    pub(crate) fn stats_by_catalog(&mut self, handle: Handle) -> Result<HashMap<Handle, Stats>> {
+        let catalogs = self.walk(handle).skip_items().collect::<Result<Vec<_>>>()?;
+        let mut stats_by_catalog = HashMap::with_capacity(catalogs.len());
+        // Children are always walked after their parents, so walking
+        // backwards counts every child before its parent.
+        for &catalog in catalogs.iter().rev() {
+            let mut stats = Stats::default();
+            for child in self.children(catalog) {
+                if let Some(child_stats) = stats_by_catalog.get(&child) {
+                    stats.merge(child_stats);
+                }
+                stats.count(self.get(child)?);
+            }
+            let _ = stats_by_catalog.insert(catalog, stats);
+        }
+        Ok(stats_by_catalog)
+    }
+}
+
+impl Stats {
+    /// Adds one object to these stats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{stac::Stats, Item, Object};
+    /// let mut stats = Stats::default();
+    /// stats.count(&Object::from(Item::new("an-id")));
+    /// assert_eq!(stats.items.count, 1);
+    /// assert_eq!(stats.items.versions["1.0.0"], 1);
+    /// ```
+    pub fn count(&mut self, object: &Object) {
+        let (count, version) = match object {
+            Object::Item(item) => (&mut self.items, &item.version),
+            Object::Catalog(catalog) => (&mut self.catalogs, &catalog.version),
+            Object::Collection(collection) => (&mut self.collections, &collection.version),
+        };
+        count.count += 1;
+        *count.versions.entry(version.clone()).or_default() += 1;
+        for extension in object.extensions().unwrap_or_default() {
+            *self.extensions.entry(extension.clone()).or_default() += 1;
+        }
+    }
+
+    /// Adds other stats to these stats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{stac::Stats, Item, Object};
+    /// let mut stats = Stats::default();
+    /// stats.count(&Object::from(Item::new("an-id")));
+    /// stats.merge(&stats.clone());
+    /// assert_eq!(stats.items.count, 2);
+    /// ```
+    pub fn merge(&mut self, other: &Stats) {
+        self.catalogs.merge(&other.catalogs);
+        self.collections.merge(&other.collections);
+        self.items.merge(&other.items);
+        for (extension, count) in &other.extensions {
+            *self.extensions.entry(extension.clone()).or_default() += count;
+        }
+    }
+
+    /// Sets these stats on a catalog or collection, and adds the stats extension to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{stac::Stats, Catalog, Object};
+    /// let mut catalog = Object::from(Catalog::new("an-id"));
+    /// Stats::default().apply(&mut catalog).unwrap();
+    /// let Object::Catalog(catalog) = catalog else { unreachable!() };
+    /// assert_eq!(catalog.additional_fields["stats:items"]["count"], 0);
+    /// assert!(catalog.extensions.unwrap()[0].contains("/stats/"));
+    /// ```
+    pub fn apply(&self, object: &mut Object) -> Result<()> {
+        let Value::Object(fields) = serde_json::to_value(self)? else {
+            unreachable!("stats serialize to objects");
+        };
+        let (additional_fields, extensions): (&mut Map<String, Value>, _) = match object {
+            Object::Catalog(catalog) => (&mut catalog.additional_fields, &mut catalog.extensions),
+            Object::Collection(collection) => (
+                &mut collection.additional_fields,
+                &mut collection.extensions,
+            ),
+            Object::Item(item) => {
+                return Err(Error::InvalidMetadata(format!(
+                    "cannot set stats on item {}",
+                    item.id
+                )))
+            }
+        };
+        additional_fields.extend(fields);
+        let stats = extensions::get("stats").expect("stats is a registered extension");
+        let uri = stats.uri(stats.latest_version());
+        let extensions = extensions.get_or_insert_with(Vec::new);
+        if !extensions.contains(&uri) {
+            extensions.push(uri);
+        }
+        Ok(())
+    }
+}
+
+impl Count {
+    fn merge(&mut self, other: &Count) {
+        self.count += other.count;
+        for (version, count) in &other.versions {
+            *self.versions.entry(version.clone()).or_default() += count;
+        }
+    }
+}
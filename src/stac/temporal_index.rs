@@ -0,0 +1,348 @@
+use super::{Handle, Observer, Stac};
+use crate::{Href, Object, Read, Result};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{Bound, RangeBounds},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// An interval tree over the datetimes of each item that has been resolved.
+///
+/// The tree is rebuilt on the next query after any item changes.
+#[derive(Debug, Default)]
+pub(super) struct TemporalIndex {
+    intervals: HashMap<Handle, Interval>,
+    unresolved: HashSet<Handle>,
+    tree: Option<IntervalTree>,
+}
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// Intervals sorted by start, as an implicit balanced tree where each
+/// subtree's root is its middle entry.
+#[derive(Debug)]
+struct IntervalTree {
+    entries: Vec<(Interval, Handle)>,
+
+    /// The latest end in the subtree rooted at each entry.
+    max_ends: Vec<DateTime<Utc>>,
+}
+
+impl<R: Read> Stac<R> {
+    /// Keeps an interval tree of item datetimes, so
+    /// [query_datetime](Stac::query_datetime) doesn't have to walk the tree.
+    ///
+    /// The index is filled in as items are added and read, and kept up to
+    /// date when they're removed or the tree is [compacted](Stac::compact).
+    /// Changing an item's datetimes through [get_mut](Stac::get_mut) isn't
+    /// tracked. Calling this more than once does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Stac;
+    /// let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// stac.index_datetimes();
+    /// ```
+    pub fn index_datetimes(&mut self) {
+        if self.temporal_index.is_none() {
+            let index = Arc::new(Mutex::new(TemporalIndex::default()));
+            self.add_observer(index.clone());
+            self.temporal_index = Some(index);
+        }
+    }
+
+    /// Returns the items at or under this handle whose datetimes intersect this range.
+    ///
+    /// An item's datetimes are its `start_datetime` and `end_datetime`, or
+    /// its `datetime` if it doesn't have both. Items without datetimes, or
+    /// whose datetimes can't be parsed, are skipped. If the datetimes are
+    /// [indexed](Stac::index_datetimes), the tree is only walked to read
+    /// objects that haven't been read yet. The items are in no particular
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// stac.index_datetimes();
+    /// let mut item = Item::new("an-item");
+    /// item.properties.datetime = Some("2023-06-01T00:00:00Z".to_string());
+    /// let item = stac.add_child(root, item).unwrap();
+    /// let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// assert_eq!(stac.query_datetime(root, start..).unwrap(), vec![item]);
+    /// assert!(stac.query_datetime(root, ..start).unwrap().is_empty());
+    /// ```
+    pub fn query_datetime(
+        &mut self,
+        handle: Handle,
+        range: impl RangeBounds<DateTime<Utc>>,
+    ) -> Result<Vec<Handle>> {
+        let Some(index) = self.temporal_index.clone() else {
+            let mut handles = Vec::new();
+            for result in self.walk(handle).visit(|stac, handle| {
+                Ok(stac
+                    .get(handle)?
+                    .as_item()
+                    .and_then(|item| item.interval().ok().flatten())
+                    .filter(|&interval| intersects(&range, interval))
+                    .map(|_| handle))
+            }) {
+                handles.extend(result?);
+            }
+            return Ok(handles);
+        };
+        let unresolved = index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .unresolved
+            .iter()
+            .any(|&unresolved| self.is_within(unresolved, handle));
+        if unresolved {
+            self.read_all(handle)?;
+        }
+        let handles = index
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .query(&range);
+        Ok(handles
+            .into_iter()
+            .filter(|&item| self.is_within(item, handle))
+            .collect())
+    }
+}
+
+impl TemporalIndex {
+    fn query(&mut self, range: &impl RangeBounds<DateTime<Utc>>) -> Vec<Handle> {
+        let intervals = &self.intervals;
+        let tree = self.tree.get_or_insert_with(|| {
+            IntervalTree::new(
+                intervals
+                    .iter()
+                    .map(|(&handle, &interval)| (interval, handle))
+                    .collect(),
+            )
+        });
+        let mut handles = Vec::new();
+        tree.search(0, tree.entries.len(), range, &mut handles);
+        handles.sort_by_key(|handle| handle.0);
+        handles
+    }
+
+    fn remove(&mut self, handle: Handle) {
+        if self.intervals.remove(&handle).is_some() {
+            self.tree = None;
+        }
+    }
+}
+
+impl Observer for TemporalIndex {
+    fn on_add(&mut self, handle: Handle, _: Option<&Href>) {
+        let _ = self.unresolved.insert(handle);
+    }
+
+    fn on_resolve(&mut self, handle: Handle, object: &Object) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+        if let Some(interval) = object
+            .as_item()
+            .and_then(|item| item.interval().ok().flatten())
+        {
+            let _ = self.intervals.insert(handle, interval);
+            self.tree = None;
+        }
+    }
+
+    fn on_remove(&mut self, handle: Handle, _: Option<&Object>) {
+        let _ = self.unresolved.remove(&handle);
+        self.remove(handle);
+    }
+
+    fn on_compact(&mut self, handles: &HashMap<Handle, Handle>) {
+        self.intervals = self
+            .intervals
+            .drain()
+            .map(|(handle, interval)| (handles[&handle], interval))
+            .collect();
+        self.unresolved = self
+            .unresolved
+            .drain()
+            .map(|handle| handles[&handle])
+            .collect();
+        self.tree = None;
+    }
+}
+
+impl IntervalTree {
+    fn new(mut entries: Vec<(Interval, Handle)>) -> IntervalTree {
+        entries.sort_by_key(|((start, _), _)| *start);
+        let mut max_ends: Vec<_> = entries.iter().map(|((_, end), _)| *end).collect();
+        let _ = fill_max_ends(&mut max_ends, 0, entries.len());
+        IntervalTree { entries, max_ends }
+    }
+
+    /// Searches the subtree of entries from `low` up to (but not including) `high`.
+    fn search(
+        &self,
+        low: usize,
+        high: usize,
+        range: &impl RangeBounds<DateTime<Utc>>,
+        handles: &mut Vec<Handle>,
+    ) {
+        if low >= high || !ends_after_start(range, self.max_ends[(low + high) / 2]) {
+            return;
+        }
+        let middle = (low + high) / 2;
+        self.search(low, middle, range, handles);
+        let (interval, handle) = self.entries[middle];
+        if starts_before_end(range, interval.0) {
+            if ends_after_start(range, interval.1) {
+                handles.push(handle);
+            }
+            self.search(middle + 1, high, range, handles);
+        }
+    }
+}
+
+/// Sets each middle entry's max end to the latest end in its subtree, and returns it.
+fn fill_max_ends(max_ends: &mut [DateTime<Utc>], low: usize, high: usize) -> Option<DateTime<Utc>> {
+    if low >= high {
+        return None;
+    }
+    let middle = (low + high) / 2;
+    let max_end = [
+        Some(max_ends[middle]),
+        fill_max_ends(max_ends, low, middle),
+        fill_max_ends(max_ends, middle + 1, high),
+    ]
+    .into_iter()
+    .flatten()
+    .max();
+    max_ends[middle] = max_end.expect("the middle entry has an end");
+    max_end
+}
+
+fn intersects(range: &impl RangeBounds<DateTime<Utc>>, (start, end): Interval) -> bool {
+    starts_before_end(range, start) && ends_after_start(range, end)
+}
+
+/// Returns true if an interval that starts here could intersect the range.
+fn starts_before_end(range: &impl RangeBounds<DateTime<Utc>>, start: DateTime<Utc>) -> bool {
+    match range.end_bound() {
+        Bound::Included(end) => start <= *end,
+        Bound::Excluded(end) => start < *end,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Returns true if an interval that ends here could intersect the range.
+fn ends_after_start(range: &impl RangeBounds<DateTime<Utc>>, end: DateTime<Utc>) -> bool {
+    match range.start_bound() {
+        Bound::Included(start) => end >= *start,
+        Bound::Excluded(start) => end > *start,
+        Bound::Unbounded => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, IntervalTree};
+    use crate::{Catalog, Item, Stac};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn day(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap() + Duration::days(n)
+    }
+
+    fn item(id: &str, start: i64, end: i64) -> Item {
+        let mut item = Item::new(id);
+        item.properties.datetime = None;
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("start_datetime".to_string(), day(start).to_rfc3339().into());
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("end_datetime".to_string(), day(end).to_rfc3339().into());
+        item
+    }
+
+    #[test]
+    fn interval_tree() {
+        let entries: Vec<_> = (0..500)
+            .map(|i| ((day(i as i64), day(i as i64 + (i % 7) as i64)), Handle(i)))
+            .collect();
+        let tree = IntervalTree::new(entries.clone());
+        for range in [
+            (day(100), day(110)),
+            (day(-10), day(0)),
+            (day(498), day(600)),
+        ] {
+            let mut handles = Vec::new();
+            tree.search(0, tree.entries.len(), &(range.0..=range.1), &mut handles);
+            handles.sort_by_key(|handle| handle.0);
+            let expected: Vec<_> = entries
+                .iter()
+                .filter(|((start, end), _)| *start <= range.1 && *end >= range.0)
+                .map(|(_, handle)| *handle)
+                .collect();
+            assert_eq!(handles, expected);
+        }
+    }
+
+    #[test]
+    fn query() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let a = stac.add_child(root, Catalog::new("a")).unwrap();
+        let january = stac.add_child(a, item("january", 0, 30)).unwrap();
+        let june = stac.add_child(root, item("june", 151, 180)).unwrap();
+        let mut no_datetime = Item::new("no-datetime");
+        no_datetime.properties.datetime = None;
+        let _ = stac.add_child(root, no_datetime).unwrap();
+        let sorted = |mut handles: Vec<Handle>| {
+            handles.sort_by_key(|handle| handle.0);
+            handles
+        };
+        for indexed in [false, true] {
+            if indexed {
+                stac.index_datetimes();
+            }
+            assert_eq!(
+                sorted(stac.query_datetime(root, ..).unwrap()),
+                vec![january, june]
+            );
+            assert_eq!(stac.query_datetime(a, ..).unwrap(), vec![january]);
+            assert_eq!(
+                stac.query_datetime(root, day(30)..day(151)).unwrap(),
+                vec![january]
+            );
+            assert_eq!(
+                sorted(stac.query_datetime(root, day(30)..=day(151)).unwrap()),
+                vec![january, june]
+            );
+            assert!(stac
+                .query_datetime(root, day(31)..day(151))
+                .unwrap()
+                .is_empty());
+        }
+        let _ = stac.remove(january).unwrap();
+        let handles = stac.compact();
+        assert_eq!(stac.query_datetime(root, ..).unwrap(), vec![handles[&june]]);
+    }
+
+    #[test]
+    fn read_through() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let mut expected = stac.query_datetime(root, ..).unwrap();
+        expected.sort_by_key(|handle| handle.0);
+        assert!(!expected.is_empty());
+
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        stac.index_datetimes();
+        assert_eq!(stac.query_datetime(root, ..).unwrap(), expected);
+    }
+}
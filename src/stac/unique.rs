@@ -0,0 +1,117 @@
+use super::{Handle, Stac};
+use crate::{Error, Read, Result};
+use std::collections::{HashMap, HashSet};
+
+impl<R: Read> Stac<R> {
+    /// Returns every pair of objects in the tree that have the same id.
+    ///
+    /// This reads the whole tree. Each pair is in walk order, and an id that
+    /// is used three times produces three pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// let a = stac.add_child(root, Catalog::new("a")).unwrap();
+    /// let b = stac.add_child(root, Catalog::new("b")).unwrap();
+    /// let first = stac.add_child(a, Item::new("an-item")).unwrap();
+    /// let second = stac.add_child(b, Item::new("an-item")).unwrap();
+    /// assert_eq!(stac.check_unique_ids().unwrap(), vec![(first, second)]);
+    /// ```
+    pub fn check_unique_ids(&mut self) -> Result<Vec<(Handle, Handle)>> {
+        let mut handles_by_id: HashMap<String, Vec<Handle>> = HashMap::new();
+        let mut duplicates = Vec::new();
+        let root = self.root();
+        for result in self
+            .walk(root)
+            .visit(|stac, handle| Ok((handle, stac.get(handle)?.id().to_string())))
+        {
+            let (handle, id) = result?;
+            let handles = handles_by_id.entry(id).or_default();
+            duplicates.extend(handles.iter().map(|&other| (other, handle)));
+            handles.push(handle);
+        }
+        Ok(duplicates)
+    }
+
+    /// Rejects children whose id is already used by a sibling.
+    ///
+    /// When set, [add_child](Stac::add_child),
+    /// [add_children](Stac::add_children), and [add_items](Stac::add_items)
+    /// return [Error::DuplicateId] instead of adding the object. Only siblings
+    /// that have already been read are checked, so use
+    /// [check_unique_ids](Stac::check_unique_ids) to check a tree that was
+    /// read lazily.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Catalog, Error, Item, Stac};
+    /// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+    /// stac.reject_duplicate_ids(true);
+    /// let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+    /// assert!(matches!(
+    ///     stac.add_child(root, Item::new("an-item")).unwrap_err(),
+    ///     Error::DuplicateId(_)
+    /// ));
+    /// ```
+    pub fn reject_duplicate_ids(&mut self, reject: bool) {
+        self.reject_duplicate_ids = reject;
+    }
+
+    pub(super) fn check_sibling_id(&self, parent: Handle, id: &str) -> Result<()> {
+        let duplicate = self.node(parent).children.iter().any(|&child| {
+            self.node(child)
+                .object
+                .as_ref()
+                .is_some_and(|object| object.id() == id)
+        });
+        if duplicate {
+            Err(Error::DuplicateId(id.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn sibling_ids(&self, parent: Handle) -> HashSet<String> {
+        self.node(parent)
+            .children
+            .iter()
+            .filter_map(|&child| self.node(child).object.as_ref())
+            .map(|object| object.id().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Catalog, Error, Item, Stac};
+
+    #[test]
+    fn read_catalog() {
+        // Two of the spec's example collections are both "sentinel-2".
+        let (mut stac, _) = Stac::read("data/catalog.json").unwrap();
+        let duplicates = stac.check_unique_ids().unwrap();
+        assert_eq!(duplicates.len(), 1);
+        let (a, b) = duplicates[0];
+        assert_eq!(stac.get(a).unwrap().id(), "sentinel-2");
+        assert_eq!(stac.get(b).unwrap().id(), "sentinel-2");
+    }
+
+    #[test]
+    fn reject_duplicate_items() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let _ = stac.add_items(root, [Item::new("a")]).unwrap();
+        let _ = stac.add_child(root, Item::new("a")).unwrap();
+        assert_eq!(stac.check_unique_ids().unwrap().len(), 1);
+        stac.reject_duplicate_ids(true);
+        assert!(matches!(
+            stac.add_items(root, [Item::new("b"), Item::new("b")])
+                .unwrap_err(),
+            Error::DuplicateId(id) if id == "b"
+        ));
+        let other = stac.add_child(root, Catalog::new("other")).unwrap();
+        let _ = stac.add_child(other, Item::new("a")).unwrap();
+    }
+}
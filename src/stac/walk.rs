@@ -115,10 +115,29 @@
 //! ```
 //!
 //! See the [Walk] trait documentation for more configuration options.
+//!
+//! ## Parallel walks
+//!
+//! For CPU-bound visitors (e.g. validation or hashing) over large catalogs, [BorrowedWalk::parallel] reads and visits objects on a pool of threads.
+//! Because the `Stac` is shared between threads, a parallel `visit` function gets an immutable reference to each [Object] instead of a mutable reference to the `Stac`.
+//! Results are still returned in the same order as a sequential walk with the same options:
+//!
+//! ```
+//! use stac::{Stac, Walk};
+//! let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+//! let ids = stac
+//!     .walk(root)
+//!     .parallel(4)
+//!     .visit(|_, object| Ok(object.id().to_string()))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(ids[0], "examples");
+//! assert_eq!(ids.len(), 6);
+//! ```
 
 use super::{Handle, Stac};
-use crate::{Read, Result};
-use std::collections::VecDeque;
+use crate::{CancellationToken, Object, Read, Result};
+use std::{collections::VecDeque, thread};
 
 /// Walk
 pub trait Walk: Sized {
@@ -191,6 +210,27 @@ pub trait Walk: Sized {
         self.options_mut().strategy = Strategy::ItemsOnly;
         self
     }
+
+    /// Stop walking when the [CancellationToken] is cancelled.
+    ///
+    /// The token is checked before each object is visited. Once it is
+    /// cancelled, the walk yields one [Error::Cancelled](crate::Error::Cancelled) and then stops.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{CancellationToken, Stac, Walk};
+    /// let token = CancellationToken::new();
+    /// token.cancel();
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let results: Vec<_> = stac.walk(root).cancel_on(token).collect();
+    /// assert_eq!(results.len(), 1);
+    /// assert!(results[0].is_err());
+    /// ```
+    fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.options_mut().cancellation = Some(token);
+        self
+    }
 }
 
 /// An iterator over a [Stac's](Stac) [Handles](Handle).
@@ -217,11 +257,30 @@ where
     options: Options,
 }
 
+/// A walk over a [Stac] that reads and visits objects on multiple threads.
+///
+/// Created by [BorrowedWalk::parallel]. The whole walk runs on the first call
+/// to `next`, and then results are yielded in the same order as a sequential
+/// walk with the same [Options].
+#[derive(Debug)]
+pub struct ParallelWalk<'a, R: Read, G, T>
+where
+    G: Fn(Handle, &Object) -> Result<T> + Sync,
+{
+    handles: VecDeque<Handle>,
+    stac: &'a mut Stac<R>,
+    visit: G,
+    options: Options,
+    threads: usize,
+    results: Option<VecDeque<Result<T>>>,
+}
+
 /// Walk options
 #[derive(Debug)]
 pub struct Options {
     depth_first: bool,
     strategy: Strategy,
+    cancellation: Option<CancellationToken>,
 }
 
 /// Walk strategy
@@ -283,6 +342,42 @@ where
             options: self.options,
         }
     }
+
+    /// Returns a [ParallelWalk] that uses `threads` threads to read and visit objects.
+    ///
+    /// A `threads` value of zero is treated as one. The parallel walk starts
+    /// with a `visit` function that returns each object's [Handle]; use
+    /// [ParallelWalk::visit] to set your own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Stac, Walk};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let handles = stac
+    ///     .walk(root)
+    ///     .items_only()
+    ///     .parallel(2)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(handles.len(), 2);
+    /// ```
+    pub fn parallel(
+        self,
+        threads: usize,
+    ) -> ParallelWalk<'a, R, impl Fn(Handle, &Object) -> Result<Handle> + Sync, Handle>
+    where
+        R: Sync,
+    {
+        ParallelWalk {
+            handles: self.handles,
+            stac: self.stac,
+            visit: |handle, _: &Object| Ok(handle),
+            options: self.options,
+            threads: threads.max(1),
+            results: None,
+        }
+    }
 }
 
 impl<R: Read, F, T> Walk for BorrowedWalk<'_, R, F, T>
@@ -301,12 +396,7 @@ where
     type Item = Result<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        walk(
-            &mut self.handles,
-            &mut self.stac,
-            &mut self.visit,
-            &self.options,
-        )
+        walk(&mut self.handles, self.stac, &mut self.visit, &self.options)
     }
 }
 
@@ -353,11 +443,129 @@ where
     }
 }
 
+impl<'a, R: Read + Sync, G, T: Send> ParallelWalk<'a, R, G, T>
+where
+    G: Fn(Handle, &Object) -> Result<T> + Sync,
+{
+    /// Returns a new `ParallelWalk` with the provided `visit` function.
+    ///
+    /// The function is called from multiple threads, so it receives the
+    /// resolved [Object] rather than a mutable reference to the [Stac].
+    pub fn visit<U: Send>(
+        self,
+        visit: impl Fn(Handle, &Object) -> Result<U> + Sync,
+    ) -> ParallelWalk<'a, R, impl Fn(Handle, &Object) -> Result<U> + Sync, U> {
+        ParallelWalk {
+            handles: self.handles,
+            stac: self.stac,
+            visit,
+            options: self.options,
+            threads: self.threads,
+            results: None,
+        }
+    }
+
+    fn run(&mut self) -> VecDeque<Result<T>> {
+        let mut results = VecDeque::new();
+        if let Err(err) = resolve(
+            self.stac,
+            self.handles.iter().copied().collect(),
+            self.threads,
+            &self.options,
+        ) {
+            results.push_back(Err(err));
+            return results;
+        }
+        let mut handles = Vec::new();
+        while let Some(result) = walk(
+            &mut self.handles,
+            self.stac,
+            |_, handle| Ok(handle),
+            &self.options,
+        ) {
+            match result {
+                Ok(handle) => handles.push(handle),
+                Err(err) => {
+                    results.push_back(Err(err));
+                    return results;
+                }
+            }
+        }
+        let stac = &*self.stac;
+        let visit = &self.visit;
+        let options = &self.options;
+        let chunk_size = handles.len().div_ceil(self.threads).max(1);
+        thread::scope(|scope| {
+            let workers: Vec<_> = handles
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&handle| {
+                                options.check_cancelled()?;
+                                let object = stac
+                                    .node(handle)
+                                    .object
+                                    .as_ref()
+                                    .expect("objects are resolved before they are visited");
+                                visit(handle, object)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            for worker in workers {
+                results.extend(worker.join().expect("visit should not panic"));
+            }
+        });
+        if let Some(i) = results.iter().position(|result| result.is_err()) {
+            results.truncate(i + 1);
+        }
+        results
+    }
+}
+
+impl<R: Read + Sync, G, T: Send> Walk for ParallelWalk<'_, R, G, T>
+where
+    G: Fn(Handle, &Object) -> Result<T> + Sync,
+{
+    fn options_mut(&mut self) -> &mut Options {
+        &mut self.options
+    }
+}
+
+impl<R: Read + Sync, G, T: Send> Iterator for ParallelWalk<'_, R, G, T>
+where
+    G: Fn(Handle, &Object) -> Result<T> + Sync,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.results.is_none() {
+            self.results = Some(self.run());
+        }
+        self.results
+            .as_mut()
+            .and_then(|results| results.pop_front())
+    }
+}
+
+impl Options {
+    fn check_cancelled(&self) -> Result<()> {
+        self.cancellation
+            .as_ref()
+            .map(|token| token.check())
+            .unwrap_or(Ok(()))
+    }
+}
+
 impl Default for Options {
     fn default() -> Options {
         Options {
             depth_first: false,
             strategy: Strategy::All,
+            cancellation: None,
         }
     }
 }
@@ -372,7 +580,10 @@ where
     R: Read,
 {
     if let Some(handle) = handles.pop_front() {
-        if let Err(err) = stac.ensure_resolved(handle) {
+        if let Err(err) = options.check_cancelled() {
+            handles.clear();
+            Some(Err(err))
+        } else if let Err(err) = stac.ensure_resolved(handle) {
             handles.clear();
             Some(Err(err))
         } else {
@@ -396,7 +607,7 @@ where
                     } else {
                         handles.extend(children)
                     }
-                    if !(matches!(options.strategy, Strategy::ItemsOnly) && !stac.is_item(handle)) {
+                    if !matches!(options.strategy, Strategy::ItemsOnly) || stac.is_item(handle) {
                         Some(Ok(value))
                     } else {
                         walk(handles, stac, visit, options)
@@ -413,6 +624,55 @@ where
     }
 }
 
+/// Resolves every node that a walk from `handles` would visit, one tree level
+/// at a time, reading each level's objects on up to `threads` threads.
+fn resolve<R: Read + Sync>(
+    stac: &mut Stac<R>,
+    mut level: Vec<Handle>,
+    threads: usize,
+    options: &Options,
+) -> Result<()> {
+    while !level.is_empty() {
+        options.check_cancelled()?;
+        let hrefs: Vec<_> = level
+            .iter()
+            .filter(|&&handle| stac.node(handle).object.is_none())
+            .filter_map(|&handle| stac.node(handle).href.clone().map(|href| (handle, href)))
+            .collect();
+        let chunk_size = hrefs.len().div_ceil(threads).max(1);
+        let reader = &stac.reader;
+        let objects: Vec<Result<Object>> = thread::scope(|scope| {
+            let workers: Vec<_> = hrefs
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(_, href)| reader.read_json(href).and_then(Object::from_value))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("reading should not panic"))
+                .collect()
+        });
+        for ((handle, href), object) in hrefs.into_iter().zip(objects) {
+            stac.set_shared_object(handle, object?, Some(href))?;
+        }
+        let mut next = Vec::new();
+        for handle in level {
+            stac.ensure_resolved(handle)?;
+            next.extend(stac.node(handle).children.iter().filter(|&&child| {
+                !(matches!(options.strategy, Strategy::SkipItems) && stac.is_item(child))
+            }));
+        }
+        level = next;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::Walk;
@@ -479,4 +739,54 @@ mod tests {
         assert!(stac.find(root, |o| o.is_collection()).unwrap().is_none());
         assert!(stac.find(root, |o| o.is_item()).unwrap().is_none());
     }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        for threads in [1, 2, 8] {
+            let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+            let sequential = stac
+                .walk(root)
+                .depth_first()
+                .visit(|stac, handle| stac.get(handle).map(|object| object.id().to_string()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+            let parallel = stac
+                .walk(root)
+                .depth_first()
+                .parallel(threads)
+                .visit(|_, object| Ok(object.id().to_string()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(sequential, parallel);
+        }
+    }
+
+    #[test]
+    fn parallel_stops_at_error() {
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let results: Vec<_> = stac
+            .walk(root)
+            .parallel(2)
+            .visit(|handle, _| {
+                if handle == root {
+                    Ok(())
+                } else {
+                    Err(crate::Error::UnresolvableNode)
+                }
+            })
+            .collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn parallel_cancelled() {
+        let token = crate::CancellationToken::new();
+        token.cancel();
+        let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+        let results: Vec<_> = stac.walk(root).cancel_on(token).parallel(2).collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(crate::Error::Cancelled)));
+    }
 }
@@ -0,0 +1,335 @@
+use crate::{Error, Item, Result};
+use serde_json::{Map, Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::File,
+    io::BufReader,
+    path::Path,
+    str::FromStr,
+};
+
+/// A partial [Item] with `{placeholders}` that renders into concrete items.
+///
+/// Every string in the template, including object keys, can contain
+/// placeholders such as `{scene}`, which are replaced with the value of the
+/// matching variable. A string that is only a placeholder, e.g. `"{cloud_cover}"`,
+/// is replaced by the variable's JSON value, so numbers, arrays, and objects
+/// keep their types. Use `{{` and `}}` for literal braces.
+///
+/// Rendered fields are laid over the fields of [Item::new], so a template only
+/// needs the fields that differ, and the rendered `properties` are merged
+/// into the default properties.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use stac::ItemTemplate;
+///
+/// let template = ItemTemplate::new(json!({
+///     "id": "{scene}",
+///     "properties": {
+///         "datetime": "{datetime}",
+///         "eo:cloud_cover": "{cloud_cover}"
+///     },
+///     "assets": {
+///         "data": {"href": "s3://bucket/{scene}/data.tif"}
+///     }
+/// }));
+/// let item = template
+///     .render([
+///         ("scene", json!("LC08_001")),
+///         ("datetime", json!("2023-01-01T00:00:00Z")),
+///         ("cloud_cover", json!(12.5)),
+///     ])
+///     .unwrap();
+/// assert_eq!(item.id, "LC08_001");
+/// assert_eq!(item.properties.additional_fields["eo:cloud_cover"], 12.5);
+/// assert_eq!(item.assets["data"].href, "s3://bucket/LC08_001/data.tif");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemTemplate {
+    value: Value,
+}
+
+impl ItemTemplate {
+    /// Creates a new template from JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::ItemTemplate;
+    /// let template = ItemTemplate::new(json!({"id": "{id}"}));
+    /// ```
+    pub fn new(value: Value) -> ItemTemplate {
+        ItemTemplate { value }
+    }
+
+    /// Reads a template from a JSON file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::ItemTemplate;
+    /// let template = ItemTemplate::from_path("templates/landsat.json").unwrap();
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ItemTemplate> {
+        let reader = BufReader::new(File::open(path)?);
+        let value = serde_json::from_reader(reader)?;
+        Ok(ItemTemplate::new(value))
+    }
+
+    /// Returns the names of all of the placeholders in this template.
+    ///
+    /// Returns an error if a string in the template isn't a valid template,
+    /// e.g. it has an unclosed `{`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::ItemTemplate;
+    /// let template = ItemTemplate::new(json!({"id": "{scene}-{band}", "collection": "{scene}"}));
+    /// let placeholders: Vec<_> = template.placeholders().unwrap().into_iter().collect();
+    /// assert_eq!(placeholders, vec!["band", "scene"]);
+    /// ```
+    pub fn placeholders(&self) -> Result<BTreeSet<String>> {
+        let mut placeholders = BTreeSet::new();
+        let mut add = |s: &str| -> Result<()> {
+            for part in parse(s)? {
+                if let Part::Placeholder(name) = part {
+                    let _ = placeholders.insert(name.to_string());
+                }
+            }
+            Ok(())
+        };
+        visit_strings(&self.value, &mut add)?;
+        Ok(placeholders)
+    }
+
+    /// Renders this template into an [Item].
+    ///
+    /// Returns an error if a placeholder has no variable, or if the rendered
+    /// JSON isn't a valid item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::ItemTemplate;
+    /// let template = ItemTemplate::new(json!({"id": "{scene}"}));
+    /// let item = template.render([("scene", "a-scene")]).unwrap();
+    /// assert_eq!(item.id, "a-scene");
+    /// assert!(template.render([("other", "value")]).is_err());
+    /// ```
+    pub fn render<K, V>(&self, vars: impl IntoIterator<Item = (K, V)>) -> Result<Item>
+    where
+        K: ToString,
+        V: Into<Value>,
+    {
+        let vars: HashMap<String, Value> = vars
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.into()))
+            .collect();
+        let rendered = render_value(&self.value, &vars)?;
+        let Value::Object(rendered) = rendered else {
+            return Err(Error::InvalidTemplate(
+                "the template is not a JSON object".to_string(),
+            ));
+        };
+        let id = rendered
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let Value::Object(mut item) = serde_json::to_value(Item::new(id))? else {
+            unreachable!("items serialize to objects");
+        };
+        for (key, value) in rendered {
+            match (item.get_mut(&key), value) {
+                (Some(Value::Object(properties)), Value::Object(value)) if key == "properties" => {
+                    properties.extend(value)
+                }
+                (_, value) => {
+                    let _ = item.insert(key, value);
+                }
+            }
+        }
+        serde_json::from_value(Value::Object(item)).map_err(Error::from)
+    }
+}
+
+impl FromStr for ItemTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ItemTemplate> {
+        serde_json::from_str(s)
+            .map(ItemTemplate::new)
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Literal(String),
+    Placeholder(&'a str),
+}
+
+/// Splits a template string into literals and placeholders.
+fn parse(s: &str) -> Result<Vec<Part<'_>>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut rest = s;
+    while let Some(i) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..i]);
+        let brace = rest.as_bytes()[i];
+        rest = &rest[i + 1..];
+        if rest.as_bytes().first() == Some(&brace) {
+            literal.push(brace as char);
+            rest = &rest[1..];
+        } else if brace == b'}' {
+            return Err(Error::InvalidTemplate(format!("unmatched '}}' in {:?}", s)));
+        } else {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| Error::InvalidTemplate(format!("unclosed '{{' in {:?}", s)))?;
+            let name = rest[..end].trim();
+            if name.is_empty() || name.contains('{') {
+                return Err(Error::InvalidTemplate(format!(
+                    "invalid placeholder in {:?}",
+                    s
+                )));
+            }
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(Part::Placeholder(name));
+            rest = &rest[end + 1..];
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    Ok(parts)
+}
+
+fn render_value(value: &Value, vars: &HashMap<String, Value>) -> Result<Value> {
+    match value {
+        Value::String(s) => {
+            let parts = parse(s)?;
+            if let [Part::Placeholder(name)] = parts.as_slice() {
+                lookup(vars, name).cloned()
+            } else {
+                render_str(parts, vars).map(Value::String)
+            }
+        }
+        Value::Array(values) => values
+            .iter()
+            .map(|value| render_value(value, vars))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        Value::Object(object) => {
+            let mut rendered = Map::with_capacity(object.len());
+            for (key, value) in object {
+                let _ = rendered.insert(render_str(parse(key)?, vars)?, render_value(value, vars)?);
+            }
+            Ok(Value::Object(rendered))
+        }
+        _ => Ok(value.clone()),
+    }
+}
+
+fn render_str(parts: Vec<Part<'_>>, vars: &HashMap<String, Value>) -> Result<String> {
+    let mut rendered = String::new();
+    for part in parts {
+        match part {
+            Part::Literal(literal) => rendered.push_str(&literal),
+            Part::Placeholder(name) => match lookup(vars, name)? {
+                Value::String(s) => rendered.push_str(s),
+                value => rendered.push_str(&value.to_string()),
+            },
+        }
+    }
+    Ok(rendered)
+}
+
+fn lookup<'a>(vars: &'a HashMap<String, Value>, name: &str) -> Result<&'a Value> {
+    vars.get(name)
+        .ok_or_else(|| Error::MissingVariable(name.to_string()))
+}
+
+fn visit_strings(value: &Value, f: &mut impl FnMut(&str) -> Result<()>) -> Result<()> {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(values) => values.iter().try_for_each(|value| visit_strings(value, f)),
+        Value::Object(object) => object.iter().try_for_each(|(key, value)| {
+            f(key)?;
+            visit_strings(value, f)
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, ItemTemplate, Part};
+    use crate::Error;
+    use serde_json::json;
+
+    #[test]
+    fn parse_escapes() {
+        assert_eq!(
+            parse("{{literal}} {name}!").unwrap(),
+            vec![
+                Part::Literal("{literal} ".to_string()),
+                Part::Placeholder("name"),
+                Part::Literal("!".to_string())
+            ]
+        );
+        assert!(matches!(parse("{unclosed"), Err(Error::InvalidTemplate(_))));
+        assert!(matches!(parse("unopened}"), Err(Error::InvalidTemplate(_))));
+        assert!(matches!(parse("{}"), Err(Error::InvalidTemplate(_))));
+    }
+
+    #[test]
+    fn render_keys_and_types() {
+        let template: ItemTemplate = r#"{
+            "id": "{scene}",
+            "bbox": "{bbox}",
+            "properties": {"{prefix}:number": "{number}", "title": "Scene {scene} #{number}"},
+            "links": [{"href": "./{scene}.json", "rel": "self"}]
+        }"#
+        .parse()
+        .unwrap();
+        let item = template
+            .render([
+                ("scene", json!("a")),
+                ("bbox", json!([1., 2., 3., 4.])),
+                ("prefix", json!("ex")),
+                ("number", json!(42)),
+            ])
+            .unwrap();
+        assert_eq!(item.bbox, Some(vec![1., 2., 3., 4.]));
+        assert_eq!(item.properties.additional_fields["ex:number"], 42);
+        assert_eq!(item.properties.additional_fields["title"], "Scene a #42");
+        assert!(item.properties.datetime.is_some());
+        assert_eq!(item.links[0].href, "./a.json");
+        assert_eq!(item.version, crate::STAC_VERSION);
+    }
+
+    #[test]
+    fn missing_variable() {
+        let template = ItemTemplate::new(json!({"id": "{scene}", "collection": "{collection}"}));
+        assert!(matches!(
+            template.render([("scene", "a")]).unwrap_err(),
+            Error::MissingVariable(name) if name == "collection"
+        ));
+        assert!(matches!(
+            ItemTemplate::new(json!(["{scene}"]))
+                .render([("scene", "a")])
+                .unwrap_err(),
+            Error::InvalidTemplate(_)
+        ));
+    }
+}
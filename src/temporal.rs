@@ -0,0 +1,271 @@
+//! Temporal distribution of items.
+//!
+//! A [Histogram] counts items per year or month, and finds the years or
+//! months with no items, which helps to monitor the completeness of an
+//! archive.
+//!
+//! # Examples
+//!
+//! ```
+//! use stac::{temporal::{Histogram, Interval}, Item};
+//! let mut january = Item::new("january");
+//! january.properties.datetime = Some("2023-01-15T00:00:00Z".to_string());
+//! let mut march = Item::new("march");
+//! march.properties.datetime = Some("2023-03-15T00:00:00Z".to_string());
+//! let histogram = Histogram::from_items(Interval::Month, [january, march]).unwrap();
+//! assert_eq!(histogram.counts["2023-01"], 1);
+//! assert_eq!(histogram.gaps(), vec!["2023-02"]);
+//! ```
+
+use crate::{Collection, Handle, Item, Object, Read, Result, Stac, Walk};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use std::{borrow::Borrow, collections::BTreeMap};
+
+/// The summary key that [Histogram::apply] writes to.
+pub const SUMMARY_KEY: &str = "datetime_histogram";
+
+/// The width of a [Histogram] bin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interval {
+    /// One bin per year, e.g. `2023`.
+    Year,
+
+    /// One bin per month, e.g. `2023-01`.
+    Month,
+}
+
+/// Counts of items per year or month.
+///
+/// Items are counted by their `start_datetime`, or their `datetime` if they
+/// don't have one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// The width of each bin.
+    pub interval: Interval,
+
+    /// The number of items in each bin, keyed by year (`2023`) or month (`2023-01`).
+    ///
+    /// Empty bins are left out.
+    pub counts: BTreeMap<String, usize>,
+
+    /// The number of items without a datetime.
+    pub undated: usize,
+}
+
+impl Histogram {
+    /// Creates a new, empty histogram.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::temporal::{Histogram, Interval};
+    /// let histogram = Histogram::new(Interval::Year);
+    /// assert!(histogram.counts.is_empty());
+    /// ```
+    pub fn new(interval: Interval) -> Histogram {
+        Histogram {
+            interval,
+            counts: BTreeMap::new(),
+            undated: 0,
+        }
+    }
+
+    /// Creates a histogram from items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{temporal::{Histogram, Interval}, Item};
+    /// let histogram = Histogram::from_items(Interval::Year, [Item::new("an-id")]).unwrap();
+    /// assert_eq!(histogram.counts.values().sum::<usize>(), 1);
+    /// ```
+    pub fn from_items(
+        interval: Interval,
+        items: impl IntoIterator<Item = impl Borrow<Item>>,
+    ) -> Result<Histogram> {
+        let mut histogram = Histogram::new(interval);
+        for item in items {
+            histogram.add(item.borrow())?;
+        }
+        Ok(histogram)
+    }
+
+    /// Counts an item.
+    ///
+    /// Returns an error if the item's datetime can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{temporal::{Histogram, Interval}, Item};
+    /// let mut histogram = Histogram::new(Interval::Year);
+    /// let mut item = Item::new("an-id");
+    /// item.properties.datetime = None;
+    /// histogram.add(&item).unwrap();
+    /// assert_eq!(histogram.undated, 1);
+    /// ```
+    pub fn add(&mut self, item: &Item) -> Result<()> {
+        match item.interval()? {
+            Some((start, _)) => {
+                let bin = match self.interval {
+                    Interval::Year => Bin::Year(start.year()),
+                    Interval::Month => Bin::Month(start.year(), start.month()),
+                };
+                *self.counts.entry(bin.to_string()).or_default() += 1;
+            }
+            None => self.undated += 1,
+        }
+        Ok(())
+    }
+
+    /// Returns the empty bins between the first and last non-empty bins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::temporal::{Histogram, Interval};
+    /// let mut histogram = Histogram::new(Interval::Year);
+    /// let _ = histogram.counts.insert("2020".to_string(), 4);
+    /// let _ = histogram.counts.insert("2023".to_string(), 2);
+    /// assert_eq!(histogram.gaps(), vec!["2021", "2022"]);
+    /// ```
+    pub fn gaps(&self) -> Vec<String> {
+        let mut bins = self.counts.keys().filter_map(|key| Bin::parse(key));
+        let Some(mut bin) = bins.next() else {
+            return Vec::new();
+        };
+        let Some(last) = bins.next_back() else {
+            return Vec::new();
+        };
+        let mut gaps = Vec::new();
+        while bin < last {
+            bin = bin.next();
+            let key = bin.to_string();
+            if !self.counts.contains_key(&key) {
+                gaps.push(key);
+            }
+        }
+        gaps
+    }
+
+    /// Writes this histogram and its gaps into a collection's summaries, under [SUMMARY_KEY].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{temporal::{Histogram, Interval, SUMMARY_KEY}, Collection, Item};
+    /// let mut collection = Collection::new("an-id");
+    /// let histogram = Histogram::from_items(Interval::Year, [Item::new("an-id")]).unwrap();
+    /// histogram.apply(&mut collection).unwrap();
+    /// assert_eq!(collection.summaries.unwrap()[SUMMARY_KEY]["interval"], "year");
+    /// ```
+    pub fn apply(&self, collection: &mut Collection) -> Result<()> {
+        let mut value = serde_json::to_value(self)?;
+        value["gaps"] = self.gaps().into();
+        let _ = collection
+            .summaries
+            .get_or_insert_with(Map::new)
+            .insert(SUMMARY_KEY.to_string(), value);
+        Ok(())
+    }
+}
+
+impl<R: Read> Stac<R> {
+    /// Counts the items below `handle` per year or month.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{temporal::Interval, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let histogram = stac.temporal_histogram(root, Interval::Month).unwrap();
+    /// assert_eq!(histogram.counts.values().sum::<usize>(), 2);
+    /// ```
+    pub fn temporal_histogram(&mut self, handle: Handle, interval: Interval) -> Result<Histogram> {
+        let mut histogram = Histogram::new(interval);
+        for result in
+            self.walk(handle)
+                .items_only()
+                .visit(|stac, handle| match stac.get(handle)? {
+                    Object::Item(item) => histogram.add(item),
+                    _ => Ok(()),
+                })
+        {
+            result?;
+        }
+        Ok(histogram)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bin {
+    Year(i32),
+    Month(i32, u32),
+}
+
+impl Bin {
+    fn parse(key: &str) -> Option<Bin> {
+        match key.split_once('-') {
+            Some((year, month)) => Some(Bin::Month(year.parse().ok()?, month.parse().ok()?)),
+            None => key.parse().ok().map(Bin::Year),
+        }
+    }
+
+    fn next(self) -> Bin {
+        match self {
+            Bin::Year(year) => Bin::Year(year + 1),
+            Bin::Month(year, 12) => Bin::Month(year + 1, 1),
+            Bin::Month(year, month) => Bin::Month(year, month + 1),
+        }
+    }
+}
+
+impl std::fmt::Display for Bin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bin::Year(year) => write!(f, "{:04}", year),
+            Bin::Month(year, month) => write!(f, "{:04}-{:02}", year, month),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Histogram, Interval};
+    use crate::Item;
+    use serde_json::json;
+
+    fn item(datetime: &str) -> Item {
+        let mut item = Item::new(datetime);
+        item.properties.datetime = Some(datetime.to_string());
+        item
+    }
+
+    #[test]
+    fn month_gaps_across_years() {
+        let histogram = Histogram::from_items(
+            Interval::Month,
+            [
+                item("2022-11-01T00:00:00Z"),
+                item("2022-11-30T00:00:00Z"),
+                item("2023-02-01T00:00:00Z"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(histogram.counts["2022-11"], 2);
+        assert_eq!(histogram.gaps(), ["2022-12", "2023-01"]);
+    }
+
+    #[test]
+    fn serialize() {
+        let histogram =
+            Histogram::from_items(Interval::Year, [item("2023-06-01T00:00:00Z")]).unwrap();
+        assert_eq!(
+            serde_json::to_value(histogram).unwrap(),
+            json!({"interval": "year", "counts": {"2023": 1}, "undated": 0})
+        );
+    }
+}
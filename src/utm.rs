@@ -0,0 +1,214 @@
+//! Conversions between WGS84 longitudes and latitudes, UTM coordinates, and
+//! MGRS grid references.
+//!
+//! The formulas are the series expansions from Snyder's *Map Projections: A
+//! Working Manual* (1987), which are accurate to well under a meter within a zone.
+
+const K0: f64 = 0.9996;
+const A: f64 = 6_378_137.0;
+const F: f64 = 1.0 / 298.257_223_563;
+const FALSE_EASTING: f64 = 500_000.0;
+const FALSE_NORTHING: f64 = 10_000_000.0;
+const LATITUDE_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+const COLUMN_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const ROW_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// A 100 km MGRS grid square, e.g. `33UUP`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Mgrs {
+    /// The UTM zone number.
+    pub(crate) zone: u8,
+    /// The latitude band letter.
+    pub(crate) latitude_band: char,
+    /// The two-letter 100 km grid square.
+    pub(crate) grid_square: String,
+}
+
+/// Returns the UTM zone number and hemisphere (`true` if north) of a WGS84 or
+/// NAD83 UTM EPSG code.
+///
+/// NAD83 and WGS84 differ by about a meter, which is ignored.
+#[cfg(feature = "adapters")]
+pub(crate) fn zone(epsg: u32) -> Option<(u8, bool)> {
+    match epsg {
+        32601..=32660 => Some(((epsg - 32600) as u8, true)),
+        32701..=32760 => Some(((epsg - 32700) as u8, false)),
+        26901..=26923 => Some(((epsg - 26900) as u8, true)),
+        _ => None,
+    }
+}
+
+/// Converts UTM coordinates to a longitude and latitude, in degrees.
+#[cfg(feature = "adapters")]
+pub(crate) fn to_lon_lat(zone: u8, north: bool, easting: f64, northing: f64) -> (f64, f64) {
+    let e2 = F * (2.0 - F);
+    let ep2 = e2 / (1.0 - e2);
+    let x = easting - FALSE_EASTING;
+    let y = if north {
+        northing
+    } else {
+        northing - FALSE_NORTHING
+    };
+    let m = y / K0;
+    let mu = m / (A * (1.0 - e2 / 4.0 - 3.0 * e2.powi(2) / 64.0 - 5.0 * e2.powi(3) / 256.0));
+    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+    let phi1 = mu
+        + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+        + (21.0 * e1.powi(2) / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+        + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+        + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+    let (sin, cos, tan) = (phi1.sin(), phi1.cos(), phi1.tan());
+    let n1 = A / (1.0 - e2 * sin.powi(2)).sqrt();
+    let t1 = tan.powi(2);
+    let c1 = ep2 * cos.powi(2);
+    let r1 = A * (1.0 - e2) / (1.0 - e2 * sin.powi(2)).powf(1.5);
+    let d = x / (n1 * K0);
+    let latitude = phi1
+        - (n1 * tan / r1)
+            * (d.powi(2) / 2.0
+                - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1.powi(2) - 9.0 * ep2) * d.powi(4) / 24.0
+                + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1.powi(2)
+                    - 252.0 * ep2
+                    - 3.0 * c1.powi(2))
+                    * d.powi(6)
+                    / 720.0);
+    let longitude = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+        + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1.powi(2) + 8.0 * ep2 + 24.0 * t1.powi(2))
+            * d.powi(5)
+            / 120.0)
+        / cos;
+    (
+        central_meridian(zone) + longitude.to_degrees(),
+        latitude.to_degrees(),
+    )
+}
+
+/// Converts a longitude and latitude, in degrees, to UTM coordinates in the
+/// given zone.
+///
+/// Returns the easting and northing.
+pub(crate) fn from_lon_lat(zone: u8, longitude: f64, latitude: f64) -> (f64, f64) {
+    let e2 = F * (2.0 - F);
+    let ep2 = e2 / (1.0 - e2);
+    let phi = latitude.to_radians();
+    let (sin, cos, tan) = (phi.sin(), phi.cos(), phi.tan());
+    let n = A / (1.0 - e2 * sin.powi(2)).sqrt();
+    let t = tan.powi(2);
+    let c = ep2 * cos.powi(2);
+    let a = cos * (longitude - central_meridian(zone)).to_radians();
+    let m = A
+        * ((1.0 - e2 / 4.0 - 3.0 * e2.powi(2) / 64.0 - 5.0 * e2.powi(3) / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e2.powi(2) / 32.0 + 45.0 * e2.powi(3) / 1024.0)
+                * (2.0 * phi).sin()
+            + (15.0 * e2.powi(2) / 256.0 + 45.0 * e2.powi(3) / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e2.powi(3) / 3072.0) * (6.0 * phi).sin());
+    let easting = K0
+        * n
+        * (a + (1.0 - t + c) * a.powi(3) / 6.0
+            + (5.0 - 18.0 * t + t.powi(2) + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+        + FALSE_EASTING;
+    let northing = K0
+        * (m + n
+            * tan
+            * (a.powi(2) / 2.0
+                + (5.0 - t + 9.0 * c + 4.0 * c.powi(2)) * a.powi(4) / 24.0
+                + (61.0 - 58.0 * t + t.powi(2) + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+    if latitude < 0.0 {
+        (easting, northing + FALSE_NORTHING)
+    } else {
+        (easting, northing)
+    }
+}
+
+/// Returns the MGRS grid square that contains a longitude and latitude.
+///
+/// Returns `None` outside of the UTM latitude limits (80°S to 84°N), which
+/// MGRS covers with the polar stereographic grid instead.
+pub(crate) fn mgrs(longitude: f64, latitude: f64) -> Option<Mgrs> {
+    if !(-80.0..=84.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    let band = (((latitude + 80.0) / 8.0).floor() as usize).min(LATITUDE_BANDS.len() - 1);
+    let zone = zone_of(longitude, latitude);
+    let (easting, northing) = from_lon_lat(zone, longitude, latitude);
+    let set = usize::from(zone - 1) % 3;
+    let column = ((easting / 100_000.0).floor() as usize).clamp(1, 8) - 1;
+    let mut row = (northing / 100_000.0).floor() as usize % ROW_LETTERS.len();
+    if zone.is_multiple_of(2) {
+        row = (row + 5) % ROW_LETTERS.len();
+    }
+    Some(Mgrs {
+        zone,
+        latitude_band: char::from(LATITUDE_BANDS[band]),
+        grid_square: [COLUMN_LETTERS[set * 8 + column], ROW_LETTERS[row]]
+            .iter()
+            .map(|&b| char::from(b))
+            .collect(),
+    })
+}
+
+/// Returns the UTM zone of a longitude and latitude, including the Norway and
+/// Svalbard exceptions.
+fn zone_of(longitude: f64, latitude: f64) -> u8 {
+    if (56.0..64.0).contains(&latitude) && (3.0..12.0).contains(&longitude) {
+        return 32;
+    }
+    if (72.0..=84.0).contains(&latitude) && (0.0..42.0).contains(&longitude) {
+        return match longitude {
+            l if l < 9.0 => 31,
+            l if l < 21.0 => 33,
+            l if l < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((longitude + 180.0) / 6.0).floor() as u8 + 1).min(60)
+}
+
+fn central_meridian(zone: u8) -> f64 {
+    f64::from(zone) * 6.0 - 183.0
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "adapters")]
+    fn to_lon_lat() {
+        let (lon, lat) = super::to_lon_lat(33, true, 500_000.0, 5_538_630.703);
+        assert!((lon - 15.0).abs() < 1e-9);
+        assert!((lat - 50.0).abs() < 1e-6, "{}", lat);
+        let (lon, lat) = super::to_lon_lat(56, false, 334_368.634, 6_250_948.345);
+        assert!((lon - 151.2093).abs() < 1e-6, "{}", lon);
+        assert!((lat + 33.8688).abs() < 1e-6, "{}", lat);
+    }
+
+    #[test]
+    #[cfg(feature = "adapters")]
+    fn zone() {
+        assert_eq!(super::zone(32633), Some((33, true)));
+        assert_eq!(super::zone(32756), Some((56, false)));
+        assert_eq!(super::zone(26910), Some((10, true)));
+        assert_eq!(super::zone(4326), None);
+    }
+
+    #[test]
+    fn from_lon_lat() {
+        let (easting, northing) = super::from_lon_lat(33, 15.0, 50.0);
+        assert!((easting - 500_000.0).abs() < 1e-6);
+        assert!((northing - 5_538_630.703).abs() < 1e-3, "{}", northing);
+        let (easting, northing) = super::from_lon_lat(56, 151.2093, -33.8688);
+        assert!((easting - 334_368.634).abs() < 1e-3, "{}", easting);
+        assert!((northing - 6_250_948.345).abs() < 1e-3, "{}", northing);
+    }
+
+    #[test]
+    fn mgrs() {
+        let mgrs = |longitude, latitude| {
+            super::mgrs(longitude, latitude)
+                .map(|m| format!("{}{}{}", m.zone, m.latitude_band, m.grid_square))
+        };
+        assert_eq!(mgrs(151.2093, -33.8688).unwrap(), "56HLH");
+        assert_eq!(mgrs(5.3221, 60.3913).unwrap(), "32VKN");
+        assert_eq!(mgrs(15.6356, 78.2232).unwrap(), "33XWG");
+        assert!(mgrs(0.0, 85.0).is_none());
+    }
+}
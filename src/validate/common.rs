@@ -0,0 +1,77 @@
+//! Checks that apply to every kind of object.
+
+use super::Issue;
+use crate::{extensions, Collection, Version};
+
+/// Checks an object's version (core), its extension URIs, and whether its id
+/// is safe to use in an href (lint).
+pub(super) fn validate(
+    id: &str,
+    version: &str,
+    stac_extensions: Option<&[String]>,
+    issues: &mut Vec<Issue>,
+) {
+    if version.parse::<Version>().is_err() {
+        issues.push(Issue::error(
+            "version-unsupported",
+            "/stac_version",
+            format!("unsupported STAC version {:?}", version),
+        ));
+    }
+    for (i, uri) in stac_extensions.unwrap_or_default().iter().enumerate() {
+        let pointer = format!("/stac_extensions/{}", i);
+        if let Some(resolved) = extensions::resolve(uri) {
+            issues.push(Issue::warning(
+                "extension-shorthand",
+                pointer,
+                format!("{:?} is a shorthand, use {:?}", uri, resolved),
+            ));
+        } else if let Some(upgraded) = extensions::upgrade(uri) {
+            issues.push(Issue::warning(
+                "extension-outdated",
+                pointer,
+                format!("{:?} is outdated, use {:?}", uri, upgraded),
+            ));
+        }
+    }
+    if let Err(err) = crate::id::validate(id) {
+        issues.push(Issue::warning("id-unsafe", "/id", err));
+    }
+}
+
+/// Checks the shape of a collection's extents.
+pub(super) fn validate_collection(collection: &Collection, issues: &mut Vec<Issue>) {
+    for (i, bbox) in collection.extent.spatial.bbox.iter().enumerate() {
+        if bbox.len() != 4 && bbox.len() != 6 {
+            issues.push(Issue::error(
+                "extent-bbox-length",
+                format!("/extent/spatial/bbox/{}", i),
+                format!("bbox has {} values, expected 4 or 6", bbox.len()),
+            ));
+        }
+    }
+    for (i, interval) in collection.extent.temporal.interval.iter().enumerate() {
+        let mut datetimes = [None, None];
+        for (j, datetime) in interval.iter().enumerate() {
+            if let Some(datetime) = datetime {
+                match chrono::DateTime::parse_from_rfc3339(datetime) {
+                    Ok(datetime) => datetimes[j] = Some(datetime),
+                    Err(err) => issues.push(Issue::error(
+                        "extent-interval-datetime",
+                        format!("/extent/temporal/interval/{}/{}", i, j),
+                        format!("invalid datetime {:?}: {}", datetime, err),
+                    )),
+                }
+            }
+        }
+        if let [Some(start), Some(end)] = datetimes {
+            if start > end {
+                issues.push(Issue::error(
+                    "extent-interval-order",
+                    format!("/extent/temporal/interval/{}", i),
+                    format!("interval starts at {} after it ends at {}", start, end),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Checks an item's `datetime`, `start_datetime`, and `end_datetime`.
+
+use super::Issue;
+use crate::{datetime, Item};
+use chrono::{DateTime, Utc};
+
+/// Checks that an item has a `datetime`, or both a `start_datetime` and an
+/// `end_datetime` if its `datetime` is null, and that they all parse.
+pub(super) fn validate(item: &Item, issues: &mut Vec<Issue>) {
+    let field = |key: &str| {
+        item.properties
+            .additional_fields
+            .get(key)
+            .filter(|value| !value.is_null())
+    };
+    if let Some(value) = item.properties.datetime.as_deref() {
+        let _ = parse("datetime", value, issues);
+    }
+    let mut interval = [None, None];
+    for (bound, key) in interval.iter_mut().zip(["start_datetime", "end_datetime"]) {
+        match field(key).map(|value| value.as_str()) {
+            Some(Some(value)) => *bound = Some(parse(key, value, issues)),
+            Some(None) => {
+                *bound = Some(None);
+                issues.push(Issue::error(
+                    "datetime-invalid",
+                    format!("/properties/{}", key),
+                    format!("{} must be a string", key),
+                ));
+            }
+            None => {}
+        }
+    }
+    match interval {
+        [Some(Some(start)), Some(Some(end))] if start > end => issues.push(Issue::error(
+            "datetime-interval-reversed",
+            "/properties/start_datetime",
+            format!("start_datetime {} is after end_datetime {}", start, end),
+        )),
+        [Some(_), Some(_)] => {}
+        [start, end] => {
+            if item.properties.datetime.is_none() {
+                issues.push(Issue::error(
+                    "datetime-missing",
+                    "/properties/datetime",
+                    "datetime is null, so start_datetime and end_datetime are required",
+                ));
+            } else if start.is_some() || end.is_some() {
+                let (key, missing) = if start.is_some() {
+                    ("start_datetime", "end_datetime")
+                } else {
+                    ("end_datetime", "start_datetime")
+                };
+                issues.push(Issue::warning(
+                    "datetime-interval-incomplete",
+                    format!("/properties/{}", key),
+                    format!("{} is set without {}", key, missing),
+                ));
+            }
+        }
+    }
+}
+
+fn parse(key: &str, value: &str, issues: &mut Vec<Issue>) -> Option<DateTime<Utc>> {
+    match datetime::parse(value) {
+        Ok(datetime) => Some(datetime),
+        Err(err) => {
+            issues.push(Issue::error(
+                "datetime-invalid",
+                format!("/properties/{}", key),
+                format!("{:?} is not an RFC 3339 datetime: {}", value, err),
+            ));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{validate::Validate, Item};
+    use chrono::{TimeZone, Utc};
+
+    fn rules(item: &Item) -> Vec<(String, String)> {
+        item.validate()
+            .into_iter()
+            .map(|issue| (issue.rule, issue.pointer))
+            .collect()
+    }
+
+    #[test]
+    fn interval() {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+        assert!(rules(&Item::new("an-id").with_interval(start, end)).is_empty());
+        assert_eq!(
+            rules(&Item::new("an-id").with_interval(end, start)),
+            [(
+                "datetime-interval-reversed".to_string(),
+                "/properties/start_datetime".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn missing() {
+        let mut item = Item::new("an-id");
+        item.properties.datetime = None;
+        assert_eq!(
+            rules(&item),
+            [(
+                "datetime-missing".to_string(),
+                "/properties/datetime".to_string()
+            )]
+        );
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("start_datetime".to_string(), "2023-01-01T00:00:00Z".into());
+        assert_eq!(
+            rules(&item),
+            [(
+                "datetime-missing".to_string(),
+                "/properties/datetime".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let mut item = Item::new("an-id");
+        item.properties.datetime = Some("yesterday".to_string());
+        let _ = item
+            .properties
+            .additional_fields
+            .insert("end_datetime".to_string(), "2023-01-01T00:00:00Z".into());
+        assert_eq!(
+            rules(&item),
+            [
+                (
+                    "datetime-invalid".to_string(),
+                    "/properties/datetime".to_string()
+                ),
+                (
+                    "datetime-interval-incomplete".to_string(),
+                    "/properties/end_datetime".to_string()
+                )
+            ]
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use super::{Issue, ValidationReport, Validator};
+use crate::{datetime, geometry, stac::Handle, Read, Result, Stac, Walk};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+
+/// An item whose bbox or datetimes fall outside of its collection's extent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ExtentViolation {
+    collection: Handle,
+    item: Handle,
+    /// How far, in degrees, the item's bbox extends beyond the collection's spatial extent.
+    spatial: f64,
+    /// How far the item's datetimes fall outside of the collection's temporal extent.
+    temporal: Duration,
+}
+
+impl Validator {
+    /// Checks that every item below `handle` falls within the extent of its
+    /// nearest [Collection](crate::Collection) ancestor.
+    ///
+    /// An item's bbox (or, if it has none, its geometry's bbox) is compared to
+    /// the collection's first spatial bbox, and its `datetime` (or
+    /// `start_datetime` and `end_datetime`) is compared to the collection's
+    /// first temporal interval. Items without a collection ancestor are
+    /// skipped.
+    ///
+    /// The report lists the worst offenders first, i.e. ordered by how far
+    /// the items fall outside of the spatial extent and then by how far they
+    /// fall outside of the temporal extent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let report = Validator::default().check_extents(&mut stac, root).unwrap();
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn check_extents<R: Read>(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+    ) -> Result<ValidationReport> {
+        let violations = self.extent_violations(stac, handle)?;
+        extent_report(stac, &violations)
+    }
+
+    /// Expands collection extents so they cover all of their descendant items.
+    ///
+    /// Returns a report of the issues that were fixed, as found by
+    /// [check_extents](Validator::check_extents).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let validator = Validator::default();
+    /// let _ = validator.fix_extents(&mut stac, root).unwrap();
+    /// assert!(validator.check_extents(&mut stac, root).unwrap().entries.is_empty());
+    /// ```
+    pub fn fix_extents<R: Read>(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+    ) -> Result<ValidationReport> {
+        let violations = self.extent_violations(stac, handle)?;
+        let report = extent_report(stac, &violations)?;
+        for violation in &violations {
+            let item = stac
+                .get(violation.item)?
+                .as_item()
+                .expect("violations are items");
+            let bbox = item.horizontal_bbox();
+            let interval = item.interval()?;
+            let collection = stac
+                .get_mut(violation.collection)?
+                .as_mut_collection()
+                .expect("violations have collections");
+            if let (Some(bbox), Some(extent)) = (bbox, collection.extent.spatial.bbox.first_mut()) {
+                expand_bbox(extent, bbox);
+            }
+            if let (Some((start, end)), Some(extent)) =
+                (interval, collection.extent.temporal.interval.first_mut())
+            {
+                expand_interval(extent, start, end)?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Returns the items that fall outside of their collection's extent, worst first.
+    fn extent_violations<R: Read>(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+    ) -> Result<Vec<ExtentViolation>> {
+        let mut violations = Vec::new();
+        for result in stac.walk(handle).items_only().visit(check_extent) {
+            if let Some(violation) = result? {
+                violations.push(violation);
+            }
+        }
+        violations.sort_by(|a, b| {
+            b.spatial
+                .total_cmp(&a.spatial)
+                .then_with(|| b.temporal.cmp(&a.temporal))
+        });
+        Ok(violations)
+    }
+}
+
+fn extent_report<R: Read>(
+    stac: &mut Stac<R>,
+    violations: &[ExtentViolation],
+) -> Result<ValidationReport> {
+    let mut report = ValidationReport::new();
+    for violation in violations {
+        let collection_id = stac.get(violation.collection)?.id().to_string();
+        let mut issues = Vec::new();
+        if violation.spatial > 0.0 {
+            issues.push(Issue::error(
+                "extent-spatial",
+                "/bbox",
+                format!(
+                    "bbox extends {} degrees beyond the spatial extent of collection {:?}",
+                    violation.spatial, collection_id
+                ),
+            ));
+        }
+        if violation.temporal > Duration::zero() {
+            issues.push(Issue::error(
+                "extent-temporal",
+                "/properties",
+                format!(
+                    "datetimes fall {} seconds outside of the temporal extent of collection {:?}",
+                    violation.temporal.num_seconds(),
+                    collection_id
+                ),
+            ));
+        }
+        let href = stac.href(violation.item).cloned();
+        report.add(stac.get(violation.item)?, href.as_ref(), issues);
+    }
+    Ok(report)
+}
+
+fn check_extent<R: Read>(stac: &mut Stac<R>, handle: Handle) -> Result<Option<ExtentViolation>> {
+    let item = if let Some(item) = stac.get(handle)?.as_item() {
+        item
+    } else {
+        return Ok(None);
+    };
+    let bbox = item.horizontal_bbox();
+    let interval = item.interval()?;
+    let collection = if let Some(collection) = nearest_collection(stac, handle)? {
+        collection
+    } else {
+        return Ok(None);
+    };
+    let extent = &stac
+        .get(collection)?
+        .as_collection()
+        .expect("should be a collection")
+        .extent;
+    let spatial = match (
+        bbox,
+        extent
+            .spatial
+            .bbox
+            .first()
+            .and_then(|b| geometry::horizontal(b)),
+    ) {
+        (Some(bbox), Some(extent)) => spatial_excess(extent, bbox),
+        _ => 0.0,
+    };
+    let temporal = match (interval, extent.temporal.interval.first()) {
+        (Some((start, end)), Some(extent)) => temporal_excess(extent, start, end)?,
+        _ => Duration::zero(),
+    };
+    if spatial > 0.0 || temporal > Duration::zero() {
+        Ok(Some(ExtentViolation {
+            collection,
+            item: handle,
+            spatial,
+            temporal,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+fn nearest_collection<R: Read>(stac: &mut Stac<R>, handle: Handle) -> Result<Option<Handle>> {
+    let mut parent = stac.parent(handle);
+    while let Some(handle) = parent {
+        if stac.get(handle)?.is_collection() {
+            return Ok(Some(handle));
+        }
+        parent = stac.parent(handle);
+    }
+    Ok(None)
+}
+
+fn spatial_excess(extent: [f64; 4], bbox: [f64; 4]) -> f64 {
+    if extent[0] > extent[2] {
+        // Extents that cross the antimeridian aren't checked.
+        return 0.0;
+    }
+    [
+        extent[0] - bbox[0],
+        extent[1] - bbox[1],
+        bbox[2] - extent[2],
+        bbox[3] - extent[3],
+    ]
+    .into_iter()
+    .fold(0.0, f64::max)
+}
+
+fn temporal_excess(
+    extent: &[Option<String>; 2],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Duration> {
+    let mut excess = Duration::zero();
+    if let Some(extent_start) = extent[0].as_deref() {
+        excess = excess.max(datetime::parse(extent_start)? - start);
+    }
+    if let Some(extent_end) = extent[1].as_deref() {
+        excess = excess.max(end - datetime::parse(extent_end)?);
+    }
+    Ok(excess)
+}
+
+fn expand_bbox(extent: &mut [f64], bbox: [f64; 4]) {
+    let (east, north) = match extent.len() {
+        4 => (2, 3),
+        6 => (3, 4),
+        _ => return,
+    };
+    if extent[0] > extent[east] {
+        return;
+    }
+    extent[0] = extent[0].min(bbox[0]);
+    extent[1] = extent[1].min(bbox[1]);
+    extent[east] = extent[east].max(bbox[2]);
+    extent[north] = extent[north].max(bbox[3]);
+}
+
+fn expand_interval(
+    extent: &mut [Option<String>; 2],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<()> {
+    if let Some(extent_start) = extent[0].as_mut() {
+        if datetime::parse(extent_start)? > start {
+            *extent_start = start.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        }
+    }
+    if let Some(extent_end) = extent[1].as_mut() {
+        if datetime::parse(extent_end)? < end {
+            *extent_end = end.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{validate::Validator, Catalog, Collection, Item, Stac};
+    use chrono::Duration;
+
+    fn item(id: &str, bbox: [f64; 4], datetime: &str) -> Item {
+        let mut item = Item::new(id);
+        item.bbox = Some(bbox.to_vec());
+        item.properties.datetime = Some(datetime.to_string());
+        item
+    }
+
+    #[test]
+    fn worst_offenders_first() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut collection = Collection::new("collection");
+        collection.extent.spatial.bbox = vec![vec![0.0, 0.0, 1.0, 1.0]];
+        collection.extent.temporal.interval = vec![[
+            Some("2022-01-01T00:00:00Z".to_string()),
+            Some("2022-12-31T00:00:00Z".to_string()),
+        ]];
+        let collection = stac.add_child(root, collection).unwrap();
+        let catalog = stac.add_child(collection, Catalog::new("nested")).unwrap();
+        let inside = item("inside", [0.1, 0.1, 0.2, 0.2], "2022-06-01T00:00:00Z");
+        let _ = stac.add_child(collection, inside).unwrap();
+        let late = item("late", [0.1, 0.1, 0.2, 0.2], "2023-01-01T00:00:00Z");
+        let late = stac.add_child(collection, late).unwrap();
+        let far = item("far", [0.5, 0.5, 3.0, 1.0], "2022-06-01T00:00:00Z");
+        let far = stac.add_child(catalog, far).unwrap();
+
+        let validator = Validator::default();
+        let violations = validator.extent_violations(&mut stac, root).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].item, far);
+        assert_eq!(violations[0].collection, collection);
+        assert_eq!(violations[0].spatial, 2.0);
+        assert_eq!(violations[1].item, late);
+        assert_eq!(violations[1].temporal, Duration::days(1));
+
+        let report = validator.check_extents(&mut stac, root).unwrap();
+        let rules: Vec<_> = report
+            .entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry.issue.rule.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            [("far", "extent-spatial"), ("late", "extent-temporal")]
+        );
+        let fixed = validator.fix_extents(&mut stac, root).unwrap();
+        assert_eq!(fixed, report);
+        assert!(validator
+            .check_extents(&mut stac, root)
+            .unwrap()
+            .entries
+            .is_empty());
+        let extent = &stac
+            .get(collection)
+            .unwrap()
+            .as_collection()
+            .unwrap()
+            .extent;
+        assert_eq!(extent.spatial.bbox[0], [0.0, 0.0, 3.0, 1.0]);
+        assert_eq!(
+            extent.temporal.interval[0][1].as_deref().unwrap(),
+            "2023-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn no_collection() {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let _ = stac
+            .add_child(
+                root,
+                item("an-item", [0.0, 0.0, 200.0, 1.0], "2022-06-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert!(Validator::default()
+            .check_extents(&mut stac, root)
+            .unwrap()
+            .entries
+            .is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use super::Issue;
+use geojson::{Geometry, PolygonType, Position, Value};
+
+pub(super) fn validate(geometry: &Geometry, pointer: &str, issues: &mut Vec<Issue>) {
+    let coordinates = format!("{}/coordinates", pointer);
+    match &geometry.value {
+        Value::Point(position) => validate_position(position, &coordinates, issues),
+        Value::MultiPoint(positions) => validate_positions(positions, &coordinates, issues),
+        Value::LineString(positions) => validate_line_string(positions, &coordinates, issues),
+        Value::MultiLineString(lines) => {
+            for (i, line) in lines.iter().enumerate() {
+                validate_line_string(line, &format!("{}/{}", coordinates, i), issues);
+            }
+        }
+        Value::Polygon(polygon) => validate_polygon(polygon, &coordinates, issues),
+        Value::MultiPolygon(polygons) => {
+            for (i, polygon) in polygons.iter().enumerate() {
+                validate_polygon(polygon, &format!("{}/{}", coordinates, i), issues);
+            }
+        }
+        Value::GeometryCollection(geometries) => {
+            for (i, geometry) in geometries.iter().enumerate() {
+                validate(geometry, &format!("{}/geometries/{}", pointer, i), issues);
+            }
+        }
+    }
+}
+
+fn validate_position(position: &Position, pointer: &str, issues: &mut Vec<Issue>) {
+    if position.len() < 2 {
+        issues.push(Issue::error(
+            "geometry-position",
+            pointer,
+            format!(
+                "position has {} coordinates, expected at least 2",
+                position.len()
+            ),
+        ));
+        return;
+    }
+    if !(-180.0..=180.0).contains(&position[0]) {
+        issues.push(Issue::error(
+            "geometry-coordinate-range",
+            format!("{}/0", pointer),
+            format!("longitude {} is outside [-180, 180]", position[0]),
+        ));
+    }
+    if !(-90.0..=90.0).contains(&position[1]) {
+        issues.push(Issue::error(
+            "geometry-coordinate-range",
+            format!("{}/1", pointer),
+            format!("latitude {} is outside [-90, 90]", position[1]),
+        ));
+    }
+}
+
+fn validate_positions(positions: &[Position], pointer: &str, issues: &mut Vec<Issue>) {
+    for (i, position) in positions.iter().enumerate() {
+        validate_position(position, &format!("{}/{}", pointer, i), issues);
+    }
+}
+
+fn validate_line_string(positions: &[Position], pointer: &str, issues: &mut Vec<Issue>) {
+    if positions.len() < 2 {
+        issues.push(Issue::error(
+            "geometry-line-too-short",
+            pointer,
+            format!(
+                "line string has {} positions, expected at least 2",
+                positions.len()
+            ),
+        ));
+    }
+    validate_positions(positions, pointer, issues);
+}
+
+fn validate_polygon(polygon: &PolygonType, pointer: &str, issues: &mut Vec<Issue>) {
+    for (i, ring) in polygon.iter().enumerate() {
+        let pointer = format!("{}/{}", pointer, i);
+        let len = issues.len();
+        validate_positions(ring, &pointer, issues);
+        if issues.len() > len {
+            // The remaining checks need valid two-dimensional positions.
+            continue;
+        }
+        if ring.len() < 4 {
+            issues.push(Issue::error(
+                "geometry-ring-too-short",
+                &pointer,
+                format!("ring has {} positions, expected at least 4", ring.len()),
+            ));
+            continue;
+        }
+        if ring[0][..2] != ring[ring.len() - 1][..2] {
+            issues.push(Issue::error(
+                "geometry-unclosed-ring",
+                format!("{}/{}", pointer, ring.len() - 1),
+                "the last position of the ring is not the same as the first",
+            ));
+            continue;
+        }
+        let area = signed_area(ring);
+        if i == 0 && area < 0.0 {
+            issues.push(Issue::warning(
+                "geometry-winding",
+                &pointer,
+                "exterior ring is clockwise, expected counterclockwise",
+            ));
+        } else if i > 0 && area > 0.0 {
+            issues.push(Issue::warning(
+                "geometry-winding",
+                &pointer,
+                "interior ring is counterclockwise, expected clockwise",
+            ));
+        }
+        if let Some((a, b)) = self_intersection(ring) {
+            issues.push(Issue::error(
+                "geometry-self-intersection",
+                format!("{}/{}", pointer, a),
+                format!("ring segment {} intersects segment {}", a, b),
+            ));
+        }
+    }
+}
+
+/// Returns twice the signed area of a closed ring, positive if the ring is counterclockwise.
+fn signed_area(ring: &[Position]) -> f64 {
+    ring.windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum()
+}
+
+/// Returns the indices of the first pair of non-adjacent segments of a closed ring that intersect.
+///
+/// Zero-length segments, from repeated positions, are skipped, so the
+/// segments on either side of one are still adjacent.
+fn self_intersection(ring: &[Position]) -> Option<(usize, usize)> {
+    let segments: Vec<usize> = (0..ring.len() - 1)
+        .filter(|&i| ring[i][..2] != ring[i + 1][..2])
+        .collect();
+    for (k, &i) in segments.iter().enumerate() {
+        for (l, &j) in segments.iter().enumerate().skip(k + 2) {
+            if k == 0 && l == segments.len() - 1 {
+                // The first and last segments share the closing position.
+                continue;
+            }
+            if intersects(&ring[i], &ring[i + 1], &ring[j], &ring[j + 1]) {
+                return Some((i, j));
+            }
+        }
+    }
+    None
+}
+
+fn intersects(p1: &[f64], p2: &[f64], q1: &[f64], q2: &[f64]) -> bool {
+    let o1 = orientation(p1, p2, q1);
+    let o2 = orientation(p1, p2, q2);
+    let o3 = orientation(q1, q2, p1);
+    let o4 = orientation(q1, q2, p2);
+    if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+        return true;
+    }
+    (o1 == 0.0 && on_segment(p1, p2, q1))
+        || (o2 == 0.0 && on_segment(p1, p2, q2))
+        || (o3 == 0.0 && on_segment(q1, q2, p1))
+        || (o4 == 0.0 && on_segment(q1, q2, p2))
+}
+
+fn orientation(a: &[f64], b: &[f64], c: &[f64]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Returns true if `c`, which is collinear with `a` and `b`, lies between them.
+fn on_segment(a: &[f64], b: &[f64], c: &[f64]) -> bool {
+    c[0] >= a[0].min(b[0])
+        && c[0] <= a[0].max(b[0])
+        && c[1] >= a[1].min(b[1])
+        && c[1] <= a[1].max(b[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validate::Validate;
+    use geojson::{Geometry, Value};
+
+    fn polygon(ring: Vec<[f64; 2]>) -> Geometry {
+        Geometry::new(Value::Polygon(vec![ring
+            .into_iter()
+            .map(|position| position.to_vec())
+            .collect()]))
+    }
+
+    fn rules(geometry: &Geometry) -> Vec<(String, String)> {
+        geometry
+            .validate()
+            .into_iter()
+            .map(|issue| (issue.rule, issue.pointer))
+            .collect()
+    }
+
+    #[test]
+    fn valid_polygon() {
+        let geometry = polygon(vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.], [0., 0.]]);
+        assert!(geometry.validate().is_empty());
+    }
+
+    #[test]
+    fn unclosed_ring() {
+        let geometry = polygon(vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+        assert_eq!(
+            rules(&geometry),
+            [(
+                "geometry-unclosed-ring".to_string(),
+                "/coordinates/0/3".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn clockwise_exterior() {
+        let geometry = polygon(vec![[0., 0.], [0., 1.], [1., 1.], [1., 0.], [0., 0.]]);
+        assert_eq!(
+            rules(&geometry),
+            [("geometry-winding".to_string(), "/coordinates/0".to_string())]
+        );
+    }
+
+    #[test]
+    fn bowtie() {
+        let geometry = polygon(vec![[0., 0.], [1., 1.], [1., 0.], [0., 1.], [0., 0.]]);
+        assert_eq!(
+            rules(&geometry),
+            [(
+                "geometry-self-intersection".to_string(),
+                "/coordinates/0/0".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn repeated_position() {
+        let geometry = polygon(vec![
+            [0., 0.],
+            [1., 0.],
+            [1., 0.],
+            [1., 1.],
+            [0., 1.],
+            [0., 0.],
+        ]);
+        assert!(geometry.validate().is_empty());
+        let geometry = polygon(vec![
+            [0., 0.],
+            [0., 0.],
+            [1., 1.],
+            [1., 0.],
+            [0., 1.],
+            [0., 0.],
+        ]);
+        assert_eq!(
+            rules(&geometry),
+            [(
+                "geometry-self-intersection".to_string(),
+                "/coordinates/0/1".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let geometry = Geometry::new(Value::MultiPoint(vec![vec![0., 0.], vec![190., -91.]]));
+        assert_eq!(
+            rules(&geometry),
+            [
+                (
+                    "geometry-coordinate-range".to_string(),
+                    "/coordinates/1/0".to_string()
+                ),
+                (
+                    "geometry-coordinate-range".to_string(),
+                    "/coordinates/1/1".to_string()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn geometry_collection() {
+        let geometry = Geometry::new(Value::GeometryCollection(vec![
+            Geometry::new(Value::Point(vec![0., 0.])),
+            Geometry::new(Value::Point(vec![0., 100.])),
+        ]));
+        assert_eq!(
+            rules(&geometry),
+            [(
+                "geometry-coordinate-range".to_string(),
+                "/geometries/1/coordinates/1".to_string()
+            )]
+        );
+    }
+}
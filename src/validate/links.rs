@@ -0,0 +1,257 @@
+//! Checks for link rels, media types, and the objects that structural links point to.
+
+use super::Issue;
+use crate::{stac::Handle, Asset, Link, Read, Result, Stac};
+use std::collections::HashMap;
+use url::Url;
+
+/// Link relation types that are registered with
+/// [IANA](https://www.iana.org/assignments/link-relations/link-relations.xhtml)
+/// or defined by the STAC and OGC API specifications.
+const KNOWN_RELS: &[&str] = &[
+    "about",
+    "aggregate",
+    "aggregations",
+    "alternate",
+    "author",
+    "canonical",
+    "child",
+    "cite-as",
+    "collection",
+    "conformance",
+    "copyright",
+    "data",
+    "derived_from",
+    "describedby",
+    "duplicate",
+    "edit",
+    "enclosure",
+    "first",
+    "help",
+    "icon",
+    "index",
+    "item",
+    "items",
+    "last",
+    "latest-version",
+    "license",
+    "next",
+    "parent",
+    "predecessor-version",
+    "prev",
+    "preview",
+    "queryables",
+    "related",
+    "replies",
+    "root",
+    "search",
+    "self",
+    "service-desc",
+    "service-doc",
+    "service-meta",
+    "start",
+    "successor-version",
+    "type",
+    "up",
+    "version-history",
+    "via",
+    "working-copy",
+    "working-copy-of",
+];
+
+/// Checks that link rels are known (or are extension rels, i.e. URIs) and
+/// that link and asset media types look like media types.
+pub(super) fn validate(
+    links: &[Link],
+    assets: Option<&HashMap<String, Asset>>,
+    issues: &mut Vec<Issue>,
+) {
+    for (i, link) in links.iter().enumerate() {
+        let rel = link.rel.to_ascii_lowercase();
+        if !KNOWN_RELS.contains(&rel.as_str()) && Url::parse(&rel).is_err() {
+            issues.push(Issue::warning(
+                "link-rel-unknown",
+                format!("/links/{}/rel", i),
+                format!(
+                    "{:?} is not a registered rel, extension rels must be URIs",
+                    link.rel.as_str()
+                ),
+            ));
+        }
+        if let Some(media_type) = link.r#type.as_deref() {
+            validate_media_type(media_type, format!("/links/{}/type", i), issues);
+        }
+    }
+    for (key, asset) in assets.into_iter().flatten() {
+        if let Some(media_type) = asset.r#type.as_deref() {
+            let pointer = format!("/assets/{}/type", escape(key));
+            validate_media_type(media_type, pointer, issues);
+        }
+    }
+}
+
+/// Checks that the `child`, `item`, `parent`, and `root` links of an object
+/// point to the right kinds of objects.
+///
+/// Only targets that are in the [Stac] are checked, and they are resolved if needed.
+pub(super) fn validate_structural<R: Read>(
+    stac: &mut Stac<R>,
+    handle: Handle,
+) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let base = stac.href(handle).cloned();
+    let links: Vec<_> = stac
+        .get(handle)?
+        .links()
+        .iter()
+        .enumerate()
+        .filter(|(_, link)| link.is_child() || link.is_item() || link.is_parent() || link.is_root())
+        .map(|(i, link)| (i, link.clone()))
+        .collect();
+    for (i, link) in links {
+        let target = if link.is_root() {
+            Some(stac.root())
+        } else if link.is_parent() {
+            stac.parent(handle)
+        } else if let Some(base) = base.as_ref() {
+            let href = base.join(&link.href)?;
+            stac.children(handle)
+                .into_iter()
+                .find(|&child| stac.href(child) == Some(&href))
+        } else {
+            None
+        };
+        let target = if let Some(target) = target {
+            stac.get(target)?
+        } else {
+            continue;
+        };
+        let expected = if link.is_item() {
+            target.is_item()
+        } else {
+            !target.is_item()
+        };
+        if !expected {
+            issues.push(Issue::error(
+                "link-target-type",
+                format!("/links/{}", i),
+                format!(
+                    "{:?} link points to a {}",
+                    link.rel.as_str(),
+                    target.r#type()
+                ),
+            ));
+        }
+    }
+    Ok(issues)
+}
+
+fn validate_media_type(media_type: &str, pointer: String, issues: &mut Vec<Issue>) {
+    if !is_media_type(media_type) {
+        issues.push(Issue::error(
+            "media-type-invalid",
+            pointer,
+            format!("{:?} is not a valid media type", media_type),
+        ));
+    }
+}
+
+/// Returns true if the string looks like a [RFC
+/// 6838](https://datatracker.ietf.org/doc/html/rfc6838) media type, with
+/// optional parameters, e.g. `image/tiff; application=geotiff`.
+fn is_media_type(s: &str) -> bool {
+    let mut parts = s.split(';');
+    let essence = parts.next().unwrap_or_default().trim();
+    let valid_essence = essence
+        .split_once('/')
+        .map(|(r#type, subtype)| is_token(r#type) && is_token(subtype))
+        .unwrap_or(false);
+    valid_essence
+        && parts.all(|parameter| {
+            parameter
+                .split_once('=')
+                .map(|(name, value)| is_token(name.trim()) && !value.trim().is_empty())
+                .unwrap_or(false)
+        })
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+/// Escapes a JSON pointer reference token.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        validate::{Validate, Validator},
+        Asset, Catalog, HrefObject, Item, Link, Stac,
+    };
+
+    #[test]
+    fn media_types() {
+        for media_type in [
+            "application/json",
+            "image/tiff; application=geotiff; profile=cloud-optimized",
+            "application/vnd.oai.openapi+json;version=3.0",
+        ] {
+            assert!(super::is_media_type(media_type), "{}", media_type);
+        }
+        for media_type in ["json", "image/", "image/tiff;", "a b/c"] {
+            assert!(!super::is_media_type(media_type), "{}", media_type);
+        }
+    }
+
+    #[test]
+    fn rels_and_types() {
+        let mut item = Item::new("an-item");
+        item.links.push(Link::new("a.json", "derived_from"));
+        item.links
+            .push(Link::new("b.json", "https://example.com/rels/custom"));
+        item.links.push(Link::new("c.json", "not-a-rel"));
+        let mut asset = Asset::new("data.tif");
+        asset.r#type = Some("geotiff".into());
+        let _ = item.assets.insert("data/raw".to_string(), asset);
+        item.bbox = None;
+        let issues = item.validate();
+        let rules: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.rule.as_str(), issue.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("link-rel-unknown", "/links/2/rel"),
+                ("media-type-invalid", "/assets/data~1raw/type")
+            ]
+        );
+    }
+
+    #[test]
+    fn structural_target_type() {
+        let mut catalog = Catalog::new("root");
+        catalog.links.push(Link::item("./sub/catalog.json"));
+        let (mut stac, root) = Stac::new(HrefObject::new(catalog, "stac/catalog.json")).unwrap();
+        let _ = stac
+            .add_child(
+                root,
+                HrefObject::new(Catalog::new("sub"), "stac/sub/catalog.json"),
+            )
+            .unwrap();
+        let report = Validator::default()
+            .validate_recursive(&mut stac, root)
+            .unwrap();
+        let entry = report
+            .entries
+            .iter()
+            .find(|entry| entry.issue.rule == "link-target-type")
+            .unwrap();
+        assert_eq!(entry.id, "root");
+        assert_eq!(entry.issue.pointer, "/links/0");
+    }
+}
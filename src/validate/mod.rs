@@ -0,0 +1,563 @@
+//! Validate STAC objects beyond what their types can express.
+//!
+//! Deserializing an [Item] only checks that its fields have the right shapes.
+//! Invalid footprints (e.g. unclosed rings or out-of-range coordinates) still
+//! parse, and they regularly break downstream indexers. The [Validate] trait
+//! checks an object's contents and returns every [Issue] it finds, each with a
+//! [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the
+//! offending value. Use a [Validator] to configure the checks, or to fix the
+//! issues that can be fixed automatically.
+//!
+//! Some checks need more than one object. [Validator::check_extents] walks a
+//! [Stac](crate::Stac) to check that items fall within their collection's extent.
+//!
+//! With the `schemas` feature, JSON schema validation is opt-in with
+//! [Validator::schemas]. The core and common extension [Schemas] are bundled
+//! with the crate, so it works offline.
+//!
+//! Validators that cover whole objects or trees return a [ValidationReport],
+//! which records the object of each issue and can be serialized to JSON or
+//! pretty-printed.
+//!
+//! # Examples
+//!
+//! ```
+//! use geojson::{Geometry, Value};
+//! use stac::{validate::Validate, Item};
+//! let mut item = Item::new("an-id");
+//! item.geometry = Some(Geometry::new(Value::Polygon(vec![vec![
+//!     vec![-105.0, 40.0],
+//!     vec![-104.0, 40.0],
+//!     vec![-104.0, 41.0],
+//! ]])));
+//! let issues = item.validate();
+//! assert_eq!(issues[0].rule, "geometry-ring-too-short");
+//! assert_eq!(issues[0].pointer, "/geometry/coordinates/0");
+//! ```
+
+mod common;
+mod datetime;
+mod extent;
+mod geometry;
+mod links;
+mod recursive;
+mod report;
+mod rule;
+#[cfg(feature = "schemas")]
+mod schema;
+
+pub use recursive::validate_recursive;
+pub use report::{ReportEntry, Summary, ValidationReport};
+pub use rule::Rule;
+#[cfg(feature = "schemas")]
+pub use schema::{SchemaResolver, Schemas};
+
+use crate::{Catalog, Collection, Href, Item, Object};
+use geojson::Geometry;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// The default tolerance, in degrees, for comparing an item's bbox to its geometry.
+pub const DEFAULT_BBOX_TOLERANCE: f64 = 1e-6;
+
+/// A problem found while validating an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    /// How serious this issue is.
+    pub severity: Severity,
+
+    /// The id of the rule that found this issue, e.g. `"geometry-unclosed-ring"`.
+    pub rule: String,
+
+    /// A [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the
+    /// offending value, relative to the validated object.
+    pub pointer: String,
+
+    /// A human-readable description of the issue.
+    pub message: String,
+}
+
+/// How serious an [Issue] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The object breaks the STAC specification or is unusable, e.g. an unclosed polygon ring.
+    Error,
+
+    /// The object is usable but goes against a recommendation, e.g. a clockwise exterior ring.
+    Warning,
+}
+
+/// Checks the contents of a STAC object.
+pub trait Validate {
+    /// Returns every issue found in this object.
+    ///
+    /// An empty vector means the object is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validate, Item};
+    /// assert!(Item::new("an-id").validate().is_empty());
+    /// ```
+    fn validate(&self) -> Vec<Issue>;
+}
+
+/// Configurable validation of STAC objects.
+///
+/// The [Validate] trait uses the default `Validator`.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{validate::Validator, Item};
+/// let validator = Validator {
+///     bbox_tolerance: 0.01,
+///     ..Default::default()
+/// };
+/// assert!(validator.validate_item(&Item::new("an-id")).is_empty());
+/// ```
+#[derive(Clone)]
+pub struct Validator {
+    /// The maximum difference, in degrees, between each coordinate of an
+    /// item's stated bbox and the bbox computed from its geometry.
+    pub bbox_tolerance: f64,
+
+    /// Stop [recursive validation](Validator::validate_recursive) after the
+    /// first object with an [error](Severity::Error).
+    pub fail_fast: bool,
+
+    /// If set, objects are also validated against their core and extension
+    /// JSON schemas.
+    #[cfg(feature = "schemas")]
+    pub schemas: Option<Schemas>,
+
+    /// Custom [Rules](Rule) that run after the built-in checks.
+    pub rules: Vec<Arc<dyn Rule>>,
+}
+
+impl Default for Validator {
+    fn default() -> Validator {
+        Validator {
+            bbox_tolerance: DEFAULT_BBOX_TOLERANCE,
+            fail_fast: false,
+            #[cfg(feature = "schemas")]
+            schemas: None,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Debug for Validator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Validator");
+        let _ = debug
+            .field("bbox_tolerance", &self.bbox_tolerance)
+            .field("fail_fast", &self.fail_fast);
+        #[cfg(feature = "schemas")]
+        let _ = debug.field("schemas", &self.schemas);
+        debug
+            .field(
+                "rules",
+                &self.rules.iter().map(|rule| rule.id()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Validator {
+    /// Adds a custom [Rule] to this validator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::{Issue, Rule, Validator}, Object};
+    /// struct NoRule;
+    /// impl Rule for NoRule {
+    ///     fn id(&self) -> &str {
+    ///         "no-rule"
+    ///     }
+    ///     fn check(&self, _: &Object) -> Vec<Issue> {
+    ///         Vec::new()
+    ///     }
+    /// }
+    /// let validator = Validator::default().with_rule(NoRule);
+    /// ```
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Validator {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Validates an [Object] and returns a [ValidationReport].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Item, Object};
+    /// let object = Object::from(Item::new("an-id"));
+    /// let report = Validator::default().report(&object, Some(&"item.json".into()));
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn report(&self, object: &Object, href: Option<&Href>) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.add(object, href, self.validate(object));
+        report
+    }
+
+    /// Returns every issue found in an [Object].
+    ///
+    /// Every object's `stac_version` must be supported, and its
+    /// `stac_extensions` shouldn't be shorthands or outdated schema URIs.
+    /// Ids that aren't safe to use in hrefs (see [id](crate::id)) are
+    /// warnings. Collections' extents must be well formed. If
+    /// [schemas](Validator::schemas) are set (with the `schemas` feature),
+    /// the object is also validated against its JSON schemas. Finally, every custom [Rule] is checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Catalog, Object};
+    /// let object = Object::from(Catalog::new("an-id"));
+    /// assert!(Validator::default().validate(&object).is_empty());
+    /// ```
+    pub fn validate(&self, object: &Object) -> Vec<Issue> {
+        let mut issues = match object {
+            Object::Item(item) => self.validate_item(item),
+            Object::Catalog(catalog) => self.validate_catalog(catalog),
+            Object::Collection(collection) => self.validate_collection(collection),
+        };
+        #[cfg(feature = "schemas")]
+        if let Some(schemas) = self.schemas.as_ref() {
+            let value = match object {
+                Object::Item(item) => serde_json::to_value(item),
+                Object::Catalog(catalog) => serde_json::to_value(catalog),
+                Object::Collection(collection) => serde_json::to_value(collection),
+            };
+            match value
+                .map_err(crate::Error::from)
+                .and_then(|value| schemas.validate(&value))
+            {
+                Ok(schema_issues) => issues.extend(schema_issues),
+                Err(err) => issues.push(Issue::error("schema-error", "", err)),
+            }
+        }
+        for rule in &self.rules {
+            issues.extend(rule.check(object));
+        }
+        issues
+    }
+
+    /// Returns every issue found in a [Catalog].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Catalog};
+    /// let mut catalog = Catalog::new("an-id");
+    /// catalog.version = "0.9.0".to_string();
+    /// let issues = Validator::default().validate_catalog(&catalog);
+    /// assert_eq!(issues[0].rule, "version-unsupported");
+    /// ```
+    pub fn validate_catalog(&self, catalog: &Catalog) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        common::validate(
+            &catalog.id,
+            &catalog.version,
+            catalog.extensions.as_deref(),
+            &mut issues,
+        );
+        links::validate(&catalog.links, None, &mut issues);
+        issues
+    }
+
+    /// Returns every issue found in a [Collection].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Collection};
+    /// let mut collection = Collection::new("an-id");
+    /// collection.extent.spatial.bbox = vec![vec![0.0, 0.0, 1.0]];
+    /// let issues = Validator::default().validate_collection(&collection);
+    /// assert_eq!(issues[0].rule, "extent-bbox-length");
+    /// ```
+    pub fn validate_collection(&self, collection: &Collection) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        common::validate(
+            &collection.id,
+            &collection.version,
+            collection.extensions.as_deref(),
+            &mut issues,
+        );
+        common::validate_collection(collection, &mut issues);
+        links::validate(&collection.links, collection.assets.as_ref(), &mut issues);
+        issues
+    }
+
+    /// Returns every issue found in an [Item].
+    ///
+    /// Along with the checks for every [Object] and the geometry checks, this
+    /// checks that the item's bbox matches the bbox of its geometry within
+    /// [bbox_tolerance](Validator::bbox_tolerance). Bboxes that cross the
+    /// antimeridian (i.e. their west edge is greater than their east edge)
+    /// aren't compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geojson::{Geometry, Value};
+    /// use stac::{validate::Validator, Item};
+    /// let mut item = Item::new("an-id");
+    /// item.geometry = Some(Geometry::new(Value::Point(vec![-105.1, 40.1])));
+    /// item.bbox = Some(vec![-105.0, 40.0, -105.0, 40.0]);
+    /// let issues = Validator::default().validate_item(&item);
+    /// assert_eq!(issues[0].rule, "bbox-mismatch");
+    /// ```
+    pub fn validate_item(&self, item: &Item) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        common::validate(
+            &item.id,
+            &item.version,
+            item.extensions.as_deref(),
+            &mut issues,
+        );
+        links::validate(&item.links, Some(&item.assets), &mut issues);
+        datetime::validate(item, &mut issues);
+        if let Some(geometry) = item.geometry.as_ref() {
+            geometry::validate(geometry, "/geometry", &mut issues);
+        }
+        if let Some(issue) = self.check_bbox(item) {
+            issues.push(issue);
+        }
+        issues
+    }
+
+    /// Fixes the issues in an [Item] that can be fixed automatically, and
+    /// returns the issues that remain.
+    ///
+    /// Currently, a missing or inconsistent bbox is replaced with the bbox of
+    /// the item's geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geojson::{Geometry, Value};
+    /// use stac::{validate::Validator, Item};
+    /// let mut item = Item::new("an-id");
+    /// item.geometry = Some(Geometry::new(Value::Point(vec![-105.1, 40.1])));
+    /// item.bbox = Some(vec![-105.0, 40.0, -105.0, 40.0]);
+    /// assert!(Validator::default().fix_item(&mut item).is_empty());
+    /// assert_eq!(item.bbox.unwrap(), vec![-105.1, 40.1, -105.1, 40.1]);
+    /// ```
+    pub fn fix_item(&self, item: &mut Item) -> Vec<Issue> {
+        if self.check_bbox(item).is_some() {
+            fix_bbox(item);
+        }
+        self.validate_item(item)
+    }
+
+    fn check_bbox(&self, item: &Item) -> Option<Issue> {
+        let computed = crate::geometry::bbox(item.geometry.as_ref()?)?;
+        let bbox = if let Some(bbox) = item.bbox.as_ref() {
+            bbox
+        } else {
+            return Some(Issue::error(
+                "bbox-missing",
+                "",
+                "item has a geometry but no bbox",
+            ));
+        };
+        let stated = match bbox.len() {
+            4 => [bbox[0], bbox[1], bbox[2], bbox[3]],
+            6 => [bbox[0], bbox[1], bbox[3], bbox[4]],
+            n => {
+                return Some(Issue::error(
+                    "bbox-length",
+                    "/bbox",
+                    format!("bbox has {} values, expected 4 or 6", n),
+                ))
+            }
+        };
+        if stated[0] > stated[2] {
+            return None;
+        }
+        if stated
+            .iter()
+            .zip(&computed)
+            .any(|(stated, computed)| (stated - computed).abs() > self.bbox_tolerance)
+        {
+            Some(Issue::error(
+                "bbox-mismatch",
+                "/bbox",
+                format!(
+                    "bbox {:?} does not match the geometry's bbox {:?}",
+                    stated, computed
+                ),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Replaces the horizontal extents of the item's bbox with those of its geometry.
+fn fix_bbox(item: &mut Item) {
+    let computed = if let Some(computed) = item.geometry.as_ref().and_then(crate::geometry::bbox) {
+        computed
+    } else {
+        return;
+    };
+    match item.bbox.as_mut() {
+        Some(bbox) if bbox.len() == 6 => {
+            bbox[0] = computed[0];
+            bbox[1] = computed[1];
+            bbox[3] = computed[2];
+            bbox[4] = computed[3];
+        }
+        _ => item.bbox = Some(computed),
+    }
+}
+
+impl Issue {
+    /// Creates a new issue with [Severity::Error].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::{Issue, Severity};
+    /// let issue = Issue::error("a-rule", "/id", "a message");
+    /// assert_eq!(issue.severity, Severity::Error);
+    /// ```
+    pub fn error(rule: &str, pointer: impl ToString, message: impl ToString) -> Issue {
+        Issue::new(Severity::Error, rule, pointer, message)
+    }
+
+    /// Creates a new issue with [Severity::Warning].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::{Issue, Severity};
+    /// let issue = Issue::warning("a-rule", "/id", "a message");
+    /// assert_eq!(issue.severity, Severity::Warning);
+    /// ```
+    pub fn warning(rule: &str, pointer: impl ToString, message: impl ToString) -> Issue {
+        Issue::new(Severity::Warning, rule, pointer, message)
+    }
+
+    /// Creates a new issue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::{Issue, Severity};
+    /// let issue = Issue::new(Severity::Warning, "a-rule", "/id", "a message");
+    /// ```
+    pub fn new(
+        severity: Severity,
+        rule: &str,
+        pointer: impl ToString,
+        message: impl ToString,
+    ) -> Issue {
+        Issue {
+            severity,
+            rule: rule.to_string(),
+            pointer: pointer.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Validate for Geometry {
+    /// Checks that positions are within longitude and latitude ranges, and
+    /// that polygon rings are long enough, closed, wound according to [RFC
+    /// 7946](https://tools.ietf.org/html/rfc7946#section-3.1.6), and don't
+    /// intersect themselves.
+    fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        geometry::validate(self, "", &mut issues);
+        issues
+    }
+}
+
+impl Validate for Item {
+    fn validate(&self) -> Vec<Issue> {
+        Validator::default().validate_item(self)
+    }
+}
+
+impl Validate for Catalog {
+    fn validate(&self) -> Vec<Issue> {
+        Validator::default().validate_catalog(self)
+    }
+}
+
+impl Validate for Collection {
+    fn validate(&self) -> Vec<Issue> {
+        Validator::default().validate_collection(self)
+    }
+}
+
+impl Validate for Object {
+    fn validate(&self) -> Vec<Issue> {
+        Validator::default().validate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Validator;
+    use crate::Item;
+    use geojson::{Geometry, Value};
+
+    fn item(bbox: Option<Vec<f64>>) -> Item {
+        let mut item = Item::new("an-id");
+        item.geometry = Some(Geometry::new(Value::LineString(vec![
+            vec![-105.0, 40.0],
+            vec![-104.0, 41.0],
+        ])));
+        item.bbox = bbox;
+        item
+    }
+
+    #[test]
+    fn bbox_within_tolerance() {
+        let item = item(Some(vec![-105.0, 40.0, -104.0, 41.0000001]));
+        assert!(Validator::default().validate_item(&item).is_empty());
+        let validator = Validator {
+            bbox_tolerance: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(validator.validate_item(&item)[0].rule, "bbox-mismatch");
+    }
+
+    #[test]
+    fn bbox_missing() {
+        let mut item = item(None);
+        assert_eq!(
+            Validator::default().validate_item(&item)[0].rule,
+            "bbox-missing"
+        );
+        assert!(Validator::default().fix_item(&mut item).is_empty());
+        assert_eq!(item.bbox.unwrap(), vec![-105.0, 40.0, -104.0, 41.0]);
+    }
+
+    #[test]
+    fn fix_three_dimensional_bbox() {
+        let mut item = item(Some(vec![0.0, 0.0, 10.0, 1.0, 1.0, 20.0]));
+        assert!(Validator::default().fix_item(&mut item).is_empty());
+        assert_eq!(
+            item.bbox.unwrap(),
+            vec![-105.0, 40.0, 10.0, -104.0, 41.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn antimeridian() {
+        let item = item(Some(vec![170.0, 40.0, -170.0, 41.0]));
+        assert!(Validator::default().validate_item(&item).is_empty());
+    }
+}
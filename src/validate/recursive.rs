@@ -0,0 +1,122 @@
+use super::{links, Severity, ValidationReport, Validator};
+use crate::{stac::Handle, Read, Result, Stac};
+
+impl Validator {
+    /// Validates every object in the tree below (and including) `handle`.
+    ///
+    /// Each object is checked with [validate](Validator::validate), along with
+    /// the types of the objects that its `child`, `item`, `parent`, and `root`
+    /// links point to. Then, every item is checked against its collection's extent with
+    /// [check_extents](Validator::check_extents). Use
+    /// [ValidationReport::summary] to count the issues by severity and rule.
+    ///
+    /// If [fail_fast](Validator::fail_fast) is set, validation stops after the
+    /// first object with an [error](Severity::Error), and the extents are
+    /// only checked if no errors were found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Validator, Stac};
+    /// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+    /// let report = Validator::default().validate_recursive(&mut stac, root).unwrap();
+    /// // The projection example's bbox doesn't match its geometry.
+    /// assert_eq!(report.summary().rules["bbox-mismatch"], 1);
+    /// ```
+    pub fn validate_recursive<R: Read>(
+        &self,
+        stac: &mut Stac<R>,
+        handle: Handle,
+    ) -> Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+        for result in stac.walk(handle).visit(|stac, handle| {
+            let href = stac.href(handle).cloned();
+            let structural = links::validate_structural(stac, handle)?;
+            let object = stac.get(handle)?;
+            let mut report = self.report(object, href.as_ref());
+            report.add(object, href.as_ref(), structural);
+            Ok(report)
+        }) {
+            let object_report = result?;
+            let has_errors = !object_report.is_valid();
+            report.append(object_report);
+            if self.fail_fast && has_errors {
+                return Ok(report);
+            }
+        }
+        let extents = self.check_extents(stac, handle)?;
+        if self.fail_fast && !extents.is_valid() {
+            // Like the objects above, stop at the first error.
+            report.entries.extend(
+                extents
+                    .entries
+                    .into_iter()
+                    .filter(|entry| entry.issue.severity == Severity::Error)
+                    .take(1),
+            );
+        } else {
+            report.append(extents);
+        }
+        Ok(report)
+    }
+}
+
+/// Validates every object in the tree below (and including) `handle` with the
+/// default [Validator].
+///
+/// # Examples
+///
+/// ```
+/// use stac::{validate, Stac};
+/// let (mut stac, root) = Stac::read("data/catalog.json").unwrap();
+/// let report = validate::validate_recursive(&mut stac, root).unwrap();
+/// let summary = report.summary();
+/// ```
+pub fn validate_recursive<R: Read>(stac: &mut Stac<R>, handle: Handle) -> Result<ValidationReport> {
+    Validator::default().validate_recursive(stac, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        validate::{Severity, Validator},
+        Catalog, Item, Stac,
+    };
+
+    fn stac() -> (Stac<crate::Reader>, crate::stac::Handle) {
+        let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+        let mut bad_version = Catalog::new("bad-version");
+        bad_version.version = "0.8.0".to_string();
+        let _ = stac.add_child(root, bad_version).unwrap();
+        let _ = stac.add_child(root, Item::new("an item")).unwrap();
+        let mut also_bad = Item::new("also-bad");
+        also_bad.version = "2.0.0".to_string();
+        let _ = stac.add_child(root, also_bad).unwrap();
+        (stac, root)
+    }
+
+    #[test]
+    fn summary() {
+        let (mut stac, root) = stac();
+        let report = Validator::default()
+            .validate_recursive(&mut stac, root)
+            .unwrap();
+        let summary = report.summary();
+        assert_eq!(summary.severities[&Severity::Error], 2);
+        assert_eq!(summary.severities[&Severity::Warning], 1);
+        assert_eq!(summary.rules["version-unsupported"], 2);
+        assert_eq!(summary.rules["id-unsafe"], 1);
+    }
+
+    #[test]
+    fn fail_fast() {
+        let (mut stac, root) = stac();
+        let validator = Validator {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let report = validator.validate_recursive(&mut stac, root).unwrap();
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].id, "bad-version");
+    }
+}
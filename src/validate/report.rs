@@ -0,0 +1,245 @@
+use super::{Issue, Severity};
+use crate::{Href, Object};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// The issues found by validating one or more objects.
+///
+/// Every validator in this module produces a `ValidationReport`, so CI
+/// pipelines and command-line tools only need to consume one format. Reports
+/// serialize to JSON, and their [Display] implementation pretty-prints one
+/// line per issue followed by a summary.
+///
+/// # Examples
+///
+/// ```
+/// use geojson::{Geometry, Value};
+/// use stac::{validate::Validator, Item, Object};
+/// let mut item = Item::new("an-id");
+/// item.geometry = Some(Geometry::new(Value::Point(vec![-105.0, 100.0])));
+/// let report = Validator::default().report(&Object::from(item), None);
+/// assert!(!report.is_valid());
+/// let value = serde_json::to_value(&report).unwrap();
+/// assert_eq!(value["entries"][0]["rule"], "geometry-coordinate-range");
+/// println!("{}", report);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// One entry per issue, grouped by object.
+    pub entries: Vec<ReportEntry>,
+}
+
+/// The number of entries in a [ValidationReport] by severity and by rule.
+///
+/// Returned by [ValidationReport::summary].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Summary {
+    /// The number of entries with each severity.
+    pub severities: BTreeMap<Severity, usize>,
+
+    /// The number of entries found by each rule.
+    pub rules: BTreeMap<String, usize>,
+}
+
+/// An [Issue] in a [ValidationReport], along with the object it was found in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportEntry {
+    /// The id of the object.
+    pub id: String,
+
+    /// The href of the object, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+
+    /// The issue.
+    #[serde(flatten)]
+    pub issue: Issue,
+}
+
+impl ValidationReport {
+    /// Creates a new, empty report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::ValidationReport;
+    /// let report = ValidationReport::new();
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn new() -> ValidationReport {
+        ValidationReport::default()
+    }
+
+    /// Adds an object's issues to this report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::{ValidationReport, Validate}, Item, Object};
+    /// let object = Object::from(Item::new("an-id"));
+    /// let mut report = ValidationReport::new();
+    /// report.add(&object, None, object.validate());
+    /// ```
+    pub fn add(&mut self, object: &Object, href: Option<&Href>, issues: Vec<Issue>) {
+        self.entries
+            .extend(issues.into_iter().map(|issue| ReportEntry {
+                id: object.id().to_string(),
+                href: href.map(|href| href.to_string()),
+                issue,
+            }));
+    }
+
+    /// Moves all of the entries of `other` into this report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::ValidationReport;
+    /// let mut report = ValidationReport::new();
+    /// report.append(ValidationReport::new());
+    /// ```
+    pub fn append(&mut self, mut other: ValidationReport) {
+        self.entries.append(&mut other.entries);
+    }
+
+    /// Returns true if this report has no [errors](Severity::Error).
+    ///
+    /// Warnings don't make a report invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::ValidationReport;
+    /// assert!(ValidationReport::new().is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+
+    /// Returns the number of entries with this severity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::{Severity, ValidationReport};
+    /// assert_eq!(ValidationReport::new().count(Severity::Warning), 0);
+    /// ```
+    pub fn count(&self, severity: Severity) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.issue.severity == severity)
+            .count()
+    }
+
+    /// Counts this report's entries by severity and by rule.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::ValidationReport;
+    /// let summary = ValidationReport::new().summary();
+    /// assert!(summary.rules.is_empty());
+    /// ```
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::default();
+        for entry in &self.entries {
+            *summary.severities.entry(entry.issue.severity).or_default() += 1;
+            *summary.rules.entry(entry.issue.rule.clone()).or_default() += 1;
+        }
+        summary
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+impl Display for ReportEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}",
+            self.issue.severity, self.issue.rule, self.id
+        )?;
+        if let Some(href) = self.href.as_ref() {
+            write!(f, " ({})", href)?;
+        }
+        if !self.issue.pointer.is_empty() {
+            write!(f, " at {}", self.issue.pointer)?;
+        }
+        write!(f, ": {}", self.issue.message)
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationReport;
+    use crate::{
+        validate::{Issue, Severity},
+        Item, Object,
+    };
+
+    fn report() -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.add(
+            &Object::from(Item::new("an-item")),
+            Some(&"data/an-item.json".into()),
+            vec![
+                Issue::error("a-rule", "/geometry", "bad geometry"),
+                Issue::warning("another-rule", "", "not great"),
+            ],
+        );
+        report
+    }
+
+    #[test]
+    fn counts() {
+        let report = report();
+        assert!(!report.is_valid());
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.count(Severity::Warning), 1);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            report().to_string(),
+            "error[a-rule] an-item (data/an-item.json) at /geometry: bad geometry\n\
+             warning[another-rule] an-item (data/an-item.json): not great\n\
+             1 error(s), 1 warning(s)"
+        );
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let report = report();
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["entries"][0]["severity"], "error");
+        assert_eq!(value["entries"][0]["pointer"], "/geometry");
+        assert_eq!(value["entries"][0]["href"], "data/an-item.json");
+        let roundtrip: ValidationReport = serde_json::from_value(value).unwrap();
+        assert_eq!(roundtrip, report);
+    }
+}
@@ -0,0 +1,89 @@
+use super::Issue;
+use crate::Object;
+
+/// A custom validation check.
+///
+/// Register rules with [Validator::with_rule](super::Validator::with_rule)
+/// to enforce organization-specific conventions (e.g. naming conventions,
+/// required providers, or mandatory assets). They run after the built-in
+/// checks and their issues appear in the same [ValidationReport](super::ValidationReport).
+///
+/// # Examples
+///
+/// ```
+/// use stac::{validate::{Issue, Rule, Validator}, Item, Object};
+///
+/// struct ThumbnailRequired;
+///
+/// impl Rule for ThumbnailRequired {
+///     fn id(&self) -> &str {
+///         "thumbnail-required"
+///     }
+///
+///     fn check(&self, object: &Object) -> Vec<Issue> {
+///         match object.as_item() {
+///             Some(item) if !item.assets.contains_key("thumbnail") => {
+///                 vec![Issue::error(self.id(), "/assets", "items must have a thumbnail")]
+///             }
+///             _ => Vec::new(),
+///         }
+///     }
+/// }
+///
+/// let validator = Validator::default().with_rule(ThumbnailRequired);
+/// let issues = validator.validate(&Object::from(Item::new("an-id")));
+/// assert_eq!(issues[0].rule, "thumbnail-required");
+/// ```
+pub trait Rule: Send + Sync {
+    /// Returns the id of this rule.
+    ///
+    /// Issues found by this rule should use this id as their
+    /// [rule](Issue::rule), so they can be counted in a
+    /// [Summary](super::Summary).
+    fn id(&self) -> &str;
+
+    /// Returns every issue this rule finds in an object.
+    fn check(&self, object: &Object) -> Vec<Issue>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rule;
+    use crate::{
+        validate::{Issue, Validator},
+        Catalog, Item, Object, Stac,
+    };
+
+    struct Prefix(&'static str);
+
+    impl Rule for Prefix {
+        fn id(&self) -> &str {
+            "id-prefix"
+        }
+
+        fn check(&self, object: &Object) -> Vec<Issue> {
+            if object.id().starts_with(self.0) {
+                Vec::new()
+            } else {
+                vec![Issue::warning(
+                    self.id(),
+                    "/id",
+                    format!("ids should start with {:?}", self.0),
+                )]
+            }
+        }
+    }
+
+    #[test]
+    fn runs_alongside_builtin_rules() {
+        let (mut stac, root) = Stac::new(Catalog::new("acme-root")).unwrap();
+        let mut item = Item::new("an-item");
+        item.version = "2.0.0".to_string();
+        let _ = stac.add_child(root, item).unwrap();
+        let validator = Validator::default().with_rule(Prefix("acme-"));
+        let report = validator.validate_recursive(&mut stac, root).unwrap();
+        let summary = report.summary();
+        assert_eq!(summary.rules["id-prefix"], 1);
+        assert_eq!(summary.rules["version-unsupported"], 1);
+    }
+}
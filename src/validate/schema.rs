@@ -0,0 +1,436 @@
+use super::Issue;
+use crate::{Error, Result};
+use flate2::read::GzDecoder;
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+use url::Url;
+
+/// The gzipped JSON object of schema URIs to schemas that is bundled with the crate.
+///
+/// Regenerate it with `scripts/bundle-schemas`.
+const BUNDLE: &[u8] = include_bytes!("schemas.json.gz");
+
+/// Looks up a JSON schema by its URI.
+///
+/// Register a resolver with [Schemas::with_resolver] to serve schemas from
+/// somewhere other than a local directory or the bundle, e.g. an internal
+/// mirror. Closures that take a URI and return a `Result<Option<Value>>`
+/// are resolvers.
+pub trait SchemaResolver: Send + Sync {
+    /// Returns the schema at this URI, or `None` if this resolver doesn't have it.
+    fn resolve(&self, uri: &str) -> Result<Option<Value>>;
+}
+
+/// The JSON schemas used to validate STAC objects.
+///
+/// These schemas are bundled (compressed) in the crate, so schema validation
+/// doesn't need network access:
+///
+/// - STAC 1.0.0 item, catalog, and collection, with the item's `basics`,
+///   `datetime`, `instrument`, `licensing`, and `provider` fragments
+/// - GeoJSON `Feature` and `Geometry`
+/// - eo 1.0.0 and 1.1.0, projection 1.0.0 and 1.1.0, view 1.0.0, sat 1.0.0,
+///   scientific 1.0.0, file 2.1.0, raster 1.1.0, pointcloud 1.0.0, and
+///   datacube 2.2.0
+///
+/// Other extensions and versions, e.g. projection 2.0.0 or mgrs, aren't
+/// bundled, so serve them with [with_directory](Schemas::with_directory) or
+/// a [SchemaResolver]. Schemas are never fetched over the network. When
+/// looking up a schema, `Schemas` tries, in order:
+///
+/// 1. Every [SchemaResolver], in the order they were added
+/// 2. The local directory, if one was set with [with_directory](Schemas::with_directory)
+/// 3. The bundled schemas
+///
+/// Compiled schemas are cached, so one `Schemas` should be shared between
+/// validations.
+///
+/// # Examples
+///
+/// ```
+/// use stac::validate::Schemas;
+/// let schemas = Schemas::new().with_directory("data/schemas");
+/// let schema = schemas
+///     .get("https://example.com/ranking/v1.0.0/schema.json")
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct Schemas {
+    sources: Arc<Sources>,
+    compiled: Arc<Mutex<HashMap<String, Arc<JSONSchema>>>>,
+}
+
+#[derive(Default)]
+struct Sources {
+    resolvers: Vec<Arc<dyn SchemaResolver>>,
+    directory: Option<PathBuf>,
+}
+
+/// Resolves `$ref`s while compiling schemas.
+struct RefResolver(Arc<Sources>);
+
+impl Schemas {
+    /// Creates a new `Schemas` that only uses the bundled schemas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::Schemas;
+    /// let schemas = Schemas::new();
+    /// ```
+    pub fn new() -> Schemas {
+        Schemas::default()
+    }
+
+    /// Looks up schemas in a local directory before the bundled schemas.
+    ///
+    /// A schema URI is mapped to a path in the directory by its host and
+    /// path, e.g.
+    /// `https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/item.json`
+    /// is read from
+    /// `{directory}/schemas.stacspec.org/v1.0.0/item-spec/json-schema/item.json`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::Schemas;
+    /// let schemas = Schemas::new().with_directory("path/to/schemas");
+    /// ```
+    pub fn with_directory(self, directory: impl AsRef<Path>) -> Schemas {
+        let mut sources = self.into_sources();
+        sources.directory = Some(directory.as_ref().to_path_buf());
+        Schemas::from(sources)
+    }
+
+    /// Looks up schemas with a custom resolver before the local directory and
+    /// the bundled schemas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use stac::validate::Schemas;
+    /// let schemas = Schemas::new().with_resolver(|uri: &str| {
+    ///     if uri == "https://example.com/schema.json" {
+    ///         Ok(Some(json!({"type": "object"})))
+    ///     } else {
+    ///         Ok(None)
+    ///     }
+    /// });
+    /// assert!(schemas.get("https://example.com/schema.json").is_ok());
+    /// ```
+    pub fn with_resolver(self, resolver: impl SchemaResolver + 'static) -> Schemas {
+        let mut sources = self.into_sources();
+        sources.resolvers.push(Arc::new(resolver));
+        Schemas::from(sources)
+    }
+
+    /// Returns the schema at this URI.
+    ///
+    /// Returns [Error::SchemaNotFound] if no resolver, the local directory, or
+    /// the bundle has the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::validate::Schemas;
+    /// assert!(Schemas::new().get("https://example.com/not-a-schema.json").is_err());
+    /// ```
+    pub fn get(&self, uri: &str) -> Result<Value> {
+        self.sources.get(uri)
+    }
+
+    /// Validates a STAC value against its core schema and the schemas of its
+    /// `stac_extensions`.
+    ///
+    /// Schemas that can't be found are reported as
+    /// [warnings](super::Severity::Warning), so validation degrades
+    /// gracefully in air-gapped environments without a schema directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{validate::Schemas, Item};
+    /// let value = serde_json::to_value(Item::new("an-id")).unwrap();
+    /// let issues = Schemas::new().validate(&value).unwrap();
+    /// ```
+    pub fn validate(&self, value: &Value) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        let mut uris = Vec::new();
+        if let Some(uri) = core_schema_uri(value) {
+            uris.push(uri);
+        }
+        if let Some(extensions) = value.get("stac_extensions").and_then(Value::as_array) {
+            uris.extend(
+                extensions
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from),
+            );
+        }
+        for uri in uris {
+            let schema = match self.compile(&uri) {
+                Ok(schema) => schema,
+                Err(Error::SchemaNotFound(_)) => {
+                    issues.push(Issue::warning(
+                        "schema-not-found",
+                        "",
+                        format!("schema not found: {}", uri),
+                    ));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let schema_issues: Vec<_> = match schema.validate(value) {
+                Ok(()) => Vec::new(),
+                Err(errors) => errors
+                    .map(|error| {
+                        Issue::error(
+                            "schema",
+                            error.instance_path.to_string(),
+                            format!("{} ({})", error, uri),
+                        )
+                    })
+                    .collect(),
+            };
+            issues.extend(schema_issues);
+        }
+        Ok(issues)
+    }
+
+    fn compile(&self, uri: &str) -> Result<Arc<JSONSchema>> {
+        if let Some(schema) = self.compiled.lock().expect("not poisoned").get(uri) {
+            return Ok(schema.clone());
+        }
+        let value = self.get(uri)?;
+        let schema = JSONSchema::options()
+            .with_resolver(RefResolver(self.sources.clone()))
+            .compile(&value)
+            .map_err(|err| Error::InvalidSchema {
+                uri: uri.to_string(),
+                message: err.to_string(),
+            })?;
+        let schema = Arc::new(schema);
+        let _ = self
+            .compiled
+            .lock()
+            .expect("not poisoned")
+            .insert(uri.to_string(), schema.clone());
+        Ok(schema)
+    }
+
+    /// Takes the sources out of this `Schemas`, dropping the compiled schemas,
+    /// since they may have been resolved from different sources.
+    fn into_sources(self) -> Sources {
+        match Arc::try_unwrap(self.sources) {
+            Ok(sources) => sources,
+            Err(sources) => Sources {
+                resolvers: sources.resolvers.clone(),
+                directory: sources.directory.clone(),
+            },
+        }
+    }
+}
+
+impl From<Sources> for Schemas {
+    fn from(sources: Sources) -> Schemas {
+        Schemas {
+            sources: Arc::new(sources),
+            compiled: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Schemas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Schemas")
+            .field("resolvers", &self.sources.resolvers.len())
+            .field("directory", &self.sources.directory)
+            .finish()
+    }
+}
+
+impl Sources {
+    fn get(&self, uri: &str) -> Result<Value> {
+        for resolver in &self.resolvers {
+            if let Some(value) = resolver.resolve(uri)? {
+                return Ok(value);
+            }
+        }
+        if let Some(directory) = self.directory.as_ref() {
+            let url = Url::parse(uri)?;
+            let path = directory
+                .join(url.host_str().unwrap_or_default())
+                .join(url.path().trim_start_matches('/'));
+            if path.exists() {
+                let file = std::fs::File::open(path)?;
+                return serde_json::from_reader(file).map_err(Error::from);
+            }
+        }
+        bundle()
+            .get(uri)
+            .cloned()
+            .ok_or_else(|| Error::SchemaNotFound(uri.to_string()))
+    }
+}
+
+impl jsonschema::SchemaResolver for RefResolver {
+    fn resolve(
+        &self,
+        _: &Value,
+        url: &Url,
+        _: &str,
+    ) -> std::result::Result<Arc<Value>, jsonschema::SchemaResolverError> {
+        self.0
+            .get(url.as_str())
+            .map(Arc::new)
+            .map_err(jsonschema::SchemaResolverError::new)
+    }
+}
+
+impl<F> SchemaResolver for F
+where
+    F: Fn(&str) -> Result<Option<Value>> + Send + Sync,
+{
+    fn resolve(&self, uri: &str) -> Result<Option<Value>> {
+        self(uri)
+    }
+}
+
+fn bundle() -> &'static HashMap<String, Value> {
+    static SCHEMAS: OnceLock<HashMap<String, Value>> = OnceLock::new();
+    SCHEMAS.get_or_init(|| {
+        serde_json::from_reader(GzDecoder::new(BUNDLE)).expect("the schema bundle should be valid")
+    })
+}
+
+/// Returns the URI of the core schema for a STAC value, based on its type and version.
+fn core_schema_uri(value: &Value) -> Option<String> {
+    let version = value.get("stac_version")?.as_str()?;
+    let path = match value.get("type")?.as_str()? {
+        crate::ITEM_TYPE => "item-spec/json-schema/item.json",
+        crate::CATALOG_TYPE => "catalog-spec/json-schema/catalog.json",
+        crate::COLLECTION_TYPE => "collection-spec/json-schema/collection.json",
+        _ => return None,
+    };
+    Some(format!(
+        "https://schemas.stacspec.org/v{}/{}",
+        version, path
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Schemas, BUNDLE};
+    use crate::Error;
+    use flate2::read::GzDecoder;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn schemas() -> Schemas {
+        Schemas::new().with_directory("data/schemas")
+    }
+
+    fn item(ranking: serde_json::Value) -> serde_json::Value {
+        let mut item = serde_json::to_value(crate::Item::new("an-id")).unwrap();
+        item["stac_extensions"] = json!(["https://example.com/ranking/v1.0.0/schema.json"]);
+        item["properties"]["ranking:score"] = ranking;
+        item
+    }
+
+    #[test]
+    fn directory() {
+        // The extension schema is in the test directory, and the core schema is bundled.
+        let issues = schemas().validate(&item(json!(3))).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn bundle_contents() {
+        let bundle: HashMap<String, serde_json::Value> =
+            serde_json::from_reader(GzDecoder::new(BUNDLE)).unwrap();
+        let mut uris: Vec<_> = bundle.keys().map(String::as_str).collect();
+        uris.sort();
+        assert_eq!(
+            uris,
+            [
+                "https://geojson.org/schema/Feature.json",
+                "https://geojson.org/schema/Geometry.json",
+                "https://schemas.stacspec.org/v1.0.0/catalog-spec/json-schema/catalog.json",
+                "https://schemas.stacspec.org/v1.0.0/collection-spec/json-schema/collection.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/basics.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/datetime.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/instrument.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/item.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/licensing.json",
+                "https://schemas.stacspec.org/v1.0.0/item-spec/json-schema/provider.json",
+                "https://stac-extensions.github.io/datacube/v2.2.0/schema.json",
+                "https://stac-extensions.github.io/eo/v1.0.0/schema.json",
+                "https://stac-extensions.github.io/eo/v1.1.0/schema.json",
+                "https://stac-extensions.github.io/file/v2.1.0/schema.json",
+                "https://stac-extensions.github.io/pointcloud/v1.0.0/schema.json",
+                "https://stac-extensions.github.io/projection/v1.0.0/schema.json",
+                "https://stac-extensions.github.io/projection/v1.1.0/schema.json",
+                "https://stac-extensions.github.io/raster/v1.1.0/schema.json",
+                "https://stac-extensions.github.io/sat/v1.0.0/schema.json",
+                "https://stac-extensions.github.io/scientific/v1.0.0/schema.json",
+                "https://stac-extensions.github.io/view/v1.0.0/schema.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn bundled() {
+        let item: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string("data/simple-item.json").unwrap())
+                .unwrap();
+        assert!(Schemas::new().validate(&item).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bundled_invalid() {
+        let mut item = serde_json::to_value(crate::Item::new("an-id")).unwrap();
+        item["stac_extensions"] =
+            json!(["https://stac-extensions.github.io/eo/v1.1.0/schema.json"]);
+        item["properties"]["eo:cloud_cover"] = json!(101);
+        let issues = Schemas::new().validate(&item).unwrap();
+        assert!(!issues.is_empty());
+        for issue in issues {
+            assert_eq!(issue.rule, "schema");
+            assert_eq!(issue.severity, crate::validate::Severity::Error);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let issues = schemas().validate(&item(json!("three"))).unwrap();
+        let issue = issues.iter().find(|issue| issue.rule == "schema").unwrap();
+        assert_eq!(issue.pointer, "/properties/ranking:score");
+    }
+
+    #[test]
+    fn resolver_first() {
+        let schemas = schemas().with_resolver(|uri: &str| {
+            if uri.ends_with("ranking/v1.0.0/schema.json") {
+                Ok(Some(json!({"type": "object"})))
+            } else {
+                Ok(None)
+            }
+        });
+        let issues = schemas.validate(&item(json!("three"))).unwrap();
+        assert!(issues.iter().all(|issue| issue.rule != "schema"));
+    }
+
+    #[test]
+    fn not_found() {
+        assert!(matches!(
+            Schemas::new().get("https://example.com/schema.json"),
+            Err(Error::SchemaNotFound(_))
+        ));
+    }
+}
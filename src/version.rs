@@ -0,0 +1,185 @@
+use crate::{Error, Result};
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// A parsed STAC version, e.g. `1.0.0` or `1.1.0-beta.1`.
+///
+/// Only `1.x.y` versions are supported.
+///
+/// # Examples
+///
+/// ```
+/// use stac::Version;
+/// let version: Version = "1.1.0".parse().unwrap();
+/// assert_eq!(version.minor, 1);
+/// assert!(version > "1.0.0".parse().unwrap());
+/// assert!("0.9.0".parse::<Version>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    /// The major version, always `1`.
+    pub major: u64,
+
+    /// The minor version.
+    pub minor: u64,
+
+    /// The patch version.
+    pub patch: u64,
+
+    /// The pre-release identifier, e.g. `beta.1`.
+    pub prerelease: Option<String>,
+}
+
+impl Version {
+    /// Returns the [STAC_VERSION](crate::STAC_VERSION) supported by this library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::Version;
+    /// assert_eq!(Version::current().to_string(), stac::STAC_VERSION);
+    /// ```
+    pub fn current() -> Version {
+        crate::STAC_VERSION
+            .parse()
+            .expect("the crate's STAC version should be valid")
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Version> {
+        let invalid = || Error::UnsupportedVersion(s.to_string());
+        let (version, prerelease) = match s.split_once('-') {
+            Some((version, prerelease)) if !prerelease.is_empty() => {
+                (version, Some(prerelease.to_string()))
+            }
+            Some(_) => return Err(invalid()),
+            None => (s, None),
+        };
+        let mut parts = version.split('.').map(|part| part.parse::<u64>());
+        let (major, minor, patch) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => (major, minor, patch),
+            _ => return Err(invalid()),
+        };
+        if major != 1 {
+            return Err(invalid());
+        }
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(prerelease) = self.prerelease.as_ref() {
+            write!(f, "-{}", prerelease)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => cmp_prerelease(a, b),
+            })
+    }
+}
+
+/// Compares pre-release identifiers per [semver §11](https://semver.org/#spec-item-11).
+///
+/// Numeric identifiers compare numerically and sort before alphanumeric ones,
+/// and a longer list of identifiers wins when all preceding identifiers are equal.
+fn cmp_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_identifiers = a.split('.');
+    let mut b_identifiers = b.split('.');
+    loop {
+        let ordering = match (a_identifiers.next(), b_identifiers.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Version;
+
+    #[test]
+    fn parse() {
+        let version: Version = "1.0.0".parse().unwrap();
+        assert_eq!(version, Version::current());
+        let version: Version = "1.1.0-beta.1".parse().unwrap();
+        assert_eq!(version.minor, 1);
+        assert_eq!(version.prerelease.as_deref(), Some("beta.1"));
+        assert_eq!(version.to_string(), "1.1.0-beta.1");
+    }
+
+    #[test]
+    fn unsupported() {
+        for s in ["0.9.0", "2.0.0", "1.0", "1.0.0.0", "1.0.0-", "one"] {
+            let _ = s.parse::<Version>().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let v100: Version = "1.0.0".parse().unwrap();
+        let v110: Version = "1.1.0".parse().unwrap();
+        let v110_beta: Version = "1.1.0-beta.1".parse().unwrap();
+        assert!(v100 < v110);
+        assert!(v100 < v110_beta);
+        assert!(v110_beta < v110);
+    }
+
+    #[test]
+    fn prerelease_ordering() {
+        let versions: Vec<Version> = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.10",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+    }
+}
@@ -1,12 +1,27 @@
-use crate::{Error, Href, HrefObject, Result};
+use crate::{retry::RetryPolicy, Error, Href, HrefObject, Object, Result, Version};
 use path_slash::PathBufExt;
 use serde_json::Value;
+#[cfg(any(feature = "hash", feature = "reqwest"))]
+use std::collections::HashMap;
+#[cfg(feature = "async")]
+use std::future::Future;
 use std::{
     fs::File,
-    io::BufWriter,
+    io::{BufWriter, Stdout, Write as _},
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 use url::Url;
+#[cfg(feature = "reqwest")]
+use {crate::auth::Request, crate::HttpClient};
+#[cfg(feature = "hash")]
+use {
+    sha2::{Digest, Sha256},
+    std::{
+        fs::OpenOptions,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+};
 
 /// A trait to describe things that can write STAC objects.
 pub trait Write {
@@ -77,13 +92,53 @@ pub trait Write {
     /// writer.write_json_to_path(value, "out.json").unwrap();
     /// ```
     fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()>;
+
+    /// Returns the settings that change what this writer writes, e.g. its
+    /// STAC version.
+    ///
+    /// A [CheckpointWriter] hashes these along with each object, so changing
+    /// them re-writes everything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{Write, Writer};
+    /// let writer = Writer {
+    ///     pretty: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(writer.settings()["pretty"], true);
+    /// ```
+    fn settings(&self) -> Value {
+        Value::Null
+    }
 }
 
+/// The path that [Writer] writes to standard output.
+pub const STDOUT: &str = "-";
+
 /// The default writer that comes with **stac-rs**.
+///
+/// The path `-` is written to standard output. To write many objects to
+/// standard output, e.g. a whole rendered catalog, use [NdjsonWriter].
 #[derive(Debug)]
 pub struct Writer {
     /// Pretty-print json?
     pub pretty: bool,
+
+    /// If set, every object's `stac_version` is normalized to this version before writing,
+    /// and its bands are converted to that version's layout.
+    pub version: Option<Version>,
+
+    /// Sort links by `rel` and `href`, and assets by key, before writing?
+    ///
+    /// Asset maps are unordered, so without sorting the same catalog can be
+    /// written differently each time. Sorting makes regenerated catalogs
+    /// diff cleanly in version control.
+    pub sort: bool,
+
+    /// If set, failed writes to the filesystem are retried with this policy.
+    pub retry: Option<Arc<dyn RetryPolicy>>,
 }
 
 impl Writer {
@@ -101,34 +156,706 @@ impl Writer {
 }
 
 impl Write for Writer {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        let value = prepare(object.object, self.version.as_ref(), self.sort)?;
+        self.write_json(value, &object.href)
+    }
+
     fn write_json_to_url(&self, _: Value, url: &Url) -> Result<()> {
         Err(Error::CannotWriteUrl(url.clone()))
     }
 
     fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()> {
-        if let Some(parent) = path.as_ref().parent() {
-            std::fs::create_dir_all(parent)?;
+        if path.as_ref() == Path::new(STDOUT) {
+            let mut stdout = std::io::stdout().lock();
+            if self.pretty {
+                serde_json::to_writer_pretty(&mut stdout, &value)?;
+            } else {
+                serde_json::to_writer(&mut stdout, &value)?;
+            }
+            return writeln!(stdout).map_err(Error::from);
+        }
+        let write = || {
+            if let Some(parent) = path.as_ref().parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = File::create(path.as_ref())?;
+            let mut writer = BufWriter::new(file);
+            if self.pretty {
+                serde_json::to_writer_pretty(&mut writer, &value)?;
+            } else {
+                serde_json::to_writer(&mut writer, &value)?;
+            }
+            writer.flush().map_err(Error::from)
+        };
+        match self.retry.as_deref() {
+            Some(policy) => crate::retry::retry(policy, write),
+            None => write(),
+        }
+    }
+
+    fn settings(&self) -> Value {
+        settings(self.pretty, self.version.as_ref(), self.sort)
+    }
+}
+
+/// Writes objects as newline-delimited JSON, one object per line.
+///
+/// Hrefs are ignored, so every object goes to the same stream. This lets a
+/// rendered catalog be piped into other tools.
+///
+/// # Examples
+///
+/// ```
+/// use stac::{Catalog, Item, Layout, NdjsonWriter, Stac};
+/// let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+/// let _ = stac.add_child(root, Item::new("an-item")).unwrap();
+/// let writer = NdjsonWriter::new(Vec::new());
+/// stac.write(&mut Layout::new("root"), &writer).unwrap();
+/// let output = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(output.lines().count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct NdjsonWriter<W: std::io::Write> {
+    /// If set, every object's `stac_version` is normalized to this version before writing,
+    /// and its bands are converted to that version's layout.
+    pub version: Option<Version>,
+
+    /// Sort links by `rel` and `href`, and assets by key, before writing?
+    pub sort: bool,
+
+    writer: Mutex<W>,
+}
+
+impl<W: std::io::Write> NdjsonWriter<W> {
+    /// Creates a new writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::NdjsonWriter;
+    /// let writer = NdjsonWriter::new(Vec::new());
+    /// ```
+    pub fn new(writer: W) -> NdjsonWriter<W> {
+        NdjsonWriter {
+            version: None,
+            sort: false,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Returns the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::NdjsonWriter;
+    /// let writer = NdjsonWriter::new(Vec::new());
+    /// assert!(writer.into_inner().is_empty());
+    /// ```
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().expect("writer lock poisoned")
+    }
+
+    fn write_line(&self, value: &Value) -> Result<()> {
+        let mut writer = self.writer.lock().expect("writer lock poisoned");
+        serde_json::to_writer(&mut *writer, value)?;
+        writeln!(writer)?;
+        writer.flush().map_err(Error::from)
+    }
+}
+
+impl NdjsonWriter<Stdout> {
+    /// Creates a writer to standard output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{Layout, NdjsonWriter, Stac};
+    /// let (stac, _) = Stac::read("data/catalog.json").unwrap();
+    /// stac.write(&mut Layout::new("root"), &NdjsonWriter::stdout()).unwrap();
+    /// ```
+    pub fn stdout() -> NdjsonWriter<Stdout> {
+        NdjsonWriter::new(std::io::stdout())
+    }
+}
+
+impl<W: std::io::Write> Write for NdjsonWriter<W> {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        let value = prepare(object.object, self.version.as_ref(), self.sort)?;
+        self.write_line(&value)
+    }
+
+    fn write_json_to_url(&self, value: Value, _: &Url) -> Result<()> {
+        self.write_line(&value)
+    }
+
+    fn write_json_to_path(&self, value: Value, _: impl AsRef<Path>) -> Result<()> {
+        self.write_line(&value)
+    }
+
+    fn settings(&self) -> Value {
+        settings(false, self.version.as_ref(), self.sort)
+    }
+}
+
+/// Wraps a [Write] and records every completed write in a checkpoint
+/// manifest, so a failed run can be resumed.
+///
+/// The manifest is a newline-delimited JSON file with one `{"href": ...,
+/// "sha256": ...}` line per written object or JSON file, e.g. an item page,
+/// appended and flushed as soon as it has been written. The hash covers the
+/// wrapped writer's [settings](Write::settings) as well as the content. When
+/// a writer is opened on an existing manifest, writes whose href and hash
+/// match a line of the manifest are skipped, so re-running a failed
+/// [Stac::write](crate::Stac::write) (or
+/// [Stac::write_ordered](crate::Stac::write_ordered)) only writes what's
+/// missing or changed. A partial last line, e.g. from a killed process, is
+/// ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stac::{CheckpointWriter, Layout, Stac, Writer};
+/// let (stac, _) = Stac::read("data/catalog.json").unwrap();
+/// let writer = CheckpointWriter::open(Writer::default(), "checkpoint.ndjson").unwrap();
+/// stac.write(&mut Layout::new("stac/v0"), &writer).unwrap();
+/// println!("skipped {} objects", writer.skipped());
+/// ```
+#[cfg(feature = "hash")]
+#[derive(Debug)]
+pub struct CheckpointWriter<W: Write> {
+    writer: W,
+    completed: Mutex<HashMap<String, String>>,
+    manifest: Mutex<File>,
+    skipped: AtomicUsize,
+}
+
+#[cfg(feature = "hash")]
+impl<W: Write> CheckpointWriter<W> {
+    /// Opens a checkpoint manifest, creating it if it doesn't exist, and wraps a writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{CheckpointWriter, Writer};
+    /// let directory = tempfile::tempdir().unwrap();
+    /// let writer =
+    ///     CheckpointWriter::open(Writer::default(), directory.path().join("checkpoint.ndjson"))
+    ///         .unwrap();
+    /// assert_eq!(writer.completed(), 0);
+    /// ```
+    pub fn open(writer: W, manifest: impl AsRef<Path>) -> Result<CheckpointWriter<W>> {
+        let manifest = manifest.as_ref();
+        let mut completed = HashMap::new();
+        if manifest.exists() {
+            for line in std::fs::read_to_string(manifest)?.lines() {
+                let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                    continue;
+                };
+                if let (Some(href), Some(sha256)) = (
+                    entry.get("href").and_then(Value::as_str),
+                    entry.get("sha256").and_then(Value::as_str),
+                ) {
+                    let _ = completed.insert(href.to_string(), sha256.to_string());
+                }
+            }
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(manifest)?;
+        if std::fs::read(manifest)?
+            .last()
+            .is_some_and(|&byte| byte != b'\n')
+        {
+            // Start a new line after a partial one, so it stays ignored.
+            writeln!(file)?;
+        }
+        Ok(CheckpointWriter {
+            writer,
+            completed: Mutex::new(completed),
+            manifest: Mutex::new(file),
+            skipped: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns the number of objects recorded in the manifest.
+    pub fn completed(&self) -> usize {
+        self.completed.lock().expect("manifest lock poisoned").len()
+    }
+
+    /// Returns the number of objects that were skipped because the manifest
+    /// shows they were already written, unchanged.
+    pub fn skipped(&self) -> usize {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes something unless the manifest shows it was already written
+    /// with the same content and settings, and records it if it's written.
+    fn checkpoint(
+        &self,
+        href: String,
+        content: &[u8],
+        write: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let sha256 = format!(
+            "{:x}",
+            Sha256::new()
+                .chain_update(serde_json::to_vec(&self.writer.settings())?)
+                .chain_update(b"\n")
+                .chain_update(content)
+                .finalize()
+        );
+        if self
+            .completed
+            .lock()
+            .expect("manifest lock poisoned")
+            .get(&href)
+            == Some(&sha256)
+        {
+            let _ = self.skipped.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        write()?;
+        let line = serde_json::json!({"href": href, "sha256": sha256});
+        {
+            let mut manifest = self.manifest.lock().expect("manifest lock poisoned");
+            serde_json::to_writer(&mut *manifest, &line)?;
+            writeln!(manifest)?;
+            manifest.flush()?;
+        }
+        let _ = self
+            .completed
+            .lock()
+            .expect("manifest lock poisoned")
+            .insert(href, sha256);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hash")]
+impl<W: Write> Write for CheckpointWriter<W> {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        let href = object.href.as_str().to_string();
+        let content = match &object.object {
+            Object::Item(item) => serde_json::to_vec(item)?,
+            Object::Catalog(catalog) => serde_json::to_vec(catalog)?,
+            Object::Collection(collection) => serde_json::to_vec(collection)?,
+        };
+        self.checkpoint(href, &content, || self.writer.write(object))
+    }
+
+    fn write_json_to_url(&self, value: Value, url: &Url) -> Result<()> {
+        let content = serde_json::to_vec(&value)?;
+        self.checkpoint(url.to_string(), &content, || {
+            self.writer.write_json_to_url(value, url)
+        })
+    }
+
+    fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let href = Href::from(path).as_str().to_string();
+        let content = serde_json::to_vec(&value)?;
+        self.checkpoint(href, &content, || {
+            self.writer.write_json_to_path(value, path)
+        })
+    }
+
+    fn settings(&self) -> Value {
+        self.writer.settings()
+    }
+}
+
+/// A condition that must hold for a write to a url to go through.
+///
+/// Conditions are sent as `If-Match` and `If-None-Match` headers, which HTTP
+/// servers and object stores such as S3 use to reject conflicting writes
+/// with [Error::PreconditionFailed].
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Precondition {
+    /// Only write if nothing exists at the url yet (`If-None-Match: *`).
+    IfNoneMatch,
+
+    /// Only write if the object at the url still has this ETag (`If-Match`).
+    IfMatch(String),
+}
+
+/// Writes STAC objects to urls with `PUT` requests, optionally conditional.
+///
+/// This is enabled by the `reqwest` feature. Requests are sent with an
+/// [HttpClient], so they're authenticated (e.g. with
+/// [SigV4](crate::auth::SigV4) for S3) and retried as configured there.
+/// Paths are written to the filesystem.
+///
+/// The writer remembers the ETag of every object it reads with
+/// [read_json](HttpWriter::read_json) or writes, and later writes to the
+/// same url are sent with `If-Match`, so publishers that read, change, and
+/// write objects concurrently don't overwrite each other's changes: the
+/// loser gets [Error::PreconditionFailed]. If
+/// [create_only](HttpWriter::create_only) is set, writes to urls without a
+/// known ETag are sent with `If-None-Match: *`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stac::{HrefObject, HttpClient, HttpWriter, Item, Write};
+/// let writer = HttpWriter::new(HttpClient::new());
+/// let url = "https://stac.test/items/an-id.json".parse().unwrap();
+/// let value = writer.read_json(&url).unwrap();
+/// writer
+///     .write(HrefObject::new(Item::new("an-id"), "https://stac.test/items/an-id.json"))
+///     .unwrap();
+/// ```
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Default)]
+pub struct HttpWriter {
+    /// Pretty-print json?
+    pub pretty: bool,
+
+    /// If set, every object's `stac_version` is normalized to this version before writing,
+    /// and its bands are converted to that version's layout.
+    pub version: Option<Version>,
+
+    /// Sort links by `rel` and `href`, and assets by key, before writing?
+    pub sort: bool,
+
+    /// Only create new objects, i.e. send `If-None-Match: *` when there's no known ETag?
+    pub create_only: bool,
+
+    http: HttpClient,
+    etags: Mutex<HashMap<Url, String>>,
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpWriter {
+    /// Creates a new writer that sends requests with this client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{HttpClient, HttpWriter};
+    /// let writer = HttpWriter::new(HttpClient::new());
+    /// ```
+    pub fn new(http: HttpClient) -> HttpWriter {
+        HttpWriter {
+            http,
+            ..Default::default()
+        }
+    }
+
+    /// Reads JSON from a url, and remembers its ETag for the next write.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use stac::{HttpClient, HttpWriter};
+    /// let writer = HttpWriter::new(HttpClient::new());
+    /// let url = "https://stac.test/catalog.json".parse().unwrap();
+    /// let value = writer.read_json(&url).unwrap();
+    /// assert!(writer.etag(&url).is_some());
+    /// ```
+    pub fn read_json(&self, url: &Url) -> Result<Value> {
+        let (etag, bytes) = self.http.send_blocking(&Request::get(url.clone()))?;
+        self.remember(url, etag);
+        serde_json::from_slice(&bytes).map_err(Error::from)
+    }
+
+    /// Returns the last known ETag of the object at a url.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{HttpClient, HttpWriter};
+    /// let writer = HttpWriter::new(HttpClient::new());
+    /// let url = "https://stac.test/catalog.json".parse().unwrap();
+    /// writer.set_etag(&url, "\"abc\"");
+    /// assert_eq!(writer.etag(&url).unwrap(), "\"abc\"");
+    /// ```
+    pub fn etag(&self, url: &Url) -> Option<String> {
+        self.etags
+            .lock()
+            .expect("etag lock poisoned")
+            .get(url)
+            .cloned()
+    }
+
+    /// Sets the ETag that the object at a url is expected to have, e.g. one
+    /// that was read by another process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{HttpClient, HttpWriter};
+    /// let writer = HttpWriter::new(HttpClient::new());
+    /// let url = "https://stac.test/catalog.json".parse().unwrap();
+    /// writer.set_etag(&url, "\"abc\"");
+    /// ```
+    pub fn set_etag(&self, url: &Url, etag: impl ToString) {
+        self.remember(url, Some(etag.to_string()));
+    }
+
+    /// Returns the precondition of the next write to a url.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stac::{HttpClient, HttpWriter, Precondition};
+    /// let mut writer = HttpWriter::new(HttpClient::new());
+    /// let url = "https://stac.test/catalog.json".parse().unwrap();
+    /// assert_eq!(writer.precondition(&url), None);
+    /// writer.create_only = true;
+    /// assert_eq!(writer.precondition(&url), Some(Precondition::IfNoneMatch));
+    /// writer.set_etag(&url, "\"abc\"");
+    /// assert_eq!(
+    ///     writer.precondition(&url),
+    ///     Some(Precondition::IfMatch("\"abc\"".to_string()))
+    /// );
+    /// ```
+    pub fn precondition(&self, url: &Url) -> Option<Precondition> {
+        match self.etag(url) {
+            Some(etag) => Some(Precondition::IfMatch(etag)),
+            None if self.create_only => Some(Precondition::IfNoneMatch),
+            None => None,
+        }
+    }
+
+    fn remember(&self, url: &Url, etag: Option<String>) {
+        let mut etags = self.etags.lock().expect("etag lock poisoned");
+        match etag {
+            Some(etag) => {
+                let _ = etags.insert(url.clone(), etag);
+            }
+            None => {
+                let _ = etags.remove(url);
+            }
         }
-        let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-        if self.pretty {
-            serde_json::to_writer_pretty(writer, &value).map_err(Error::from)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Write for HttpWriter {
+    fn write(&self, object: HrefObject) -> Result<()> {
+        let value = prepare(object.object, self.version.as_ref(), self.sort)?;
+        self.write_json(value, &object.href)
+    }
+
+    fn write_json_to_url(&self, value: Value, url: &Url) -> Result<()> {
+        let body = if self.pretty {
+            serde_json::to_vec_pretty(&value)?
         } else {
-            serde_json::to_writer(writer, &value).map_err(Error::from)
+            serde_json::to_vec(&value)?
+        };
+        let mut request = Request::put(url.clone(), body);
+        request.set_header("Content-Type", crate::media_type::JSON);
+        match self.precondition(url) {
+            Some(Precondition::IfMatch(etag)) => request.set_header("If-Match", etag),
+            Some(Precondition::IfNoneMatch) => request.set_header("If-None-Match", "*"),
+            None => {}
         }
+        let (etag, _) = self.http.send_blocking(&request)?;
+        self.remember(url, etag);
+        Ok(())
+    }
+
+    fn write_json_to_path(&self, value: Value, path: impl AsRef<Path>) -> Result<()> {
+        let writer = Writer {
+            pretty: self.pretty,
+            ..Default::default()
+        };
+        writer.write_json_to_path(value, path)
+    }
+
+    fn settings(&self) -> Value {
+        settings(self.pretty, self.version.as_ref(), self.sort)
     }
 }
 
 impl Default for Writer {
     fn default() -> Writer {
-        Writer { pretty: true }
+        Writer {
+            pretty: true,
+            version: None,
+            sort: false,
+            retry: None,
+        }
+    }
+}
+
+/// A trait to describe things that can write STAC objects asynchronously.
+///
+/// This is the async counterpart to [Write], and is used by
+/// [AsyncStac](crate::AsyncStac).
+#[cfg(feature = "async")]
+pub trait AsyncWrite: Sync {
+    /// Writes a [HrefObject], consuming it.
+    ///
+    /// # Examples
+    ///
+    /// [AsyncWriter] implements [AsyncWrite]:
+    ///
+    /// ```no_run
+    /// use stac::{AsyncWriter, AsyncWrite, Item, HrefObject};
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let object = HrefObject::new(Item::new("an-id"), "item.json");
+    /// let writer = AsyncWriter::default();
+    /// writer.write(object).await.unwrap();
+    /// # })
+    /// ```
+    fn write(&self, object: HrefObject) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let value = object.object.into_value()?;
+            self.write_json(value, &object.href).await
+        }
+    }
+
+    /// Writes a [serde_json::Value] to an href.
+    fn write_json(&self, value: Value, href: &Href) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            match href {
+                Href::Url(url) => self.write_json_to_url(value, url).await,
+                Href::Path(path) => {
+                    self.write_json_to_path(value, &PathBuf::from_slash(path))
+                        .await
+                }
+            }
+        }
+    }
+
+    /// Writes JSON data to a url.
+    fn write_json_to_url(&self, value: Value, url: &Url)
+        -> impl Future<Output = Result<()>> + Send;
+
+    /// Writes JSON data to a path.
+    fn write_json_to_path(
+        &self,
+        value: Value,
+        path: &Path,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// The default async writer, which writes to the filesystem with
+/// [tokio](https://docs.rs/tokio/latest/tokio/).
+///
+/// Its options match those of [Writer].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncWriter {
+    /// Pretty-print json?
+    pub pretty: bool,
+
+    /// If set, every object's `stac_version` is normalized to this version before writing,
+    /// and its bands are converted to that version's layout.
+    pub version: Option<Version>,
+
+    /// Sort links by `rel` and `href`, and assets by key, before writing?
+    pub sort: bool,
+
+    /// If set, failed writes to the filesystem are retried with this policy.
+    pub retry: Option<Arc<dyn RetryPolicy>>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncWrite for AsyncWriter {
+    async fn write(&self, object: HrefObject) -> Result<()> {
+        let value = prepare(object.object, self.version.as_ref(), self.sort)?;
+        self.write_json(value, &object.href).await
+    }
+
+    async fn write_json_to_url(&self, _: Value, url: &Url) -> Result<()> {
+        Err(Error::CannotWriteUrl(url.clone()))
+    }
+
+    async fn write_json_to_path(&self, value: Value, path: &Path) -> Result<()> {
+        let bytes = if self.pretty {
+            serde_json::to_vec_pretty(&value)?
+        } else {
+            serde_json::to_vec(&value)?
+        };
+        let write = || async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, &bytes).await.map_err(Error::from)
+        };
+        match self.retry.as_deref() {
+            Some(policy) => crate::retry::retry_async(policy, write).await,
+            None => write().await,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Default for AsyncWriter {
+    fn default() -> AsyncWriter {
+        AsyncWriter {
+            pretty: true,
+            version: None,
+            sort: false,
+            retry: None,
+        }
+    }
+}
+
+/// Returns the [settings](Write::settings) of a writer with these options.
+pub(crate) fn settings(pretty: bool, version: Option<&Version>, sort: bool) -> Value {
+    serde_json::json!({
+        "pretty": pretty,
+        "version": version.map(ToString::to_string),
+        "sort": sort,
+    })
+}
+
+/// Converts an object to JSON, applying the version and sort options shared by the writers.
+pub(crate) fn prepare(
+    mut object: Object,
+    version: Option<&Version>,
+    should_sort: bool,
+) -> Result<Value> {
+    if let Some(version) = version {
+        object.set_version(version);
+        object.convert_bands(version)?;
+    }
+    let mut value = object.into_value()?;
+    if should_sort {
+        sort(&mut value);
+    }
+    Ok(value)
+}
+
+fn link_key(link: &Value) -> (&str, &str) {
+    (
+        link.get("rel").and_then(Value::as_str).unwrap_or_default(),
+        link.get("href").and_then(Value::as_str).unwrap_or_default(),
+    )
+}
+
+/// Sorts an object's links by `rel` and `href`, and its asset maps by key.
+pub(crate) fn sort(value: &mut Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    if let Some(Value::Array(links)) = object.get_mut("links") {
+        links.sort_by(|a, b| link_key(a).cmp(&link_key(b)));
+    }
+    for key in ["assets", "item_assets"] {
+        if let Some(Value::Object(assets)) = object.get_mut(key) {
+            assets.sort_keys();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Write, Writer};
-    use crate::{HrefObject, Item};
+    use super::{NdjsonWriter, Write, Writer};
+    use crate::{Asset, Band, HrefObject, Item, Link};
+    use serde_json::Value;
 
     #[test]
     fn write() {
@@ -143,4 +870,249 @@ mod tests {
         let read_object = crate::read(href).unwrap();
         assert_eq!(read_object, object);
     }
+
+    #[test]
+    fn write_normalized_version() {
+        let mut item = Item::new("an-item");
+        item.version = "1.0.0-rc.1".to_string();
+        let directory = tempfile::tempdir().unwrap();
+        let href = directory.path().join("item.json");
+        let writer = Writer {
+            version: Some("1.1.0".parse().unwrap()),
+            ..Default::default()
+        };
+        writer.write(HrefObject::new(item, href.clone())).unwrap();
+        let object = crate::read(href).unwrap().object;
+        assert_eq!(object.version().unwrap().to_string(), "1.1.0");
+    }
+
+    #[test]
+    fn write_bands_for_version() {
+        let mut item = Item::new("an-item");
+        let mut asset = Asset::new("data.tif");
+        asset.bands = Some(vec![Band::new("B04").common_name("red")]);
+        let _ = item.assets.insert("data".to_string(), asset);
+        let directory = tempfile::tempdir().unwrap();
+        for (version, legacy) in [("1.0.0", true), ("1.1.0", false)] {
+            let href = directory.path().join(format!("{}.json", version));
+            let writer = Writer {
+                version: Some(version.parse().unwrap()),
+                ..Default::default()
+            };
+            writer
+                .write(HrefObject::new(item.clone(), href.clone()))
+                .unwrap();
+            let value: Value = serde_json::from_slice(&std::fs::read(href).unwrap()).unwrap();
+            let asset = &value["assets"]["data"];
+            assert_eq!(asset.get("eo:bands").is_some(), legacy);
+            assert_eq!(asset.get("bands").is_some(), !legacy);
+        }
+    }
+
+    #[test]
+    fn write_sorted() {
+        let mut item = Item::new("an-item");
+        for key in ["c", "a", "b"] {
+            let _ = item.assets.insert(key.to_string(), Asset::new(key));
+        }
+        item.links.push(Link::root("./z.json"));
+        item.links.push(Link::parent("./catalog.json"));
+        item.links.push(Link::root("./catalog.json"));
+        let directory = tempfile::tempdir().unwrap();
+        let href = directory.path().join("item.json");
+        let writer = Writer {
+            sort: true,
+            ..Default::default()
+        };
+        writer.write(HrefObject::new(item, href.clone())).unwrap();
+        let value: Value = serde_json::from_reader(std::fs::File::open(href).unwrap()).unwrap();
+        let keys: Vec<_> = value["assets"].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+        let links: Vec<_> = value["links"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|link| link["href"].as_str().unwrap())
+            .collect();
+        assert_eq!(links, ["./catalog.json", "./catalog.json", "./z.json"]);
+        assert_eq!(value["links"][0]["rel"], "parent");
+    }
+
+    #[test]
+    fn write_ndjson() {
+        let writer = NdjsonWriter::new(Vec::new());
+        writer
+            .write(HrefObject::new(Item::new("a"), "a/a.json"))
+            .unwrap();
+        writer
+            .write(HrefObject::new(Item::new("b"), "b/b.json"))
+            .unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let ids: Vec<_> = output
+            .lines()
+            .map(|line| serde_json::from_str::<Item>(line).unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_write() {
+        use super::{AsyncWrite, AsyncWriter};
+
+        let item = Item::new("an-item");
+        let directory = tempfile::tempdir().unwrap();
+        let href = directory.path().join("sub").join("item.json");
+        let object = HrefObject::new(item, href.clone());
+
+        let writer = AsyncWriter::default();
+        writer.write(object.clone()).await.unwrap();
+
+        let read_object = crate::read(href).unwrap();
+        assert_eq!(read_object, object);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn http_writer_conditional() {
+        use crate::{
+            http::test_server::{response, serve},
+            Error, HttpClient, HttpWriter,
+        };
+        use url::Url;
+
+        let (url, handle) = serve(vec![
+            response(200, &[("ETag", "\"v1\"")], r#"{"type": "Catalog"}"#),
+            response(200, &[("ETag", "\"v2\"")], ""),
+            response(412, &[], ""),
+            response(201, &[("ETag", "\"v1\"")], ""),
+        ]);
+        let writer = HttpWriter::new(HttpClient::new());
+        let catalog: Url = format!("{}/catalog.json", url).parse().unwrap();
+        let _ = writer.read_json(&catalog).unwrap();
+        writer
+            .write_json_to_url(serde_json::json!({}), &catalog)
+            .unwrap();
+        assert_eq!(writer.etag(&catalog).unwrap(), "\"v2\"");
+        writer.set_etag(&catalog, "\"stale\"");
+        assert!(matches!(
+            writer
+                .write_json_to_url(serde_json::json!({}), &catalog)
+                .unwrap_err(),
+            Error::PreconditionFailed(_)
+        ));
+
+        let mut writer = HttpWriter::new(HttpClient::new());
+        writer.create_only = true;
+        let item: Url = format!("{}/item.json", url).parse().unwrap();
+        writer
+            .write(HrefObject::new(Item::new("an-item"), item.as_str()))
+            .unwrap();
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[1].line, "PUT /catalog.json HTTP/1.1");
+        assert_eq!(requests[1].header("if-match"), Some("\"v1\""));
+        assert_eq!(requests[2].header("if-match"), Some("\"stale\""));
+        assert_eq!(requests[3].header("if-none-match"), Some("*"));
+        assert_eq!(requests[3].header("if-match"), None);
+        let value: Value = serde_json::from_str(&requests[3].body).unwrap();
+        assert_eq!(value["id"], "an-item");
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn checkpoint_resume() {
+        use super::CheckpointWriter;
+        use crate::{Catalog, Layout, Stac};
+
+        fn item(id: &str) -> Item {
+            let mut item = Item::new(id);
+            item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+            item
+        }
+
+        fn stac() -> Stac<crate::Reader> {
+            let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+            let _ = stac.add_child(root, item("a")).unwrap();
+            let _ = stac.add_child(root, item("b")).unwrap();
+            stac
+        }
+
+        let directory = tempfile::tempdir().unwrap();
+        let manifest = directory.path().join("checkpoint.ndjson");
+        let root = directory.path().join("stac");
+        let root = root.to_str().unwrap();
+
+        let writer = CheckpointWriter::open(Writer::default(), &manifest).unwrap();
+        stac().write(&mut Layout::new(root), &writer).unwrap();
+        assert_eq!(writer.completed(), 3);
+        assert_eq!(writer.skipped(), 0);
+
+        // Simulate a crash in the middle of writing a manifest line.
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&manifest)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"{\"href\": \"trunc").unwrap();
+
+        let (mut changed, root_handle) = Stac::new(Catalog::new("root")).unwrap();
+        let _ = changed.add_child(root_handle, item("a")).unwrap();
+        let mut b = item("b");
+        let _ = b
+            .properties
+            .additional_fields
+            .insert("title".to_string(), "changed".into());
+        let _ = changed.add_child(root_handle, b).unwrap();
+        let writer = CheckpointWriter::open(Writer::default(), &manifest).unwrap();
+        assert_eq!(writer.completed(), 3);
+        changed.write(&mut Layout::new(root), &writer).unwrap();
+        // The root's `item` link to `b` carries b's title, so the root changed too.
+        assert_eq!(writer.skipped(), 1);
+
+        let writer = CheckpointWriter::open(Writer::default(), &manifest).unwrap();
+        assert_eq!(writer.completed(), 3);
+        stac().write(&mut Layout::new(root), &writer).unwrap();
+        // Writing the original again changes `b` and the root back.
+        assert_eq!(writer.skipped(), 1);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn checkpoint_settings_and_pages() {
+        use super::CheckpointWriter;
+        use crate::{Catalog, Layout, Stac};
+
+        fn stac() -> Stac<crate::Reader> {
+            let (mut stac, root) = Stac::new(Catalog::new("root")).unwrap();
+            for id in ["a", "b", "c"] {
+                let mut item = Item::new(id);
+                item.properties.datetime = Some("2023-01-01T00:00:00Z".to_string());
+                let _ = stac.add_child(root, item).unwrap();
+            }
+            stac
+        }
+
+        let directory = tempfile::tempdir().unwrap();
+        let manifest = directory.path().join("checkpoint.ndjson");
+        let root = directory.path().join("stac");
+        let root = root.to_str().unwrap();
+        let layout = || Layout::new(root).with_item_pages(2);
+
+        let writer = CheckpointWriter::open(Writer::default(), &manifest).unwrap();
+        stac().write(&mut layout(), &writer).unwrap();
+        // The root, three items, and two item pages.
+        assert_eq!(writer.completed(), 6);
+
+        let writer = CheckpointWriter::open(Writer::default(), &manifest).unwrap();
+        stac().write(&mut layout(), &writer).unwrap();
+        assert_eq!(writer.skipped(), 6);
+
+        let compact = Writer {
+            pretty: false,
+            ..Default::default()
+        };
+        let writer = CheckpointWriter::open(compact, &manifest).unwrap();
+        stac().write(&mut layout(), &writer).unwrap();
+        assert_eq!(writer.skipped(), 0);
+    }
 }